@@ -2,11 +2,7 @@ use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId, Through
 use std::hint::black_box;
 use serde_json::json;
 
-#[path = "../src/toon/mod.rs"]
-mod toon;
-
-#[path = "../src/converter.rs"]
-mod converter;
+use toonify::converter;
 
 fn generate_json_data(size: usize) -> String {
     let users: Vec<_> = (0..size)
@@ -41,5 +41,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("cargo:warning=Skipping gRPC/protobuf build for WASM target");
     }
 
+    // Build metadata for `toonify version --json`
+    let git_sha = std::process::Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|sha| sha.trim().to_string())
+        .or_else(|| std::env::var("GITHUB_SHA").ok())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=TOONIFY_GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=TOONIFY_BUILD_TARGET={}", target);
+    println!("cargo:rustc-env=TOONIFY_BUILD_PROFILE={}", std::env::var("PROFILE").unwrap_or_default());
+
     Ok(())
 }
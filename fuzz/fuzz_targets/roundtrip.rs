@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Any TOON the parser accepts must re-serialize to TOON that parses back to the same value
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(value) = toonify::toon::parse_toon(input) else {
+        return;
+    };
+    let toon = toonify::toon::serialize_toon(&value).expect("parsed TOON must serialize");
+    let reparsed = toonify::toon::parse_toon(&toon).expect("serialized TOON must parse");
+    // Tables write a field missing from some rows as null
+    let (reparsed, value) = (toonify::selftest::normalize(&reparsed), toonify::selftest::normalize(&value));
+    assert_eq!(reparsed, value, "round trip changed the value\n  toon: {:?}", toon);
+});
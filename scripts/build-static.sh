@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+# Build fully static (musl) toonify binaries for scratch or distroless images
+#
+#   scripts/build-static.sh                                 # x86_64 and aarch64
+#   scripts/build-static.sh x86_64-unknown-linux-musl
+#
+# Uses `cross` when installed (needed for the non-host architecture);
+# otherwise plain cargo, which needs the target's musl toolchain
+# (rustup target add <target>, plus musl-tools on Debian/Ubuntu).
+set -euo pipefail
+
+targets=("$@")
+if [ ${#targets[@]} -eq 0 ]; then
+    targets=(x86_64-unknown-linux-musl aarch64-unknown-linux-musl)
+fi
+
+builder=cargo
+if command -v cross >/dev/null 2>&1; then
+    builder=cross
+fi
+
+for target in "${targets[@]}"; do
+    echo "Building $target with $builder"
+    "$builder" build --release --target "$target" --features static,self-update
+    binary="target/$target/release/toonify"
+    if command -v file >/dev/null 2>&1; then
+        file "$binary"
+    fi
+done
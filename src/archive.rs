@@ -0,0 +1,133 @@
+// Archive input for batch (`toonify batch --input archive.zip --output-dir out/`)
+//
+// Members of a zip, tar or tar.gz archive are read into memory one at a time
+// and converted like files, without extracting the archive to disk first.
+// A member's path inside the archive becomes its path under --output-dir, so
+// members with an absolute path or a `..` component are reported as failures
+// instead of being written outside it. Directories, links and other entries
+// that are not plain files are skipped.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use toonify::converter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// From the file name, falling back to the magic bytes
+    pub fn of(path: &Path) -> Result<Self, String> {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            return Ok(Self::Zip);
+        }
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            return Ok(Self::TarGz);
+        }
+        if name.ends_with(".tar") {
+            return Ok(Self::Tar);
+        }
+
+        // The tar magic sits at offset 257 of the first header
+        let mut magic = Vec::with_capacity(262);
+        open(path)?.take(262).read_to_end(&mut magic).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        if magic.starts_with(b"PK\x03\x04") {
+            Ok(Self::Zip)
+        } else if converter::is_gzip(&magic) {
+            Ok(Self::TarGz)
+        } else if magic.get(257..262) == Some(&b"ustar"[..]) {
+            Ok(Self::Tar)
+        } else {
+            Err(format!("{:?} is not a zip, tar or tar.gz archive", path))
+        }
+    }
+}
+
+/// Member contents, or why they could not be read
+pub type MemberContents = Result<Vec<u8>, String>;
+
+/// Call `visit` with every file member whose path `select` accepts, in
+/// archive order; returns how many were visited
+pub fn for_each_member(path: &Path, kind: ArchiveKind, select: impl Fn(&Path) -> bool, mut visit: impl FnMut(PathBuf, MemberContents)) -> Result<usize, String> {
+    walk(path, kind, &select, true, &mut visit)
+}
+
+/// How many members `for_each_member` would visit, without reading their contents
+pub fn count_members(path: &Path, kind: ArchiveKind, select: impl Fn(&Path) -> bool) -> Result<usize, String> {
+    walk(path, kind, &select, false, &mut |_, _| {})
+}
+
+fn walk(path: &Path, kind: ArchiveKind, select: &dyn Fn(&Path) -> bool, read: bool, visit: &mut dyn FnMut(PathBuf, MemberContents)) -> Result<usize, String> {
+    let file = BufReader::new(open(path)?);
+    let walked = match kind {
+        ArchiveKind::Zip => walk_zip(file, select, read, visit),
+        ArchiveKind::Tar => walk_tar(file, select, read, visit),
+        ArchiveKind::TarGz => walk_tar(GzDecoder::new(file), select, read, visit),
+    };
+    walked.map_err(|e| format!("Failed to read archive {:?}: {}", path, e))
+}
+
+fn walk_zip(file: BufReader<File>, select: &dyn Fn(&Path) -> bool, read: bool, visit: &mut dyn FnMut(PathBuf, MemberContents)) -> Result<usize, String> {
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut visited = 0;
+    for index in 0..archive.len() {
+        let mut member = archive.by_index(index).map_err(|e| e.to_string())?;
+        if !member.is_file() {
+            continue;
+        }
+        let name = PathBuf::from(member.name());
+        if !select(&name) {
+            continue;
+        }
+        visited += 1;
+        if !read {
+            continue;
+        }
+        let contents = read_member(&name, &mut member);
+        visit(name, contents);
+    }
+    Ok(visited)
+}
+
+fn walk_tar(reader: impl Read, select: &dyn Fn(&Path) -> bool, read: bool, visit: &mut dyn FnMut(PathBuf, MemberContents)) -> Result<usize, String> {
+    let mut archive = tar::Archive::new(reader);
+    let mut visited = 0;
+    for entry in archive.entries().map_err(|e| e.to_string())? {
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path().map_err(|e| e.to_string())?.into_owned();
+        if !select(&name) {
+            continue;
+        }
+        visited += 1;
+        if !read {
+            continue;
+        }
+        let contents = read_member(&name, &mut entry);
+        visit(name, contents);
+    }
+    Ok(visited)
+}
+
+fn read_member(name: &Path, member: &mut impl Read) -> MemberContents {
+    // Only plain relative components, so the member stays under the output directory
+    if !name.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir)) {
+        return Err(format!("Refusing member {:?}: its path leaves the output directory", name));
+    }
+    let mut contents = Vec::new();
+    member.read_to_end(&mut contents).map_err(|e| e.to_string())?;
+    Ok(contents)
+}
+
+fn open(path: &Path) -> Result<File, String> {
+    File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))
+}
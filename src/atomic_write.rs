@@ -0,0 +1,102 @@
+// Crash-safe output files for batch and watch
+//
+// Output is written to a temporary sibling (`name.<pid>-<n>.tmp`) and renamed
+// over the destination, so readers see either the old file or the complete
+// new one, never a truncated write. With `fsync` the data is flushed before
+// the rename and the directory entry after it, for pipelines that must not
+// lose converted files on power loss.
+//
+// `preserve` copies permissions, mtime/atime and, where the process is
+// allowed to, ownership from the source file onto the temp file before the
+// rename, so the output never appears with the wrong metadata.
+// `write_atomic_keeping_mode` takes only permissions and ownership, for a
+// file that replaces another but is itself new, like an updated binary.
+
+use std::fs::{self, File, FileTimes, Metadata};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub fn write_atomic(path: &Path, contents: &[u8], fsync: bool, preserve: Option<&Metadata>) -> io::Result<()> {
+    write_atomic_with(path, contents, fsync, preserve, true)
+}
+
+#[cfg(feature = "self-update")]
+pub fn write_atomic_keeping_mode(path: &Path, contents: &[u8], fsync: bool, source: &Metadata) -> io::Result<()> {
+    write_atomic_with(path, contents, fsync, Some(source), false)
+}
+
+fn write_atomic_with(path: &Path, contents: &[u8], fsync: bool, preserve: Option<&Metadata>, times: bool) -> io::Result<()> {
+    let temp_path = temp_path_for(path);
+
+    let result = write_then_rename(&temp_path, path, contents, fsync, preserve, times);
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn write_then_rename(
+    temp_path: &Path,
+    path: &Path,
+    contents: &[u8],
+    fsync: bool,
+    preserve: Option<&Metadata>,
+    times: bool,
+) -> io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    file.write_all(contents)?;
+    if let Some(source) = preserve {
+        copy_metadata(&file, source, times)?;
+    }
+    if fsync {
+        file.sync_all()?;
+    }
+    drop(file);
+
+    fs::rename(temp_path, path)?;
+
+    #[cfg(unix)]
+    if fsync {
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+fn copy_metadata(file: &File, source: &Metadata, times: bool) -> io::Result<()> {
+    #[cfg(unix)]
+    copy_ownership(file, source);
+
+    file.set_permissions(source.permissions())?;
+    if !times {
+        return Ok(());
+    }
+
+    let mut times = FileTimes::new().set_modified(source.modified()?);
+    if let Ok(accessed) = source.accessed() {
+        times = times.set_accessed(accessed);
+    }
+    file.set_times(times)
+}
+
+// Only root can give a file away; otherwise keep at least the group when the
+// user belongs to it, and fall back to the current owner silently
+#[cfg(unix)]
+fn copy_ownership(file: &File, source: &Metadata) {
+    use std::os::unix::fs::{fchown, MetadataExt};
+
+    if fchown(file, Some(source.uid()), Some(source.gid())).is_err() {
+        let _ = fchown(file, None, Some(source.gid()));
+    }
+}
+
+// Same directory as the target so the rename never crosses filesystems;
+// unique per process and call so parallel writers never share a temp file
+fn temp_path_for(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!("{}.{}-{}.tmp", name, std::process::id(), n))
+}
@@ -0,0 +1,181 @@
+// Audit log of server conversions (`serve --audit-log audit.jsonl`)
+//
+// One JSON line per REST or gRPC conversion, written after the response is
+// decided:
+//
+//     {"timestamp":"2025-01-31T12:00:00.123Z","transport":"rest","endpoint":"/json-to-toon",
+//      "from":"json","to":"toon","client_ip":"10.0.0.7","forwarded_for":null,"api_key":"…f00d",
+//      "input_bytes":120,"output_bytes":74,"duration_ms":0.41,"status":200,"error":null}
+//
+// API keys (`X-API-Key`, or an `Authorization: Bearer` token) are logged as
+// their last four characters only. With `--audit-log-payloads` the request
+// data and the result are included too, which `toonify replay` needs; leave
+// it off where the payloads themselves are sensitive. When the file would grow past the size
+// limit it is rotated: `audit.jsonl` becomes `audit.jsonl.1`, `.1` becomes
+// `.2`, and so on, dropping the oldest beyond `keep`. A failed write is
+// reported on stderr and never fails the request.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::HeaderMap;
+use axum::http::request::Parts;
+use serde::Serialize;
+
+use crate::type_rules::civil_from_days;
+
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: Option<u64>,
+    keep: usize,
+    payloads: bool,
+    file: Mutex<(File, u64)>,
+}
+
+/// Who sent a request, as far as the audit log is concerned
+#[derive(Clone, Default, Serialize)]
+pub struct Caller {
+    pub client_ip: Option<String>,
+    pub forwarded_for: Option<String>,
+    pub api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AuditRecord<'a> {
+    pub timestamp: String,
+    pub transport: &'a str,
+    pub endpoint: &'a str,
+    pub from: &'a str,
+    pub to: &'a str,
+    #[serde(flatten)]
+    pub caller: Caller,
+    pub input_bytes: usize,
+    pub output_bytes: usize,
+    pub duration_ms: f64,
+    /// HTTP status, or the gRPC code for gRPC calls
+    pub status: u16,
+    pub error: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<&'a str>,
+}
+
+impl AuditLog {
+    /// `keep` is the number of rotated files kept next to the live one
+    pub fn open(path: &Path, max_bytes: Option<u64>, keep: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path: path.to_path_buf(), max_bytes, keep, payloads: false, file: Mutex::new((file, size)) })
+    }
+
+    /// Also record request data and results
+    pub fn with_payloads(mut self, payloads: bool) -> Self {
+        self.payloads = payloads;
+        self
+    }
+
+    pub fn payloads(&self) -> bool {
+        self.payloads
+    }
+
+    pub fn record(&self, record: &AuditRecord) {
+        if let Err(e) = self.write(record) {
+            eprintln!("[AUDIT] Failed to write {:?}: {}", self.path, e);
+        }
+    }
+
+    fn write(&self, record: &AuditRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut guard = self.file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (file, size) = &mut *guard;
+        if self.max_bytes.is_some_and(|max| *size > 0 && *size + line.len() as u64 > max) {
+            *file = self.rotate()?;
+            *size = 0;
+        }
+        file.write_all(&line)?;
+        *size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&self) -> io::Result<File> {
+        let rotated = |n: usize| {
+            let mut name = self.path.as_os_str().to_owned();
+            name.push(format!(".{}", n));
+            PathBuf::from(name)
+        };
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(rotated(self.keep));
+            for n in (1..self.keep).rev() {
+                if rotated(n).exists() {
+                    fs::rename(rotated(n), rotated(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, rotated(1))?;
+        }
+        OpenOptions::new().create(true).append(true).open(&self.path)
+    }
+}
+
+impl Caller {
+    pub fn from_headers(client_ip: Option<SocketAddr>, headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::trim).filter(|value| !value.is_empty());
+        let api_key = header("x-api-key")
+            .or_else(|| header("authorization").and_then(|value| value.strip_prefix("Bearer ")))
+            .map(mask_key);
+        Self {
+            client_ip: client_ip.map(|addr| addr.ip().to_string()),
+            forwarded_for: header("x-forwarded-for").map(String::from),
+            api_key,
+        }
+    }
+}
+
+// Works with or without `into_make_service_with_connect_info`
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let client_ip = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| *addr);
+        Ok(Self::from_headers(client_ip, &parts.headers))
+    }
+}
+
+fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let tail: String = chars[chars.len().saturating_sub(4)..].iter().collect();
+    format!("…{}", tail)
+}
+
+pub fn duration_ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+/// Current time as RFC 3339 UTC with milliseconds
+pub fn timestamp() -> String {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, day_secs) = ((secs / 86_400) as i64, secs % 86_400);
+
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        day_secs / 3600,
+        day_secs % 3600 / 60,
+        day_secs % 60,
+        since_epoch.subsec_millis()
+    )
+}
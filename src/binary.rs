@@ -0,0 +1,133 @@
+// Schema-driven binary encodings (features `protobuf` and `avro`)
+//
+//     let schema = BinarySchema::load(Path::new("user.proto"), Some("Users"))?;
+//     let bytes = schema.encode(&converter::toon_to_value(toon)?)?;
+//     let value = schema.decode(&bytes)?;
+//
+// The schema describes the whole document, so `users[2]{id,name}:` maps to a
+// message (or Avro record) with a repeated `users` field. `.proto` files are
+// compiled at runtime; fields are matched by their proto names, and decoding
+// emits every field, defaults included, with 64-bit integers as numbers so
+// rows stay tabular. Avro schemas (`.avsc`) produce a single datum with no
+// container header; the reader needs the same schema.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+pub enum BinarySchema {
+    #[cfg(feature = "protobuf")]
+    Protobuf(prost_reflect::MessageDescriptor),
+    #[cfg(feature = "avro")]
+    Avro(apache_avro::Schema),
+}
+
+impl BinarySchema {
+    /// Load a `.proto` (choosing `message`, or the file's only top-level
+    /// message) or an Avro schema (`.avsc` / `.json`)
+    pub fn load(path: &Path, message: Option<&str>) -> Result<Self, String> {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+        match extension.as_str() {
+            #[cfg(feature = "protobuf")]
+            "proto" => load_proto(path, message).map(BinarySchema::Protobuf),
+            #[cfg(feature = "avro")]
+            "avsc" | "json" => {
+                if message.is_some() {
+                    return Err("--message only applies to .proto schemas".to_string());
+                }
+                let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+                apache_avro::Schema::parse_str(&text)
+                    .map(BinarySchema::Avro)
+                    .map_err(|e| format!("Invalid Avro schema {:?}: {}", path, e))
+            }
+            _ => {
+                let _ = message;
+                Err(format!("Unsupported schema {:?}: expected {}", path, supported_extensions()))
+            }
+        }
+    }
+
+    /// Encode a JSON value as the schema's binary wire format
+    pub fn encode(&self, value: &Value) -> Result<Vec<u8>, String> {
+        match self {
+            #[cfg(feature = "protobuf")]
+            BinarySchema::Protobuf(descriptor) => {
+                use prost::Message;
+                let message = prost_reflect::DynamicMessage::deserialize(descriptor.clone(), value.clone())
+                    .map_err(|e| format!("Data doesn't match {}: {}", descriptor.full_name(), e))?;
+                Ok(message.encode_to_vec())
+            }
+            #[cfg(feature = "avro")]
+            BinarySchema::Avro(schema) => {
+                let datum = apache_avro::types::Value::from(value.clone())
+                    .resolve(schema)
+                    .map_err(|e| format!("Data doesn't match the Avro schema: {}", e))?;
+                apache_avro::to_avro_datum(schema, datum).map_err(|e| format!("Avro encoding failed: {}", e))
+            }
+        }
+    }
+
+    /// Decode bytes written by `encode` (or any producer using the same schema)
+    pub fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        match self {
+            #[cfg(feature = "protobuf")]
+            BinarySchema::Protobuf(descriptor) => {
+                let message = prost_reflect::DynamicMessage::decode(descriptor.clone(), bytes)
+                    .map_err(|e| format!("Invalid {} message: {}", descriptor.full_name(), e))?;
+                let options = prost_reflect::SerializeOptions::new()
+                    .stringify_64_bit_integers(false)
+                    .use_proto_field_name(true)
+                    .skip_default_fields(false);
+                message
+                    .serialize_with_options(serde_json::value::Serializer, &options)
+                    .map_err(|e| format!("Failed to convert {} to JSON: {}", descriptor.full_name(), e))
+            }
+            #[cfg(feature = "avro")]
+            BinarySchema::Avro(schema) => {
+                let datum = apache_avro::from_avro_datum(schema, &mut &bytes[..], None)
+                    .map_err(|e| format!("Invalid Avro datum: {}", e))?;
+                Value::try_from(datum).map_err(|e| format!("Failed to convert Avro datum to JSON: {}", e))
+            }
+        }
+    }
+}
+
+fn supported_extensions() -> &'static str {
+    match (cfg!(feature = "protobuf"), cfg!(feature = "avro")) {
+        (true, true) => ".proto, .avsc or .json",
+        (true, false) => ".proto (Avro needs the 'avro' feature)",
+        _ => ".avsc or .json (.proto needs the 'protobuf' feature)",
+    }
+}
+
+#[cfg(feature = "protobuf")]
+fn load_proto(path: &Path, message: Option<&str>) -> Result<prost_reflect::MessageDescriptor, String> {
+    // Imports resolve relative to the schema's directory
+    let include = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let file_name = path.file_name().ok_or_else(|| format!("Invalid schema path {:?}", path))?;
+    let files = protox::compile([file_name], [include]).map_err(|e| format!("Failed to compile {:?}: {}", path, e))?;
+    let pool = prost_reflect::DescriptorPool::from_file_descriptor_set(files)
+        .map_err(|e| format!("Invalid descriptors in {:?}: {}", path, e))?;
+
+    match message {
+        Some(name) => pool
+            .get_message_by_name(name)
+            .or_else(|| pool.all_messages().find(|candidate| candidate.name() == name))
+            .ok_or_else(|| format!("No message {:?} in {:?}", name, path)),
+        None => {
+            let file = pool
+                .get_file_by_name(&file_name.to_string_lossy())
+                .ok_or_else(|| format!("{:?} was not compiled", path))?;
+            let mut messages: Vec<_> = file.messages().collect();
+            match messages.len() {
+                1 => Ok(messages.remove(0)),
+                0 => Err(format!("{:?} defines no messages", path)),
+                _ => Err(format!(
+                    "{:?} defines several messages; choose one with --message ({})",
+                    path,
+                    messages.iter().map(|message| message.name().to_string()).collect::<Vec<_>>().join(", ")
+                )),
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// Build-script helper for embedding TOON assets (feature `build-helper`)
+//
+//     // build.rs
+//     fn main() {
+//         let out = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("fixtures");
+//         toonify::build::convert_dir("fixtures", &out).unwrap();
+//     }
+//
+//     // src/lib.rs
+//     const USERS: &str = include_str!(concat!(env!("OUT_DIR"), "/fixtures/users.toon"));
+//
+// Every `.json` under `src` is converted to `.toon` at the same relative
+// path under `out`. Cargo is told to rerun the build script when any source
+// file or directory changes, and outputs whose content is unchanged are not
+// rewritten, so an unrelated edit doesn't rebuild everything that embeds them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::converter::Converter;
+
+/// Convert a directory of JSON files with the default converter
+pub fn convert_dir(src: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<Vec<PathBuf>, String> {
+    convert_dir_with(&Converter::builder().build(), src, out)
+}
+
+/// Convert with a configured converter (typed headers, flattening, hooks, ...);
+/// returns the `.toon` paths, in sorted order
+pub fn convert_dir_with(converter: &Converter, src: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<Vec<PathBuf>, String> {
+    let (src, out) = (src.as_ref(), out.as_ref());
+    let mut outputs = Vec::new();
+    convert_tree(converter, src, src, out, &mut outputs)?;
+    Ok(outputs)
+}
+
+fn convert_tree(converter: &Converter, root: &Path, dir: &Path, out: &Path, outputs: &mut Vec<PathBuf>) -> Result<(), String> {
+    // Directory entries cover added and removed files
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {:?}: {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            convert_tree(converter, root, &path, out, outputs)?;
+            continue;
+        }
+        if !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            continue;
+        }
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        let toon = converter.convert(&json, "json", "toon").map_err(|e| format!("{:?}: {}", path, e))?;
+
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let output_path = out.join(relative).with_extension("toon");
+        if fs::read_to_string(&output_path).ok().as_deref() != Some(toon.as_str()) {
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+            }
+            fs::write(&output_path, &toon).map_err(|e| format!("Failed to write {:?}: {}", output_path, e))?;
+        }
+        outputs.push(output_path);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+// Build metadata for `toonify version`, for auditing what is deployed where
+//
+// The git commit, target and profile come from build.rs; a build outside a
+// git checkout (e.g. the Docker image) reports the commit as `unknown`
+// unless GITHUB_SHA was set.
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub target: &'static str,
+    pub profile: &'static str,
+    /// TOON syntax version read and written
+    pub format_version: &'static str,
+    /// Built with `--features static` (musl, vendored TLS)
+    pub static_build: bool,
+    pub features: Vec<&'static str>,
+}
+
+// User-facing Cargo features; dependency aliases are left out
+const FEATURES: &[(&str, bool)] = &[
+    ("server", cfg!(feature = "server")),
+    ("cli", cfg!(feature = "cli")),
+    ("compression", cfg!(feature = "compression")),
+    ("validation", cfg!(feature = "validation")),
+    ("batch", cfg!(feature = "batch")),
+    ("watch", cfg!(feature = "watch")),
+    ("cache", cfg!(feature = "cache")),
+    ("persistent-cache", cfg!(feature = "persistent-cache")),
+    ("job-queue", cfg!(feature = "job-queue")),
+    ("rate-limit", cfg!(feature = "rate-limit")),
+    ("uniffi", cfg!(feature = "uniffi")),
+    ("formats", cfg!(feature = "formats")),
+    ("yaml", cfg!(feature = "yaml")),
+    ("csv", cfg!(feature = "csv")),
+    ("toml", cfg!(feature = "toml")),
+    ("xml", cfg!(feature = "xml")),
+    ("scripting", cfg!(feature = "scripting")),
+    ("color", cfg!(feature = "color")),
+    ("tui", cfg!(feature = "tui")),
+    ("signing", cfg!(feature = "signing")),
+    ("progress", cfg!(feature = "progress")),
+    ("database", cfg!(feature = "database")),
+    ("kafka", cfg!(feature = "kafka")),
+    ("xlsx", cfg!(feature = "xlsx")),
+    ("arrow", cfg!(feature = "arrow")),
+    ("protobuf", cfg!(feature = "protobuf")),
+    ("avro", cfg!(feature = "avro")),
+    ("sqlite", cfg!(feature = "sqlite")),
+    ("duckdb", cfg!(feature = "duckdb")),
+    ("mmap", cfg!(feature = "mmap")),
+    ("cache-encryption", cfg!(feature = "cache-encryption")),
+    ("pseudonymize", cfg!(feature = "pseudonymize")),
+    ("cli-cache", cfg!(feature = "cli-cache")),
+    ("self-update", cfg!(feature = "self-update")),
+    ("static", cfg!(feature = "static")),
+    ("config", cfg!(feature = "config")),
+    ("testing", cfg!(feature = "testing")),
+    ("build-helper", cfg!(feature = "build-helper")),
+    ("macros", cfg!(feature = "macros")),
+];
+
+pub fn current() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: env!("TOONIFY_GIT_SHA"),
+        target: env!("TOONIFY_BUILD_TARGET"),
+        profile: env!("TOONIFY_BUILD_PROFILE"),
+        format_version: toonify::toon::FORMAT_VERSION,
+        static_build: cfg!(feature = "static"),
+        features: FEATURES.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| *name).collect(),
+    }
+}
@@ -0,0 +1,99 @@
+// Encryption at rest for the Sled persistent cache (--cache-encryption-key)
+//
+// Cached payloads are whole documents and may contain PII, so with a key
+// configured nothing readable is written to the Sled file:
+//
+// - values are sealed with AES-256-GCM under a random nonce, with the
+//   lookup key as associated data so entries cannot be swapped
+// - lookup keys (which embed the request payload) are replaced by their
+//   HMAC-SHA256 under the same key
+//
+// The key is 32 bytes, either raw or as 64 hex characters, read from a
+// keyfile or from an environment variable (`env:TOONIFY_CACHE_KEY`), e.g.
+//
+//     openssl rand -hex 32 > cache.key
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const FORMAT_VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM cipher for persistent cache entries
+#[derive(Clone)]
+pub struct CacheCipher {
+    cipher: Aes256Gcm,
+    key: [u8; 32],
+}
+
+impl CacheCipher {
+    pub fn from_key(key: [u8; 32]) -> Self {
+        Self { cipher: Aes256Gcm::new(&key.into()), key }
+    }
+
+    /// Load the key from `env:VAR` or a keyfile path
+    pub fn from_source(source: &str) -> Result<Self, String> {
+        let material = match source.strip_prefix("env:") {
+            Some(var) => std::env::var(var)
+                .map_err(|_| format!("Cache encryption key variable {} is not set", var))?
+                .into_bytes(),
+            None => std::fs::read(source)
+                .map_err(|e| format!("Failed to read cache encryption key {:?}: {}", source, e))?,
+        };
+        Self::from_material(&material)
+    }
+
+    /// Parse 32 raw bytes or 64 hex characters (surrounding whitespace ignored)
+    pub fn from_material(material: &[u8]) -> Result<Self, String> {
+        if let Ok(key) = <[u8; 32]>::try_from(material) {
+            return Ok(Self::from_key(key));
+        }
+
+        let text = std::str::from_utf8(material).map(str::trim).unwrap_or_default();
+        if text.len() != 64 || !text.is_ascii() {
+            return Err("Cache encryption key must be 32 bytes or 64 hex characters".to_string());
+        }
+        let mut key = [0u8; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16)
+                .map_err(|_| "Cache encryption key is not valid hex".to_string())?;
+        }
+        Ok(Self::from_key(key))
+    }
+
+    /// Opaque Sled key for a cache key, so request payloads are not stored in the clear
+    pub fn lookup_key(&self, cache_key: &str) -> Vec<u8> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+            .expect("HMAC accepts any key length");
+        mac.update(cache_key.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// Seal a value stored under `lookup_key`: version byte, nonce, ciphertext
+    pub fn encrypt(&self, lookup_key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad: lookup_key })
+            .map_err(|_| "Cache encryption failed".to_string())?;
+
+        let mut sealed = Vec::with_capacity(1 + NONCE_LEN + ciphertext.len());
+        sealed.push(FORMAT_VERSION);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Open a value written by `encrypt`; fails on a wrong key, tampering, or plaintext entries
+    pub fn decrypt(&self, lookup_key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, String> {
+        let body = match sealed.split_first() {
+            Some((&FORMAT_VERSION, body)) if body.len() > NONCE_LEN => body,
+            _ => return Err("Not an encrypted cache entry".to_string()),
+        };
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: lookup_key })
+            .map_err(|_| "Cache entry failed authentication".to_string())
+    }
+}
@@ -0,0 +1,87 @@
+// Cluster-wide cache invalidation over Valkey/Redis pub/sub (feature `cluster-cache`)
+//
+// Every node started with `serve --cache-invalidation-url redis://host:6379`
+// subscribes to one channel. `POST /cache/clear` on any node clears its own
+// caches and publishes its node id there; the other nodes clear their Moka and
+// Sled caches when the message arrives, so no node keeps serving results from
+// before a corpus update. A node ignores its own messages.
+//
+// The subscription reconnects with backoff when the server goes away. Clears
+// published while a node is disconnected are missed (pub/sub keeps no
+// history), so a node clears its caches once more whenever it resubscribes.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::StreamExt;
+use redis::AsyncCommands;
+
+use crate::server::CacheState;
+
+pub const CHANNEL: &str = "toonify:cache:invalidate";
+
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct CacheInvalidation {
+    client: redis::Client,
+    node_id: String,
+}
+
+impl CacheInvalidation {
+    /// A `redis://` (or `rediss://`) URL of the Valkey/Redis server the nodes share
+    pub fn new(url: &str) -> Result<Self, String> {
+        let client = redis::Client::open(url).map_err(|e| format!("Invalid cache invalidation URL {:?}: {}", url, e))?;
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos());
+        Ok(Self { client, node_id: format!("{}-{:x}", std::process::id(), nanos) })
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Tell the other nodes to clear their caches
+    pub async fn publish(&self) -> Result<(), String> {
+        let mut connection = self.client.get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+        let _: () = connection.publish(CHANNEL, &self.node_id).await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Clear `cache` whenever another node publishes; runs until the process exits (needs a Tokio runtime)
+    pub fn subscribe(&self, cache: CacheState) {
+        let invalidation = self.clone();
+        tokio::spawn(async move {
+            let mut backoff = Duration::from_secs(1);
+            loop {
+                match invalidation.listen(&cache).await {
+                    Ok(()) => {
+                        eprintln!("[CACHE] Invalidation channel closed; reconnecting");
+                        backoff = Duration::from_secs(1);
+                    }
+                    Err(e) => {
+                        eprintln!("[CACHE] Invalidation channel error: {}; retrying in {}s", e, backoff.as_secs());
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+            }
+        });
+    }
+
+    async fn listen(&self, cache: &CacheState) -> Result<(), String> {
+        let mut pubsub = self.client.get_async_pubsub().await.map_err(|e| e.to_string())?;
+        pubsub.subscribe(CHANNEL).await.map_err(|e| e.to_string())?;
+        eprintln!("[CACHE] Subscribed to {} as node {}", CHANNEL, self.node_id);
+        // Anything published while we were away is lost; start clean
+        cache.clear();
+
+        let mut messages = pubsub.into_on_message();
+        while let Some(message) = messages.next().await {
+            let sender: String = message.get_payload().unwrap_or_default();
+            if sender != self.node_id {
+                eprintln!("[CACHE] Cleared by node {}", sender);
+                cache.clear();
+            }
+        }
+        Ok(())
+    }
+}
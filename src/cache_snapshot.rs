@@ -0,0 +1,130 @@
+// Warm restarts for the Moka cache (`serve --cache-snapshot PATH`)
+//
+// Every `interval` the entries resident in Moka are written to a snapshot
+// file, and once more on graceful shutdown; at startup the file is loaded
+// back before the first periodic save. Moka only keeps what is hot, so this
+// restores the working set without needing `--persistent-cache`.
+//
+// The file is JSON lines: a header with the TOONify version and the write
+// time, then one `{"key":..,"value":..}` per entry. It is written to a
+// temporary sibling and renamed into place, so a crash mid-save leaves the
+// previous snapshot. A snapshot from another version is ignored (its keys
+// could never hit), and so is one older than the cache TTL. Loaded entries
+// start a fresh TTL.
+
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::server::MokaConversionCache;
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    toonify: String,
+    written: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    key: String,
+    value: String,
+}
+
+/// Write every entry in `moka` to `path`; returns how many were written
+pub async fn save(path: &Path, moka: &MokaConversionCache) -> Result<usize, String> {
+    moka.run_pending_tasks().await;
+    let entries: Vec<Entry> = moka.iter().map(|(key, value)| Entry { key: key.to_string(), value }).collect();
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || write_file(&path, &entries))
+        .await
+        .map_err(|e| format!("Snapshot task failed: {}", e))?
+}
+
+/// Insert the entries of the snapshot at `path` into `moka`; a missing,
+/// stale or foreign snapshot loads nothing
+pub async fn load(path: &Path, moka: &MokaConversionCache, ttl: Option<Duration>) -> Result<usize, String> {
+    let path = path.to_path_buf();
+    let entries = tokio::task::spawn_blocking(move || read_file(&path, ttl))
+        .await
+        .map_err(|e| format!("Snapshot task failed: {}", e))??;
+    let loaded = entries.len();
+    for entry in entries {
+        moka.insert(entry.key, entry.value).await;
+    }
+    Ok(loaded)
+}
+
+/// Load `path`, then save to it every `interval`; runs until the process exits (needs a Tokio runtime)
+pub fn start(path: PathBuf, moka: MokaConversionCache, ttl: Option<Duration>, interval: Duration) {
+    tokio::spawn(async move {
+        match load(&path, &moka, ttl).await {
+            Ok(loaded) => eprintln!("[CACHE] Loaded {} entries from snapshot {:?}", loaded, path),
+            Err(e) => eprintln!("[CACHE] Not loading snapshot {:?}: {}", path, e),
+        }
+        let mut ticks = tokio::time::interval(interval);
+        // The first tick is immediate; skip it so the snapshot just loaded is not rewritten
+        ticks.tick().await;
+        loop {
+            ticks.tick().await;
+            if let Err(e) = save(&path, &moka).await {
+                eprintln!("[CACHE] Failed to write snapshot {:?}: {}", path, e);
+            }
+        }
+    });
+}
+
+fn write_file(path: &Path, entries: &[Entry]) -> Result<usize, String> {
+    let temporary = sibling(path);
+    let file = std::fs::File::create(&temporary).map_err(|e| format!("Failed to create {:?}: {}", temporary, e))?;
+    let mut writer = BufWriter::new(file);
+    let header = Header { toonify: env!("CARGO_PKG_VERSION").to_string(), written: now_secs() };
+    let mut write_line = |line: String| writeln!(writer, "{}", line).map_err(|e| format!("Failed to write {:?}: {}", temporary, e));
+    write_line(serde_json::to_string(&header).map_err(|e| e.to_string())?)?;
+    for entry in entries {
+        write_line(serde_json::to_string(entry).map_err(|e| e.to_string())?)?;
+    }
+    let file = writer.into_inner().map_err(|e| format!("Failed to write {:?}: {}", temporary, e.error()))?;
+    file.sync_all().map_err(|e| format!("Failed to write {:?}: {}", temporary, e))?;
+    std::fs::rename(&temporary, path).map_err(|e| format!("Failed to move {:?} into place: {}", temporary, e))?;
+    Ok(entries.len())
+}
+
+fn read_file(path: &Path, ttl: Option<Duration>) -> Result<Vec<Entry>, String> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to open: {}", e)),
+    };
+    let mut lines = BufReader::new(file).lines();
+    let Some(header) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Header = serde_json::from_str(&header.map_err(|e| e.to_string())?).map_err(|e| format!("Invalid header: {}", e))?;
+    if header.toonify != env!("CARGO_PKG_VERSION") {
+        return Err(format!("written by TOONify {}", header.toonify));
+    }
+    if ttl.is_some_and(|ttl| now_secs().saturating_sub(header.written) > ttl.as_secs()) {
+        return Err("older than the cache TTL".to_string());
+    }
+    let mut entries = Vec::new();
+    for (number, line) in lines.enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("[CACHE] Skipping snapshot line {}: {}", number + 2, e),
+        }
+    }
+    Ok(entries)
+}
+
+fn sibling(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
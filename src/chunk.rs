@@ -0,0 +1,179 @@
+// Splitting a document into prompt-sized TOON documents (`toonify chunk`)
+//
+// Entities are packed into chunks in document order while the chunk stays
+// within `max_tokens`. A table or list that doesn't fit is cut into runs of
+// consecutive rows, each written as a table of its own with the full header,
+// so every chunk parses on its own. How long a run can be is found by
+// serializing candidates and counting them with the caller's tokenizer, so
+// the budget holds for the exact text written. Each chunk ends with a
+// `_chunk` table: one row per entity in the chunk, giving the chunk's place
+// in the sequence and, for split tables, which rows of how many it holds.
+
+use serde_json::{Map, Value};
+
+use crate::toon::{serialize_toon_with, SerializeOptions, ToonWriter};
+
+/// Name of the trailing table that indexes each chunk
+pub const CHUNK_ENTITY: &str = "_chunk";
+
+const FOOTER_COLUMNS: [&str; 6] = ["index", "chunks", "entity", "offset", "rows", "total"];
+
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub output: String,
+    pub tokens: usize,
+}
+
+// One entity, or a run of its rows, placed in a chunk
+struct Part {
+    entity: String,
+    value: Value,
+    /// (offset, rows, total) for tables and lists
+    rows: Option<(usize, usize, usize)>,
+}
+
+struct Budget<'a> {
+    max_tokens: usize,
+    options: &'a SerializeOptions,
+    count_tokens: &'a dyn Fn(&str) -> usize,
+    // Stands in for the chunk index and count while packing; never shorter than the real ones
+    placeholder: usize,
+}
+
+impl Budget<'_> {
+    fn render(&self, parts: &[Part], index: usize, chunks: usize) -> Result<String, String> {
+        let mut document = Map::new();
+        for part in parts {
+            document.insert(part.entity.clone(), part.value.clone());
+        }
+        let mut output = serialize_toon_with(&Value::Object(document), self.options)?;
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        let mut writer = ToonWriter::new(Vec::new());
+        writer.begin_table_with_len(CHUNK_ENTITY, &FOOTER_COLUMNS, parts.len()).map_err(|e| e.to_string())?;
+        for part in parts {
+            let (offset, rows, total) = match part.rows {
+                Some((offset, rows, total)) => (Value::from(offset), Value::from(rows), Value::from(total)),
+                None => (Value::Null, Value::Null, Value::Null),
+            };
+            writer.write_row(&[Value::from(index), Value::from(chunks), Value::from(part.entity.as_str()), offset, rows, total])
+                .map_err(|e| e.to_string())?;
+        }
+        let footer = writer.finish().map_err(|e| e.to_string())?;
+        output.push_str(&String::from_utf8(footer).map_err(|e| e.to_string())?);
+        Ok(output)
+    }
+
+    // Whether `parts` plus `part` still fits one chunk
+    fn fits(&self, parts: &mut Vec<Part>, part: Part) -> Result<bool, String> {
+        parts.push(part);
+        let rendered = self.render(parts, self.placeholder, self.placeholder);
+        parts.pop();
+        Ok((self.count_tokens)(&rendered?) <= self.max_tokens)
+    }
+}
+
+/// Cut `value` into TOON documents of at most `max_tokens` tokens each
+pub fn chunk(
+    value: &Value,
+    max_tokens: usize,
+    options: &SerializeOptions,
+    count_tokens: &dyn Fn(&str) -> usize,
+) -> Result<Vec<Chunk>, String> {
+    let Value::Object(map) = value else {
+        return Err("Only documents with an object root can be chunked".to_string());
+    };
+    if map.contains_key(CHUNK_ENTITY) {
+        return Err(format!("Entity name '{}' is reserved for the chunk index", CHUNK_ENTITY));
+    }
+
+    // Every row in a chunk of its own is the most chunks there can be
+    let placeholder = map.values()
+        .map(|entity| entity.as_array().map_or(1, |items| items.len().max(1)))
+        .sum::<usize>()
+        .max(1);
+    let budget = Budget { max_tokens, options, count_tokens, placeholder };
+
+    let mut chunks: Vec<Vec<Part>> = Vec::new();
+    let mut current: Vec<Part> = Vec::new();
+    for (name, entity) in map {
+        let items = match entity {
+            Value::Array(items) if !items.is_empty() => items,
+            _ => {
+                let whole = || Part { entity: name.clone(), value: entity.clone(), rows: None };
+                if !budget.fits(&mut current, whole())? {
+                    if current.is_empty() || !budget.fits(&mut Vec::new(), whole())? {
+                        return Err(format!("Entity '{}' needs more than {} tokens and is not a table that can be split", name, max_tokens));
+                    }
+                    chunks.push(std::mem::take(&mut current));
+                }
+                current.push(whole());
+                continue;
+            }
+        };
+
+        let mut offset = 0;
+        while offset < items.len() {
+            let run = |end: usize| Part {
+                entity: name.clone(),
+                value: Value::Array(items[offset..end].to_vec()),
+                rows: Some((offset, end - offset, items.len())),
+            };
+            let Some(end) = longest_run(offset, items.len(), |end| budget.fits(&mut current, run(end)))? else {
+                if current.is_empty() {
+                    return Err(format!("Row {} of '{}' needs more than {} tokens on its own", offset, name, max_tokens));
+                }
+                chunks.push(std::mem::take(&mut current));
+                continue;
+            };
+            current.push(run(end));
+            offset = end;
+            if offset < items.len() {
+                chunks.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+
+    let count = chunks.len();
+    chunks.iter()
+        .enumerate()
+        .map(|(index, parts)| {
+            let output = budget.render(parts, index + 1, count)?;
+            Ok(Chunk { tokens: count_tokens(&output), output })
+        })
+        .collect()
+}
+
+// Largest `end` in `start + 1..=len` for which `fits` holds, assuming longer
+// runs never fit when a shorter one doesn't: gallop up, then bisect
+fn longest_run(start: usize, len: usize, mut fits: impl FnMut(usize) -> Result<bool, String>) -> Result<Option<usize>, String> {
+    if !fits(start + 1)? {
+        return Ok(None);
+    }
+    let mut good = start + 1;
+    let mut bad = len + 1;
+    let mut step = 1;
+    while good < len && bad == len + 1 {
+        let probe = (good + step).min(len);
+        if fits(probe)? {
+            good = probe;
+            step *= 2;
+        } else {
+            bad = probe;
+        }
+    }
+    while bad - good > 1 {
+        let mid = good + (bad - good) / 2;
+        if fits(mid)? {
+            good = mid;
+        } else {
+            bad = mid;
+        }
+    }
+    Ok(Some(good))
+}
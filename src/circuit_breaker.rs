@@ -0,0 +1,199 @@
+// Circuit breaker for cache backends (Sled and embedder caches in the server)
+//
+// A backend that keeps failing or answering slowly is skipped for a while, so
+// requests go straight to Moka and the converter instead of paying for it
+// every time. After `open_for` one request probes the backend again; success
+// closes the breaker, failure keeps it open for another round.
+//
+//   closed --(failure_threshold failures in a row)--> open
+//   open   --(open_for elapsed)--> half-open: one probe call
+//   probe ok --> closed    probe failed --> open
+//
+// A call slower than `slow_call` counts as a failure even when it succeeds,
+// and the server stops waiting for an embedder cache at that point. State
+// changes are logged; `stats` feeds `GET /cache/status`.
+//
+// Callers take a `Permit` rather than pairing `allow` and `record` by hand: a
+// permit dropped without an outcome (the request was cancelled mid-call)
+// records a failure, so a cancelled probe cannot leave the breaker half-open.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerOptions {
+    /// Consecutive failures that open the breaker
+    pub failure_threshold: u32,
+    /// Successful calls slower than this count as failures
+    pub slow_call: Duration,
+    /// How long the backend is skipped before a probe
+    pub open_for: Duration,
+}
+
+impl Default for BreakerOptions {
+    fn default() -> Self {
+        Self { failure_threshold: 5, slow_call: Duration::from_millis(250), open_for: Duration::from_secs(30) }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// A probe call is in flight
+    HalfOpen,
+}
+
+pub struct CircuitBreaker {
+    backend: &'static str,
+    options: BreakerOptions,
+    state: Mutex<State>,
+    calls: AtomicU64,
+    failures: AtomicU64,
+    bypassed: AtomicU64,
+    trips: AtomicU64,
+}
+
+/// Counters since start, for `GET /cache/status`
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStats {
+    pub backend: &'static str,
+    /// "closed", "open" or "half-open"
+    pub state: &'static str,
+    pub calls: u64,
+    pub failures: u64,
+    /// Calls skipped while the breaker was open
+    pub bypassed: u64,
+    /// Times the breaker opened
+    pub trips: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new(backend: &'static str, options: BreakerOptions) -> Self {
+        Self {
+            backend,
+            options,
+            state: Mutex::new(State::Closed { failures: 0 }),
+            calls: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            bypassed: AtomicU64::new(0),
+            trips: AtomicU64::new(0),
+        }
+    }
+
+    pub fn options(&self) -> BreakerOptions {
+        self.options
+    }
+
+    /// Whether to call the backend now; every `true` must be followed by `record` (see `permit`)
+    pub fn allow(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        let allowed = match *state {
+            State::Closed { .. } => true,
+            State::Open { until } if Instant::now() >= until => {
+                eprintln!("[CACHE] {} breaker half-open, probing", self.backend);
+                *state = State::HalfOpen;
+                true
+            }
+            State::Open { .. } | State::HalfOpen => false,
+        };
+        if allowed {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.bypassed.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    /// `allow` as a guard that records a failure unless given an outcome
+    pub fn permit(&self) -> Option<Permit<'_>> {
+        self.allow().then(|| Permit { breaker: self, started: Instant::now(), recorded: false })
+    }
+
+    /// The outcome of an allowed call and how long it took
+    pub fn record(&self, succeeded: bool, elapsed: Duration) {
+        let failed = !succeeded || elapsed > self.options.slow_call;
+        if failed {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        *state = match (*state, failed) {
+            (State::HalfOpen, false) => {
+                eprintln!("[CACHE] {} breaker closed, backend recovered", self.backend);
+                State::Closed { failures: 0 }
+            }
+            (State::Closed { .. }, false) => State::Closed { failures: 0 },
+            (State::Closed { failures }, true) if failures + 1 < self.options.failure_threshold => State::Closed { failures: failures + 1 },
+            (State::Closed { .. } | State::HalfOpen, true) => {
+                eprintln!("[CACHE] {} breaker open for {}s: backend failing or slower than {}ms", self.backend, self.options.open_for.as_secs(), self.options.slow_call.as_millis());
+                self.trips.fetch_add(1, Ordering::Relaxed);
+                State::Open { until: Instant::now() + self.options.open_for }
+            }
+            // A call allowed before the breaker opened finished late
+            (open @ State::Open { .. }, _) => open,
+        };
+    }
+
+    pub fn stats(&self) -> BreakerStats {
+        let state = match self.state.lock().map(|state| *state) {
+            Ok(State::Closed { .. }) => "closed",
+            Ok(State::Open { .. }) | Err(_) => "open",
+            Ok(State::HalfOpen) => "half-open",
+        };
+        BreakerStats {
+            backend: self.backend,
+            state,
+            calls: self.calls.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            bypassed: self.bypassed.load(Ordering::Relaxed),
+            trips: self.trips.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// One allowed call, timed from when it was granted
+pub struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    started: Instant,
+    recorded: bool,
+}
+
+impl Permit<'_> {
+    pub fn record(mut self, succeeded: bool) {
+        self.recorded = true;
+        self.breaker.record(succeeded, self.started.elapsed());
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.record(false, self.started.elapsed());
+        }
+    }
+}
+
+/// One breaker per cache backend the server talks to
+pub struct CacheBreakers {
+    pub sled: CircuitBreaker,
+    pub custom: CircuitBreaker,
+}
+
+impl CacheBreakers {
+    pub fn new(options: BreakerOptions) -> Self {
+        Self { sled: CircuitBreaker::new("sled", options), custom: CircuitBreaker::new("custom", options) }
+    }
+}
+
+impl Default for CacheBreakers {
+    fn default() -> Self {
+        Self::new(BreakerOptions::default())
+    }
+}
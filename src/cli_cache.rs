@@ -0,0 +1,137 @@
+// Conversion cache shared across CLI runs (`convert --cache-dir`, `toonify cache gc`)
+//
+// Entries are plain files named by the SHA-256 of everything that decides
+// the output: the toonify version, source and target format, the conversion
+// options and the input bytes. Files rather than a Sled database, because
+// Sled allows one process per database and build scripts run conversions in
+// parallel. Writes go through `write_atomic`, so a concurrent reader sees a
+// complete entry or none; a hit refreshes the entry's mtime, which `gc` uses
+// to expire and evict least recently used entries first.
+//
+//     <cache-dir>/ab/ab3f…e1.out
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+use crate::atomic_write::write_atomic;
+
+const EXTENSION: &str = "out";
+
+pub struct CliCache {
+    dir: PathBuf,
+}
+
+/// What `gc` removed and kept
+pub struct GcReport {
+    pub removed: usize,
+    pub removed_bytes: u64,
+    pub kept: usize,
+    pub kept_bytes: u64,
+}
+
+impl CliCache {
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    /// Digest of the cache key parts; each part is length-prefixed so parts can't run together
+    pub fn key(parts: &[&[u8]]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        for part in parts {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+        hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let path = self.path(key);
+        let content = fs::read_to_string(&path).ok()?;
+        // Best effort: a read-only cache still serves hits
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(content)
+    }
+
+    pub fn put(&self, key: &str, content: &str) -> io::Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&path, content.as_bytes(), false, None)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{}.{}", key, EXTENSION))
+    }
+}
+
+/// Default cache location: `$XDG_CACHE_HOME/toonify`, else `~/.cache/toonify`
+pub fn default_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("toonify"))
+}
+
+/// Remove entries unused for `max_age`, then the least recently used until the cache fits in `max_bytes`
+pub fn gc(dir: &Path, max_age: Option<Duration>, max_bytes: Option<u64>) -> io::Result<GcReport> {
+    let mut entries = Vec::new();
+    for shard in fs::read_dir(dir)? {
+        let shard = shard?.path();
+        if !shard.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&shard)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let modified = metadata.modified()?;
+            // Temp files left behind by an interrupted write
+            if name.ends_with(".tmp") {
+                if modified.elapsed().unwrap_or_default() > Duration::from_secs(3600) {
+                    let _ = fs::remove_file(&path);
+                }
+                continue;
+            }
+            if path.extension().is_some_and(|extension| extension == EXTENSION) {
+                entries.push((path, metadata.len(), modified));
+            }
+        }
+    }
+
+    // Oldest first
+    entries.sort_by_key(|(_, _, modified)| *modified);
+    let mut report = GcReport { removed: 0, removed_bytes: 0, kept: 0, kept_bytes: entries.iter().map(|(_, len, _)| len).sum() };
+    let now = SystemTime::now();
+    for (path, len, modified) in &entries {
+        let expired = max_age.is_some_and(|age| now.duration_since(*modified).unwrap_or_default() > age);
+        let oversized = max_bytes.is_some_and(|max| report.kept_bytes > max);
+        if !expired && !oversized {
+            report.kept += 1;
+            continue;
+        }
+        fs::remove_file(path)?;
+        report.removed += 1;
+        report.removed_bytes += len;
+        report.kept_bytes -= len;
+    }
+
+    // Drop shard directories that are now empty
+    for shard in fs::read_dir(dir)? {
+        let shard = shard?.path();
+        if shard.is_dir() {
+            let _ = fs::remove_dir(&shard);
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,130 @@
+// Defaults for CLI options from ~/.config/toonify/config.toml (feature `config`)
+//
+//     color = "always"              # every command with --color
+//     http_addr = "127.0.0.1:5000"
+//
+//     [batch]
+//     jobs = 4
+//
+//     [cache.gc]
+//     max_age_days = 7
+//
+// Keys are long option names (`-` or `_`). A top-level key sets the default
+// of that option on the root command and every subcommand; inside a table it
+// applies only to the named subcommand and the ones below it. Values become
+// clap defaults, so flags on the command line win, then the `TOONIFY_*`
+// environment variables declared on the options, then this file.
+//
+// $TOONIFY_CONFIG names another file, or `none` to skip it. Keys that match
+// no option are errors, so a typo doesn't silently do nothing.
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use clap::Command;
+use toml::{Table, Value};
+
+/// Apply the config file's defaults to the CLI definition
+pub fn apply(command: Command) -> Result<Command, String> {
+    let explicit = env::var_os("TOONIFY_CONFIG");
+    let path = match &explicit {
+        Some(path) if path == "none" => return Ok(command),
+        Some(path) => PathBuf::from(path),
+        None => match default_path() {
+            Some(path) => path,
+            None => return Ok(command),
+        },
+    };
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound && explicit.is_none() => return Ok(command),
+        Err(e) => return Err(format!("Failed to read config {:?}: {}", path, e)),
+    };
+    let table: Table = content.parse().map_err(|e| format!("Invalid config {:?}: {}", path, e))?;
+    check(&command, &table, "").map_err(|e| format!("Invalid config {:?}: {}", path, e))?;
+    Ok(apply_table(command, &table))
+}
+
+/// `$XDG_CONFIG_HOME/toonify/config.toml`, else `~/.config/toonify/config.toml`
+pub fn default_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")).map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("toonify").join("config.toml"))
+}
+
+fn check(command: &Command, table: &Table, section: &str) -> Result<(), String> {
+    for (key, value) in table {
+        let qualified = if section.is_empty() { key.clone() } else { format!("{}.{}", section, key) };
+        match value {
+            Value::Table(nested) => {
+                let sub = command
+                    .find_subcommand(long_name(key))
+                    .ok_or_else(|| format!("[{}] is not a toonify command", qualified))?;
+                check(sub, nested, &qualified)?;
+            }
+            value => {
+                values(value).map_err(|e| format!("{}: {}", qualified, e))?;
+                if !has_option(command, &long_name(key)) {
+                    return Err(format!("{} is not an option of {}", qualified, if section.is_empty() { "any command" } else { section }));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Scalars first, so a table's more specific value overrides a top-level one
+fn apply_table(mut command: Command, table: &Table) -> Command {
+    for (key, value) in table.iter().filter(|(_, value)| !value.is_table()) {
+        command = set_default(command, &long_name(key), &values(value).unwrap_or_default());
+    }
+    for (key, value) in table {
+        if let Value::Table(nested) = value {
+            command = command.mut_subcommand(long_name(key), |sub| apply_table(sub, nested));
+        }
+    }
+    command
+}
+
+fn set_default(mut command: Command, long: &str, defaults: &[String]) -> Command {
+    let ids: Vec<String> = command.get_arguments().filter(|arg| arg.get_long() == Some(long)).map(|arg| arg.get_id().to_string()).collect();
+    for id in ids {
+        command = command.mut_arg(id, |arg| arg.default_values(defaults.to_vec()));
+    }
+    let subcommands: Vec<String> = command.get_subcommands().map(|sub| sub.get_name().to_string()).collect();
+    for name in subcommands {
+        command = command.mut_subcommand(name, |sub| set_default(sub, long, defaults));
+    }
+    command
+}
+
+fn has_option(command: &Command, long: &str) -> bool {
+    command.get_arguments().any(|arg| arg.get_long() == Some(long)) || command.get_subcommands().any(|sub| has_option(sub, long))
+}
+
+fn long_name(key: &str) -> String {
+    key.replace('_', "-")
+}
+
+// Arrays fill options that take several values (value_delimiter = ',')
+fn values(value: &Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::Array(items) => items.iter().map(scalar).collect(),
+        value => Ok(vec![scalar(value)?]),
+    }
+}
+
+fn scalar(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Integer(n) => Ok(n.to_string()),
+        Value::Float(f) => Ok(f.to_string()),
+        Value::Boolean(b) => Ok(b.to_string()),
+        Value::Datetime(d) => Ok(d.to_string()),
+        Value::Array(_) | Value::Table(_) => Err("expected a string, number, boolean, or a list of them".to_string()),
+    }
+}
@@ -0,0 +1,315 @@
+// Thin API clients for integrators (`toonify gen-client --lang python --out dir/`)
+//
+// Each client wraps the REST conversion endpoints in one method apiece,
+// posting `{"data": ...}` and returning `result`, or raising/returning the
+// server's `error`. The methods are rendered from `ENDPOINTS`, which mirrors
+// the conversion routes in `server::routes`, and use only the standard library
+// (urllib, fetch, net/http), so there is nothing to install. The generated
+// directory also gets a copy of proto/converter.proto for gRPC users, to
+// feed to protoc or their language's gRPC tooling.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// The service definition, shipped next to the REST client for gRPC code generation
+pub const CONVERTER_PROTO: &str = include_str!("../proto/converter.proto");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientLang {
+    Python,
+    TypeScript,
+    Go,
+}
+
+impl FromStr for ClientLang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "python" | "py" => Ok(ClientLang::Python),
+            "typescript" | "ts" => Ok(ClientLang::TypeScript),
+            "go" | "golang" => Ok(ClientLang::Go),
+            _ => Err(format!("Unknown client language '{}' (expected python, typescript or go)", s)),
+        }
+    }
+}
+
+/// A REST conversion endpoint: `{name}` segments in `path` are method parameters
+pub struct Endpoint {
+    /// snake_case method name
+    pub name: &'static str,
+    pub path: &'static str,
+    pub doc: &'static str,
+}
+
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint { name: "json_to_toon", path: "/json-to-toon", doc: "Converts JSON to TOON" },
+    Endpoint { name: "toon_to_json", path: "/toon-to-json", doc: "Converts TOON to JSON" },
+    Endpoint { name: "convert", path: "/convert/{source}/{target}", doc: "Converts between any two formats the server has registered" },
+];
+
+/// A file to write, relative to the output directory
+pub struct GeneratedFile {
+    pub path: String,
+    pub contents: String,
+}
+
+/// The client sources for `lang`, plus converter.proto
+pub fn generate(lang: ClientLang) -> Vec<GeneratedFile> {
+    let (path, contents) = match lang {
+        ClientLang::Python => ("toonify_client.py", python()),
+        ClientLang::TypeScript => ("toonifyClient.ts", typescript()),
+        ClientLang::Go => ("toonify/client.go", go()),
+    };
+    vec![
+        GeneratedFile { path: path.to_string(), contents },
+        GeneratedFile { path: "converter.proto".to_string(), contents: CONVERTER_PROTO.to_string() },
+    ]
+}
+
+enum Segment<'a> {
+    Literal(&'a str),
+    Param(&'a str),
+}
+
+fn segments(path: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').map_or(rest.len(), |end| start + end);
+        segments.push(Segment::Literal(&rest[..start]));
+        segments.push(Segment::Param(&rest[start + 1..end]));
+        rest = &rest[(end + 1).min(rest.len())..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+    segments
+}
+
+fn params(path: &str) -> Vec<&str> {
+    segments(path).into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Param(name) => Some(name),
+            Segment::Literal(_) => None,
+        })
+        .collect()
+}
+
+fn camel_case(name: &str, upper_first: bool) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = upper_first;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn header(comment: &str) -> String {
+    format!(
+        "{} TOONify API client, generated by `toonify gen-client` {}; do not edit.\n",
+        comment,
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+fn python() -> String {
+    let mut out = header("#");
+    out.push_str(r#"
+import json
+import urllib.error
+import urllib.parse
+import urllib.request
+
+
+class ToonifyError(Exception):
+    """A conversion the server rejected"""
+
+
+class ToonifyClient:
+    def __init__(self, base_url="http://localhost:5000", timeout=30.0):
+        self.base_url = base_url.rstrip("/")
+        self.timeout = timeout
+
+    def _post(self, path, data):
+        body = json.dumps({"data": data}).encode("utf-8")
+        request = urllib.request.Request(
+            self.base_url + path,
+            data=body,
+            headers={"Content-Type": "application/json"},
+            method="POST",
+        )
+        try:
+            with urllib.request.urlopen(request, timeout=self.timeout) as response:
+                reply = json.load(response)
+        except urllib.error.HTTPError as e:
+            raise ToonifyError(f"HTTP {e.code}: {e.read().decode('utf-8', 'replace')}") from e
+        if reply.get("error"):
+            raise ToonifyError(reply["error"])
+        return reply["result"]
+"#);
+    for endpoint in ENDPOINTS {
+        let args: String = params(endpoint.path).iter().map(|param| format!(", {}", param)).collect();
+        let path: String = segments(endpoint.path).iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.to_string(),
+                Segment::Param(name) => format!("{{urllib.parse.quote({}, safe='')}}", name),
+            })
+            .collect();
+        let _ = write!(
+            out,
+            "\n    def {}(self, data{}):\n        \"\"\"{} (POST {})\"\"\"\n        return self._post(f\"{}\", data)\n",
+            endpoint.name, args, endpoint.doc, endpoint.path, path
+        );
+    }
+    out
+}
+
+fn typescript() -> String {
+    let mut out = header("//");
+    out.push_str(r#"
+export class ToonifyError extends Error {}
+
+interface ConvertResult {
+  result?: string | null;
+  error?: string | null;
+  warnings?: string[];
+}
+
+export class ToonifyClient {
+  private readonly baseUrl: string;
+
+  constructor(baseUrl = "http://localhost:5000") {
+    this.baseUrl = baseUrl.replace(/\/+$/, "");
+  }
+
+  private async post(path: string, data: string): Promise<string> {
+    const response = await fetch(this.baseUrl + path, {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify({ data }),
+    });
+    if (!response.ok) {
+      throw new ToonifyError(`HTTP ${response.status}: ${await response.text()}`);
+    }
+    const reply = (await response.json()) as ConvertResult;
+    if (reply.error) {
+      throw new ToonifyError(reply.error);
+    }
+    return reply.result ?? "";
+  }
+"#);
+    for endpoint in ENDPOINTS {
+        let args: String = params(endpoint.path).iter().map(|param| format!(", {}: string", param)).collect();
+        let path: String = segments(endpoint.path).iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.to_string(),
+                Segment::Param(name) => format!("${{encodeURIComponent({})}}", name),
+            })
+            .collect();
+        let _ = write!(
+            out,
+            "\n  /** {} (POST {}) */\n  {}(data: string{}): Promise<string> {{\n    return this.post(`{}`, data);\n  }}\n",
+            endpoint.doc, endpoint.path, camel_case(endpoint.name, false), args, path
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn go() -> String {
+    let mut out = header("//");
+    out.push_str(r#"
+package toonify
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"strings"
+)
+
+// Client calls a TOONify server's REST API
+type Client struct {
+	BaseURL    string
+	HTTPClient *http.Client
+}
+
+// NewClient returns a client for the server at baseURL, e.g. http://localhost:5000
+func NewClient(baseURL string) *Client {
+	return &Client{BaseURL: strings.TrimRight(baseURL, "/"), HTTPClient: http.DefaultClient}
+}
+
+type convertResult struct {
+	Result   *string  `json:"result"`
+	Error    *string  `json:"error"`
+	Warnings []string `json:"warnings"`
+}
+
+func (c *Client) post(ctx context.Context, path, data string) (string, error) {
+	body, err := json.Marshal(map[string]string{"data": data})
+	if err != nil {
+		return "", err
+	}
+	request, err := http.NewRequestWithContext(ctx, http.MethodPost, c.BaseURL+path, bytes.NewReader(body))
+	if err != nil {
+		return "", err
+	}
+	request.Header.Set("Content-Type", "application/json")
+	response, err := c.HTTPClient.Do(request)
+	if err != nil {
+		return "", err
+	}
+	defer response.Body.Close()
+	if response.StatusCode >= 300 {
+		text, _ := io.ReadAll(response.Body)
+		return "", fmt.Errorf("HTTP %d: %s", response.StatusCode, text)
+	}
+	var reply convertResult
+	if err := json.NewDecoder(response.Body).Decode(&reply); err != nil {
+		return "", err
+	}
+	if reply.Error != nil && *reply.Error != "" {
+		return "", errors.New(*reply.Error)
+	}
+	if reply.Result == nil {
+		return "", nil
+	}
+	return *reply.Result, nil
+}
+"#);
+    for endpoint in ENDPOINTS {
+        let args: String = params(endpoint.path).iter().map(|param| format!(", {} string", param)).collect();
+        let path = segments(endpoint.path).iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => format!("{:?}", text),
+                Segment::Param(name) => format!("url.PathEscape({})", name),
+            })
+            .collect::<Vec<_>>()
+            .join(" + ");
+        let name = camel_case(endpoint.name, true);
+        let _ = write!(
+            out,
+            "\n// {} {} (POST {})\nfunc (c *Client) {}(ctx context.Context, data string{}) (string, error) {{\n\treturn c.post(ctx, {}, data)\n}}\n",
+            name, lowercase_first(endpoint.doc), endpoint.path, name, args, path
+        );
+    }
+    out
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or_else(String::new, |first| first.to_lowercase().chain(chars).collect())
+}
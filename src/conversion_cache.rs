@@ -0,0 +1,61 @@
+// Pluggable result caches for embedders
+//
+// `CachedConverter::with_cache` and `ServerBuilder::conversion_cache` read
+// through any `ConversionCache`, for instance a wrapper around the
+// application's own Redis client, instead of (or, in the server, behind) the
+// built-in Moka/Sled pair. Keys are opaque and already name the formats, the
+// options and the TOONify version, so an implementation only stores strings.
+//
+// Calls are synchronous; the server runs them on the blocking pool, so an
+// implementation may do network I/O. Report backend failures as `Err`: the
+// caller treats them as misses, and the server's circuit breaker stops
+// calling a backend that keeps failing or answering slowly.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub trait ConversionCache: Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<String>, String>;
+    fn put(&self, key: &str, value: &str) -> Result<(), String>;
+    fn clear(&self) -> Result<(), String>;
+}
+
+/// An unbounded in-process map; handy in tests and as the smallest example
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map_or(0, |entries| entries.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, String>>, String> {
+        self.entries.lock().map_err(|_| "MemoryCache lock poisoned".to_string())
+    }
+}
+
+impl ConversionCache for MemoryCache {
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        Ok(self.lock()?.get(key).cloned())
+    }
+
+    fn put(&self, key: &str, value: &str) -> Result<(), String> {
+        self.lock()?.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        self.lock()?.clear();
+        Ok(())
+    }
+}
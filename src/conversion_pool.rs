@@ -0,0 +1,163 @@
+// Dedicated thread pool for server conversions
+//
+// Parsing and serializing are CPU-bound, so running them inside async
+// handlers stalls the Tokio runtime under load. Every REST and gRPC
+// conversion is handed to this rayon pool instead. The queue in front of it
+// is bounded: when `max_queued` conversions are already waiting or running,
+// new requests are shed with 429 / RESOURCE_EXHAUSTED rather than piling up.
+//
+// A timed-out conversion cannot be cancelled; it keeps its pool thread (and
+// its queue slot) until it finishes, so stuck work shows up as back-pressure.
+//
+// With `small_lane`, inputs up to a size threshold run on a second, smaller
+// pool with its own queue, so editor and other interactive requests keep
+// answering in milliseconds while multi-MB conversions saturate the main
+// pool. Each lane sheds on its own bound.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use tonic::Status;
+
+/// Default bound on queued plus running conversions
+pub const DEFAULT_MAX_QUEUED: usize = 256;
+
+/// Default size limit for the small-request lane (`serve --small-request-bytes`)
+pub const DEFAULT_SMALL_REQUEST_BYTES: usize = 64 * 1024;
+
+// Server-side limits applied to every REST and gRPC conversion
+#[derive(Clone)]
+pub struct ConversionLimits {
+    timeout: Option<Duration>,
+    lane: Lane,
+    /// Inputs up to this many bytes, and the lane they run on
+    small: Option<(usize, Lane)>,
+    max_queued: usize,
+}
+
+// A pool and the count of conversions queued on or running in it
+#[derive(Clone)]
+struct Lane {
+    pool: Arc<rayon::ThreadPool>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl Lane {
+    fn new(threads: usize, name: &'static str) -> Result<Self, String> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(move |i| format!("toonify-{}-{}", name, i))
+            .build()
+            .map_err(|e| format!("Failed to start conversion pool: {}", e))?;
+        Ok(Self { pool: Arc::new(pool), queued: Arc::new(AtomicUsize::new(0)) })
+    }
+}
+
+#[derive(Debug)]
+pub enum ConversionFailure {
+    TimedOut(Duration),
+    Overloaded(usize),
+    Panicked,
+}
+
+impl ConversionLimits {
+    /// `threads == 0` uses one thread per CPU
+    pub fn new(timeout: Option<Duration>, threads: usize, max_queued: usize) -> Result<Self, String> {
+        Ok(Self {
+            timeout,
+            lane: Lane::new(threads, "convert")?,
+            small: None,
+            max_queued: max_queued.max(1),
+        })
+    }
+
+    /// Run inputs of at most `max_bytes` on a separate pool of `threads` threads
+    pub fn small_lane(mut self, max_bytes: usize, threads: usize) -> Result<Self, String> {
+        self.small = Some((max_bytes, Lane::new(threads.max(1), "small")?));
+        Ok(self)
+    }
+
+    pub fn threads(&self) -> usize {
+        self.lane.pool.current_num_threads()
+    }
+
+    /// Size limit and thread count of the small-request lane, when there is one
+    pub fn small_lane_config(&self) -> Option<(usize, usize)> {
+        self.small.as_ref().map(|(max_bytes, lane)| (*max_bytes, lane.pool.current_num_threads()))
+    }
+
+    /// Whether new conversions would be shed right now (small requests may still be accepted)
+    pub fn saturated(&self) -> bool {
+        self.lane.queued.load(Ordering::Acquire) >= self.max_queued
+    }
+
+    /// Run `work`, which converts `input_bytes` of input, on the lane for its size
+    pub async fn run<T, F>(&self, input_bytes: usize, work: F) -> Result<T, ConversionFailure>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let lane = match &self.small {
+            Some((max_bytes, small)) if input_bytes <= *max_bytes => small,
+            _ => &self.lane,
+        };
+
+        // Reserve a slot first so concurrent requests cannot overshoot the bound
+        if lane.queued.fetch_add(1, Ordering::AcqRel) >= self.max_queued {
+            lane.queued.fetch_sub(1, Ordering::AcqRel);
+            return Err(ConversionFailure::Overloaded(self.max_queued));
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let queued = Arc::clone(&lane.queued);
+        lane.pool.spawn(move || {
+            // rayon aborts the process on a panicking job, so catch it here
+            let result = panic::catch_unwind(AssertUnwindSafe(work));
+            queued.fetch_sub(1, Ordering::AcqRel);
+            let _ = tx.send(result);
+        });
+
+        let joined = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, rx)
+                .await
+                .map_err(|_| ConversionFailure::TimedOut(timeout))?,
+            None => rx.await,
+        };
+        match joined {
+            Ok(Ok(value)) => Ok(value),
+            _ => Err(ConversionFailure::Panicked),
+        }
+    }
+}
+
+impl ConversionFailure {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ConversionFailure::TimedOut(_) => StatusCode::GATEWAY_TIMEOUT,
+            ConversionFailure::Overloaded(_) => StatusCode::TOO_MANY_REQUESTS,
+            ConversionFailure::Panicked => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn grpc_status(&self) -> Status {
+        match self {
+            ConversionFailure::TimedOut(_) => Status::deadline_exceeded(self.to_string()),
+            ConversionFailure::Overloaded(_) => Status::resource_exhausted(self.to_string()),
+            ConversionFailure::Panicked => Status::internal(self.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ConversionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConversionFailure::TimedOut(timeout) => write!(f, "Conversion timed out after {}ms", timeout.as_millis()),
+            ConversionFailure::Overloaded(max) => write!(f, "Server busy: {} conversions already queued", max),
+            ConversionFailure::Panicked => write!(f, "Conversion failed unexpectedly"),
+        }
+    }
+}
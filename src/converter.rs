@@ -1,16 +1,918 @@
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
 use serde_json::Value;
-use crate::toon::{parse_toon, serialize_toon};
+use crate::toon::{parse_toon, parse_toon_lossy, parse_toon_with, serialize_toon_chunked, serialize_toon_with, ArrayLayout, DuplicateKeyPolicy, ParseOptions, RecoverableError, SerializeOptions};
+use crate::guards::ParserGuards;
+use crate::locale::Locale;
+use crate::secrets::{scan_value, SecretPolicy};
+use crate::type_rules::TypeRules;
+
+/// A text format that can be parsed into and emitted from a JSON `Value`
+///
+/// Every conversion goes through `Value`, so registering a codec makes the
+/// format available as both a source and a target for all other formats.
+pub trait FormatCodec: Send + Sync {
+    /// Canonical lowercase name used by the CLI and server routes (e.g. "json")
+    fn name(&self) -> &'static str;
+
+    /// File extensions for this format, preferred extension first
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Media types for this format, preferred first; `POST /convert` negotiates with these
+    fn media_types(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Parse input text into a `Value`
+    fn parse(&self, input: &str) -> Result<Value, String>;
+
+    /// Emit a `Value` as text in this format
+    fn emit(&self, value: &Value) -> Result<String, String>;
+
+    /// Parse input, resolving repeated keys with `policy` and reporting them as warnings
+    ///
+    /// Formats whose parser cannot observe duplicates keep the default,
+    /// which ignores the policy.
+    fn parse_with_policy(&self, input: &str, policy: DuplicateKeyPolicy) -> Result<(Value, Vec<String>), String> {
+        let _ = policy;
+        self.parse(input).map(|value| (value, Vec::new()))
+    }
+}
+
+pub struct JsonCodec;
+
+impl FormatCodec for JsonCodec {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn media_types(&self) -> &'static [&'static str] {
+        &["application/json"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        serde_json::from_str(input)
+            .map_err(|e| format!("Invalid JSON: {}", e))
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize JSON: {}", e))
+    }
+
+    fn parse_with_policy(&self, input: &str, policy: DuplicateKeyPolicy) -> Result<(Value, Vec<String>), String> {
+        crate::json::parse_json_with_policy(input, policy)
+    }
+}
+
+#[derive(Default)]
+pub struct ToonCodec {
+    pub options: SerializeOptions,
+}
+
+impl ToonCodec {
+    pub fn with_options(options: SerializeOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl FormatCodec for ToonCodec {
+    fn name(&self) -> &'static str {
+        "toon"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["toon"]
+    }
+
+    fn media_types(&self) -> &'static [&'static str] {
+        &["application/toon", "text/toon"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        parse_toon(input)
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        serialize_toon_with(value, &self.options)
+    }
+
+    fn parse_with_policy(&self, input: &str, policy: DuplicateKeyPolicy) -> Result<(Value, Vec<String>), String> {
+        parse_toon_with(input, &ParseOptions { duplicate_keys: policy, ..Default::default() })
+    }
+}
+
+/// One `entity: key=value ...` line per record, see `crate::rows`
+pub struct RowsCodec;
+
+impl FormatCodec for RowsCodec {
+    fn name(&self) -> &'static str {
+        "rows"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rows"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        crate::rows::parse_rows(input)
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        crate::rows::emit_rows(value)
+    }
+}
+
+#[cfg(feature = "yaml")]
+pub struct YamlCodec;
+
+#[cfg(feature = "yaml")]
+impl FormatCodec for YamlCodec {
+    fn name(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yaml", "yml"]
+    }
+
+    fn media_types(&self) -> &'static [&'static str] {
+        &["application/yaml", "application/x-yaml", "text/yaml"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        serde_yaml::from_str(input)
+            .map_err(|e| format!("Invalid YAML: {}", e))
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        serde_yaml::to_string(value)
+            .map_err(|e| format!("Failed to serialize YAML: {}", e))
+    }
+}
+
+/// CSV codec mapping one table to and from `{"rows": [...]}`
+#[cfg(feature = "csv")]
+pub struct CsvCodec;
+
+#[cfg(feature = "csv")]
+impl FormatCodec for CsvCodec {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    fn media_types(&self) -> &'static [&'static str] {
+        &["text/csv"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        parse_csv(input, &ParseOptions::default(), None)
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        // Accept either a bare array of objects or an object holding exactly one table
+        let rows = match value {
+            Value::Array(rows) => rows,
+            Value::Object(map) if map.len() == 1 => match map.values().next() {
+                Some(Value::Array(rows)) => rows,
+                _ => return Err("CSV output requires a single array of objects".to_string()),
+            },
+            _ => return Err("CSV output requires a single array of objects".to_string()),
+        };
+
+        let mut columns: Vec<String> = Vec::new();
+        for row in rows {
+            let obj = row.as_object()
+                .ok_or("CSV output requires a single array of objects")?;
+            for key in obj.keys() {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(&columns)
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+
+        for row in rows {
+            let obj = row.as_object()
+                .ok_or("CSV output requires a single array of objects")?;
+            let cells: Vec<String> = columns
+                .iter()
+                .map(|col| match obj.get(col) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                })
+                .collect();
+            writer.write_record(&cells)
+                .map_err(|e| format!("Failed to write CSV: {}", e))?;
+        }
+
+        let bytes = writer.into_inner()
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+        String::from_utf8(bytes)
+            .map_err(|e| format!("Failed to write CSV: {}", e))
+    }
+}
+
+// Cells of columns with a declared type are read as that type, others with
+// the locale's separators when there is one, before falling back to sniffing
+#[cfg(feature = "csv")]
+fn parse_csv(input: &str, options: &ParseOptions, locale: Option<&Locale>) -> Result<Value, String> {
+    let mut reader = csv::Reader::from_reader(input.as_bytes());
+    let headers = reader.headers()
+        .map_err(|e| format!("Invalid CSV: {}", e))?
+        .clone();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+        let mut row = serde_json::Map::new();
+        for (header, cell) in headers.iter().zip(record.iter()) {
+            let value = match options.column_type("rows", header) {
+                Some(ty) => ty.parse_cell(cell, false),
+                None => locale.and_then(|locale| locale.parse_text(cell))
+                    .unwrap_or_else(|| crate::toon::parse_value(cell)),
+            };
+            row.insert(header.to_string(), value);
+        }
+        rows.push(Value::Object(row));
+    }
+
+    let mut root = serde_json::Map::new();
+    root.insert("rows".to_string(), Value::Array(rows));
+    Ok(Value::Object(root))
+}
+
+#[cfg(feature = "toml")]
+pub struct TomlCodec;
+
+#[cfg(feature = "toml")]
+impl FormatCodec for TomlCodec {
+    fn name(&self) -> &'static str {
+        "toml"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["toml"]
+    }
+
+    fn media_types(&self) -> &'static [&'static str] {
+        &["application/toml"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        toml::from_str(input)
+            .map_err(|e| format!("Invalid TOML: {}", e))
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        toml::to_string(value)
+            .map_err(|e| format!("Failed to serialize TOML: {}", e))
+    }
+}
+
+/// XML codec using quick-xml's serde mapping (attributes as `@name`, text as `$text`)
+#[cfg(feature = "xml")]
+pub struct XmlCodec;
+
+#[cfg(feature = "xml")]
+impl FormatCodec for XmlCodec {
+    fn name(&self) -> &'static str {
+        "xml"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xml"]
+    }
+
+    fn media_types(&self) -> &'static [&'static str] {
+        &["application/xml", "text/xml"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        quick_xml::de::from_str(input)
+            .map_err(|e| format!("Invalid XML: {}", e))
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        quick_xml::se::to_string_with_root("root", value)
+            .map_err(|e| format!("Failed to serialize XML: {}", e))
+    }
+}
+
+/// Set of codecs that conversions are routed through by format name
+pub struct FormatRegistry {
+    codecs: Vec<Box<dyn FormatCodec>>,
+}
+
+impl FormatRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self { codecs: Vec::new() }
+    }
+
+    /// Create a registry with every format compiled into this build
+    pub fn with_builtin_formats() -> Self {
+        let mut registry = Self::new();
+        registry.register(JsonCodec);
+        registry.register(ToonCodec::default());
+        registry.register(RowsCodec);
+        #[cfg(feature = "yaml")]
+        registry.register(YamlCodec);
+        #[cfg(feature = "csv")]
+        registry.register(CsvCodec);
+        #[cfg(feature = "toml")]
+        registry.register(TomlCodec);
+        #[cfg(feature = "xml")]
+        registry.register(XmlCodec);
+        registry
+    }
+
+    /// Register a codec, replacing any existing codec with the same name
+    pub fn register<C: FormatCodec + 'static>(&mut self, codec: C) {
+        self.codecs.retain(|existing| existing.name() != codec.name());
+        self.codecs.push(Box::new(codec));
+    }
+
+    /// Look up a codec by name (case-insensitive)
+    pub fn get(&self, name: &str) -> Option<&dyn FormatCodec> {
+        self.codecs
+            .iter()
+            .find(|codec| codec.name().eq_ignore_ascii_case(name))
+            .map(|codec| codec.as_ref())
+    }
+
+    /// Look up a codec by file extension (without the leading dot)
+    pub fn for_extension(&self, extension: &str) -> Option<&dyn FormatCodec> {
+        self.codecs
+            .iter()
+            .find(|codec| codec.extensions().iter().any(|ext| ext.eq_ignore_ascii_case(extension)))
+            .map(|codec| codec.as_ref())
+    }
+
+    /// Look up a codec by media type, ignoring parameters such as `charset`
+    pub fn for_media_type(&self, media_type: &str) -> Option<&dyn FormatCodec> {
+        let essence = media_type.split(';').next().unwrap_or_default().trim();
+        self.codecs
+            .iter()
+            .find(|codec| codec.media_types().iter().any(|known| known.eq_ignore_ascii_case(essence)))
+            .map(|codec| codec.as_ref())
+    }
+
+    /// Media types of all registered formats
+    pub fn media_types(&self) -> Vec<&'static str> {
+        self.codecs.iter().flat_map(|codec| codec.media_types().iter().copied()).collect()
+    }
+
+    /// Names of all registered formats in registration order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.codecs.iter().map(|codec| codec.name()).collect()
+    }
+
+    /// Parse `input` with the codec registered as `format`
+    pub fn parse(&self, input: &str, format: &str) -> Result<Value, String> {
+        let source = self.get(format)
+            .ok_or_else(|| format!("Unsupported source format: {} (available: {})", format, self.names().join(", ")))?;
+        source.parse(input)
+    }
+
+    /// Emit `value` with the codec registered as `format`
+    pub fn emit(&self, value: &Value, format: &str) -> Result<String, String> {
+        let target = self.get(format)
+            .ok_or_else(|| format!("Unsupported target format: {} (available: {})", format, self.names().join(", ")))?;
+        target.emit(value)
+    }
+
+    /// Parse `input` as format `from` and emit it as format `to`
+    pub fn convert(&self, input: &str, from: &str, to: &str) -> Result<String, String> {
+        // Resolve the target up front so an unknown format fails before parsing
+        if self.get(to).is_none() {
+            return Err(format!("Unsupported target format: {} (available: {})", to, self.names().join(", ")));
+        }
+
+        let value = self.parse(input, from)?;
+        self.emit(&value, to)
+    }
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        Self::with_builtin_formats()
+    }
+}
+
+fn shared_registry() -> &'static Arc<FormatRegistry> {
+    static REGISTRY: OnceLock<Arc<FormatRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Arc::new(FormatRegistry::with_builtin_formats()))
+}
+
+/// Shared registry of the built-in formats
+pub fn registry() -> &'static FormatRegistry {
+    shared_registry()
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Whether `content` starts with the gzip magic bytes
+pub fn is_gzip(content: &[u8]) -> bool {
+    content.starts_with(&GZIP_MAGIC)
+}
+
+/// Guess the format of `content`, read from `path` when there is one
+///
+/// Gzip magic bytes give "gzip". Otherwise a file extension claimed by a
+/// built-in codec decides (`.json`, `.toon`, `.yaml`, ...), looking through
+/// a `.gz` suffix: `data.toon.gz` holds TOON once decompressed. Without
+/// either, the content is sniffed: JSON that parses, a TOON array header on
+/// the first line, a YAML `---` marker or an XML declaration; anything else
+/// is taken for TOON.
+pub fn detect_format(path: Option<&Path>, content: &[u8]) -> Result<&'static str, String> {
+    if is_gzip(content) {
+        return Ok("gzip");
+    }
+    if let Some(format) = path.and_then(format_for_path) {
+        return Ok(format);
+    }
+    let text = std::str::from_utf8(content).map_err(|e| format!("Input is neither gzip nor UTF-8 text: {}", e))?;
+    Ok(sniff_format(text))
+}
+
+fn format_for_path(path: &Path) -> Option<&'static str> {
+    let mut extension = path.extension()?.to_str()?;
+    if extension.eq_ignore_ascii_case("gz") {
+        extension = Path::new(path.file_stem()?).extension()?.to_str()?;
+    }
+    registry().for_extension(extension).map(|codec| codec.name())
+}
+
+fn sniff_format(text: &str) -> &'static str {
+    let trimmed = text.trim_start_matches('\u{feff}').trim();
+    let parses_as_json = || serde_json::from_str::<Value>(trimmed).is_ok();
+    if (trimmed.starts_with('{') || trimmed.starts_with('[')) && parses_as_json() {
+        return "json";
+    }
+    let first_line = trimmed.lines().next().unwrap_or_default().trim_end();
+    if is_toon_array_header(first_line) {
+        return "toon";
+    }
+    let marked = match first_line {
+        "---" => registry().get("yaml"),
+        line if line.starts_with("<?xml") => registry().get("xml"),
+        _ => None,
+    };
+    if let Some(codec) = marked {
+        return codec.name();
+    }
+    // Bare JSON scalars such as `42` or `"text"`
+    if parses_as_json() {
+        return "json";
+    }
+    "toon"
+}
+
+// `users[2]{id,name}:`, `tags[3]:` or a root `[2]:`, with anything after the colon
+fn is_toon_array_header(line: &str) -> bool {
+    let Some((key, rest)) = line.split_once('[') else {
+        return false;
+    };
+    let Some((count, rest)) = rest.split_once(']') else {
+        return false;
+    };
+    if key.contains([':', '{', '"']) || count.is_empty() || !count.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let rest = match rest.strip_prefix('{') {
+        Some(fields) => match fields.split_once('}') {
+            Some((_, rest)) => rest,
+            None => return false,
+        },
+        None => rest,
+    };
+    rest.starts_with(':')
+}
+
+/// A `Value` transformation run during conversion
+pub type ValueHook = Arc<dyn Fn(Value) -> Result<Value, String> + Send + Sync>;
+
+/// Builder for a `Converter` with custom formats and transformation hooks
+#[derive(Default)]
+pub struct ConverterBuilder {
+    registry: Option<FormatRegistry>,
+    pre_hooks: Vec<ValueHook>,
+    post_hooks: Vec<ValueHook>,
+    toon_options: SerializeOptions,
+    duplicate_keys: DuplicateKeyPolicy,
+    guards: ParserGuards,
+    paths: PathMode,
+    secrets: SecretPolicy,
+    type_rules: TypeRules,
+    locale: Option<Locale>,
+}
+
+impl ConverterBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a custom format registry instead of the built-in one
+    pub fn with_registry(mut self, registry: FormatRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Options used whenever this converter emits TOON
+    pub fn with_toon_options(mut self, options: SerializeOptions) -> Self {
+        self.toon_options = options;
+        self
+    }
+
+    /// Annotate TOON header columns with their types
+    pub fn typed_headers(mut self, enabled: bool) -> Self {
+        self.toon_options.typed_headers = enabled;
+        self
+    }
+
+    /// Table or one-object-per-line output for arrays of objects
+    pub fn array_layout(mut self, layout: ArrayLayout) -> Self {
+        self.toon_options.layout = layout;
+        self
+    }
+
+    /// Write repetitive string columns as codes under a `#dict` line
+    pub fn dictionary(mut self, enabled: bool) -> Self {
+        self.toon_options.dictionary = enabled;
+        self
+    }
+
+    /// How repeated keys in JSON objects and repeated TOON entity names are resolved
+    pub fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Refuse inputs that are nested too deeply, hold too many values, or have overlong lines
+    pub fn guards(mut self, guards: ParserGuards) -> Self {
+        self.guards = guards;
+        self
+    }
+
+    /// Convert nested objects into dotted-path columns (`user.address.city`)
+    pub fn flatten(mut self, enabled: bool) -> Self {
+        self.paths = match (enabled, self.paths) {
+            (true, _) => PathMode::Flatten,
+            (false, PathMode::Flatten) => PathMode::Preserve,
+            (false, other) => other,
+        };
+        self
+    }
+
+    /// Rebuild nested objects from dotted-path columns
+    pub fn unflatten(mut self, enabled: bool) -> Self {
+        self.paths = match (enabled, self.paths) {
+            (true, _) => PathMode::Unflatten,
+            (false, PathMode::Unflatten) => PathMode::Preserve,
+            (false, other) => other,
+        };
+        self
+    }
+
+    /// Scan the converted document for likely secrets and warn or refuse
+    pub fn secrets(mut self, policy: SecretPolicy) -> Self {
+        self.secrets = policy;
+        self
+    }
+
+    /// Give the named fields a declared type instead of the one sniffed from their text
+    pub fn type_rules(mut self, rules: TypeRules) -> Self {
+        self.type_rules = rules;
+        self
+    }
+
+    /// Read numbers and dates written with this locale's separators (`1.234,56`, `01.05.2024`)
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Register a hook that runs on the parsed input before any built-in processing
+    pub fn with_pre_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        self.pre_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Register a hook that runs last, immediately before serialization
+    pub fn with_post_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        self.post_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Run a compiled Rhai transform script as a pre-hook
+    #[cfg(feature = "scripting")]
+    pub fn with_transform_script(self, script: crate::scripting::ScriptTransform) -> Self {
+        self.with_pre_hook(move |value| script.apply(value))
+    }
+
+    /// Clean string values of the named fields (trim, collapse whitespace, fold case), as a pre-hook
+    pub fn sanitize(self, sanitizers: crate::sanitize::Sanitizers) -> Self {
+        self.with_pre_hook(move |value| Ok(sanitizers.apply(value)))
+    }
+
+    /// Replace PII fields with deterministic pseudonyms, as a pre-hook
+    #[cfg(feature = "pseudonymize")]
+    pub fn pseudonymize(self, pseudonymizer: crate::pseudonymize::Pseudonymizer) -> Self {
+        self.with_pre_hook(move |value| Ok(pseudonymizer.apply(value)))
+    }
+
+    pub fn build(self) -> Converter {
+        Converter {
+            registry: self.registry.map(Arc::new).unwrap_or_else(|| Arc::clone(shared_registry())),
+            pre_hooks: self.pre_hooks,
+            post_hooks: self.post_hooks,
+            toon_options: self.toon_options,
+            duplicate_keys: self.duplicate_keys,
+            guards: self.guards,
+            paths: self.paths,
+            secrets: self.secrets,
+            type_rules: self.type_rules,
+            locale: self.locale,
+        }
+    }
+}
+
+/// Built-in structural transform applied between pre- and post-hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum PathMode {
+    #[default]
+    Preserve,
+    Flatten,
+    Unflatten,
+}
+
+/// Configured conversion pipeline: parse → pre-hooks → flatten/unflatten → post-hooks → emit
+///
+/// Hooks run in registration order. Cloning is cheap; hooks and the
+/// registry are shared.
+#[derive(Clone)]
+pub struct Converter {
+    registry: Arc<FormatRegistry>,
+    pre_hooks: Vec<ValueHook>,
+    post_hooks: Vec<ValueHook>,
+    toon_options: SerializeOptions,
+    duplicate_keys: DuplicateKeyPolicy,
+    guards: ParserGuards,
+    paths: PathMode,
+    secrets: SecretPolicy,
+    type_rules: TypeRules,
+    locale: Option<Locale>,
+}
+
+// Buffers serde_json's many small writes into `chunk_size` pieces for `emit`
+struct ChunkWriter<'a> {
+    buffer: Vec<u8>,
+    chunk_size: usize,
+    emit: &'a mut dyn FnMut(&[u8]) -> Result<(), String>,
+}
+
+impl std::io::Write for ChunkWriter<'_> {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(bytes);
+        if self.buffer.len() >= self.chunk_size {
+            self.flush()?;
+        }
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            (self.emit)(&self.buffer).map_err(std::io::Error::other)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+/// Converted text plus any warnings raised while parsing or scanning
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionOutput {
+    pub output: String,
+    pub warnings: Vec<String>,
+}
+
+impl Converter {
+    pub fn builder() -> ConverterBuilder {
+        ConverterBuilder::new()
+    }
+
+    /// Formats this converter can read and write
+    pub fn registry(&self) -> &FormatRegistry {
+        &self.registry
+    }
+
+    /// Options used whenever this converter emits TOON
+    pub fn toon_options(&self) -> &SerializeOptions {
+        &self.toon_options
+    }
+
+    /// Whether any hooks, built-in transforms, type rules or a locale would change the parsed value
+    pub fn has_hooks(&self) -> bool {
+        !self.pre_hooks.is_empty()
+            || !self.post_hooks.is_empty()
+            || self.paths != PathMode::Preserve
+            || !self.type_rules.is_empty()
+            || self.locale.is_some()
+    }
+
+    /// Apply all hooks and built-in transforms to an already-parsed value
+    pub fn transform(&self, value: Value) -> Result<Value, String> {
+        let mut value = value;
+        for hook in &self.pre_hooks {
+            value = hook(value)?;
+        }
+        value = match self.paths {
+            PathMode::Preserve => value,
+            PathMode::Flatten => crate::flatten::flatten(value)?,
+            PathMode::Unflatten => crate::flatten::unflatten(value)?,
+        };
+        for hook in &self.post_hooks {
+            value = hook(value)?;
+        }
+        Ok(value)
+    }
+
+    /// Parse `input` as format `from`, run the hooks, and emit it as format `to`
+    pub fn convert(&self, input: &str, from: &str, to: &str) -> Result<String, String> {
+        self.convert_detailed(input, from, to).map(|result| result.output)
+    }
+
+    /// Like `convert`, but also returns warnings (e.g. resolved duplicate keys)
+    pub fn convert_detailed(&self, input: &str, from: &str, to: &str) -> Result<ConversionOutput, String> {
+        if self.registry.get(to).is_none() {
+            return Err(format!("Unsupported target format: {} (available: {})", to, self.registry.names().join(", ")));
+        }
+
+        let (value, warnings) = self.convert_to_value(input, from)?;
+        let output = self.emit(&value, to)?;
+        Ok(ConversionOutput { output, warnings })
+    }
+
+    /// Everything `convert_detailed` does short of emitting: parse, run the
+    /// hooks and apply the secret policy. For targets that aren't text formats
+    pub fn convert_to_value(&self, input: &str, from: &str) -> Result<(Value, Vec<String>), String> {
+        let (value, mut warnings) = self.parse(input, from)?;
+        let value = self.transform(value)?;
+        self.check_secrets(&value, &mut warnings)?;
+        Ok((value, warnings))
+    }
+
+    // Scans what will actually be emitted, so secrets removed by hooks do not count
+    fn check_secrets(&self, value: &Value, warnings: &mut Vec<String>) -> Result<(), String> {
+        if self.secrets == SecretPolicy::Ignore {
+            return Ok(());
+        }
+
+        let findings = scan_value(value);
+        if findings.is_empty() {
+            return Ok(());
+        }
+        if self.secrets == SecretPolicy::Block {
+            let findings: Vec<String> = findings.iter().map(ToString::to_string).collect();
+            return Err(format!("Refusing to convert likely secrets: {}", findings.join("; ")));
+        }
+        warnings.extend(findings.iter().map(ToString::to_string));
+        Ok(())
+    }
+
+    /// Parse `input` as format `from`, applying this converter's duplicate key policy, parser guards, locale and type rules
+    pub fn parse(&self, input: &str, from: &str) -> Result<(Value, Vec<String>), String> {
+        let source = self.registry.get(from)
+            .ok_or_else(|| format!("Unsupported source format: {} (available: {})", from, self.registry.names().join(", ")))?;
+        self.guards.check_text(input)?;
+        let (value, warnings) = if self.type_rules.is_empty() && self.locale.is_none() && !self.guards.limits_values() {
+            source.parse_with_policy(input, self.duplicate_keys)?
+        } else {
+            self.parse_typed(source, input)?
+        };
+        self.guards.check_value(&value)?;
+        let value = match &self.locale {
+            Some(locale) => locale.apply(value, &|entity, field| self.type_rules.rule(entity, field).is_some()),
+            None => value,
+        };
+        Ok((self.type_rules.apply(value)?, warnings))
+    }
+
+    // TOON and CSV cells are read with their declared types (and CSV cells
+    // with the locale) before sniffing can lose anything; JSON and TOON stop
+    // at the first value past the parser guards
+    fn parse_typed(&self, source: &dyn FormatCodec, input: &str) -> Result<(Value, Vec<String>), String> {
+        let options = ParseOptions { duplicate_keys: self.duplicate_keys, column_types: self.type_rules.column_types(), guards: self.guards };
+        match source.name() {
+            "json" => crate::json::parse_json_guarded(input, self.duplicate_keys, self.guards),
+            "toon" => parse_toon_with(input, &options),
+            #[cfg(feature = "csv")]
+            "csv" => parse_csv(input, &options, self.locale.as_ref()).map(|value| (value, Vec::new())),
+            _ => source.parse_with_policy(input, self.duplicate_keys),
+        }
+    }
+
+    /// Emit `value` as format `to`, applying this converter's TOON options
+    pub fn emit(&self, value: &Value, to: &str) -> Result<String, String> {
+        if to.eq_ignore_ascii_case("toon") {
+            ToonCodec::with_options(self.toon_options.clone()).emit(value)
+        } else {
+            self.registry.emit(value, to)
+        }
+    }
+
+    /// Emit `value` as format `to` through `emit`, about `chunk_size` bytes at a
+    /// time; TOON and JSON are handed off as they are written, other formats
+    /// in one piece
+    pub fn emit_chunked(
+        &self,
+        value: &Value,
+        to: &str,
+        chunk_size: usize,
+        emit: &mut dyn FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if to.eq_ignore_ascii_case("toon") {
+            serialize_toon_chunked(value, &self.toon_options, chunk_size, emit)
+        } else if to.eq_ignore_ascii_case("json") {
+            let mut writer = ChunkWriter { buffer: Vec::with_capacity(chunk_size), chunk_size, emit };
+            serde_json::to_writer_pretty(&mut writer, value).map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+            std::io::Write::flush(&mut writer).map_err(|e| e.to_string())
+        } else {
+            emit(self.emit(value, to)?.as_bytes())
+        }
+    }
+
+    pub fn json_to_toon(&self, json_str: &str) -> Result<String, String> {
+        self.convert(json_str, "json", "toon")
+    }
+
+    pub fn toon_to_json(&self, toon_str: &str) -> Result<String, String> {
+        self.convert(toon_str, "toon", "json")
+    }
+}
+
+impl Default for Converter {
+    fn default() -> Self {
+        ConverterBuilder::new().build()
+    }
+}
+
+/// Convert between any two built-in formats by name
+pub fn convert(input: &str, from: &str, to: &str) -> Result<String, String> {
+    registry().convert(input, from, to)
+}
 
 pub fn json_to_toon(json_str: &str) -> Result<String, String> {
-    let value: Value = serde_json::from_str(json_str)
-        .map_err(|e| format!("Invalid JSON: {}", e))?;
-    
-    serialize_toon(&value)
+    let value = JsonCodec.parse(json_str)?;
+
+    json_value_to_toon(&value)
 }
 
 pub fn toon_to_json(toon_str: &str) -> Result<String, String> {
-    let value = parse_toon(toon_str)?;
-    
-    serde_json::to_string_pretty(&value)
-        .map_err(|e| format!("Failed to serialize JSON: {}", e))
+    let value = toon_to_value(toon_str)?;
+
+    JsonCodec.emit(&value)
+}
+
+/// JSON recovered from damaged TOON, plus the lines that had to be skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct LossyConversion {
+    pub output: String,
+    pub errors: Vec<RecoverableError>,
+}
+
+/// Like `toon_to_json`, but skips malformed rows and lines instead of failing
+pub fn toon_to_json_lossy(toon_str: &str) -> Result<LossyConversion, String> {
+    let (value, errors) = parse_toon_lossy(toon_str);
+
+    Ok(LossyConversion { output: JsonCodec.emit(&value)?, errors })
+}
+
+/// Parse TOON straight into a `Value`, for callers that would otherwise re-parse `toon_to_json` output
+pub fn toon_to_value(toon_str: &str) -> Result<Value, String> {
+    ToonCodec::default().parse(toon_str)
+}
+
+/// Emit TOON for an already-parsed JSON `Value`
+pub fn json_value_to_toon(value: &Value) -> Result<String, String> {
+    ToonCodec::default().emit(value)
 }
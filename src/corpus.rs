@@ -0,0 +1,133 @@
+// Regression corpus of tricky real-world documents (`toonify corpus run`)
+//
+// Each case in tests/corpus/ is a JSON document (`name.json`) and the TOON
+// this crate writes for it (`name.toon`). A case passes when JSON → TOON
+// matches the .toon file exactly and the .toon file parses back to the same
+// JSON. The cases are compiled into the library, so packagers can run
+// `toonify corpus run` against their own platform builds without the source
+// tree; `--dir` checks a directory of cases on disk instead.
+//
+// Line endings in .toon files are normalized before comparing, so a CRLF
+// checkout still passes.
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::toon::{parse_toon, serialize_toon};
+
+/// One corpus document and its expected TOON
+#[derive(Debug, Clone)]
+pub struct CorpusCase {
+    pub name: String,
+    pub json: String,
+    pub toon: String,
+}
+
+/// Outcome of a corpus run: case names and what went wrong
+#[derive(Debug, Clone, Default)]
+pub struct CorpusReport {
+    pub cases: usize,
+    pub failures: Vec<(String, String)>,
+}
+
+impl CorpusReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+macro_rules! builtin_case {
+    ($name:literal) => {
+        (
+            $name,
+            include_str!(concat!("../tests/corpus/", $name, ".json")),
+            include_str!(concat!("../tests/corpus/", $name, ".toon")),
+        )
+    };
+}
+
+const BUILTIN: &[(&str, &str, &str)] = &[
+    builtin_case!("package_basic"),
+    builtin_case!("package_monorepo"),
+    builtin_case!("urls"),
+    builtin_case!("unicode"),
+    builtin_case!("mixed_arrays"),
+    builtin_case!("numbers"),
+];
+
+/// The cases shipped with this build
+pub fn builtin() -> Vec<CorpusCase> {
+    BUILTIN
+        .iter()
+        .map(|(name, json, toon)| CorpusCase { name: name.to_string(), json: json.to_string(), toon: toon.to_string() })
+        .collect()
+}
+
+/// Every `name.json` in `dir` with its `name.toon`, sorted by name
+pub fn load_dir(dir: &Path) -> Result<Vec<CorpusCase>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read corpus {:?}: {}", dir, e))?;
+    let mut cases = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let toon_path = path.with_extension("toon");
+        let read = |path: &Path| fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e));
+        cases.push(CorpusCase {
+            name: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+            json: read(&path)?,
+            toon: read(&toon_path)?,
+        });
+    }
+    if cases.is_empty() {
+        return Err(format!("No corpus cases (name.json + name.toon) in {:?}", dir));
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cases)
+}
+
+/// Check every case; a panic counts as a failure of that case
+pub fn run(cases: &[CorpusCase]) -> CorpusReport {
+    let mut report = CorpusReport::default();
+    for case in cases {
+        report.cases += 1;
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| check(case))).unwrap_or_else(|_| Err("panicked".to_string()));
+        if let Err(e) = outcome {
+            report.failures.push((case.name.clone(), e));
+        }
+    }
+    report
+}
+
+/// JSON → TOON must match the expected TOON, which must parse back to the JSON
+pub fn check(case: &CorpusCase) -> Result<(), String> {
+    let value: Value = serde_json::from_str(&case.json).map_err(|e| format!("invalid JSON: {}", e))?;
+    let expected = case.toon.replace("\r\n", "\n");
+    let expected = expected.trim_end();
+
+    let toon = serialize_toon(&value)?;
+    if toon != expected {
+        return Err(first_difference(expected, &toon));
+    }
+
+    let parsed = parse_toon(expected).map_err(|e| format!("expected TOON does not parse: {}", e))?;
+    if parsed != value {
+        return Err(format!("TOON parses to a different document: {}", parsed));
+    }
+    Ok(())
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut actual_lines = actual.lines();
+    for (index, expected_line) in expected.lines().enumerate() {
+        let actual_line = actual_lines.next();
+        if actual_line != Some(expected_line) {
+            return format!("TOON differs at line {}: expected {:?}, got {:?}", index + 1, expected_line, actual_line.unwrap_or("<end>"));
+        }
+    }
+    format!("TOON has extra lines from line {}: {:?}", expected.lines().count() + 1, actual_lines.next().unwrap_or_default())
+}
@@ -0,0 +1,240 @@
+// `toonify daemon`: a warm conversion process for editors
+//
+// Speaks newline-delimited JSON-RPC 2.0 (one request per line, one response
+// per line) over a Unix socket, or a named pipe on Windows:
+//
+//     {"jsonrpc":"2.0","id":1,"method":"convert","params":{"input":"{\"a\":1}","to":"toon"}}
+//     {"jsonrpc":"2.0","id":1,"result":{"output":"a:1","from":"json","to":"toon","warnings":[]}}
+//
+// Methods:
+//   convert  {input, from?, to?}      -> {output, from, to, warnings}
+//   validate {input, format?, schema?} -> {valid, error}
+//   stats    {}                        -> counters since start
+//
+// Formats are detected like on the CLI when omitted. Conversions run on the
+// blocking pool so one large document doesn't stall other connections.
+//
+// `toonify serve --transport stdio` speaks the same protocol on stdin/stdout
+// for a single client, so agent frameworks can spawn TOONify as a tool
+// subprocess; `--transport unix --socket PATH` is this daemon.
+
+use std::error::Error;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use toonify::converter::{self, Converter};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+struct DaemonState {
+    converter: Converter,
+    started: Instant,
+    connections: AtomicU64,
+    requests: AtomicU64,
+    conversions: AtomicU64,
+    errors: AtomicU64,
+}
+
+#[cfg(unix)]
+pub async fn run(socket: std::path::PathBuf, converter: Converter) -> Result<(), Box<dyn Error>> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+
+    // A socket file nobody answers on is left over from a crashed daemon
+    if socket.exists() {
+        if tokio::net::UnixStream::connect(&socket).await.is_ok() {
+            return Err(format!("A daemon is already listening on {:?}", socket).into());
+        }
+        std::fs::remove_file(&socket)?;
+    }
+    let listener = UnixListener::bind(&socket).map_err(|e| format!("Failed to bind {:?}: {}", socket, e))?;
+    // Conversions may see private documents; only the owner may connect
+    std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600))?;
+    eprintln!("[DAEMON] Listening on {:?}", socket);
+
+    let state = Arc::new(new_state(converter));
+    let result = loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve_connection(stream, Arc::clone(&state)));
+                }
+                Err(e) => break Err(e),
+            },
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+        }
+    };
+
+    let _ = std::fs::remove_file(&socket);
+    eprintln!("[DAEMON] Stopped");
+    Ok(result?)
+}
+
+#[cfg(windows)]
+pub async fn run(socket: std::path::PathBuf, converter: Converter) -> Result<(), Box<dyn Error>> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    // Named pipes live in their own namespace; accept a bare name too
+    let name = socket.to_string_lossy().into_owned();
+    let name = if name.starts_with(r"\\.\pipe\") { name } else { format!(r"\\.\pipe\{}", name) };
+    let mut server = ServerOptions::new().first_pipe_instance(true).create(&name)
+        .map_err(|e| format!("Failed to create pipe {}: {}", name, e))?;
+    eprintln!("[DAEMON] Listening on {}", name);
+
+    let state = Arc::new(new_state(converter));
+    loop {
+        tokio::select! {
+            connected = server.connect() => {
+                connected?;
+                // Open the next instance before handing this one off
+                let client = std::mem::replace(&mut server, ServerOptions::new().create(&name)?);
+                tokio::spawn(serve_connection(client, Arc::clone(&state)));
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    eprintln!("[DAEMON] Stopped");
+    Ok(())
+}
+
+fn new_state(converter: Converter) -> DaemonState {
+    DaemonState {
+        converter,
+        started: Instant::now(),
+        connections: AtomicU64::new(0),
+        requests: AtomicU64::new(0),
+        conversions: AtomicU64::new(0),
+        errors: AtomicU64::new(0),
+    }
+}
+
+/// The same protocol on stdin/stdout for one client, until stdin closes (`serve --transport stdio`)
+pub async fn run_stdio(converter: Converter) -> Result<(), Box<dyn Error>> {
+    eprintln!("[DAEMON] JSON-RPC on stdin/stdout");
+    let state = Arc::new(new_state(converter));
+    state.connections.fetch_add(1, Ordering::Relaxed);
+    serve_lines(tokio::io::stdin(), tokio::io::stdout(), state).await;
+    Ok(())
+}
+
+async fn serve_connection<S: AsyncRead + AsyncWrite + Unpin>(stream: S, state: Arc<DaemonState>) {
+    state.connections.fetch_add(1, Ordering::Relaxed);
+    let (reader, writer) = tokio::io::split(stream);
+    serve_lines(reader, writer, state).await;
+}
+
+async fn serve_lines<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(reader: R, mut writer: W, state: Arc<DaemonState>) {
+    let mut lines = BufReader::new(reader).lines();
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("[DAEMON] Connection error: {}", e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let state_for_request = Arc::clone(&state);
+        let response = tokio::task::spawn_blocking(move || handle_line(&state_for_request, &line))
+            .await
+            .unwrap_or_else(|e| error_response(Value::Null, INVALID_REQUEST, format!("Request failed: {}", e)));
+        // Notifications (no id) get no reply
+        let Some(response) = response else { continue };
+
+        let mut bytes = response.to_string().into_bytes();
+        bytes.push(b'\n');
+        // Stdout is buffered by tokio; flush so each reply reaches the client right away
+        if writer.write_all(&bytes).await.is_err() || writer.flush().await.is_err() {
+            break;
+        }
+    }
+}
+
+fn handle_line(state: &DaemonState, line: &str) -> Option<Value> {
+    state.requests.fetch_add(1, Ordering::Relaxed);
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            state.errors.fetch_add(1, Ordering::Relaxed);
+            return error_response(Value::Null, PARSE_ERROR, format!("Invalid JSON: {}", e));
+        }
+    };
+    let id = request.get("id").cloned();
+    let Some(method) = request["method"].as_str() else {
+        state.errors.fetch_add(1, Ordering::Relaxed);
+        return error_response(id.unwrap_or(Value::Null), INVALID_REQUEST, "Missing method".to_string());
+    };
+
+    let params = &request["params"];
+    let result = match method {
+        "convert" => convert(state, params),
+        "validate" => validate(state, params),
+        "stats" => Ok(stats(state)),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+    };
+
+    let id = id?;
+    match result {
+        Ok(result) => Some(json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+        Err((code, message)) => {
+            state.errors.fetch_add(1, Ordering::Relaxed);
+            error_response(id, code, message)
+        }
+    }
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Option<Value> {
+    Some(json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }))
+}
+
+fn input_and_format(params: &Value, key: &str) -> Result<(String, String), (i64, String)> {
+    let input = params["input"].as_str().ok_or((INVALID_PARAMS, "params.input must be a string".to_string()))?;
+    let format = match params[key].as_str() {
+        Some(format) => format.to_string(),
+        None => converter::detect_format(None, input.as_bytes()).map_err(|e| (INVALID_PARAMS, e))?.to_string(),
+    };
+    Ok((input.to_string(), format))
+}
+
+fn convert(state: &DaemonState, params: &Value) -> Result<Value, (i64, String)> {
+    let (input, from) = input_and_format(params, "from")?;
+    let to = params["to"].as_str().unwrap_or_else(|| crate::default_target_format(&from)).to_string();
+    let converted = state.converter.convert_detailed(&input, &from, &to).map_err(|e| (INVALID_PARAMS, e))?;
+    state.conversions.fetch_add(1, Ordering::Relaxed);
+    Ok(json!({ "output": converted.output, "from": from, "to": to, "warnings": converted.warnings }))
+}
+
+fn validate(state: &DaemonState, params: &Value) -> Result<Value, (i64, String)> {
+    let (input, format) = input_and_format(params, "format")?;
+    let checked = state.converter.parse(&input, &format).and_then(|(value, _)| match params.get("schema") {
+        Some(schema) if !schema.is_null() => crate::validate_value(&value, schema).map_err(|e| e.to_string()),
+        _ => Ok(()),
+    });
+    Ok(match checked {
+        Ok(()) => json!({ "valid": true, "error": null }),
+        Err(e) => json!({ "valid": false, "error": e }),
+    })
+}
+
+fn stats(state: &DaemonState) -> Value {
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_secs": state.started.elapsed().as_secs(),
+        "connections": state.connections.load(Ordering::Relaxed),
+        "requests": state.requests.load(Ordering::Relaxed),
+        "conversions": state.conversions.load(Ordering::Relaxed),
+        "errors": state.errors.load(Ordering::Relaxed),
+    })
+}
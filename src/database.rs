@@ -0,0 +1,151 @@
+// `toonify db export`: SQL query results straight into TOON tables
+//
+// Rows are pulled from the driver one at a time and written through
+// `ToonWriter`, so results are never collected as JSON. Postgres, MySQL and
+// SQLite DSNs go through sqlx's `Any` driver, which decodes integers,
+// floats, booleans, text and blobs; cast other column types (timestamps,
+// numerics, JSON) to text in the query.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use futures_util::TryStreamExt;
+use serde_json::{Number, Value};
+use sqlx::any::{AnyPoolOptions, AnyRow};
+use sqlx::{AnyPool, Column, Row};
+
+use toonify::toon::ToonWriter;
+
+pub struct ExportOptions {
+    pub dsn: String,
+    /// (entity name, SQL) pairs, exported in order
+    pub queries: Vec<(String, String)>,
+    pub output: Option<PathBuf>,
+    pub gzip: bool,
+}
+
+pub async fn connect(dsn: &str) -> Result<AnyPool, Box<dyn Error>> {
+    sqlx::any::install_default_drivers();
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect(dsn)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", redact_dsn(dsn), e))?;
+    Ok(pool)
+}
+
+pub async fn run_export(options: ExportOptions) -> Result<(), Box<dyn Error>> {
+    eprintln!("[DB] Connecting to {}", redact_dsn(&options.dsn));
+    let pool = connect(&options.dsn).await?;
+
+    let out: Box<dyn Write> = match &options.output {
+        Some(path) => {
+            eprintln!("[DB] Writing to file: {:?}", path);
+            Box::new(BufWriter::new(File::create(path)?))
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+
+    if options.gzip {
+        let writer = ToonWriter::new(GzEncoder::new(out, Compression::default()));
+        export_queries(&pool, &options.queries, writer).await?.finish()?.flush()?;
+    } else {
+        export_queries(&pool, &options.queries, ToonWriter::new(out)).await?.flush()?;
+    }
+
+    pool.close().await;
+    eprintln!("[DB] ✓ Export complete");
+    Ok(())
+}
+
+async fn export_queries<W: Write>(
+    pool: &AnyPool,
+    queries: &[(String, String)],
+    mut writer: ToonWriter<W>,
+) -> Result<W, Box<dyn Error>> {
+    for (name, sql) in queries {
+        eprintln!("[DB] Running query for {:?}", name);
+        writer = export_table(pool, name, sql, writer).await?;
+    }
+    Ok(writer.finish()?)
+}
+
+/// Stream one query into `writer` as a table named `name`
+pub async fn export_table<W: Write>(
+    pool: &AnyPool,
+    name: &str,
+    sql: &str,
+    mut writer: ToonWriter<W>,
+) -> Result<ToonWriter<W>, Box<dyn Error>> {
+    let mut rows = sqlx::query(sql).fetch(pool);
+    let mut count = 0usize;
+    let mut cells = Vec::new();
+
+    while let Some(row) = rows.try_next().await.map_err(|e| format!("Query for {:?} failed: {}", name, e))? {
+        if count == 0 {
+            let columns: Vec<&str> = row.columns().iter().map(|column| column.name()).collect();
+            writer.begin_table(name, &columns)?;
+        }
+        cells.clear();
+        for index in 0..row.columns().len() {
+            cells.push(cell_value(&row, index)?);
+        }
+        writer.write_row(&cells)?;
+        count += 1;
+    }
+
+    // Column names aren't known without a row; still emit the entity
+    if count == 0 {
+        writer.begin_table(name, &[])?;
+    }
+    eprintln!("[DB] {}: {} rows", name, count);
+    Ok(writer)
+}
+
+// The Any driver only decodes a handful of types; try them from most to least specific
+fn cell_value(row: &AnyRow, index: usize) -> Result<Value, Box<dyn Error>> {
+    if let Ok(value) = row.try_get::<Option<i64>, _>(index) {
+        return Ok(value.map_or(Value::Null, Value::from));
+    }
+    if let Ok(value) = row.try_get::<Option<f64>, _>(index) {
+        return Ok(value.and_then(Number::from_f64).map_or(Value::Null, Value::Number));
+    }
+    if let Ok(value) = row.try_get::<Option<bool>, _>(index) {
+        return Ok(value.map_or(Value::Null, Value::Bool));
+    }
+    if let Ok(value) = row.try_get::<Option<String>, _>(index) {
+        return Ok(value.map_or(Value::Null, Value::String));
+    }
+    if let Ok(value) = row.try_get::<Option<Vec<u8>>, _>(index) {
+        return Ok(value.map_or(Value::Null, |bytes| Value::String(hex(&bytes))));
+    }
+    let column = &row.columns()[index];
+    Err(format!(
+        "Column {:?} has type {} which the exporter can't decode; cast it to text in the query",
+        column.name(),
+        sqlx::TypeInfo::name(column.type_info())
+    )
+    .into())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Keep passwords out of logs and error messages
+fn redact_dsn(dsn: &str) -> String {
+    match (dsn.find("://"), dsn.rfind('@')) {
+        (Some(scheme_end), Some(at)) if at > scheme_end => {
+            let credentials = &dsn[scheme_end + 3..at];
+            match credentials.find(':') {
+                Some(colon) => format!("{}{}:***{}", &dsn[..scheme_end + 3], &credentials[..colon], &dsn[at..]),
+                None => dsn.to_string(),
+            }
+        }
+        _ => dsn.to_string(),
+    }
+}
@@ -0,0 +1,164 @@
+// `toonify sql`: run SQL over a document's entities with an in-memory DuckDB
+//
+//     toonify sql "SELECT name, count(*) AS n FROM users GROUP BY 1" data.toon
+//
+// Every table-shaped entity becomes a table of the same name (a root array is
+// `data`); column types are inferred from the values as in `sqlite import`,
+// and nested arrays/objects are stored as DuckDB JSON. The query must be a
+// SELECT (or WITH ...) since the rows are read back through `to_json`, which
+// keeps column order and renders dates, decimals and lists the way DuckDB
+// does. The result is a single entity, `result` unless `--name` says otherwise.
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use duckdb::types::Value as SqlValue;
+use duckdb::{params_from_iter, Connection};
+use serde_json::{Map, Value};
+
+use toonify::converter::{self, Converter};
+use toonify::toon::{columns_of, ColumnType};
+
+pub struct SqlOptions {
+    pub query: String,
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub to: String,
+    pub name: String,
+}
+
+pub fn run_sql(options: SqlOptions, converter: &Converter) -> Result<(), Box<dyn Error>> {
+    let content = match options.input.as_ref().filter(|path| path.as_os_str() != "-") {
+        Some(path) => {
+            eprintln!("[SQL] Reading from file: {:?}", path);
+            fs::read_to_string(path)?
+        }
+        None => {
+            eprintln!("[SQL] Reading from STDIN");
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        }
+    };
+    let source_format = converter::detect_format(options.input.as_deref(), content.as_bytes())?;
+    let (value, warnings) = converter.convert_to_value(&content, source_format)?;
+    for warning in &warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+
+    let connection = Connection::open_in_memory().map_err(|e| format!("Failed to start DuckDB: {}", e))?;
+    let tables = register_tables(&connection, &value)?;
+    if tables.is_empty() {
+        return Err("No table-shaped entities to query".into());
+    }
+
+    let rows = query_rows(&connection, &options.query)?;
+    eprintln!("[SQL] {} rows from {} tables ({})", rows.len(), tables.len(), tables.join(", "));
+
+    let mut doc = Map::new();
+    doc.insert(options.name, Value::Array(rows));
+    let content = converter.emit(&Value::Object(doc), &options.to)?;
+    match options.output {
+        Some(path) => {
+            eprintln!("[SQL] Writing to file: {:?}", path);
+            fs::write(path, content)?;
+        }
+        None => {
+            io::stdout().write_all(content.as_bytes())?;
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn register_tables(connection: &Connection, value: &Value) -> Result<Vec<String>, String> {
+    let entities: Vec<(&str, &Value)> = match value {
+        Value::Object(root) => root.iter().map(|(name, entity)| (name.as_str(), entity)).collect(),
+        other => vec![("data", other)],
+    };
+
+    let mut registered = Vec::new();
+    for (name, entity) in entities {
+        let rows: Vec<&Map<String, Value>> = match entity {
+            Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => {
+                items.iter().filter_map(Value::as_object).collect()
+            }
+            Value::Object(row) => vec![row],
+            _ => continue,
+        };
+
+        let columns = columns_of(rows.iter().copied());
+        if columns.is_empty() {
+            continue;
+        }
+
+        let types: Vec<Option<ColumnType>> = columns
+            .iter()
+            .map(|column| ColumnType::infer(rows.iter().filter_map(|row| row.get(*column))))
+            .collect();
+        let definitions: Vec<String> = columns
+            .iter()
+            .zip(&types)
+            .map(|(column, ty)| format!("{} {}", quote_identifier(column), sql_type(*ty)))
+            .collect();
+        let table = quote_identifier(name);
+        connection
+            .execute_batch(&format!("CREATE TABLE {} ({})", table, definitions.join(", ")))
+            .map_err(|e| format!("Failed to create table {:?}: {}", name, e))?;
+
+        let insert = format!("INSERT INTO {} VALUES ({})", table, vec!["?"; columns.len()].join(", "));
+        let mut statement = connection.prepare(&insert).map_err(|e| e.to_string())?;
+        for row in &rows {
+            let cells = columns.iter().zip(&types).map(|(column, ty)| sql_value(row.get(*column), *ty));
+            statement
+                .execute(params_from_iter(cells))
+                .map_err(|e| format!("Failed to load {:?}: {}", name, e))?;
+        }
+        registered.push(name.to_string());
+    }
+    Ok(registered)
+}
+
+fn query_rows(connection: &Connection, query: &str) -> Result<Vec<Value>, String> {
+    let query = query.trim().trim_end_matches(';');
+    let wrapped = format!("SELECT CAST(to_json(toonify_row) AS VARCHAR) FROM ({}) AS toonify_row", query);
+    let mut statement = connection.prepare(&wrapped).map_err(|e| format!("Query failed: {}", e))?;
+    let rows = statement
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Query failed: {}", e))?;
+
+    let mut values = Vec::new();
+    for row in rows {
+        let json = row.map_err(|e| format!("Query failed: {}", e))?;
+        values.push(serde_json::from_str(&json).map_err(|e| format!("Unexpected row from DuckDB: {}", e))?);
+    }
+    Ok(values)
+}
+
+fn sql_type(ty: Option<ColumnType>) -> &'static str {
+    match ty {
+        Some(ColumnType::Int) => "BIGINT",
+        Some(ColumnType::Float | ColumnType::Num) => "DOUBLE",
+        Some(ColumnType::Bool) => "BOOLEAN",
+        Some(ColumnType::Json) => "JSON",
+        Some(ColumnType::Str) | None => "VARCHAR",
+    }
+}
+
+// Cells are bound as the column's type; text columns take anything as text
+fn sql_value(cell: Option<&Value>, ty: Option<ColumnType>) -> SqlValue {
+    match (cell.unwrap_or(&Value::Null), ty) {
+        (Value::Null, _) => SqlValue::Null,
+        (Value::Bool(b), Some(ColumnType::Bool)) => SqlValue::Boolean(*b),
+        (Value::Number(n), Some(ColumnType::Int)) if n.is_i64() => SqlValue::BigInt(n.as_i64().unwrap_or_default()),
+        (Value::Number(n), Some(ColumnType::Float | ColumnType::Num)) => SqlValue::Double(n.as_f64().unwrap_or_default()),
+        (Value::String(s), _) => SqlValue::Text(s.clone()),
+        (other, _) => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,317 @@
+// Table exports for tools that aren't TOON-aware: HTML, Excel and Arrow
+//
+// These aren't `FormatCodec`s because nothing parses them back and only
+// HTML is text. Each root entity becomes one table (HTML) or worksheet
+// (xlsx); an Arrow stream holds a single chosen entity. Rows of objects get
+// a header over the union of their keys, a single object is a one-row
+// table, a list of primitives is a single `value` column, and root scalars
+// are collected into a name/value table.
+
+use serde_json::{Map, Value};
+
+use crate::converter::toon_to_value;
+use crate::toon::columns_of;
+#[cfg(feature = "arrow")]
+use crate::toon::ColumnType;
+
+/// One exported table
+struct Table {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+fn tables(value: &Value) -> Vec<Table> {
+    let Value::Object(root) = value else {
+        return vec![entity_table("data", value)];
+    };
+
+    let mut tables = Vec::new();
+    let mut scalars = Vec::new();
+    for (name, entity) in root {
+        if entity.is_array() || entity.is_object() {
+            tables.push(entity_table(name, entity));
+        } else {
+            scalars.push(vec![Value::String(name.clone()), entity.clone()]);
+        }
+    }
+    if !scalars.is_empty() {
+        tables.push(Table { name: "values".to_string(), columns: vec!["name".to_string(), "value".to_string()], rows: scalars });
+    }
+    tables
+}
+
+fn entity_table(name: &str, entity: &Value) -> Table {
+    let objects: Option<Vec<&Map<String, Value>>> = match entity {
+        Value::Array(items) => items.iter().map(Value::as_object).collect(),
+        Value::Object(row) => Some(vec![row]),
+        _ => None,
+    };
+
+    match objects {
+        Some(objects) if !objects.is_empty() => {
+            let columns: Vec<String> = columns_of(objects.iter().copied()).into_iter().map(str::to_string).collect();
+            let rows = objects
+                .iter()
+                .map(|row| columns.iter().map(|column| row.get(column).cloned().unwrap_or(Value::Null)).collect())
+                .collect();
+            Table { name: name.to_string(), columns, rows }
+        }
+        _ => {
+            let rows = match entity {
+                Value::Array(items) => items.iter().map(|item| vec![item.clone()]).collect(),
+                other => vec![vec![other.clone()]],
+            };
+            Table { name: name.to_string(), columns: vec!["value".to_string()], rows }
+        }
+    }
+}
+
+// Text shown for a cell: strings unquoted, nested values as compact JSON
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Escape text for HTML element content and attribute values
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// A `<table>` for one entity, with at most `max_rows` rows
+pub fn html_table(entity: &Value, max_rows: usize) -> String {
+    render_table(&entity_table("", entity), max_rows)
+}
+
+fn render_table(table: &Table, max_rows: usize) -> String {
+    let mut html = String::from("<table>\n<tr>");
+    for column in &table.columns {
+        html.push_str(&format!("<th>{}</th>", escape_html(column)));
+    }
+    html.push_str("</tr>\n");
+    for row in table.rows.iter().take(max_rows) {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", escape_html(&cell_text(cell))));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// A standalone HTML page with one headed table per entity
+pub fn value_to_html(value: &Value) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<style>\n");
+    html.push_str("body { font-family: system-ui, sans-serif; margin: 2rem; }\n");
+    html.push_str("table { border-collapse: collapse; margin-bottom: 1.5rem; }\n");
+    html.push_str("th, td { border: 1px solid #ddd; padding: 0.25rem 0.5rem; text-align: left; }\n");
+    html.push_str("th { background: #f5f5f5; }\n</style>\n</head>\n<body>\n");
+    for table in tables(value) {
+        html.push_str(&format!("<h2>{}</h2>\n", escape_html(&table.name)));
+        html.push_str(&render_table(&table, usize::MAX));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Render a TOON document as an HTML page
+pub fn toon_to_html(toon: &str) -> Result<String, String> {
+    Ok(value_to_html(&toon_to_value(toon)?))
+}
+
+/// Build an xlsx workbook with one worksheet per entity (feature `xlsx`)
+#[cfg(feature = "xlsx")]
+pub fn value_to_xlsx(value: &Value) -> Result<Vec<u8>, String> {
+    use rust_xlsxwriter::{Format, Workbook};
+
+    let error = |e: rust_xlsxwriter::XlsxError| format!("Failed to write xlsx: {}", e);
+    let bold = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+    let mut used: Vec<String> = Vec::new();
+
+    for table in tables(value) {
+        let sheet = workbook.add_worksheet();
+        let name = sheet_name(&table.name, &used);
+        sheet.set_name(&name).map_err(error)?;
+        used.push(name);
+
+        for (col, column) in table.columns.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, column, &bold).map_err(error)?;
+        }
+        for (index, row) in table.rows.iter().enumerate() {
+            let row_number = u32::try_from(index + 1).map_err(|_| format!("{}: too many rows for a worksheet", table.name))?;
+            for (col, cell) in row.iter().enumerate() {
+                let col = col as u16;
+                match cell {
+                    Value::Null => {}
+                    Value::Bool(b) => {
+                        sheet.write_boolean(row_number, col, *b).map_err(error)?;
+                    }
+                    Value::Number(n) => {
+                        sheet.write_number(row_number, col, n.as_f64().unwrap_or_default()).map_err(error)?;
+                    }
+                    other => {
+                        sheet.write_string(row_number, col, cell_text(other)).map_err(error)?;
+                    }
+                }
+            }
+        }
+        sheet.set_freeze_panes(1, 0).map_err(error)?;
+        sheet.autofit();
+    }
+
+    // Excel refuses workbooks without sheets
+    if used.is_empty() {
+        workbook.add_worksheet();
+    }
+    workbook.save_to_buffer().map_err(error)
+}
+
+/// Convert a TOON document to an xlsx workbook (feature `xlsx`)
+#[cfg(feature = "xlsx")]
+pub fn toon_to_xlsx(toon: &str) -> Result<Vec<u8>, String> {
+    value_to_xlsx(&toon_to_value(toon)?)
+}
+
+// Worksheet names: at most 31 characters, none of []:*?/\, unique ignoring case
+#[cfg(feature = "xlsx")]
+fn sheet_name(name: &str, used: &[String]) -> String {
+    let base: String = name.chars().filter(|c| !"[]:*?/\\".contains(*c)).collect();
+    let base = base.trim_matches('\'');
+    let base = if base.is_empty() { "Sheet" } else { base };
+
+    let taken = |candidate: &str| used.iter().any(|existing| existing.eq_ignore_ascii_case(candidate));
+    let truncate = |text: &str, max: usize| text.chars().take(max).collect::<String>();
+    let mut candidate = truncate(base, 31);
+    let mut suffix = 2;
+    while taken(&candidate) {
+        let tail = format!(" ({})", suffix);
+        candidate = format!("{}{}", truncate(base, 31 - tail.len()), tail);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Write one table entity as an Arrow IPC stream (feature `arrow`)
+///
+/// `entity` may be omitted when the document has a single table. Column
+/// types are inferred from the values; nested values become JSON strings.
+#[cfg(feature = "arrow")]
+pub fn value_to_arrow(value: &Value, entity: Option<&str>) -> Result<Vec<u8>, String> {
+    let table = arrow_table(value, entity)?;
+    let types = vec![None; table.columns.len()];
+    arrow_stream(&table, &types)
+}
+
+/// Like `value_to_arrow`, but columns declared in a typed header
+/// (`users[2]{id:int,name:str}:`) keep their declared type
+#[cfg(feature = "arrow")]
+pub fn toon_to_arrow(toon: &str, entity: Option<&str>) -> Result<Vec<u8>, String> {
+    let table = arrow_table(&toon_to_value(toon)?, entity)?;
+    let declared = crate::toon::outline(toon).into_iter().rfind(|candidate| candidate.name == table.name);
+    let types: Vec<Option<ColumnType>> = table
+        .columns
+        .iter()
+        .map(|column| {
+            let declared = declared.as_ref()?;
+            let index = declared.columns.iter().position(|name| name == column)?;
+            declared.column_types[index]
+        })
+        .collect();
+    arrow_stream(&table, &types)
+}
+
+#[cfg(feature = "arrow")]
+fn arrow_table(value: &Value, entity: Option<&str>) -> Result<Table, String> {
+    let Value::Object(root) = value else {
+        return Ok(entity_table("data", value));
+    };
+    let candidates: Vec<&String> = root.iter().filter(|(_, entity)| entity.is_array()).map(|(name, _)| name).collect();
+    let name = match entity {
+        Some(name) => root.keys().find(|key| *key == name).ok_or_else(|| format!("No entity {:?} to export", name))?,
+        None if candidates.len() == 1 => candidates[0],
+        None => {
+            let names: Vec<&str> = candidates.iter().map(|name| name.as_str()).collect();
+            return Err(format!("Arrow streams hold one table; choose one with --entity ({})", names.join(", ")));
+        }
+    };
+    Ok(entity_table(name, &root[name]))
+}
+
+#[cfg(feature = "arrow")]
+fn arrow_stream(table: &Table, declared: &[Option<ColumnType>]) -> Result<Vec<u8>, String> {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, BooleanBuilder, Float64Builder, Int64Builder, StringBuilder};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::ipc::writer::StreamWriter;
+    use arrow::record_batch::RecordBatch;
+
+    let mut fields = Vec::with_capacity(table.columns.len());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(table.columns.len());
+
+    for (index, column) in table.columns.iter().enumerate() {
+        let cells = || table.rows.iter().map(move |row| &row[index]);
+        let ty = declared[index].or_else(|| ColumnType::infer(cells()));
+        let mismatch = |row: usize, cell: &Value| {
+            format!("{}.{}: row {} holds {} in a {} column", table.name, column, row + 1, cell, ty.map_or("str", |ty| ty.as_str()))
+        };
+
+        let (data_type, array): (DataType, ArrayRef) = match ty {
+            Some(ColumnType::Int) => {
+                let mut builder = Int64Builder::with_capacity(table.rows.len());
+                for (row, cell) in cells().enumerate() {
+                    match cell {
+                        Value::Null => builder.append_null(),
+                        _ => builder.append_value(cell.as_i64().ok_or_else(|| mismatch(row, cell))?),
+                    }
+                }
+                (DataType::Int64, Arc::new(builder.finish()))
+            }
+            Some(ColumnType::Float | ColumnType::Num) => {
+                let mut builder = Float64Builder::with_capacity(table.rows.len());
+                for (row, cell) in cells().enumerate() {
+                    match cell {
+                        Value::Null => builder.append_null(),
+                        _ => builder.append_value(cell.as_f64().ok_or_else(|| mismatch(row, cell))?),
+                    }
+                }
+                (DataType::Float64, Arc::new(builder.finish()))
+            }
+            Some(ColumnType::Bool) => {
+                let mut builder = BooleanBuilder::with_capacity(table.rows.len());
+                for (row, cell) in cells().enumerate() {
+                    match cell {
+                        Value::Null => builder.append_null(),
+                        _ => builder.append_value(cell.as_bool().ok_or_else(|| mismatch(row, cell))?),
+                    }
+                }
+                (DataType::Boolean, Arc::new(builder.finish()))
+            }
+            // Strings, nested JSON, and columns mixing kinds
+            _ => {
+                let mut builder = StringBuilder::new();
+                for cell in cells() {
+                    match cell {
+                        Value::Null => builder.append_null(),
+                        _ => builder.append_value(cell_text(cell)),
+                    }
+                }
+                (DataType::Utf8, Arc::new(builder.finish()))
+            }
+        };
+        fields.push(Field::new(column, data_type, true));
+        arrays.push(array);
+    }
+
+    let error = |e: arrow::error::ArrowError| format!("Failed to write Arrow stream: {}", e);
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(Arc::clone(&schema), arrays).map_err(error)?;
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema).map_err(error)?;
+    writer.write(&batch).map_err(error)?;
+    writer.into_inner().map_err(error)
+}
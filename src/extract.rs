@@ -0,0 +1,157 @@
+// TOON request bodies and responses for axum services (feature `server`)
+//
+//     async fn create(format: ResponseFormat, Toon(order): Toon<Order>) -> Response {
+//         format.respond(store(order))
+//     }
+//
+// `Toon<T>` as an extractor requires `Content-Type: application/toon` (or
+// `text/toon`), parses the body as TOON and deserializes it into `T`. As a
+// response it always writes TOON. `ResponseFormat` reads `Accept`, so one
+// handler can answer TOON clients with TOON and everyone else with JSON.
+//
+// Rejections mirror axum's `Json`: 415 for the wrong content type, 400 for a
+// body that is not valid TOON, 422 for TOON that does not fit `T`.
+
+use std::convert::Infallible;
+use std::ops::{Deref, DerefMut};
+
+use axum::body::Bytes;
+use axum::extract::rejection::BytesRejection;
+use axum::extract::{FromRequest, FromRequestParts, Request};
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::toon::{parse_toon, serialize_toon};
+
+pub const TOON_MEDIA_TYPE: &str = "application/toon";
+
+/// A TOON request body, or a TOON response
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Toon<T>(pub T);
+
+#[derive(Debug)]
+pub enum ToonRejection {
+    MissingToonContentType,
+    Body(BytesRejection),
+    InvalidUtf8,
+    /// The body is not valid TOON
+    Syntax(String),
+    /// Valid TOON that does not deserialize into the target type
+    Data(String),
+}
+
+impl IntoResponse for ToonRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::MissingToonContentType => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, format!("Expected request with `Content-Type: {}`", TOON_MEDIA_TYPE)).into_response()
+            }
+            Self::Body(rejection) => rejection.into_response(),
+            Self::InvalidUtf8 => (StatusCode::BAD_REQUEST, "Request body is not valid UTF-8".to_string()).into_response(),
+            Self::Syntax(e) => (StatusCode::BAD_REQUEST, format!("Failed to parse the request body as TOON: {}", e)).into_response(),
+            Self::Data(e) => (StatusCode::UNPROCESSABLE_ENTITY, format!("Failed to deserialize the TOON body: {}", e)).into_response(),
+        }
+    }
+}
+
+impl<T, S> FromRequest<S> for Toon<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ToonRejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !request.headers().get(CONTENT_TYPE).is_some_and(is_toon) {
+            return Err(ToonRejection::MissingToonContentType);
+        }
+        let bytes = Bytes::from_request(request, state).await.map_err(ToonRejection::Body)?;
+        let text = std::str::from_utf8(&bytes).map_err(|_| ToonRejection::InvalidUtf8)?;
+        let value = parse_toon(text).map_err(ToonRejection::Syntax)?;
+        serde_json::from_value(value).map(Toon).map_err(|e| ToonRejection::Data(e.to_string()))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Toon<T> {
+    fn into_response(self) -> Response {
+        let toon = serde_json::to_value(&self.0).map_err(|e| e.to_string()).and_then(|value| serialize_toon(&value));
+        match toon {
+            Ok(toon) => ([(CONTENT_TYPE, HeaderValue::from_static("application/toon; charset=utf-8"))], toon).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to serialize the response as TOON: {}", e)).into_response(),
+        }
+    }
+}
+
+impl<T> Deref for Toon<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Toon<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Toon<T> {
+    fn from(value: T) -> Self {
+        Toon(value)
+    }
+}
+
+/// The response format the client asked for: TOON when `Accept` names it, JSON otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Toon,
+    Json,
+}
+
+impl ResponseFormat {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let toon = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|range| is_toon_media_type(range) && !refused(range));
+        if toon { Self::Toon } else { Self::Json }
+    }
+
+    pub fn respond<T: Serialize>(self, value: T) -> Response {
+        match self {
+            Self::Toon => Toon(value).into_response(),
+            Self::Json => Json(value).into_response(),
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+fn is_toon(content_type: &HeaderValue) -> bool {
+    content_type.to_str().is_ok_and(is_toon_media_type)
+}
+
+// `q=0` in an Accept range means "not this type"
+fn refused(range: &str) -> bool {
+    range.split(';').skip(1).any(|param| param.trim().strip_prefix("q=").and_then(|q| q.trim().parse::<f32>().ok()) == Some(0.0))
+}
+
+// application/toon, text/toon or a +toon suffix, ignoring parameters
+fn is_toon_media_type(value: &str) -> bool {
+    let essence = value.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    essence == TOON_MEDIA_TYPE || essence == "text/toon" || essence.ends_with("+toon")
+}
@@ -0,0 +1,147 @@
+// Input discovery for batch and watch
+//
+// `glob` follows symlinked directories blindly, so a link back up the tree
+// recursed until the path got too long and a link to a sibling data
+// directory converted the same files twice. This walker follows symlinks
+// only when asked, remembers every directory it entered by canonical path
+// (so a cycle is entered once) and every file it yielded (so a file reachable
+// through two links is converted once, under the first path found).
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+pub struct FileWalk {
+    pattern: Pattern,
+    /// How many directories below the root can hold a match; None for any depth
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    visited_dirs: HashSet<PathBuf>,
+    seen_files: HashSet<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+impl FileWalk {
+    /// `pattern` is matched against paths relative to the walk root
+    pub fn new(pattern: Option<&str>, recursive: bool, follow_symlinks: bool) -> Result<Self, String> {
+        // Without recursion `sub/*.json` still reaches into `sub`, as glob did
+        let max_depth = match pattern {
+            _ if recursive => None,
+            Some(pat) if pat.contains("**") => None,
+            Some(pat) => Some(pat.matches('/').count()),
+            None => Some(0),
+        };
+        let pattern = match (pattern, recursive) {
+            (Some(pat), true) => format!("**/{}", pat),
+            (Some(pat), false) => pat.to_string(),
+            (None, _) => "**/*".to_string(),
+        };
+        let pattern = Pattern::new(&pattern).map_err(|e| format!("Invalid pattern {:?}: {}", pattern, e))?;
+
+        Ok(Self {
+            pattern,
+            max_depth,
+            follow_symlinks,
+            visited_dirs: HashSet::new(),
+            seen_files: HashSet::new(),
+            files: Vec::new(),
+        })
+    }
+
+    /// All matching files under `root`, in sorted order per directory
+    pub fn run(mut self, root: &Path) -> Vec<PathBuf> {
+        self.walk_dir(root, root, 0);
+        self.files
+    }
+
+    fn walk_dir(&mut self, root: &Path, dir: &Path, depth: usize) {
+        match fs::canonicalize(dir) {
+            Ok(canonical) if self.visited_dirs.contains(&canonical) => {
+                eprintln!("[BATCH] Skipping symlink cycle at {:?}", dir);
+                return;
+            }
+            Ok(canonical) => {
+                self.visited_dirs.insert(canonical);
+            }
+            Err(e) => {
+                eprintln!("[BATCH] Error reading path: {:?}: {}", dir, e);
+                return;
+            }
+        }
+
+        let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+            Ok(entries) => entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect(),
+            Err(e) => {
+                eprintln!("[BATCH] Error reading path: {:?}: {}", dir, e);
+                return;
+            }
+        };
+        entries.sort();
+
+        for path in entries {
+            let Ok(link_metadata) = fs::symlink_metadata(&path) else { continue };
+            if link_metadata.file_type().is_symlink() && !self.follow_symlinks {
+                continue;
+            }
+            // Follows the link, if any; dangling links are skipped
+            let Ok(metadata) = fs::metadata(&path) else { continue };
+
+            if metadata.is_dir() {
+                if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                    self.walk_dir(root, &path, depth + 1);
+                }
+            } else if metadata.is_file() && self.matches(root, &path) {
+                let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                if self.seen_files.insert(canonical) {
+                    self.files.push(path);
+                }
+            }
+        }
+    }
+
+    /// Whether an explicitly listed file passes the same pattern and symlink policy
+    pub fn admits(&self, root: &Path, path: &Path) -> bool {
+        (self.follow_symlinks || !crosses_symlink(root, path)) && self.matches(root, path)
+    }
+
+    fn matches(&self, root: &Path, path: &Path) -> bool {
+        self.matches_relative(path.strip_prefix(root).unwrap_or(path))
+    }
+
+    /// Whether a path relative to the root (or inside an archive) matches the pattern
+    pub fn matches_relative(&self, relative: &Path) -> bool {
+        self.pattern.matches_path_with(relative, MATCH_OPTIONS)
+    }
+}
+
+/// Whether `path` is, or sits below, a symlink somewhere beneath `root`
+pub fn crosses_symlink(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else { return false };
+    let mut current = root.to_path_buf();
+    relative.components().any(|component| {
+        current.push(component);
+        fs::symlink_metadata(&current).is_ok_and(|metadata| metadata.file_type().is_symlink())
+    })
+}
+
+/// `fs::canonicalize`, minus the `\\?\` prefix Windows puts on drive paths;
+/// paths typed by users and many tools lack it, so prefix checks would fail
+pub fn canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+    let canonical = fs::canonicalize(path)?;
+    #[cfg(windows)]
+    if let Some(plain) = canonical.to_str().and_then(|text| text.strip_prefix(r"\\?\")) {
+        // Only `C:\...`; `\\?\UNC\...` and device paths need the prefix
+        if plain.as_bytes().get(1) == Some(&b':') {
+            return Ok(PathBuf::from(plain));
+        }
+    }
+    Ok(canonical)
+}
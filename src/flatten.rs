@@ -0,0 +1,106 @@
+// Dotted-path flattening for nested objects
+//
+// `flatten` turns rows like {"user": {"address": {"city": "Paris"}}} into
+// {"user.address.city": "Paris"} so every table has scalar columns, and
+// `unflatten` reverses it. Entity names at the document root are left
+// alone (TOON entity names cannot contain '.'); flattening applies to each
+// entity's object and to every object row inside an entity's array.
+//
+// Keys that already contain '.' are indistinguishable from flattened paths,
+// so `unflatten` will nest them too.
+
+use serde_json::{Map, Value};
+
+const SEPARATOR: char = '.';
+
+/// Flatten nested objects under each root entity into dotted-path keys
+pub fn flatten(value: Value) -> Result<Value, String> {
+    map_entities(value, flatten_entity)
+}
+
+/// Rebuild nested objects from dotted-path keys under each root entity
+pub fn unflatten(value: Value) -> Result<Value, String> {
+    map_entities(value, unflatten_entity)
+}
+
+fn map_entities(value: Value, f: fn(Value) -> Result<Value, String>) -> Result<Value, String> {
+    match value {
+        Value::Object(map) => {
+            let mut out = Map::new();
+            for (key, entity) in map {
+                out.insert(key, f(entity)?);
+            }
+            Ok(Value::Object(out))
+        }
+        Value::Array(_) => f(value),
+        other => Ok(other),
+    }
+}
+
+fn flatten_entity(value: Value) -> Result<Value, String> {
+    match value {
+        Value::Object(map) => {
+            let mut out = Map::new();
+            flatten_into(&mut out, "", map)?;
+            Ok(Value::Object(out))
+        }
+        Value::Array(items) => items.into_iter().map(flatten_entity).collect::<Result<_, _>>().map(Value::Array),
+        other => Ok(other),
+    }
+}
+
+fn flatten_into(out: &mut Map<String, Value>, prefix: &str, map: Map<String, Value>) -> Result<(), String> {
+    for (key, value) in map {
+        let path = if prefix.is_empty() { key } else { format!("{}{}{}", prefix, SEPARATOR, key) };
+        match value {
+            // Empty objects have no leaves; keep them so unflatten restores them
+            Value::Object(nested) if !nested.is_empty() => flatten_into(out, &path, nested)?,
+            leaf => {
+                if out.contains_key(&path) {
+                    return Err(format!("Flatten conflict: path '{}' appears more than once", path));
+                }
+                out.insert(path, leaf);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn unflatten_entity(value: Value) -> Result<Value, String> {
+    match value {
+        Value::Object(map) => {
+            let mut out = Map::new();
+            for (path, leaf) in map {
+                insert_path(&mut out, &path, leaf)?;
+            }
+            Ok(Value::Object(out))
+        }
+        Value::Array(items) => items.into_iter().map(unflatten_entity).collect::<Result<_, _>>().map(Value::Array),
+        other => Ok(other),
+    }
+}
+
+fn insert_path(out: &mut Map<String, Value>, path: &str, leaf: Value) -> Result<(), String> {
+    let mut current = out;
+    let mut segments = path.split(SEPARATOR).peekable();
+
+    while let Some(segment) = segments.next() {
+        if segments.peek().is_none() {
+            if current.contains_key(segment) {
+                return Err(format!("Unflatten conflict: '{}' is both a value and a parent path", path));
+            }
+            current.insert(segment.to_string(), leaf);
+            return Ok(());
+        }
+
+        let next = current
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        current = match next {
+            Value::Object(map) => map,
+            _ => return Err(format!("Unflatten conflict: '{}' is both a value and a parent path", path)),
+        };
+    }
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+// Synthetic documents from a validation schema (`toonify generate`)
+//
+// Reads the same schema `toonify validate` checks against and produces rows
+// that pass it: `field_types`, `ranges`, `string_lengths`, `enums` and
+// `formats` (email, url, date, uuid) are honored, and the row count is kept
+// within `min_items`/`max_items`. Where the schema says nothing more than
+// "string", the field name picks a plausible value (`name`, `city`,
+// `country`, `phone`, `company`, ...); an `id` column counts up from its
+// range minimum so keys are unique. Regex `patterns` can't be generated
+// from and are reported as warnings.
+//
+// Output depends only on the schema and the seed.
+
+use serde_json::{Map, Number, Value};
+
+pub struct GenerateOptions {
+    /// Rows per entity, clamped to the entity's min_items/max_items
+    pub rows: usize,
+    pub seed: u64,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self { rows: 10, seed: 0x67_65_6e }
+    }
+}
+
+/// Generate a document for `schema`, plus warnings about constraints that weren't honored
+pub fn generate(schema: &Value, options: &GenerateOptions) -> Result<(Value, Vec<String>), String> {
+    let entities = schema.as_object().ok_or("Schema must be a JSON object")?;
+    let mut rng = Rng::new(options.seed);
+    let mut warnings = Vec::new();
+    let mut doc = Map::new();
+
+    for (name, entity) in entities {
+        let entity = entity.as_object().ok_or_else(|| format!("Schema for '{}' must be an object", name))?;
+        match entity.get("type").and_then(Value::as_str) {
+            Some("array") => {}
+            Some(other) => return Err(format!("Unsupported entity type: {}", other)),
+            None => return Err(format!("Schema for '{}' must have 'type' field", name)),
+        }
+
+        let constraint = |key: &str| entity.get(key).and_then(Value::as_object);
+        let mut fields: Vec<&str> = entity
+            .get("fields")
+            .and_then(Value::as_array)
+            .ok_or_else(|| format!("Schema for '{}' must have 'fields' array", name))?
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        for key in ["field_types", "ranges", "string_lengths", "enums", "formats"] {
+            for field in constraint(key).into_iter().flat_map(|map| map.keys()) {
+                if !fields.contains(&field.as_str()) {
+                    fields.push(field);
+                }
+            }
+        }
+        if let Some(patterns) = constraint("patterns") {
+            for field in patterns.keys() {
+                let covered = [constraint("enums"), constraint("formats")].iter().flatten().any(|map| map.contains_key(field));
+                if !covered {
+                    warnings.push(format!("{}.{}: regex patterns are not generated from; values may not match", name, field));
+                }
+            }
+        }
+
+        let mut rows = options.rows;
+        if let Some(min) = entity.get("min_items").and_then(Value::as_u64) {
+            rows = rows.max(min as usize);
+        }
+        if let Some(max) = entity.get("max_items").and_then(Value::as_u64) {
+            rows = rows.min(max as usize);
+        }
+
+        let specs: Vec<FieldSpec> = fields
+            .iter()
+            .map(|field| FieldSpec {
+                name: field,
+                ty: constraint("field_types").and_then(|map| map.get(*field)).and_then(Value::as_str),
+                range: constraint("ranges").and_then(|map| map.get(*field)).and_then(Value::as_object),
+                length: constraint("string_lengths").and_then(|map| map.get(*field)).and_then(Value::as_object),
+                choices: constraint("enums").and_then(|map| map.get(*field)).and_then(Value::as_array),
+                format: constraint("formats").and_then(|map| map.get(*field)).and_then(Value::as_str),
+            })
+            .collect();
+
+        let mut items = Vec::with_capacity(rows);
+        for index in 0..rows {
+            let mut row = Map::new();
+            for spec in &specs {
+                row.insert(spec.name.to_string(), spec.value(index, &mut rng)?);
+            }
+            items.push(Value::Object(row));
+        }
+        doc.insert(name.clone(), Value::Array(items));
+    }
+    Ok((Value::Object(doc), warnings))
+}
+
+struct FieldSpec<'a> {
+    name: &'a str,
+    ty: Option<&'a str>,
+    range: Option<&'a Map<String, Value>>,
+    length: Option<&'a Map<String, Value>>,
+    choices: Option<&'a Vec<Value>>,
+    format: Option<&'a str>,
+}
+
+impl FieldSpec<'_> {
+    fn value(&self, index: usize, rng: &mut Rng) -> Result<Value, String> {
+        if let Some(choices) = self.choices.filter(|choices| !choices.is_empty()) {
+            return Ok(choices[rng.below(choices.len())].clone());
+        }
+        if let Some(format) = self.format {
+            return self.format_value(format, rng).map(Value::String);
+        }
+        let name = self.name.to_ascii_lowercase();
+        let numeric = self.range.is_some() || name == "id" || name.ends_with("_id") || name.ends_with("count") || name == "age";
+        match self.ty {
+            Some("number") => Ok(self.number(index, rng)),
+            Some("boolean") => Ok(Value::Bool(rng.below(2) == 0)),
+            Some("null") => Ok(Value::Null),
+            Some("string") => Ok(Value::String(self.string(rng))),
+            Some(other) => Err(format!("Unknown type: {}", other)),
+            None if numeric => Ok(self.number(index, rng)),
+            None if name.starts_with("is_") || name.starts_with("has_") || name == "active" || name == "enabled" => {
+                Ok(Value::Bool(rng.below(2) == 0))
+            }
+            None => Ok(Value::String(self.string(rng))),
+        }
+    }
+
+    fn format_value(&self, format: &str, rng: &mut Rng) -> Result<String, String> {
+        Ok(match format {
+            "email" => format!("{}.{}{}@example.com", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES), rng.below(100)).to_ascii_lowercase(),
+            "url" => format!("https://example.com/{}/{}", rng.pick(WORDS), rng.below(10_000)),
+            "date" => {
+                let month = 1 + rng.below(12);
+                let days = match month {
+                    2 => 28,
+                    4 | 6 | 9 | 11 => 30,
+                    _ => 31,
+                };
+                format!("{}-{:02}-{:02}", 2000 + rng.below(30), month, 1 + rng.below(days))
+            }
+            "uuid" => {
+                let hex = |rng: &mut Rng, digits: usize| (0..digits).map(|_| char::from(b"0123456789abcdef"[rng.below(16)])).collect::<String>();
+                format!(
+                    "{}-{}-4{}-{}{}-{}",
+                    hex(rng, 8),
+                    hex(rng, 4),
+                    hex(rng, 3),
+                    ['8', '9', 'a', 'b'][rng.below(4)],
+                    hex(rng, 3),
+                    hex(rng, 12)
+                )
+            }
+            other => return Err(format!("Unknown format type: {}", other)),
+        })
+    }
+
+    fn number(&self, index: usize, rng: &mut Rng) -> Value {
+        let bound = |key: &str| self.range.and_then(|range| range.get(key));
+        let integral = [bound("min"), bound("max")].iter().flatten().all(|bound| bound.is_i64() || bound.is_u64());
+        let min = bound("min").and_then(Value::as_f64).unwrap_or(0.0);
+        let max = bound("max").and_then(Value::as_f64).unwrap_or(min + 1000.0).max(min);
+
+        if integral {
+            let (min, max) = (min as i64, max as i64);
+            // ids count up (wrapping within the range) so they stay unique
+            if self.name.eq_ignore_ascii_case("id") {
+                let span = (max - min + 1).max(1) as usize;
+                return Value::from(min + (index % span) as i64);
+            }
+            return Value::from(min + rng.below((max - min + 1).max(1) as usize) as i64);
+        }
+        let value = min + (max - min) * rng.unit();
+        let rounded = ((value * 100.0).round() / 100.0).clamp(min, max);
+        Number::from_f64(rounded).map_or(Value::Null, Value::Number)
+    }
+
+    fn string(&self, rng: &mut Rng) -> String {
+        let name = self.name.to_ascii_lowercase();
+        let text = if name.contains("email") {
+            self.format_value("email", rng).unwrap_or_default()
+        } else if name == "first_name" || name == "firstname" {
+            rng.pick(FIRST_NAMES).to_string()
+        } else if name == "last_name" || name == "lastname" || name == "surname" {
+            rng.pick(LAST_NAMES).to_string()
+        } else if name.contains("name") && !name.contains("company") {
+            format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES))
+        } else if name.contains("city") {
+            rng.pick(CITIES).to_string()
+        } else if name.contains("country") {
+            rng.pick(COUNTRIES).to_string()
+        } else if name.contains("company") || name.contains("org") {
+            format!("{} {}", rng.pick(LAST_NAMES), rng.pick(&["Labs", "Systems", "Group", "Partners", "Works"]))
+        } else if name.contains("phone") {
+            format!("+1-555-{:03}-{:04}", rng.below(1000), rng.below(10_000))
+        } else if name.contains("date") || name.ends_with("_at") || name.ends_with("_on") {
+            self.format_value("date", rng).unwrap_or_default()
+        } else if name.contains("url") || name.contains("website") {
+            self.format_value("url", rng).unwrap_or_default()
+        } else {
+            let words = 1 + rng.below(3);
+            (0..words).map(|_| rng.pick(WORDS)).collect::<Vec<_>>().join(" ")
+        };
+        self.fit_length(text, rng)
+    }
+
+    // string_lengths counts bytes, like validate; generated text is ASCII
+    fn fit_length(&self, mut text: String, rng: &mut Rng) -> String {
+        let bound = |key: &str| self.length.and_then(|length| length.get(key)).and_then(Value::as_u64).map(|n| n as usize);
+        if let Some(max) = bound("max") {
+            text.truncate(max);
+        }
+        if let Some(min) = bound("min") {
+            while text.len() < min {
+                text.push(char::from(b'a' + rng.below(26) as u8));
+            }
+        }
+        text
+    }
+}
+
+const FIRST_NAMES: &[&str] = &["Alice", "Bob", "Carol", "Dmitri", "Elena", "Farah", "Goro", "Hana", "Ivan", "Julia", "Kofi", "Lena", "Mateo", "Nina", "Omar", "Priya"];
+const LAST_NAMES: &[&str] = &["Johnson", "Smith", "Garcia", "Meyer", "Tanaka", "Okafor", "Rossi", "Kowalski", "Nguyen", "Silva", "Haddad", "Larsen"];
+const CITIES: &[&str] = &["Paris", "Oslo", "Lagos", "Osaka", "Lima", "Toronto", "Nairobi", "Krakow", "Austin", "Porto"];
+const COUNTRIES: &[&str] = &["France", "Norway", "Nigeria", "Japan", "Peru", "Canada", "Kenya", "Poland", "United States", "Portugal"];
+const WORDS: &[&str] = &["alpha", "amber", "basil", "cedar", "delta", "ember", "fjord", "garnet", "harbor", "iris", "juniper", "kestrel", "lumen", "maple"];
+
+// The same xorshift as selftest's generator, so output is reproducible from the seed
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
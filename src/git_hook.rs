@@ -0,0 +1,139 @@
+// `toonify hook`: keep committed TOON artifacts in sync with their JSON sources
+//
+// `hook run` looks at the staged files (or the paths it is given, which is
+// how hook frameworks such as pre-commit call it). Every `.json` must convert
+// cleanly, and when a `.toon` sibling is tracked next to it the sibling is
+// regenerated and re-staged, or reported as stale with `--check`. A `.toon`
+// without a JSON source only has to parse.
+//
+// `hook install` writes a `.git/hooks/pre-commit` that calls `hook run` with
+// this binary, refusing to replace a hook it did not write unless `--force`.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use toonify::converter::{self, Converter};
+
+use crate::atomic_write;
+
+// Written into the hook script so reinstalling can recognise our own hook
+const HOOK_MARKER: &str = "# Installed by `toonify hook install`";
+
+pub fn install(force: bool) -> Result<(), Box<dyn Error>> {
+    // Respects core.hooksPath and linked worktrees
+    let hooks_dir = PathBuf::from(git(&["rev-parse", "--git-path", "hooks"])?.trim());
+    fs::create_dir_all(&hooks_dir)?;
+    let hook_path = hooks_dir.join("pre-commit");
+
+    let foreign_hook = fs::read_to_string(&hook_path).is_ok_and(|existing| !existing.contains(HOOK_MARKER));
+    if foreign_hook && !force {
+        return Err(format!("{:?} already exists; re-run with --force to replace it", hook_path).into());
+    }
+
+    let exe = std::env::current_exe()?;
+    let script = format!(
+        "#!/bin/sh\n{}\nexec '{}' hook run\n",
+        HOOK_MARKER,
+        exe.display().to_string().replace('\'', r"'\''"),
+    );
+    fs::write(&hook_path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    eprintln!("[HOOK] Installed pre-commit hook: {:?}", hook_path);
+    println!("✓ pre-commit hook installed");
+    Ok(())
+}
+
+pub fn run(files: Vec<PathBuf>, check: bool, converter: &Converter) -> Result<(), Box<dyn Error>> {
+    let files = if files.is_empty() { staged_files()? } else { files };
+
+    // One entry per JSON source / TOON artifact pair, whichever of the two changed
+    let stems: BTreeSet<PathBuf> = files
+        .iter()
+        .filter(|path| matches!(extension(path).as_deref(), Some("json" | "toon")))
+        .map(|path| path.with_extension(""))
+        .collect();
+
+    eprintln!("[HOOK] Checking {} JSON/TOON file(s)", stems.len());
+
+    let mut problems = Vec::new();
+    let mut updated = Vec::new();
+    for stem in &stems {
+        let json_path = stem.with_extension("json");
+        let toon_path = stem.with_extension("toon");
+
+        if json_path.is_file() {
+            let json = fs::read_to_string(&json_path)?;
+            let toon = match converter.convert(&json, "json", "toon") {
+                Ok(toon) => toon,
+                Err(e) => {
+                    problems.push(format!("{:?}: {}", json_path, e));
+                    continue;
+                }
+            };
+
+            let Ok(existing) = fs::read_to_string(&toon_path) else { continue };
+            if existing.trim_end() == toon.trim_end() {
+                continue;
+            }
+            if check {
+                problems.push(format!("{:?} is out of date with {:?}", toon_path, json_path));
+            } else {
+                atomic_write::write_atomic(&toon_path, toon.as_bytes(), false, None)?;
+                eprintln!("[HOOK] Regenerated {:?}", toon_path);
+                updated.push(toon_path);
+            }
+        } else if toon_path.is_file() {
+            let toon = fs::read_to_string(&toon_path)?;
+            if let Err(e) = converter::toon_to_value(&toon) {
+                problems.push(format!("{:?}: {}", toon_path, e));
+            }
+        }
+    }
+
+    if !updated.is_empty() {
+        let mut args = vec!["add".to_string(), "--".to_string()];
+        args.extend(updated.iter().map(|path| path.display().to_string()));
+        git(&args.iter().map(String::as_str).collect::<Vec<_>>())?;
+        println!("Regenerated and staged {} TOON file(s)", updated.len());
+    }
+
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("[HOOK] ✗ {}", problem);
+        }
+        return Err(format!("{} file(s) failed the TOON pre-commit check", problems.len()).into());
+    }
+
+    println!("✓ TOON files are in sync");
+    Ok(())
+}
+
+// Added, copied, modified or renamed paths, relative to the current directory
+fn staged_files() -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let output = git(&["diff", "--cached", "--name-only", "--diff-filter=ACMR", "--relative", "-z"])?;
+    Ok(output.split('\0').filter(|path| !path.is_empty()).map(PathBuf::from).collect())
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+fn git(args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
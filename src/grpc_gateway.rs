@@ -0,0 +1,185 @@
+// JSON/HTTP transcoding of the gRPC service (`serve --grpc-gateway`)
+//
+// Every unary method in proto/converter.proto is exposed on the REST port at
+// its gRPC path, as grpc-gateway does for methods without an HTTP binding:
+//
+//     POST /converter.ConverterService/JsonToToon  {"data": "..."}
+//       -> {"result": "..."}
+//
+// Routes come from the proto itself, compiled at startup, and each call goes
+// through the gRPC service in-process, so a method added to the proto is
+// served over JSON with the same limits, guards and audit log as over gRPC
+// and nothing is written twice. Bodies use the proto3 JSON mapping (fields in
+// lowerCamelCase, defaults omitted); gRPC errors come back as grpc-gateway's
+// `{"code", "message", "details"}` with the matching HTTP status.
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use prost::bytes::Buf;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use protox::file::{File, FileResolver};
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::MetadataMap;
+use tonic::{Code, Status};
+
+use crate::pb::converter_service_server::ConverterServiceServer;
+use crate::server::ConverterServiceImpl;
+
+const PROTO_NAME: &str = "converter.proto";
+const PROTO_SOURCE: &str = include_str!("../proto/converter.proto");
+
+// The service definition the binary was built with, so routes always match it
+struct EmbeddedProto;
+
+impl FileResolver for EmbeddedProto {
+    fn open_file(&self, name: &str) -> Result<File, protox::Error> {
+        match name {
+            PROTO_NAME => File::from_source(name, PROTO_SOURCE),
+            _ => Err(protox::Error::file_not_found(name)),
+        }
+    }
+}
+
+/// The transcoded methods, read from the embedded proto
+#[derive(Clone)]
+pub struct Gateway {
+    methods: Vec<MethodDescriptor>,
+}
+
+impl Gateway {
+    pub fn new() -> Result<Self, String> {
+        let mut compiler = protox::Compiler::with_file_resolver(EmbeddedProto);
+        compiler.open_file(PROTO_NAME).map_err(|e| format!("Failed to compile {}: {}", PROTO_NAME, e))?;
+        Ok(Self::from_pool(&compiler.descriptor_pool()))
+    }
+
+    /// Transcode the unary methods of every service in `pool`
+    pub fn from_pool(pool: &DescriptorPool) -> Self {
+        let methods = pool.services()
+            .flat_map(|service| service.methods().collect::<Vec<_>>())
+            .filter(|method| !method.is_client_streaming() && !method.is_server_streaming())
+            .collect();
+        Self { methods }
+    }
+
+    /// `/package.Service/Method` for each transcoded method, for logs
+    pub fn paths(&self) -> Vec<String> {
+        self.methods.iter().map(grpc_path).collect()
+    }
+
+    /// One `POST` route per method, answered by `service`
+    pub fn router(&self, service: ConverterServiceServer<ConverterServiceImpl>) -> Router {
+        self.methods.iter().fold(Router::new(), |app, method| {
+            let (method, service) = (method.clone(), service.clone());
+            app.route(&grpc_path(&method), post(move |headers: HeaderMap, body: Bytes| async move {
+                match transcode(service, &method, headers, &body).await {
+                    Ok(reply) => Json(reply).into_response(),
+                    Err(status) => error_response(&status),
+                }
+            }))
+        })
+    }
+}
+
+fn grpc_path(method: &MethodDescriptor) -> String {
+    format!("/{}/{}", method.parent_service().full_name(), method.name())
+}
+
+async fn transcode(
+    service: ConverterServiceServer<ConverterServiceImpl>,
+    method: &MethodDescriptor,
+    headers: HeaderMap,
+    body: &[u8],
+) -> Result<serde_json::Value, Status> {
+    let input = method.input();
+    // An empty body is the default message, as with grpc-gateway
+    let body = if body.iter().all(u8::is_ascii_whitespace) { b"{}".as_slice() } else { body };
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let message = DynamicMessage::deserialize(input.clone(), &mut deserializer)
+        .and_then(|message| deserializer.end().map(|_| message))
+        .map_err(|e| Status::invalid_argument(format!("Invalid {} JSON: {}", input.full_name(), e)))?;
+
+    let path = PathAndQuery::try_from(grpc_path(method)).map_err(|e| Status::internal(e.to_string()))?;
+    let request = tonic::Request::from_parts(MetadataMap::from_headers(headers), Default::default(), message);
+    let mut grpc = tonic::client::Grpc::new(service);
+    grpc.ready().await.map_err(|e| Status::unavailable(e.to_string()))?;
+    let reply = grpc.unary(request, path, DynamicCodec(method.output())).await?.into_inner();
+
+    reply
+        .serialize_with_options(serde_json::value::Serializer, &prost_reflect::SerializeOptions::new())
+        .map_err(|e| Status::internal(format!("Failed to convert {} to JSON: {}", method.output().full_name(), e)))
+}
+
+fn error_response(status: &Status) -> Response {
+    let body = serde_json::json!({
+        "code": status.code() as i32,
+        "message": status.message(),
+        "details": [],
+    });
+    (http_status(status.code()), Json(body)).into_response()
+}
+
+// grpc-gateway's mapping from gRPC codes to HTTP statuses
+fn http_status(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::Cancelled => StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+        Code::InvalidArgument | Code::OutOfRange | Code::FailedPrecondition => StatusCode::BAD_REQUEST,
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::Unknown | Code::Internal | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// Encodes any request message and decodes replies of the method's output type
+struct DynamicCodec(MessageDescriptor);
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicCodec;
+    type Decoder = DynamicCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicCodec(self.0.clone())
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicCodec(self.0.clone())
+    }
+}
+
+impl Encoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.reserve(item.encoded_len());
+        item.encode(dst).map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+impl Decoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        let bytes = src.copy_to_bytes(src.remaining());
+        DynamicMessage::decode(self.0.clone(), bytes)
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
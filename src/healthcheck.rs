@@ -0,0 +1,82 @@
+// `toonify healthcheck`: exit 0 when a local server is ready, 1 otherwise
+//
+//     HEALTHCHECK CMD ["toonify", "healthcheck"]
+//
+// The HTTP probe is a bare HTTP/1.1 GET /readyz over a Tokio socket, so
+// images need neither curl nor a TLS stack. The gRPC probe runs a tiny
+// json_to_toon conversion, which also exercises the conversion pool. A
+// wildcard listen address (the server default, 0.0.0.0) is probed on
+// loopback, so the same TOONIFY_HTTP_ADDR / TOONIFY_GRPC_ADDR can configure
+// both.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use toonify::pb::ConvertRequest;
+use toonify::pb::converter_service_client::ConverterServiceClient;
+
+pub enum Probe {
+    Http(SocketAddr),
+    Grpc(SocketAddr),
+}
+
+pub async fn run(probe: Probe, timeout: Duration) -> Result<(), String> {
+    let (what, result) = match probe {
+        Probe::Http(addr) => {
+            let addr = loopback(addr);
+            (format!("http://{}/readyz", addr), tokio::time::timeout(timeout, probe_http(addr)).await)
+        }
+        Probe::Grpc(addr) => {
+            let addr = loopback(addr);
+            (format!("grpc://{}", addr), tokio::time::timeout(timeout, probe_grpc(addr)).await)
+        }
+    };
+    match result {
+        Ok(Ok(())) => {
+            println!("✓ Ready ({})", what);
+            Ok(())
+        }
+        Ok(Err(e)) => Err(format!("Not ready ({}): {}", what, e)),
+        Err(_) => Err(format!("Not ready ({}): no answer within {}ms", what, timeout.as_millis())),
+    }
+}
+
+fn loopback(addr: SocketAddr) -> SocketAddr {
+    match addr.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), addr.port()),
+        IpAddr::V6(ip) if ip.is_unspecified() => SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), addr.port()),
+        _ => addr,
+    }
+}
+
+async fn probe_http(addr: SocketAddr) -> Result<(), String> {
+    let mut stream = TcpStream::connect(addr).await.map_err(|e| e.to_string())?;
+    let request = format!("GET /readyz HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", addr);
+    stream.write_all(request.as_bytes()).await.map_err(|e| e.to_string())?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some("200") => Ok(()),
+        Some(_) => Err(status_line.to_string()),
+        None => Err("not an HTTP response".to_string()),
+    }
+}
+
+async fn probe_grpc(addr: SocketAddr) -> Result<(), String> {
+    let mut client = ConverterServiceClient::connect(format!("http://{}", addr)).await.map_err(|e| e.to_string())?;
+    let response = client
+        .json_to_toon(ConvertRequest { data: r#"{"healthcheck":true}"#.to_string() })
+        .await
+        .map_err(|status| status.message().to_string())?
+        .into_inner();
+    if !response.error.is_empty() {
+        return Err(response.error);
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// ANSI syntax highlighting for terminal output
+//
+// TOON is colored with a small line classifier that follows the parser's
+// header rules; JSON goes through syntect when the `color` feature is on.
+
+use serde_json::Value;
+
+use crate::toon::format::split_cells;
+use crate::toon::parser::is_entry_header_line;
+use crate::toon::parse_value;
+
+const RESET: &str = "\x1b[0m";
+const ENTITY: &str = "\x1b[1;34m";
+const COUNT: &str = "\x1b[33m";
+const COLUMNS: &str = "\x1b[36m";
+const NUMBER: &str = "\x1b[35m";
+const STRING: &str = "\x1b[32m";
+const LITERAL: &str = "\x1b[33m";
+const NULL: &str = "\x1b[2m";
+
+/// Color TOON headers, numbers, strings, booleans, and nulls with ANSI escapes
+pub fn highlight_toon(toon: &str) -> String {
+    let mut output = String::with_capacity(toon.len() * 2);
+    for line in toon.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            output.push('\n');
+            continue;
+        }
+
+        if is_entry_header_line(trimmed) {
+            highlight_header(&mut output, trimmed);
+        } else {
+            highlight_cells(&mut output, trimmed);
+        }
+        output.push('\n');
+    }
+
+    if !toon.ends_with('\n') {
+        output.pop();
+    }
+    output
+}
+
+fn highlight_header(output: &mut String, line: &str) {
+    let name_end = line.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(line.len());
+    paint(output, ENTITY, &line[..name_end]);
+
+    let mut rest = &line[name_end..];
+    if rest.starts_with('[') {
+        let end = rest.find(']').map_or(rest.len(), |i| i + 1);
+        paint(output, COUNT, &rest[..end]);
+        rest = &rest[end..];
+    }
+    if rest.starts_with('{') {
+        let end = rest.find('}').map_or(rest.len(), |i| i + 1);
+        paint(output, COLUMNS, &rest[..end]);
+        rest = &rest[end..];
+    }
+
+    match rest.split_once(':') {
+        Some((before, inline)) => {
+            output.push_str(before);
+            output.push(':');
+            if !inline.trim().is_empty() {
+                highlight_cells(output, inline.trim());
+            }
+        }
+        None => output.push_str(rest),
+    }
+}
+
+fn highlight_cells(output: &mut String, line: &str) {
+    for (i, cell) in split_cells(line).iter().enumerate() {
+        if i > 0 {
+            output.push(',');
+        }
+        let color = if cell.starts_with('"') {
+            STRING
+        } else {
+            match parse_value(cell) {
+                Value::Null => NULL,
+                Value::Bool(_) => LITERAL,
+                Value::Number(_) => NUMBER,
+                _ => STRING,
+            }
+        };
+        paint(output, color, cell);
+    }
+}
+
+fn paint(output: &mut String, color: &str, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    output.push_str(color);
+    output.push_str(text);
+    output.push_str(RESET);
+}
+
+/// Color JSON with syntect's bundled JSON grammar
+#[cfg(feature = "color")]
+pub fn highlight_json(json: &str) -> String {
+    use std::sync::OnceLock;
+    use syntect::easy::HighlightLines;
+    use syntect::highlighting::{Theme, ThemeSet};
+    use syntect::parsing::SyntaxSet;
+    use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+
+    static ASSETS: OnceLock<(SyntaxSet, Theme)> = OnceLock::new();
+    let (syntaxes, theme) = ASSETS.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults();
+        let theme = themes.themes.remove("base16-ocean.dark").unwrap_or_default();
+        (SyntaxSet::load_defaults_newlines(), theme)
+    });
+
+    let Some(syntax) = syntaxes.find_syntax_by_extension("json") else {
+        return json.to_string();
+    };
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut output = String::with_capacity(json.len() * 2);
+    for line in LinesWithEndings::from(json) {
+        match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => output.push_str(&as_24_bit_terminal_escaped(&ranges, false)),
+            Err(_) => return json.to_string(),
+        }
+    }
+    output.push_str(RESET);
+    output
+}
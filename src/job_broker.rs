@@ -0,0 +1,291 @@
+// Jobs shared through Valkey/Redis (feature `distributed-jobs`)
+//
+// API nodes (`serve --enable-job-queue --job-queue-backend redis://host:6379`)
+// and worker nodes (`toonify worker --queue redis://host:6379`) share these keys:
+//
+//     toonify:jobs:pending     ids waiting for a worker, oldest on the right
+//     toonify:jobs:processing  ids a worker has claimed
+//     toonify:job:{id}         the job as JSON; expires ttl_secs after it finishes
+//     toonify:lease:{name}     holder of a `LeaseStore` lease, with the lease's TTL
+//
+// A worker claims a job with BLMOVE from pending to processing, runs it, writes
+// the result back and drops the id from processing. An id left in processing
+// belongs to a worker that died mid-job; LMOVE it back to pending to retry.
+//
+// The API node's handlers keep reading its in-memory `JobStore`. `relay` keeps
+// that store in step with Redis: jobs submitted locally are queued, the
+// progress of queued jobs is copied back, and every few seconds the whole key
+// space is read, so jobs submitted on other nodes (or before a restart) can be
+// looked up here too.
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use redis::{Commands, Direction};
+
+use crate::guards::ParserGuards;
+use crate::job_queue::{self, Job, JobStatus, JobStore, LeaseStore};
+
+pub const PENDING: &str = "toonify:jobs:pending";
+pub const PROCESSING: &str = "toonify:jobs:processing";
+pub const JOB_PREFIX: &str = "toonify:job:";
+pub const LEASE_PREFIX: &str = "toonify:lease:";
+
+/// How long a worker's BLMOVE waits before asking again
+const CLAIM_TIMEOUT_SECS: f64 = 1.0;
+const RELAY_INTERVAL: Duration = Duration::from_millis(100);
+/// How often the relay reads every job, for jobs submitted on other nodes
+const RESYNC_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct JobBroker {
+    client: redis::Client,
+}
+
+impl JobBroker {
+    /// A `redis://` (or `rediss://`) URL of the Valkey/Redis server the nodes share
+    pub fn new(url: &str) -> Result<Self, String> {
+        let client = redis::Client::open(url).map_err(|e| format!("Invalid job queue URL {:?}: {}", url, e))?;
+        Ok(Self { client })
+    }
+
+    pub fn connection(&self) -> Result<redis::Connection, String> {
+        self.client.get_connection().map_err(|e| e.to_string())
+    }
+
+    /// Store `job` and queue it for the workers
+    pub fn submit(&self, connection: &mut redis::Connection, job: &Job) -> Result<(), String> {
+        let json = serde_json::to_string(job).map_err(|e| e.to_string())?;
+        redis::pipe()
+            .atomic()
+            .set(job_key(&job.id), json)
+            .ignore()
+            .lpush(PENDING, &job.id)
+            .ignore()
+            .query::<()>(connection)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn get(&self, connection: &mut redis::Connection, id: &str) -> Result<Option<Job>, String> {
+        let json: Option<String> = connection.get(job_key(id)).map_err(|e| e.to_string())?;
+        json.map(|json| serde_json::from_str(&json).map_err(|e| format!("Invalid job {}: {}", id, e))).transpose()
+    }
+
+    /// Every job still in Redis, in no particular order
+    pub fn list(&self, connection: &mut redis::Connection) -> Result<Vec<Job>, String> {
+        let keys: Vec<String> = connection
+            .scan_match::<_, String>(format!("{}*", JOB_PREFIX))
+            .map_err(|e| e.to_string())?
+            .collect();
+        let mut jobs = Vec::new();
+        for key in keys {
+            // Expired between the scan and the read
+            if let Some(job) = self.get(connection, &key[JOB_PREFIX.len()..])? {
+                jobs.push(job);
+            }
+        }
+        Ok(jobs)
+    }
+
+    fn save(&self, connection: &mut redis::Connection, job: &Job) -> Result<(), String> {
+        let json = serde_json::to_string(job).map_err(|e| e.to_string())?;
+        match (job.finished_at, job.metadata.ttl_secs) {
+            (Some(_), Some(ttl)) => connection.set_ex::<_, _, ()>(job_key(&job.id), json, ttl.max(1)),
+            _ => connection.set::<_, _, ()>(job_key(&job.id), json),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    // Claim and run jobs until the connection fails
+    fn run_jobs(&self, connection: &mut redis::Connection, worker_id: usize, guards: ParserGuards) -> Result<Infallible, String> {
+        loop {
+            let claimed: Option<String> = connection
+                .blmove(PENDING, PROCESSING, Direction::Right, Direction::Left, CLAIM_TIMEOUT_SECS)
+                .map_err(|e| e.to_string())?;
+            let Some(id) = claimed else {
+                continue;
+            };
+
+            // Gone if it expired or was deleted while queued
+            if let Some(mut job) = self.get(connection, &id)? {
+                eprintln!("[WORKER {}] Processing job: {}{}", worker_id, id, job.metadata.trace_note());
+                job_queue::start_job(&mut job);
+                self.save(connection, &job)?;
+
+                let outcome = job_queue::run_operation(&job.operation, &job.data, guards);
+                job_queue::finish_job(&mut job, outcome);
+                self.save(connection, &job)?;
+                match &job.error {
+                    None => eprintln!("[WORKER {}] Job completed: {}{}", worker_id, id, job.metadata.trace_note()),
+                    Some(error) => eprintln!("[WORKER {}] Job failed: {} - {}{}", worker_id, id, error, job.metadata.trace_note()),
+                }
+                job_queue::notify(&job);
+            }
+            let _: usize = connection.lrem(PROCESSING, 1, &id).map_err(|e| e.to_string())?;
+        }
+    }
+}
+
+// Compare-and-set, so a lease only ever changes hands once it has lapsed
+const ACQUIRE: &str = r#"
+local holder = redis.call('GET', KEYS[1])
+if holder == false then
+    redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+    return 1
+elseif holder == ARGV[1] then
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+    return 1
+end
+return 0
+"#;
+
+const RELEASE: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+impl LeaseStore for JobBroker {
+    fn acquire(&self, name: &str, holder: &str, ttl: Duration) -> Result<bool, String> {
+        let mut connection = self.connection()?;
+        let acquired: i32 = redis::Script::new(ACQUIRE)
+            .key(format!("{}{}", LEASE_PREFIX, name))
+            .arg(holder)
+            .arg(ttl.as_millis().max(1) as u64)
+            .invoke(&mut connection)
+            .map_err(|e| e.to_string())?;
+        Ok(acquired == 1)
+    }
+
+    fn release(&self, name: &str, holder: &str) -> Result<(), String> {
+        let mut connection = self.connection()?;
+        redis::Script::new(RELEASE)
+            .key(format!("{}{}", LEASE_PREFIX, name))
+            .arg(holder)
+            .invoke::<i32>(&mut connection)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn job_key(id: &str) -> String {
+    format!("{}{}", JOB_PREFIX, id)
+}
+
+/// Run queued jobs on `worker_count` threads, reconnecting with backoff when Redis goes away;
+/// job input past `guards` fails the job
+pub fn start_workers(broker: JobBroker, worker_count: usize, guards: ParserGuards) -> Vec<JoinHandle<()>> {
+    eprintln!("[JOB QUEUE] Starting {} worker threads on {}", worker_count, PENDING);
+    (0..worker_count)
+        .map(|worker_id| {
+            let broker = broker.clone();
+            std::thread::spawn(move || work(&broker, worker_id, guards))
+        })
+        .collect()
+}
+
+/// `start_workers` for a process that does nothing else; returns only if every worker panics
+pub fn run_workers(broker: JobBroker, worker_count: usize, guards: ParserGuards) {
+    for handle in start_workers(broker, worker_count, guards) {
+        let _ = handle.join();
+    }
+}
+
+fn work(broker: &JobBroker, worker_id: usize, guards: ParserGuards) {
+    eprintln!("[WORKER {}] Started", worker_id);
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        let error = match broker.connection() {
+            Ok(mut connection) => {
+                backoff = Duration::from_secs(1);
+                match broker.run_jobs(&mut connection, worker_id, guards) {
+                    Ok(never) => match never {},
+                    Err(e) => e,
+                }
+            }
+            Err(e) => e,
+        };
+        eprintln!("[WORKER {}] Job queue error: {}; retrying in {}s", worker_id, error, backoff.as_secs());
+        std::thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Keep an API node's `store` in step with the shared queue, in the background
+pub fn relay(broker: JobBroker, store: JobStore) {
+    let mut relay = Relay { broker, store, connection: None, queued: HashSet::new(), synced: None };
+    std::thread::spawn(move || loop {
+        if let Err(e) = relay.tick() {
+            eprintln!("[JOB QUEUE] Job queue error: {}; retrying", e);
+            relay.connection = None;
+            relay.synced = None;
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        std::thread::sleep(RELAY_INTERVAL);
+    });
+}
+
+struct Relay {
+    broker: JobBroker,
+    store: JobStore,
+    connection: Option<redis::Connection>,
+    /// In Redis and not finished yet
+    queued: HashSet<String>,
+    synced: Option<Instant>,
+}
+
+impl Relay {
+    fn tick(&mut self) -> Result<(), String> {
+        let connection = match &mut self.connection {
+            Some(connection) => connection,
+            None => self.connection.insert(self.broker.connection()?),
+        };
+
+        if self.synced.is_none_or(|synced| synced.elapsed() >= RESYNC_INTERVAL) {
+            let now = job_queue::now_secs();
+            let jobs = self.broker.list(connection)?;
+            let mut store = self.store.lock().unwrap();
+            for job in jobs.into_iter().filter(|job| !job_queue::is_expired(job, now)) {
+                if job.finished_at.is_none() {
+                    self.queued.insert(job.id.clone());
+                }
+                store.insert(job.id.clone(), job);
+            }
+            self.synced = Some(Instant::now());
+        }
+
+        // Submitted here since the last tick
+        let submitted: Vec<Job> = self.store.lock().unwrap()
+            .values()
+            .filter(|job| job.status == JobStatus::Pending && !self.queued.contains(&job.id))
+            .cloned()
+            .collect();
+        for job in submitted {
+            self.broker.submit(connection, &job)?;
+            self.queued.insert(job.id);
+        }
+
+        let queued: Vec<String> = self.queued.iter().cloned().collect();
+        for id in queued {
+            let job = self.broker.get(connection, &id)?;
+            let mut store = self.store.lock().unwrap();
+            match job {
+                Some(job) => {
+                    if job.finished_at.is_some() {
+                        self.queued.remove(&id);
+                    }
+                    store.insert(id, job);
+                }
+                None => {
+                    self.queued.remove(&id);
+                    store.remove(&id);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,18 @@
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use serde::{Serialize, Deserialize};
+use serde_json::Value;
 use uuid::Uuid;
 
+use crate::converter::{Converter, FormatCodec, JsonCodec};
+use crate::guards::ParserGuards;
+
+/// How often finished jobs are checked against their TTL
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+#[cfg(feature = "job-webhooks")]
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum JobStatus {
     Pending,
@@ -19,6 +29,156 @@ pub struct Job {
     pub status: JobStatus,
     pub result: Option<String>,
     pub error: Option<String>,
+    #[serde(flatten)]
+    pub metadata: JobMetadata,
+    /// Unix seconds
+    pub submitted_at: u64,
+    /// Unix seconds when a worker picked the job up
+    #[serde(default)]
+    pub started_at: Option<u64>,
+    /// Unix seconds when the job completed or failed
+    pub finished_at: Option<u64>,
+    /// What running the job cost, once it has run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<JobUsage>,
+}
+
+/// Resources one job used, for finding heavy tenants and pathological payloads
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobUsage {
+    /// CPU time of the worker thread converting it; missing where the OS has no per-thread clock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time_ms: Option<f64>,
+    /// Input, parsed document and output held at once, estimated from their sizes
+    pub peak_bytes_estimate: u64,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+}
+
+/// Who submitted a job, how to find it again, and how long to keep it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobMetadata {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitter: Option<String>,
+    /// Remove the job this long after it finishes; kept until restart without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+    /// POSTed the outcome once the job finishes (feature `job-webhooks`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+    /// W3C trace the job belongs to, continued from the submitting request's `traceparent`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// Span of the submit request, the parent of the job's callback span
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+}
+
+impl JobMetadata {
+    pub fn trace(&self) -> Option<TraceContext> {
+        Some(TraceContext { trace_id: self.trace_id.clone()?, span_id: self.span_id.clone()? })
+    }
+
+    /// ` (trace <id>)` for log lines, so one grep follows a job from submit to callback
+    pub(crate) fn trace_note(&self) -> String {
+        self.trace_id.as_ref().map_or(String::new(), |trace_id| format!(" (trace {})", trace_id))
+    }
+}
+
+/// W3C trace context: `traceparent: 00-<32 hex trace id>-<16 hex span id>-<flags>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceContext {
+    pub trace_id: String,
+    pub span_id: String,
+}
+
+impl TraceContext {
+    /// A new trace, for a request that did not send one
+    pub fn new() -> Self {
+        Self { trace_id: random_hex(32), span_id: random_hex(16) }
+    }
+
+    /// A span of our own in the caller's trace; None for a malformed header
+    pub fn continue_from(traceparent: &str) -> Option<Self> {
+        let parts: Vec<&str> = traceparent.trim().split('-').collect();
+        let [version, trace_id, parent_id, flags] = parts[..] else {
+            return None;
+        };
+        let hex = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        let valid = hex(version, 2) && version != "ff" && hex(flags, 2)
+            && hex(trace_id, 32) && trace_id.bytes().any(|b| b != b'0')
+            && hex(parent_id, 16) && parent_id.bytes().any(|b| b != b'0');
+        valid.then(|| Self { trace_id: trace_id.to_string(), span_id: random_hex(16) })
+    }
+
+    /// The next span in the same trace
+    pub fn child(&self) -> Self {
+        Self { trace_id: self.trace_id.clone(), span_id: random_hex(16) }
+    }
+
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id)
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn random_hex(len: usize) -> String {
+    let mut hex = Uuid::new_v4().simple().to_string();
+    hex.truncate(len);
+    hex
+}
+
+/// Listing filters; every one given must match
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    pub labels: BTreeMap<String, String>,
+    pub submitter: Option<String>,
+    pub status: Option<JobStatus>,
+}
+
+impl JobFilter {
+    pub fn matches(&self, job: &Job) -> bool {
+        self.labels.iter().all(|(key, value)| job.metadata.labels.get(key) == Some(value))
+            && self.submitter.as_ref().is_none_or(|submitter| job.metadata.submitter.as_ref() == Some(submitter))
+            && self.status.as_ref().is_none_or(|status| &job.status == status)
+    }
+}
+
+impl JobStatus {
+    /// The lowercase name `GET /jobs/{id}/status` reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Processing => "processing",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pending" => Ok(JobStatus::Pending),
+            "processing" => Ok(JobStatus::Processing),
+            "completed" => Ok(JobStatus::Completed),
+            "failed" => Ok(JobStatus::Failed),
+            _ => Err(format!("Unknown job status '{}' (expected pending, processing, completed or failed)", s)),
+        }
+    }
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
 }
 
 pub type JobStore = Arc<Mutex<HashMap<String, Job>>>;
@@ -28,6 +188,10 @@ pub fn create_job_store() -> JobStore {
 }
 
 pub fn submit_job(store: JobStore, operation: String, data: String) -> String {
+    submit_job_with(store, operation, data, JobMetadata::default())
+}
+
+pub fn submit_job_with(store: JobStore, operation: String, data: String, metadata: JobMetadata) -> String {
     let job_id = Uuid::new_v4().to_string();
     
     let job = Job {
@@ -37,9 +201,14 @@ pub fn submit_job(store: JobStore, operation: String, data: String) -> String {
         status: JobStatus::Pending,
         result: None,
         error: None,
+        metadata,
+        submitted_at: now_secs(),
+        started_at: None,
+        finished_at: None,
+        usage: None,
     };
     
-    eprintln!("[JOB QUEUE] Submitted job: {}", job_id);
+    eprintln!("[JOB QUEUE] Submitted job: {}{}", job_id, job.metadata.trace_note());
     
     let mut jobs = store.lock().unwrap();
     jobs.insert(job_id.clone(), job);
@@ -57,23 +226,73 @@ pub fn get_job_result(store: JobStore, job_id: &str) -> Option<String> {
     jobs.get(job_id).and_then(|job| job.result.clone())
 }
 
+pub fn get_job(store: JobStore, job_id: &str) -> Option<Job> {
+    let jobs = store.lock().unwrap();
+    jobs.get(job_id).cloned()
+}
+
+/// The format an operation's result is in, e.g. for a download's file extension
+pub fn output_format(operation: &str) -> &'static str {
+    match operation {
+        "toon_to_json" => "json",
+        _ => "toon",
+    }
+}
+
 pub fn list_jobs(store: JobStore) -> Vec<Job> {
+    list_jobs_matching(store, &JobFilter::default())
+}
+
+/// Matching jobs, oldest first
+pub fn list_jobs_matching(store: JobStore, filter: &JobFilter) -> Vec<Job> {
     let jobs = store.lock().unwrap();
-    jobs.values().cloned().collect()
+    let mut matching: Vec<Job> = jobs.values().filter(|job| filter.matches(job)).cloned().collect();
+    matching.sort_by(|a, b| a.submitted_at.cmp(&b.submitted_at).then_with(|| a.id.cmp(&b.id)));
+    matching
 }
 
-pub fn start_workers(store: JobStore, worker_count: usize) {
+/// Remove finished jobs whose TTL had run out by `now` (Unix seconds); returns how many
+pub fn sweep_expired(store: &JobStore, now: u64) -> usize {
+    let mut jobs = store.lock().unwrap();
+    let before = jobs.len();
+    jobs.retain(|_, job| !is_expired(job, now));
+    before - jobs.len()
+}
+
+/// Finished, with a TTL that had run out by `now`
+pub fn is_expired(job: &Job, now: u64) -> bool {
+    match (job.finished_at, job.metadata.ttl_secs) {
+        (Some(finished), Some(ttl)) => finished.saturating_add(ttl) <= now,
+        _ => false,
+    }
+}
+
+/// Run jobs from `store` on `worker_count` threads, parsing their input within `guards`
+pub fn start_workers(store: JobStore, worker_count: usize, guards: ParserGuards) {
     eprintln!("[JOB QUEUE] Starting {} worker threads", worker_count);
     
     for worker_id in 0..worker_count {
         let store_clone = Arc::clone(&store);
         std::thread::spawn(move || {
-            worker_loop(store_clone, worker_id);
+            worker_loop(store_clone, worker_id, guards);
         });
     }
+
+    start_sweeper(store);
 }
 
-fn worker_loop(store: JobStore, worker_id: usize) {
+/// Remove expired jobs from `store` in the background
+pub fn start_sweeper(store: JobStore) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SWEEP_INTERVAL);
+        let removed = sweep_expired(&store, now_secs());
+        if removed > 0 {
+            eprintln!("[JOB QUEUE] Removed {} expired jobs", removed);
+        }
+    });
+}
+
+fn worker_loop(store: JobStore, worker_id: usize, guards: ParserGuards) {
     eprintln!("[WORKER {}] Started", worker_id);
     
     loop {
@@ -83,55 +302,36 @@ fn worker_loop(store: JobStore, worker_id: usize) {
             jobs.iter_mut()
                 .find(|(_, job)| job.status == JobStatus::Pending)
                 .map(|(id, job)| {
-                    job.status = JobStatus::Processing;
+                    start_job(job);
                     id.clone()
                 })
         };
         
         if let Some(job_id) = job_id {
-            eprintln!("[WORKER {}] Processing job: {}", worker_id, job_id);
-            
             // Get job details
-            let (operation, data) = {
+            let (operation, data, trace_note) = {
                 let jobs = store.lock().unwrap();
                 let job = jobs.get(&job_id).unwrap();
-                (job.operation.clone(), job.data.clone())
-            };
-            
-            // Process the job
-            let result = match operation.as_str() {
-                "json_to_toon" => {
-                    match crate::converter::json_to_toon(&data) {
-                        Ok(toon) => Ok(toon),
-                        Err(e) => Err(format!("Conversion error: {}", e)),
-                    }
-                }
-                "toon_to_json" => {
-                    match crate::converter::toon_to_json(&data) {
-                        Ok(json) => Ok(json),
-                        Err(e) => Err(format!("Conversion error: {}", e)),
-                    }
-                }
-                _ => Err(format!("Unknown operation: {}", operation)),
+                (job.operation.clone(), job.data.clone(), job.metadata.trace_note())
             };
+            eprintln!("[WORKER {}] Processing job: {}{}", worker_id, job_id, trace_note);
             
+            let outcome = run_operation(&operation, &data, guards);
+
             // Update job with result
-            {
+            let finished = {
                 let mut jobs = store.lock().unwrap();
-                if let Some(job) = jobs.get_mut(&job_id) {
-                    match result {
-                        Ok(output) => {
-                            job.status = JobStatus::Completed;
-                            job.result = Some(output);
-                            eprintln!("[WORKER {}] Job completed: {}", worker_id, job_id);
-                        }
-                        Err(error) => {
-                            job.status = JobStatus::Failed;
-                            job.error = Some(error.clone());
-                            eprintln!("[WORKER {}] Job failed: {} - {}", worker_id, job_id, error);
-                        }
-                    }
+                jobs.get_mut(&job_id).map(|job| {
+                    finish_job(job, outcome);
+                    job.clone()
+                })
+            };
+            if let Some(job) = finished {
+                match &job.error {
+                    None => eprintln!("[WORKER {}] Job completed: {}{}", worker_id, job_id, trace_note),
+                    Some(error) => eprintln!("[WORKER {}] Job failed: {} - {}{}", worker_id, job_id, error, trace_note),
                 }
+                notify(&job);
             }
         } else {
             // No pending jobs, sleep briefly
@@ -139,3 +339,247 @@ fn worker_loop(store: JobStore, worker_id: usize) {
         }
     }
 }
+
+/// The conversion a job asks for, and what it cost; input past `guards` fails the job
+pub fn run_operation(operation: &str, data: &str, guards: ParserGuards) -> (Result<String, String>, JobUsage) {
+    let cpu_started = thread_cpu_time();
+    let mut usage = JobUsage { input_bytes: data.len() as u64, ..Default::default() };
+    let result = convert(operation, data, guards, &mut usage);
+    usage.output_bytes = result.as_ref().map_or(0, |output| output.len() as u64);
+    usage.peak_bytes_estimate += usage.input_bytes + usage.output_bytes;
+    usage.cpu_time_ms = cpu_started
+        .zip(thread_cpu_time())
+        .map(|(started, ended)| ended.saturating_sub(started).as_secs_f64() * 1000.0);
+    (result, usage)
+}
+
+fn convert(operation: &str, data: &str, guards: ParserGuards, usage: &mut JobUsage) -> Result<String, String> {
+    let from = match operation {
+        "json_to_toon" => "json",
+        "toon_to_json" => "toon",
+        _ => return Err(format!("Unknown operation: {}", operation)),
+    };
+    let (value, _) = Converter::builder()
+        .guards(guards)
+        .build()
+        .parse(data, from)
+        .map_err(|e| format!("Conversion error: {}", e))?;
+    usage.peak_bytes_estimate = value_footprint(&value);
+    match operation {
+        "json_to_toon" => crate::converter::json_value_to_toon(&value),
+        _ => JsonCodec.emit(&value),
+    }
+    .map_err(|e| format!("Conversion error: {}", e))
+}
+
+// Heap held by a parsed document: a `Value` per node, plus string bytes and
+// an (String, Value) entry per object key. Ignores spare capacity.
+fn value_footprint(value: &Value) -> u64 {
+    let node = std::mem::size_of::<Value>() as u64;
+    let key = std::mem::size_of::<String>() as u64;
+    node + match value {
+        Value::String(s) => s.len() as u64,
+        Value::Array(items) => items.iter().map(value_footprint).sum(),
+        Value::Object(map) => map.iter().map(|(name, value)| key + name.len() as u64 + value_footprint(value)).sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(unix)]
+fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: clock_gettime only writes the timespec it is given
+    let read = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) } == 0;
+    read.then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+#[cfg(not(unix))]
+fn thread_cpu_time() -> Option<Duration> {
+    None
+}
+
+/// Mark a job claimed by a worker
+pub fn start_job(job: &mut Job) {
+    job.status = JobStatus::Processing;
+    job.started_at = Some(now_secs());
+}
+
+/// Record the outcome of `run_operation` on its job
+pub fn finish_job(job: &mut Job, (result, usage): (Result<String, String>, JobUsage)) {
+    job.finished_at = Some(now_secs());
+    job.usage = Some(usage);
+    match result {
+        Ok(output) => {
+            job.status = JobStatus::Completed;
+            job.result = Some(output);
+        }
+        Err(error) => {
+            job.status = JobStatus::Failed;
+            job.error = Some(error);
+        }
+    }
+}
+
+/// POST a finished job's outcome to its `callback_url`, as the next span of the job's trace
+pub fn notify(job: &Job) {
+    let Some(url) = &job.metadata.callback_url else {
+        return;
+    };
+    #[cfg(feature = "job-webhooks")]
+    {
+        let span = job.metadata.trace().map_or_else(TraceContext::new, |trace| trace.child());
+        let payload = serde_json::json!({
+            "job_id": job.id,
+            "status": job.status.as_str(),
+            "result": job.result,
+            "error": job.error,
+            "trace_id": span.trace_id,
+        });
+        let sent = reqwest::blocking::Client::new()
+            .post(url)
+            .header("traceparent", span.traceparent())
+            .timeout(CALLBACK_TIMEOUT)
+            .json(&payload)
+            .send()
+            .and_then(|response| response.error_for_status());
+        match sent {
+            Ok(response) => eprintln!("[JOB QUEUE] Callback for job {} to {}: {} (trace {}, span {})", job.id, url, response.status(), span.trace_id, span.span_id),
+            Err(e) => eprintln!("[JOB QUEUE] Callback for job {} to {} failed: {} (trace {}, span {})", job.id, url, e, span.trace_id, span.span_id),
+        }
+    }
+    #[cfg(not(feature = "job-webhooks"))]
+    eprintln!("[JOB QUEUE] Not calling back {} for job {}: built without the job-webhooks feature", url, job.id);
+}
+
+// Leases: work that must happen once per cluster, not once per node
+//
+// A lease is a named lock that lapses after a TTL unless its holder renews it.
+// `run_as_leader` takes the lease on every tick and runs its task only while it
+// holds it, so among nodes sharing a `LeaseStore` (the Redis `JobBroker`, or
+// `MemoryLeases` within one process) one node leads and the rest stand by.
+// When the leader stops renewing, another node takes over within two ticks.
+
+/// Expiring named locks shared by the nodes of a cluster
+pub trait LeaseStore: Send + Sync {
+    /// Take the `name` lease for `holder`, or renew it if `holder` already has it; false while someone else does
+    fn acquire(&self, name: &str, holder: &str, ttl: Duration) -> Result<bool, String>;
+    /// Give the lease up before it lapses, if `holder` still has it
+    fn release(&self, name: &str, holder: &str) -> Result<(), String>;
+}
+
+/// Leases for nodes in one process (and the default for a server without a Redis backend)
+#[derive(Default)]
+pub struct MemoryLeases {
+    leases: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl LeaseStore for MemoryLeases {
+    fn acquire(&self, name: &str, holder: &str, ttl: Duration) -> Result<bool, String> {
+        let mut leases = self.leases.lock().unwrap();
+        let now = Instant::now();
+        match leases.get(name) {
+            Some((current, expires)) if current != holder && *expires > now => Ok(false),
+            _ => {
+                leases.insert(name.to_string(), (holder.to_string(), now + ttl));
+                Ok(true)
+            }
+        }
+    }
+
+    fn release(&self, name: &str, holder: &str) -> Result<(), String> {
+        let mut leases = self.leases.lock().unwrap();
+        if leases.get(name).is_some_and(|(current, _)| current == holder) {
+            leases.remove(name);
+        }
+        Ok(())
+    }
+}
+
+/// Distinguishes this process from other nodes holding leases
+pub fn node_id() -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos());
+    format!("{}-{:x}", std::process::id(), nanos)
+}
+
+/// Run `task` every `interval` on whichever node holds the `name` lease, in the
+/// background, until the returned `Leadership` is stopped or dropped
+pub fn run_as_leader<F>(leases: Arc<dyn LeaseStore>, name: String, interval: Duration, mut task: F) -> Leadership
+where
+    F: FnMut() + Send + 'static,
+{
+    let holder = node_id();
+    // Outlives one tick, so the leader renews it before it lapses
+    let ttl = interval * 2;
+    let (stop, stopped) = mpsc::channel::<()>();
+    let thread = std::thread::spawn(move || {
+        let mut leading = false;
+        loop {
+            match leases.acquire(&name, &holder, ttl) {
+                Ok(true) => {
+                    if !leading {
+                        eprintln!("[JOB QUEUE] Leading {} as {}", name, holder);
+                        leading = true;
+                    }
+                    task();
+                }
+                Ok(false) => leading = false,
+                Err(e) => {
+                    eprintln!("[JOB QUEUE] Lease {} unavailable: {}", name, e);
+                    leading = false;
+                }
+            }
+            // A message or a dropped sender both mean stop
+            if stopped.recv_timeout(interval) != Err(mpsc::RecvTimeoutError::Timeout) {
+                break;
+            }
+        }
+        // Hand over now rather than leaving the others to wait out the TTL
+        if leading && let Err(e) = leases.release(&name, &holder) {
+            eprintln!("[JOB QUEUE] Failed to release lease {}: {}", name, e);
+        }
+    });
+    Leadership { stop: Some(stop), thread: Some(thread) }
+}
+
+/// The background thread of `run_as_leader`; dropping it stops the thread without waiting
+pub struct Leadership {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Leadership {
+    /// Stop running the task and wait until the lease is released
+    pub fn stop(mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Leadership {
+    fn drop(&mut self) {
+        self.stop.take();
+    }
+}
+
+/// A job submitted every `every`, once cluster-wide
+#[derive(Debug, Clone)]
+pub struct RecurringJob {
+    pub name: String,
+    pub every: Duration,
+    pub operation: String,
+    pub data: String,
+    pub metadata: JobMetadata,
+}
+
+/// Submit `job` to `store` on schedule while this node leads it; each run is labelled `schedule=<name>`
+pub fn schedule(store: JobStore, leases: Arc<dyn LeaseStore>, job: RecurringJob) -> Leadership {
+    let mut metadata = job.metadata;
+    metadata.labels.insert("schedule".to_string(), job.name.clone());
+    let lease = format!("schedule:{}", job.name);
+    run_as_leader(leases, lease, job.every, move || {
+        let id = submit_job_with(Arc::clone(&store), job.operation.clone(), job.data.clone(), metadata.clone());
+        eprintln!("[JOB QUEUE] Scheduled {} submitted job {}", job.name, id);
+    })
+}
@@ -0,0 +1,163 @@
+// JSON parsing with duplicate key detection
+//
+// serde_json silently keeps the last value for repeated keys. This visitor
+// builds the same `Value` tree but routes every object insert through a
+// `DuplicateKeyPolicy` and reports collisions as warnings.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+
+use crate::guards::ParserGuards;
+use crate::toon::duplicates::{insert_with_policy, DuplicateKeyPolicy};
+
+/// Parse JSON, applying `policy` to duplicate keys; returns the value and any warnings
+pub fn parse_json_with_policy(input: &str, policy: DuplicateKeyPolicy) -> Result<(Value, Vec<String>), String> {
+    parse_json_guarded(input, policy, ParserGuards::default())
+}
+
+/// `parse_json_with_policy`, stopping at the first value past `guards`' depth or entity limit
+pub fn parse_json_guarded(input: &str, policy: DuplicateKeyPolicy, guards: ParserGuards) -> Result<(Value, Vec<String>), String> {
+    let warnings = RefCell::new(Vec::new());
+    let values = Cell::new(0);
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+
+    let seed = ValueSeed { policy, guards, warnings: &warnings, values: &values, depth: 0, path: Path::Root };
+    let value = seed
+        .deserialize(&mut deserializer)
+        .map_err(|e| format!("Invalid JSON: {}", e))?;
+    deserializer.end()
+        .map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    Ok((value, warnings.into_inner()))
+}
+
+// Where a value sits, as links back to its parent; only spelled out ("$.users[0]")
+// for a warning, so large documents don't pay for a string per element
+enum Path<'p> {
+    Root,
+    Index(&'p Path<'p>, usize),
+    Key(&'p Path<'p>, &'p str),
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Path::Root => f.write_str("$"),
+            Path::Index(parent, index) => write!(f, "{}[{}]", parent, index),
+            Path::Key(parent, key) => write!(f, "{}.{}", parent, key),
+        }
+    }
+}
+
+impl Path<'_> {
+    /// The top-level key this value is under
+    fn entity(&self) -> Option<&str> {
+        match self {
+            Path::Root => None,
+            Path::Key(Path::Root, key) => Some(key),
+            Path::Index(parent, _) | Path::Key(parent, _) => parent.entity(),
+        }
+    }
+}
+
+struct ValueSeed<'a, 'p> {
+    policy: DuplicateKeyPolicy,
+    guards: ParserGuards,
+    warnings: &'a RefCell<Vec<String>>,
+    /// Values started so far, the root included
+    values: &'a Cell<usize>,
+    /// Nesting of the enclosing container; 0 for the root
+    depth: usize,
+    path: Path<'p>,
+}
+
+impl<'a> ValueSeed<'a, '_> {
+    // A value inside this one, which is a container at `depth`
+    fn child<'c>(&self, depth: usize, path: Path<'c>) -> ValueSeed<'a, 'c> {
+        ValueSeed { policy: self.policy, guards: self.guards, warnings: self.warnings, values: self.values, depth, path }
+    }
+
+    fn enter<E: de::Error>(&self) -> Result<usize, E> {
+        let depth = self.depth + 1;
+        self.guards.check_depth(depth, self.path.entity()).map_err(E::custom)?;
+        Ok(depth)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_, '_> {
+    type Value = Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        let values = self.values.get() + 1;
+        self.values.set(values);
+        self.guards.check_entities(values).map_err(de::Error::custom)?;
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ValueSeed<'_, '_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(v).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let depth = self.enter()?;
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(self.child(depth, Path::Index(&self.path, items.len())))? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let depth = self.enter()?;
+        let mut map = Map::new();
+        while let Some(key) = access.next_key::<String>()? {
+            let value = access.next_value_seed(self.child(depth, Path::Key(&self.path, &key)))?;
+            if !map.contains_key(&key) {
+                map.insert(key, value);
+                continue;
+            }
+            let mut warnings = self.warnings.borrow_mut();
+            insert_with_policy(&mut map, key, value, self.policy, &self.path.to_string(), &mut warnings)
+                .map_err(de::Error::custom)?;
+        }
+        Ok(Value::Object(map))
+    }
+}
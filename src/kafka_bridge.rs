@@ -0,0 +1,194 @@
+// `toonify kafka-bridge`: consume JSON messages, produce TOON documents
+//
+// Messages are collected until `batch_size` have arrived or `batch_timeout`
+// passes with a partial batch. A batch of one is converted as-is; larger
+// batches become a single `<entity>[n]{...}:` table. Offsets are committed
+// only after the TOON document has been acknowledged by the output topic, so
+// a crash replays the uncommitted batch rather than losing it. Messages that
+// are not valid JSON, and batches the converter rejects, are logged and
+// skipped so one bad message cannot stall the partition; their offsets are
+// committed with the next document that is produced.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::message::Message;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::{Offset, TopicPartitionList};
+use serde_json::{Map, Value};
+
+use toonify::converter::Converter;
+
+pub struct BridgeOptions {
+    pub brokers: String,
+    pub topic: String,
+    pub topic_out: String,
+    pub group_id: String,
+    pub batch_size: usize,
+    pub batch_timeout: Duration,
+    pub entity: String,
+}
+
+pub async fn run(options: BridgeOptions, converter: Converter) -> Result<(), Box<dyn Error>> {
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set("bootstrap.servers", &options.brokers)
+        .set("group.id", &options.group_id)
+        .set("enable.auto.commit", "false")
+        .set("auto.offset.reset", "earliest")
+        .create()
+        .map_err(|e| format!("Failed to create Kafka consumer: {}", e))?;
+    let producer: FutureProducer = ClientConfig::new()
+        .set("bootstrap.servers", &options.brokers)
+        .set("message.timeout.ms", "30000")
+        .create()
+        .map_err(|e| format!("Failed to create Kafka producer: {}", e))?;
+
+    consumer.subscribe(&[&options.topic])?;
+    eprintln!("[KAFKA] Bridging {} -> {} via {}", options.topic, options.topic_out, options.brokers);
+    eprintln!("[KAFKA] Batch size: {}, timeout: {}ms", options.batch_size, options.batch_timeout.as_millis());
+
+    let batch_size = options.batch_size.max(1);
+    let mut batch = Batch::default();
+
+    loop {
+        let received = if batch.values.is_empty() {
+            Some(consumer.recv().await)
+        } else {
+            tokio::time::timeout(options.batch_timeout, consumer.recv()).await.ok()
+        };
+
+        match received {
+            Some(Ok(message)) => {
+                if let Err(e) = batch.push(message.partition(), message.offset(), message.payload()) {
+                    eprintln!("[KAFKA] Skipping message at {}/{}: {}", message.partition(), message.offset(), e);
+                }
+                if batch.values.len() < batch_size {
+                    continue;
+                }
+            }
+            Some(Err(e)) => {
+                eprintln!("[KAFKA] Consumer error: {}", e);
+                continue;
+            }
+            // Timed out with a partial batch
+            None => {}
+        }
+
+        let count = batch.values.len();
+        match batch.take(&converter, &options.entity) {
+            Some(Ok((toon, pending))) => {
+                // A failed produce exits without committing, so the batch is consumed again
+                producer
+                    .send(FutureRecord::<(), _>::to(&options.topic_out).payload(&toon), Duration::from_secs(30))
+                    .await
+                    .map_err(|(e, _)| format!("Failed to produce to {}: {}", options.topic_out, e))?;
+                eprintln!("[KAFKA] ✓ Produced {} message(s) as {} bytes of TOON", count, toon.len());
+
+                let mut offsets = TopicPartitionList::new();
+                for (partition, offset) in pending {
+                    offsets.add_partition_offset(&options.topic, partition, Offset::Offset(offset + 1))?;
+                }
+                consumer.commit(&offsets, CommitMode::Async)?;
+            }
+            // Retrying would fail the same way; skip rather than wedge the partition
+            Some(Err(e)) => eprintln!("[KAFKA] Skipping batch of {} message(s): {}", count, e),
+            None => {}
+        }
+    }
+}
+
+// Highest consumed offset per partition since the last commit
+type Offsets = HashMap<i32, i64>;
+
+// Messages consumed since the last produced document
+#[derive(Default)]
+struct Batch {
+    values: Vec<Value>,
+    pending: Offsets,
+}
+
+impl Batch {
+    // A message that is not JSON is left out, but its offset is still pending
+    fn push(&mut self, partition: i32, offset: i64, payload: Option<&[u8]>) -> Result<(), String> {
+        self.pending.insert(partition, offset);
+        let payload = payload.ok_or_else(|| "empty message".to_string())?;
+        let value = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+        self.values.push(value);
+        Ok(())
+    }
+
+    // The TOON document for the collected messages and the offsets to commit
+    // once it is produced, or `None` with nothing collected. A rejected batch
+    // is dropped, and its offsets wait for the next document.
+    fn take(&mut self, converter: &Converter, entity: &str) -> Option<Result<(String, Offsets), String>> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let toon = convert_batch(converter, entity, std::mem::take(&mut self.values)).map_err(|e| e.to_string());
+        Some(toon.map(|toon| (toon, std::mem::take(&mut self.pending))))
+    }
+}
+
+fn convert_batch(converter: &Converter, entity: &str, mut batch: Vec<Value>) -> Result<String, Box<dyn Error>> {
+    let document = if batch.len() == 1 && batch[0].is_object() {
+        batch.remove(0)
+    } else {
+        let mut document = Map::new();
+        document.insert(entity.to_string(), Value::Array(batch));
+        Value::Object(document)
+    };
+    // Through the full pipeline so hooks, flattening and secret checks apply
+    let toon = converter.convert(&serde_json::to_string(&document)?, "json", "toon")?;
+    Ok(toon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_message_passes_through() {
+        let converter = Converter::default();
+        let mut batch = Batch::default();
+        batch.push(0, 7, Some(br#"{"order":{"id":42,"total":9.5}}"#.as_slice())).unwrap();
+
+        let (toon, pending) = batch.take(&converter, "events").unwrap().unwrap();
+        assert_eq!(toon, converter.convert(r#"{"order":{"id":42,"total":9.5}}"#, "json", "toon").unwrap());
+        assert!(!toon.contains("events"));
+        assert_eq!(pending, HashMap::from([(0, 7)]));
+        assert!(batch.take(&converter, "events").is_none());
+    }
+
+    #[test]
+    fn test_messages_collapse_into_one_table() {
+        let converter = Converter::default();
+        let mut batch = Batch::default();
+        batch.push(0, 1, Some(br#"{"id":1,"name":"Alice"}"#.as_slice())).unwrap();
+        batch.push(1, 4, Some(br#"{"id":2,"name":"Bob"}"#.as_slice())).unwrap();
+        batch.push(0, 2, Some(br#"{"id":3,"name":"Carol"}"#.as_slice())).unwrap();
+
+        let (toon, pending) = batch.take(&converter, "events").unwrap().unwrap();
+        assert_eq!(toon.trim_end(), "events[3]{id,name}:\n1,Alice\n2,Bob\n3,Carol");
+        assert_eq!(pending, HashMap::from([(0, 2), (1, 4)]));
+    }
+
+    #[test]
+    fn test_rejected_batch_is_skipped_without_committing() {
+        let rejecting = Converter::builder().with_pre_hook(|_| Err("rejected".to_string())).build();
+        let mut batch = Batch::default();
+        batch.push(0, 3, Some(br#"{"id":1}"#.as_slice())).unwrap();
+        assert!(batch.push(0, 4, Some(b"not json".as_slice())).is_err());
+
+        assert!(batch.take(&rejecting, "events").unwrap().is_err());
+        assert!(batch.values.is_empty(), "the rejected messages are dropped");
+        assert_eq!(batch.pending, HashMap::from([(0, 4)]), "nothing is committed for them");
+
+        // The next document produced commits past them
+        batch.push(0, 5, Some(br#"{"id":2}"#.as_slice())).unwrap();
+        let (_, pending) = batch.take(&Converter::default(), "events").unwrap().unwrap();
+        assert_eq!(pending, HashMap::from([(0, 5)]));
+    }
+}
@@ -1,5 +1,102 @@
-mod toon;
+pub use toonify_core::toon;
 pub mod converter;
+pub mod chunk;
+pub mod client_gen;
+pub mod conversion_cache;
+pub mod corpus;
+pub mod export;
+pub mod flatten;
+pub mod generate;
+pub use toonify_core::guards;
+pub mod highlight;
+pub mod locale;
+mod json;
+pub mod merge;
+pub mod profile;
+pub mod rows;
+pub mod sanitize;
+pub mod secrets;
+pub mod selftest;
+pub mod summarize;
+pub mod tokens;
+pub mod type_rules;
+
+#[cfg(feature = "scripting")]
+pub mod scripting;
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+#[cfg(feature = "signing")]
+pub mod signing;
+
+#[cfg(feature = "persistent-cache")]
+pub mod sled_cache;
+
+#[cfg(feature = "cache-encryption")]
+pub mod cache_crypto;
+
+#[cfg(feature = "pseudonymize")]
+pub mod pseudonymize;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "build-helper")]
+pub mod build;
+
+#[cfg(any(feature = "protobuf", feature = "avro"))]
+pub mod binary;
+
+// Server building blocks shared with the binary: REST router, gRPC service,
+// conversion pool, audit log and job queue, plus TOON extractors for embedders
+#[cfg(feature = "server")]
+pub mod server;
+
+#[cfg(feature = "grpc-gateway")]
+pub mod grpc_gateway;
+
+#[cfg(feature = "server")]
+pub mod audit_log;
+
+#[cfg(feature = "server")]
+pub mod extract;
+
+#[cfg(feature = "server")]
+pub mod conversion_pool;
+
+#[cfg(feature = "job-queue")]
+pub mod job_queue;
+
+#[cfg(feature = "cluster-cache")]
+pub mod cache_invalidation;
+#[cfg(feature = "distributed-jobs")]
+pub mod job_broker;
+
+#[cfg(feature = "server")]
+pub mod circuit_breaker;
+
+#[cfg(all(feature = "server", feature = "cache"))]
+pub mod cache_snapshot;
+
+#[cfg(all(feature = "server", feature = "validation"))]
+pub mod schema_registry;
+
+// Pre-generated protobuf code (no need for protoc/cmake at build time)
+#[cfg(feature = "server")]
+mod proto;
+#[cfg(feature = "server")]
+pub use proto::generated as pb;
+
+#[cfg(feature = "macros")]
+pub use toonify_macros::toon_include;
+
+// Paths used by code that `toon_include!` expands to
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+}
 
 // WASM bindings (only compiled for wasm32 target)
 #[cfg(target_arch = "wasm32")]
@@ -13,6 +110,8 @@ use converter::{json_to_toon as json_to_toon_internal, toon_to_json as toon_to_j
 mod uniffi_bindings {
     use super::*;
     use std::sync::{Arc, Mutex};
+
+    use crate::conversion_cache::ConversionCache;
     
     /// Error type for TOON conversion operations
     #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -46,6 +145,7 @@ mod uniffi_bindings {
         moka_cache: Option<Arc<moka::sync::Cache<String, String>>>,
         #[cfg(feature = "persistent-cache")]
         sled_db: Option<Arc<Mutex<sled::Db>>>,
+        custom: Option<Arc<dyn ConversionCache>>,
     }
 
     #[uniffi::export]
@@ -83,105 +183,18 @@ mod uniffi_bindings {
                 moka_cache,
                 #[cfg(feature = "persistent-cache")]
                 sled_db,
+                custom: None,
             })
         }
 
         /// Convert JSON to TOON with caching
         pub fn json_to_toon(&self, json_data: String) -> Result<String, ToonError> {
-            let cache_key = format!("j2t:{}", json_data);
-            
-            // Check Moka cache (hot path)
-            #[cfg(feature = "cache")]
-            if let Some(ref cache) = self.moka_cache {
-                if let Some(cached) = cache.get(&cache_key) {
-                    return Ok(cached);
-                }
-            }
-
-            // Check Sled cache (cold path)
-            #[cfg(feature = "persistent-cache")]
-            if let Some(ref db) = self.sled_db {
-                if let Ok(db_lock) = db.lock() {
-                    if let Ok(Some(cached)) = db_lock.get(cache_key.as_bytes()) {
-                        if let Ok(result) = String::from_utf8(cached.to_vec()) {
-                            // Warm up Moka cache
-                            #[cfg(feature = "cache")]
-                            if let Some(ref cache) = self.moka_cache {
-                                cache.insert(cache_key.clone(), result.clone());
-                            }
-                            return Ok(result);
-                        }
-                    }
-                }
-            }
-
-            // Cache miss - perform conversion
-            let result = json_to_toon_internal(&json_data).map_err(ToonError::from)?;
-
-            // Store in both caches
-            #[cfg(feature = "cache")]
-            if let Some(ref cache) = self.moka_cache {
-                cache.insert(cache_key.clone(), result.clone());
-            }
-
-            #[cfg(feature = "persistent-cache")]
-            if let Some(ref db) = self.sled_db {
-                if let Ok(db_lock) = db.lock() {
-                    let _ = db_lock.insert(cache_key.as_bytes(), result.as_bytes());
-                    let _ = db_lock.flush();
-                }
-            }
-
-            Ok(result)
+            self.read_through(format!("j2t:{}", json_data), || json_to_toon_internal(&json_data))
         }
 
         /// Convert TOON to JSON with caching
         pub fn toon_to_json(&self, toon_data: String) -> Result<String, ToonError> {
-            let cache_key = format!("t2j:{}", toon_data);
-            
-            // Check Moka cache (hot path)
-            #[cfg(feature = "cache")]
-            if let Some(ref cache) = self.moka_cache {
-                if let Some(cached) = cache.get(&cache_key) {
-                    return Ok(cached);
-                }
-            }
-
-            // Check Sled cache (cold path)
-            #[cfg(feature = "persistent-cache")]
-            if let Some(ref db) = self.sled_db {
-                if let Ok(db_lock) = db.lock() {
-                    if let Ok(Some(cached)) = db_lock.get(cache_key.as_bytes()) {
-                        if let Ok(result) = String::from_utf8(cached.to_vec()) {
-                            // Warm up Moka cache
-                            #[cfg(feature = "cache")]
-                            if let Some(ref cache) = self.moka_cache {
-                                cache.insert(cache_key.clone(), result.clone());
-                            }
-                            return Ok(result);
-                        }
-                    }
-                }
-            }
-
-            // Cache miss - perform conversion
-            let result = toon_to_json_internal(&toon_data).map_err(ToonError::from)?;
-
-            // Store in both caches
-            #[cfg(feature = "cache")]
-            if let Some(ref cache) = self.moka_cache {
-                cache.insert(cache_key.clone(), result.clone());
-            }
-
-            #[cfg(feature = "persistent-cache")]
-            if let Some(ref db) = self.sled_db {
-                if let Ok(db_lock) = db.lock() {
-                    let _ = db_lock.insert(cache_key.as_bytes(), result.as_bytes());
-                    let _ = db_lock.flush();
-                }
-            }
-
-            Ok(result)
+            self.read_through(format!("t2j:{}", toon_data), || toon_to_json_internal(&toon_data))
         }
 
         /// Clear all caches
@@ -192,11 +205,15 @@ mod uniffi_bindings {
             }
 
             #[cfg(feature = "persistent-cache")]
-            if let Some(ref db) = self.sled_db {
-                if let Ok(db_lock) = db.lock() {
-                    let _ = db_lock.clear();
-                    let _ = db_lock.flush();
-                }
+            if let Some(ref db) = self.sled_db
+                && let Ok(db_lock) = db.lock()
+            {
+                let _ = db_lock.clear();
+                let _ = db_lock.flush();
+            }
+
+            if let Some(ref cache) = self.custom {
+                let _ = cache.clear();
             }
         }
 
@@ -235,9 +252,77 @@ mod uniffi_bindings {
                 stats.push_str("  Sled: disabled\n");
             }
 
+            if self.custom.is_some() {
+                stats.push_str("  Custom cache: enabled\n");
+            }
+
             stats
         }
     }
+
+    impl CachedConverter {
+        /// A converter that reads through the embedder's own cache instead of Moka/Sled
+        pub fn with_cache(cache: Arc<dyn ConversionCache>) -> Arc<Self> {
+            Arc::new(Self {
+                #[cfg(feature = "cache")]
+                moka_cache: None,
+                #[cfg(feature = "persistent-cache")]
+                sled_db: None,
+                custom: Some(cache),
+            })
+        }
+
+        // Moka, then Sled, then the custom cache; a hit warms Moka, a miss converts and stores everywhere
+        fn read_through(&self, cache_key: String, convert: impl FnOnce() -> Result<String, String>) -> Result<String, ToonError> {
+            // Check Moka cache (hot path)
+            #[cfg(feature = "cache")]
+            if let Some(ref cache) = self.moka_cache
+                && let Some(cached) = cache.get(&cache_key)
+            {
+                return Ok(cached);
+            }
+
+            // Check Sled cache (cold path), then the embedder's cache
+            #[cfg(feature = "persistent-cache")]
+            let cached = self.sled_db.as_ref().and_then(|db| {
+                let cached = db.lock().ok()?.get(cache_key.as_bytes()).ok()??;
+                String::from_utf8(cached.to_vec()).ok()
+            });
+            #[cfg(not(feature = "persistent-cache"))]
+            let cached = None;
+            if let Some(result) = cached.or_else(|| self.custom.as_ref().and_then(|cache| cache.get(&cache_key).ok().flatten())) {
+                // Warm up Moka cache
+                #[cfg(feature = "cache")]
+                if let Some(ref cache) = self.moka_cache {
+                    cache.insert(cache_key.clone(), result.clone());
+                }
+                return Ok(result);
+            }
+
+            // Cache miss - perform conversion
+            let result = convert().map_err(ToonError::from)?;
+
+            // Store in every cache
+            #[cfg(feature = "cache")]
+            if let Some(ref cache) = self.moka_cache {
+                cache.insert(cache_key.clone(), result.clone());
+            }
+
+            #[cfg(feature = "persistent-cache")]
+            if let Some(ref db) = self.sled_db
+                && let Ok(db_lock) = db.lock()
+            {
+                let _ = db_lock.insert(cache_key.as_bytes(), result.as_bytes());
+                let _ = db_lock.flush();
+            }
+
+            if let Some(ref cache) = self.custom {
+                let _ = cache.put(&cache_key, &result);
+            }
+
+            Ok(result)
+        }
+    }
 }
 
 // Re-export for non-WASM targets
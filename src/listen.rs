@@ -0,0 +1,117 @@
+// `toonify listen`: capture webhook deliveries as TOON files
+//
+// Every `POST /` (or `POST /{id}`) with a JSON body is converted and written
+// to `<output-dir>/<unix-millis>-<id>.toon`. The id comes from the path, then
+// the `--id-header` request header (defaulting to X-Request-Id), then a
+// per-process sequence number, and is reduced to `[A-Za-z0-9_-]` so a sender
+// can't choose where the file lands. Files are written atomically, so a
+// consumer watching the directory never sees a partial document.
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use axum::{Json, Router};
+
+use toonify::converter::Converter;
+
+use crate::atomic_write::write_atomic;
+
+pub struct ListenOptions {
+    pub addr: SocketAddr,
+    pub output_dir: PathBuf,
+    pub id_header: String,
+}
+
+#[derive(Clone)]
+struct ListenState {
+    converter: Converter,
+    output_dir: Arc<PathBuf>,
+    id_header: Arc<String>,
+    sequence: Arc<AtomicU64>,
+}
+
+pub async fn run(options: ListenOptions, converter: Converter) -> Result<(), Box<dyn Error>> {
+    std::fs::create_dir_all(&options.output_dir)
+        .map_err(|e| format!("Failed to create {:?}: {}", options.output_dir, e))?;
+
+    let state = ListenState {
+        converter,
+        output_dir: Arc::new(options.output_dir.clone()),
+        id_header: Arc::new(options.id_header.to_ascii_lowercase()),
+        sequence: Arc::new(AtomicU64::new(0)),
+    };
+    let app = Router::new()
+        .route("/", post(capture_handler))
+        .route("/{id}", post(capture_with_id_handler))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(options.addr).await?;
+    eprintln!("[LISTEN] Accepting JSON POSTs on {}", options.addr);
+    eprintln!("[LISTEN] Writing TOON to {:?}", options.output_dir);
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+    Ok(())
+}
+
+async fn capture_handler(State(state): State<ListenState>, headers: HeaderMap, body: String) -> impl IntoResponse {
+    capture(state, None, headers, body).await
+}
+
+async fn capture_with_id_handler(
+    State(state): State<ListenState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> impl IntoResponse {
+    capture(state, Some(id), headers, body).await
+}
+
+async fn capture(state: ListenState, id: Option<String>, headers: HeaderMap, body: String) -> (StatusCode, Json<serde_json::Value>) {
+    let id = id
+        .or_else(|| headers.get(state.id_header.as_str()).and_then(|value| value.to_str().ok()).map(str::to_string))
+        .map(|id| sanitize_id(&id))
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| format!("{:06}", state.sequence.fetch_add(1, Ordering::Relaxed)));
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis());
+    let path = state.output_dir.join(format!("{}-{}.toon", millis, id));
+
+    let converter = state.converter.clone();
+    let target = path.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let toon = converter.convert(&body, "json", "toon").map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+        write_atomic(&target, toon.as_bytes(), false, None)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write {:?}: {}", target, e)))
+    })
+    .await
+    .unwrap_or_else(|e| Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Conversion task failed: {}", e))));
+
+    match result {
+        Ok(()) => {
+            eprintln!("[LISTEN] ✓ {:?}", path);
+            (StatusCode::CREATED, Json(serde_json::json!({ "file": path.display().to_string() })))
+        }
+        Err((status, error)) => {
+            eprintln!("[LISTEN] ✗ Rejected delivery {}: {}", id, error);
+            (status, Json(serde_json::json!({ "error": error })))
+        }
+    }
+}
+
+fn sanitize_id(id: &str) -> String {
+    id.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .take(128)
+        .collect()
+}
@@ -0,0 +1,187 @@
+// Locale-aware numbers and dates (`--locale de`)
+//
+// European sources write `1.234,56` and `01.05.2024`; read without a locale
+// these stay strings (or, worse, `1.234` reads as one point two three four).
+// With a locale, strings that use its separators become numbers, and dates
+// in its day/month order become ISO 8601 (`2024-05-01`, or
+// `2024-05-01T10:30:00` with a time). A string is only converted when it
+// needs the locale to be read: plain digits, and numbers or dates that don't
+// fit the locale's pattern, are left alone. CSV cells are read this way
+// before sniffing; for other formats strings are converted after parsing,
+// at any depth. Fields with a `--types` rule are skipped, since the rule
+// decides what they are. Dates need a four-digit year; `01/05/24` is too
+// ambiguous to guess at.
+
+use std::str::FromStr;
+
+use serde_json::{Map, Number, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// 31/12/2024
+    DayMonthYear,
+    /// 12/31/2024
+    MonthDayYear,
+    /// 2024/12/31
+    YearMonthDay,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale {
+    pub tag: String,
+    pub decimal: char,
+    /// Thousands separators; French and Nordic text uses (non-breaking) spaces
+    pub grouping: &'static [char],
+    pub dates: DateOrder,
+}
+
+const POINT_GROUPING: &[char] = &['.'];
+const COMMA_GROUPING: &[char] = &[','];
+const SPACE_GROUPING: &[char] = &[' ', '\u{a0}', '\u{202f}'];
+const APOSTROPHE_GROUPING: &[char] = &['\'', '\u{2019}'];
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.trim().replace('_', "-");
+        let lower = tag.to_ascii_lowercase();
+        let (language, region) = lower.split_once('-').unwrap_or((lower.as_str(), ""));
+        let (decimal, grouping, dates) = match (language, region) {
+            ("en", "" | "us" | "ca" | "ph") => ('.', COMMA_GROUPING, DateOrder::MonthDayYear),
+            ("en", _) => ('.', COMMA_GROUPING, DateOrder::DayMonthYear),
+            ("de" | "it" | "fr", "ch") => ('.', APOSTROPHE_GROUPING, DateOrder::DayMonthYear),
+            ("de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr", _) => (',', POINT_GROUPING, DateOrder::DayMonthYear),
+            ("sv" | "hu" | "lt", _) => (',', SPACE_GROUPING, DateOrder::YearMonthDay),
+            ("fr" | "fi" | "nb" | "nn" | "no" | "cs" | "sk" | "pl" | "ru" | "uk" | "bg" | "lv" | "et", _) => (',', SPACE_GROUPING, DateOrder::DayMonthYear),
+            ("ja" | "zh" | "ko", _) => ('.', COMMA_GROUPING, DateOrder::YearMonthDay),
+            _ => return Err(format!("Unknown locale '{}' (expected a tag such as de, fr, en-GB, or en-US)", s)),
+        };
+        Ok(Locale { tag, decimal, grouping, dates })
+    }
+}
+
+impl Locale {
+    /// `text` as a number, if it is written with this locale's separators
+    pub fn parse_number(&self, text: &str) -> Option<Value> {
+        let text = text.trim();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (whole, fraction) = match digits.split_once(self.decimal) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+        if fraction.is_some_and(|fraction| fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit())) {
+            return None;
+        }
+
+        let groups: Vec<&str> = whole.split(self.grouping).collect();
+        let grouped = groups.len() > 1;
+        let valid_groups = groups.iter().enumerate().all(|(i, group)| {
+            group.bytes().all(|b| b.is_ascii_digit()) && match i {
+                0 => (1..=3).contains(&group.len()) || (!grouped && !group.is_empty()),
+                _ => group.len() == 3,
+            }
+        });
+        // Nothing locale-specific about plain digits or a bare '.' decimal point
+        if !valid_groups || (!grouped && (fraction.is_none() || self.decimal == '.')) {
+            return None;
+        }
+
+        let sign = if negative { "-" } else { "" };
+        let whole: String = groups.concat();
+        match fraction {
+            Some(fraction) => format!("{}{}.{}", sign, whole, fraction).parse::<f64>().ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number),
+            None => format!("{}{}", sign, whole).parse::<i64>().ok().map(Value::from),
+        }
+    }
+
+    /// `text` as an ISO 8601 date (and time), if it is a date in this locale's order
+    pub fn parse_date(&self, text: &str) -> Option<String> {
+        let text = text.trim();
+        let (date, time) = match text.split_once([' ', 'T']) {
+            Some((date, time)) => (date, Some(time.trim())),
+            None => (text, None),
+        };
+        let separator = date.chars().find(|c| matches!(c, '/' | '.' | '-'))?;
+        let parts: Vec<&str> = date.split(separator).collect();
+        if parts.len() != 3 || parts.iter().any(|part| part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit())) {
+            return None;
+        }
+        let (year, month, day) = match self.dates {
+            DateOrder::DayMonthYear => (parts[2], parts[1], parts[0]),
+            DateOrder::MonthDayYear => (parts[2], parts[0], parts[1]),
+            DateOrder::YearMonthDay => (parts[0], parts[1], parts[2]),
+        };
+        // Already ISO 8601; nothing for the locale to do
+        if separator == '-' && self.dates == DateOrder::YearMonthDay {
+            return None;
+        }
+        if year.len() != 4 || month.len() > 2 || day.len() > 2 {
+            return None;
+        }
+        let (year, month, day): (i64, u32, u32) = (year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        let date = format!("{:04}-{:02}-{:02}", year, month, day);
+        let Some(time) = time else { return Some(date) };
+        let fields: Vec<&str> = time.split(':').collect();
+        let numbers: Option<Vec<u32>> = fields.iter()
+            .map(|field| if field.len() == 2 { field.parse().ok() } else { None })
+            .collect();
+        match numbers?.as_slice() {
+            [h, m] if *h < 24 && *m < 60 => Some(format!("{}T{:02}:{:02}:00", date, h, m)),
+            [h, m, s] if *h < 24 && *m < 60 && *s < 60 => Some(format!("{}T{:02}:{:02}:{:02}", date, h, m, s)),
+            _ => None,
+        }
+    }
+
+    /// A cell or string value read with this locale, or `None` to leave it as it is
+    pub fn parse_text(&self, text: &str) -> Option<Value> {
+        self.parse_number(text).or_else(|| self.parse_date(text).map(Value::String))
+    }
+
+    /// Convert locale-formatted strings anywhere in `value`, except under fields `skip` names
+    pub fn apply(&self, value: Value, skip: &dyn Fn(&str, &str) -> bool) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(map.into_iter()
+                .map(|(entity, value)| {
+                    let value = self.walk(value, &entity, skip);
+                    (entity, value)
+                })
+                .collect()),
+            other => self.walk(other, "", skip),
+        }
+    }
+
+    fn walk(&self, value: Value, entity: &str, skip: &dyn Fn(&str, &str) -> bool) -> Value {
+        match value {
+            Value::String(s) => self.parse_text(&s).unwrap_or(Value::String(s)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|item| self.walk(item, entity, skip)).collect()),
+            Value::Object(fields) => {
+                let mut converted = Map::new();
+                for (field, value) in fields {
+                    let value = if skip(entity, &field) { value } else { self.walk(value, entity, skip) };
+                    converted.insert(field, value);
+                }
+                Value::Object(converted)
+            }
+            other => other,
+        }
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
@@ -0,0 +1,235 @@
+// `toonify lsp`: a Language Server Protocol server for TOON over stdio
+//
+// Supports full-document sync and publishes diagnostics on every change:
+// lines the lossy parser had to skip (errors) and `[n]` counts that don't
+// match the rows present (warnings). Hover shows the JSON a row decodes to,
+// formatting runs `format_toon`, and document symbols list the entities so
+// editors can jump between them. Messages are handled one at a time; TOON
+// documents are small enough that nothing needs to run in the background.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value};
+
+use toonify::toon::{format_toon, outline, parse_toon_lossy, FormatOptions, OutlineEntity};
+
+// LSP constants used below
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SYMBOL_FIELD: u8 = 8;
+const SYMBOL_OBJECT: u8 = 19;
+const SYMBOL_ARRAY: u8 = 18;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+pub fn run() -> Result<(), Box<dyn Error>> {
+    eprintln!("[LSP] TOON language server on stdio");
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut output = io::stdout().lock();
+    let mut documents: HashMap<String, String> = HashMap::new();
+    let mut shutdown = false;
+
+    while let Some(message) = read_message(&mut input)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+
+        match method {
+            "exit" => break,
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default().to_string();
+                publish_diagnostics(&mut output, &uri, &text)?;
+                documents.insert(uri, text);
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+                // Full sync: the last change holds the whole document
+                if let Some(text) = params["contentChanges"].as_array().and_then(|changes| changes.last()).and_then(|change| change["text"].as_str()) {
+                    publish_diagnostics(&mut output, &uri, text)?;
+                    documents.insert(uri, text.to_string());
+                }
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                documents.remove(uri);
+                send(&mut output, &notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] })))?;
+            }
+            _ => {
+                // Remaining notifications (initialized, didSave, $/...) need no reply
+                let Some(id) = id else { continue };
+                let document = params["textDocument"]["uri"].as_str().and_then(|uri| documents.get(uri)).map(String::as_str);
+                let result = match method {
+                    "initialize" => Ok(capabilities()),
+                    "shutdown" => {
+                        shutdown = true;
+                        Ok(Value::Null)
+                    }
+                    "textDocument/hover" => Ok(document.map_or(Value::Null, |text| hover(text, params))),
+                    "textDocument/formatting" => document.map_or(Ok(Value::Null), formatting),
+                    "textDocument/documentSymbol" => Ok(document.map_or(Value::Null, symbols)),
+                    _ => Err((METHOD_NOT_FOUND, format!("Unsupported method: {}", method))),
+                };
+                let response = match result {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+                };
+                send(&mut output, &response)?;
+            }
+        }
+    }
+
+    if !shutdown {
+        eprintln!("[LSP] Exiting without shutdown request");
+    }
+    Ok(())
+}
+
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1,
+            "hoverProvider": true,
+            "documentFormattingProvider": true,
+            "documentSymbolProvider": true,
+        },
+        "serverInfo": { "name": "toonify", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+// Base protocol: `Content-Length: n\r\n\r\n` followed by n bytes of JSON
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>, Box<dyn Error>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':').filter(|(name, _)| name.eq_ignore_ascii_case("content-length")) {
+            length = Some(value.trim().parse::<usize>().map_err(|e| format!("Bad {} header: {}", name, e))?);
+        }
+    }
+    let length = length.ok_or("Message without Content-Length header")?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn send(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn publish_diagnostics(output: &mut impl Write, uri: &str, text: &str) -> io::Result<()> {
+    send(output, &notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics(text) })))
+}
+
+fn diagnostics(text: &str) -> Vec<Value> {
+    let lines: Vec<&str> = text.lines().collect();
+    let (_, errors) = parse_toon_lossy(text);
+    let mut diagnostics: Vec<Value> = errors
+        .iter()
+        .map(|error| diagnostic(line_range(&lines, error.line), SEVERITY_ERROR, &error.message))
+        .collect();
+
+    for entity in outline(text) {
+        if let Some(declared) = entity.declared_len.filter(|declared| *declared != entity.len()) {
+            let message = format!("{} declares {} rows but has {}", entity.name, declared, entity.len());
+            diagnostics.push(diagnostic(line_range(&lines, entity.line), SEVERITY_WARNING, &message));
+        }
+    }
+    diagnostics
+}
+
+fn diagnostic(range: Value, severity: u8, message: &str) -> Value {
+    json!({ "range": range, "severity": severity, "source": "toonify", "message": message })
+}
+
+// Ranges in LSP are 0-based and measured in UTF-16 code units
+fn line_range(lines: &[&str], line: usize) -> Value {
+    let end = lines.get(line - 1).map_or(0, |text| text.encode_utf16().count());
+    json!({ "start": { "line": line - 1, "character": 0 }, "end": { "line": line - 1, "character": end } })
+}
+
+fn entity_range(lines: &[&str], entity: &OutlineEntity) -> Value {
+    let start = line_range(lines, entity.line);
+    let end = line_range(lines, entity.end_line());
+    json!({ "start": start["start"], "end": end["end"] })
+}
+
+fn hover(text: &str, params: &Value) -> Value {
+    let Some(line) = params["position"]["line"].as_u64().map(|line| line as usize + 1) else {
+        return Value::Null;
+    };
+    let lines: Vec<&str> = text.lines().collect();
+
+    for entity in outline(text) {
+        if let Some(row) = entity.rows.iter().find(|row| row.line == line) {
+            return hover_json(&lines, line, &entity.name, &row.value);
+        }
+        if entity.line == line {
+            let value = match &entity.scalar {
+                Some(scalar) => scalar.clone(),
+                None if entity.is_array() => json!(format!("{} rows", entity.len())),
+                None => entity.rows.first().map_or(Value::Null, |row| row.value.clone()),
+            };
+            return hover_json(&lines, line, &entity.name, &value);
+        }
+    }
+    Value::Null
+}
+
+fn hover_json(lines: &[&str], line: usize, name: &str, value: &Value) -> Value {
+    let json = serde_json::to_string_pretty(value).unwrap_or_default();
+    json!({
+        "contents": { "kind": "markdown", "value": format!("**{}**\n```json\n{}\n```", name, json) },
+        "range": line_range(lines, line),
+    })
+}
+
+fn formatting(text: &str) -> Result<Value, (i64, String)> {
+    let formatted = format_toon(text, &FormatOptions::default()).map_err(|e| (INVALID_PARAMS, e))?;
+    if formatted == text {
+        return Ok(json!([]));
+    }
+    // Replace the whole document; (lines, 0) is past the last character
+    let end = text.lines().count() + 1;
+    Ok(json!([{
+        "range": { "start": { "line": 0, "character": 0 }, "end": { "line": end, "character": 0 } },
+        "newText": formatted,
+    }]))
+}
+
+fn symbols(text: &str) -> Value {
+    let lines: Vec<&str> = text.lines().collect();
+    let symbols: Vec<Value> = outline(text)
+        .iter()
+        .map(|entity| {
+            let (kind, detail) = match &entity.scalar {
+                Some(scalar) => (SYMBOL_FIELD, scalar.to_string()),
+                None if entity.is_array() => (SYMBOL_ARRAY, format!("{} rows", entity.len())),
+                None => (SYMBOL_OBJECT, entity.columns.join(", ")),
+            };
+            json!({
+                "name": entity.name,
+                "detail": detail,
+                "kind": kind,
+                "range": entity_range(&lines, entity),
+                "selectionRange": line_range(&lines, entity.line),
+            })
+        })
+        .collect();
+    Value::Array(symbols)
+}
@@ -1,60 +1,90 @@
-mod toon;
-mod converter;
-
-#[cfg(feature = "job-queue")]
-mod job_queue;
-
-use axum::{
-    routing::{post, get},
-    Router,
-    Json,
-    http::StatusCode,
-    response::IntoResponse,
-};
-use clap::{Parser, Subcommand};
+use toonify::converter;
+use toonify::guards::ParserGuards;
+use toonify::locale::Locale;
+use toonify::secrets::SecretPolicy;
+use toonify::tokens::Tokenizer;
+use toonify::type_rules::TypeRules;
+use toonify::toon::{ArrayLayout, DuplicateKeyPolicy};
+use toonify::validation::validate_value;
+
+#[cfg(feature = "tui")]
+mod tui;
+
+mod verify_bindings;
+
+use toonify::audit_log;
+use toonify::conversion_pool::{self, ConversionLimits};
+use toonify::server::ServerBuilder;
+
+mod mapped_input;
+use file_walk::FileWalk;
+use mapped_input::{read_input_file, InputText};
+
+#[cfg(feature = "database")]
+mod database;
+#[cfg(feature = "sqlite")]
+mod sqlite_bridge;
+#[cfg(feature = "duckdb")]
+mod duckdb_query;
+#[cfg(feature = "kafka")]
+mod kafka_bridge;
+
+mod atomic_write;
+mod build_info;
+mod daemon;
+mod file_walk;
+mod git_hook;
+mod healthcheck;
+mod listen;
+mod lsp;
+mod mcp;
+mod sync;
+mod watch_queue;
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "cli-cache")]
+mod cli_cache;
+#[cfg(feature = "config")]
+mod cli_config;
+#[cfg(feature = "self-update")]
+mod self_update;
+#[cfg(feature = "replay")]
+mod replay;
+mod table_ops;
+
+mod progress;
+mod publish;
+mod report;
+use progress::{BatchProgress, ByteProgress, ProgressReader};
+
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
-use tonic::{transport::Server, Request, Response, Status};
 use std::net::SocketAddr;
-use std::path::PathBuf;
-use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::io::{self, IsTerminal, Read, Write};
+use std::borrow::Cow;
 use std::fs;
-use tracing_subscriber;
 use flate2::Compression;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder as GzEncoderWrite;
-use glob::glob;
 use notify::{Watcher, RecursiveMode, Event, event::{CreateKind, ModifyKind}, EventKind};
 use std::sync::mpsc::channel;
-use regex::Regex;
 use rayon::prelude::*;
 use std::sync::{Arc, Mutex};
 
-#[cfg(feature = "cache")]
-use moka::future::Cache as MokaCache;
-
-#[cfg(feature = "persistent-cache")]
-use sled::Db as SledDb;
-
-#[cfg(feature = "rate-limit")]
-use tower_governor::{
-    governor::GovernorConfigBuilder, 
-    GovernorLayer,
-    key_extractor::GlobalKeyExtractor,
-};
-
-// Use pre-generated protobuf code (no need for protoc/cmake at build time)
-mod proto;
-pub use proto::generated as pb;
-
-use pb::converter_service_server::{ConverterService, ConverterServiceServer};
-use pb::{ConvertRequest, ConvertResponse};
+#[cfg(feature = "cache-encryption")]
+use toonify::cache_crypto::CacheCipher;
 
 #[derive(Parser)]
-#[command(name = "toonify")]
+#[command(name = "toonify", version)]
 #[command(about = "TOONify - High-performance JSON ↔ TOON converter", long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    
+    /// Listen addresses when run without a subcommand
+    #[command(flatten)]
+    addrs: ServerAddrs,
 }
 
 #[derive(Subcommand)]
@@ -67,6 +97,40 @@ enum Commands {
         /// Output file path (defaults to stdout)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        
+        /// Source format (json, toon, rows, yaml, csv, toml, xml; auto-detect if omitted)
+        #[arg(long)]
+        from: Option<String>,
+        
+        /// Target format (defaults to toon for non-TOON input, json for TOON; also html, and xlsx/arrow with those features)
+        #[arg(long)]
+        to: Option<String>,
+        
+        /// Table to write with --to arrow, when the document has several
+        #[arg(long)]
+        entity: Option<String>,
+        
+        /// Syntax-highlight output written to the terminal
+        #[arg(long, value_enum, env = "TOONIFY_COLOR", default_value_t = ColorMode::Auto)]
+        color: ColorMode,
+        
+        /// Reuse outputs cached in this directory, keyed by input and options (e.g. ~/.cache/toonify)
+        #[arg(long, env = "TOONIFY_CACHE_DIR")]
+        cache_dir: Option<PathBuf>,
+        
+        /// Gzip the output (gzip input is always decompressed before conversion)
+        #[arg(long)]
+        gzip: bool,
+        
+        /// Reorder table columns and pick cell separators to minimize tokens for a tokenizer (estimate; cl100k, o200k with the tiktoken feature)
+        #[arg(long, value_name = "TOKENIZER", num_args = 0..=1, default_missing_value = "estimate", conflicts_with = "cache_dir")]
+        optimize_tokens: Option<Tokenizer>,
+        
+        #[command(flatten)]
+        signing: SigningArgs,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
     },
     /// Compress TOON data with gzip
     Compress {
@@ -78,6 +142,153 @@ enum Commands {
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
+    /// Encode TOON/JSON as Protobuf or Avro binary using a schema
+    #[cfg(any(feature = "protobuf", feature = "avro"))]
+    Encode {
+        /// Schema describing the whole document (.proto, .avsc)
+        #[arg(long)]
+        schema: PathBuf,
+        
+        /// Message to encode as, if the .proto defines several
+        #[arg(long)]
+        message: Option<String>,
+        
+        /// Input file path (omit for stdin)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        
+        /// Output file path (omit for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Decode Protobuf or Avro binary back to TOON (or JSON)
+    #[cfg(any(feature = "protobuf", feature = "avro"))]
+    Decode {
+        /// Schema the data was written with (.proto, .avsc)
+        #[arg(long)]
+        schema: PathBuf,
+        
+        /// Message to decode as, if the .proto defines several
+        #[arg(long)]
+        message: Option<String>,
+        
+        /// Input file path (omit for stdin)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        
+        /// Output file path (omit for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Output format
+        #[arg(long, default_value = "toon")]
+        to: String,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Combine documents: entities are concatenated or rekeyed on conflict
+    Merge {
+        /// Documents to merge, in order (TOON, JSON, ...)
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        
+        /// How tables with the same name combine: union or intersect-columns
+        #[arg(long, default_value = "union")]
+        strategy: toonify::merge::MergeStrategy,
+        
+        /// Output file path (omit for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Output format
+        #[arg(long, default_value = "toon")]
+        to: String,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Write each entity of a document to its own file (<entity>.toon)
+    Split {
+        /// Input file path (omit for stdin)
+        input: Option<PathBuf>,
+        
+        /// Directory for the entity files
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+        
+        /// Output format (also the file extension)
+        #[arg(long, default_value = "toon")]
+        to: String,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Cut a document into TOON files of at most --max-tokens tokens, splitting large tables by rows
+    Chunk {
+        /// Input file path (omit for stdin)
+        input: Option<PathBuf>,
+        
+        /// Token budget for each chunk, including its headers and index footer
+        #[arg(long, default_value_t = 4000)]
+        max_tokens: usize,
+        
+        /// Directory for the chunk files (chunk-0001.toon, ...)
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+        
+        /// Tokenizer the budget is counted in (estimate; cl100k, o200k with the tiktoken feature)
+        #[arg(long, default_value = "estimate")]
+        tokenizer: Tokenizer,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Drop TOON table rows whose key repeats an earlier row (the first one is kept)
+    Dedupe {
+        /// Input file path (omit for stdin)
+        input: Option<PathBuf>,
+        
+        /// Key columns, comma-separated
+        #[arg(long, required = true, value_delimiter = ',')]
+        key: Vec<String>,
+        
+        /// Only this entity (default: every table with the key columns)
+        #[arg(long)]
+        entity: Option<String>,
+        
+        /// Output file path (omit for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Sort TOON table rows; tables larger than --buffer-rows are sorted on disk
+    Sort {
+        /// Input file path (omit for stdin)
+        input: Option<PathBuf>,
+        
+        /// Sort columns, comma-separated, most significant first
+        #[arg(long, required = true, value_delimiter = ',')]
+        by: Vec<String>,
+        
+        /// Largest first
+        #[arg(long)]
+        desc: bool,
+        
+        /// Only this entity (default: every table with the sort columns)
+        #[arg(long)]
+        entity: Option<String>,
+        
+        /// Rows sorted in memory before spilling a run to a temp file
+        #[arg(long, default_value_t = 100_000)]
+        buffer_rows: usize,
+        
+        /// Output file path (omit for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Decompress gzip-compressed TOON data
     Decompress {
         /// Input file path (omit for stdin)
@@ -98,21 +309,25 @@ enum Commands {
         #[arg(short, long)]
         input: Option<PathBuf>,
     },
-    /// Batch convert multiple files in a directory
+    /// Batch convert multiple files in a directory or archive
     Batch {
         /// Input directory containing files to convert
-        #[arg(short, long)]
-        input_dir: PathBuf,
+        #[arg(short, long, required_unless_present = "input")]
+        input_dir: Option<PathBuf>,
+        
+        /// Zip, tar or tar.gz archive to convert instead of a directory; members keep their paths under --output-dir
+        #[arg(long, value_name = "ARCHIVE", conflicts_with_all = ["input_dir", "retry_failed", "files_from", "preserve_metadata"])]
+        input: Option<PathBuf>,
         
         /// Output directory for converted files
         #[arg(short, long)]
         output_dir: PathBuf,
         
-        /// Source format (json or toon, auto-detect if omitted)
+        /// Source format (json, toon, rows, yaml, csv, toml, xml; auto-detect if omitted)
         #[arg(long)]
         from: Option<String>,
         
-        /// Target format (json or toon, auto-detect if omitted)
+        /// Target format (defaults to toon for non-TOON input, json for TOON)
         #[arg(long)]
         to: Option<String>,
         
@@ -125,8 +340,47 @@ enum Commands {
         recursive: bool,
         
         /// Enable parallel processing for faster batch conversions
-        #[arg(long)]
+        #[arg(long, env = "TOONIFY_PARALLEL")]
         parallel: bool,
+        
+        /// Threads for parallel conversion; implies --parallel (default: one per CPU)
+        #[arg(long, env = "TOONIFY_JOBS")]
+        jobs: Option<usize>,
+        
+        /// Show a progress bar instead of per-file log lines (failures are still reported)
+        #[arg(short, long)]
+        quiet: bool,
+        
+        /// Re-run only the inputs listed in a failures.jsonl from an earlier batch
+        #[arg(long, conflicts_with = "pattern")]
+        retry_failed: Option<PathBuf>,
+        
+        /// Convert only the newline-separated paths in this file ("-" for stdin)
+        #[arg(long, value_name = "FILE", conflicts_with = "retry_failed")]
+        files_from: Option<PathBuf>,
+        
+        /// Flush each output file and its directory to disk before moving on
+        #[arg(long)]
+        fsync: bool,
+        
+        /// Copy permissions, timestamps and (where permitted) ownership from each source file
+        #[arg(long)]
+        preserve_metadata: bool,
+        
+        /// Gzip each output and add .gz to its name (gzip inputs are always decompressed)
+        #[arg(long)]
+        gzip: bool,
+        
+        /// Follow symlinked files and directories (the default); cycles are entered once
+        #[arg(long, overrides_with = "no_follow_symlinks")]
+        follow_symlinks: bool,
+        
+        /// Skip symlinks entirely
+        #[arg(long, overrides_with = "follow_symlinks")]
+        no_follow_symlinks: bool,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
     },
     /// Watch directory and auto-convert files on change
     Watch {
@@ -138,429 +392,1039 @@ enum Commands {
         #[arg(short, long)]
         output_dir: PathBuf,
         
-        /// Source format (json or toon, auto-detect if omitted)
+        /// Source format (json, toon, rows, yaml, csv, toml, xml; auto-detect if omitted)
         #[arg(long)]
         from: Option<String>,
         
-        /// Target format (json or toon, auto-detect if omitted)
+        /// Target format (defaults to toon for non-TOON input, json for TOON)
         #[arg(long)]
         to: Option<String>,
         
         /// File pattern (e.g., "*.json", defaults to all files)
         #[arg(short, long)]
         pattern: Option<String>,
+        
+        /// Files converted at once (default: one per CPU); events for a file already converting are coalesced
+        #[arg(long, env = "TOONIFY_JOBS")]
+        jobs: Option<usize>,
+        
+        /// Flush each output file and its directory to disk before moving on
+        #[arg(long)]
+        fsync: bool,
+        
+        /// Copy permissions, timestamps and (where permitted) ownership from each source file
+        #[arg(long)]
+        preserve_metadata: bool,
+        
+        /// Follow symlinked files and directories (the default); cycles are entered once
+        #[arg(long, overrides_with = "no_follow_symlinks")]
+        follow_symlinks: bool,
+        
+        /// Skip symlinks entirely
+        #[arg(long, overrides_with = "follow_symlinks")]
+        no_follow_symlinks: bool,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
     },
-    /// Start the API server (gRPC + REST)
-    Serve {
-        /// Enable Moka cache with specified size (number of entries)
+    /// Keep two directories equivalent in both directions (e.g. JSON and TOON copies of the same files)
+    Sync {
+        /// First directory
         #[arg(long)]
-        cache_size: Option<u64>,
+        dir_a: PathBuf,
         
-        /// TTL for cache entries in seconds (optional, no expiration if not set)
+        /// Second directory
         #[arg(long)]
-        cache_ttl: Option<u64>,
+        dir_b: PathBuf,
         
-        /// Enable Sled persistent cache (path to database file, e.g., "./cache.db")
+        /// Format of the files in --dir-a
+        #[arg(long, default_value = "json")]
+        format_a: String,
+        
+        /// Format of the files in --dir-b
+        #[arg(long, default_value = "toon")]
+        format_b: String,
+        
+        /// When both files of a pair changed: keep the newer one, or neither and write .conflict copies
+        #[arg(long, value_enum, default_value_t = sync::ConflictPolicy::Newest)]
+        conflict: sync::ConflictPolicy,
+        
+        /// Reconcile once and exit (non-zero while manual conflicts remain) instead of watching
         #[arg(long)]
-        persistent_cache: Option<String>,
+        once: bool,
         
-        /// Enable job queue for distributed processing
+        /// Flush each written file and its directory to disk
         #[arg(long)]
-        enable_job_queue: bool,
+        fsync: bool,
         
-        /// Number of worker threads for job processing (default: 4)
-        #[arg(long, default_value = "4")]
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Write a REST client for the server (plus converter.proto for gRPC) into a directory
+    GenClient {
+        /// Client language: python, typescript or go
+        #[arg(long)]
+        lang: toonify::client_gen::ClientLang,
+        
+        /// Directory for the generated files
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Generate fake data that passes a validation schema
+    Generate {
+        /// Validation schema (the `validate --schema` format)
+        #[arg(long)]
+        schema: PathBuf,
+        
+        /// Rows per entity (clamped to min_items/max_items)
+        #[arg(long, default_value = "10")]
+        rows: usize,
+        
+        /// Generator seed; the same seed gives the same data
+        #[arg(long)]
+        seed: Option<u64>,
+        
+        /// Output format
+        #[arg(long, default_value = "toon")]
+        to: String,
+        
+        /// Output file path (omit for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Per-column statistics: inferred type, null rate, distinct count, min/max, string length
+    Profile {
+        /// Input file path (omit or `-` for stdin)
+        input: Option<PathBuf>,
+        
+        /// Source format (auto-detect if omitted)
+        #[arg(long)]
+        from: Option<String>,
+        
+        /// Print the profile as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compact overview for prompts: every table's full typed header, its first rows, and entity sizes
+    Summarize {
+        /// Input file path (omit or `-` for stdin)
+        input: Option<PathBuf>,
+        
+        /// Rows kept per table (values per list)
+        #[arg(long, default_value = "5")]
+        rows: usize,
+        
+        /// Source format (auto-detect if omitted)
+        #[arg(long)]
+        from: Option<String>,
+        
+        /// Output file path (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Browse a data file's tables in an interactive terminal UI
+    #[cfg(feature = "tui")]
+    View {
+        /// File to open (any supported format)
+        input: PathBuf,
+        
+        /// Source format (auto-detect if omitted)
+        #[arg(long)]
+        from: Option<String>,
+    },
+    /// Export SQL query results (Postgres, MySQL, SQLite) as TOON tables
+    #[cfg(feature = "database")]
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Snapshot SQLite tables to typed TOON, or load TOON into SQLite
+    #[cfg(feature = "sqlite")]
+    Sqlite {
+        #[command(subcommand)]
+        action: SqliteAction,
+    },
+    /// Run SQL over TOON entities with DuckDB (each entity is a table)
+    #[cfg(feature = "duckdb")]
+    Sql {
+        /// SELECT query, e.g. "SELECT name, count(*) FROM users GROUP BY 1"
+        query: String,
+        
+        /// Input file path (omit or `-` for stdin)
+        input: Option<PathBuf>,
+        
+        /// Output file path (omit for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Output format
+        #[arg(long, default_value = "toon")]
+        to: String,
+        
+        /// Entity name for the result rows
+        #[arg(long, default_value = "result")]
+        name: String,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Consume JSON messages from Kafka and produce TOON to another topic
+    #[cfg(feature = "kafka")]
+    KafkaBridge {
+        /// Bootstrap servers (host:port,...)
+        #[arg(long)]
+        brokers: String,
+        
+        /// Topic to consume JSON from
+        #[arg(long)]
+        topic: String,
+        
+        /// Topic to produce TOON to
+        #[arg(long)]
+        topic_out: String,
+        
+        /// Consumer group id
+        #[arg(long, default_value = "toonify-bridge")]
+        group_id: String,
+        
+        /// Messages per TOON document (more than one becomes a table)
+        #[arg(long, default_value = "1")]
+        batch_size: usize,
+        
+        /// Flush a partial batch after this long without new messages
+        #[arg(long, default_value = "1000")]
+        batch_timeout_ms: u64,
+        
+        /// Entity name for batched messages
+        #[arg(long, default_value = "messages")]
+        entity: String,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Accept webhook POSTs of JSON and save each one as a TOON file
+    Listen {
+        /// Port to listen on
+        #[arg(long, default_value = "8081")]
+        port: u16,
+        
+        /// Address to bind
+        #[arg(long, default_value = "0.0.0.0")]
+        bind: std::net::IpAddr,
+        
+        /// Directory the captured .toon files are written to
+        #[arg(long)]
+        output_dir: PathBuf,
+        
+        /// Request header used to name files when the path has no id
+        #[arg(long, default_value = "X-Request-Id")]
+        id_header: String,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Run queued jobs from Valkey/Redis without serving HTTP or gRPC
+    #[cfg(feature = "distributed-jobs")]
+    Worker {
+        /// Valkey/Redis URL the API nodes queue jobs in (their --job-queue-backend)
+        #[arg(long)]
+        queue: String,
+        
+        /// Jobs run at once (default: 4)
+        #[arg(long, env = "TOONIFY_WORKERS", default_value = "4")]
         workers: usize,
         
-        /// Job queue backend ("memory" or redis URL like "redis://127.0.0.1:6379")
+        #[command(flatten)]
+        guards: GuardArgs,
+    },
+    /// Serve convert/validate/stats as JSON-RPC over a Unix socket (named pipe on Windows)
+    Daemon {
+        /// Socket path, or pipe name on Windows
+        #[arg(long, default_value = "/tmp/toonify.sock")]
+        socket: PathBuf,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Run a TOON language server on stdio (diagnostics, hover, formatting, symbols)
+    Lsp,
+    /// Run a Model Context Protocol tool server on stdio (convert, stats, validate)
+    Mcp {
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Write a self-contained HTML report: entity tables, size savings, validation
+    Report {
+        /// Input file path (use '-' for stdin)
+        input: String,
+        
+        /// HTML file to write (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Input format (default: auto-detect)
         #[arg(long)]
-        job_queue_backend: Option<String>,
+        from: Option<String>,
         
-        /// Enable rate limiting (requests per window)
+        /// Schema to validate against, as for `toonify validate`
         #[arg(long)]
-        rate_limit: Option<u32>,
+        schema: Option<PathBuf>,
         
-        /// Rate limit window in seconds (default: 60)
-        #[arg(long, default_value = "60")]
-        rate_limit_window: u64,
+        /// Rows shown per entity table
+        #[arg(long, default_value = "100")]
+        max_rows: usize,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Build a static site to browse a directory of datasets and download them as TOON, JSON or gzip
+    Publish {
+        /// Directory of data files (searched recursively)
+        #[arg(long)]
+        input_dir: PathBuf,
+        
+        /// Directory for the site (index.html at its root)
+        #[arg(short, long)]
+        output: PathBuf,
+        
+        /// Only publish files matching this glob, relative to --input-dir (e.g. "*.json")
+        #[arg(long)]
+        pattern: Option<String>,
+        
+        /// Rows shown on each table page
+        #[arg(long, default_value = "100")]
+        max_rows: usize,
+        
+        /// Site title
+        #[arg(long, default_value = "Datasets")]
+        title: String,
+        
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+    /// Format TOON files in place (or stdin to stdout)
+    Fmt {
+        /// TOON files to format (omit for stdin)
+        inputs: Vec<PathBuf>,
+        
+        /// Report files that would change and exit non-zero instead of writing
+        #[arg(long)]
+        check: bool,
+        
+        /// Pad cells so table columns line up
+        #[arg(long)]
+        align: bool,
+        
+        /// Order entities by name
+        #[arg(long)]
+        sort: bool,
+    },
+    /// Verify JSON ↔ TOON round trips on generated documents and your own files
+    Selftest {
+        /// JSON files whose round trip should also be checked
+        inputs: Vec<PathBuf>,
+        
+        /// Number of generated documents
+        #[arg(long, default_value = "256")]
+        cases: usize,
+        
+        /// Generator seed (reuse a reported seed to reproduce a failure)
+        #[arg(long)]
+        seed: Option<u64>,
+        
+        /// Round-trip with typed TOON headers
+        #[arg(long)]
+        typed_headers: bool,
+    },
+    /// Verify an Ed25519 signature produced by `convert --sign-key`
+    VerifySignature {
+        /// Signed file
+        input: PathBuf,
+        
+        /// Ed25519 public key (SPKI PEM)
+        #[arg(long)]
+        public_key: PathBuf,
+        
+        /// Detached signature file (defaults to <input>.sig, then a signature footer)
+        #[arg(long)]
+        signature: Option<PathBuf>,
+        
+        /// Write the verified content (footer removed) to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Re-issue conversions from an audit log against another server and diff the results
+    #[cfg(feature = "replay")]
+    Replay {
+        /// Audit logs written with serve --audit-log --audit-log-payloads
+        #[arg(required = true)]
+        logs: Vec<PathBuf>,
+        
+        /// Server to replay against, e.g. http://staging:5000
+        #[arg(long)]
+        target: String,
+        
+        /// Requests in flight at once
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+        
+        /// Write each difference as a JSON line to this file
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+    /// Replace this binary with the latest GitHub release (checksum and signature verified)
+    #[cfg(feature = "self-update")]
+    SelfUpdate {
+        /// Only report whether an update is available; exits non-zero if one is
+        #[arg(long)]
+        check: bool,
+        
+        /// Ed25519 public key (SPKI PEM) the release's .sig must verify against
+        #[arg(long)]
+        public_key: Option<PathBuf>,
+    },
+    /// Print the version, or build metadata with --json
+    Version {
+        /// Git commit, target, enabled features and TOON format version as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Probe a local server and exit 0 when it is ready (for Docker HEALTHCHECK)
+    Healthcheck {
+        /// Probe the gRPC service with a conversion instead of GET /readyz
+        #[arg(long)]
+        grpc: bool,
+        
+        /// Give up after this long
+        #[arg(long, default_value = "2000")]
+        timeout_ms: u64,
+        
+        #[command(flatten)]
+        addrs: ServerAddrs,
+    },
+    /// Maintain the --cache-dir conversion cache and the serve --persistent-cache database
+    #[cfg(any(feature = "cli-cache", feature = "persistent-cache"))]
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Git pre-commit integration that keeps TOON artifacts in sync with JSON sources
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
+    /// Regression corpus of tricky real-world documents, for checking platform builds
+    Corpus {
+        #[command(subcommand)]
+        action: CorpusAction,
+    },
+    /// Check that the Python, Kotlin, Swift, and WASM bindings match the Rust converter
+    VerifyBindings {
+        /// Directory containing generated UniFFI bindings (python/, kotlin/, swift/)
+        #[arg(long, default_value = "bindings")]
+        bindings_dir: PathBuf,
+        
+        /// wasm-pack output directory
+        #[arg(long, default_value = "pkg")]
+        pkg_dir: PathBuf,
+        
+        /// Directory containing the native libtoonify library
+        #[arg(long, default_value = "target/release")]
+        lib_dir: PathBuf,
+        
+        /// Only check these layers (comma-separated: python,kotlin,swift,wasm)
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<String>,
+        
+        /// Fail when a layer is skipped because its artifacts or toolchain are missing
+        #[arg(long)]
+        require_all: bool,
+    },
+    /// Start the API server (gRPC + REST)
+    Serve {
+        /// Enable Moka cache with specified size (number of entries)
+        #[arg(long)]
+        cache_size: Option<u64>,
+        
+        /// TTL for cache entries in seconds (optional, no expiration if not set)
+        #[arg(long)]
+        cache_ttl: Option<u64>,
+        
+        /// Reload the Moka cache from this file at startup and write it back periodically and on shutdown
+        #[arg(long, requires = "cache_size")]
+        cache_snapshot: Option<PathBuf>,
+        
+        /// Seconds between Moka snapshots
+        #[arg(long, default_value = "300")]
+        cache_snapshot_interval: u64,
+        
+        /// Enable Sled persistent cache (path to database file, e.g., "./cache.db")
+        #[arg(long)]
+        persistent_cache: Option<String>,
+        
+        /// Encrypt persistent cache entries with AES-256-GCM (keyfile path or env:VAR holding 32 bytes / 64 hex chars)
+        #[arg(long)]
+        cache_encryption_key: Option<String>,
+        
+        /// Enable job queue for distributed processing
+        #[arg(long)]
+        enable_job_queue: bool,
+        
+        /// Number of worker threads for job processing (default: 4)
+        #[arg(long, env = "TOONIFY_WORKERS", default_value = "4")]
+        workers: usize,
+        
+        /// Job queue backend ("memory" or redis URL like "redis://127.0.0.1:6379")
+        #[arg(long)]
+        job_queue_backend: Option<String>,
+        
+        /// Abort REST/gRPC conversions that run longer than this (504 / DEADLINE_EXCEEDED)
+        #[arg(long)]
+        conversion_timeout_ms: Option<u64>,
+        
+        /// Threads in the conversion pool (default: one per CPU)
+        #[arg(long, default_value = "0")]
+        conversion_threads: usize,
+        
+        /// Queued plus running conversions before requests are shed with 429
+        #[arg(long, default_value_t = conversion_pool::DEFAULT_MAX_QUEUED)]
+        conversion_queue: usize,
+        
+        /// Inputs up to this size run on a separate pool, so they are not stuck behind large conversions (0 disables)
+        #[arg(long, default_value_t = conversion_pool::DEFAULT_SMALL_REQUEST_BYTES)]
+        small_request_bytes: usize,
+        
+        /// Threads in the small-request pool
+        #[arg(long, default_value = "2")]
+        small_request_threads: usize,
+        
+        /// Enable rate limiting (requests per window)
+        #[arg(long)]
+        rate_limit: Option<u32>,
+        
+        /// Rate limit window in seconds (default: 60)
+        #[arg(long, default_value = "60")]
+        rate_limit_window: u64,
+        
+        /// Append a JSON line per REST/gRPC conversion to this file (client, sizes, duration, status)
+        #[arg(long)]
+        audit_log: Option<PathBuf>,
+        
+        /// Rotate the audit log when it would grow past this size
+        #[arg(long, default_value = "100")]
+        audit_log_max_mb: u64,
+        
+        /// Rotated audit logs to keep (audit.jsonl.1 ... audit.jsonl.N)
+        #[arg(long, default_value = "5")]
+        audit_log_keep: usize,
+        
+        /// Include request data and results in the audit log, for `toonify replay`
+        #[arg(long, requires = "audit_log")]
+        audit_log_payloads: bool,
+        
+        /// Also accept gRPC-Web (HTTP/1.1, CORS) on the gRPC port, for browser clients
+        #[cfg(feature = "grpc-web")]
+        #[arg(long)]
+        grpc_web: bool,
+        
+        /// Serve a browser page at /playground for pasting documents and converting them here
+        #[arg(long)]
+        playground: bool,
+        
+        /// Also serve each gRPC method as JSON on the REST port, at its gRPC path (POST /converter.ConverterService/JsonToToon)
+        #[cfg(feature = "grpc-gateway")]
+        #[arg(long)]
+        grpc_gateway: bool,
+        
+        /// Valkey/Redis URL whose pub/sub channel carries POST /cache/clear to every node
+        #[cfg(feature = "cluster-cache")]
+        #[arg(long, env = "TOONIFY_CACHE_INVALIDATION_URL")]
+        cache_invalidation_url: Option<String>,
+        
+        /// Register every *.json schema in this directory under its file stem (served at /schemas)
+        #[arg(long)]
+        schema_dir: Option<PathBuf>,
+        
+        /// tcp: REST and gRPC listeners; stdio or unix: line-delimited JSON-RPC (the `daemon` protocol)
+        #[arg(long, value_enum, default_value_t = ServeTransport::Tcp)]
+        transport: ServeTransport,
+        
+        /// Socket path (pipe name on Windows) for --transport unix
+        #[arg(long, required_if_eq("transport", "unix"))]
+        socket: Option<PathBuf>,
+        
+        #[command(flatten)]
+        guards: GuardArgs,
+        
+        #[command(flatten)]
+        addrs: ServerAddrs,
     },
 }
 
-// Moka cache type for high-performance in-memory caching
-#[cfg(feature = "cache")]
-type MokaConversionCache = Arc<MokaCache<String, String>>;
-
-#[cfg(feature = "cache")]
-fn create_moka_cache(size: u64, ttl_seconds: Option<u64>) -> MokaConversionCache {
-    let mut builder = MokaCache::builder()
-        .max_capacity(size);
-    
-    if let Some(ttl) = ttl_seconds {
-        builder = builder.time_to_live(std::time::Duration::from_secs(ttl));
-    }
-    
-    Arc::new(builder.build())
-}
-
-// Sled database type for persistent caching
-#[cfg(feature = "persistent-cache")]
-type SledCacheDb = Arc<SledDb>;
-
-// Cache state with Moka (hot) and Sled (persistent)
-#[derive(Clone)]
-struct CacheState {
-    #[cfg(feature = "cache")]
-    moka: Option<MokaConversionCache>,
-    #[cfg(feature = "persistent-cache")]
-    sled: Option<SledCacheDb>,
-}
-
-// Combined app state for all handlers
-#[derive(Clone)]
-struct AppState {
-    cache: CacheState,
-    #[cfg(feature = "job-queue")]
-    job_store: Option<job_queue::JobStore>,
-}
-
-#[derive(Clone)]
-struct ConverterServiceImpl;
-
-#[tonic::async_trait]
-impl ConverterService for ConverterServiceImpl {
-    async fn json_to_toon(
-        &self,
-        request: Request<ConvertRequest>,
-    ) -> Result<Response<ConvertResponse>, Status> {
-        let req = request.into_inner();
-        
-        match converter::json_to_toon(&req.data) {
-            Ok(result) => Ok(Response::new(ConvertResponse {
-                result,
-                error: String::new(),
-            })),
-            Err(e) => Ok(Response::new(ConvertResponse {
-                result: String::new(),
-                error: e,
-            })),
-        }
-    }
-
-    async fn toon_to_json(
-        &self,
-        request: Request<ConvertRequest>,
-    ) -> Result<Response<ConvertResponse>, Status> {
-        let req = request.into_inner();
-        
-        match converter::toon_to_json(&req.data) {
-            Ok(result) => Ok(Response::new(ConvertResponse {
-                result,
-                error: String::new(),
-            })),
-            Err(e) => Ok(Response::new(ConvertResponse {
-                result: String::new(),
-                error: e,
-            })),
-        }
-    }
-}
-
-#[derive(Deserialize)]
-struct ConvertPayload {
-    data: String,
-}
-
-#[derive(Serialize)]
-struct ConvertResult {
-    result: Option<String>,
-    error: Option<String>,
-}
-
-async fn health_check() -> &'static str {
-    "TOONify API - Blazing Fast!"
-}
-
-// Job Queue HTTP Handlers
-#[cfg(feature = "job-queue")]
-#[derive(Deserialize)]
-struct SubmitJobPayload {
-    operation: String,
-    data: String,
+// Listen addresses for serve and the default server mode
+#[derive(Args, Clone)]
+struct ServerAddrs {
+    /// gRPC listen address
+    #[arg(long, env = "TOONIFY_GRPC_ADDR", default_value = "0.0.0.0:50051")]
+    grpc_addr: SocketAddr,
+    
+    /// REST API listen address
+    #[arg(long, env = "TOONIFY_HTTP_ADDR", default_value = "0.0.0.0:5000")]
+    http_addr: SocketAddr,
 }
 
-#[cfg(feature = "job-queue")]
-#[derive(Serialize)]
-struct SubmitJobResponse {
-    job_id: String,
+// Output signing options for convert
+#[derive(Args, Clone)]
+struct SigningArgs {
+    /// Ed25519 private key (PKCS#8 PEM) used to sign the output
+    #[arg(long)]
+    sign_key: Option<PathBuf>,
+    
+    /// Detached signature path (defaults to <output>.sig)
+    #[arg(long, requires = "sign_key")]
+    signature: Option<PathBuf>,
+    
+    /// Append the signature as a footer line instead of writing a detached file
+    #[arg(long, requires = "sign_key", conflicts_with = "signature")]
+    sign_footer: bool,
 }
 
-#[cfg(feature = "job-queue")]
-async fn submit_job_handler(
-    axum::extract::State(app_state): axum::extract::State<AppState>,
-    Json(payload): Json<SubmitJobPayload>,
-) -> impl IntoResponse {
-    if let Some(job_store) = app_state.job_store {
-        let job_id = job_queue::submit_job(job_store, payload.operation, payload.data);
-        Json(SubmitJobResponse { job_id })
-    } else {
-        Json(SubmitJobResponse { job_id: "error:job_queue_disabled".to_string() })
-    }
+#[cfg(feature = "database")]
+#[derive(Subcommand)]
+enum DbAction {
+    /// Run queries and write each result set as a TOON table
+    Export {
+        /// Connection string (postgres://, mysql://, sqlite://)
+        #[arg(long)]
+        dsn: String,
+        
+        /// SQL query; repeat for several tables
+        #[arg(long, required = true)]
+        query: Vec<String>,
+        
+        /// Entity name for each --query, in order (defaults to rows, rows_2, ...)
+        #[arg(long)]
+        name: Vec<String>,
+        
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Gzip the output
+        #[arg(long)]
+        gzip: bool,
+    },
 }
 
-#[cfg(feature = "job-queue")]
-#[derive(Serialize)]
-struct JobStatusResponse {
-    status: String,
-    error: Option<String>,
+#[cfg(feature = "sqlite")]
+#[derive(Subcommand)]
+enum SqliteAction {
+    /// Write tables as typed TOON entities
+    Export {
+        /// SQLite database file
+        database: PathBuf,
+        
+        /// Tables to export, comma-separated (defaults to all)
+        #[arg(long, value_delimiter = ',')]
+        tables: Vec<String>,
+        
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Create and populate tables from a TOON document
+    Import {
+        /// SQLite database file (created if missing)
+        database: PathBuf,
+        
+        /// TOON file (omit for stdin)
+        input: Option<PathBuf>,
+        
+        /// Drop existing tables of the same name first
+        #[arg(long)]
+        replace: bool,
+    },
 }
 
-#[cfg(feature = "job-queue")]
-async fn get_job_status_handler(
-    axum::extract::State(app_state): axum::extract::State<AppState>,
-    axum::extract::Path(job_id): axum::extract::Path<String>,
-) -> impl IntoResponse {
-    if let Some(job_store) = app_state.job_store {
-        if let Some((status, error)) = job_queue::get_job_status(job_store, &job_id) {
-            let status_str = match status {
-                job_queue::JobStatus::Pending => "pending",
-                job_queue::JobStatus::Processing => "processing",
-                job_queue::JobStatus::Completed => "completed",
-                job_queue::JobStatus::Failed => "failed",
-            };
-            Json(JobStatusResponse {
-                status: status_str.to_string(),
-                error,
-            })
-        } else {
-            Json(JobStatusResponse {
-                status: "not_found".to_string(),
-                error: Some("Job not found".to_string()),
-            })
+#[cfg(any(feature = "cli-cache", feature = "persistent-cache"))]
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove entries unused for --max-age-days, then the least recently used beyond --max-size-mb
+    #[cfg(feature = "cli-cache")]
+    Gc {
+        /// Cache directory (default: $XDG_CACHE_HOME/toonify or ~/.cache/toonify)
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
+        
+        /// Remove entries not used for this many days
+        #[arg(long, default_value = "30")]
+        max_age_days: u64,
+        
+        /// Evict least recently used entries until the cache is at most this size
+        #[arg(long)]
+        max_size_mb: Option<u64>,
+    },
+    /// Entry count, size on disk and entry ages of a Sled cache (stop the server first)
+    #[cfg(feature = "persistent-cache")]
+    Stats {
+        /// Sled database path, as given to serve --persistent-cache
+        path: PathBuf,
+    },
+    /// Remove Sled cache entries written longer ago than --older-than
+    #[cfg(feature = "persistent-cache")]
+    Prune {
+        /// Sled database path, as given to serve --persistent-cache
+        path: PathBuf,
+        
+        /// Age such as 12h, 7d or 2w; entries with no recorded write time are always removed
+        #[arg(long)]
+        older_than: String,
+    },
+    /// Rewrite a Sled cache into fresh files to give back disk space
+    #[cfg(feature = "persistent-cache")]
+    Compact {
+        /// Sled database path, as given to serve --persistent-cache
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum CorpusAction {
+    /// Check that every case converts to its expected TOON and back
+    Run {
+        /// Directory of name.json + name.toon cases instead of the built-in corpus
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookAction {
+    /// Write a .git/hooks/pre-commit that runs `toonify hook run`
+    Install {
+        /// Replace an existing pre-commit hook that toonify did not write
+        #[arg(long)]
+        force: bool,
+    },
+    /// Convert/validate staged (or the given) .json and .toon files
+    Run {
+        /// Files to check instead of the staged ones (as passed by hook frameworks)
+        files: Vec<PathBuf>,
+        
+        /// Fail on stale TOON artifacts instead of regenerating and staging them
+        #[arg(long)]
+        check: bool,
+        
+        #[command(flatten)]
+        conversion: Box<ConversionArgs>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+enum ServeTransport {
+    Tcp,
+    /// One client on stdin/stdout, e.g. a tool subprocess of an agent framework
+    Stdio,
+    Unix,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+enum ColorMode {
+    /// Color when stdout is a terminal and NO_COLOR is unset
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    fn enabled(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
         }
-    } else {
-        Json(JobStatusResponse {
-            status: "error".to_string(),
-            error: Some("Job queue disabled".to_string()),
-        })
     }
 }
 
-#[cfg(feature = "job-queue")]
-#[derive(Serialize)]
-struct JobResultResponse {
-    result: Option<String>,
+// Conversion pipeline options shared by convert, batch, and watch
+#[derive(Args, Clone, Debug)]
+struct ConversionArgs {
+    /// Rhai script applied to the parsed document before conversion
+    #[arg(long)]
+    transform: Option<PathBuf>,
+    
+    /// Annotate TOON header columns with types (e.g. {id:int,name:str})
+    #[arg(long, env = "TOONIFY_TYPED_HEADERS")]
+    typed_headers: bool,
+    
+    /// Arrays of objects as tables, one object per line, or auto (a table unless most cells would be empty)
+    #[arg(long, env = "TOONIFY_LAYOUT", default_value = "auto")]
+    layout: ArrayLayout,
+    
+    /// Replace repeated values in low-cardinality string columns with codes (#dict status: pending=0,shipped=1)
+    #[arg(long, env = "TOONIFY_DICTIONARY")]
+    dictionary: bool,
+    
+    /// Flatten nested objects into dotted-path columns (user.address.city)
+    #[arg(long, conflicts_with = "unflatten")]
+    flatten: bool,
+    
+    /// Rebuild nested objects from dotted-path columns
+    #[arg(long)]
+    unflatten: bool,
+    
+    /// Duplicate key policy: error, first-wins, last-wins, or merge-arrays
+    #[arg(long, env = "TOONIFY_DUPLICATE_KEYS", default_value = "last-wins")]
+    duplicate_keys: DuplicateKeyPolicy,
+    
+    /// File mapping fields (or entity.field) to string, int, float, bool, date, or datetime instead of guessing from the text
+    #[arg(long, env = "TOONIFY_TYPES")]
+    types: Option<PathBuf>,
+    
+    /// Read numbers and dates the way this locale writes them (de: 1.234,56 and 01.05.2024; fr, en-GB, en-US, ...)
+    #[arg(long, env = "TOONIFY_LOCALE")]
+    locale: Option<Locale>,
+    
+    /// Fail instead of warning when the input looks like it contains secrets (API keys, JWTs, AWS keys)
+    #[arg(long)]
+    block_secrets: bool,
+    
+    /// Clean a field's strings, e.g. name=trim,collapse_ws (repeatable; trim, collapse_ws, lowercase, uppercase, casefold, strip_control)
+    #[arg(long, value_name = "FIELD=SANITIZERS")]
+    sanitize: Vec<String>,
+    
+    /// Replace these fields with deterministic pseudonyms, e.g. fields=email,name
+    #[arg(long, requires = "salt")]
+    pseudonymize: Option<String>,
+    
+    /// HMAC key for --pseudonymize, or env:VAR to read it from the environment
+    #[arg(long)]
+    salt: Option<String>,
+    
+    #[command(flatten)]
+    guards: GuardArgs,
 }
 
-#[cfg(feature = "job-queue")]
-async fn get_job_result_handler(
-    axum::extract::State(app_state): axum::extract::State<AppState>,
-    axum::extract::Path(job_id): axum::extract::Path<String>,
-) -> impl IntoResponse {
-    if let Some(job_store) = app_state.job_store {
-        let result = job_queue::get_job_result(job_store, &job_id);
-        Json(JobResultResponse { result })
-    } else {
-        Json(JobResultResponse { result: None })
+// Parser guards shared by the conversion pipeline and the server
+#[derive(Args, Clone, Debug)]
+struct GuardArgs {
+    /// Refuse input nested more than this many objects/arrays deep
+    #[arg(long)]
+    max_depth: Option<usize>,
+    
+    /// Refuse input holding more than this many values (objects, arrays and scalars)
+    #[arg(long)]
+    max_entities: Option<usize>,
+    
+    /// Refuse input with a line longer than this many bytes
+    #[arg(long)]
+    max_line_length: Option<usize>,
+}
+
+impl GuardArgs {
+    fn guards(&self) -> ParserGuards {
+        ParserGuards { max_depth: self.max_depth, max_entities: self.max_entities, max_line_length: self.max_line_length }
     }
 }
 
-#[cfg(feature = "job-queue")]
-#[derive(Serialize)]
-struct ListJobsResponse {
-    jobs: Vec<job_queue::Job>,
+
+// Files starting with the gzip magic bytes are decompressed; others are read as usual
+fn read_convert_input(path: &Path) -> Result<InputText, Box<dyn std::error::Error>> {
+    let mut magic = Vec::with_capacity(2);
+    fs::File::open(path)?.take(2).read_to_end(&mut magic)?;
+    if !converter::is_gzip(&magic) {
+        return Ok(read_input_file(path)?);
+    }
+    Ok(InputText::Owned(decode_input(fs::read(path)?)?))
 }
 
-#[cfg(feature = "job-queue")]
-async fn list_jobs_handler(
-    axum::extract::State(app_state): axum::extract::State<AppState>,
-) -> impl IntoResponse {
-    if let Some(job_store) = app_state.job_store {
-        let jobs = job_queue::list_jobs(job_store);
-        Json(ListJobsResponse { jobs })
+fn decode_input(bytes: Vec<u8>) -> Result<String, Box<dyn std::error::Error>> {
+    if !converter::is_gzip(&bytes) {
+        return Ok(String::from_utf8(bytes).map_err(|e| format!("Input is not valid UTF-8: {}", e))?);
+    }
+    let mut text = String::new();
+    GzDecoder::new(&bytes[..]).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+// TOON converts back to JSON; every other format converts to TOON
+fn default_target_format(source_format: &str) -> &'static str {
+    if source_format.eq_ignore_ascii_case("toon") {
+        "json"
     } else {
-        Json(ListJobsResponse { jobs: vec![] })
+        "toon"
     }
 }
 
-async fn json_to_toon_handler(
-    axum::extract::State(app_state): axum::extract::State<AppState>,
-    Json(payload): Json<ConvertPayload>,
-) -> impl IntoResponse {
-    let cache_state = app_state.cache;
-    let cache_key = format!("toonify:json_to_toon:{}", payload.data);
-    
-    // Try Moka cache first (hot, lock-free, < 100ns)
-    #[cfg(feature = "cache")]
-    if let Some(ref moka) = cache_state.moka {
-        if let Some(cached_result) = moka.get(&cache_key).await {
-            eprintln!("[CACHE] Moka hit for json-to-toon");
-            return (
-                StatusCode::OK,
-                Json(ConvertResult {
-                    result: Some(cached_result),
-                    error: None,
-                }),
-            );
+// Preferred file extension for a registered format
+fn extension_for_format(format: &str) -> &'static str {
+    converter::registry()
+        .get(format)
+        .and_then(|codec| codec.extensions().first().copied())
+        .unwrap_or("txt")
+}
+
+// Build the conversion pipeline from CLI options
+fn build_converter(args: ConversionArgs) -> Result<converter::Converter, Box<dyn std::error::Error>> {
+    let builder = converter::Converter::builder()
+        .typed_headers(args.typed_headers)
+        .array_layout(args.layout)
+        .dictionary(args.dictionary)
+        .duplicate_keys(args.duplicate_keys)
+        .guards(args.guards.guards())
+        .flatten(args.flatten)
+        .unflatten(args.unflatten)
+        .secrets(if args.block_secrets { SecretPolicy::Block } else { SecretPolicy::Warn });
+    
+    let builder = match args.locale {
+        Some(locale) => builder.locale(locale),
+        None => builder,
+    };
+    
+    let builder = match &args.types {
+        Some(path) => {
+            let rules = TypeRules::load(path)?;
+            eprintln!("[CLI] Loaded {} type rules from {:?}", rules.len(), path);
+            builder.type_rules(rules)
         }
-    }
+        None => builder,
+    };
     
-    // Try Sled persistent cache if enabled (cold, ~1ms)
-    #[cfg(feature = "persistent-cache")]
-    if let Some(ref sled) = cache_state.sled {
-        if let Ok(Some(cached_bytes)) = sled.get(cache_key.as_bytes()) {
-            if let Ok(cached_result) = String::from_utf8(cached_bytes.to_vec()) {
-                eprintln!("[CACHE] Sled hit for json-to-toon");
-                
-                // Warm up Moka cache from Sled
-                #[cfg(feature = "cache")]
-                if let Some(ref moka) = cache_state.moka {
-                    moka.insert(cache_key.clone(), cached_result.clone()).await;
-                }
-                
-                return (
-                    StatusCode::OK,
-                    Json(ConvertResult {
-                        result: Some(cached_result),
-                        error: None,
-                    }),
-                );
-            }
+    #[cfg(feature = "scripting")]
+    let builder = match args.transform {
+        Some(path) => {
+            eprintln!("[CLI] Loading transform script: {:?}", path);
+            builder.with_transform_script(toonify::scripting::ScriptTransform::from_file(&path)?)
         }
+        None => builder,
+    };
+    
+    #[cfg(not(feature = "scripting"))]
+    if args.transform.is_some() {
+        return Err("--transform requires the 'scripting' feature".into());
     }
     
-    // Cache miss - perform conversion
-    match converter::json_to_toon(&payload.data) {
-        Ok(result) => {
-            // Store in both Moka and Sled (write-through)
-            #[cfg(feature = "cache")]
-            if let Some(ref moka) = cache_state.moka {
-                moka.insert(cache_key.clone(), result.clone()).await;
-            }
-            
-            #[cfg(feature = "persistent-cache")]
-            if let Some(ref sled) = cache_state.sled {
-                let _ = sled.insert(cache_key.as_bytes(), result.as_bytes());
-            }
-            
-            (
-            StatusCode::OK,
-            Json(ConvertResult {
-                result: Some(result),
-                error: None,
-            }),
-            )
-        }
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ConvertResult {
-                result: None,
-                error: Some(e),
-            }),
-        ),
+    // Before pseudonymizing, so " Alice" and "alice" get the same pseudonym once cleaned
+    let builder = if args.sanitize.is_empty() {
+        builder
+    } else {
+        let sanitizers = toonify::sanitize::Sanitizers::parse(&args.sanitize)?;
+        eprintln!("[CLI] Sanitizing fields: {}", sanitizers.describe().join(" "));
+        builder.sanitize(sanitizers)
+    };
+    
+    #[cfg(feature = "pseudonymize")]
+    let builder = match (args.pseudonymize, args.salt) {
+        (Some(spec), Some(salt)) => {
+            let salt = match salt.strip_prefix("env:") {
+                Some(var) => std::env::var(var).map_err(|_| format!("Salt variable {} is not set", var))?,
+                None => salt,
+            };
+            let fields = toonify::pseudonymize::Pseudonymizer::parse_fields(&spec)?;
+            eprintln!("[CLI] Pseudonymizing fields: {}", fields.join(", "));
+            builder.pseudonymize(toonify::pseudonymize::Pseudonymizer::new(&fields, salt.as_bytes()))
+        }
+        _ => builder,
+    };
+    
+    #[cfg(not(feature = "pseudonymize"))]
+    if args.pseudonymize.is_some() || args.salt.is_some() {
+        return Err("--pseudonymize requires the 'pseudonymize' feature".into());
     }
+    
+    Ok(builder.build())
 }
 
-async fn toon_to_json_handler(
-    axum::extract::State(app_state): axum::extract::State<AppState>,
-    Json(payload): Json<ConvertPayload>,
-) -> impl IntoResponse {
-    let cache_state = app_state.cache;
-    let cache_key = format!("toonify:toon_to_json:{}", payload.data);
-    
-    // Try Moka cache first (hot, lock-free, < 100ns)
-    #[cfg(feature = "cache")]
-    if let Some(ref moka) = cache_state.moka {
-        if let Some(cached_result) = moka.get(&cache_key).await {
-            eprintln!("[CACHE] Moka hit for toon-to-json");
-            return (
-                StatusCode::OK,
-                Json(ConvertResult {
-                    result: Some(cached_result),
-                    error: None,
-                }),
-            );
-        }
+// Cache key material for the pipeline options; transform scripts and type rules count by content
+#[cfg(feature = "cli-cache")]
+fn conversion_cache_options(args: &ConversionArgs) -> Result<String, Box<dyn std::error::Error>> {
+    let read = |path: &Option<PathBuf>| match path {
+        Some(path) => fs::read_to_string(path),
+        None => Ok(String::new()),
+    };
+    Ok(format!("{:?}\n{}\n{}", args, read(&args.transform)?, read(&args.types)?))
+}
+
+// Convert and print any parser warnings to stderr
+fn convert_reporting_warnings(
+    converter: &converter::Converter,
+    content: &str,
+    source_format: &str,
+    target_format: &str,
+) -> Result<String, String> {
+    let converted = converter.convert_detailed(content, source_format, target_format)?;
+    for warning in &converted.warnings {
+        eprintln!("[WARN] {}", warning);
     }
-    
-    // Try Sled persistent cache if enabled (cold, ~1ms)
-    #[cfg(feature = "persistent-cache")]
-    if let Some(ref sled) = cache_state.sled {
-        if let Ok(Some(cached_bytes)) = sled.get(cache_key.as_bytes()) {
-            if let Ok(cached_result) = String::from_utf8(cached_bytes.to_vec()) {
-                eprintln!("[CACHE] Sled hit for toon-to-json");
-                
-                // Warm up Moka cache from Sled
-                #[cfg(feature = "cache")]
-                if let Some(ref moka) = cache_state.moka {
-                    moka.insert(cache_key.clone(), cached_result.clone()).await;
-                }
-                
-                return (
-                    StatusCode::OK,
-                    Json(ConvertResult {
-                        result: Some(cached_result),
-                        error: None,
-                    }),
-                );
-            }
-        }
+    Ok(converted.output)
+}
+
+// --optimize-tokens: TOON in the table layout that tokenizes shortest, reporting the savings
+fn convert_optimizing_tokens(
+    converter: &converter::Converter,
+    content: &str,
+    source_format: &str,
+    tokenizer: Tokenizer,
+) -> Result<String, String> {
+    let (value, warnings) = converter.convert_to_value(content, source_format)?;
+    for warning in &warnings {
+        eprintln!("[WARN] {}", warning);
     }
     
-    // Cache miss - perform conversion
-    match converter::toon_to_json(&payload.data) {
-        Ok(result) => {
-            // Store in both Moka and Sled (write-through)
-            #[cfg(feature = "cache")]
-            if let Some(ref moka) = cache_state.moka {
-                moka.insert(cache_key.clone(), result.clone()).await;
-            }
-            
-            #[cfg(feature = "persistent-cache")]
-            if let Some(ref sled) = cache_state.sled {
-                let _ = sled.insert(cache_key.as_bytes(), result.as_bytes());
-            }
-            
-            (
-            StatusCode::OK,
-            Json(ConvertResult {
-                result: Some(result),
-                error: None,
-            }),
-            )
-        }
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ConvertResult {
-                result: None,
-                error: Some(e),
-            }),
-        ),
+    let optimized = toonify::toon::optimize_tokens(&value, converter.toon_options(), &|text| tokenizer.count(text))?;
+    for table in &optimized.tables {
+        eprintln!(
+            "[TOKENS] {}: columns {}{}{}",
+            table.entity,
+            table.columns.join(","),
+            if table.spaced { ", cells separated by \", \"" } else { "" },
+            if table.bare_colons { ", colons unquoted" } else { "" },
+        );
     }
+    eprintln!(
+        "[TOKENS] {} tokens ({}), {} fewer than the default layout ({:.1}%)",
+        optimized.tokens,
+        tokenizer.as_str(),
+        optimized.saved(),
+        optimized.saved_percent(),
+    );
+    Ok(optimized.output)
 }
 
-fn detect_format(content: &str) -> Result<&'static str, String> {
-    let trimmed = content.trim();
-    
-    // Try to parse as JSON first
-    if trimmed.starts_with('{') || trimmed.starts_with('[') {
-        match serde_json::from_str::<serde_json::Value>(trimmed) {
-            Ok(_) => return Ok("json"),
-            Err(_) => {}
+// Format selection, conversion pipeline and output writing shared by batch and watch
+struct FileConversion {
+    from: Option<String>,
+    to: Option<String>,
+    converter: converter::Converter,
+    fsync: bool,
+    preserve_metadata: bool,
+    gzip: bool,
+}
+
+impl FileConversion {
+    // Same-format conversions copy the input verbatim unless hooks need to run
+    fn convert(&self, content: &str, source_format: &str, target_format: &str) -> Result<String, String> {
+        if source_format.eq_ignore_ascii_case(target_format) && !self.converter.has_hooks() {
+            Ok(content.to_string())
+        } else {
+            convert_reporting_warnings(&self.converter, content, source_format, target_format)
         }
     }
     
-    // Check for TOON format patterns
-    if trimmed.contains("{") && trimmed.contains("}:") {
-        return Ok("toon");
-    }
-    
-    // Default: try to parse as JSON
-    match serde_json::from_str::<serde_json::Value>(trimmed) {
-        Ok(_) => Ok("json"),
-        Err(_) => Ok("toon"), // Assume TOON if JSON parse fails
+    // Temp file + rename so an interrupted run never leaves a truncated output
+    fn write_output(&self, source: &Path, path: &Path, content: &str) -> io::Result<()> {
+        let metadata = if self.preserve_metadata { Some(fs::metadata(source)?) } else { None };
+        let bytes = if self.gzip { Cow::Owned(gzip_bytes(content.as_bytes())?) } else { Cow::Borrowed(content.as_bytes()) };
+        atomic_write::write_atomic(path, &bytes, self.fsync, metadata.as_ref())
     }
 }
 
+fn gzip_bytes(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoderWrite::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
 fn run_compress(input: Option<PathBuf>, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
     eprintln!("[COMPRESS] Starting compression...");
     
@@ -577,10 +1441,15 @@ fn run_compress(input: Option<PathBuf>, output: Option<PathBuf>) -> Result<(), B
     
     eprintln!("[COMPRESS] Input size: {} bytes", input_data.len());
     
-    // Compress using gzip
+    // Compress using gzip, in chunks so large inputs can report progress
+    let progress = ByteProgress::new("[COMPRESS]", input_data.len() as u64);
     let mut encoder = GzEncoderWrite::new(Vec::new(), Compression::default());
-    encoder.write_all(&input_data)?;
+    for chunk in input_data.chunks(1 << 20) {
+        encoder.write_all(chunk)?;
+        progress.inc(chunk.len() as u64);
+    }
     let compressed_data = encoder.finish()?;
+    progress.finish();
     
     eprintln!("[COMPRESS] Compressed size: {} bytes", compressed_data.len());
     let ratio = (1.0 - (compressed_data.len() as f64 / input_data.len() as f64)) * 100.0;
@@ -600,42 +1469,448 @@ fn run_compress(input: Option<PathBuf>, output: Option<PathBuf>) -> Result<(), B
     Ok(())
 }
 
-fn run_decompress(input: Option<PathBuf>, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
-    eprintln!("[DECOMPRESS] Starting decompression...");
-    
-    // Read input
-    let compressed_data = if let Some(input_path) = input {
-        eprintln!("[DECOMPRESS] Reading from file: {:?}", input_path);
-        fs::read(&input_path)?
-    } else {
-        eprintln!("[DECOMPRESS] Reading from STDIN");
-        let mut buffer = Vec::new();
-        io::stdin().read_to_end(&mut buffer)?;
-        buffer
+fn run_decompress(input: Option<PathBuf>, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!("[DECOMPRESS] Starting decompression...");
+    
+    // Read input
+    let compressed_data = if let Some(input_path) = input {
+        eprintln!("[DECOMPRESS] Reading from file: {:?}", input_path);
+        fs::read(&input_path)?
+    } else {
+        eprintln!("[DECOMPRESS] Reading from STDIN");
+        let mut buffer = Vec::new();
+        io::stdin().read_to_end(&mut buffer)?;
+        buffer
+    };
+    
+    eprintln!("[DECOMPRESS] Compressed size: {} bytes", compressed_data.len());
+    
+    // Decompress using gzip; progress counts compressed bytes consumed
+    let progress = ByteProgress::new("[DECOMPRESS]", compressed_data.len() as u64);
+    let mut decoder = GzDecoder::new(ProgressReader { inner: &compressed_data[..], progress: &progress });
+    let mut decompressed_data = Vec::new();
+    decoder.read_to_end(&mut decompressed_data)?;
+    progress.finish();
+    
+    eprintln!("[DECOMPRESS] Decompressed size: {} bytes", decompressed_data.len());
+    let ratio = (decompressed_data.len() as f64 / compressed_data.len() as f64 - 1.0) * 100.0;
+    eprintln!("[DECOMPRESS] Expansion ratio: {:.2}%", ratio);
+    
+    // Write output
+    if let Some(output_path) = output {
+        eprintln!("[DECOMPRESS] Writing to file: {:?}", output_path);
+        fs::write(output_path, decompressed_data)?;
+        eprintln!("[DECOMPRESS] File written successfully");
+    } else {
+        eprintln!("[DECOMPRESS] Writing to STDOUT");
+        io::stdout().write_all(&decompressed_data)?;
+        io::stdout().flush()?;
+    }
+    
+    Ok(())
+}
+
+#[cfg(any(feature = "protobuf", feature = "avro"))]
+fn run_encode(schema_path: &Path, message: Option<&str>, input: Option<PathBuf>, output: Option<PathBuf>, converter: &converter::Converter) -> Result<(), Box<dyn std::error::Error>> {
+    let schema = toonify::binary::BinarySchema::load(schema_path, message)?;
+    eprintln!("[ENCODE] Schema loaded: {:?}", schema_path);
+    
+    let content = if let Some(input_path) = &input {
+        eprintln!("[ENCODE] Reading from file: {:?}", input_path);
+        fs::read_to_string(input_path)?
+    } else {
+        eprintln!("[ENCODE] Reading from STDIN");
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer
+    };
+    let source_format = converter::detect_format(input.as_deref(), content.as_bytes())?;
+    let (value, warnings) = converter.convert_to_value(&content, source_format)?;
+    for warning in &warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    
+    let encoded = schema.encode(&value)?;
+    eprintln!("[ENCODE] {} bytes of {} → {} bytes", content.len(), source_format.to_uppercase(), encoded.len());
+    
+    if let Some(output_path) = output {
+        eprintln!("[ENCODE] Writing to file: {:?}", output_path);
+        fs::write(output_path, encoded)?;
+    } else {
+        io::stdout().write_all(&encoded)?;
+        io::stdout().flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(any(feature = "protobuf", feature = "avro"))]
+fn run_decode(schema_path: &Path, message: Option<&str>, input: Option<PathBuf>, output: Option<PathBuf>, to: &str, converter: &converter::Converter) -> Result<(), Box<dyn std::error::Error>> {
+    let schema = toonify::binary::BinarySchema::load(schema_path, message)?;
+    eprintln!("[DECODE] Schema loaded: {:?}", schema_path);
+    
+    let bytes = if let Some(input_path) = input {
+        eprintln!("[DECODE] Reading from file: {:?}", input_path);
+        fs::read(&input_path)?
+    } else {
+        eprintln!("[DECODE] Reading from STDIN");
+        let mut buffer = Vec::new();
+        io::stdin().read_to_end(&mut buffer)?;
+        buffer
+    };
+    
+    let value = converter.transform(schema.decode(&bytes)?)?;
+    let content = converter.emit(&value, to)?;
+    eprintln!("[DECODE] {} bytes → {} bytes of {}", bytes.len(), content.len(), to.to_uppercase());
+    
+    if let Some(output_path) = output {
+        eprintln!("[DECODE] Writing to file: {:?}", output_path);
+        fs::write(output_path, content)?;
+    } else {
+        io::stdout().write_all(content.as_bytes())?;
+        io::stdout().flush()?;
+    }
+    Ok(())
+}
+
+fn run_merge(inputs: Vec<PathBuf>, strategy: toonify::merge::MergeStrategy, output: Option<PathBuf>, to: &str, converter: &converter::Converter) -> Result<(), Box<dyn std::error::Error>> {
+    let mut documents = Vec::with_capacity(inputs.len());
+    for input_path in &inputs {
+        eprintln!("[MERGE] Reading from file: {:?}", input_path);
+        let content = fs::read_to_string(input_path)?;
+        let (value, warnings) = converter.convert_to_value(&content, converter::detect_format(Some(input_path.as_path()), content.as_bytes())?)?;
+        for warning in &warnings {
+            eprintln!("[WARN] {}: {}", input_path.display(), warning);
+        }
+        documents.push(value);
+    }
+    
+    let (merged, warnings) = toonify::merge::merge(documents, strategy)?;
+    for warning in &warnings {
+        eprintln!("[MERGE] {}", warning);
+    }
+    let content = converter.emit(&merged, to)?;
+    eprintln!("[MERGE] {} documents merged ({})", inputs.len(), strategy.as_str());
+    
+    if let Some(output_path) = output {
+        eprintln!("[MERGE] Writing to file: {:?}", output_path);
+        fs::write(output_path, content)?;
+    } else {
+        println!("{}", content);
+    }
+    Ok(())
+}
+
+fn run_split(input: Option<PathBuf>, output_dir: &Path, to: &str, converter: &converter::Converter) -> Result<(), Box<dyn std::error::Error>> {
+    let content = if let Some(input_path) = &input {
+        eprintln!("[SPLIT] Reading from file: {:?}", input_path);
+        fs::read_to_string(input_path)?
+    } else {
+        eprintln!("[SPLIT] Reading from STDIN");
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer
+    };
+    let (value, warnings) = converter.convert_to_value(&content, converter::detect_format(input.as_deref(), content.as_bytes())?)?;
+    for warning in &warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    
+    fs::create_dir_all(output_dir)?;
+    let parts = toonify::merge::split(value)?;
+    let mut used = std::collections::HashSet::new();
+    for (name, document) in &parts {
+        // Entity names come from the input, so keep them out of other directories
+        let sanitized: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+        // `a.b` and `a_b` both sanitize to `a_b`; number the later ones
+        let mut file_name = sanitized.clone();
+        let mut suffix = 2;
+        while !used.insert(file_name.clone()) {
+            file_name = format!("{}_{}", sanitized, suffix);
+            suffix += 1;
+        }
+        if file_name != sanitized {
+            eprintln!("[SPLIT] {:?} collides with another entity's file name; writing it as {}", name, file_name);
+        }
+        let path = output_dir.join(format!("{}.{}", file_name, to));
+        eprintln!("[SPLIT] Writing {} to {:?}", name, path);
+        fs::write(&path, converter.emit(document, to)?)?;
+    }
+    println!("✓ Split {} entities into {:?}", parts.len(), output_dir);
+    Ok(())
+}
+
+fn run_chunk(input: Option<PathBuf>, max_tokens: usize, output_dir: &Path, tokenizer: Tokenizer, converter: &converter::Converter) -> Result<(), Box<dyn std::error::Error>> {
+    let content = if let Some(input_path) = &input {
+        eprintln!("[CHUNK] Reading from file: {:?}", input_path);
+        fs::read_to_string(input_path)?
+    } else {
+        eprintln!("[CHUNK] Reading from STDIN");
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer
+    };
+    let (value, warnings) = converter.convert_to_value(&content, converter::detect_format(input.as_deref(), content.as_bytes())?)?;
+    for warning in &warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    
+    let chunks = toonify::chunk::chunk(&value, max_tokens, converter.toon_options(), &|text| tokenizer.count(text))?;
+    fs::create_dir_all(output_dir)?;
+    for (index, chunk) in chunks.iter().enumerate() {
+        let path = output_dir.join(format!("chunk-{:04}.toon", index + 1));
+        eprintln!("[CHUNK] Writing {:?} ({} tokens, {})", path, chunk.tokens, tokenizer.as_str());
+        fs::write(&path, &chunk.output)?;
+    }
+    println!("✓ Wrote {} chunks of at most {} tokens into {:?}", chunks.len(), max_tokens, output_dir);
+    Ok(())
+}
+
+fn run_gen_client(lang: toonify::client_gen::ClientLang, out: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let files = toonify::client_gen::generate(lang);
+    for file in &files {
+        let path = out.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        eprintln!("[GEN-CLIENT] Writing {:?}", path);
+        fs::write(&path, &file.contents)?;
+    }
+    println!("✓ Wrote {} files for a {:?} client into {:?}", files.len(), lang, out);
+    Ok(())
+}
+
+fn run_generate(schema_path: &Path, rows: usize, seed: Option<u64>, to: &str, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!("[GENERATE] Reading schema from: {:?}", schema_path);
+    let schema: serde_json::Value = serde_json::from_str(&fs::read_to_string(schema_path)?)
+        .map_err(|e| format!("Invalid schema JSON: {}", e))?;
+    
+    let mut options = toonify::generate::GenerateOptions { rows, ..Default::default() };
+    if let Some(seed) = seed {
+        options.seed = seed;
+    }
+    let (value, warnings) = toonify::generate::generate(&schema, &options)?;
+    for warning in &warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    let content = converter::registry().emit(&value, to)?;
+    eprintln!("[GENERATE] {} rows per entity (seed {})", rows, options.seed);
+    
+    if let Some(output_path) = output {
+        eprintln!("[GENERATE] Writing to file: {:?}", output_path);
+        fs::write(output_path, content)?;
+    } else {
+        println!("{}", content);
+    }
+    Ok(())
+}
+
+fn run_summarize(input: Option<PathBuf>, rows: usize, from: Option<String>, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let input = input.filter(|path| path.as_os_str() != "-");
+    let content = match &input {
+        Some(input_path) => {
+            eprintln!("[SUMMARIZE] Reading from file: {:?}", input_path);
+            fs::read_to_string(input_path)?
+        }
+        None => {
+            eprintln!("[SUMMARIZE] Reading from STDIN");
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        }
+    };
+    let source_format = match from {
+        Some(f) => f,
+        None => converter::detect_format(input.as_deref(), content.as_bytes())?.to_string(),
+    };
+    let value = converter::registry().parse(&content, &source_format)?;
+    let summary = toonify::summarize::summarize(&value, rows)?;
+    eprintln!("[SUMMARIZE] {} bytes in, {} bytes out", content.len(), summary.len());
+    
+    match output {
+        Some(output_path) => fs::write(output_path, summary)?,
+        None => io::stdout().write_all(summary.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn run_profile(input: Option<PathBuf>, from: Option<String>, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let input = input.filter(|path| path.as_os_str() != "-");
+    let content = match &input {
+        Some(input_path) => {
+            eprintln!("[PROFILE] Reading from file: {:?}", input_path);
+            fs::read_to_string(input_path)?
+        }
+        None => {
+            eprintln!("[PROFILE] Reading from STDIN");
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        }
+    };
+    let source_format = match from {
+        Some(f) => f,
+        None => converter::detect_format(input.as_deref(), content.as_bytes())?.to_string(),
+    };
+    let value = converter::registry().parse(&content, &source_format)?;
+    let profiles = toonify::profile::profile(&value);
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&profiles)?);
+        return Ok(());
+    }
+    if profiles.is_empty() {
+        println!("No table entities to profile");
+        return Ok(());
+    }
+    
+    let cell = |value: &Option<serde_json::Value>| match value {
+        Some(serde_json::Value::String(s)) if s.chars().count() > 24 => format!("{}…", s.chars().take(23).collect::<String>()),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+        None => "-".to_string(),
+    };
+    for entity in &profiles {
+        println!("{} ({} rows)", entity.name, entity.rows);
+        let mut lines = vec![["column", "type", "nulls", "distinct", "min", "max", "avg len"].map(String::from).to_vec()];
+        for column in &entity.columns {
+            lines.push(vec![
+                column.name.clone(),
+                column.inferred_type.clone(),
+                format!("{:.1}%", column.null_rate * 100.0),
+                column.distinct.to_string(),
+                cell(&column.min),
+                cell(&column.max),
+                column.avg_length.map_or("-".to_string(), |len| format!("{:.1}", len)),
+            ]);
+        }
+        let widths: Vec<usize> = (0..7).map(|i| lines.iter().map(|line| line[i].chars().count()).max().unwrap_or(0)).collect();
+        for line in &lines {
+            let padded: Vec<String> = line.iter().zip(&widths).map(|(text, width)| format!("{:<width$}", text, width = *width)).collect();
+            println!("  {}", padded.join("  ").trim_end());
+        }
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(feature = "tui")]
+fn run_view(input: PathBuf, from: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    if !io::stdout().is_terminal() {
+        return Err("view requires an interactive terminal".into());
+    }
+    
+    let content = fs::read_to_string(&input)?;
+    let registry = converter::registry();
+    let source_format = match from {
+        Some(f) => f,
+        None => converter::detect_format(Some(input.as_path()), content.as_bytes())?.to_string(),
+    };
+    
+    let value = registry.parse(&content, &source_format)?;
+    tui::run(&value, &input.display().to_string())?;
+    Ok(())
+}
+
+fn run_fmt(inputs: Vec<PathBuf>, check: bool, options: toonify::toon::FormatOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if inputs.is_empty() {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        let formatted = toonify::toon::format_toon(&buffer, &options)?;
+        
+        if check {
+            let diff = toonify::toon::format::diff_lines(&buffer, &formatted);
+            if !diff.is_empty() {
+                eprint!("--- <stdin>\n+++ <stdin> (formatted)\n{}", diff);
+                return Err("STDIN is not formatted".into());
+            }
+        } else {
+            io::stdout().write_all(formatted.as_bytes())?;
+            io::stdout().flush()?;
+        }
+        return Ok(());
+    }
+    
+    let mut unformatted = 0;
+    for path in &inputs {
+        let content = fs::read_to_string(path)?;
+        let formatted = toonify::toon::format_toon(&content, &options)
+            .map_err(|e| format!("{:?}: {}", path, e))?;
+        
+        if formatted == content {
+            continue;
+        }
+        
+        if check {
+            unformatted += 1;
+            eprint!("--- {}\n+++ {} (formatted)\n{}", path.display(), path.display(), toonify::toon::format::diff_lines(&content, &formatted));
+        } else {
+            fs::write(path, formatted)?;
+            eprintln!("[FMT] Formatted {:?}", path);
+        }
+    }
+    
+    if unformatted > 0 {
+        return Err(format!("{} file(s) need formatting", unformatted).into());
+    }
+    
+    Ok(())
+}
+
+fn run_selftest(inputs: Vec<PathBuf>, cases: usize, seed: Option<u64>, typed_headers: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut options = toonify::selftest::SelftestOptions {
+        cases,
+        serialize: toonify::toon::SerializeOptions { typed_headers, ..Default::default() },
+        ..Default::default()
+    };
+    if let Some(seed) = seed {
+        options.seed = seed;
+    }
+    
+    eprintln!("[SELFTEST] Running {} generated cases (seed {})", options.cases, options.seed);
+    let report = toonify::selftest::run(&options);
+    let mut failures = report.failures.len();
+    for failure in &report.failures {
+        eprintln!("[SELFTEST] FAIL {}", failure);
+    }
+    eprintln!("[SELFTEST] Generated: {}/{} passed", report.cases - report.failures.len(), report.cases);
+    
+    for path in &inputs {
+        let content = fs::read_to_string(path)?;
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid JSON in {:?}: {}", path, e))?;
+        match toonify::selftest::check_roundtrip_with(&value, &options.serialize) {
+            Ok(()) => eprintln!("[SELFTEST] OK   {:?}", path),
+            Err(e) => {
+                failures += 1;
+                eprintln!("[SELFTEST] FAIL {:?}: {}", path, e);
+            }
+        }
+    }
+    
+    if failures > 0 {
+        return Err(format!("Self test failed: {} failure(s)", failures).into());
+    }
+    
+    eprintln!("[SELFTEST] All checks passed");
+    Ok(())
+}
+
+fn run_corpus(dir: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let cases = match &dir {
+        Some(dir) => toonify::corpus::load_dir(dir)?,
+        None => toonify::corpus::builtin(),
     };
+    eprintln!("[CORPUS] Running {} cases ({})", cases.len(), dir.as_ref().map_or("built-in".to_string(), |dir| format!("{:?}", dir)));
     
-    eprintln!("[DECOMPRESS] Compressed size: {} bytes", compressed_data.len());
-    
-    // Decompress using gzip
-    let mut decoder = GzDecoder::new(&compressed_data[..]);
-    let mut decompressed_data = Vec::new();
-    decoder.read_to_end(&mut decompressed_data)?;
-    
-    eprintln!("[DECOMPRESS] Decompressed size: {} bytes", decompressed_data.len());
-    let ratio = (decompressed_data.len() as f64 / compressed_data.len() as f64 - 1.0) * 100.0;
-    eprintln!("[DECOMPRESS] Expansion ratio: {:.2}%", ratio);
-    
-    // Write output
-    if let Some(output_path) = output {
-        eprintln!("[DECOMPRESS] Writing to file: {:?}", output_path);
-        fs::write(output_path, decompressed_data)?;
-        eprintln!("[DECOMPRESS] File written successfully");
-    } else {
-        eprintln!("[DECOMPRESS] Writing to STDOUT");
-        io::stdout().write_all(&decompressed_data)?;
-        io::stdout().flush()?;
+    let report = toonify::corpus::run(&cases);
+    for (name, failure) in &report.failures {
+        eprintln!("[CORPUS] FAIL {}: {}", name, failure);
+    }
+    if !report.passed() {
+        return Err(format!("Corpus failed: {} of {} cases", report.failures.len(), report.cases).into());
     }
     
+    println!("✓ {} corpus cases passed (toonify {}, {})", report.cases, env!("CARGO_PKG_VERSION"), build_info::current().target);
     Ok(())
 }
 
@@ -665,10 +1940,8 @@ fn run_validate(schema_path: PathBuf, input: Option<PathBuf>) -> Result<(), Box<
     
     // Convert TOON to JSON for validation
     eprintln!("[VALIDATE] Parsing TOON data...");
-    let json_value = converter::toon_to_json(&toon_data)
+    let parsed_value = converter::toon_to_value(&toon_data)
         .map_err(|e| format!("Failed to parse TOON: {}", e))?;
-    
-    let parsed_value: serde_json::Value = serde_json::from_str(&json_value)?;
     eprintln!("[VALIDATE] TOON parsed successfully");
     
     // Validate against schema
@@ -681,411 +1954,313 @@ fn run_validate(schema_path: PathBuf, input: Option<PathBuf>) -> Result<(), Box<
     Ok(())
 }
 
-fn validate_value(value: &serde_json::Value, schema: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
-    eprintln!("[VALIDATE] Validating data structure...");
-    
-    let schema_obj = schema.as_object()
-        .ok_or("Schema must be a JSON object")?;
+#[allow(clippy::too_many_arguments)]
+fn run_convert(input: String, output: Option<PathBuf>, from: Option<String>, to: Option<String>, entity: Option<String>, color: ColorMode, cache_dir: Option<PathBuf>, gzip: bool, optimize_tokens: Option<Tokenizer>, signing: SigningArgs, conversion: ConversionArgs) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "cli-cache")]
+    let cache_options = cache_dir.as_ref().map(|_| conversion_cache_options(&conversion)).transpose()?;
+    #[cfg(not(feature = "cli-cache"))]
+    if cache_dir.is_some() {
+        return Err("--cache-dir requires the 'cli-cache' feature".into());
+    }
+    let converter = build_converter(conversion)?;
     
-    let value_obj = value.as_object()
-        .ok_or("TOON data must represent an object")?;
+    eprintln!("[CLI] Reading input...");
     
-    // Validate each entity in schema
-    for (entity_name, entity_schema) in schema_obj {
-        eprintln!("[VALIDATE] Validating entity: {}", entity_name);
-        
-        if !value_obj.contains_key(entity_name) {
-            return Err(format!("Missing entity '{}' in TOON data", entity_name).into());
+    // Read input; gzip input is decompressed first
+    let input_path = Some(Path::new(&input)).filter(|_| input != "-");
+    let input_content = match input_path {
+        None => {
+            eprintln!("[CLI] Reading from STDIN");
+            let mut buffer = Vec::new();
+            io::stdin().read_to_end(&mut buffer)?;
+            InputText::Owned(decode_input(buffer)?)
         }
-        
-        let entity_value = &value_obj[entity_name];
-        validate_entity(entity_name, entity_value, entity_schema)?;
-    }
+        Some(path) => {
+            eprintln!("[CLI] Reading from file: {}", input);
+            read_convert_input(path)?
+        }
+    };
     
-    eprintln!("[VALIDATE] All entities validated successfully");
-    Ok(())
-}
-
-fn validate_entity(name: &str, value: &serde_json::Value, schema: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
-    let schema_obj = schema.as_object()
-        .ok_or(format!("Schema for '{}' must be an object", name))?;
+    eprintln!("[CLI] Input size: {} bytes{}", input_content.len(), if input_content.is_mapped() { " (memory-mapped)" } else { "" });
     
-    // Check type
-    let entity_type = schema_obj.get("type")
-        .and_then(|v| v.as_str())
-        .ok_or(format!("Schema for '{}' must have 'type' field", name))?;
+    // Detect format unless given explicitly
+    let source_format = match from {
+        Some(f) => f,
+        None => converter::detect_format(input_path, input_content.as_bytes())?.to_string(),
+    };
+    eprintln!("[CLI] Detected format: {}", source_format);
     
-    eprintln!("[VALIDATE] Entity '{}' type: {}", name, entity_type);
+    let target_format = to.unwrap_or_else(|| default_target_format(&source_format).to_string());
     
-    match entity_type {
-        "array" => {
-            let array = value.as_array()
-                .ok_or(format!("Entity '{}' must be an array", name))?;
-            
-            eprintln!("[VALIDATE] Array '{}' has {} items", name, array.len());
-            
-            // Check min/max items
-            if let Some(min_items) = schema_obj.get("min_items").and_then(|v| v.as_u64()) {
-                eprintln!("[VALIDATE] Checking min_items: {} (actual: {})", min_items, array.len());
-                if (array.len() as u64) < min_items {
-                    return Err(format!(
-                        "Entity '{}' has {} items but minimum is {}",
-                        name, array.len(), min_items
-                    ).into());
-                }
-            }
-            
-            if let Some(max_items) = schema_obj.get("max_items").and_then(|v| v.as_u64()) {
-                eprintln!("[VALIDATE] Checking max_items: {} (actual: {})", max_items, array.len());
-                if (array.len() as u64) > max_items {
-                    return Err(format!(
-                        "Entity '{}' has {} items but maximum is {}",
-                        name, array.len(), max_items
-                    ).into());
-                }
-            }
-            
-            // Get required fields
-            let required_fields = schema_obj.get("fields")
-                .and_then(|v| v.as_array())
-                .ok_or(format!("Schema for '{}' must have 'fields' array", name))?;
-            
-            let required_field_names: Vec<String> = required_fields
-                .iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect();
-            
-            eprintln!("[VALIDATE] Required fields: {:?}", required_field_names);
-            
-            // Get field types if specified
-            let field_types = schema_obj.get("field_types")
-                .and_then(|v| v.as_object());
-            
-            // Get advanced validation constraints
-            let patterns = schema_obj.get("patterns")
-                .and_then(|v| v.as_object());
-            let ranges = schema_obj.get("ranges")
-                .and_then(|v| v.as_object());
-            let string_lengths = schema_obj.get("string_lengths")
-                .and_then(|v| v.as_object());
-            let enums = schema_obj.get("enums")
-                .and_then(|v| v.as_object());
-            let formats = schema_obj.get("formats")
-                .and_then(|v| v.as_object());
-            
-            // Validate each item in array
-            for (idx, item) in array.iter().enumerate() {
-                eprintln!("[VALIDATE] Validating item {}", idx);
-                
-                let item_obj = item.as_object()
-                    .ok_or(format!("Item {} in '{}' must be an object", idx, name))?;
-                
-                // Check all required fields are present
-                for field_name in &required_field_names {
-                    if !item_obj.contains_key(field_name) {
-                        return Err(format!(
-                            "Item {} in '{}' is missing required field '{}'",
-                            idx, name, field_name
-                        ).into());
-                    }
-                    
-                    let field_value = &item_obj[field_name];
-                    
-                    // Check field type if specified
-                    if let Some(types) = field_types {
-                        if let Some(expected_type) = types.get(field_name).and_then(|v| v.as_str()) {
-                            validate_field_type(name, idx, field_name, field_value, expected_type)?;
-                        }
-                    }
-                    
-                    // Check regex pattern if specified
-                    if let Some(patterns_map) = patterns {
-                        if let Some(pattern_str) = patterns_map.get(field_name).and_then(|v| v.as_str()) {
-                            validate_pattern(name, idx, field_name, field_value, pattern_str)?;
-                        }
-                    }
-                    
-                    // Check number range if specified
-                    if let Some(ranges_map) = ranges {
-                        if let Some(range_obj) = ranges_map.get(field_name).and_then(|v| v.as_object()) {
-                            validate_range(name, idx, field_name, field_value, range_obj)?;
-                        }
-                    }
-                    
-                    // Check string length if specified
-                    if let Some(lengths_map) = string_lengths {
-                        if let Some(length_obj) = lengths_map.get(field_name).and_then(|v| v.as_object()) {
-                            validate_string_length(name, idx, field_name, field_value, length_obj)?;
-                        }
-                    }
-                    
-                    // Check enum values if specified
-                    if let Some(enums_map) = enums {
-                        if let Some(allowed_values) = enums_map.get(field_name).and_then(|v| v.as_array()) {
-                            validate_enum(name, idx, field_name, field_value, allowed_values)?;
-                        }
-                    }
-                    
-                    // Check custom format if specified
-                    if let Some(formats_map) = formats {
-                        if let Some(format_type) = formats_map.get(field_name).and_then(|v| v.as_str()) {
-                            validate_format(name, idx, field_name, field_value, format_type)?;
-                        }
-                    }
-                }
-            }
-            
-            eprintln!("[VALIDATE] Array '{}' validated successfully", name);
+    if entity.is_some() && target_format != "arrow" {
+        return Err("--entity only applies to --to arrow".into());
+    }
+    
+    if optimize_tokens.is_some() && target_format != "toon" {
+        return Err("--optimize-tokens only applies to TOON output".into());
+    }
+    
+    // A footer line is only skipped by the TOON reader; anything else needs a detached file
+    if signing.sign_footer && target_format != "toon" {
+        return Err(format!("--sign-footer only applies to TOON output; use --signature for --to {}", target_format).into());
+    }
+    
+    if ["html", "xlsx", "arrow"].contains(&target_format.as_str()) {
+        if gzip {
+            return Err(format!("--gzip doesn't apply to --to {} output", target_format).into());
         }
-        _ => {
-            return Err(format!("Unsupported entity type: {}", entity_type).into());
+        if signing.sign_key.is_some() {
+            return Err(format!("--sign-key can't sign --to {} output", target_format).into());
         }
+        return export_document(&converter, &input_content, &source_format, &target_format, entity.as_deref(), output);
     }
     
-    Ok(())
-}
-
-fn validate_field_type(entity: &str, idx: usize, field: &str, value: &serde_json::Value, expected_type: &str) -> Result<(), Box<dyn std::error::Error>> {
-    eprintln!("[VALIDATE] Checking field '{}' type: expected {}, got {:?}", field, expected_type, value);
-    
-    let matches = match expected_type {
-        "string" => value.is_string(),
-        "number" => value.is_number(),
-        "boolean" => value.is_boolean(),
-        "null" => value.is_null(),
-        _ => return Err(format!("Unknown type: {}", expected_type).into()),
+    #[cfg(feature = "cli-cache")]
+    let cache = match (cache_dir, &cache_options) {
+        (Some(dir), Some(options)) => {
+            let key = cli_cache::CliCache::key(&[
+                source_format.as_bytes(),
+                target_format.as_bytes(),
+                options.as_bytes(),
+                input_content.as_bytes(),
+            ]);
+            Some((cli_cache::CliCache::open(&dir)?, key))
+        }
+        _ => None,
     };
+    #[cfg(feature = "cli-cache")]
+    let cached = cache.as_ref().and_then(|(cache, key)| cache.get(key));
+    #[cfg(not(feature = "cli-cache"))]
+    let cached: Option<String> = None;
     
-    if !matches {
-        return Err(format!(
-            "Item {} in '{}': field '{}' has wrong type (expected {}, got {})",
-            idx,
-            entity,
-            field,
-            expected_type,
-            if value.is_string() { "string" }
-            else if value.is_number() { "number" }
-            else if value.is_boolean() { "boolean" }
-            else if value.is_null() { "null" }
-            else { "unknown" }
-        ).into());
-    }
+    // Convert
+    let output_content = match cached {
+        Some(content) => {
+            eprintln!("[CACHE] Hit, skipping conversion");
+            content
+        }
+        None => {
+            eprintln!("[CLI] Converting {} → {}", source_format.to_uppercase(), target_format.to_uppercase());
+            let content = match optimize_tokens {
+                Some(tokenizer) => convert_optimizing_tokens(&converter, &input_content, &source_format, tokenizer),
+                None => convert_reporting_warnings(&converter, &input_content, &source_format, &target_format),
+            };
+            let content = content.map_err(|e| format!("Conversion failed: {}", e))?;
+            #[cfg(feature = "cli-cache")]
+            if let Some((cache, key)) = &cache {
+                match cache.put(key, &content) {
+                    Ok(()) => eprintln!("[CACHE] Stored result"),
+                    Err(e) => eprintln!("[CACHE] Failed to store result: {}", e),
+                }
+            }
+            content
+        }
+    };
     
-    Ok(())
-}
-
-fn validate_pattern(entity: &str, idx: usize, field: &str, value: &serde_json::Value, pattern_str: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let string_value = value.as_str()
-        .ok_or(format!("Item {} in '{}': field '{}' must be a string for pattern matching", idx, entity, field))?;
+    eprintln!("[CLI] Conversion successful");
+    eprintln!("[CLI] Output size: {} bytes", output_content.len());
     
-    eprintln!("[VALIDATE] Checking pattern for field '{}': value='{}', pattern='{}'", field, string_value, pattern_str);
+    let (output_content, detached_signature) = sign_output(output_content, output.as_deref(), &signing)?;
     
-    let regex = Regex::new(pattern_str)
-        .map_err(|e| format!("Invalid regex pattern '{}': {}", pattern_str, e))?;
+    // Write output
+    if gzip {
+        let compressed = gzip_bytes(output_content.as_bytes())?;
+        eprintln!("[CLI] Compressed output: {} bytes", compressed.len());
+        match output {
+            Some(output_path) => {
+                eprintln!("[CLI] Writing to file: {:?}", output_path);
+                fs::write(output_path, compressed)?;
+            }
+            None if io::stdout().is_terminal() => {
+                return Err("Refusing to write gzip output to the terminal; use --output".into());
+            }
+            None => io::stdout().write_all(&compressed)?,
+        }
+    } else if let Some(output_path) = output {
+        eprintln!("[CLI] Writing to file: {:?}", output_path);
+        fs::write(output_path, output_content)?;
+        eprintln!("[CLI] File written successfully");
+    } else {
+        eprintln!("[CLI] Writing to STDOUT");
+        // Signed output must stay byte-exact, so it is never colored
+        let output_content = if color.enabled() && signing.sign_key.is_none() {
+            colorize(&output_content, &target_format)
+        } else {
+            output_content
+        };
+        io::stdout().write_all(output_content.as_bytes())?;
+        io::stdout().flush()?;
+    }
     
-    if !regex.is_match(string_value) {
-        return Err(format!(
-            "Item {} in '{}': field '{}' value '{}' does not match pattern '{}'",
-            idx, entity, field, string_value, pattern_str
-        ).into());
+    if let Some((path, signature)) = detached_signature {
+        fs::write(&path, signature)?;
+        eprintln!("[SIGN] Detached signature written to {:?}", path);
     }
     
-    eprintln!("[VALIDATE] Pattern match successful for '{}'", field);
     Ok(())
 }
 
-fn validate_range(entity: &str, idx: usize, field: &str, value: &serde_json::Value, range_obj: &serde_json::Map<String, serde_json::Value>) -> Result<(), Box<dyn std::error::Error>> {
-    let num_value = value.as_f64()
-        .ok_or(format!("Item {} in '{}': field '{}' must be a number for range validation", idx, entity, field))?;
-    
-    eprintln!("[VALIDATE] Checking range for field '{}': value={}", field, num_value);
+// Table exports (--to html|xlsx|arrow): not registry formats, they can't be parsed back
+fn export_document(converter: &converter::Converter, content: &str, source_format: &str, target_format: &str, entity: Option<&str>, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!("[CLI] Exporting {} → {}", source_format.to_uppercase(), target_format.to_uppercase());
+    let (value, warnings) = converter.convert_to_value(content, source_format)
+        .map_err(|e| format!("Conversion failed: {}", e))?;
+    for warning in &warnings {
+        eprintln!("[WARN] {}", warning);
+    }
     
-    if let Some(min_val) = range_obj.get("min").and_then(|v| v.as_f64()) {
-        eprintln!("[VALIDATE] Checking minimum: {} >= {}", num_value, min_val);
-        if num_value < min_val {
-            return Err(format!(
-                "Item {} in '{}': field '{}' value {} is below minimum {}",
-                idx, entity, field, num_value, min_val
-            ).into());
+    let bytes = match target_format {
+        #[cfg(feature = "xlsx")]
+        "xlsx" => toonify::export::value_to_xlsx(&value)?,
+        #[cfg(not(feature = "xlsx"))]
+        "xlsx" => return Err("--to xlsx requires the 'xlsx' feature".into()),
+        // Typed headers decide column types when the TOON reaches the writer unchanged
+        #[cfg(feature = "arrow")]
+        "arrow" if source_format == "toon" && !converter.has_hooks() => toonify::export::toon_to_arrow(content, entity)?,
+        #[cfg(feature = "arrow")]
+        "arrow" => toonify::export::value_to_arrow(&value, entity)?,
+        #[cfg(not(feature = "arrow"))]
+        "arrow" => {
+            let _ = entity;
+            return Err("--to arrow requires the 'arrow' feature".into());
         }
-    }
+        _ => toonify::export::value_to_html(&value).into_bytes(),
+    };
     
-    if let Some(max_val) = range_obj.get("max").and_then(|v| v.as_f64()) {
-        eprintln!("[VALIDATE] Checking maximum: {} <= {}", num_value, max_val);
-        if num_value > max_val {
-            return Err(format!(
-                "Item {} in '{}': field '{}' value {} exceeds maximum {}",
-                idx, entity, field, num_value, max_val
-            ).into());
+    match output {
+        Some(output_path) => {
+            eprintln!("[CLI] Writing to file: {:?}", output_path);
+            fs::write(output_path, bytes)?;
+            eprintln!("[CLI] File written successfully");
         }
+        None if target_format != "html" && io::stdout().is_terminal() => {
+            return Err(format!("Refusing to write binary {} output to the terminal; use --output", target_format).into());
+        }
+        None => io::stdout().write_all(&bytes)?,
     }
-    
-    eprintln!("[VALIDATE] Range check successful for '{}'", field);
     Ok(())
 }
 
-fn validate_string_length(entity: &str, idx: usize, field: &str, value: &serde_json::Value, length_obj: &serde_json::Map<String, serde_json::Value>) -> Result<(), Box<dyn std::error::Error>> {
-    let string_value = value.as_str()
-        .ok_or(format!("Item {} in '{}': field '{}' must be a string for length validation", idx, entity, field))?;
-    
-    let length = string_value.len();
-    eprintln!("[VALIDATE] Checking string length for field '{}': length={}", field, length);
+// The content to write, and any detached signature file with its contents
+type SignedOutput = (String, Option<(PathBuf, String)>);
+
+// Sign converted output; returns the content to write and any detached signature file
+#[cfg(feature = "signing")]
+fn sign_output(content: String, output: Option<&std::path::Path>, signing: &SigningArgs) -> Result<SignedOutput, Box<dyn std::error::Error>> {
+    let Some(key_path) = &signing.sign_key else {
+        return Ok((content, None));
+    };
+    let key = toonify::signing::load_signing_key(key_path)?;
     
-    if let Some(min_len) = length_obj.get("min").and_then(|v| v.as_u64()) {
-        eprintln!("[VALIDATE] Checking minimum length: {} >= {}", length, min_len);
-        if (length as u64) < min_len {
-            return Err(format!(
-                "Item {} in '{}': field '{}' length {} is below minimum {}",
-                idx, entity, field, length, min_len
-            ).into());
-        }
+    if signing.sign_footer {
+        eprintln!("[SIGN] Appending signature footer");
+        return Ok((toonify::signing::sign_footer(&content, &key), None));
     }
     
-    if let Some(max_len) = length_obj.get("max").and_then(|v| v.as_u64()) {
-        eprintln!("[VALIDATE] Checking maximum length: {} <= {}", length, max_len);
-        if (length as u64) > max_len {
-            return Err(format!(
-                "Item {} in '{}': field '{}' length {} exceeds maximum {}",
-                idx, entity, field, length, max_len
-            ).into());
+    let signature_path = match (&signing.signature, output) {
+        (Some(path), _) => path.clone(),
+        (None, Some(output)) => {
+            let mut path = output.as_os_str().to_owned();
+            path.push(".sig");
+            PathBuf::from(path)
         }
-    }
-    
-    eprintln!("[VALIDATE] String length check successful for '{}'", field);
-    Ok(())
+        (None, None) => return Err("--signature or --sign-footer is required when writing to stdout".into()),
+    };
+    let signature = toonify::signing::sign_detached(content.as_bytes(), &key);
+    Ok((content, Some((signature_path, signature))))
 }
 
-fn validate_enum(entity: &str, idx: usize, field: &str, value: &serde_json::Value, allowed_values: &Vec<serde_json::Value>) -> Result<(), Box<dyn std::error::Error>> {
-    eprintln!("[VALIDATE] Checking enum for field '{}': value={:?}", field, value);
-    eprintln!("[VALIDATE] Allowed values: {:?}", allowed_values);
-    
-    let is_allowed = allowed_values.iter().any(|allowed| allowed == value);
-    
-    if !is_allowed {
-        let allowed_strs: Vec<String> = allowed_values
-            .iter()
-            .filter_map(|v| v.as_str().map(|s| format!("'{}'", s)))
-            .collect();
-        
-        return Err(format!(
-            "Item {} in '{}': field '{}' value {:?} is not one of allowed values: [{}]",
-            idx, entity, field, value, allowed_strs.join(", ")
-        ).into());
+#[cfg(not(feature = "signing"))]
+fn sign_output(content: String, _output: Option<&std::path::Path>, signing: &SigningArgs) -> Result<SignedOutput, Box<dyn std::error::Error>> {
+    if signing.sign_key.is_some() {
+        return Err("--sign-key requires the 'signing' feature".into());
     }
-    
-    eprintln!("[VALIDATE] Enum check successful for '{}'", field);
-    Ok(())
+    Ok((content, None))
 }
 
-fn validate_format(entity: &str, idx: usize, field: &str, value: &serde_json::Value, format_type: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let string_value = value.as_str()
-        .ok_or(format!("Item {} in '{}': field '{}' must be a string for format validation", idx, entity, field))?;
-    
-    eprintln!("[VALIDATE] Checking format for field '{}': value='{}', format='{}'", field, string_value, format_type);
+#[cfg(feature = "signing")]
+fn run_verify_signature(input: PathBuf, public_key: PathBuf, signature: Option<PathBuf>, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let key = toonify::signing::load_verifying_key(&public_key)?;
+    let text = fs::read_to_string(&input)?;
+    
+    let default_signature = {
+        let mut path = input.as_os_str().to_owned();
+        path.push(".sig");
+        PathBuf::from(path)
+    };
+    let detached = signature.or_else(|| default_signature.exists().then_some(default_signature));
     
-    let is_valid = match format_type {
-        "email" => {
-            // Basic email validation regex
-            let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
-            email_regex.is_match(string_value)
-        }
-        "url" => {
-            // Basic URL validation
-            let url_regex = Regex::new(r"^https?://[^\s/$.?#].[^\s]*$").unwrap();
-            url_regex.is_match(string_value)
-        }
-        "date" => {
-            // ISO 8601 date format (YYYY-MM-DD)
-            let date_regex = Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
-            date_regex.is_match(string_value)
-        }
-        "uuid" => {
-            // UUID format
-            let uuid_regex = Regex::new(r"^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$").unwrap();
-            uuid_regex.is_match(string_value)
+    let content = match detached {
+        Some(path) => {
+            eprintln!("[VERIFY] Using detached signature {:?}", path);
+            toonify::signing::verify_detached(text.as_bytes(), &fs::read_to_string(&path)?, &key)?;
+            text.as_str()
         }
-        _ => {
-            return Err(format!("Unknown format type: {}", format_type).into());
+        None => {
+            let (content, signature_hex) = toonify::signing::split_footer(&text)
+                .ok_or("No signature found (no detached .sig file and no signature footer)")?;
+            eprintln!("[VERIFY] Using signature footer");
+            toonify::signing::verify_hex(content.as_bytes(), signature_hex, &key)?;
+            content
         }
     };
     
-    if !is_valid {
-        return Err(format!(
-            "Item {} in '{}': field '{}' value '{}' does not match format '{}'",
-            idx, entity, field, string_value, format_type
-        ).into());
+    eprintln!("[VERIFY] Signature OK for {:?}", input);
+    if let Some(output) = output {
+        fs::write(output, content)?;
     }
-    
-    eprintln!("[VALIDATE] Format check successful for '{}'", field);
     Ok(())
 }
 
-fn run_convert(input: String, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
-    eprintln!("[CLI] Reading input...");
-    
-    // Read input
-    let input_content = if input == "-" {
-        eprintln!("[CLI] Reading from STDIN");
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        buffer
-    } else {
-        eprintln!("[CLI] Reading from file: {}", input);
-        fs::read_to_string(&input)?
-    };
-    
-    eprintln!("[CLI] Input size: {} bytes", input_content.len());
-    
-    // Detect format
-    let format = detect_format(&input_content)?;
-    eprintln!("[CLI] Detected format: {}", format);
-    
-    // Convert
-    let output_content = match format {
-        "json" => {
-            eprintln!("[CLI] Converting JSON → TOON");
-            converter::json_to_toon(&input_content)
-                .map_err(|e| format!("Conversion failed: {}", e))?
-        }
-        "toon" => {
-            eprintln!("[CLI] Converting TOON → JSON");
-            converter::toon_to_json(&input_content)
-                .map_err(|e| format!("Conversion failed: {}", e))?
-        }
-        _ => return Err("Unknown format".into()),
-    };
-    
-    eprintln!("[CLI] Conversion successful");
-    eprintln!("[CLI] Output size: {} bytes", output_content.len());
-    
-    // Write output
-    if let Some(output_path) = output {
-        eprintln!("[CLI] Writing to file: {:?}", output_path);
-        fs::write(output_path, output_content)?;
-        eprintln!("[CLI] File written successfully");
-    } else {
-        eprintln!("[CLI] Writing to STDOUT");
-        io::stdout().write_all(output_content.as_bytes())?;
-        io::stdout().flush()?;
+#[cfg(not(feature = "signing"))]
+fn run_verify_signature(_input: PathBuf, _public_key: PathBuf, _signature: Option<PathBuf>, _output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    Err("verify-signature requires the 'signing' feature".into())
+}
+
+// Highlight TOON always; JSON only when built with syntect; other formats as-is
+fn colorize(content: &str, format: &str) -> String {
+    match format.to_ascii_lowercase().as_str() {
+        "toon" => toonify::highlight::highlight_toon(content),
+        #[cfg(feature = "color")]
+        "json" => toonify::highlight::highlight_json(content),
+        _ => content.to_string(),
     }
-    
-    Ok(())
 }
 
-fn run_batch(
-    input_dir: PathBuf,
-    output_dir: PathBuf,
-    from: Option<String>,
-    to: Option<String>,
+// File selection and reporting options for batch
+struct BatchOptions {
     pattern: Option<String>,
     recursive: bool,
     parallel: bool,
+    jobs: Option<usize>,
+    quiet: bool,
+    retry_failed: Option<PathBuf>,
+    files_from: Option<PathBuf>,
+    follow_symlinks: bool,
+}
+
+// One line of failures.jsonl
+#[derive(Debug, Serialize, Deserialize)]
+struct BatchFailure {
+    path: PathBuf,
+    /// read, detect, convert, or write
+    stage: String,
+    error: String,
+}
+
+const FAILURES_FILE: &str = "failures.jsonl";
+
+fn run_batch(
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    options: BatchOptions,
+    conversion: FileConversion,
 ) -> Result<(), Box<dyn std::error::Error>> {
     eprintln!("[BATCH] Starting batch conversion...");
     eprintln!("[BATCH] Input directory: {:?}", input_dir);
     eprintln!("[BATCH] Output directory: {:?}", output_dir);
-    eprintln!("[BATCH] Recursive: {}", recursive);
-    eprintln!("[BATCH] Parallel: {}", parallel);
+    eprintln!("[BATCH] Recursive: {}", options.recursive);
+    eprintln!("[BATCH] Parallel: {}", options.parallel);
     
     if !input_dir.exists() {
         return Err(format!("Input directory does not exist: {:?}", input_dir).into());
@@ -1095,38 +2270,12 @@ fn run_batch(
     fs::create_dir_all(&output_dir)?;
     eprintln!("[BATCH] Output directory created/verified");
     
-    // Build glob pattern
-    let glob_pattern = if let Some(pat) = pattern {
-        eprintln!("[BATCH] Using pattern: {}", pat);
-        if recursive {
-            format!("{}/**/{}", input_dir.display(), pat)
-        } else {
-            format!("{}/{}", input_dir.display(), pat)
-        }
-    } else {
-        eprintln!("[BATCH] Using default pattern (all files)");
-        if recursive {
-            format!("{}/**/*", input_dir.display())
-        } else {
-            format!("{}/*", input_dir.display())
-        }
+    let files_to_process = match (&options.retry_failed, &options.files_from) {
+        (Some(failures_path), _) => read_failed_paths(failures_path)?,
+        (None, Some(list_path)) => read_file_list(list_path, &input_dir, options.pattern.as_deref(), options.follow_symlinks)?,
+        (None, None) => find_batch_files(&input_dir, options.pattern.as_deref(), options.recursive, options.follow_symlinks)?,
     };
     
-    eprintln!("[BATCH] Glob pattern: {}", glob_pattern);
-    
-    // Find all matching files
-    let mut files_to_process = Vec::new();
-    for entry in glob(&glob_pattern)? {
-        match entry {
-            Ok(path) => {
-                if path.is_file() {
-                    files_to_process.push(path);
-                }
-            }
-            Err(e) => eprintln!("[BATCH] Error reading path: {:?}", e),
-        }
-    }
-    
     eprintln!("[BATCH] Found {} files to process", files_to_process.len());
     
     if files_to_process.is_empty() {
@@ -1134,55 +2283,69 @@ fn run_batch(
         return Ok(());
     }
     
-    // Use Arc<Mutex<>> for thread-safe counters in parallel mode
-    let successful = Arc::new(Mutex::new(0));
-    let failed = Arc::new(Mutex::new(0));
+    let progress = BatchProgress::new(files_to_process.len(), options.quiet);
+    let successful = Mutex::new(0);
+    let failures = Mutex::new(Vec::new());
+    
+    let handle_file = |idx: usize, file_path: &PathBuf| {
+        progress.log(&format!("[BATCH] Processing file {}/{}: {:?}", idx + 1, files_to_process.len(), file_path));
+        
+        match process_file(file_path, &input_dir, &output_dir, &conversion, &progress) {
+            Ok(()) => *successful.lock().unwrap() += 1,
+            Err(failure) => {
+                progress.error(&format!("[BATCH] Failed to {} {:?}: {}", failure.stage, file_path, failure.error));
+                failures.lock().unwrap().push(failure);
+            }
+        }
+        progress.file_done(fs::metadata(file_path).map(|m| m.len()).unwrap_or(0));
+    };
     
     // Process files either in parallel or sequentially
-    if parallel {
-        // Parallel processing with rayon
-        files_to_process.par_iter().enumerate().for_each(|(idx, file_path)| {
-            eprintln!("[BATCH] Processing file {}/{}: {:?}", idx + 1, files_to_process.len(), file_path);
-            
-            process_file(
-                file_path,
-                &input_dir,
-                &output_dir,
-                &from,
-                &to,
-                Arc::clone(&successful),
-                Arc::clone(&failed),
-            );
-        });
+    if options.parallel {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(options.jobs.unwrap_or(0)).build()?;
+        pool.install(|| files_to_process.par_iter().enumerate().for_each(|(idx, file_path)| handle_file(idx, file_path)));
     } else {
-        // Sequential processing
-        for (idx, file_path) in files_to_process.iter().enumerate() {
-            eprintln!("[BATCH] Processing file {}/{}: {:?}", idx + 1, files_to_process.len(), file_path);
-            
-            process_file(
-                file_path,
-                &input_dir,
-                &output_dir,
-                &from,
-                &to,
-                Arc::clone(&successful),
-                Arc::clone(&failed),
-            );
-        }
+        files_to_process.iter().enumerate().for_each(|(idx, file_path)| handle_file(idx, file_path));
     }
     
-    // Extract final counts from Arc<Mutex<>>
-    let successful_count = *successful.lock().unwrap();
-    let failed_count = *failed.lock().unwrap();
+    progress.finish();
+    
+    finish_batch(files_to_process.len(), successful.into_inner().unwrap(), failures.into_inner().unwrap(), &output_dir, progress.bytes())
+}
+
+// Write (or clear) failures.jsonl, print the summary, and fail the run if any file failed
+fn finish_batch(total: usize, successful_count: usize, mut failures: Vec<BatchFailure>, output_dir: &Path, bytes_read: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let failed_count = failures.len();
+    
+    // Parallel runs finish in any order; keep the log stable
+    failures.sort_by(|a, b| a.path.cmp(&b.path));
+    let failures_path = output_dir.join(FAILURES_FILE);
+    if failures.is_empty() {
+        // A stale log from an earlier run would make --retry-failed redo fixed files
+        if failures_path.exists() {
+            fs::remove_file(&failures_path)?;
+        }
+    } else {
+        let mut log = String::new();
+        for failure in &failures {
+            log.push_str(&serde_json::to_string(failure)?);
+            log.push('\n');
+        }
+        fs::write(&failures_path, log)?;
+    }
     
     eprintln!("\n[BATCH] ==================== SUMMARY ====================");
-    eprintln!("[BATCH] Total files processed: {}", files_to_process.len());
+    eprintln!("[BATCH] Total files processed: {}", total);
     eprintln!("[BATCH] Successful: {}", successful_count);
     eprintln!("[BATCH] Failed: {}", failed_count);
+    eprintln!("[BATCH] Bytes read: {}", bytes_read);
+    if failed_count > 0 {
+        eprintln!("[BATCH] Failure log: {:?} (re-run with --retry-failed)", failures_path);
+    }
     eprintln!("[BATCH] ===================================================\n");
     
     println!("Batch conversion completed successfully!");
-    println!("Processed {} files ({} successful, {} failed)", files_to_process.len(), successful_count, failed_count);
+    println!("Processed {} files ({} successful, {} failed)", total, successful_count, failed_count);
     
     if failed_count > 0 {
         return Err(format!("{} files failed to convert", failed_count).into());
@@ -1191,124 +2354,234 @@ fn run_batch(
     Ok(())
 }
 
-// Helper function to process a single file
-fn process_file(
-    file_path: &PathBuf,
-    input_dir: &PathBuf,
-    output_dir: &PathBuf,
-    from: &Option<String>,
-    to: &Option<String>,
-    successful: Arc<Mutex<i32>>,
-    failed: Arc<Mutex<i32>>,
-) {
-    // Read file
-    let content = match fs::read_to_string(file_path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("[BATCH] Failed to read file: {}", e);
-            *failed.lock().unwrap() += 1;
-            return;
+fn find_batch_files(
+    input_dir: &Path,
+    pattern: Option<&str>,
+    recursive: bool,
+    follow_symlinks: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    match pattern {
+        Some(pat) => eprintln!("[BATCH] Using pattern: {}", pat),
+        None => eprintln!("[BATCH] Using default pattern (all files)"),
+    }
+    eprintln!("[BATCH] Follow symlinks: {}", follow_symlinks);
+    
+    let mut files = FileWalk::new(pattern, recursive, follow_symlinks)?.run(input_dir);
+    
+    // Never pick up our own failure log from a nested output dir
+    files.retain(|path| path.file_name() != Some(std::ffi::OsStr::new(FAILURES_FILE)));
+    
+    Ok(files)
+}
+
+fn read_failed_paths(failures_path: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    eprintln!("[BATCH] Retrying failures from: {:?}", failures_path);
+    let log = fs::read_to_string(failures_path)?;
+    
+    let mut paths = Vec::new();
+    for (line_number, line) in log.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let failure: BatchFailure = serde_json::from_str(line)
+            .map_err(|e| format!("{:?} line {}: {}", failures_path, line_number + 1, e))?;
+        if !paths.contains(&failure.path) {
+            paths.push(failure.path);
         }
-    };
+    }
     
-    // Detect format if not specified
-    let source_format = if let Some(f) = from.as_ref() {
-        f.as_str()
+    Ok(paths)
+}
+
+// Paths may be relative to the working directory (as `git diff --name-only`
+// prints them) but must lie inside the input directory, which roots the
+// output tree. Missing files are skipped so deleted paths in a diff are harmless.
+fn read_file_list(
+    list_path: &Path,
+    input_dir: &Path,
+    pattern: Option<&str>,
+    follow_symlinks: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let list = if list_path == Path::new("-") {
+        eprintln!("[BATCH] Reading file list from stdin");
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer
     } else {
-        match detect_format(&content) {
-            Ok(f) => f,
-            Err(e) => {
-                eprintln!("[BATCH] Failed to detect format: {}", e);
-                *failed.lock().unwrap() += 1;
-                return;
-            }
+        eprintln!("[BATCH] Reading file list from: {:?}", list_path);
+        fs::read_to_string(list_path)?
+    };
+    
+    // Listed files are matched like a recursive walk, so a pattern still filters them
+    let walk = FileWalk::new(pattern, true, follow_symlinks)?;
+    let canonical_input = file_walk::canonicalize(input_dir)?;
+    
+    let mut paths = Vec::new();
+    for line in list.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let path = Path::new(line);
+        if !path.is_file() {
+            eprintln!("[BATCH] Skipping missing file: {:?}", path);
+            continue;
+        }
+        // Resolve only when the path is not spelled under the input directory,
+        // so symlinks inside it stay visible to the symlink policy
+        let spelled = path.strip_prefix(input_dir).ok()
+            .filter(|relative| !relative.components().any(|c| c == std::path::Component::ParentDir));
+        let relative = match spelled {
+            Some(relative) => relative.to_path_buf(),
+            None => file_walk::canonicalize(path)?
+                .strip_prefix(&canonical_input)
+                .map(Path::to_path_buf)
+                .map_err(|_| format!("{:?} is outside the input directory {:?}", path, input_dir))?,
+        };
+        let path = input_dir.join(relative);
+        if walk.admits(input_dir, &path) && !paths.contains(&path) {
+            paths.push(path);
         }
+    }
+    
+    Ok(paths)
+}
+
+// Convert one file into the output tree; errors name the stage that failed
+fn process_file(
+    file_path: &Path,
+    input_dir: &Path,
+    output_dir: &Path,
+    conversion: &FileConversion,
+    progress: &BatchProgress,
+) -> Result<(), BatchFailure> {
+    // Read file (large files are memory-mapped, gzip files decompressed)
+    let content = read_convert_input(file_path)
+        .map_err(|e| BatchFailure { path: file_path.to_path_buf(), stage: "read".to_string(), error: e.to_string() })?;
+    
+    let relative_path = file_path.strip_prefix(input_dir)
+        .unwrap_or(file_path);
+    convert_entry(file_path, relative_path, &content, output_dir, conversion, progress)
+}
+
+// Convert one input (a file or archive member) to `relative_path` under the output tree
+fn convert_entry(
+    source: &Path,
+    relative_path: &Path,
+    content: &str,
+    output_dir: &Path,
+    conversion: &FileConversion,
+    progress: &BatchProgress,
+) -> Result<(), BatchFailure> {
+    let fail = |stage: &str, error: String| BatchFailure { path: source.to_path_buf(), stage: stage.to_string(), error };
+    
+    // Detect format if not specified
+    let source_format = match conversion.from.as_ref() {
+        Some(f) => f.as_str(),
+        None => converter::detect_format(Some(source), content.as_bytes()).map_err(|e| fail("detect", e))?,
     };
     
-    eprintln!("[BATCH] Source format: {}", source_format);
+    progress.log(&format!("[BATCH] Source format: {}", source_format));
     
     // Determine target format
-    let target_format = if let Some(t) = to.as_ref() {
+    let target_format = if let Some(t) = conversion.to.as_ref() {
         t.as_str()
-    } else {
-        // Auto-detect target: if source is JSON, target is TOON, and vice versa
-        if source_format == "json" {
-            "toon"
-        } else {
-            "json"
-        }
+    } else {
+        // Auto-detect target: TOON converts to JSON, everything else to TOON
+        default_target_format(source_format)
     };
     
-    eprintln!("[BATCH] Target format: {}", target_format);
+    progress.log(&format!("[BATCH] Target format: {}", target_format));
     
     // Convert
-    let converted = match (source_format, target_format) {
-        ("json", "toon") => converter::json_to_toon(&content),
-        ("toon", "json") => converter::toon_to_json(&content),
-        ("json", "json") | ("toon", "toon") => {
-            eprintln!("[BATCH] Source and target formats are the same, copying file");
-            Ok(content)
-        }
-        _ => {
-            eprintln!("[BATCH] Unsupported format combination: {} -> {}", source_format, target_format);
-            *failed.lock().unwrap() += 1;
-            return;
-        }
-    };
-    
-    let converted_content = match converted {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("[BATCH] Conversion failed: {}", e);
-            *failed.lock().unwrap() += 1;
-            return;
-        }
-    };
+    if source_format.eq_ignore_ascii_case(target_format) && !conversion.converter.has_hooks() {
+        progress.log("[BATCH] Source and target formats are the same, copying file");
+    }
+    let converted_content = conversion.convert(content, source_format, target_format)
+        .map_err(|e| fail("convert", e))?;
     
     // Determine output path
-    let relative_path = file_path.strip_prefix(&input_dir)
-        .unwrap_or(file_path);
-    
     let mut output_path = output_dir.join(relative_path);
     
-    // Change extension based on target format
-    let new_extension = match target_format {
-        "json" => "json",
-        "toon" => "toon",
-        _ => "txt",
-    };
-    output_path.set_extension(new_extension);
+    // Change extension based on target format; data.json.gz becomes data.toon
+    if output_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gz")) {
+        output_path.set_extension("");
+    }
+    output_path.set_extension(extension_for_format(target_format));
+    if conversion.gzip {
+        output_path.as_mut_os_string().push(".gz");
+    }
     
-    eprintln!("[BATCH] Output path: {:?}", output_path);
+    progress.log(&format!("[BATCH] Output path: {:?}", output_path));
     
     // Create parent directories if needed
     if let Some(parent) = output_path.parent() {
-        if let Err(e) = fs::create_dir_all(parent) {
-            eprintln!("[BATCH] Failed to create parent directory: {}", e);
-            *failed.lock().unwrap() += 1;
-            return;
-        }
+        fs::create_dir_all(parent).map_err(|e| fail("write", e.to_string()))?;
     }
     
     // Write output
-    match fs::write(&output_path, converted_content) {
-        Ok(_) => {
-            eprintln!("[BATCH] ✓ Successfully converted: {:?}", file_path);
-            *successful.lock().unwrap() += 1;
-        }
-        Err(e) => {
-            eprintln!("[BATCH] Failed to write output: {}", e);
-            *failed.lock().unwrap() += 1;
+    conversion.write_output(source, &output_path, &converted_content).map_err(|e| fail("write", e.to_string()))?;
+    progress.log(&format!("[BATCH] ✓ Successfully converted: {:?}", source));
+    Ok(())
+}
+
+// Convert the members of an archive; they are read in archive order, so --parallel has no effect
+#[cfg(feature = "archive")]
+fn run_batch_archive(
+    archive_path: PathBuf,
+    output_dir: PathBuf,
+    options: BatchOptions,
+    conversion: FileConversion,
+) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!("[BATCH] Starting batch conversion...");
+    eprintln!("[BATCH] Input archive: {:?}", archive_path);
+    eprintln!("[BATCH] Output directory: {:?}", output_dir);
+    
+    let kind = archive::ArchiveKind::of(&archive_path)?;
+    eprintln!("[BATCH] Archive type: {:?}", kind);
+    if options.parallel {
+        eprintln!("[BATCH] Archive members are converted in order; ignoring --parallel");
+    }
+    
+    fs::create_dir_all(&output_dir)?;
+    
+    // Patterns match member paths the way they match paths under --input-dir
+    let walk = FileWalk::new(options.pattern.as_deref(), options.recursive, false)?;
+    let select = |member: &Path| walk.matches_relative(member);
+    
+    // Only the progress bar needs the total up front
+    let total = if options.quiet { archive::count_members(&archive_path, kind, select)? } else { 0 };
+    let progress = BatchProgress::new(total, options.quiet);
+    let mut successful = 0;
+    let mut failures = Vec::new();
+    
+    let processed = archive::for_each_member(&archive_path, kind, select, |member, contents| {
+        progress.log(&format!("[BATCH] Processing member: {:?}", member));
+        let source = archive_path.join(&member);
+        let bytes = contents.as_ref().map_or(0, |contents| contents.len() as u64);
+        let outcome = contents
+            .and_then(|contents| decode_input(contents).map_err(|e| e.to_string()))
+            .map_err(|error| BatchFailure { path: source.clone(), stage: "read".to_string(), error })
+            .and_then(|content| convert_entry(&source, &member, &content, &output_dir, &conversion, &progress));
+        match outcome {
+            Ok(()) => successful += 1,
+            Err(failure) => {
+                progress.error(&format!("[BATCH] Failed to {} {:?}: {}", failure.stage, member, failure.error));
+                failures.push(failure);
+            }
         }
+        progress.file_done(bytes);
+    })?;
+    
+    progress.finish();
+    
+    if processed == 0 {
+        eprintln!("[BATCH] No archive members matching pattern");
+        return Ok(());
     }
+    finish_batch(processed, successful, failures, &output_dir, progress.bytes())
 }
 
 fn run_watch(
     input_dir: PathBuf,
     output_dir: PathBuf,
-    from: Option<String>,
-    to: Option<String>,
     pattern: Option<String>,
+    jobs: Option<usize>,
+    follow_symlinks: bool,
+    conversion: FileConversion,
 ) -> Result<(), Box<dyn std::error::Error>> {
     eprintln!("[WATCH] Starting watch mode...");
     eprintln!("[WATCH] Watching directory: {:?}", input_dir);
@@ -1323,11 +2596,22 @@ fn run_watch(
     eprintln!("[WATCH] Output directory created/verified");
     
     // Canonicalize paths to handle symlinks like /tmp -> /private/tmp on macOS
-    let input_dir = input_dir.canonicalize()?;
-    let output_dir = output_dir.canonicalize()?;
+    let input_dir = file_walk::canonicalize(&input_dir)?;
+    let output_dir = file_walk::canonicalize(&output_dir)?;
     eprintln!("[WATCH] Canonical input: {:?}", input_dir);
     eprintln!("[WATCH] Canonical output: {:?}", output_dir);
     
+    // Conversions run on worker threads, at most one per file at a time
+    let queue = {
+        let input_dir = input_dir.clone();
+        let output_dir = output_dir.clone();
+        watch_queue::WatchQueue::new(jobs.unwrap_or(0), move |file_path| {
+            if let Err(e) = convert_watched_file(file_path, &input_dir, &output_dir, &conversion) {
+                eprintln!("[WATCH] Error converting {:?}: {}", file_path, e);
+            }
+        })?
+    };
+    eprintln!("[WATCH] Workers: {}", queue.threads());
     
     // Create channel for file system events
     let (tx, rx) = channel();
@@ -1368,6 +2652,11 @@ fn run_watch(
                             continue;
                         }
                         
+                        // The watcher descends into symlinked directories regardless
+                        if !follow_symlinks && file_walk::crosses_symlink(&input_dir, &file_path) {
+                            continue;
+                        }
+                        
                         // Check pattern match
                         let matches = if let Some(ref pat) = pattern {
                             if let Some(filename) = file_path.file_name().and_then(|n| n.to_str()) {
@@ -1393,50 +2682,8 @@ fn run_watch(
                         }
                         
                         eprintln!("[WATCH] File changed: {:?}", file_path);
-                        
-                        // Convert file
-                        match (|| -> Result<(), Box<dyn std::error::Error>> {
-                            eprintln!("[WATCH] Processing: {:?}", file_path);
-                            
-                            let content = fs::read_to_string(&file_path)?;
-                            
-                            let source_format = if let Some(f) = from.as_ref() {
-                                f.as_str()
-                            } else {
-                                detect_format(&content)?
-                            };
-                            
-                            let target_format = if let Some(t) = to.as_ref() {
-                                t.as_str()
-                            } else {
-                                if source_format == "json" { "toon" } else { "json" }
-                            };
-                            
-                            eprintln!("[WATCH] Format: {} -> {}", source_format, target_format);
-                            
-                            let converted = match (source_format, target_format) {
-                                ("json", "toon") => converter::json_to_toon(&content),
-                                ("toon", "json") => converter::toon_to_json(&content),
-                                ("json", "json") | ("toon", "toon") => Ok(content),
-                                _ => return Err(format!("Unsupported conversion: {} -> {}", source_format, target_format).into()),
-                            }?;
-                            
-                            let relative_path = file_path.strip_prefix(&input_dir).unwrap_or(&file_path);
-                            let mut output_path = output_dir.join(relative_path);
-                            let new_extension = if target_format == "json" { "json" } else { "toon" };
-                            output_path.set_extension(new_extension);
-                            
-                            if let Some(parent) = output_path.parent() {
-                                fs::create_dir_all(parent)?;
-                            }
-                            
-                            fs::write(&output_path, converted)?;
-                            eprintln!("[WATCH] ✓ Converted: {:?} -> {:?}", file_path, output_path);
-                            
-                            Ok(())
-                        })() {
-                            Ok(_) => {},
-                            Err(e) => eprintln!("[WATCH] Error converting {:?}: {}", file_path, e),
+                        if !queue.submit(file_path.clone()) {
+                            eprintln!("[WATCH] Already pending: {:?}", file_path);
                         }
                     }
                 }
@@ -1451,14 +2698,93 @@ fn run_watch(
     Ok(())
 }
 
+// Convert one changed file into the output tree
+fn convert_watched_file(file_path: &Path, input_dir: &Path, output_dir: &Path, conversion: &FileConversion) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!("[WATCH] Processing: {:?}", file_path);
+    
+    let content = fs::read_to_string(file_path)?;
+    
+    let source_format = if let Some(f) = conversion.from.as_ref() {
+        f.as_str()
+    } else {
+        converter::detect_format(Some(file_path), content.as_bytes())?
+    };
+    
+    let target_format = if let Some(t) = conversion.to.as_ref() {
+        t.as_str()
+    } else {
+        default_target_format(source_format)
+    };
+    
+    eprintln!("[WATCH] Format: {} -> {}", source_format, target_format);
+    
+    let converted = conversion.convert(&content, source_format, target_format)?;
+    
+    let relative_path = file_path.strip_prefix(input_dir).unwrap_or(file_path);
+    let mut output_path = output_dir.join(relative_path);
+    output_path.set_extension(extension_for_format(target_format));
+    
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    
+    conversion.write_output(file_path, &output_path, &converted)?;
+    eprintln!("[WATCH] ✓ Converted: {:?} -> {:?}", file_path, output_path);
+    
+    Ok(())
+}
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+    // Precedence: command-line flags, TOONIFY_* variables, config file, built-in defaults
+    #[cfg(feature = "config")]
+    let command = cli_config::apply(Cli::command())?;
+    #[cfg(not(feature = "config"))]
+    let command = Cli::command();
+    let cli = Cli::from_arg_matches(&command.get_matches()).unwrap_or_else(|e| e.exit());
     
     match cli.command {
-        Some(Commands::Convert { input, output }) => {
+        Some(Commands::Convert { input, output, from, to, entity, color, cache_dir, gzip, optimize_tokens, signing, conversion }) => {
             // CLI mode - convert file
-            run_convert(input, output)?;
+            run_convert(input, output, from, to, entity, color, cache_dir, gzip, optimize_tokens, signing, conversion)?;
+            Ok(())
+        }
+        #[cfg(any(feature = "protobuf", feature = "avro"))]
+        Some(Commands::Encode { schema, message, input, output, conversion }) => {
+            // CLI mode - schema-driven binary encoding
+            run_encode(&schema, message.as_deref(), input, output, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        #[cfg(any(feature = "protobuf", feature = "avro"))]
+        Some(Commands::Decode { schema, message, input, output, to, conversion }) => {
+            // CLI mode - schema-driven binary decoding
+            run_decode(&schema, message.as_deref(), input, output, &to, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Merge { inputs, strategy, output, to, conversion }) => {
+            // CLI mode - combine documents
+            run_merge(inputs, strategy, output, &to, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Split { input, output_dir, to, conversion }) => {
+            // CLI mode - one file per entity
+            run_split(input, &output_dir, &to, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Chunk { input, max_tokens, output_dir, tokenizer, conversion }) => {
+            // CLI mode - token-bounded chunk files
+            run_chunk(input, max_tokens, &output_dir, tokenizer, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Dedupe { input, key, entity, output }) => {
+            // CLI mode - streaming row deduplication
+            table_ops::run(table_ops::Operation::Dedupe { keys: key }, table_ops::TableOptions { input, output, entity })?;
+            Ok(())
+        }
+        Some(Commands::Sort { input, by, desc, entity, buffer_rows, output }) => {
+            // CLI mode - external sort of table rows
+            let operation = table_ops::Operation::Sort { by, descending: desc, buffer_rows: buffer_rows.max(1) };
+            table_ops::run(operation, table_ops::TableOptions { input, output, entity })?;
             Ok(())
         }
         Some(Commands::Compress { input, output }) => {
@@ -1476,158 +2802,456 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             run_validate(schema, input)?;
             Ok(())
         }
-        Some(Commands::Batch { input_dir, output_dir, from, to, pattern, recursive, parallel }) => {
+        Some(Commands::Batch { input_dir, input, output_dir, from, to, pattern, recursive, parallel, jobs, quiet, retry_failed, files_from, fsync, preserve_metadata, gzip, follow_symlinks: _, no_follow_symlinks, conversion }) => {
             // CLI mode - batch convert files
-            run_batch(input_dir, output_dir, from, to, pattern, recursive, parallel)?;
+            let conversion = FileConversion { from, to, converter: build_converter(conversion)?, fsync, preserve_metadata, gzip };
+            let options = BatchOptions { pattern, recursive, parallel: parallel || jobs.is_some(), jobs, quiet, retry_failed, files_from, follow_symlinks: !no_follow_symlinks };
+            match (input_dir, input) {
+                #[cfg(feature = "archive")]
+                (_, Some(archive_path)) => run_batch_archive(archive_path, output_dir, options, conversion)?,
+                #[cfg(not(feature = "archive"))]
+                (_, Some(_)) => return Err("--input requires the 'archive' feature".into()),
+                (Some(input_dir), None) => run_batch(input_dir, output_dir, options, conversion)?,
+                (None, None) => return Err("batch needs --input-dir or --input".into()),
+            }
             Ok(())
         }
-        Some(Commands::Watch { input_dir, output_dir, from, to, pattern }) => {
+        Some(Commands::Watch { input_dir, output_dir, from, to, pattern, jobs, fsync, preserve_metadata, follow_symlinks: _, no_follow_symlinks, conversion }) => {
             // CLI mode - watch directory for changes
-            run_watch(input_dir, output_dir, from, to, pattern)?;
+            let conversion = FileConversion { from, to, converter: build_converter(conversion)?, fsync, preserve_metadata, gzip: false };
+            run_watch(input_dir, output_dir, pattern, jobs, !no_follow_symlinks, conversion)?;
+            Ok(())
+        }
+        Some(Commands::GenClient { lang, out }) => {
+            // CLI mode - client SDK
+            run_gen_client(lang, &out)?;
+            Ok(())
+        }
+        Some(Commands::Generate { schema, rows, seed, to, output }) => {
+            // CLI mode - synthetic data
+            run_generate(&schema, rows, seed, &to, output)?;
+            Ok(())
+        }
+        Some(Commands::Profile { input, from, json }) => {
+            // CLI mode - column statistics
+            run_profile(input, from, json)?;
+            Ok(())
+        }
+        Some(Commands::Summarize { input, rows, from, output }) => {
+            // CLI mode - head and schema overview
+            run_summarize(input, rows, from, output)?;
+            Ok(())
+        }
+        #[cfg(feature = "tui")]
+        Some(Commands::View { input, from }) => {
+            // Interactive mode - table browser
+            run_view(input, from)?;
+            Ok(())
+        }
+        #[cfg(feature = "database")]
+        Some(Commands::Db { action: DbAction::Export { dsn, query, name, output, gzip } }) => {
+            // CLI mode - SQL export
+            if name.len() > query.len() {
+                return Err("More --name values than --query values".into());
+            }
+            let queries = query.into_iter().enumerate()
+                .map(|(i, sql)| {
+                    let entity = name.get(i).cloned().unwrap_or_else(|| if i == 0 { "rows".to_string() } else { format!("rows_{}", i + 1) });
+                    (entity, sql)
+                })
+                .collect();
+            database::run_export(database::ExportOptions { dsn, queries, output, gzip }).await?;
+            Ok(())
+        }
+        #[cfg(feature = "sqlite")]
+        Some(Commands::Sqlite { action }) => {
+            // CLI mode - SQLite snapshots
+            match action {
+                SqliteAction::Export { database, tables, output } => sqlite_bridge::run_export(database, tables, output).await?,
+                SqliteAction::Import { database, input, replace } => sqlite_bridge::run_import(database, input, replace).await?,
+            }
+            Ok(())
+        }
+        #[cfg(feature = "duckdb")]
+        Some(Commands::Sql { query, input, output, to, name, conversion }) => {
+            // CLI mode - SQL over TOON entities
+            let options = duckdb_query::SqlOptions { query, input, output, to, name };
+            duckdb_query::run_sql(options, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        #[cfg(feature = "kafka")]
+        Some(Commands::KafkaBridge { brokers, topic, topic_out, group_id, batch_size, batch_timeout_ms, entity, conversion }) => {
+            // Long-running mode - Kafka bridge
+            let options = kafka_bridge::BridgeOptions {
+                brokers,
+                topic,
+                topic_out,
+                group_id,
+                batch_size,
+                batch_timeout: std::time::Duration::from_millis(batch_timeout_ms),
+                entity,
+            };
+            kafka_bridge::run(options, build_converter(conversion)?).await?;
+            Ok(())
+        }
+        Some(Commands::Listen { port, bind, output_dir, id_header, conversion }) => {
+            // Long-running mode - webhook capture
+            let options = listen::ListenOptions {
+                addr: SocketAddr::new(bind, port),
+                output_dir,
+                id_header,
+            };
+            listen::run(options, build_converter(conversion)?).await?;
+            Ok(())
+        }
+        #[cfg(feature = "distributed-jobs")]
+        Some(Commands::Worker { queue, workers, guards }) => {
+            // Long-running mode - job worker node
+            if workers == 0 {
+                return Err("--workers must be at least 1".into());
+            }
+            let broker = toonify::job_broker::JobBroker::new(&queue)?;
+            eprintln!("[JOB QUEUE] Worker node taking jobs from {}", queue);
+            let guards = guards.guards();
+            tokio::task::spawn_blocking(move || toonify::job_broker::run_workers(broker, workers, guards)).await?;
+            Ok(())
+        }
+        Some(Commands::Daemon { socket, conversion }) => {
+            // Long-running mode - editor daemon
+            daemon::run(socket, build_converter(conversion)?).await?;
+            Ok(())
+        }
+        Some(Commands::Lsp) => {
+            // Long-running mode - language server
+            lsp::run()?;
+            Ok(())
+        }
+        Some(Commands::Sync { dir_a, dir_b, format_a, format_b, conflict, once, fsync, conversion }) => {
+            // CLI mode - two-way directory sync
+            let options = sync::SyncOptions { dir_a, dir_b, format_a, format_b, conflict, once, fsync };
+            sync::run(options, build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Mcp { conversion }) => {
+            // Long-running mode - tool server for LLM agents
+            mcp::run(build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Report { input, output, from, schema, max_rows, conversion }) => {
+            // CLI mode - HTML report
+            let options = report::ReportOptions { input, output, from, schema, max_rows };
+            report::run_report(options, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Publish { input_dir, output, pattern, max_rows, title, conversion }) => {
+            // CLI mode - static dataset site
+            let options = publish::PublishOptions { input_dir, output, pattern, max_rows, title };
+            publish::run_publish(options, &build_converter(conversion)?)?;
+            Ok(())
+        }
+        Some(Commands::Fmt { inputs, check, align, sort }) => {
+            // CLI mode - format TOON files
+            let options = toonify::toon::FormatOptions { align_columns: align, sort_entities: sort };
+            run_fmt(inputs, check, options)?;
+            Ok(())
+        }
+        Some(Commands::Selftest { inputs, cases, seed, typed_headers }) => {
+            // CLI mode - round-trip self test
+            run_selftest(inputs, cases, seed, typed_headers)?;
+            Ok(())
+        }
+        Some(Commands::VerifySignature { input, public_key, signature, output }) => {
+            // CLI mode - check an output signature
+            run_verify_signature(input, public_key, signature, output)?;
+            Ok(())
+        }
+        Some(Commands::Version { json }) => {
+            // CLI mode - build metadata
+            let info = build_info::current();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&info)?);
+            } else {
+                println!("toonify {} ({}, {})", info.version, info.git_sha, info.target);
+            }
+            Ok(())
+        }
+        Some(Commands::Healthcheck { grpc, timeout_ms, addrs }) => {
+            // CLI mode - container health probe
+            let target = if grpc { healthcheck::Probe::Grpc(addrs.grpc_addr) } else { healthcheck::Probe::Http(addrs.http_addr) };
+            healthcheck::run(target, std::time::Duration::from_millis(timeout_ms)).await?;
+            Ok(())
+        }
+        #[cfg(feature = "replay")]
+        Some(Commands::Replay { logs, target, concurrency, report }) => {
+            // CLI mode - replay recorded traffic
+            replay::run(replay::ReplayOptions { logs, target, concurrency, report }).await?;
+            Ok(())
+        }
+        #[cfg(feature = "self-update")]
+        Some(Commands::SelfUpdate { check, public_key }) => {
+            // CLI mode - update from GitHub releases
+            self_update::run(self_update::UpdateOptions { check, public_key }).await?;
+            Ok(())
+        }
+        #[cfg(feature = "cli-cache")]
+        Some(Commands::Cache { action: CacheAction::Gc { cache_dir, max_age_days, max_size_mb } }) => {
+            // CLI mode - cache maintenance
+            let dir = cache_dir.or_else(cli_cache::default_dir).ok_or("No cache directory; pass --cache-dir")?;
+            if !dir.exists() {
+                println!("✓ No cache at {:?}", dir);
+                return Ok(());
+            }
+            let max_age = std::time::Duration::from_secs(max_age_days.saturating_mul(86_400));
+            let report = cli_cache::gc(&dir, Some(max_age), max_size_mb.map(|mb| mb.saturating_mul(1024 * 1024)))?;
+            println!(
+                "✓ Removed {} entries ({} bytes); {} entries ({} bytes) kept in {:?}",
+                report.removed, report.removed_bytes, report.kept, report.kept_bytes, dir
+            );
+            Ok(())
+        }
+        #[cfg(feature = "persistent-cache")]
+        Some(Commands::Cache { action: CacheAction::Stats { path } }) => {
+            // CLI mode - Sled cache maintenance
+            let stats = toonify::sled_cache::stats(&toonify::sled_cache::open(&path)?)?;
+            let age = |time: Option<u64>| {
+                let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+                time.map_or("-".to_string(), |time| format!("{}s ago", now.saturating_sub(time)))
+            };
+            println!("entries:      {}", stats.entries);
+            println!("size on disk: {} bytes", stats.size_on_disk);
+            println!("oldest:       {}", age(stats.oldest));
+            println!("newest:       {}", age(stats.newest));
+            println!("untimed:      {}", stats.untimed);
+            Ok(())
+        }
+        #[cfg(feature = "persistent-cache")]
+        Some(Commands::Cache { action: CacheAction::Prune { path, older_than } }) => {
+            let older_than = toonify::sled_cache::parse_age(&older_than)?;
+            let db = toonify::sled_cache::open(&path)?;
+            let removed = toonify::sled_cache::prune(&db, older_than)?;
+            println!("✓ Removed {} entries; {} kept in {:?}", removed, db.len(), path);
+            Ok(())
+        }
+        #[cfg(feature = "persistent-cache")]
+        Some(Commands::Cache { action: CacheAction::Compact { path } }) => {
+            let (before, after) = toonify::sled_cache::compact(&path)?;
+            println!("✓ Compacted {:?}: {} bytes -> {} bytes", path, before, after);
+            Ok(())
+        }
+        Some(Commands::Hook { action }) => {
+            // CLI mode - git pre-commit integration
+            match action {
+                HookAction::Install { force } => git_hook::install(force)?,
+                HookAction::Run { files, check, conversion } => git_hook::run(files, check, &build_converter(*conversion)?)?,
+            }
             Ok(())
         }
-        Some(Commands::Serve { cache_size, cache_ttl, persistent_cache, enable_job_queue, workers, job_queue_backend, rate_limit, rate_limit_window }) => {
+        Some(Commands::Corpus { action: CorpusAction::Run { dir } }) => {
+            // CLI mode - corpus regression check
+            run_corpus(dir)
+        }
+        Some(Commands::VerifyBindings { bindings_dir, pkg_dir, lib_dir, only, require_all }) => {
+            // CLI mode - cross-language smoke test
+            let options = verify_bindings::VerifyOptions { bindings_dir, pkg_dir, lib_dir, only, require_all };
+            if !verify_bindings::run(&options)? {
+                return Err("Binding verification failed".into());
+            }
+            Ok(())
+        }
+        Some(Commands::Serve { cache_size, cache_ttl, cache_snapshot, cache_snapshot_interval, persistent_cache, cache_encryption_key, enable_job_queue, workers, job_queue_backend, conversion_timeout_ms, conversion_threads, conversion_queue, small_request_bytes, small_request_threads, rate_limit, rate_limit_window, audit_log: audit_log_path, audit_log_max_mb, audit_log_keep, audit_log_payloads, #[cfg(feature = "grpc-web")] grpc_web, playground, #[cfg(feature = "grpc-gateway")] grpc_gateway, #[cfg(feature = "cluster-cache")] cache_invalidation_url, schema_dir, transport, socket, guards, addrs }) => {
+            // JSON-RPC transports; stdout belongs to the protocol, so no tracing output there
+            let rpc_converter = || converter::Converter::builder().guards(guards.guards()).build();
+            match transport {
+                ServeTransport::Stdio => return daemon::run_stdio(rpc_converter()).await,
+                ServeTransport::Unix => return daemon::run(socket.unwrap_or_default(), rpc_converter()).await,
+                ServeTransport::Tcp => {}
+            }
+
             // Server mode
     tracing_subscriber::fmt::init();
 
-            let grpc_addr: SocketAddr = "0.0.0.0:50051".parse()?;
-            let http_addr: SocketAddr = "0.0.0.0:5000".parse()?;
+            let ServerAddrs { grpc_addr, http_addr } = addrs;
             
+            let mut builder = ServerBuilder::new();
+
             // Create Moka cache if requested
             #[cfg(feature = "cache")]
-            let moka_cache = if let Some(size) = cache_size {
+            let has_moka = if let Some(size) = cache_size {
                 if let Some(ttl) = cache_ttl {
                     eprintln!("[CACHE] Moka enabled: {} entries with {}s TTL", size, ttl);
                 } else {
                     eprintln!("[CACHE] Moka enabled: {} entries (no TTL)", size);
                 }
-                Some(create_moka_cache(size, cache_ttl))
+                builder = builder.cache(size, cache_ttl);
+                true
             } else {
-                None
+                false
             };
-            
+
+            #[cfg(feature = "cache")]
+            if let Some(ref path) = cache_snapshot {
+                eprintln!("[CACHE] Moka snapshot: {:?} every {}s", path, cache_snapshot_interval);
+                builder = builder.cache_snapshot(path.clone(), std::time::Duration::from_secs(cache_snapshot_interval));
+            }
+
             #[cfg(not(feature = "cache"))]
-            let moka_cache: Option<()> = None;
-            
+            let has_moka = false;
+
+            #[cfg(not(feature = "cache"))]
+            if cache_snapshot.is_some() {
+                return Err("--cache-snapshot requires the 'cache' feature".into());
+            }
+
             // Create Sled persistent cache if requested
             #[cfg(feature = "persistent-cache")]
-            let sled_cache = if let Some(path) = persistent_cache {
+            let has_sled = if let Some(path) = persistent_cache {
                 eprintln!("[CACHE] Sled persistent cache enabled: {}", path);
                 match sled::open(&path) {
-                    Ok(db) => Some(Arc::new(db)),
+                    Ok(db) => {
+                        builder = builder.persistent_cache(db);
+                        true
+                    }
                     Err(e) => {
                         eprintln!("[ERROR] Failed to open Sled database: {}", e);
-                        None
+                        false
                     }
                 }
             } else {
-                None
+                false
             };
-            
+
             #[cfg(not(feature = "persistent-cache"))]
-            let sled_cache: Option<()> = None;
-            
+            let has_sled = false;
+
+            #[cfg(feature = "cache-encryption")]
+            match cache_encryption_key {
+                Some(_) if !has_sled => {
+                    return Err("--cache-encryption-key requires --persistent-cache".into());
+                }
+                // The snapshot file would hold the same results in the clear
+                Some(_) if cache_snapshot.is_some() => {
+                    return Err("--cache-snapshot writes results unencrypted; it cannot be combined with --cache-encryption-key".into());
+                }
+                Some(source) => {
+                    eprintln!("[CACHE] Sled entries encrypted with AES-256-GCM");
+                    builder = builder.cache_cipher(Arc::new(CacheCipher::from_source(&source)?));
+                }
+                None => {}
+            }
+
+            #[cfg(not(feature = "cache-encryption"))]
+            if cache_encryption_key.is_some() {
+                return Err("--cache-encryption-key requires the 'cache-encryption' feature".into());
+            }
+
             // Log cache status
-            if moka_cache.is_none() && sled_cache.is_none() {
+            if !has_moka && !has_sled {
                 eprintln!("[CACHE] Disabled (no cache configured)");
             }
 
-    let grpc_service = ConverterServiceServer::new(ConverterServiceImpl);
+            let mut limits = ConversionLimits::new(
+                conversion_timeout_ms.map(std::time::Duration::from_millis),
+                conversion_threads,
+                conversion_queue,
+            )?;
+            if small_request_bytes > 0 {
+                limits = limits.small_lane(small_request_bytes, small_request_threads)?;
+            }
+            eprintln!("[LIMITS] Conversion pool: {} threads, {} queued max", limits.threads(), conversion_queue);
+            if let Some((max_bytes, threads)) = limits.small_lane_config() {
+                eprintln!("[LIMITS] Small-request pool: {} threads for inputs up to {} bytes", threads, max_bytes);
+            }
+            if let Some(ms) = conversion_timeout_ms {
+                eprintln!("[LIMITS] Conversion timeout: {}ms", ms);
+            }
+            let guards = guards.guards();
+            if !guards.is_unlimited() {
+                let describe = |limit: Option<usize>| limit.map_or("unlimited".to_string(), |n| n.to_string());
+                eprintln!(
+                    "[LIMITS] Parser guards: depth {}, entities {}, line length {}",
+                    describe(guards.max_depth),
+                    describe(guards.max_entities),
+                    describe(guards.max_line_length)
+                );
+            }
+            builder = builder.limits(limits).guards(guards);
 
-    tokio::spawn(async move {
-                eprintln!("[gRPC] Server listening on {}", grpc_addr);
-        Server::builder()
-            .add_service(grpc_service)
-            .serve(grpc_addr)
-            .await
-            .expect("gRPC server failed");
-    });
+            if let Some(path) = audit_log_path {
+                let max_bytes = audit_log_max_mb.saturating_mul(1024 * 1024);
+                let log = audit_log::AuditLog::open(&path, Some(max_bytes), audit_log_keep)
+                    .map_err(|e| format!("Failed to open audit log {:?}: {}", path, e))?
+                    .with_payloads(audit_log_payloads);
+                eprintln!("[AUDIT] Logging conversions to {:?} (rotated at {} MB, {} kept)", path, audit_log_max_mb, audit_log_keep);
+                builder = builder.audit_log(Arc::new(log));
+            }
 
-    let cache_state = CacheState {
-        #[cfg(feature = "cache")]
-        moka: moka_cache,
-        #[cfg(feature = "persistent-cache")]
-        sled: sled_cache,
-    };
-    
     // Initialize job queue if enabled
     #[cfg(feature = "job-queue")]
-    let job_store = if enable_job_queue {
+    if enable_job_queue {
         eprintln!("[JOB QUEUE] Enabled with {} workers", workers);
-        let store = if let Some(backend) = job_queue_backend {
-            if backend.starts_with("redis://") {
-                eprintln!("[JOB QUEUE] Using Redis backend: {}", backend);
-                // For now, use memory store. Redis implementation would go here.
-                job_queue::create_job_store()
-            } else {
-                eprintln!("[JOB QUEUE] Using in-memory backend");
-                job_queue::create_job_store()
+        match job_queue_backend {
+            #[cfg(feature = "distributed-jobs")]
+            Some(backend) if backend.starts_with("redis://") || backend.starts_with("rediss://") => {
+                eprintln!("[JOB QUEUE] Using Redis backend: {} (shared with `toonify worker` nodes)", backend);
+                builder = builder.job_broker(toonify::job_broker::JobBroker::new(&backend)?);
             }
-        } else {
-            eprintln!("[JOB QUEUE] Using in-memory backend");
-            job_queue::create_job_store()
-        };
-        
-        // Start worker threads
-        job_queue::start_workers(Arc::clone(&store), workers);
-        Some(store)
-    } else {
-        None
-    };
-    
-    // Create combined app state
-    #[cfg(feature = "job-queue")]
-    let app_state = AppState {
-        cache: cache_state,
-        job_store,
-    };
-    
-    #[cfg(not(feature = "job-queue"))]
-    let app_state = AppState {
-        cache: cache_state,
-    };
-    
-    let mut app = Router::new()
-        .route("/", get(health_check))
-        .route("/json-to-toon", post(json_to_toon_handler))
-        .route("/toon-to-json", post(toon_to_json_handler));
-
-    // Add job queue routes if enabled
-    #[cfg(feature = "job-queue")]
-    if enable_job_queue {
-        app = app
-            .route("/jobs/submit", post(submit_job_handler))
-            .route("/jobs/{job_id}/status", get(get_job_status_handler))
-            .route("/jobs/{job_id}/result", get(get_job_result_handler))
-            .route("/jobs", get(list_jobs_handler));
+            #[cfg(not(feature = "distributed-jobs"))]
+            Some(backend) if backend.starts_with("redis://") || backend.starts_with("rediss://") => {
+                eprintln!("[JOB QUEUE] Redis backend {} needs a build with --features distributed-jobs; using in-memory backend", backend);
+            }
+            Some(backend) if backend != "memory" => return Err(format!("Unknown job queue backend {:?} (expected memory or a redis:// URL)", backend).into()),
+            _ => eprintln!("[JOB QUEUE] Using in-memory backend"),
+        }
+        builder = builder.job_queue(workers);
     }
-    
-    let mut app = app.with_state(app_state);
-    
+
     // Add rate limiting if enabled
     #[cfg(feature = "rate-limit")]
     if let Some(limit) = rate_limit {
         eprintln!("[RATE LIMIT] Enabled: {} requests per {} seconds (global rate limiting)", limit, rate_limit_window);
-        
-        // Use governor's period() method which takes Duration directly
-        // For N requests per W seconds, we allow N requests total in a W-second window
-        let period = std::time::Duration::from_secs(rate_limit_window) / limit;
-        
-        // Note: burst_size needs to be limit+1 due to how governor's GCRA algorithm works
-        // With burst_size(N), only N-1 rapid requests succeed, so we use N+1 to allow N requests
-        let governor_conf = Arc::new(
-            GovernorConfigBuilder::default()
-                .period(period)                      // Time to replenish 1 token
-                .burst_size(limit + 1)               // burst_size + 1 to allow 'limit' rapid requests
-                .key_extractor(GlobalKeyExtractor)   // Use global rate limiting (not per-IP)
-                .finish()
-                .unwrap()
-        );
-        
-        app = app.layer(GovernorLayer::new(governor_conf));
+        builder = builder.rate_limit(limit, std::time::Duration::from_secs(rate_limit_window));
     }
-    
-    let app = app;
+
+    #[cfg(feature = "grpc-web")]
+    {
+        builder = builder.grpc_web(grpc_web);
+    }
+
+    #[cfg(feature = "grpc-gateway")]
+    {
+        builder = builder.grpc_gateway(grpc_gateway);
+    }
+
+    builder = builder.playground(playground);
+
+    #[cfg(feature = "cluster-cache")]
+    if let Some(url) = cache_invalidation_url {
+        eprintln!("[CACHE] Cache clears shared with other nodes over Valkey/Redis pub/sub");
+        builder = builder.cache_invalidation(toonify::cache_invalidation::CacheInvalidation::new(&url)?);
+    }
+
+    if let Some(dir) = schema_dir {
+        let schemas = toonify::schema_registry::SchemaRegistry::load_dir(&dir)?;
+        eprintln!("[SCHEMAS] Registered {} schemas from {:?}", schemas.list().len(), dir);
+        builder = builder.schema_registry(Arc::new(schemas));
+    }
+
+    let toonify = builder.build()?;
+    let grpc = toonify.clone();
+
+    tokio::spawn(async move {
+                eprintln!("[gRPC] Server listening on {}", grpc_addr);
+        #[cfg(feature = "grpc-web")]
+        if grpc_web {
+            eprintln!("[gRPC] gRPC-Web enabled on {} (HTTP/1.1, CORS for any origin)", grpc_addr);
+        }
+        grpc.serve_grpc(grpc_addr)
+            .await
+            .expect("gRPC server failed");
+    });
+
+    // Job queue routes are mounted when the builder has a job queue
+    let app = toonify.router();
             
             // Bind with custom socket options for better concurrency
             let socket = tokio::net::TcpSocket::new_v4()?;
@@ -1638,59 +3262,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("[HTTP] REST API listening on {}", http_addr);
             eprintln!("Endpoints:");
             eprintln!("   GET  /            - Health check");
+            eprintln!("   GET  /healthz     - Liveness probe");
+            eprintln!("   GET  /readyz      - Readiness probe (503 when the conversion queue is full)");
             eprintln!("   POST /json-to-toon - Convert JSON to TOON");
             eprintln!("   POST /toon-to-json - Convert TOON to JSON");
+            eprintln!("   POST /convert      - Convert from the Content-Type format to the Accept format");
+            eprintln!("   POST /convert/{{from}}/{{to}} - Convert between any formats ({})", converter::registry().names().join(", "));
+            eprintln!("   POST /cache/clear  - Clear cached results");
+            eprintln!("   GET  /cache/status - Cache backend circuit breakers");
+            eprintln!("   PUT  /schemas/{{name}} - Register a validation schema (GET /schemas to list)");
+            #[cfg(feature = "rate-limit")]
+            if rate_limit.is_some() {
+                eprintln!("   GET  /rate-limit   - Remaining rate limit quota");
+            }
+            if playground {
+                eprintln!("   GET  /playground   - Browser playground for trying conversions");
+            }
+            #[cfg(feature = "grpc-gateway")]
+            for path in toonify.grpc_gateway_paths() {
+                eprintln!("   POST {} - gRPC method as JSON", path);
+            }
 
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
                 .with_graceful_shutdown(async {
                     tokio::signal::ctrl_c().await.ok();
                 })
                 .await?;
+
+            #[cfg(feature = "cache")]
+            match toonify.save_cache_snapshot().await {
+                Ok(Some(saved)) => eprintln!("[CACHE] Wrote {} entries to the Moka snapshot", saved),
+                Ok(None) => {}
+                Err(e) => eprintln!("[ERROR] Failed to write the Moka snapshot: {}", e),
+            }
             Ok(())
         }
         None => {
             // Default to serve mode without cache
             tracing_subscriber::fmt::init();
             
-            let grpc_addr: SocketAddr = "0.0.0.0:50051".parse()?;
-            let http_addr: SocketAddr = "0.0.0.0:5000".parse()?;
+            let ServerAddrs { grpc_addr, http_addr } = cli.addrs;
             
             eprintln!("[CACHE] Disabled");
-            
-            let cache_state = CacheState {
-                #[cfg(feature = "cache")]
-                moka: None,
-                #[cfg(feature = "persistent-cache")]
-                sled: None,
-            };
-            
-            #[cfg(feature = "job-queue")]
-            let app_state = AppState {
-                cache: cache_state,
-                job_store: None,
-            };
-            
-            #[cfg(not(feature = "job-queue"))]
-            let app_state = AppState {
-                cache: cache_state,
-            };
-            
-            let grpc_service = ConverterServiceServer::new(ConverterServiceImpl);
-            
+
+            let toonify = ServerBuilder::new().build()?;
+            let grpc = toonify.clone();
+
             tokio::spawn(async move {
                 eprintln!("[gRPC] Server listening on {}", grpc_addr);
-                Server::builder()
-                    .add_service(grpc_service)
-                    .serve(grpc_addr)
+                grpc.serve_grpc(grpc_addr)
         .await
                     .expect("gRPC server failed");
             });
-            
-            let app = Router::new()
-                .route("/", get(health_check))
-                .route("/json-to-toon", post(json_to_toon_handler))
-                .route("/toon-to-json", post(toon_to_json_handler))
-                .with_state(app_state);
+
+            let app = toonify.router();
             
             // Bind with custom socket options for better concurrency
             let socket = tokio::net::TcpSocket::new_v4()?;
@@ -1701,10 +3326,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("[HTTP] REST API listening on {}", http_addr);
             eprintln!("Endpoints:");
             eprintln!("   GET  /            - Health check");
+            eprintln!("   GET  /healthz     - Liveness probe");
+            eprintln!("   GET  /readyz      - Readiness probe (503 when the conversion queue is full)");
             eprintln!("   POST /json-to-toon - Convert JSON to TOON");
             eprintln!("   POST /toon-to-json - Convert TOON to JSON");
+            eprintln!("   POST /convert      - Convert from the Content-Type format to the Accept format");
+            eprintln!("   POST /convert/{{from}}/{{to}} - Convert between any formats ({})", converter::registry().names().join(", "));
+            eprintln!("   POST /cache/clear  - Clear cached results");
+            eprintln!("   GET  /cache/status - Cache backend circuit breakers");
+            eprintln!("   PUT  /schemas/{{name}} - Register a validation schema (GET /schemas to list)");
 
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
                 .with_graceful_shutdown(async {
                     tokio::signal::ctrl_c().await.ok();
                 })
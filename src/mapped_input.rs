@@ -0,0 +1,63 @@
+// File input for CLI conversions
+//
+// Small files are read into a String as before. Files at or above
+// MMAP_THRESHOLD are memory-mapped instead, so a multi-GB input is paged in
+// by the kernel as the parser walks it rather than copied into the heap
+// first. The mapping is validated as UTF-8 once and then borrowed as &str,
+// so the parser and format detection see the same text either way.
+
+#[cfg(feature = "mmap")]
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Inputs this large are mapped rather than read
+#[cfg(feature = "mmap")]
+pub const MMAP_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+pub enum InputText {
+    Owned(String),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl InputText {
+    pub fn is_mapped(&self) -> bool {
+        !matches!(self, InputText::Owned(_))
+    }
+}
+
+impl Deref for InputText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            InputText::Owned(text) => text,
+            // Validated in `read_input_file`
+            #[cfg(feature = "mmap")]
+            InputText::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+}
+
+pub fn read_input_file(path: &Path) -> io::Result<InputText> {
+    #[cfg(feature = "mmap")]
+    {
+        let file = File::open(path)?;
+        if file.metadata()?.len() >= MMAP_THRESHOLD {
+            // SAFETY: the map is read-only. If another process truncates the file
+            // while we convert it, reads fault; that is the usual trade-off for
+            // mapped input and the CLI does not convert files it is writing.
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+            #[cfg(unix)]
+            let _ = map.advise(memmap2::Advice::Sequential);
+            std::str::from_utf8(&map).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{:?} is not valid UTF-8: {}", path, e))
+            })?;
+            return Ok(InputText::Mapped(map));
+        }
+    }
+
+    std::fs::read_to_string(path).map(InputText::Owned)
+}
@@ -0,0 +1,193 @@
+// `toonify mcp`: a Model Context Protocol tool server on stdio
+//
+// Agentic clients (Claude Desktop, IDE agents) spawn this as a subprocess and
+// exchange newline-delimited JSON-RPC 2.0 with it. Three tools are listed:
+//
+//   convert  {input, from?, to?}        -> the converted document
+//   stats    {input, from?}              -> JSON vs TOON sizes and estimated tokens saved
+//   validate {input, format?, schema?}  -> "valid" or the first problem found
+//
+// Tool failures (bad input, guard limits) come back as results with
+// `isError: true` so the model can read them; only protocol mistakes are
+// JSON-RPC errors. Token counts are estimates, as in `toonify report`.
+// Stdout carries protocol messages only; logs go to stderr.
+
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value};
+
+use toonify::converter::{self, Converter};
+
+// Newest first; a client asking for anything else gets the newest
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+pub fn run(converter: Converter) -> Result<(), Box<dyn Error>> {
+    eprintln!("[MCP] Tool server on stdio");
+    let stdin = io::stdin();
+    let mut output = io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Notifications (initialized, cancelled) get no reply
+        let Some(response) = handle_line(&converter, &line) else { continue };
+        writeln!(output, "{}", response)?;
+        output.flush()?;
+    }
+    eprintln!("[MCP] Client closed stdin");
+    Ok(())
+}
+
+fn handle_line(converter: &Converter, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, format!("Invalid JSON: {}", e))),
+    };
+    let id = message.get("id").cloned();
+    let Some(method) = message["method"].as_str() else {
+        // Responses (to requests we never send) need no reply either
+        if message.get("result").is_some() || message.get("error").is_some() {
+            return None;
+        }
+        return Some(error_response(id.unwrap_or(Value::Null), INVALID_REQUEST, "Missing method".to_string()));
+    };
+    let id = id?;
+
+    let params = &message["params"];
+    let result = match method {
+        "initialize" => Ok(initialize(params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call_tool(converter, params),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params["protocolVersion"].as_str().unwrap_or_default();
+    let version = PROTOCOL_VERSIONS.iter().find(|version| **version == requested).unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "toonify", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Convert large JSON tool outputs to TOON before reasoning over them; TOON writes arrays of objects as tables and usually needs far fewer tokens.",
+    })
+}
+
+fn tools() -> Value {
+    let input = || json!({ "type": "string", "description": "The document text" });
+    let format_arg = |what: &str| json!({
+        "type": "string",
+        "enum": ["json", "toon", "yaml", "xml", "csv", "toml"],
+        "description": format!("{} format; detected from the input when omitted", what),
+    });
+    json!([
+        {
+            "name": "convert",
+            "description": "Convert a document between JSON, TOON, YAML, XML, CSV and TOML. Defaults to TOON, or JSON when the input is TOON.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "input": input(), "from": format_arg("Source"), "to": format_arg("Target") },
+                "required": ["input"],
+            },
+        },
+        {
+            "name": "stats",
+            "description": "Compare the size of a document as pretty JSON, minified JSON and TOON, with estimated tokens saved by TOON.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "input": input(), "from": format_arg("Source") },
+                "required": ["input"],
+            },
+        },
+        {
+            "name": "validate",
+            "description": "Check that a document parses, and optionally that it matches a TOONify validation schema.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "input": input(),
+                    "format": format_arg("Input"),
+                    "schema": { "type": "object", "description": "Validation schema, as used by `toonify validate`" },
+                },
+                "required": ["input"],
+            },
+        },
+    ])
+}
+
+fn call_tool(converter: &Converter, params: &Value) -> Result<Value, (i64, String)> {
+    let name = params["name"].as_str().ok_or((INVALID_PARAMS, "params.name must be a string".to_string()))?;
+    let arguments = &params["arguments"];
+    let outcome = match name {
+        "convert" => convert(converter, arguments),
+        "stats" => stats(converter, arguments),
+        "validate" => validate(converter, arguments),
+        _ => return Err((INVALID_PARAMS, format!("Unknown tool: {}", name))),
+    };
+    Ok(match outcome {
+        Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+        Err(e) => json!({ "content": [{ "type": "text", "text": e }], "isError": true }),
+    })
+}
+
+fn input_and_format<'a>(arguments: &'a Value, key: &str) -> Result<(&'a str, String), String> {
+    let input = arguments["input"].as_str().ok_or("arguments.input must be a string")?;
+    let format = match arguments[key].as_str() {
+        Some(format) => format.to_string(),
+        None => converter::detect_format(None, input.as_bytes())?.to_string(),
+    };
+    Ok((input, format))
+}
+
+fn convert(converter: &Converter, arguments: &Value) -> Result<String, String> {
+    let (input, from) = input_and_format(arguments, "from")?;
+    let to = arguments["to"].as_str().unwrap_or_else(|| crate::default_target_format(&from));
+    let converted = converter.convert_detailed(input, &from, to)?;
+    for warning in &converted.warnings {
+        eprintln!("[MCP] Warning: {}", warning);
+    }
+    Ok(converted.output)
+}
+
+fn stats(converter: &Converter, arguments: &Value) -> Result<String, String> {
+    let (input, from) = input_and_format(arguments, "from")?;
+    let (value, _) = converter.parse(input, &from)?;
+    let pretty = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?.len();
+    let minified = serde_json::to_string(&value).map_err(|e| e.to_string())?.len();
+    let toon = converter.emit(&value, "toon")?.len();
+    let tokens = crate::report::estimated_tokens;
+    let stats = json!({
+        "from": from,
+        "bytes": { "json_pretty": pretty, "json_minified": minified, "toon": toon },
+        "estimated_tokens": { "json_pretty": tokens(pretty), "json_minified": tokens(minified), "toon": tokens(toon) },
+        "estimated_tokens_saved": tokens(pretty).saturating_sub(tokens(toon)),
+        "toon_percent_of_json": (1000.0 * toon as f64 / pretty.max(1) as f64).round() / 10.0,
+    });
+    serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())
+}
+
+fn validate(converter: &Converter, arguments: &Value) -> Result<String, String> {
+    let (input, format) = input_and_format(arguments, "format")?;
+    let (value, _) = converter.parse(input, &format)?;
+    if let Some(schema) = arguments.get("schema").filter(|schema| !schema.is_null()) {
+        crate::validate_value(&value, schema).map_err(|e| e.to_string())?;
+    }
+    Ok(format!("valid {}", format))
+}
@@ -0,0 +1,151 @@
+// Combining and splitting documents by entity (`toonify merge` / `split`)
+//
+// `merge` folds documents left to right. An entity present in only one input
+// is copied; two tables with compatible schemas are concatenated; identical
+// values are kept once. Anything else (a scalar that differs, a table meeting
+// an object, or a column whose types disagree, say `id:int` and `id:str`) is
+// a conflict, and the later entity is rekeyed as `<name>_<n>` for the n-th
+// input. `Union` keeps every column (missing cells become null);
+// `IntersectColumns` keeps the columns both tables share, and tables sharing
+// none conflict.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+use crate::toon::{columns_of, ColumnType};
+
+/// How `merge` combines two tables with the same entity name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep every column from both tables
+    #[default]
+    Union,
+    /// Keep only the columns both tables have
+    IntersectColumns,
+}
+
+impl MergeStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeStrategy::Union => "union",
+            MergeStrategy::IntersectColumns => "intersect-columns",
+        }
+    }
+}
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "union" => Ok(MergeStrategy::Union),
+            "intersect-columns" => Ok(MergeStrategy::IntersectColumns),
+            _ => Err(format!("Unknown merge strategy '{}' (expected union or intersect-columns)", s)),
+        }
+    }
+}
+
+/// Merge documents (each with an object root) in order
+///
+/// Returns the combined document and one message per rekeyed entity.
+pub fn merge(documents: Vec<Value>, strategy: MergeStrategy) -> Result<(Value, Vec<String>), String> {
+    let mut merged = Map::new();
+    let mut warnings = Vec::new();
+
+    for (index, document) in documents.into_iter().enumerate() {
+        let Value::Object(entities) = document else {
+            return Err(format!("Input {} must have an object root to merge", index + 1));
+        };
+        for (name, entity) in entities {
+            let Some(existing) = merged.get_mut(&name) else {
+                merged.insert(name, entity);
+                continue;
+            };
+            if *existing == entity {
+                continue;
+            }
+            match combine(existing, &entity, strategy) {
+                Ok(combined) => *existing = combined,
+                Err(reason) => {
+                    let mut rekeyed = format!("{}_{}", name, index + 1);
+                    let mut suffix = 1;
+                    while merged.contains_key(&rekeyed) {
+                        suffix += 1;
+                        rekeyed = format!("{}_{}_{}", name, index + 1, suffix);
+                    }
+                    warnings.push(format!("{} from input {} renamed to {}: {}", name, index + 1, rekeyed, reason));
+                    merged.insert(rekeyed, entity);
+                }
+            }
+        }
+    }
+    Ok((Value::Object(merged), warnings))
+}
+
+/// Split a document into one single-entity document per root key
+pub fn split(document: Value) -> Result<Vec<(String, Value)>, String> {
+    let Value::Object(entities) = document else {
+        return Err("Only documents with an object root can be split by entity".to_string());
+    };
+    Ok(entities
+        .into_iter()
+        .map(|(name, entity)| {
+            let mut single = Map::new();
+            single.insert(name.clone(), entity);
+            (name, Value::Object(single))
+        })
+        .collect())
+}
+
+// Concatenate two tables, or explain why they conflict
+fn combine(left: &Value, right: &Value, strategy: MergeStrategy) -> Result<Value, String> {
+    let (Some(left_rows), Some(right_rows)) = (table_rows(left), table_rows(right)) else {
+        return Err("values differ and are not both tables".to_string());
+    };
+    let kept: Vec<&str> = match strategy {
+        MergeStrategy::Union => columns_of(left_rows.iter().chain(&right_rows).copied()),
+        MergeStrategy::IntersectColumns if left_rows.is_empty() => columns_of(right_rows.iter().copied()),
+        MergeStrategy::IntersectColumns if right_rows.is_empty() => columns_of(left_rows.iter().copied()),
+        MergeStrategy::IntersectColumns => {
+            let right_columns: HashSet<&str> = columns_of(right_rows.iter().copied()).into_iter().collect();
+            let shared: Vec<&str> = columns_of(left_rows.iter().copied())
+                .into_iter()
+                .filter(|column| right_columns.contains(column))
+                .collect();
+            if shared.is_empty() {
+                return Err("no columns in common".to_string());
+            }
+            shared
+        }
+    };
+
+    for column in &kept {
+        let left_type = ColumnType::infer(left_rows.iter().filter_map(|row| row.get(*column)));
+        let right_type = ColumnType::infer(right_rows.iter().filter_map(|row| row.get(*column)));
+        if let (Some(a), Some(b)) = (left_type, right_type) {
+            let both = left_rows.iter().chain(&right_rows).filter_map(|row| row.get(*column));
+            if ColumnType::infer(both).is_none() {
+                return Err(format!("column {} is {} in one table and {} in the other", column, a.as_str(), b.as_str()));
+            }
+        }
+    }
+
+    let rows = left_rows
+        .iter()
+        .chain(&right_rows)
+        .map(|row| {
+            let projected: Map<String, Value> = kept
+                .iter()
+                .map(|column| (column.to_string(), row.get(*column).cloned().unwrap_or(Value::Null)))
+                .collect();
+            Value::Object(projected)
+        })
+        .collect();
+    Ok(Value::Array(rows))
+}
+
+fn table_rows(value: &Value) -> Option<Vec<&Map<String, Value>>> {
+    value.as_array()?.iter().map(Value::as_object).collect()
+}
@@ -0,0 +1,113 @@
+// Per-column statistics for table entities (`toonify profile`)
+//
+// Every entity that is a table (array of objects, or a single object as one
+// row) is profiled column by column; a list of scalars is one column named
+// `value`. Root scalars are skipped. The inferred type is what typed headers
+// would declare (`mixed` when values disagree, `null` when there are none);
+// min/max compare numbers numerically and strings lexicographically and are
+// left out for mixed and nested columns.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::toon::ColumnType;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityProfile {
+    pub name: String,
+    pub rows: usize,
+    pub columns: Vec<ColumnProfile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub inferred_type: String,
+    /// Rows where the cell is null or missing
+    pub nulls: usize,
+    pub null_rate: f64,
+    /// Distinct non-null values
+    pub distinct: usize,
+    pub min: Option<Value>,
+    pub max: Option<Value>,
+    /// Mean length in characters of the string cells
+    pub avg_length: Option<f64>,
+}
+
+/// Profile every table entity of a document
+pub fn profile(value: &Value) -> Vec<EntityProfile> {
+    match value {
+        Value::Object(root) => root.iter().filter_map(|(name, entity)| profile_entity(name, entity)).collect(),
+        other => profile_entity("data", other).into_iter().collect(),
+    }
+}
+
+fn profile_entity(name: &str, entity: &Value) -> Option<EntityProfile> {
+    let rows: Vec<Map<String, Value>> = match entity {
+        Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => {
+            items.iter().filter_map(Value::as_object).cloned().collect()
+        }
+        Value::Array(items) if !items.is_empty() => items
+            .iter()
+            .map(|item| {
+                let mut row = Map::new();
+                row.insert("value".to_string(), item.clone());
+                row
+            })
+            .collect(),
+        Value::Object(row) => vec![row.clone()],
+        _ => return None,
+    };
+
+    let mut names: Vec<&str> = Vec::new();
+    for row in &rows {
+        for key in row.keys() {
+            if !names.contains(&key.as_str()) {
+                names.push(key);
+            }
+        }
+    }
+    let columns = names.iter().map(|column| profile_column(column, &rows)).collect();
+    Some(EntityProfile { name: name.to_string(), rows: rows.len(), columns })
+}
+
+fn profile_column(name: &str, rows: &[Map<String, Value>]) -> ColumnProfile {
+    let values: Vec<&Value> = rows.iter().filter_map(|row| row.get(name)).filter(|value| !value.is_null()).collect();
+    let nulls = rows.len() - values.len();
+    let ty = ColumnType::infer(values.iter().copied());
+
+    let distinct: HashSet<String> = values.iter().map(|value| value.to_string()).collect();
+    let (min, max) = match ty {
+        Some(ColumnType::Int | ColumnType::Float | ColumnType::Num) => extremes(&values, |a, b| a.as_f64().partial_cmp(&b.as_f64()).unwrap_or(Ordering::Equal)),
+        Some(ColumnType::Str) => extremes(&values, |a, b| a.as_str().cmp(&b.as_str())),
+        Some(ColumnType::Bool) => extremes(&values, |a, b| a.as_bool().cmp(&b.as_bool())),
+        _ => (None, None),
+    };
+    let lengths: Vec<usize> = values.iter().filter_map(|value| value.as_str()).map(|s| s.chars().count()).collect();
+    let avg_length = (!lengths.is_empty()).then(|| lengths.iter().sum::<usize>() as f64 / lengths.len() as f64);
+
+    let inferred_type = match ty {
+        Some(ty) => ty.as_str(),
+        None if values.is_empty() => "null",
+        None => "mixed",
+    };
+    ColumnProfile {
+        name: name.to_string(),
+        inferred_type: inferred_type.to_string(),
+        nulls,
+        null_rate: if rows.is_empty() { 0.0 } else { nulls as f64 / rows.len() as f64 },
+        distinct: distinct.len(),
+        min,
+        max,
+        avg_length,
+    }
+}
+
+fn extremes(values: &[&Value], cmp: impl Fn(&Value, &Value) -> Ordering) -> (Option<Value>, Option<Value>) {
+    let min = values.iter().copied().min_by(|a, b| cmp(a, b)).cloned();
+    let max = values.iter().copied().max_by(|a, b| cmp(a, b)).cloned();
+    (min, max)
+}
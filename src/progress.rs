@@ -0,0 +1,148 @@
+// Progress reporting for batch and compress
+//
+// `batch --quiet` replaces the per-file [BATCH] log lines with a single
+// progress bar (files/sec, bytes processed, ETA); failures are still
+// printed, above the bar. `compress`/`decompress` show a byte bar for large
+// inputs. Bars draw only when stderr is a terminal, so scripted runs and
+// tests see plain output, and are left out entirely without the `progress`
+// feature.
+
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "progress")]
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+
+/// Inputs at least this large get a byte progress bar in compress/decompress
+#[cfg(feature = "progress")]
+pub const BYTE_BAR_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+pub struct BatchProgress {
+    quiet: bool,
+    bytes: AtomicU64,
+    #[cfg(feature = "progress")]
+    bar: Option<ProgressBar>,
+}
+
+impl BatchProgress {
+    pub fn new(total_files: usize, quiet: bool) -> Self {
+        #[cfg(feature = "progress")]
+        let bar = quiet.then(|| {
+            let bar = ProgressBar::new(total_files as u64);
+            bar.set_style(
+                ProgressStyle::with_template("[BATCH] {bar:40.cyan/blue} {pos}/{len} files ({per_sec}, {msg}) ETA {eta}")
+                    .expect("valid progress template")
+                    .progress_chars("=> "),
+            );
+            bar
+        });
+        #[cfg(not(feature = "progress"))]
+        let _ = total_files;
+
+        Self {
+            quiet,
+            bytes: AtomicU64::new(0),
+            #[cfg(feature = "progress")]
+            bar,
+        }
+    }
+
+    /// Per-file detail, suppressed by --quiet
+    pub fn log(&self, message: &str) {
+        if !self.quiet {
+            eprintln!("{}", message);
+        }
+    }
+
+    /// Failures are always shown, without tearing the bar
+    pub fn error(&self, message: &str) {
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.suspend(|| eprintln!("{}", message));
+            return;
+        }
+        eprintln!("{}", message);
+    }
+
+    /// Count one finished file (converted or failed) and the bytes read for it
+    pub fn file_done(&self, bytes: u64) {
+        let total = self.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.set_message(format!("{} read", HumanBytes(total)));
+            bar.inc(1);
+        }
+        #[cfg(not(feature = "progress"))]
+        let _ = total;
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn finish(&self) {
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// Byte progress for a single large stream
+pub struct ByteProgress {
+    #[cfg(feature = "progress")]
+    bar: Option<ProgressBar>,
+}
+
+impl ByteProgress {
+    pub fn new(label: &str, total_bytes: u64) -> Self {
+        #[cfg(feature = "progress")]
+        let bar = (total_bytes >= BYTE_BAR_THRESHOLD).then(|| {
+            let bar = ProgressBar::new(total_bytes);
+            bar.set_style(
+                ProgressStyle::with_template("{prefix} {bar:40.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}) ETA {eta}")
+                    .expect("valid progress template")
+                    .progress_chars("=> "),
+            );
+            bar.set_prefix(label.to_string());
+            bar
+        });
+        #[cfg(not(feature = "progress"))]
+        let _ = (label, total_bytes);
+
+        Self {
+            #[cfg(feature = "progress")]
+            bar,
+        }
+    }
+
+    pub fn inc(&self, bytes: u64) {
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.inc(bytes);
+        }
+        #[cfg(not(feature = "progress"))]
+        let _ = bytes;
+    }
+
+    pub fn finish(&self) {
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// Reader adapter that advances a `ByteProgress` as data is consumed
+pub struct ProgressReader<'a, R> {
+    pub inner: R,
+    pub progress: &'a ByteProgress,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.progress.inc(n as u64);
+        Ok(n)
+    }
+}
@@ -0,0 +1,285 @@
+// `toonify publish`: a static site for browsing and downloading datasets
+//
+// Every file under the input directory with a registered format extension
+// (.json, .toon, .csv, ...) is a dataset; others are skipped. The site
+// has an index of datasets and their entities, one page per entity with its
+// table (first `max_rows` rows), and downloads of each dataset and each
+// entity as TOON, JSON and gzipped TOON. Only relative links are used and
+// pages have no external assets, so the directory can be served by any web
+// server, attached to a wiki, or opened from disk. Paths and entity names
+// are reduced to `[A-Za-z0-9._-]` for file names; pages show the originals.
+//
+//     site/index.html
+//     site/sales/q1.toon, q1.json, q1.toon.gz     (data/sales/q1.json)
+//     site/sales/q1/orders.html, orders.toon, ...
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use toonify::converter::{self, Converter};
+use toonify::export::{escape_html, html_table};
+
+use crate::file_walk::{self, FileWalk};
+
+pub struct PublishOptions {
+    pub input_dir: PathBuf,
+    pub output: PathBuf,
+    /// Glob matched against paths below `input_dir` (default: every file)
+    pub pattern: Option<String>,
+    pub max_rows: usize,
+    pub title: String,
+}
+
+struct Dataset {
+    /// Source path relative to the input directory
+    source: String,
+    format: &'static str,
+    /// Sanitized relative path without extension: `sales/q1`
+    slug: String,
+    downloads: Vec<Download>,
+    entities: Vec<Entity>,
+}
+
+struct Entity {
+    name: String,
+    /// File stem for the page and downloads, unique within the dataset
+    file: String,
+    rows: usize,
+    downloads: Vec<Download>,
+}
+
+struct Download {
+    label: &'static str,
+    file: String,
+    bytes: usize,
+}
+
+pub fn run_publish(options: PublishOptions, converter: &Converter) -> Result<(), Box<dyn Error>> {
+    if !options.input_dir.is_dir() {
+        return Err(format!("Input directory {:?} does not exist", options.input_dir).into());
+    }
+    fs::create_dir_all(&options.output)?;
+    eprintln!("[PUBLISH] Reading datasets from {:?}", options.input_dir);
+
+    // Publishing into a directory under the input must not pick up the last run's downloads
+    let site = file_walk::canonicalize(&options.output)?;
+    let files = FileWalk::new(options.pattern.as_deref(), true, false)?.run(&options.input_dir);
+    let mut datasets = Vec::new();
+    let mut slugs = HashSet::new();
+    for path in files {
+        if file_walk::canonicalize(&path).is_ok_and(|path| path.starts_with(&site)) {
+            continue;
+        }
+        match publish_dataset(&options, converter, &path, &mut slugs) {
+            Ok(dataset) => {
+                eprintln!("[PUBLISH] {} ({} entities)", dataset.source, dataset.entities.len());
+                datasets.push(dataset);
+            }
+            Err(e) => eprintln!("[PUBLISH] Skipping {:?}: {}", path, e),
+        }
+    }
+    if datasets.is_empty() {
+        return Err(format!("No datasets found in {:?}", options.input_dir).into());
+    }
+
+    fs::write(options.output.join("index.html"), render_index(&options.title, &datasets))?;
+    let tables: usize = datasets.iter().map(|dataset| dataset.entities.len()).sum();
+    println!("✓ Published {} datasets ({} tables) to {:?}", datasets.len(), tables, options.output);
+    Ok(())
+}
+
+fn publish_dataset(options: &PublishOptions, converter: &Converter, path: &Path, slugs: &mut HashSet<String>) -> Result<Dataset, Box<dyn Error>> {
+    let format = path.extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| converter::registry().for_extension(extension))
+        .map(|codec| codec.name())
+        .ok_or("not a registered data format")?;
+    let content = fs::read_to_string(path)?;
+    let (value, _) = converter.parse(&content, format)?;
+    let value = converter.transform(value)?;
+
+    let relative = path.strip_prefix(&options.input_dir).unwrap_or(path);
+    let source = relative.to_string_lossy().replace('\\', "/");
+    let slug: Vec<String> = relative.with_extension("").components()
+        .map(|component| file_name(&component.as_os_str().to_string_lossy()))
+        .collect();
+    let mut slug = slug.join("/");
+    // sales.json and sales.csv side by side
+    if !slugs.insert(slug.clone()) {
+        slug = format!("{}_{}", slug, format);
+        slugs.insert(slug.clone());
+    }
+    let dataset_dir = options.output.join(&slug);
+    fs::create_dir_all(&dataset_dir)?;
+
+    let stem = slug.rsplit('/').next().unwrap_or(&slug).to_string();
+    let downloads = write_downloads(dataset_dir.parent().unwrap_or(&options.output), &stem, converter, &value)?;
+
+    let named: Vec<(String, Value)> = match value {
+        Value::Object(map) => map.into_iter().collect(),
+        other => vec![(stem.clone(), other)],
+    };
+    let depth = slug.split('/').count();
+    let mut used = HashSet::new();
+    let mut entities = Vec::new();
+    for (name, entity) in named {
+        let mut file = file_name(&name);
+        let base = file.clone();
+        let mut n = 1;
+        while !used.insert(file.clone()) {
+            n += 1;
+            file = format!("{}-{}", base, n);
+        }
+
+        let mut single = Map::new();
+        single.insert(name.clone(), entity);
+        let single = Value::Object(single);
+        let entity = Entity {
+            rows: single[&name].as_array().map_or(1, Vec::len),
+            downloads: write_downloads(&dataset_dir, &file, converter, &single)?,
+            name,
+            file,
+        };
+        let page = render_entity(&options.title, &source, &entity, &single[&entity.name], options.max_rows, depth);
+        fs::write(dataset_dir.join(format!("{}.html", entity.file)), page)?;
+        entities.push(entity);
+    }
+
+    Ok(Dataset { source, format, slug, downloads, entities })
+}
+
+// `stem`.toon, .json and .toon.gz in `dir`
+fn write_downloads(dir: &Path, stem: &str, converter: &Converter, value: &Value) -> Result<Vec<Download>, Box<dyn Error>> {
+    let toon = converter.emit(value, "toon")?;
+    let json = serde_json::to_string_pretty(value)?;
+    let gzip = crate::gzip_bytes(toon.as_bytes())?;
+
+    let mut downloads = Vec::new();
+    for (label, extension, bytes) in [("TOON", "toon", toon.as_bytes()), ("JSON", "json", json.as_bytes()), ("TOON (gzip)", "toon.gz", gzip.as_slice())] {
+        let file = format!("{}.{}", stem, extension);
+        fs::write(dir.join(&file), bytes)?;
+        downloads.push(Download { label, file, bytes: bytes.len() });
+    }
+    Ok(downloads)
+}
+
+// Safe in a file name and an href without escaping
+fn file_name(name: &str) -> String {
+    let cleaned: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+        .collect();
+    match cleaned.trim_start_matches('.') {
+        "" => "_".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+fn download_links(prefix: &str, downloads: &[Download]) -> String {
+    downloads.iter()
+        .map(|download| format!(
+            "<a href=\"{}{}\" download>{}</a> <span class=\"meta\">{}</span>",
+            prefix,
+            download.file,
+            download.label,
+            size(download.bytes)
+        ))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+fn size(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn page_start(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n{}</head>\n<body>\n",
+        escape_html(title),
+        STYLE
+    )
+}
+
+fn page_end() -> String {
+    format!("<footer class=\"meta\">Published with toonify {}</footer>\n</body>\n</html>\n", env!("CARGO_PKG_VERSION"))
+}
+
+fn render_index(title: &str, datasets: &[Dataset]) -> String {
+    let mut html = page_start(title);
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(title)));
+    html.push_str(&format!(
+        "<p class=\"meta\">{} datasets · {} tables</p>\n",
+        datasets.len(),
+        datasets.iter().map(|dataset| dataset.entities.len()).sum::<usize>()
+    ));
+
+    for dataset in datasets {
+        let dir = dataset.slug.rsplit_once('/').map_or(String::new(), |(dir, _)| format!("{}/", dir));
+        html.push_str(&format!("<h2>{}</h2>\n", escape_html(&dataset.source)));
+        html.push_str(&format!(
+            "<p>{} · <span class=\"meta\">from {}</span></p>\n",
+            download_links(&dir, &dataset.downloads),
+            escape_html(dataset.format)
+        ));
+        html.push_str("<table>\n<tr><th>Entity</th><th>Rows</th><th>Downloads</th></tr>\n");
+        for entity in &dataset.entities {
+            let prefix = format!("{}/", dataset.slug);
+            html.push_str(&format!(
+                "<tr><td><a href=\"{}{}.html\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+                prefix,
+                entity.file,
+                escape_html(&entity.name),
+                entity.rows,
+                download_links(&prefix, &entity.downloads)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str(&page_end());
+    html
+}
+
+fn render_entity(title: &str, source: &str, entity: &Entity, value: &Value, max_rows: usize, depth: usize) -> String {
+    let mut html = page_start(&format!("{}: {}", entity.name, title));
+    html.push_str(&format!(
+        "<p class=\"meta\"><a href=\"{}index.html\">{}</a> / {}</p>\n",
+        "../".repeat(depth),
+        escape_html(title),
+        escape_html(source)
+    ));
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&entity.name)));
+    html.push_str(&format!("<p>{} rows · {}</p>\n", entity.rows, download_links("", &entity.downloads)));
+
+    match value {
+        Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => html.push_str(&html_table(value, max_rows)),
+        Value::Object(_) => html.push_str(&html_table(value, max_rows)),
+        other => html.push_str(&format!("<pre>{}</pre>\n", escape_html(&serde_json::to_string_pretty(other).unwrap_or_default()))),
+    }
+    if entity.rows > max_rows {
+        html.push_str(&format!("<p class=\"meta\">Showing {} of {} rows; download the file for the rest</p>\n", max_rows, entity.rows));
+    }
+
+    html.push_str(&page_end());
+    html
+}
+
+const STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 72rem; padding: 0 1rem; color: #222; }
+h1 { font-size: 1.5rem; }
+h2 { font-size: 1.15rem; margin-top: 2rem; }
+a { color: #1f5fbf; }
+.meta { color: #666; }
+table { border-collapse: collapse; margin: 0.5rem 0 1.5rem; font-size: 0.9rem; }
+th, td { border: 1px solid #ddd; padding: 0.25rem 0.5rem; text-align: left; }
+th { background: #f5f5f5; }
+footer { margin-top: 3rem; font-size: 0.85rem; }
+</style>
+";
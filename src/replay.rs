@@ -0,0 +1,196 @@
+// `toonify replay`: re-issue conversions from an audit log against another server (feature `replay`)
+//
+//     toonify serve --audit-log audit.jsonl --audit-log-payloads     # production
+//     toonify replay audit.jsonl --target http://staging:5000
+//
+// Every record with a payload is POSTed to the same REST endpoint on the
+// target (gRPC records go to the equivalent /convert/{from}/{to}) and the
+// outcome is compared with the recorded one: success or failure must agree,
+// and successful results must be identical, or equal as JSON when both sides
+// parse as JSON. Records without payloads are skipped. Any difference makes
+// the command fail, so it can gate an upgrade in CI.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+pub struct ReplayOptions {
+    pub logs: Vec<PathBuf>,
+    /// Base URL of the server to replay against, e.g. http://staging:5000
+    pub target: String,
+    pub concurrency: usize,
+    /// JSONL file describing each difference
+    pub report: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Recorded {
+    transport: String,
+    endpoint: String,
+    from: String,
+    to: String,
+    status: u16,
+    error: Option<String>,
+    input: Option<String>,
+    output: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ConvertResult {
+    result: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Difference {
+    log: PathBuf,
+    line: usize,
+    endpoint: String,
+    reason: String,
+    expected: Option<String>,
+    actual: Option<String>,
+}
+
+// The outcome of one conversion: the result, or the error message
+type Outcome = Result<String, String>;
+
+pub async fn run(options: ReplayOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let target = options.target.trim_end_matches('/').to_string();
+    let client = reqwest::Client::new();
+    let permits = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+    let mut skipped = 0;
+    let mut index = 0;
+
+    for log in &options.logs {
+        let reader = BufReader::new(File::open(log).map_err(|e| format!("Failed to open {:?}: {}", log, e))?);
+        for (line_index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let recorded: Recorded = serde_json::from_str(&line)
+                .map_err(|e| format!("{:?} line {}: not an audit record: {}", log, line_index + 1, e))?;
+            let Some(input) = recorded.input.clone() else {
+                skipped += 1;
+                continue;
+            };
+
+            let path = match recorded.transport.as_str() {
+                "rest" => recorded.endpoint.clone(),
+                _ => format!("/convert/{}/{}", recorded.from, recorded.to),
+            };
+            let (client, permits, url) = (client.clone(), Arc::clone(&permits), format!("{}{}", target, path));
+            let (log, line) = (log.clone(), line_index + 1);
+            index += 1;
+            let order = index;
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let actual = replay_one(&client, &url, input).await;
+                (order, compare(log, line, recorded, actual))
+            });
+        }
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined?);
+    }
+    results.sort_by_key(|(order, _)| *order);
+
+    let replayed = results.len();
+    let differences: Vec<Difference> = results.into_iter().filter_map(|(_, difference)| difference).collect();
+    for difference in &differences {
+        eprintln!("[REPLAY] ✗ {:?} line {} {}: {}", difference.log, difference.line, difference.endpoint, difference.reason);
+    }
+    if let Some(path) = &options.report {
+        let mut report = File::create(path)?;
+        for difference in &differences {
+            writeln!(report, "{}", serde_json::to_string(difference)?)?;
+        }
+    }
+
+    println!(
+        "{} {} replayed against {}: {} identical, {} different, {} skipped (no payload; record with --audit-log-payloads)",
+        if differences.is_empty() { "✓" } else { "✗" },
+        replayed,
+        target,
+        replayed - differences.len(),
+        differences.len(),
+        skipped
+    );
+    if !differences.is_empty() {
+        return Err(format!("{} of {} replayed conversions differ", differences.len(), replayed).into());
+    }
+    Ok(())
+}
+
+async fn replay_one(client: &reqwest::Client, url: &str, input: String) -> Result<Outcome, String> {
+    let response = client
+        .post(url)
+        .json(&serde_json::json!({ "data": input }))
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| format!("reading response failed: {}", e))?;
+    match serde_json::from_str::<ConvertResult>(&body) {
+        Ok(ConvertResult { result: Some(result), error: None }) if status.is_success() => Ok(Ok(result)),
+        Ok(ConvertResult { error: Some(error), .. }) => Ok(Err(error)),
+        _ => Ok(Err(format!("HTTP {}", status))),
+    }
+}
+
+fn compare(log: PathBuf, line: usize, recorded: Recorded, actual: Result<Outcome, String>) -> Option<Difference> {
+    let succeeded = match recorded.transport.as_str() {
+        "rest" => recorded.status == 200,
+        _ => recorded.status == 0,
+    } && recorded.error.is_none();
+    let expected: Outcome = if succeeded { Ok(recorded.output.unwrap_or_default()) } else { Err(recorded.error.unwrap_or_default()) };
+    let difference = |reason: String, expected: Option<String>, actual: Option<String>| {
+        Some(Difference { log: log.clone(), line, endpoint: recorded.endpoint.clone(), reason, expected, actual })
+    };
+
+    match (expected, actual) {
+        (_, Err(e)) => difference(e, None, None),
+        (Ok(expected), Ok(Ok(actual))) => {
+            if expected == actual || same_json(&expected, &actual) {
+                None
+            } else {
+                let reason = first_difference(&expected, &actual);
+                difference(reason, Some(expected), Some(actual))
+            }
+        }
+        (Ok(expected), Ok(Err(error))) => difference(format!("recorded success, now fails: {}", error), Some(expected), None),
+        (Err(error), Ok(Ok(actual))) => difference(format!("recorded failure ({}), now succeeds", error), None, Some(actual)),
+        // Both failed; messages may legitimately change between versions
+        (Err(_), Ok(Err(_))) => None,
+    }
+}
+
+fn same_json(a: &str, b: &str) -> bool {
+    match (serde_json::from_str::<serde_json::Value>(a), serde_json::from_str::<serde_json::Value>(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut number = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => number += 1,
+            (None, None) => return "results differ only in line endings or trailing whitespace".to_string(),
+            (e, a) => {
+                return format!("result differs at line {}: expected {:?}, got {:?}", number, e.unwrap_or("<end>"), a.unwrap_or("<end>"));
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// `toonify report`: a self-contained HTML audit of one conversion
+//
+// The page has no external assets (styles inline, charts are CSS bars) so it
+// can be mailed or attached to a ticket as-is. It shows the size of the
+// document as pretty JSON, minified JSON and TOON, the same per entity, each
+// entity as a table (first `max_rows` rows), and validation results: the
+// TOON round-trip, parser/secret warnings and an optional schema check.
+// Token counts are estimates (about four bytes per token), not a tokenizer.
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use toonify::converter::{self, Converter};
+use toonify::export::{escape_html, html_table};
+
+pub struct ReportOptions {
+    pub input: String,
+    pub output: Option<PathBuf>,
+    pub from: Option<String>,
+    pub schema: Option<PathBuf>,
+    pub max_rows: usize,
+}
+
+struct Sizes {
+    pretty: usize,
+    minified: usize,
+    toon: usize,
+}
+
+pub fn run_report(options: ReportOptions, converter: &Converter) -> Result<(), Box<dyn Error>> {
+    let content = if options.input == "-" {
+        eprintln!("[REPORT] Reading from STDIN");
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer
+    } else {
+        eprintln!("[REPORT] Reading from file: {}", options.input);
+        fs::read_to_string(&options.input)?
+    };
+    let from = match options.from {
+        Some(from) => from,
+        None => converter::detect_format(Some(Path::new(&options.input)).filter(|_| options.input != "-"), content.as_bytes())?.to_string(),
+    };
+
+    let converted = converter.convert_detailed(&content, &from, "toon").map_err(|e| format!("Conversion failed: {}", e))?;
+    let (value, _) = converter.parse(&content, &from)?;
+    let value = converter.transform(value)?;
+
+    let mut checks: Vec<(bool, String)> = Vec::new();
+    let (decoded, _) = converter.parse(&converted.output, "toon")?;
+    checks.push(if decoded == value {
+        (true, "TOON round-trip reproduces the input".to_string())
+    } else {
+        (false, "TOON round-trip changes the data".to_string())
+    });
+    if let Some(schema_path) = &options.schema {
+        let schema: Value = serde_json::from_str(&fs::read_to_string(schema_path)?)
+            .map_err(|e| format!("Invalid schema JSON: {}", e))?;
+        checks.push(match crate::validate_value(&value, &schema) {
+            Ok(()) => (true, format!("Matches schema {}", schema_path.display())),
+            Err(e) => (false, format!("Schema {}: {}", schema_path.display(), e)),
+        });
+    }
+    checks.extend(converted.warnings.iter().map(|warning| (false, format!("Warning: {}", warning))));
+
+    let total = Sizes {
+        pretty: serde_json::to_string_pretty(&value)?.len(),
+        minified: serde_json::to_string(&value)?.len(),
+        toon: converted.output.len(),
+    };
+    let mut entities: Vec<(String, Sizes, &Value)> = Vec::new();
+    if let Value::Object(map) = &value {
+        for (name, entity) in map {
+            let mut single = Map::new();
+            single.insert(name.clone(), entity.clone());
+            let single = Value::Object(single);
+            let sizes = Sizes {
+                pretty: serde_json::to_string_pretty(&single)?.len(),
+                minified: serde_json::to_string(&single)?.len(),
+                toon: converter.emit(&single, "toon")?.len(),
+            };
+            entities.push((name.clone(), sizes, entity));
+        }
+    }
+
+    let title = if options.input == "-" { "stdin".to_string() } else { options.input.clone() };
+    let html = render(&title, &from, &total, &entities, &checks, options.max_rows);
+
+    match options.output {
+        Some(path) => {
+            eprintln!("[REPORT] Writing to file: {:?}", path);
+            fs::write(&path, html)?;
+            println!("✓ Report written to {:?}", path);
+        }
+        None => io::stdout().write_all(html.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn render(title: &str, from: &str, total: &Sizes, entities: &[(String, Sizes, &Value)], checks: &[(bool, String)], max_rows: usize) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>TOON report: {}</title>\n", escape_html(title)));
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>TOON report: {}</h1>\n", escape_html(title)));
+    html.push_str(&format!(
+        "<p class=\"meta\">Source format {} · {} entities · toonify {}</p>\n",
+        escape_html(from),
+        entities.len(),
+        env!("CARGO_PKG_VERSION")
+    ));
+
+    html.push_str("<h2>Validation</h2>\n<ul class=\"checks\">\n");
+    for (ok, message) in checks {
+        let (class, mark) = if *ok { ("pass", "✓") } else { ("fail", "✗") };
+        html.push_str(&format!("<li class=\"{}\">{} {}</li>\n", class, mark, escape_html(message)));
+    }
+    html.push_str("</ul>\n");
+
+    html.push_str("<h2>Size</h2>\n");
+    let saved = 100.0 * (1.0 - total.toon as f64 / total.pretty.max(1) as f64);
+    html.push_str(&format!(
+        "<p>TOON saves <strong>{:.1}%</strong> against pretty JSON: about {} of {} estimated tokens.</p>\n",
+        saved,
+        estimated_tokens(total.pretty).saturating_sub(estimated_tokens(total.toon)),
+        estimated_tokens(total.pretty)
+    ));
+    html.push_str(&size_chart(total));
+
+    if !entities.is_empty() {
+        html.push_str("<h2>Entities</h2>\n<table>\n<tr><th>Entity</th><th>Rows</th><th>JSON</th><th>TOON</th><th>Est. tokens saved</th><th></th></tr>\n");
+        for (name, sizes, entity) in entities {
+            let rows = entity.as_array().map_or(1, Vec::len);
+            let ratio = 100.0 * sizes.toon as f64 / sizes.pretty.max(1) as f64;
+            html.push_str(&format!(
+                "<tr><td><a href=\"#entity-{}\">{}</a></td><td>{}</td><td>{} B</td><td>{} B</td><td>{}</td><td class=\"bar\"><span style=\"width:{:.0}%\"></span></td></tr>\n",
+                escape_html(name),
+                escape_html(name),
+                rows,
+                sizes.pretty,
+                sizes.toon,
+                estimated_tokens(sizes.pretty).saturating_sub(estimated_tokens(sizes.toon)),
+                ratio.min(100.0)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    for (name, _, entity) in entities {
+        html.push_str(&format!("<h3 id=\"entity-{}\">{}</h3>\n", escape_html(name), escape_html(name)));
+        html.push_str(&entity_table(entity, max_rows));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn size_chart(sizes: &Sizes) -> String {
+    let largest = sizes.pretty.max(sizes.minified).max(sizes.toon).max(1) as f64;
+    let mut chart = String::from("<table class=\"chart\">\n");
+    for (label, bytes) in [("JSON (pretty)", sizes.pretty), ("JSON (minified)", sizes.minified), ("TOON", sizes.toon)] {
+        chart.push_str(&format!(
+            "<tr><th>{}</th><td class=\"bar\"><span style=\"width:{:.0}%\"></span></td><td>{} B · ~{} tokens</td></tr>\n",
+            label,
+            100.0 * bytes as f64 / largest,
+            bytes,
+            estimated_tokens(bytes)
+        ));
+    }
+    chart.push_str("</table>\n");
+    chart
+}
+
+fn entity_table(entity: &Value, max_rows: usize) -> String {
+    let mut table = match entity {
+        Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => html_table(entity, max_rows),
+        Value::Object(_) => html_table(entity, max_rows),
+        other => return format!("<pre>{}</pre>\n", escape_html(&serde_json::to_string_pretty(other).unwrap_or_default())),
+    };
+    if let Some(rows) = entity.as_array().map(Vec::len).filter(|rows| *rows > max_rows) {
+        table.push_str(&format!("<p class=\"meta\">Showing {} of {} rows</p>\n", max_rows, rows));
+    }
+    table
+}
+
+pub(crate) fn estimated_tokens(bytes: usize) -> usize {
+    bytes.div_ceil(4)
+}
+
+const STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 72rem; padding: 0 1rem; color: #222; }
+h1 { font-size: 1.5rem; }
+.meta { color: #666; }
+table { border-collapse: collapse; margin: 0.5rem 0 1.5rem; font-size: 0.9rem; }
+th, td { border: 1px solid #ddd; padding: 0.25rem 0.5rem; text-align: left; }
+th { background: #f5f5f5; }
+.chart th, .chart td { border: none; }
+.bar { width: 20rem; }
+.bar span { display: block; height: 0.9rem; background: #4c8bf5; }
+.checks { list-style: none; padding: 0; }
+.pass { color: #1a7f37; }
+.fail { color: #b35900; }
+</style>
+";
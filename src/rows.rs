@@ -0,0 +1,175 @@
+// One self-contained line per record, for embedding pipelines (`--to rows`)
+//
+//     users: id=1 name=Alice role=admin
+//     users: id=2 name="Bob Stone" tags=["ops","db"]
+//     page: 3
+//
+// A vector store embeds and returns each line on its own, so every line
+// names its entity and spells out every field instead of leaning on a
+// header. Strings that contain whitespace, '"' or '=', or that would read
+// as a number, boolean or null, are written as JSON strings; nested arrays
+// and objects as compact JSON. A table becomes one line per row; an element
+// that isn't an object, a lone object and a scalar entity are written the
+// same way, and read back as one-element tables, since a line can't tell
+// which it came from. Empty tables have no lines at all.
+
+use serde_json::{Map, Value};
+
+/// Write every record of `value` as a line `entity: key=value ...`
+pub fn emit_rows(value: &Value) -> Result<String, String> {
+    let Value::Object(map) = value else {
+        return Err("Row output needs an object root of entities".to_string());
+    };
+
+    let mut output = String::new();
+    for (name, entity) in map {
+        let records = match entity {
+            Value::Array(items) => items.as_slice(),
+            single => std::slice::from_ref(single),
+        };
+        for record in records {
+            write_line(&mut output, name, record)?;
+        }
+    }
+    Ok(output)
+}
+
+/// Read lines written by `emit_rows` back into a document of tables
+pub fn parse_rows(input: &str) -> Result<Value, String> {
+    let mut document = Map::new();
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (entity, record) = parse_row(line).map_err(|e| format!("Line {}: {}", index + 1, e))?;
+        if let Value::Array(records) = document.entry(entity).or_insert_with(|| Value::Array(Vec::new())) {
+            records.push(record);
+        }
+    }
+    Ok(Value::Object(document))
+}
+
+/// The entity and record of one line, e.g. a search hit from a vector store
+pub fn parse_row(line: &str) -> Result<(String, Value), String> {
+    let line = line.trim();
+    let (entity, rest) = if line.starts_with('"') {
+        let (entity, len) = json_at(line)?;
+        let Value::String(entity) = entity else { return Err("expected a quoted entity name".to_string()) };
+        (entity, &line[len..])
+    } else {
+        let end = line.find(':').ok_or("expected 'entity: key=value ...'")?;
+        (line[..end].to_string(), &line[end..])
+    };
+    let body = rest.strip_prefix(':').ok_or("expected ':' after the entity name")?.trim_start();
+
+    let mut record = Map::new();
+    let mut rest = body;
+    while !rest.is_empty() {
+        let (key, after_key) = match key_at(rest)? {
+            Some(key) => key,
+            None if record.is_empty() => {
+                // A bare value: a list element or scalar entity
+                let (value, len) = value_at(rest)?;
+                if !rest[len..].trim().is_empty() {
+                    return Err(format!("expected one value or key=value pairs, found '{}'", rest.trim()));
+                }
+                return Ok((entity, value));
+            }
+            None => return Err(format!("expected key=value, found '{}'", rest)),
+        };
+        let (value, len) = value_at(after_key)?;
+        let after_value = &after_key[len..];
+        if !after_value.is_empty() && !after_value.starts_with(char::is_whitespace) {
+            return Err(format!("expected whitespace after the value of '{}'", key));
+        }
+        record.insert(key, value);
+        rest = after_value.trim_start();
+    }
+    Ok((entity, Value::Object(record)))
+}
+
+fn write_line(output: &mut String, entity: &str, record: &Value) -> Result<(), String> {
+    if entity.is_empty() || entity.contains([':', '"']) || entity.contains(char::is_whitespace) {
+        output.push_str(&json(&Value::from(entity))?);
+    } else {
+        output.push_str(entity);
+    }
+    output.push(':');
+    match record {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                output.push(' ');
+                if needs_quotes(key) {
+                    output.push_str(&json(&Value::from(key.as_str()))?);
+                } else {
+                    output.push_str(key);
+                }
+                output.push('=');
+                write_value(output, value)?;
+            }
+        }
+        value => {
+            output.push(' ');
+            write_value(output, value)?;
+        }
+    }
+    output.push('\n');
+    Ok(())
+}
+
+fn write_value(output: &mut String, value: &Value) -> Result<(), String> {
+    match value {
+        Value::String(s) if !needs_quotes(s) => output.push_str(s),
+        value => output.push_str(&json(value)?),
+    }
+    Ok(())
+}
+
+// Bare text must read back as the same string, and never as a key
+fn needs_quotes(s: &str) -> bool {
+    s.is_empty()
+        || s.contains(['"', '='])
+        || s.contains(char::is_whitespace)
+        || s.starts_with(['[', '{'])
+        || serde_json::from_str::<Value>(s).is_ok()
+}
+
+fn json(value: &Value) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("Failed to serialize row value: {}", e))
+}
+
+// A JSON value at the start of `s` and its length in bytes
+fn json_at(s: &str) -> Result<(Value, usize), String> {
+    let mut values = serde_json::Deserializer::from_str(s).into_iter::<Value>();
+    let value = values.next()
+        .ok_or("expected a value")?
+        .map_err(|e| format!("Invalid JSON in row: {}", e))?;
+    Ok((value, values.byte_offset()))
+}
+
+// `key=` at the start of `s`, with whatever follows the '='
+fn key_at(s: &str) -> Result<Option<(String, &str)>, String> {
+    if s.starts_with('"') {
+        let (key, len) = json_at(s)?;
+        return Ok(match (key, s[len..].strip_prefix('=')) {
+            (Value::String(key), Some(rest)) => Some((key, rest)),
+            _ => None,
+        });
+    }
+    if s.starts_with(['[', '{']) {
+        return Ok(None);
+    }
+    let token = s.split(char::is_whitespace).next().unwrap_or_default();
+    Ok(token.split_once('=').map(|(key, _)| (key.to_string(), &s[key.len() + 1..])))
+}
+
+// A value at the start of `s` and its length in bytes
+fn value_at(s: &str) -> Result<(Value, usize), String> {
+    if s.starts_with(['"', '[', '{']) {
+        return json_at(s);
+    }
+    let token = s.split(char::is_whitespace).next().unwrap_or_default();
+    // Bare text is a number, boolean or null when JSON reads it as one
+    let value = serde_json::from_str::<Value>(token).unwrap_or_else(|_| Value::from(token));
+    Ok((value, token.len()))
+}
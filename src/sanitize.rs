@@ -0,0 +1,157 @@
+// Per-field string clean-up during conversion (`--sanitize name=trim,collapse_ws`)
+//
+//     let sanitizers = Sanitizers::parse(&["name=trim,collapse_ws", "email=lowercase"])?;
+//     let converter = Converter::builder().sanitize(sanitizers).build();
+//
+// Each named field, at any depth, has its string values run through its
+// sanitizers in the order given; strings in arrays under the field are
+// cleaned one by one, and other values are left alone. Like --pseudonymize,
+// a flattened column (`user.email`) matches on its last segment. Besides the
+// built-ins, `Sanitizer::new` wraps any string function, so library users
+// can register their own under a name and mix them with the built-ins.
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+type Clean = dyn Fn(&str) -> String + Send + Sync;
+
+/// Names accepted by `Sanitizer::builtin`
+pub const BUILTIN_SANITIZERS: &[&str] = &["trim", "collapse_ws", "lowercase", "uppercase", "casefold", "strip_control"];
+
+#[derive(Clone)]
+pub struct Sanitizer {
+    name: String,
+    clean: Arc<Clean>,
+}
+
+impl fmt::Debug for Sanitizer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Sanitizer({})", self.name)
+    }
+}
+
+impl Sanitizer {
+    /// A custom sanitizer
+    pub fn new(name: &str, clean: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self { name: name.to_string(), clean: Arc::new(clean) }
+    }
+
+    /// A built-in sanitizer by name
+    pub fn builtin(name: &str) -> Result<Self, String> {
+        let clean: fn(&str) -> String = match name {
+            "trim" => |s| s.trim().to_string(),
+            // Runs of whitespace, including tabs and newlines, become one space
+            "collapse_ws" => |s| {
+                let mut out = String::with_capacity(s.len());
+                let mut in_space = false;
+                for c in s.chars() {
+                    if !c.is_whitespace() {
+                        out.push(c);
+                    } else if !in_space {
+                        out.push(' ');
+                    }
+                    in_space = c.is_whitespace();
+                }
+                out
+            },
+            "lowercase" => |s| s.to_lowercase(),
+            "uppercase" => |s| s.to_uppercase(),
+            // Lowercase, plus the caseless forms `to_lowercase` keeps apart
+            "casefold" => |s| s.to_lowercase().replace('ß', "ss").replace('ς', "σ"),
+            "strip_control" => |s| s.chars().filter(|&c| !c.is_control() || matches!(c, '\n' | '\t')).collect(),
+            _ => return Err(format!("Unknown sanitizer '{}' (expected one of {})", name, BUILTIN_SANITIZERS.join(", "))),
+        };
+        Ok(Self::new(name, clean))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn clean(&self, text: &str) -> String {
+        (self.clean)(text)
+    }
+}
+
+/// Sanitizers to run, by field name
+#[derive(Debug, Clone, Default)]
+pub struct Sanitizers {
+    fields: Vec<(String, Vec<Sanitizer>)>,
+}
+
+impl Sanitizers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse CLI specs such as `name=trim,collapse_ws`; one spec may hold several, separated by spaces
+    pub fn parse<S: AsRef<str>>(specs: &[S]) -> Result<Self, String> {
+        let mut sanitizers = Self::new();
+        for spec in specs.iter().flat_map(|spec| spec.as_ref().split_whitespace()) {
+            let (field, names) = spec.split_once('=')
+                .filter(|(field, names)| !field.is_empty() && !names.is_empty())
+                .ok_or_else(|| format!("Invalid sanitizer spec {:?} (expected field=trim,lowercase)", spec))?;
+            for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                sanitizers = sanitizers.with(field, Sanitizer::builtin(name)?);
+            }
+        }
+        Ok(sanitizers)
+    }
+
+    /// Run `sanitizer` on `field` after those already registered for it
+    pub fn with(mut self, field: &str, sanitizer: Sanitizer) -> Self {
+        match self.fields.iter_mut().find(|(existing, _)| existing == field) {
+            Some((_, list)) => list.push(sanitizer),
+            None => self.fields.push((field.to_string(), vec![sanitizer])),
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// `field=name,...` for each field, for logs
+    pub fn describe(&self) -> Vec<String> {
+        self.fields.iter()
+            .map(|(field, list)| format!("{}={}", field, list.iter().map(Sanitizer::name).collect::<Vec<_>>().join(",")))
+            .collect()
+    }
+
+    /// Clean the named fields throughout the document
+    pub fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = match self.for_field(&key) {
+                            Some(list) => clean(value, list),
+                            None => self.apply(value),
+                        };
+                        (key, value)
+                    })
+                    .collect::<Map<String, Value>>(),
+            ),
+            Value::Array(items) => Value::Array(items.into_iter().map(|item| self.apply(item)).collect()),
+            other => other,
+        }
+    }
+
+    // Flattened columns (`user.email`) match on their last segment
+    fn for_field(&self, key: &str) -> Option<&[Sanitizer]> {
+        let leaf = key.rsplit('.').next().unwrap_or(key);
+        self.fields.iter()
+            .find(|(field, _)| field == key || field == leaf)
+            .map(|(_, list)| list.as_slice())
+    }
+}
+
+fn clean(value: Value, sanitizers: &[Sanitizer]) -> Value {
+    match value {
+        Value::String(s) => Value::String(sanitizers.iter().fold(s, |s, sanitizer| sanitizer.clean(&s))),
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| clean(item, sanitizers)).collect()),
+        other => other,
+    }
+}
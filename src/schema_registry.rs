@@ -0,0 +1,126 @@
+// Named, versioned validation schemas for the server (`PUT /schemas/{name}`)
+//
+// Clients register a schema once and refer to it by name, instead of every
+// CLI run and pipeline carrying its own copy of the file:
+//
+//   PUT  /schemas/orders            -> {"name":"orders","version":2}
+//   GET  /schemas                   -> every name with its latest version
+//   GET  /schemas/orders?version=1  -> that version (latest when omitted)
+//   POST /convert?schema=orders     -> 422 unless the input matches
+//
+// Each PUT of a changed schema adds a version; versions are never replaced,
+// so a caller pinned to `schema_version=1` keeps validating the same way.
+// Putting the latest schema again is a no-op. `serve --schema-dir` registers
+// every `*.json` there (the files `toonify validate --schema` reads) as
+// version 1 under its file stem. The registry lives in memory.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::validation;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisteredSchema {
+    pub name: String,
+    pub version: u32,
+    /// Seconds since the Unix epoch
+    pub registered: u64,
+    pub schema: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaSummary {
+    pub name: String,
+    pub latest: u32,
+    pub versions: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    schemas: RwLock<BTreeMap<String, Vec<RegisteredSchema>>>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register every `*.json` file in `dir` under its file stem
+    pub fn load_dir(dir: &Path) -> Result<Self, String> {
+        let registry = Self::new();
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read schema directory {:?}: {}", dir, e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+            let schema: Value = serde_json::from_str(&text).map_err(|e| format!("Invalid schema JSON in {:?}: {}", path, e))?;
+            registry.register(name, schema).map_err(|e| format!("{:?}: {}", path, e))?;
+        }
+        Ok(registry)
+    }
+
+    /// Store `schema` as the next version of `name`; returns its version and
+    /// whether it is new (false when it equals the latest version)
+    pub fn register(&self, name: &str, schema: Value) -> Result<(u32, bool), String> {
+        check_name(name)?;
+        validation::check_schema(&schema)?;
+        let mut schemas = self.schemas.write().map_err(|_| "Schema registry lock poisoned".to_string())?;
+        let versions = schemas.entry(name.to_string()).or_default();
+        if let Some(latest) = versions.last().filter(|latest| latest.schema == schema) {
+            return Ok((latest.version, false));
+        }
+        let version = versions.len() as u32 + 1;
+        versions.push(RegisteredSchema { name: name.to_string(), version, registered: now_secs(), schema });
+        eprintln!("[SCHEMAS] Registered {} version {}", name, version);
+        Ok((version, true))
+    }
+
+    /// `version`, or the latest when `None`
+    pub fn get(&self, name: &str, version: Option<u32>) -> Option<RegisteredSchema> {
+        let schemas = self.schemas.read().ok()?;
+        let versions = schemas.get(name)?;
+        match version {
+            Some(version) => versions.iter().find(|schema| schema.version == version).cloned(),
+            None => versions.last().cloned(),
+        }
+    }
+
+    pub fn list(&self) -> Vec<SchemaSummary> {
+        let Ok(schemas) = self.schemas.read() else {
+            return Vec::new();
+        };
+        schemas
+            .iter()
+            .filter_map(|(name, versions)| {
+                let latest = versions.last()?;
+                Some(SchemaSummary { name: name.clone(), latest: latest.version, versions: versions.len() })
+            })
+            .collect()
+    }
+}
+
+// Names appear in URLs and file stems
+fn check_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid schema name {:?}: use up to 128 letters, digits, '-', '_' or '.'", name))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -0,0 +1,62 @@
+// Rhai transform scripts applied to the parsed document during conversion
+//
+// The document is bound to the `data` variable. A script can modify `data`
+// in place or evaluate to a replacement value:
+//
+//     // drop inactive users and compute a display name
+//     data.users = data.users.filter(|u| u.active);
+//     data.users = data.users.map(|u| { u.display = u.first + " " + u.last; u });
+//
+// Rhai object maps are key-sorted, so entity order in the output follows
+// key order rather than input order.
+
+use std::path::Path;
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde_json::Value;
+
+/// A compiled transform script that can be reused across conversions
+pub struct ScriptTransform {
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptTransform {
+    /// Compile a script from source text
+    pub fn compile(source: &str) -> Result<Self, String> {
+        let engine = Engine::new();
+        let ast = engine.compile(source)
+            .map_err(|e| format!("Invalid transform script: {}", e))?;
+        Ok(Self { engine, ast })
+    }
+
+    /// Compile a script from a `.rhai` file
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read transform script {:?}: {}", path, e))?;
+        Self::compile(&source)
+    }
+
+    /// Run the script against a document and return the transformed document
+    pub fn apply(&self, value: Value) -> Result<Value, String> {
+        let data = rhai::serde::to_dynamic(&value)
+            .map_err(|e| format!("Transform script input error: {}", e))?;
+
+        let mut scope = Scope::new();
+        scope.push_dynamic("data", data);
+
+        let result: Dynamic = self.engine
+            .eval_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| format!("Transform script failed: {}", e))?;
+
+        // A script that ends in a statement evaluates to unit; use the mutated `data`
+        let output = if result.is_unit() {
+            scope.get_value::<Dynamic>("data")
+                .ok_or("Transform script removed the 'data' variable")?
+        } else {
+            result
+        };
+
+        rhai::serde::from_dynamic(&output)
+            .map_err(|e| format!("Transform script output error: {}", e))
+    }
+}
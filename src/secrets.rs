@@ -0,0 +1,159 @@
+// Likely-secret detection for conversion input
+//
+// People convert payloads to TOON to paste them into LLM prompts, which is
+// exactly where leaked credentials do damage. This is a lightweight scan
+// over string values (no regex dependency): well-known token shapes (AWS
+// access keys, JWTs, GitHub/Slack/Stripe/Google tokens, `sk-` API keys,
+// PEM private keys) plus credential-named fields such as `password`.
+//
+// Findings name the kind and the JSON path, never the value itself, so the
+// warnings are safe to log.
+
+use std::fmt;
+use serde_json::Value;
+
+/// What a converter does when the document looks like it contains secrets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecretPolicy {
+    /// Do not scan
+    #[default]
+    Ignore,
+    /// Report findings as conversion warnings
+    Warn,
+    /// Fail the conversion
+    Block,
+}
+
+/// A likely secret found in a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretFinding {
+    /// JSON path of the value, e.g. `$.users[0].api_key`
+    pub path: String,
+    /// Human-readable kind, e.g. "AWS access key ID"
+    pub kind: &'static str,
+}
+
+impl fmt::Display for SecretFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Possible {} at {}", self.kind, self.path)
+    }
+}
+
+const CREDENTIAL_FIELDS: &[&str] = &[
+    "password", "passwd", "pwd", "secret", "client_secret", "api_key", "apikey",
+    "access_token", "refresh_token", "auth_token", "private_key", "secret_key",
+];
+
+/// Scan every string value in `value`
+pub fn scan_value(value: &Value) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    scan_into(value, None, "$".to_string(), &mut findings);
+    findings
+}
+
+/// Kinds of token-shaped secrets found in a single string
+pub fn scan_text(text: &str) -> Vec<&'static str> {
+    let mut kinds = Vec::new();
+
+    if text.contains("-----BEGIN") && text.contains("PRIVATE KEY-----") {
+        kinds.push("private key");
+    }
+
+    let tokens = text.split(|c: char| !(c.is_ascii_alphanumeric() || "_-./+=".contains(c)));
+    for token in tokens.filter(|token| token.len() >= 16) {
+        if let Some(kind) = token_kind(token).filter(|kind| !kinds.contains(kind)) {
+            kinds.push(kind);
+        }
+    }
+
+    kinds
+}
+
+fn scan_into(value: &Value, field: Option<&str>, path: String, findings: &mut Vec<SecretFinding>) {
+    match value {
+        Value::String(text) => {
+            for kind in scan_text(text) {
+                findings.push(SecretFinding { path: path.clone(), kind });
+            }
+            let already_found = findings.iter().any(|finding| finding.path == path);
+            if let Some(kind) = field.and_then(|field| field_kind(field, text)).filter(|_| !already_found) {
+                findings.push(SecretFinding { path, kind });
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                scan_into(item, field, format!("{}[{}]", path, i), findings);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                scan_into(item, Some(key), format!("{}.{}", path, key), findings);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn token_kind(token: &str) -> Option<&'static str> {
+    let rest_is = |prefix_len: usize, min: usize, allowed: fn(char) -> bool| {
+        let rest = &token[prefix_len..];
+        rest.len() >= min && rest.chars().all(allowed)
+    };
+    let upper_alnum = |c: char| c.is_ascii_uppercase() || c.is_ascii_digit();
+    let alnum = |c: char| c.is_ascii_alphanumeric();
+    let url_safe = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+
+    if (token.starts_with("AKIA") || token.starts_with("ASIA")) && token.len() == 20 && rest_is(4, 16, upper_alnum) {
+        return Some("AWS access key ID");
+    }
+    if token.starts_with("eyJ") && is_jwt(token) {
+        return Some("JSON Web Token");
+    }
+    if ["ghp_", "gho_", "ghu_", "ghs_", "ghr_"].iter().any(|p| token.starts_with(p)) && rest_is(4, 36, alnum) {
+        return Some("GitHub token");
+    }
+    if token.starts_with("github_pat_") && rest_is(11, 22, url_safe) {
+        return Some("GitHub token");
+    }
+    if ["xoxb-", "xoxp-", "xoxa-", "xoxr-", "xoxs-"].iter().any(|p| token.starts_with(p)) && rest_is(5, 10, url_safe) {
+        return Some("Slack token");
+    }
+    if (token.starts_with("sk_live_") || token.starts_with("rk_live_")) && rest_is(8, 16, alnum) {
+        return Some("Stripe secret key");
+    }
+    if token.starts_with("AIza") && rest_is(4, 35, url_safe) {
+        return Some("Google API key");
+    }
+    if token.starts_with("sk-") && rest_is(3, 32, url_safe) {
+        return Some("API secret key");
+    }
+    None
+}
+
+// header.payload.signature, each base64url; header and payload are JSON objects ("eyJ" = `{"`)
+fn is_jwt(token: &str) -> bool {
+    let segments: Vec<&str> = token.split('.').collect();
+    segments.len() == 3
+        && segments[1].starts_with("eyJ")
+        && segments.iter().all(|segment| {
+            !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+// Values under credential-named fields, unless they are obviously placeholders
+fn field_kind(field: &str, text: &str) -> Option<&'static str> {
+    let field = field.to_ascii_lowercase().replace('-', "_");
+    let text = text.trim();
+
+    if field.contains("aws") && field.contains("secret") && text.len() == 40
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '/' || c == '+')
+    {
+        return Some("AWS secret access key");
+    }
+
+    let placeholder = text.len() < 8 || text.contains(' ') || text.chars().all(|c| c == '*' || c == 'x' || c == 'X');
+    if CREDENTIAL_FIELDS.contains(&field.as_str()) && !placeholder {
+        return Some("credential");
+    }
+    None
+}
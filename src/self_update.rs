@@ -0,0 +1,153 @@
+// `toonify self-update`: replace the running binary with the latest GitHub release (feature `self-update`)
+//
+// The release must carry this platform's binary (`toonify-linux-x86_64`,
+// `toonify-macos-arm64`, ...) and `<asset>.sha256`; the download is rejected
+// unless its SHA-256 matches. With a release public key (`--public-key`, or
+// TOONIFY_RELEASE_PUBLIC_KEY holding the PEM at build time) the detached
+// `<asset>.sig` must verify as well. The new binary goes through
+// `write_atomic_keeping_mode`, so an interrupted update leaves the old one
+// in place.
+//
+// TOONIFY_UPDATE_URL replaces the releases API base, for mirrors.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ed25519_dalek::VerifyingKey;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::atomic_write::write_atomic_keeping_mode;
+
+const DEFAULT_API: &str = "https://api.github.com/repos/npiesco/TOONify";
+
+pub struct UpdateOptions {
+    /// Report whether an update is available without installing it
+    pub check: bool,
+    pub public_key: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+pub async fn run(options: UpdateOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let api = env::var("TOONIFY_UPDATE_URL").unwrap_or_else(|_| DEFAULT_API.to_string());
+    let client = reqwest::Client::builder().user_agent(concat!("toonify/", env!("CARGO_PKG_VERSION"))).build()?;
+
+    let url = format!("{}/releases/latest", api.trim_end_matches('/'));
+    eprintln!("[UPDATE] Checking {}", url);
+    let release: Release = client.get(&url).send().await?.error_for_status()?.json().await?;
+
+    let current = env!("CARGO_PKG_VERSION");
+    let latest = release.tag_name.trim_start_matches('v');
+    if parse_version(latest)? <= parse_version(current)? {
+        println!("✓ toonify {} is up to date", current);
+        return Ok(());
+    }
+    if options.check {
+        println!("Update available: {} -> {}", current, latest);
+        return Err(format!("toonify {} is available (running {})", latest, current).into());
+    }
+
+    let name = asset_name().ok_or_else(|| format!("No release binary for {}-{}", env::consts::OS, env::consts::ARCH))?;
+    let asset_url = |asset: &str| {
+        release
+            .assets
+            .iter()
+            .find(|candidate| candidate.name == asset)
+            .map(|candidate| candidate.browser_download_url.clone())
+            .ok_or_else(|| format!("Release {} has no {}", release.tag_name, asset))
+    };
+    let download = |url: String| {
+        let client = client.clone();
+        async move {
+            eprintln!("[UPDATE] Downloading {}", url);
+            client.get(&url).send().await?.error_for_status()?.bytes().await
+        }
+    };
+
+    let binary = download(asset_url(name)?).await?;
+    let checksum = download(asset_url(&format!("{}.sha256", name))?).await?;
+    verify_checksum(&binary, &String::from_utf8_lossy(&checksum))?;
+    eprintln!("[UPDATE] Checksum OK");
+
+    match release_key(options.public_key.as_deref())? {
+        Some(key) => {
+            let signature = download(asset_url(&format!("{}.sig", name))?).await?;
+            toonify::signing::verify_detached(&binary, &String::from_utf8_lossy(&signature), &key)?;
+            eprintln!("[UPDATE] Signature OK");
+        }
+        None => eprintln!("[UPDATE] No release public key configured; verified the checksum only"),
+    }
+
+    let exe = env::current_exe()?;
+    let exe = fs::canonicalize(&exe).unwrap_or(exe);
+    replace_binary(&exe, &binary)?;
+    println!("✓ Updated toonify {} -> {} ({:?})", current, latest, exe);
+    Ok(())
+}
+
+// Names the release workflow uploads binaries under
+fn asset_name() -> Option<&'static str> {
+    match (env::consts::OS, env::consts::ARCH) {
+        ("linux", "x86_64") if cfg!(target_env = "musl") => Some("toonify-linux-x86_64-musl"),
+        ("linux", "x86_64") => Some("toonify-linux-x86_64"),
+        ("linux", "aarch64") => Some("toonify-linux-arm64-musl"),
+        ("macos", "x86_64") => Some("toonify-macos-x86_64"),
+        ("macos", "aarch64") => Some("toonify-macos-arm64"),
+        ("windows", "x86_64") => Some("toonify-windows-x86_64.exe"),
+        _ => None,
+    }
+}
+
+// `1.2.3` (pre-release suffixes ignored) as comparable parts
+fn parse_version(version: &str) -> Result<Vec<u64>, String> {
+    let release = version.split(['-', '+']).next().unwrap_or(version);
+    release
+        .split('.')
+        .map(|part| part.parse().map_err(|_| format!("Invalid version: {}", version)))
+        .collect()
+}
+
+// `sha256sum` output: the digest, then optionally the file name
+fn verify_checksum(binary: &[u8], checksum_file: &str) -> Result<(), String> {
+    let expected = checksum_file.split_whitespace().next().ok_or("Checksum file is empty")?.to_ascii_lowercase();
+    let actual: String = Sha256::digest(binary).iter().map(|byte| format!("{:02x}", byte)).collect();
+    if expected != actual {
+        return Err(format!("Checksum mismatch: expected {}, downloaded {}", expected, actual));
+    }
+    Ok(())
+}
+
+fn release_key(path: Option<&Path>) -> Result<Option<VerifyingKey>, String> {
+    match (path, option_env!("TOONIFY_RELEASE_PUBLIC_KEY")) {
+        (Some(path), _) => toonify::signing::load_verifying_key(path).map(Some),
+        (None, Some(pem)) => toonify::signing::parse_verifying_key(pem).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+fn replace_binary(exe: &Path, binary: &[u8]) -> std::io::Result<()> {
+    // Windows can't overwrite a running executable, but it can rename it out of the way
+    #[cfg(windows)]
+    let current = {
+        let old = exe.with_extension("old.exe");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old)?;
+        old
+    };
+    #[cfg(not(windows))]
+    let current = exe.to_path_buf();
+    // Keep the executable bit and ownership; the timestamps are the new binary's own
+    write_atomic_keeping_mode(exe, binary, true, &fs::metadata(&current)?)
+}
@@ -0,0 +1,248 @@
+// Round-trip self test for JSON ↔ TOON
+//
+// Generates random documents in the shapes TOON represents (tabular arrays,
+// single-row objects, primitive lists, scalars), checks that JSON → TOON →
+// JSON is lossless, and feeds mutated TOON to the parser to check it never
+// panics. The generator is a small seeded xorshift so `toonify selftest`
+// needs no extra dependencies and failures are reproducible from the seed.
+//
+// Generated strings are arbitrary text, including ones that look like
+// numbers, JSON or TOON syntax, since the serializer quotes and escapes
+// whatever the parser would misread. Numbers span all of i64 and u64 and
+// every finite f64, which must come back bit for bit.
+
+use std::panic::{self, AssertUnwindSafe};
+use serde_json::{Map, Number, Value};
+
+use crate::toon::{parse_toon, serialize_toon_with, SerializeOptions};
+
+/// Options for a self-test run
+#[derive(Debug, Clone)]
+pub struct SelftestOptions {
+    /// Number of generated documents
+    pub cases: usize,
+    /// Seed for the generator; the same seed reproduces the same documents
+    pub seed: u64,
+    /// TOON output options used for the round trip
+    pub serialize: SerializeOptions,
+}
+
+impl Default for SelftestOptions {
+    fn default() -> Self {
+        Self { cases: 256, seed: 0x70_6f_6e_69_66_79, serialize: SerializeOptions::default() }
+    }
+}
+
+/// Outcome of a self-test run
+#[derive(Debug, Clone, Default)]
+pub struct SelftestReport {
+    pub cases: usize,
+    pub failures: Vec<String>,
+}
+
+impl SelftestReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Run the generated round-trip and parser robustness checks
+pub fn run(options: &SelftestOptions) -> SelftestReport {
+    let mut generator = ValueGenerator::new(options.seed);
+    let mut report = SelftestReport::default();
+
+    for case in 0..options.cases {
+        let document = generator.document();
+        report.cases += 1;
+
+        if let Err(e) = check_roundtrip_with(&document, &options.serialize) {
+            report.failures.push(format!("case {}: {}\n  input: {}", case, e, document));
+            continue;
+        }
+
+        // Robustness: truncated and corrupted TOON must be rejected or parsed, never panic
+        if let Ok(toon) = serialize_toon_with(&document, &options.serialize) {
+            let mutated = generator.mutate(&toon);
+            if let Err(e) = check_no_panic(&mutated) {
+                report.failures.push(format!("case {}: {}\n  input: {:?}", case, e, mutated));
+            }
+        }
+    }
+
+    report
+}
+
+/// Check that `value` survives JSON → TOON → JSON unchanged
+pub fn check_roundtrip(value: &Value) -> Result<(), String> {
+    check_roundtrip_with(value, &SerializeOptions::default())
+}
+
+/// Like `check_roundtrip`, with explicit TOON output options
+pub fn check_roundtrip_with(value: &Value, options: &SerializeOptions) -> Result<(), String> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let toon = serialize_toon_with(value, options)?;
+        let parsed = parse_toon(&toon).map_err(|e| format!("{}\n  toon: {:?}", e, toon))?;
+        if normalize(value) == normalize(&parsed) {
+            Ok(())
+        } else {
+            Err(format!("round trip changed the document\n  toon: {:?}\n  back: {}", toon, parsed))
+        }
+    }));
+
+    match result {
+        Ok(outcome) => outcome,
+        Err(_) => Err("panic during round trip".to_string()),
+    }
+}
+
+/// Check that parsing `input` returns rather than panicking
+pub fn check_no_panic(input: &str) -> Result<(), String> {
+    panic::catch_unwind(|| {
+        let _ = parse_toon(input);
+    })
+    .map_err(|_| "parser panicked".to_string())
+}
+
+/// `value` with null object fields dropped, for comparing documents: tables
+/// pad missing columns with null, so absent and null fields are equal
+pub fn normalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), normalize(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(normalize).collect()),
+        other => other.clone(),
+    }
+}
+
+const WORDS: &[&str] = &["alpha", "bravo", "charlie", "delta", "echo", "fox trot", "golf", "hotel"];
+
+/// Seeded generator of TOON-representable JSON documents
+pub struct ValueGenerator {
+    state: u64,
+}
+
+impl ValueGenerator {
+    pub fn new(seed: u64) -> Self {
+        // xorshift has a fixed point at zero
+        Self { state: seed.max(1) }
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// A root object with one to five entities of random shape
+    pub fn document(&mut self) -> Value {
+        let mut root = Map::new();
+        for _ in 0..1 + self.below(5) {
+            let key = self.key();
+            let entity = match self.below(4) {
+                0 => self.table(),
+                1 => {
+                    let columns = self.columns();
+                    Value::Object(self.row(&columns))
+                }
+                // Null list items would serialize as blank lines, which TOON drops
+                2 => Value::Array((0..self.below(4)).map(|_| self.scalar()).filter(|v| !v.is_null()).collect()),
+                _ => self.scalar(),
+            };
+            root.insert(key, entity);
+        }
+        Value::Object(root)
+    }
+
+    /// Corrupt TOON text by truncating it or inserting structural characters
+    pub fn mutate(&mut self, toon: &str) -> String {
+        let boundaries: Vec<usize> = toon.char_indices().map(|(i, _)| i).chain([toon.len()]).collect();
+        let at = boundaries[self.below(boundaries.len())];
+        match self.below(3) {
+            0 => toon[..at].to_string(),
+            1 => format!("{}{}{}", &toon[..at], ["[", "]", "{", "}", ":", ",", "\"", "\n"][self.below(8)], &toon[at..]),
+            _ => format!("{}[99]{{x:int}}:\n{}", &toon[..at], &toon[at..]),
+        }
+    }
+
+    fn key(&mut self) -> String {
+        format!("{}_{}", WORDS[self.below(WORDS.len())].replace(' ', "_"), self.below(100))
+    }
+
+    fn columns(&mut self) -> Vec<String> {
+        let mut columns: Vec<String> = Vec::new();
+        for _ in 0..1 + self.below(5) {
+            let column = self.key();
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        columns
+    }
+
+    fn table(&mut self) -> Value {
+        let columns = self.columns();
+        Value::Array((0..1 + self.below(6)).map(|_| Value::Object(self.row(&columns))).collect())
+    }
+
+    // The first cell is never null so a one-column row is never a blank line
+    fn row(&mut self, columns: &[String]) -> Map<String, Value> {
+        columns.iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let mut value = if self.below(8) == 0 { self.nested() } else { self.scalar() };
+                while i == 0 && value.is_null() {
+                    value = self.scalar();
+                }
+                (column.clone(), value)
+            })
+            .collect()
+    }
+
+    // Up to 12 characters, mostly ones TOON gives meaning to
+    fn text(&mut self) -> String {
+        const TRICKY: &[char] = &['"', '\\', ',', ':', '\n', '\r', ' ', '[', ']', '{', '}', '#', '=', '0', '1', '.', 'e', '-'];
+        (0..self.below(13))
+            .map(|_| match self.below(4) {
+                0 => char::from_u32(self.next() as u32 % 0x11_0000).unwrap_or('\u{fffd}'),
+                1 => (b' ' + self.below(95) as u8) as char,
+                _ => TRICKY[self.below(TRICKY.len())],
+            })
+            .collect()
+    }
+
+    // Nested values are embedded as quoted JSON inside a table cell
+    fn nested(&mut self) -> Value {
+        if self.below(2) == 0 {
+            Value::Array((0..self.below(3)).map(|_| self.scalar()).filter(|v| !v.is_null()).collect())
+        } else {
+            let key = self.key();
+            let value = self.scalar();
+            Value::Object([(key, value)].into_iter().filter(|(_, v)| !v.is_null()).collect())
+        }
+    }
+
+    fn scalar(&mut self) -> Value {
+        match self.below(8) {
+            0 => Value::Null,
+            1 => Value::Bool(self.below(2) == 0),
+            2 => Value::Number(Number::from(self.next() as i64 >> 40)),
+            3 => Number::from_f64((self.next() % 100_000) as f64 / 4.0 + 0.25)
+                .map_or(Value::Null, Value::Number),
+            4 => Value::Number(Number::from(self.next())),
+            5 => Number::from_f64(f64::from_bits(self.next())).map_or(Value::Null, Value::Number),
+            6 => Value::String(format!("{}, {}", WORDS[self.below(WORDS.len())], WORDS[self.below(WORDS.len())])),
+            _ => Value::String(self.text()),
+        }
+    }
+}
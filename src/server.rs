@@ -0,0 +1,1798 @@
+// REST and gRPC conversion services behind `toonify serve` (feature `server`)
+//
+// `router` builds the axum app the binary serves, and `ConverterServiceImpl`
+// is the gRPC service; both share the conversion pool, parser guards, caches
+// and audit log held in `AppState`. `ServerBuilder` wires these together the
+// way `toonify serve` does, so other services can mount TOONify in their own
+// application instead of running a separate process:
+//
+//     let toonify = ServerBuilder::new().cache(10_000, Some(300)).job_queue(4).build()?;
+//     let app = Router::new().nest("/toonify", toonify.router()).merge(my_routes);
+//     tonic::transport::Server::builder().add_service(toonify.grpc_service())
+//
+// or `toonify.serve_grpc(addr)` for a standalone gRPC port (gRPC-Web too with
+// `.grpc_web(true)`, feature `grpc-web`).
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+#[cfg(feature = "cache")]
+use std::path::PathBuf;
+use std::sync::Arc;
+#[cfg(any(feature = "rate-limit", feature = "cache"))]
+use std::time::Duration;
+
+use axum::{
+    body::{Body, Bytes},
+    routing::{post, get, Route},
+    Router,
+    Json,
+    http::{header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH}, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tonic::{Request, Response, Status};
+use tower::{Layer, Service};
+#[cfg(feature = "rate-limit")]
+use tower_governor::{governor::GovernorConfigBuilder, key_extractor::GlobalKeyExtractor, GovernorLayer};
+
+#[cfg(feature = "cache")]
+use moka::future::Cache as MokaCache;
+
+#[cfg(feature = "persistent-cache")]
+use sled::Db as SledDb;
+
+#[cfg(feature = "cache-encryption")]
+use crate::cache_crypto::CacheCipher;
+#[cfg(feature = "cluster-cache")]
+use crate::cache_invalidation::CacheInvalidation;
+#[cfg(feature = "distributed-jobs")]
+use crate::job_broker::JobBroker;
+
+use crate::audit_log::{self, AuditLog};
+use crate::circuit_breaker::{BreakerOptions, BreakerStats, CacheBreakers, CircuitBreaker};
+use crate::conversion_cache::ConversionCache;
+use crate::conversion_pool::{self, ConversionLimits};
+use crate::converter;
+use crate::guards::ParserGuards;
+#[cfg(feature = "job-queue")]
+use crate::job_queue;
+#[cfg(feature = "validation")]
+use crate::schema_registry::SchemaRegistry;
+use crate::pb::converter_service_server::{ConverterService, ConverterServiceServer};
+use crate::pb::{ConvertRequest, ConvertResponse};
+
+/// The REST API: conversions, health probes, and /jobs when the state has a job store
+pub fn router(state: AppState) -> Router {
+    routes(state, true)
+}
+
+fn routes(state: AppState, health_routes: bool) -> Router {
+    let app = if health_routes { probe_routes() } else { Router::new() };
+    let app = app
+        .route("/json-to-toon", post(json_to_toon_handler))
+        .route("/toon-to-json", post(toon_to_json_handler))
+        .route("/convert", post(negotiated_convert_handler))
+        .route("/convert/{from}/{to}", post(convert_handler))
+        .route("/cache/clear", post(clear_cache_handler))
+        .route("/cache/status", get(cache_status_handler));
+
+    #[cfg(feature = "validation")]
+    let app = app
+        .route("/schemas", get(list_schemas_handler))
+        .route("/schemas/{name}", get(get_schema_handler).put(put_schema_handler));
+
+    #[cfg(feature = "job-queue")]
+    let app = if state.job_store.is_some() {
+        app.route("/jobs/submit", post(submit_job_handler))
+            .route("/jobs/{job_id}/status", get(get_job_status_handler))
+            .route("/jobs/{job_id}/result", get(get_job_result_handler))
+            .route("/jobs/bundle", get(job_bundle_handler))
+            .route("/jobs", get(list_jobs_handler))
+    } else {
+        app
+    };
+
+    app.with_state(state)
+}
+
+// Liveness and readiness probes
+fn probe_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(health_check))
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+}
+
+type RouterLayer = Arc<dyn Fn(Router) -> Router + Send + Sync>;
+
+// The global quota as clients see it. Governor does not expose its state, so
+// this follows it: every request it lets through takes one, every 429 means
+// none are left, and one comes back each window / limit, as with GCRA.
+#[cfg(feature = "rate-limit")]
+struct RateQuota {
+    limit: u32,
+    window: Duration,
+    last_seen: std::sync::Mutex<Option<(u32, std::time::Instant)>>,
+}
+
+#[cfg(feature = "rate-limit")]
+impl RateQuota {
+    fn new(limit: u32, window: Duration) -> Self {
+        Self { limit: limit.max(1), window, last_seen: std::sync::Mutex::new(None) }
+    }
+
+    // One request comes back every window / limit
+    fn period(&self) -> Duration {
+        self.window / self.limit
+    }
+
+    /// Count a request governor let through (or refused); returns what is left
+    fn record(&self, allowed: bool) -> u32 {
+        let Ok(mut last_seen) = self.last_seen.lock() else {
+            return 0;
+        };
+        let remaining = if allowed { self.replenished(*last_seen).saturating_sub(1) } else { 0 };
+        *last_seen = Some((remaining, std::time::Instant::now()));
+        remaining
+    }
+
+    fn remaining(&self) -> u32 {
+        self.last_seen.lock().map_or(self.limit, |last_seen| self.replenished(*last_seen))
+    }
+
+    fn replenished(&self, last_seen: Option<(u32, std::time::Instant)>) -> u32 {
+        let Some((remaining, at)) = last_seen else {
+            return self.limit;
+        };
+        let returned = at.elapsed().as_nanos() / self.period().as_nanos().max(1);
+        (remaining as u128 + returned).min(self.limit as u128) as u32
+    }
+}
+
+#[cfg(feature = "rate-limit")]
+#[derive(Serialize)]
+struct RateLimitResponse {
+    limit: u32,
+    remaining: u32,
+    window_secs: u64,
+    /// Seconds until the full quota is available again
+    reset_secs: u64,
+}
+
+#[cfg(feature = "rate-limit")]
+async fn rate_limit_handler(axum::extract::State(quota): axum::extract::State<Arc<RateQuota>>) -> Json<RateLimitResponse> {
+    let remaining = quota.remaining();
+    Json(RateLimitResponse {
+        limit: quota.limit,
+        remaining,
+        window_secs: quota.window.as_secs(),
+        reset_secs: (quota.period() * (quota.limit - remaining)).as_secs_f64().ceil() as u64,
+    })
+}
+
+// X-RateLimit-Limit and X-RateLimit-Remaining on every limited response, and
+// Retry-After on 429s, so clients can pace themselves
+#[cfg(feature = "rate-limit")]
+async fn rate_limit_headers(
+    axum::extract::State(quota): axum::extract::State<Arc<RateQuota>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let mut response = next.run(request).await;
+    let refused = response.status() == StatusCode::TOO_MANY_REQUESTS;
+    let headers = response.headers_mut();
+    if refused && !headers.contains_key(axum::http::header::RETRY_AFTER) {
+        let wait = quota.period().as_secs_f64().ceil().max(1.0) as u64;
+        headers.insert(axum::http::header::RETRY_AFTER, wait.into());
+    }
+    let remaining = quota.record(!refused);
+    headers.insert("x-ratelimit-limit", quota.limit.into());
+    headers.insert("x-ratelimit-remaining", remaining.into());
+    response
+}
+
+/// Builds the REST router and gRPC service with their caches, limits, job queue and middleware
+pub struct ServerBuilder {
+    #[cfg(feature = "cache")]
+    cache: Option<(u64, Option<u64>)>,
+    #[cfg(feature = "cache")]
+    cache_snapshot: Option<(PathBuf, Duration)>,
+    #[cfg(feature = "persistent-cache")]
+    persistent_cache: Option<SledDb>,
+    #[cfg(feature = "cache-encryption")]
+    cache_cipher: Option<Arc<CacheCipher>>,
+    conversion_cache: Option<Arc<dyn ConversionCache>>,
+    breaker: BreakerOptions,
+    limits: Option<ConversionLimits>,
+    guards: ParserGuards,
+    audit: Option<Arc<AuditLog>>,
+    #[cfg(feature = "job-queue")]
+    job_workers: Option<usize>,
+    #[cfg(feature = "distributed-jobs")]
+    job_broker: Option<JobBroker>,
+    #[cfg(feature = "job-queue")]
+    leases: Option<Arc<dyn job_queue::LeaseStore>>,
+    #[cfg(feature = "job-queue")]
+    recurring_jobs: Vec<job_queue::RecurringJob>,
+    #[cfg(feature = "rate-limit")]
+    rate_limit: Option<(u32, Duration)>,
+    #[cfg(feature = "grpc-web")]
+    grpc_web: bool,
+    #[cfg(feature = "grpc-gateway")]
+    grpc_gateway: bool,
+    #[cfg(feature = "cluster-cache")]
+    invalidation: Option<CacheInvalidation>,
+    #[cfg(feature = "validation")]
+    schemas: Option<Arc<SchemaRegistry>>,
+    health_routes: bool,
+    playground: bool,
+    layers: Vec<RouterLayer>,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "cache")]
+            cache: None,
+            #[cfg(feature = "cache")]
+            cache_snapshot: None,
+            #[cfg(feature = "persistent-cache")]
+            persistent_cache: None,
+            #[cfg(feature = "cache-encryption")]
+            cache_cipher: None,
+            conversion_cache: None,
+            breaker: BreakerOptions::default(),
+            limits: None,
+            guards: ParserGuards::default(),
+            audit: None,
+            #[cfg(feature = "job-queue")]
+            job_workers: None,
+            #[cfg(feature = "distributed-jobs")]
+            job_broker: None,
+            #[cfg(feature = "job-queue")]
+            leases: None,
+            #[cfg(feature = "job-queue")]
+            recurring_jobs: Vec::new(),
+            #[cfg(feature = "rate-limit")]
+            rate_limit: None,
+            #[cfg(feature = "grpc-web")]
+            grpc_web: false,
+            #[cfg(feature = "grpc-gateway")]
+            grpc_gateway: false,
+            #[cfg(feature = "cluster-cache")]
+            invalidation: None,
+            #[cfg(feature = "validation")]
+            schemas: None,
+            health_routes: true,
+            playground: false,
+            layers: Vec::new(),
+        }
+    }
+}
+
+impl ServerBuilder {
+    /// No caches, no job queue, default conversion limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// In-memory result cache of `size` entries, expiring after `ttl_seconds` when set
+    #[cfg(feature = "cache")]
+    pub fn cache(mut self, size: u64, ttl_seconds: Option<u64>) -> Self {
+        self.cache = Some((size, ttl_seconds));
+        self
+    }
+
+    /// Reload the in-memory cache from `path` at startup and write it back every
+    /// `interval` (and from `EmbeddedServer::save_cache_snapshot`); requires `cache`,
+    /// and `build` then needs a Tokio runtime
+    #[cfg(feature = "cache")]
+    pub fn cache_snapshot(mut self, path: impl Into<PathBuf>, interval: Duration) -> Self {
+        self.cache_snapshot = Some((path.into(), interval));
+        self
+    }
+
+    /// Results persisted in an open Sled database; the embedder decides where it lives
+    #[cfg(feature = "persistent-cache")]
+    pub fn persistent_cache(mut self, db: SledDb) -> Self {
+        self.persistent_cache = Some(db);
+        self
+    }
+
+    /// Encrypt persistent cache entries; requires `persistent_cache`
+    #[cfg(feature = "cache-encryption")]
+    pub fn cache_cipher(mut self, cipher: Arc<CacheCipher>) -> Self {
+        self.cache_cipher = Some(cipher);
+        self
+    }
+
+    /// The embedder's own result cache, read through after Moka and Sled (or alone when neither is set)
+    pub fn conversion_cache(mut self, cache: Arc<dyn ConversionCache>) -> Self {
+        self.conversion_cache = Some(cache);
+        self
+    }
+
+    /// When to stop calling a failing or slow Sled or embedder cache, and when to try it again
+    pub fn cache_breaker(mut self, options: BreakerOptions) -> Self {
+        self.breaker = options;
+        self
+    }
+
+    /// Conversion pool, timeout and queue bound shared by REST and gRPC
+    pub fn limits(mut self, limits: ConversionLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    pub fn guards(mut self, guards: ParserGuards) -> Self {
+        self.guards = guards;
+        self
+    }
+
+    pub fn audit_log(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Serve /jobs, processed by `workers` background threads started by `build`
+    #[cfg(feature = "job-queue")]
+    pub fn job_queue(mut self, workers: usize) -> Self {
+        self.job_workers = Some(workers);
+        self
+    }
+
+    /// Queue /jobs in Valkey/Redis instead of in this process; the `job_queue`
+    /// workers, if any, then run jobs from the shared queue alongside `toonify worker` nodes
+    #[cfg(feature = "distributed-jobs")]
+    pub fn job_broker(mut self, broker: JobBroker) -> Self {
+        self.job_broker = Some(broker);
+        self
+    }
+
+    /// Submit `job` on its schedule from whichever server leads it (needs `job_queue`)
+    #[cfg(feature = "job-queue")]
+    pub fn recurring_job(mut self, job: job_queue::RecurringJob) -> Self {
+        self.recurring_jobs.push(job);
+        self
+    }
+
+    /// Where recurring jobs take their leases; defaults to the `job_broker`, or to this process alone
+    #[cfg(feature = "job-queue")]
+    pub fn leases(mut self, leases: Arc<dyn job_queue::LeaseStore>) -> Self {
+        self.leases = Some(leases);
+        self
+    }
+
+    /// At most `limit` requests per `window` across all clients
+    #[cfg(feature = "rate-limit")]
+    pub fn rate_limit(mut self, limit: u32, window: Duration) -> Self {
+        self.rate_limit = Some((limit, window));
+        self
+    }
+
+    /// Also answer gRPC-Web (HTTP/1.1, with CORS) in `serve_grpc`, so browsers can call the service
+    #[cfg(feature = "grpc-web")]
+    pub fn grpc_web(mut self, enabled: bool) -> Self {
+        self.grpc_web = enabled;
+        self
+    }
+
+    /// Also serve each gRPC method as JSON over HTTP in `router`, at its gRPC path (grpc-gateway style)
+    #[cfg(feature = "grpc-gateway")]
+    pub fn grpc_gateway(mut self, enabled: bool) -> Self {
+        self.grpc_gateway = enabled;
+        self
+    }
+
+    /// Share /cache/clear with every node subscribed to the same channel; `build` then needs a Tokio runtime
+    #[cfg(feature = "cluster-cache")]
+    pub fn cache_invalidation(mut self, invalidation: CacheInvalidation) -> Self {
+        self.invalidation = Some(invalidation);
+        self
+    }
+
+    /// Schemas served under /schemas, e.g. from `SchemaRegistry::load_dir`; an empty registry by default
+    #[cfg(feature = "validation")]
+    pub fn schema_registry(mut self, schemas: Arc<SchemaRegistry>) -> Self {
+        self.schemas = Some(schemas);
+        self
+    }
+
+    /// Leave out `/`, `/healthz` and `/readyz` when the host application has its own probes
+    pub fn health_routes(mut self, enabled: bool) -> Self {
+        self.health_routes = enabled;
+        self
+    }
+
+    /// Serve a page at /playground for converting pasted documents against this server
+    pub fn playground(mut self, enabled: bool) -> Self {
+        self.playground = enabled;
+        self
+    }
+
+    /// Middleware around the TOONify routes, applied after rate limiting (outermost last)
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<axum::extract::Request> + Clone + Send + Sync + 'static,
+        <L::Service as Service<axum::extract::Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<axum::extract::Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<axum::extract::Request>>::Future: Send + 'static,
+    {
+        self.layers.push(Arc::new(move |router: Router| router.layer(layer.clone())));
+        self
+    }
+
+    /// Creates the caches and conversion pool and starts any job workers
+    pub fn build(self) -> Result<EmbeddedServer, String> {
+        #[cfg(feature = "cache-encryption")]
+        if self.cache_cipher.is_some() && self.persistent_cache.is_none() {
+            return Err("Cache encryption requires a persistent cache".to_string());
+        }
+
+        #[cfg(feature = "cache")]
+        if self.cache_snapshot.is_some() && self.cache.is_none() {
+            return Err("Cache snapshots require an in-memory cache".to_string());
+        }
+
+        #[cfg(feature = "grpc-gateway")]
+        let gateway = if self.grpc_gateway { Some(crate::grpc_gateway::Gateway::new()?) } else { None };
+
+        let limits = match self.limits {
+            Some(limits) => limits,
+            None => ConversionLimits::new(None, 0, conversion_pool::DEFAULT_MAX_QUEUED)?,
+        };
+
+        #[cfg(feature = "job-queue")]
+        let mut schedules = Vec::new();
+        #[cfg(feature = "job-queue")]
+        let job_store = self.job_workers.map(|workers| {
+            let store = job_queue::create_job_store();
+            #[cfg(feature = "distributed-jobs")]
+            let leases = self.leases.or_else(|| self.job_broker.clone().map(|broker| Arc::new(broker) as Arc<dyn job_queue::LeaseStore>));
+            #[cfg(not(feature = "distributed-jobs"))]
+            let leases = self.leases;
+            let leases = leases.unwrap_or_else(|| Arc::new(job_queue::MemoryLeases::default()));
+            for job in self.recurring_jobs {
+                schedules.push(job_queue::schedule(Arc::clone(&store), Arc::clone(&leases), job));
+            }
+            #[cfg(feature = "distributed-jobs")]
+            if let Some(broker) = self.job_broker {
+                crate::job_broker::relay(broker.clone(), Arc::clone(&store));
+                crate::job_broker::start_workers(broker, workers, self.guards);
+                job_queue::start_sweeper(Arc::clone(&store));
+                return store;
+            }
+            job_queue::start_workers(Arc::clone(&store), workers, self.guards);
+            store
+        });
+
+        let state = AppState {
+            cache: CacheState {
+                #[cfg(feature = "cache")]
+                moka: self.cache.map(|(size, ttl)| create_moka_cache(size, ttl)),
+                #[cfg(feature = "persistent-cache")]
+                sled: self.persistent_cache.map(Arc::new),
+                #[cfg(feature = "cache-encryption")]
+                cipher: self.cache_cipher,
+                custom: self.conversion_cache,
+                breakers: Arc::new(CacheBreakers::new(self.breaker)),
+            },
+            limits,
+            guards: self.guards,
+            audit: self.audit,
+            #[cfg(feature = "job-queue")]
+            job_store,
+            #[cfg(feature = "cluster-cache")]
+            invalidation: self.invalidation,
+            #[cfg(feature = "validation")]
+            schemas: self.schemas.unwrap_or_default(),
+        };
+
+        #[cfg(feature = "cluster-cache")]
+        if let Some(ref invalidation) = state.invalidation {
+            invalidation.subscribe(state.cache.clone());
+        }
+
+        #[cfg(feature = "cache")]
+        if let (Some((path, interval)), Some(moka)) = (self.cache_snapshot.clone(), state.cache.moka.clone()) {
+            let ttl = self.cache.and_then(|(_, ttl)| ttl).map(Duration::from_secs);
+            crate::cache_snapshot::start(path, moka, ttl, interval);
+        }
+
+        Ok(EmbeddedServer {
+            state,
+            #[cfg(feature = "job-queue")]
+            schedules: Arc::new(std::sync::Mutex::new(schedules)),
+            #[cfg(feature = "cache")]
+            cache_snapshot: self.cache_snapshot.map(|(path, _)| path),
+            #[cfg(feature = "rate-limit")]
+            rate_limit: self.rate_limit,
+            #[cfg(feature = "grpc-web")]
+            grpc_web: self.grpc_web,
+            #[cfg(feature = "grpc-gateway")]
+            gateway,
+            health_routes: self.health_routes,
+            playground: self.playground,
+            layers: self.layers,
+        })
+    }
+}
+
+/// The shared state from `ServerBuilder::build`; `router` and `grpc_service` can be called more than once
+#[derive(Clone)]
+pub struct EmbeddedServer {
+    state: AppState,
+    /// Recurring jobs; they stop when the last clone is dropped
+    #[cfg(feature = "job-queue")]
+    schedules: Arc<std::sync::Mutex<Vec<job_queue::Leadership>>>,
+    #[cfg(feature = "cache")]
+    cache_snapshot: Option<PathBuf>,
+    #[cfg(feature = "rate-limit")]
+    rate_limit: Option<(u32, Duration)>,
+    #[cfg(feature = "grpc-web")]
+    grpc_web: bool,
+    #[cfg(feature = "grpc-gateway")]
+    gateway: Option<crate::grpc_gateway::Gateway>,
+    health_routes: bool,
+    playground: bool,
+    layers: Vec<RouterLayer>,
+}
+
+impl EmbeddedServer {
+    pub fn state(&self) -> &AppState {
+        &self.state
+    }
+
+    /// Write the in-memory cache to the `cache_snapshot` file now, e.g. on
+    /// shutdown; returns the entries written, or `None` without a snapshot file
+    #[cfg(feature = "cache")]
+    pub async fn save_cache_snapshot(&self) -> Result<Option<usize>, String> {
+        match (&self.cache_snapshot, &self.state.cache.moka) {
+            (Some(path), Some(moka)) => crate::cache_snapshot::save(path, moka).await.map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Stop submitting recurring jobs and release their leases to the other
+    /// nodes, e.g. on shutdown
+    #[cfg(feature = "job-queue")]
+    pub fn stop_recurring_jobs(&self) {
+        let schedules = std::mem::take(&mut *self.schedules.lock().unwrap());
+        schedules.into_iter().for_each(job_queue::Leadership::stop);
+    }
+
+    /// The REST routes with the configured middleware, ready to `nest` or `merge`
+    pub fn router(&self) -> Router {
+        #[cfg(feature = "rate-limit")]
+        let rate_limited = self.rate_limit.is_some();
+        #[cfg(not(feature = "rate-limit"))]
+        let rate_limited = false;
+        let mut app = routes(self.state.clone(), self.health_routes && !rate_limited);
+
+        #[cfg(feature = "grpc-gateway")]
+        if let Some(gateway) = &self.gateway {
+            app = app.merge(gateway.router(self.grpc_service()));
+        }
+
+        if self.playground {
+            app = app.route("/playground", get(playground_handler));
+        }
+
+        #[cfg(feature = "rate-limit")]
+        if let Some((limit, window)) = self.rate_limit {
+            // For N requests per W seconds one token comes back every W/N, and
+            // N requests may arrive at once
+            let governor_conf = Arc::new(
+                GovernorConfigBuilder::default()
+                    .period(window / limit.max(1))
+                    .burst_size(limit.max(1))
+                    .key_extractor(GlobalKeyExtractor)
+                    .finish()
+                    .unwrap(),
+            );
+            // /rate-limit and the probes sit outside the governor so asking
+            // for the quota or polling health costs nothing
+            let quota = Arc::new(RateQuota::new(limit, window));
+            let mut unlimited = Router::new().route("/rate-limit", get(rate_limit_handler).with_state(Arc::clone(&quota)));
+            if self.health_routes {
+                unlimited = unlimited.merge(probe_routes().with_state(self.state.clone()));
+            }
+            app = unlimited.merge(
+                app.layer(GovernorLayer::new(governor_conf))
+                    .layer(axum::middleware::from_fn_with_state(quota, rate_limit_headers)),
+            );
+        }
+
+        self.layers.iter().fold(app, |app, layer| layer(app))
+    }
+
+    /// The methods `router` transcodes, when the gateway is enabled
+    #[cfg(feature = "grpc-gateway")]
+    pub fn grpc_gateway_paths(&self) -> Vec<String> {
+        self.gateway.as_ref().map(|gateway| gateway.paths()).unwrap_or_default()
+    }
+
+    /// The gRPC ConverterService sharing the router's limits, guards and audit log
+    pub fn grpc_service(&self) -> ConverterServiceServer<ConverterServiceImpl> {
+        ConverterServiceServer::new(ConverterServiceImpl {
+            limits: self.state.limits.clone(),
+            guards: self.state.guards,
+            audit: self.state.audit.clone(),
+        })
+    }
+
+    /// Serve `grpc_service` on `addr`; with gRPC-Web enabled the same port also
+    /// accepts HTTP/1.1 gRPC-Web calls from browsers
+    pub async fn serve_grpc(self, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+        let service = self.grpc_service();
+
+        #[cfg(feature = "grpc-web")]
+        if self.grpc_web {
+            // Browsers keep one HTTP/1.1 connection open between calls; probe it so
+            // idle connections through NATs and proxies are not silently dropped
+            return tonic::transport::Server::builder()
+                .accept_http1(true)
+                .tcp_keepalive(Some(std::time::Duration::from_secs(60)))
+                .layer(grpc_web_cors())
+                .layer(tonic_web::GrpcWebLayer::new())
+                .add_service(service)
+                .serve(addr)
+                .await;
+        }
+
+        tonic::transport::Server::builder().add_service(service).serve(addr).await
+    }
+}
+
+// Any origin may call; the grpc-* trailers come back as headers and must be readable
+#[cfg(feature = "grpc-web")]
+fn grpc_web_cors() -> tower_http::cors::CorsLayer {
+    use axum::http::{HeaderName, Method};
+    use tower_http::cors::{Any, CorsLayer};
+
+    CorsLayer::new()
+        .allow_origin(Any)
+        .allow_headers(Any)
+        .allow_methods([Method::POST, Method::OPTIONS])
+        .expose_headers([
+            HeaderName::from_static("grpc-status"),
+            HeaderName::from_static("grpc-message"),
+            HeaderName::from_static("grpc-status-details-bin"),
+        ])
+        .max_age(std::time::Duration::from_secs(24 * 60 * 60))
+}
+
+// Moka cache type for high-performance in-memory caching
+#[cfg(feature = "cache")]
+pub type MokaConversionCache = Arc<MokaCache<String, String>>;
+
+#[cfg(feature = "cache")]
+pub fn create_moka_cache(size: u64, ttl_seconds: Option<u64>) -> MokaConversionCache {
+    let mut builder = MokaCache::builder()
+        .max_capacity(size);
+    
+    if let Some(ttl) = ttl_seconds {
+        builder = builder.time_to_live(std::time::Duration::from_secs(ttl));
+    }
+    
+    Arc::new(builder.build())
+}
+
+// Sled database type for persistent caching
+#[cfg(feature = "persistent-cache")]
+pub type SledCacheDb = Arc<SledDb>;
+
+// Cache state with Moka (hot) and Sled (persistent); the default caches nothing
+#[derive(Clone, Default)]
+pub struct CacheState {
+    #[cfg(feature = "cache")]
+    pub moka: Option<MokaConversionCache>,
+    #[cfg(feature = "persistent-cache")]
+    pub sled: Option<SledCacheDb>,
+    #[cfg(feature = "cache-encryption")]
+    pub cipher: Option<Arc<CacheCipher>>,
+    /// The embedder's cache, consulted after Moka and Sled
+    pub custom: Option<Arc<dyn ConversionCache>>,
+    /// Skip Sled or the embedder's cache while they fail or lag
+    pub breakers: Arc<CacheBreakers>,
+}
+
+impl CacheState {
+    /// Whether any result cache is configured
+    pub fn is_enabled(&self) -> bool {
+        #[cfg(feature = "cache")]
+        if self.moka.is_some() {
+            return true;
+        }
+        #[cfg(feature = "persistent-cache")]
+        if self.sled.is_some() {
+            return true;
+        }
+        self.custom.is_some()
+    }
+
+    /// Drop every cached result, hot and persistent
+    pub fn clear(&self) {
+        #[cfg(feature = "cache")]
+        if let Some(ref moka) = self.moka {
+            moka.invalidate_all();
+        }
+        #[cfg(feature = "persistent-cache")]
+        if let Some(ref sled) = self.sled {
+            let _ = sled.clear();
+            if let Ok(written) = sled.open_tree(crate::sled_cache::WRITTEN_TREE) {
+                let _ = written.clear();
+            }
+        }
+        if let Some(ref custom) = self.custom
+            && let Err(e) = custom.clear()
+        {
+            eprintln!("[CACHE] Failed to clear the custom cache: {}", e);
+        }
+    }
+}
+
+// Combined app state for all handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub cache: CacheState,
+    pub limits: ConversionLimits,
+    pub guards: ParserGuards,
+    pub audit: Option<Arc<AuditLog>>,
+    /// Serves the /jobs routes when set
+    #[cfg(feature = "job-queue")]
+    pub job_store: Option<job_queue::JobStore>,
+    /// Tells the other nodes when /cache/clear runs here
+    #[cfg(feature = "cluster-cache")]
+    pub invalidation: Option<CacheInvalidation>,
+    /// Schemas for /schemas and `?schema=` validation
+    #[cfg(feature = "validation")]
+    pub schemas: Arc<SchemaRegistry>,
+}
+
+#[derive(Clone)]
+pub struct ConverterServiceImpl {
+    pub limits: ConversionLimits,
+    pub guards: ParserGuards,
+    pub audit: Option<Arc<AuditLog>>,
+}
+
+impl ConverterServiceImpl {
+    // Shared path for both RPCs: run on the conversion pool, then audit
+    async fn convert(
+        &self,
+        request: Request<ConvertRequest>,
+        endpoint: &str,
+        (from, to): (&'static str, &'static str),
+    ) -> Result<Response<ConvertResponse>, Status> {
+        let started = std::time::Instant::now();
+        let caller = self.audit.as_ref().map(|_| {
+            audit_log::Caller::from_headers(request.remote_addr(), &request.metadata().clone().into_headers())
+        });
+        let req = request.into_inner();
+        let input_bytes = req.data.len();
+        let input = self.audit.as_ref().filter(|audit| audit.payloads()).map(|_| req.data.clone());
+        let converter = converter::Converter::builder().guards(self.guards).build();
+        let converted = self.limits.run(input_bytes, move || converter.convert(&req.data, from, to)).await;
+        
+        let response = match converted {
+            Ok(Ok(result)) => Ok(ConvertResponse { result, error: String::new() }),
+            Ok(Err(e)) => Ok(ConvertResponse { result: String::new(), error: e }),
+            Err(failure) => Err(failure.grpc_status()),
+        };
+        
+        if let (Some(audit), Some(caller)) = (&self.audit, caller) {
+            let (status, output, error) = match &response {
+                Ok(response) => (tonic::Code::Ok as u16, response.result.as_str(), Some(response.error.as_str()).filter(|e| !e.is_empty())),
+                Err(status) => (status.code() as u16, "", Some(status.message())),
+            };
+            audit.record(&audit_log::AuditRecord {
+                timestamp: audit_log::timestamp(),
+                transport: "grpc",
+                endpoint,
+                from,
+                to,
+                caller,
+                input_bytes,
+                output_bytes: output.len(),
+                duration_ms: audit_log::duration_ms(started.elapsed()),
+                status,
+                error,
+                output: input.is_some().then_some(output),
+                input,
+            });
+        }
+        response.map(Response::new)
+    }
+}
+
+#[tonic::async_trait]
+impl ConverterService for ConverterServiceImpl {
+    async fn json_to_toon(
+        &self,
+        request: Request<ConvertRequest>,
+    ) -> Result<Response<ConvertResponse>, Status> {
+        self.convert(request, "ConverterService/JsonToToon", ("json", "toon")).await
+    }
+
+    async fn toon_to_json(
+        &self,
+        request: Request<ConvertRequest>,
+    ) -> Result<Response<ConvertResponse>, Status> {
+        self.convert(request, "ConverterService/ToonToJson", ("toon", "json")).await
+    }
+}
+
+#[derive(Deserialize)]
+struct ConvertPayload {
+    data: String,
+}
+
+#[derive(Serialize)]
+struct ConvertResult {
+    result: Option<String>,
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+}
+
+const PLAYGROUND_HTML: &str = include_str!("playground.html");
+
+// The page calls convert/{from}/{to} relative to itself, so it also works under a nested prefix
+async fn playground_handler() -> axum::response::Html<String> {
+    let formats = serde_json::to_string(&converter::registry().names()).unwrap_or_else(|_| "[]".to_string());
+    axum::response::Html(PLAYGROUND_HTML.replace("__FORMATS__", &formats))
+}
+
+async fn health_check() -> &'static str {
+    "TOONify API - Blazing Fast!"
+}
+
+// Liveness: the process is up and serving HTTP
+async fn healthz_handler() -> &'static str {
+    "ok"
+}
+
+// Readiness: 503 while the conversion queue is full, so load balancers back off
+async fn readyz_handler(axum::extract::State(app_state): axum::extract::State<AppState>) -> impl IntoResponse {
+    if app_state.limits.saturated() {
+        (StatusCode::SERVICE_UNAVAILABLE, "overloaded")
+    } else {
+        (StatusCode::OK, "ready")
+    }
+}
+
+// Job Queue HTTP Handlers
+#[cfg(feature = "job-queue")]
+#[derive(Deserialize)]
+struct SubmitJobPayload {
+    operation: String,
+    data: String,
+    /// Labels, submitter and ttl_secs, kept with the job
+    #[serde(flatten)]
+    metadata: job_queue::JobMetadata,
+    /// Registered schema the data must match before the job is queued
+    #[cfg(feature = "validation")]
+    schema: Option<String>,
+    #[cfg(feature = "validation")]
+    schema_version: Option<u32>,
+}
+
+#[cfg(feature = "job-queue")]
+#[derive(Serialize)]
+struct SubmitJobResponse {
+    job_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Also sent back as the `traceparent` header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trace_id: Option<String>,
+}
+
+#[cfg(feature = "job-queue")]
+async fn submit_job_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Json(mut payload): Json<SubmitJobPayload>,
+) -> axum::response::Response {
+    #[cfg(feature = "validation")]
+    {
+        let from = if payload.operation == "toon_to_json" { "toon" } else { "json" };
+        let checked = match payload.schema {
+            Some(ref name) => check_schema_param(&app_state, name, payload.schema_version, from, &payload.data).await,
+            None => Ok(()),
+        };
+        if let Err((status, error)) = checked {
+            let job_id = if status == StatusCode::NOT_FOUND { "error:schema_not_found" } else { "error:schema_validation_failed" };
+            return (status, Json(SubmitJobResponse { job_id: job_id.to_string(), error: Some(error), trace_id: None })).into_response();
+        }
+    }
+
+    if let Some(job_store) = app_state.job_store {
+        // The caller's trace, or a new one; the job's logs and callback carry it
+        let trace = headers.get("traceparent")
+            .and_then(|value| value.to_str().ok())
+            .and_then(job_queue::TraceContext::continue_from)
+            .unwrap_or_default();
+        payload.metadata.trace_id = Some(trace.trace_id.clone());
+        payload.metadata.span_id = Some(trace.span_id.clone());
+        let job_id = job_queue::submit_job_with(job_store, payload.operation, payload.data, payload.metadata);
+        let response = SubmitJobResponse { job_id, error: None, trace_id: Some(trace.trace_id.clone()) };
+        ([("traceparent", trace.traceparent())], Json(response)).into_response()
+    } else {
+        Json(SubmitJobResponse { job_id: "error:job_queue_disabled".to_string(), error: None, trace_id: None }).into_response()
+    }
+}
+
+#[cfg(feature = "job-queue")]
+#[derive(Serialize)]
+struct JobStatusResponse {
+    status: String,
+    error: Option<String>,
+    /// CPU time, peak allocation estimate and input/output bytes, once the job has run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<job_queue::JobUsage>,
+}
+
+#[cfg(feature = "job-queue")]
+async fn get_job_status_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    if let Some(job_store) = app_state.job_store {
+        if let Some(job) = job_queue::get_job(job_store, &job_id) {
+            Json(JobStatusResponse {
+                status: job.status.as_str().to_string(),
+                error: job.error,
+                usage: job.usage,
+            })
+        } else {
+            Json(JobStatusResponse {
+                status: "not_found".to_string(),
+                error: Some("Job not found".to_string()),
+                usage: None,
+            })
+        }
+    } else {
+        Json(JobStatusResponse {
+            status: "error".to_string(),
+            error: Some("Job queue disabled".to_string()),
+            usage: None,
+        })
+    }
+}
+
+#[cfg(feature = "job-queue")]
+#[derive(Serialize)]
+struct JobResultResponse {
+    result: Option<String>,
+}
+
+#[cfg(feature = "job-queue")]
+#[derive(Deserialize)]
+struct JobResultQuery {
+    /// `gzip` for the result as a gzipped file instead of JSON
+    format: Option<String>,
+}
+
+#[cfg(feature = "job-queue")]
+async fn get_job_result_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<JobResultQuery>,
+) -> axum::response::Response {
+    let job = app_state.job_store.and_then(|job_store| job_queue::get_job(job_store, &job_id));
+    match query.format.as_deref() {
+        None | Some("json") => Json(JobResultResponse { result: job.and_then(|job| job.result) }).into_response(),
+        Some("gzip") => match job {
+            Some(job_queue::Job { result: Some(result), operation, .. }) => {
+                let file_name = format!("{}.{}.gz", job_id, job_queue::output_format(&operation));
+                gzipped_result(&file_name, &result)
+            }
+            _ => (StatusCode::NOT_FOUND, "No result for this job").into_response(),
+        },
+        Some(other) => (StatusCode::BAD_REQUEST, format!("Unknown result format '{}' (expected json or gzip)", other)).into_response(),
+    }
+}
+
+#[cfg(feature = "job-queue")]
+fn attachment(content_type: &'static str, file_name: &str, body: Vec<u8>) -> axum::response::Response {
+    let disposition = format!("attachment; filename=\"{}\"", file_name);
+    (
+        [(CONTENT_TYPE, content_type.to_string()), (axum::http::header::CONTENT_DISPOSITION, disposition)],
+        body,
+    )
+        .into_response()
+}
+
+#[cfg(all(feature = "job-queue", feature = "compression"))]
+fn gzipped_result(file_name: &str, result: &str) -> axum::response::Response {
+    use std::io::Write;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    match encoder.write_all(result.as_bytes()).and_then(|_| encoder.finish()) {
+        Ok(gzipped) => attachment("application/gzip", file_name, gzipped),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to gzip the result: {}", e)).into_response(),
+    }
+}
+
+#[cfg(all(feature = "job-queue", not(feature = "compression")))]
+fn gzipped_result(_file_name: &str, _result: &str) -> axum::response::Response {
+    (StatusCode::NOT_IMPLEMENTED, "gzip results need the 'compression' feature").into_response()
+}
+
+#[cfg(feature = "job-queue")]
+#[derive(Deserialize)]
+struct JobBundleQuery {
+    /// Comma-separated job IDs, e.g. the jobs of one batch
+    ids: String,
+}
+
+// A zip with each completed job's result plus manifest.json giving every job's status
+#[cfg(feature = "job-queue")]
+async fn job_bundle_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<JobBundleQuery>,
+) -> axum::response::Response {
+    let Some(job_store) = app_state.job_store else {
+        return (StatusCode::NOT_FOUND, "Job queue disabled").into_response();
+    };
+    let ids: Vec<&str> = query.ids.split(',').map(str::trim).filter(|id| !id.is_empty()).collect();
+    if ids.is_empty() {
+        return (StatusCode::BAD_REQUEST, "No job IDs given (expected ids=a,b,...)").into_response();
+    }
+    let mut jobs = Vec::with_capacity(ids.len());
+    for id in &ids {
+        match job_queue::get_job(Arc::clone(&job_store), id) {
+            Some(job) => jobs.push(job),
+            None => return (StatusCode::NOT_FOUND, format!("Job not found: {}", id)).into_response(),
+        }
+    }
+    zipped_jobs(&jobs)
+}
+
+#[cfg(all(feature = "job-queue", feature = "archive"))]
+fn zipped_jobs(jobs: &[job_queue::Job]) -> axum::response::Response {
+    match write_bundle(jobs) {
+        Ok(bytes) => attachment("application/zip", "jobs.zip", bytes),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write the bundle: {}", e)).into_response(),
+    }
+}
+
+#[cfg(all(feature = "job-queue", feature = "archive"))]
+fn write_bundle(jobs: &[job_queue::Job]) -> zip::result::ZipResult<Vec<u8>> {
+    use std::io::Write;
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let mut manifest = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let file = job.result.as_ref().map(|result| (format!("{}.{}", job.id, job_queue::output_format(&job.operation)), result));
+        if let Some((name, result)) = &file {
+            zip.start_file(name.as_str(), options)?;
+            zip.write_all(result.as_bytes())?;
+        }
+        manifest.push(serde_json::json!({
+            "id": job.id,
+            "operation": job.operation,
+            "status": job.status,
+            "file": file.map(|(name, _)| name),
+            "error": job.error,
+        }));
+    }
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest).unwrap_or_default().as_bytes())?;
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(all(feature = "job-queue", not(feature = "archive")))]
+fn zipped_jobs(_jobs: &[job_queue::Job]) -> axum::response::Response {
+    (StatusCode::NOT_IMPLEMENTED, "Job bundles need the 'archive' feature").into_response()
+}
+
+#[cfg(feature = "job-queue")]
+#[derive(Serialize)]
+struct ListJobsResponse {
+    jobs: Vec<job_queue::Job>,
+}
+
+#[cfg(feature = "job-queue")]
+#[derive(Deserialize)]
+struct ListJobsQuery {
+    /// `key:value` pairs, comma-separated; a job must carry all of them
+    labels: Option<String>,
+    submitter: Option<String>,
+    status: Option<String>,
+}
+
+#[cfg(feature = "job-queue")]
+impl ListJobsQuery {
+    fn filter(self) -> Result<job_queue::JobFilter, String> {
+        let mut labels = std::collections::BTreeMap::new();
+        for pair in self.labels.iter().flat_map(|labels| labels.split(',')).map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once(':').ok_or_else(|| format!("Invalid label filter {:?} (expected key:value)", pair))?;
+            labels.insert(key.to_string(), value.to_string());
+        }
+        let status = self.status.as_deref().map(str::parse).transpose()?;
+        Ok(job_queue::JobFilter { labels, submitter: self.submitter, status })
+    }
+}
+
+// Filter with ?labels=team:payments,env:prod&submitter=etl&status=completed
+#[cfg(feature = "job-queue")]
+async fn list_jobs_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<ListJobsQuery>,
+) -> axum::response::Response {
+    let filter = match query.filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    if let Some(job_store) = app_state.job_store {
+        let jobs = job_queue::list_jobs_matching(job_store, &filter);
+        Json(ListJobsResponse { jobs }).into_response()
+    } else {
+        Json(ListJobsResponse { jobs: vec![] }).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct ClearCacheResponse {
+    cleared: bool,
+    /// Whether the other nodes were told to clear theirs
+    broadcast: bool,
+}
+
+// Clear this node's caches, and with --cache-invalidation-url every other node's
+async fn clear_cache_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+) -> Json<ClearCacheResponse> {
+    app_state.cache.clear();
+    eprintln!("[CACHE] Cleared");
+
+    #[cfg(feature = "cluster-cache")]
+    let broadcast = match app_state.invalidation {
+        Some(ref invalidation) => match invalidation.publish().await {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("[CACHE] Failed to publish invalidation: {}", e);
+                false
+            }
+        },
+        None => false,
+    };
+    #[cfg(not(feature = "cluster-cache"))]
+    let broadcast = false;
+
+    Json(ClearCacheResponse { cleared: true, broadcast })
+}
+
+#[derive(Serialize)]
+struct CacheStatusResponse {
+    backends: Vec<BreakerStats>,
+}
+
+// Circuit breaker state of each configured Sled or embedder cache
+async fn cache_status_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+) -> Json<CacheStatusResponse> {
+    let cache = &app_state.cache;
+    let mut backends = Vec::new();
+    #[cfg(feature = "persistent-cache")]
+    if cache.sled.is_some() {
+        backends.push(cache.breakers.sled.stats());
+    }
+    if cache.custom.is_some() {
+        backends.push(cache.breakers.custom.stats());
+    }
+    Json(CacheStatusResponse { backends })
+}
+
+async fn json_to_toon_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    caller: audit_log::Caller,
+    headers: HeaderMap,
+    Json(payload): Json<ConvertPayload>,
+) -> axum::response::Response {
+    conditional_convert(app_state, caller, &headers, "/json-to-toon", "json", "toon", payload.data).await
+}
+
+async fn toon_to_json_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    caller: audit_log::Caller,
+    headers: HeaderMap,
+    Json(payload): Json<ConvertPayload>,
+) -> axum::response::Response {
+    conditional_convert(app_state, caller, &headers, "/toon-to-json", "toon", "json", payload.data).await
+}
+
+// Generic route for any pair of registered formats, e.g. /convert/yaml/toon
+async fn convert_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    axum::extract::Path((from, to)): axum::extract::Path<(String, String)>,
+    caller: audit_log::Caller,
+    headers: HeaderMap,
+    Json(payload): Json<ConvertPayload>,
+) -> axum::response::Response {
+    let endpoint = format!("/convert/{}/{}", from, to);
+    conditional_convert(app_state, caller, &headers, &endpoint, &from, &to, payload.data).await
+}
+
+// JSON-wrapped conversion answering If-None-Match with 304 and tagging each success
+async fn conditional_convert(
+    mut app_state: AppState,
+    caller: audit_log::Caller,
+    headers: &HeaderMap,
+    endpoint: &str,
+    from: &str,
+    to: &str,
+    data: String,
+) -> axum::response::Response {
+    let etag = conversion_etag(from, to, &app_state.guards, &data);
+    if if_none_match(headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+    }
+    if no_store(headers) {
+        app_state.cache = CacheState::default();
+    }
+    let (status, result) = audited_convert(app_state, caller, endpoint, from, to, data).await;
+    if status == StatusCode::OK {
+        (status, [(ETAG, etag)], result).into_response()
+    } else {
+        (status, result).into_response()
+    }
+}
+
+// Strong validator for a conversion: same input, formats, guards and version give the same bytes
+fn conversion_etag(from: &str, to: &str, guards: &ParserGuards, data: &str) -> String {
+    let mut hasher = Sha256::new();
+    let options = format!("{}\0{}\0{}\0{}\0", env!("CARGO_PKG_VERSION"), from.to_ascii_lowercase(), to.to_ascii_lowercase(), options_fingerprint(guards));
+    hasher.update(options.as_bytes());
+    hasher.update(data.as_bytes());
+    let digest: String = hasher.finalize()[..16].iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("\"{}\"", digest)
+}
+
+// If-None-Match uses weak comparison, so W/"x" matches "x"
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+// The raw body in the format its Content-Type names, answered in the format
+// Accept prefers; without an Accept, TOON becomes JSON and everything else TOON
+async fn negotiated_convert_handler(
+    axum::extract::State(mut app_state): axum::extract::State<AppState>,
+    caller: audit_log::Caller,
+    #[cfg(feature = "validation")] axum::extract::Query(query): axum::extract::Query<SchemaQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> axum::response::Response {
+    let registry = converter::registry();
+    let Some(from) = headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok()).and_then(|value| registry.for_media_type(value)) else {
+        let message = format!("Content-Type must be one of: {}", registry.media_types().join(", "));
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, message).into_response();
+    };
+    let from = from.name();
+    let default_target = if from == "toon" { "json" } else { "toon" };
+    let Some(to) = negotiate_target(&headers, default_target) else {
+        let message = format!("Accept must include one of: {}", registry.media_types().join(", "));
+        return (StatusCode::NOT_ACCEPTABLE, message).into_response();
+    };
+
+    #[cfg(feature = "validation")]
+    {
+        let checked = match query.schema {
+            Some(ref name) => check_schema_param(&app_state, name, query.schema_version, from, &body).await,
+            None => Ok(()),
+        };
+        if let Err(rejected) = checked {
+            return rejected.into_response();
+        }
+    }
+
+    let etag = conversion_etag(from, to, &app_state.guards, &body);
+    if if_none_match(&headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+    }
+
+    if no_store(&headers) {
+        app_state.cache = CacheState::default();
+    }
+    if matches!(to, "toon" | "json") && !app_state.cache.is_enabled() {
+        return streamed_convert(app_state, caller, from, to, body, etag).await;
+    }
+
+    let (status, Json(result)) = audited_convert(app_state, caller, "/convert", from, to, body).await;
+    match result.result {
+        Some(output) => (status, [(CONTENT_TYPE, content_type(to)), (ETAG, etag)], output).into_response(),
+        None => (status, result.error.unwrap_or_default()).into_response(),
+    }
+}
+
+#[cfg(feature = "validation")]
+#[derive(Deserialize)]
+struct SchemaQuery {
+    schema: Option<String>,
+    schema_version: Option<u32>,
+}
+
+// `schema=name[&schema_version=N]`: 404 for an unknown schema or version,
+// 400 when the input does not parse, 422 when it does not match. The input
+// is parsed once more for the conversion itself.
+#[cfg(feature = "validation")]
+async fn check_schema_param(
+    app_state: &AppState,
+    name: &str,
+    version: Option<u32>,
+    from: &'static str,
+    data: &str,
+) -> Result<(), (StatusCode, String)> {
+    let Some(registered) = app_state.schemas.get(name, version) else {
+        let which = version.map_or(String::new(), |version| format!(" version {}", version));
+        return Err((StatusCode::NOT_FOUND, format!("Unknown schema {}{}", name, which)));
+    };
+    let label = format!("Schema {} version {}", registered.name, registered.version);
+    let converter = converter::Converter::builder().guards(app_state.guards).build();
+    let data = data.to_string();
+    let checked = app_state.limits.run(data.len(), move || {
+        let (value, _) = converter.parse(&data, from).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        crate::validation::validate_value(&value, &registered.schema).map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, format!("{}: {}", label, e)))
+    }).await;
+    checked.unwrap_or_else(|failure| Err((failure.status_code(), failure.to_string())))
+}
+
+#[cfg(feature = "validation")]
+#[derive(Deserialize)]
+struct SchemaVersionQuery {
+    version: Option<u32>,
+}
+
+#[cfg(feature = "validation")]
+#[derive(Serialize)]
+struct PutSchemaResponse {
+    name: String,
+    version: u32,
+}
+
+// A changed schema becomes the next version (201); the latest one again is a no-op (200)
+#[cfg(feature = "validation")]
+async fn put_schema_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    Json(schema): Json<serde_json::Value>,
+) -> axum::response::Response {
+    match app_state.schemas.register(&name, schema) {
+        Ok((version, created)) => {
+            let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+            (status, Json(PutSchemaResponse { name, version })).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+#[cfg(feature = "validation")]
+async fn get_schema_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<SchemaVersionQuery>,
+) -> axum::response::Response {
+    match app_state.schemas.get(&name, query.version) {
+        Some(registered) => Json(registered).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("Unknown schema {}", name)).into_response(),
+    }
+}
+
+#[cfg(feature = "validation")]
+#[derive(Serialize)]
+struct ListSchemasResponse {
+    schemas: Vec<crate::schema_registry::SchemaSummary>,
+}
+
+#[cfg(feature = "validation")]
+async fn list_schemas_handler(
+    axum::extract::State(app_state): axum::extract::State<AppState>,
+) -> Json<ListSchemasResponse> {
+    Json(ListSchemasResponse { schemas: app_state.schemas.list() })
+}
+
+fn content_type(format: &str) -> String {
+    let media_type = converter::registry().get(format).and_then(|codec| codec.media_types().first()).copied();
+    format!("{}; charset=utf-8", media_type.unwrap_or("text/plain"))
+}
+
+/// Bytes per body chunk when a conversion is streamed
+const STREAM_CHUNK: usize = 64 * 1024;
+
+enum StreamEvent {
+    Chunk(Bytes),
+    Failed(StatusCode, String),
+}
+
+// Uncached /convert to TOON or JSON: the body goes out while it is emitted,
+// so the first bytes leave before the whole result exists. A failure before
+// the first chunk gets a normal error response; a later one aborts the body.
+async fn streamed_convert(
+    app_state: AppState,
+    caller: audit_log::Caller,
+    from: &'static str,
+    to: &'static str,
+    data: String,
+    etag: String,
+) -> axum::response::Response {
+    let (events, mut received) = tokio::sync::mpsc::channel(4);
+    let chunks = events.clone();
+    let converter = converter::Converter::builder().guards(app_state.guards).build();
+    let keep_output = app_state.audit.as_ref().is_some_and(|audit| audit.payloads());
+    let input = keep_output.then(|| data.clone());
+
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        let input_bytes = data.len();
+        let converted = app_state.limits.run(input_bytes, move || -> Result<(usize, Option<String>), String> {
+            let (value, warnings) = converter.convert_to_value(&data, from)?;
+            for warning in &warnings {
+                eprintln!("[WARN] {}", warning);
+            }
+            let mut output_bytes = 0;
+            let mut output = keep_output.then(Vec::new);
+            converter.emit_chunked(&value, to, STREAM_CHUNK, &mut |chunk: &[u8]| {
+                output_bytes += chunk.len();
+                if let Some(output) = &mut output {
+                    output.extend_from_slice(chunk);
+                }
+                chunks.blocking_send(StreamEvent::Chunk(Bytes::copy_from_slice(chunk))).map_err(|_| "Client disconnected".to_string())
+            })?;
+            Ok((output_bytes, output.map(|bytes| String::from_utf8_lossy(&bytes).into_owned())))
+        }).await;
+
+        let (status, error, output_bytes, output) = match converted {
+            Ok(Ok((output_bytes, output))) => (StatusCode::OK, None, output_bytes, output),
+            Ok(Err(e)) => (StatusCode::BAD_REQUEST, Some(e), 0, None),
+            Err(failure) => (failure.status_code(), Some(failure.to_string()), 0, None),
+        };
+        if let Some(e) = &error {
+            eprintln!("[ERROR] {}-to-{}: {}", from, to, e);
+            let _ = events.send(StreamEvent::Failed(status, e.clone())).await;
+        }
+        if let Some(audit) = &app_state.audit {
+            audit.record(&audit_log::AuditRecord {
+                timestamp: audit_log::timestamp(),
+                transport: "rest",
+                endpoint: "/convert",
+                from,
+                to,
+                caller,
+                input_bytes,
+                output_bytes,
+                duration_ms: audit_log::duration_ms(started.elapsed()),
+                status: status.as_u16(),
+                error: error.as_deref(),
+                output: input.is_some().then_some(output.as_deref().unwrap_or_default()),
+                input,
+            });
+        }
+    });
+
+    let first = match received.recv().await {
+        Some(StreamEvent::Failed(status, e)) => return (status, e).into_response(),
+        Some(StreamEvent::Chunk(chunk)) => Some(chunk),
+        None => None,
+    };
+    let rest = stream::unfold(received, |mut received| async move {
+        match received.recv().await? {
+            StreamEvent::Chunk(chunk) => Some((Ok(chunk), received)),
+            StreamEvent::Failed(_, e) => Some((Err(std::io::Error::other(e)), received)),
+        }
+    });
+    let body = Body::from_stream(stream::iter(first.map(Ok)).chain(rest));
+    (StatusCode::OK, [(CONTENT_TYPE, content_type(to)), (ETAG, etag)], body).into_response()
+}
+
+// Highest-q registered format in Accept; wildcards and a missing header mean `default`
+fn negotiate_target(headers: &HeaderMap, default: &'static str) -> Option<&'static str> {
+    let registry = converter::registry();
+    let mut ranges = headers.get_all(ACCEPT).iter().filter_map(|value| value.to_str().ok()).flat_map(|value| value.split(',')).peekable();
+    if ranges.peek().is_none() {
+        return Some(default);
+    }
+
+    let mut best: Option<(&'static str, f32)> = None;
+    for range in ranges {
+        let mut params = range.split(';');
+        let essence = params.next().unwrap_or_default().trim();
+        let q = params
+            .find_map(|param| param.trim().strip_prefix("q="))
+            .and_then(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if q <= 0.0 {
+            continue;
+        }
+        let name = if essence.ends_with("/*") { Some(default) } else { registry.for_media_type(essence).map(|codec| codec.name()) };
+        if let Some(name) = name.filter(|_| best.is_none_or(|(_, best_q)| q > best_q)) {
+            best = Some((name, q));
+        }
+    }
+    best.map(|(name, _)| name)
+}
+
+// REST conversion plus its audit record when --audit-log is set
+async fn audited_convert(
+    app_state: AppState,
+    caller: audit_log::Caller,
+    endpoint: &str,
+    from: &str,
+    to: &str,
+    data: String,
+) -> (StatusCode, Json<ConvertResult>) {
+    let started = std::time::Instant::now();
+    let input_bytes = data.len();
+    let input = app_state.audit.as_ref().filter(|audit| audit.payloads()).map(|_| data.clone());
+    let response = convert_with_cache(app_state.cache, app_state.limits, app_state.guards, from, to, data).await;
+    
+    if let Some(audit) = &app_state.audit {
+        let (status, Json(result)) = &response;
+        audit.record(&audit_log::AuditRecord {
+            timestamp: audit_log::timestamp(),
+            transport: "rest",
+            endpoint,
+            from,
+            to,
+            caller,
+            input_bytes,
+            output_bytes: result.result.as_ref().map_or(0, String::len),
+            duration_ms: audit_log::duration_ms(started.elapsed()),
+            status: status.as_u16(),
+            error: result.error.as_deref(),
+            output: input.is_some().then_some(result.result.as_deref().unwrap_or_default()),
+            input,
+        });
+    }
+    response
+}
+
+// Cache entries are keyed by the API version, the options fingerprint, both
+// formats and the input, so entries written by another release or under other
+// guards are never served
+fn cache_key(from: &str, to: &str, guards: &ParserGuards, data: &str) -> String {
+    format!("toonify:{}:{}:{}_to_{}:{}", env!("CARGO_PKG_VERSION"), options_fingerprint(guards), from, to, data)
+}
+
+// Hash of every option that shapes a conversion result besides the formats;
+// a new conversion option belongs here so both cache keys and ETags change with it
+fn options_fingerprint(guards: &ParserGuards) -> String {
+    let digest = Sha256::digest(format!("{:?}", guards).as_bytes());
+    digest[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// `Cache-Control: no-store` on a request: convert without reading or writing the cache
+fn no_store(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|directive| directive.trim().eq_ignore_ascii_case("no-store"))
+}
+
+// Shared conversion path for all REST handlers: Moka -> Sled -> FormatRegistry
+async fn convert_with_cache(
+    cache_state: CacheState,
+    limits: ConversionLimits,
+    guards: ParserGuards,
+    from: &str,
+    to: &str,
+    data: String,
+) -> (StatusCode, Json<ConvertResult>) {
+    let cache_key = cache_key(from, to, &guards, &data);
+    
+    // Try Moka cache first (hot, lock-free, < 100ns)
+    #[cfg(feature = "cache")]
+    if let Some(ref moka) = cache_state.moka
+        && let Some(cached_result) = moka.get(&cache_key).await
+    {
+        eprintln!("[CACHE] Moka hit for {}-to-{}", from, to);
+        return (
+            StatusCode::OK,
+            Json(ConvertResult {
+                result: Some(cached_result),
+                error: None,
+                warnings: Vec::new(),
+            }),
+        );
+    }
+    
+    // Try Sled persistent cache if enabled (cold, ~1ms)
+    #[cfg(feature = "persistent-cache")]
+    if let Some(cached_result) = through_breaker(&cache_state.breakers.sled, || sled_get(&cache_state, &cache_key)) {
+        eprintln!("[CACHE] Sled hit for {}-to-{}", from, to);
+        
+        // Warm up Moka cache from Sled
+        #[cfg(feature = "cache")]
+        if let Some(ref moka) = cache_state.moka {
+            moka.insert(cache_key.clone(), cached_result.clone()).await;
+        }
+        
+        return (
+            StatusCode::OK,
+            Json(ConvertResult {
+                result: Some(cached_result),
+                error: None,
+                warnings: Vec::new(),
+            }),
+        );
+    }
+    
+    // Then the embedder's cache; its calls may block on I/O
+    if let Some(custom) = cache_state.custom.clone() {
+        let key = cache_key.clone();
+        if let Some(cached_result) = custom_call(&cache_state.breakers.custom, move || custom.get(&key)).await {
+            eprintln!("[CACHE] Custom cache hit for {}-to-{}", from, to);
+            
+            #[cfg(feature = "cache")]
+            if let Some(ref moka) = cache_state.moka {
+                moka.insert(cache_key.clone(), cached_result.clone()).await;
+            }
+            
+            return (
+                StatusCode::OK,
+                Json(ConvertResult {
+                    result: Some(cached_result),
+                    error: None,
+                    warnings: Vec::new(),
+                }),
+            );
+        }
+    }
+    
+    // Cache miss - perform conversion
+    let (source, target) = (from.to_string(), to.to_string());
+    let converter = converter::Converter::builder().guards(guards).build();
+    let converted = limits.run(data.len(), move || converter.convert_detailed(&data, &source, &target)).await;
+    let converted = match converted {
+        Ok(converted) => converted,
+        Err(failure) => {
+            eprintln!("[ERROR] {}-to-{}: {}", from, to, failure);
+            return (
+                failure.status_code(),
+                Json(ConvertResult {
+                    result: None,
+                    error: Some(failure.to_string()),
+                    warnings: Vec::new(),
+                }),
+            );
+        }
+    };
+    
+    match converted {
+        Ok(converter::ConversionOutput { output: result, warnings }) => {
+            for warning in &warnings {
+                eprintln!("[WARN] {}", warning);
+            }
+            
+            // Store in both Moka and Sled (write-through)
+            #[cfg(feature = "cache")]
+            if let Some(ref moka) = cache_state.moka {
+                moka.insert(cache_key.clone(), result.clone()).await;
+            }
+            
+            #[cfg(feature = "persistent-cache")]
+            through_breaker(&cache_state.breakers.sled, || sled_put(&cache_state, &cache_key, &result));
+            
+            if let Some(custom) = cache_state.custom.clone() {
+                let (key, value) = (cache_key.clone(), result.clone());
+                custom_call(&cache_state.breakers.custom, move || custom.put(&key, &value)).await;
+            }
+            
+            (
+            StatusCode::OK,
+            Json(ConvertResult {
+                result: Some(result),
+                error: None,
+                warnings,
+            }),
+            )
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ConvertResult {
+                result: None,
+                error: Some(e),
+                warnings: Vec::new(),
+            }),
+        ),
+    }
+}
+
+// One synchronous cache call through its breaker; skipped and failed calls read as misses
+#[cfg(feature = "persistent-cache")]
+fn through_breaker<T: Default>(breaker: &CircuitBreaker, call: impl FnOnce() -> Result<T, String>) -> T {
+    let Some(permit) = breaker.permit() else {
+        return T::default();
+    };
+    let outcome = call();
+    permit.record(outcome.is_ok());
+    outcome.unwrap_or_else(|e| {
+        eprintln!("[CACHE] Backend error: {}", e);
+        T::default()
+    })
+}
+
+// An embedder cache call on the blocking pool. A call still running after
+// `slow_call` is abandoned (it finishes in the background) and counts as failed,
+// as does one whose request is cancelled while it runs (the permit is dropped).
+async fn custom_call<T>(breaker: &CircuitBreaker, call: impl FnOnce() -> Result<T, String> + Send + 'static) -> T
+where
+    T: Default + Send + 'static,
+{
+    let Some(permit) = breaker.permit() else {
+        return T::default();
+    };
+    let outcome = match tokio::time::timeout(breaker.options().slow_call, tokio::task::spawn_blocking(call)).await {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(e)) => Err(format!("cache call panicked: {}", e)),
+        Err(_) => Err(format!("no answer within {}ms", breaker.options().slow_call.as_millis())),
+    };
+    permit.record(outcome.is_ok());
+    outcome.unwrap_or_else(|e| {
+        eprintln!("[CACHE] Custom cache error: {}", e);
+        T::default()
+    })
+}
+
+// Sled entries are sealed when --cache-encryption-key is set; entries that do
+// not decrypt (plaintext from before, or another key) count as misses
+#[cfg(feature = "persistent-cache")]
+fn sled_get(cache_state: &CacheState, cache_key: &str) -> Result<Option<String>, String> {
+    let Some(ref sled) = cache_state.sled else {
+        return Ok(None);
+    };
+    
+    #[cfg(feature = "cache-encryption")]
+    if let Some(ref cipher) = cache_state.cipher {
+        let lookup_key = cipher.lookup_key(cache_key);
+        let Some(sealed) = sled.get(&lookup_key).map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        return Ok(match cipher.decrypt(&lookup_key, &sealed) {
+            Ok(bytes) => String::from_utf8(bytes).ok(),
+            Err(e) => {
+                eprintln!("[CACHE] Ignoring Sled entry: {}", e);
+                None
+            }
+        });
+    }
+    
+    let cached_bytes = sled.get(cache_key.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(cached_bytes.and_then(|bytes| String::from_utf8(bytes.to_vec()).ok()))
+}
+
+#[cfg(feature = "persistent-cache")]
+fn sled_put(cache_state: &CacheState, cache_key: &str, result: &str) -> Result<(), String> {
+    let Some(ref sled) = cache_state.sled else {
+        return Ok(());
+    };
+    
+    #[cfg(feature = "cache-encryption")]
+    if let Some(ref cipher) = cache_state.cipher {
+        let lookup_key = cipher.lookup_key(cache_key);
+        match cipher.encrypt(&lookup_key, result.as_bytes()) {
+            Ok(sealed) => {
+                crate::sled_cache::record_write(sled, &lookup_key);
+                sled.insert(lookup_key, sealed).map_err(|e| e.to_string())?;
+            }
+            Err(e) => eprintln!("[CACHE] Not storing Sled entry: {}", e),
+        }
+        return Ok(());
+    }
+    
+    crate::sled_cache::record_write(sled, cache_key.as_bytes());
+    sled.insert(cache_key.as_bytes(), result.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(())
+}
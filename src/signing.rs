@@ -0,0 +1,117 @@
+// Ed25519 signatures for conversion outputs
+//
+// Keys are PKCS#8 / SPKI PEM files, e.g.
+//
+//     openssl genpkey -algorithm ed25519 -out signing.pem
+//     openssl pkey -in signing.pem -pubout -out signing.pub.pem
+//
+// A signature is either a detached text file (`output.toon.sig`) or a
+// footer line appended to TOON output. The TOON reader skips the footer, so
+// footer-signed files still convert; `verify-signature` checks it.
+
+use std::path::Path;
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+const DETACHED_HEADER: &str = "toonify-signature v1";
+const FOOTER_PREFIX: &str = crate::toon::parser::SIGNATURE_FOOTER;
+
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, String> {
+    let pem = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read signing key {:?}: {}", path, e))?;
+    SigningKey::from_pkcs8_pem(&pem)
+        .map_err(|e| format!("Invalid Ed25519 private key {:?}: {}", path, e))
+}
+
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey, String> {
+    let pem = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read public key {:?}: {}", path, e))?;
+    VerifyingKey::from_public_key_pem(&pem)
+        .map_err(|e| format!("Invalid Ed25519 public key {:?}: {}", path, e))
+}
+
+/// Public key from SPKI PEM text, e.g. one embedded at build time
+pub fn parse_verifying_key(pem: &str) -> Result<VerifyingKey, String> {
+    VerifyingKey::from_public_key_pem(pem)
+        .map_err(|e| format!("Invalid Ed25519 public key: {}", e))
+}
+
+/// Detached signature file contents for `content`
+pub fn sign_detached(content: &[u8], key: &SigningKey) -> String {
+    let signature = key.sign(content);
+    format!(
+        "{}\nalgorithm: ed25519\npublic-key: {}\nsignature: {}\n",
+        DETACHED_HEADER,
+        to_hex(key.verifying_key().as_bytes()),
+        to_hex(&signature.to_bytes())
+    )
+}
+
+/// `content` with a signature footer line appended
+pub fn sign_footer(content: &str, key: &SigningKey) -> String {
+    let signature = key.sign(content.as_bytes());
+    format!("{}{}{}\n", content, FOOTER_PREFIX, to_hex(&signature.to_bytes()))
+}
+
+/// Split footer-signed text into the signed content and its signature, if it has a footer
+pub fn split_footer(text: &str) -> Option<(&str, &str)> {
+    let at = text.rfind(FOOTER_PREFIX)?;
+    Some((&text[..at], text[at + FOOTER_PREFIX.len()..].trim_end()))
+}
+
+/// Check a detached signature file against `content`
+pub fn verify_detached(content: &[u8], signature_file: &str, key: &VerifyingKey) -> Result<(), String> {
+    let mut lines = signature_file.lines();
+    if lines.next() != Some(DETACHED_HEADER) {
+        return Err("Not a toonify signature file".to_string());
+    }
+
+    let mut algorithm = None;
+    let mut signer = None;
+    let mut signature = None;
+    for line in lines {
+        match line.split_once(": ") {
+            Some(("algorithm", value)) => algorithm = Some(value),
+            Some(("public-key", value)) => signer = Some(value),
+            Some(("signature", value)) => signature = Some(value),
+            _ => {}
+        }
+    }
+
+    if algorithm != Some("ed25519") {
+        return Err(format!("Unsupported signature algorithm: {}", algorithm.unwrap_or("<missing>")));
+    }
+    if let Some(signer) = signer.filter(|signer| *signer != to_hex(key.as_bytes())) {
+        return Err(format!("Signed by a different key ({})", signer));
+    }
+
+    verify_hex(content, signature.ok_or("Signature file has no signature")?, key)
+}
+
+/// Check a hex-encoded Ed25519 signature against `content`
+pub fn verify_hex(content: &[u8], signature_hex: &str, key: &VerifyingKey) -> Result<(), String> {
+    let bytes: [u8; 64] = from_hex(signature_hex)?
+        .try_into()
+        .map_err(|_| "Signature must be 64 bytes".to_string())?;
+    key.verify(content, &Signature::from_bytes(&bytes))
+        .map_err(|_| "Signature does not match content".to_string())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return Err("Invalid hex signature".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| "Invalid hex signature".to_string()))
+        .collect()
+}
@@ -0,0 +1,146 @@
+// Maintenance for the Sled result cache (`serve --persistent-cache`,
+// `toonify cache stats|prune|compact`)
+//
+// Results live in the default tree. The time each one was written lives in
+// the `written` tree under the same key (seconds since the Unix epoch, big
+// endian), so `prune --older-than` expires entries without decoding them,
+// sealed or not. Entries from before timestamps were recorded have no time
+// and count as older than any cutoff.
+//
+// Sled allows one process per database, so stop the server before running
+// these; opening a database the server holds fails with a lock error.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sled::Db;
+
+pub const WRITTEN_TREE: &str = "written";
+
+/// What `toonify cache stats` prints
+#[derive(Debug, Clone, Default)]
+pub struct SledCacheStats {
+    pub entries: usize,
+    pub size_on_disk: u64,
+    /// Write times of the oldest and newest timed entries, seconds since the epoch
+    pub oldest: Option<u64>,
+    pub newest: Option<u64>,
+    /// Entries with no recorded write time
+    pub untimed: usize,
+}
+
+pub fn open(path: &Path) -> Result<Db, String> {
+    if !path.exists() {
+        return Err(format!("No Sled cache at {:?}", path));
+    }
+    sled::open(path).map_err(|e| format!("Failed to open Sled cache {:?} (is a server using it?): {}", path, e))
+}
+
+/// Note when the entry under `key` was written
+pub fn record_write(db: &Db, key: &[u8]) {
+    if let Ok(written) = db.open_tree(WRITTEN_TREE) {
+        let _ = written.insert(key, now_secs().to_be_bytes().to_vec());
+    }
+}
+
+pub fn stats(db: &Db) -> Result<SledCacheStats, String> {
+    let written = db.open_tree(WRITTEN_TREE).map_err(|e| e.to_string())?;
+    let mut stats = SledCacheStats {
+        entries: db.len(),
+        size_on_disk: db.size_on_disk().map_err(|e| e.to_string())?,
+        ..Default::default()
+    };
+    for key in db.iter().keys() {
+        let key = key.map_err(|e| e.to_string())?;
+        match written.get(&key).map_err(|e| e.to_string())?.and_then(|time| decode_secs(&time)) {
+            Some(time) => {
+                stats.oldest = Some(stats.oldest.map_or(time, |oldest| oldest.min(time)));
+                stats.newest = Some(stats.newest.map_or(time, |newest| newest.max(time)));
+            }
+            None => stats.untimed += 1,
+        }
+    }
+    Ok(stats)
+}
+
+/// Remove entries written more than `older_than` ago; returns how many went
+pub fn prune(db: &Db, older_than: Duration) -> Result<usize, String> {
+    let written = db.open_tree(WRITTEN_TREE).map_err(|e| e.to_string())?;
+    let cutoff = now_secs().saturating_sub(older_than.as_secs());
+    let mut removed = 0;
+    for key in db.iter().keys() {
+        let key = key.map_err(|e| e.to_string())?;
+        let time = written.get(&key).map_err(|e| e.to_string())?.and_then(|time| decode_secs(&time));
+        if time.is_none_or(|time| time < cutoff) {
+            db.remove(&key).map_err(|e| e.to_string())?;
+            written.remove(&key).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+    // Times whose entry is already gone (evicted by hand, or a crash between the two writes)
+    for key in written.iter().keys() {
+        let key = key.map_err(|e| e.to_string())?;
+        if !db.contains_key(&key).map_err(|e| e.to_string())? {
+            written.remove(&key).map_err(|e| e.to_string())?;
+        }
+    }
+    db.flush().map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
+/// Rewrite the database into fresh files, dropping the space held by removed
+/// and overwritten entries; returns the size on disk before and after
+pub fn compact(path: &Path) -> Result<(u64, u64), String> {
+    let db = open(path)?;
+    let before = db.size_on_disk().map_err(|e| e.to_string())?;
+
+    let rewritten = sibling(path, "compacting");
+    if rewritten.exists() {
+        std::fs::remove_dir_all(&rewritten).map_err(|e| format!("Failed to remove {:?}: {}", rewritten, e))?;
+    }
+    let fresh = sled::open(&rewritten).map_err(|e| format!("Failed to create {:?}: {}", rewritten, e))?;
+    fresh.import(db.export());
+    fresh.flush().map_err(|e| e.to_string())?;
+    let after = fresh.size_on_disk().map_err(|e| e.to_string())?;
+    drop(fresh);
+    drop(db);
+
+    // Swap directories so an interruption leaves either database whole
+    let previous = sibling(path, "precompact");
+    std::fs::rename(path, &previous).map_err(|e| format!("Failed to move {:?} aside: {}", path, e))?;
+    std::fs::rename(&rewritten, path).map_err(|e| format!("Failed to move {:?} into place: {}", rewritten, e))?;
+    std::fs::remove_dir_all(&previous).map_err(|e| format!("Failed to remove {:?}: {}", previous, e))?;
+    Ok((before, after))
+}
+
+/// `30s`, `15m`, `12h`, `7d` or `2w`
+pub fn parse_age(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number.parse().map_err(|_| format!("Invalid age {:?}: expected a number and a unit, e.g. 7d", text))?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return Err(format!("Invalid age {:?}: the unit must be s, m, h, d or w", text)),
+    };
+    Ok(Duration::from_secs(number.saturating_mul(unit_secs)))
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn decode_secs(bytes: &[u8]) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.try_into().ok()?))
+}
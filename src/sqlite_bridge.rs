@@ -0,0 +1,196 @@
+// `toonify sqlite export|import`: SQLite files ↔ TOON snapshots
+//
+// Export writes each table as a typed TOON entity (`users[2]{id:int,name:str}:`)
+// so values keep their SQLite storage class: a TEXT "123" stays a string and
+// an INTEGER stays a number. Columns declared BOOLEAN come back as true/false.
+// Import goes the other way: every table-shaped entity becomes a table whose
+// column types are inferred from the values, so export → import → export is
+// stable.
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Number, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::{Column, Row, TypeInfo, ValueRef};
+
+use toonify::converter;
+use toonify::toon::{columns_of, serialize_toon_with, ColumnType, SerializeOptions};
+
+async fn open(path: &Path, create: bool) -> Result<SqlitePool, Box<dyn Error>> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(create)
+        .read_only(!create);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    Ok(pool)
+}
+
+pub async fn run_export(database: PathBuf, tables: Vec<String>, output: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    eprintln!("[SQLITE] Opening {:?}", database);
+    let pool = open(&database, false).await?;
+
+    let existing: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let tables = if tables.is_empty() { existing.clone() } else { tables };
+    let mut doc = Map::new();
+    for table in &tables {
+        // Only names read from sqlite_master are interpolated into SQL
+        if !existing.contains(table) {
+            return Err(format!("No such table: {:?} (available: {})", table, existing.join(", ")).into());
+        }
+        let rows = sqlx::query(&format!("SELECT * FROM {}", quote_identifier(table))).fetch_all(&pool).await?;
+        eprintln!("[SQLITE] {}: {} rows", table, rows.len());
+
+        let mut values = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let mut object = Map::new();
+            for (index, column) in row.columns().iter().enumerate() {
+                object.insert(column.name().to_string(), cell_value(row, index)?);
+            }
+            values.push(Value::Object(object));
+        }
+        doc.insert(table.clone(), Value::Array(values));
+    }
+    pool.close().await;
+
+    let toon = serialize_toon_with(&Value::Object(doc), &SerializeOptions { typed_headers: true, ..Default::default() })?;
+    match output {
+        Some(path) => {
+            eprintln!("[SQLITE] Writing to file: {:?}", path);
+            fs::write(path, toon)?;
+        }
+        None => {
+            io::stdout().write_all(toon.as_bytes())?;
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn cell_value(row: &SqliteRow, index: usize) -> Result<Value, Box<dyn Error>> {
+    let raw = row.try_get_raw(index)?;
+    if raw.is_null() {
+        return Ok(Value::Null);
+    }
+    let storage = raw.type_info().name().to_string();
+    let declared = row.columns()[index].type_info().name().to_ascii_uppercase();
+
+    Ok(match storage.as_str() {
+        "INTEGER" if declared.contains("BOOL") => Value::Bool(row.try_get::<i64, _>(index)? != 0),
+        "INTEGER" => Value::from(row.try_get::<i64, _>(index)?),
+        "REAL" => Number::from_f64(row.try_get::<f64, _>(index)?).map_or(Value::Null, Value::Number),
+        "BLOB" => Value::String(row.try_get::<Vec<u8>, _>(index)?.iter().map(|byte| format!("{:02x}", byte)).collect()),
+        _ => Value::String(row.try_get::<String, _>(index)?),
+    })
+}
+
+pub async fn run_import(database: PathBuf, input: Option<PathBuf>, replace: bool) -> Result<(), Box<dyn Error>> {
+    let toon = match &input {
+        Some(path) => {
+            eprintln!("[SQLITE] Reading TOON from file: {:?}", path);
+            fs::read_to_string(path)?
+        }
+        None => {
+            eprintln!("[SQLITE] Reading TOON from STDIN");
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        }
+    };
+    let Value::Object(doc) = converter::toon_to_value(&toon)? else {
+        return Err("TOON document root must be an object".into());
+    };
+
+    eprintln!("[SQLITE] Opening {:?}", database);
+    let pool = open(&database, true).await?;
+    let mut tx = pool.begin().await?;
+
+    for (name, entity) in &doc {
+        let rows: Vec<&Map<String, Value>> = match entity {
+            Value::Array(items) if items.iter().all(Value::is_object) => items.iter().filter_map(Value::as_object).collect(),
+            Value::Object(row) => vec![row],
+            _ => {
+                eprintln!("[SQLITE] Skipping {:?}: not a table", name);
+                continue;
+            }
+        };
+
+        let columns = columns_of(rows.iter().copied());
+
+        // SQLite has no zero-column tables, and an empty TOON table carries no header
+        if columns.is_empty() {
+            eprintln!("[SQLITE] Skipping {:?}: no columns", name);
+            continue;
+        }
+
+        let table = quote_identifier(name);
+        if replace {
+            sqlx::query(&format!("DROP TABLE IF EXISTS {}", table)).execute(&mut *tx).await?;
+        }
+        let definitions: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                let ty = ColumnType::infer(rows.iter().filter_map(|row| row.get(*column)));
+                format!("{} {}", quote_identifier(column), sql_type(ty)).trim_end().to_string()
+            })
+            .collect();
+        sqlx::query(&format!("CREATE TABLE IF NOT EXISTS {} ({})", table, definitions.join(", ")))
+            .execute(&mut *tx)
+            .await?;
+
+        let insert = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            columns.iter().map(|column| quote_identifier(column)).collect::<Vec<_>>().join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        for row in &rows {
+            let mut query = sqlx::query(&insert);
+            for column in &columns {
+                query = match row.get(*column).unwrap_or(&Value::Null) {
+                    Value::Null => query.bind(None::<i64>),
+                    Value::Bool(b) => query.bind(*b),
+                    Value::Number(n) => match n.as_i64() {
+                        Some(i) => query.bind(i),
+                        None => query.bind(n.as_f64()),
+                    },
+                    Value::String(s) => query.bind(s.as_str()),
+                    nested => query.bind(nested.to_string()),
+                };
+            }
+            query.execute(&mut *tx).await?;
+        }
+        eprintln!("[SQLITE] {}: {} rows imported", name, rows.len());
+    }
+
+    tx.commit().await?;
+    pool.close().await;
+    println!("✓ Imported {} entities into {:?}", doc.len(), database);
+    Ok(())
+}
+
+// Declared types chosen so that export restores the same TOON types
+fn sql_type(ty: Option<ColumnType>) -> &'static str {
+    match ty {
+        Some(ColumnType::Int) => "INTEGER",
+        Some(ColumnType::Bool) => "BOOLEAN",
+        Some(ColumnType::Float | ColumnType::Num) => "REAL",
+        Some(ColumnType::Str | ColumnType::Json) => "TEXT",
+        None => "",
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,71 @@
+// Structural overview of a document for prompts (`toonify summarize`)
+//
+// A table keeps its whole header: every column that any row has, typed the
+// way typed headers would declare it over all rows, but only the first
+// `rows` rows. A list keeps its first `rows` values; objects and scalars are
+// written whole. A closing `_summary` table gives the real length of every
+// table and list, so a reader knows how much was left out. The result is
+// valid TOON and parses like any other document.
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::toon::{ColumnType, ToonWriter};
+
+/// Name of the trailing table of entity sizes
+pub const SUMMARY_ENTITY: &str = "_summary";
+
+/// `value` with each table and list cut to its first `rows` elements
+pub fn summarize(value: &Value, rows: usize) -> Result<String, String> {
+    let Value::Object(map) = value else {
+        return Err("Root value must be an object".to_string());
+    };
+
+    let mut writer = ToonWriter::new(Vec::new());
+    // (entity, length, columns, rows shown)
+    let mut sizes: Vec<(&str, usize, usize, usize)> = Vec::new();
+    for (name, entity) in map {
+        match entity {
+            Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => {
+                let columns: Vec<&str> = items.iter()
+                    .filter_map(Value::as_object)
+                    .flat_map(|row| row.keys().map(String::as_str))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+                let header: Vec<String> = columns.iter()
+                    .map(|column| match ColumnType::infer(items.iter().filter_map(|item| item.get(column))) {
+                        Some(ty) => format!("{}:{}", column, ty.as_str()),
+                        None => column.to_string(),
+                    })
+                    .collect();
+                let header: Vec<&str> = header.iter().map(String::as_str).collect();
+
+                let shown = items.len().min(rows);
+                writer.begin_table_with_len(name, &header, shown).map_err(|e| e.to_string())?;
+                for item in &items[..shown] {
+                    let cells: Vec<Value> = columns.iter().map(|column| item.get(column).cloned().unwrap_or(Value::Null)).collect();
+                    writer.write_row(&cells).map_err(|e| e.to_string())?;
+                }
+                sizes.push((name, items.len(), columns.len(), shown));
+            }
+            Value::Array(items) => {
+                let shown = items.len().min(rows);
+                writer.write_entity(name, &Value::Array(items[..shown].to_vec())).map_err(|e| e.to_string())?;
+                sizes.push((name, items.len(), 0, shown));
+            }
+            _ => writer.write_entity(name, entity).map_err(|e| e.to_string())?,
+        }
+    }
+
+    if !sizes.is_empty() {
+        writer.begin_table_with_len(SUMMARY_ENTITY, &["entity", "rows", "columns", "shown"], sizes.len()).map_err(|e| e.to_string())?;
+        for (name, len, columns, shown) in sizes {
+            writer.write_row(&[Value::from(name), Value::from(len), Value::from(columns), Value::from(shown)]).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let output = writer.finish().map_err(|e| e.to_string())?;
+    String::from_utf8(output).map_err(|e| e.to_string())
+}
@@ -0,0 +1,303 @@
+// `toonify sync`: keep two directories equivalent in both directions
+//
+// `--dir-a ./json --dir-b ./toon` pairs `json/x/users.json` with
+// `toon/x/users.toon` (formats from --format-a/--format-b). A file on one
+// side only is converted to the other; a file whose content changed since the
+// last sync is converted over its partner; a file deleted on one side is
+// deleted on the other, unless the partner was edited meanwhile. Pairs that
+// already hold the same document are left alone, so sync's own writes do not
+// bounce back.
+//
+// When both files of a pair changed and differ, `--conflict newest` keeps the
+// more recently modified one; `--conflict manual` touches neither and writes
+// `users.json.conflict` / `users.toon.conflict` with the other side's version
+// for comparison. Delete the side you do not want to resolve it.
+//
+// What was last synced is kept in `<dir-a>/.toonify-sync.state` (content
+// hashes per pair), so changes made while sync was not running are picked up
+// by the first pass.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use notify::{Event, RecursiveMode, Watcher};
+
+use toonify::converter::{self, Converter};
+
+use crate::atomic_write::write_atomic;
+use crate::file_walk::{self, FileWalk};
+
+const STATE_FILE: &str = ".toonify-sync.state";
+
+// Editors save in several steps; wait this long for the events to settle
+const SETTLE: Duration = Duration::from_millis(200);
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConflictPolicy {
+    /// Keep the more recently modified file
+    Newest,
+    /// Leave both files and write .conflict copies for a person to resolve
+    Manual,
+}
+
+pub struct SyncOptions {
+    pub dir_a: PathBuf,
+    pub dir_b: PathBuf,
+    pub format_a: String,
+    pub format_b: String,
+    pub conflict: ConflictPolicy,
+    pub once: bool,
+    pub fsync: bool,
+}
+
+struct Side {
+    dir: PathBuf,
+    format: String,
+    extension: &'static str,
+}
+
+impl Side {
+    fn new(dir: &Path, format: &str) -> Result<Self, String> {
+        let codec = converter::registry().get(format).ok_or(format!("Unknown format: {}", format))?;
+        let extension = codec.extensions().first().copied().unwrap_or("txt");
+        let dir = file_walk::canonicalize(dir).map_err(|e| format!("Cannot open {:?}: {}", dir, e))?;
+        Ok(Self { dir, format: codec.name().to_string(), extension })
+    }
+
+    fn path(&self, stem: &Path) -> PathBuf {
+        let mut name = self.dir.join(stem).into_os_string();
+        name.push(".");
+        name.push(self.extension);
+        PathBuf::from(name)
+    }
+
+    // The pair a file of this side belongs to, relative and without the extension
+    fn stem(&self, path: &Path) -> Option<PathBuf> {
+        if path.extension().and_then(|extension| extension.to_str()) != Some(self.extension) {
+            return None;
+        }
+        path.strip_prefix(&self.dir).ok().map(|relative| relative.with_extension(""))
+    }
+}
+
+struct Sync {
+    a: Side,
+    b: Side,
+    converter: Converter,
+    conflict: ConflictPolicy,
+    fsync: bool,
+    state_path: PathBuf,
+    /// Content hashes of both files as last synced, by pair
+    state: BTreeMap<String, (u64, u64)>,
+    conflicts: BTreeSet<PathBuf>,
+}
+
+pub fn run(options: SyncOptions, converter: Converter) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(&options.dir_a)?;
+    fs::create_dir_all(&options.dir_b)?;
+    let a = Side::new(&options.dir_a, &options.format_a)?;
+    let b = Side::new(&options.dir_b, &options.format_b)?;
+    if a.dir.starts_with(&b.dir) || b.dir.starts_with(&a.dir) {
+        return Err("--dir-a and --dir-b must not contain each other".into());
+    }
+
+    let state_path = a.dir.join(STATE_FILE);
+    let state = match fs::read_to_string(&state_path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| format!("Invalid sync state {:?}: {}", state_path, e))?,
+        Err(_) => BTreeMap::new(),
+    };
+    let mut sync = Sync { a, b, converter, conflict: options.conflict, fsync: options.fsync, state_path, state, conflicts: BTreeSet::new() };
+
+    eprintln!("[SYNC] {:?} ({}) <-> {:?} ({})", sync.a.dir, sync.a.format, sync.b.dir, sync.b.format);
+    let stems = sync.all_stems();
+    sync.reconcile(stems)?;
+
+    if options.once {
+        if !sync.conflicts.is_empty() {
+            return Err(format!("{} conflicts need resolving", sync.conflicts.len()).into());
+        }
+        return Ok(());
+    }
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    })?;
+    watcher.watch(&sync.a.dir, RecursiveMode::Recursive)?;
+    watcher.watch(&sync.b.dir, RecursiveMode::Recursive)?;
+    println!("Sync active between {:?} and {:?}.", sync.a.dir, sync.b.dir);
+
+    loop {
+        let first = rx.recv()?;
+        let mut paths = first.paths;
+        loop {
+            match rx.recv_timeout(SETTLE) {
+                Ok(event) => paths.extend(event.paths),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+        let stems: BTreeSet<PathBuf> = paths.iter().filter_map(|path| sync.a.stem(path).or_else(|| sync.b.stem(path))).collect();
+        sync.reconcile(stems)?;
+    }
+}
+
+impl Sync {
+    fn all_stems(&self) -> BTreeSet<PathBuf> {
+        let mut stems = BTreeSet::new();
+        for side in [&self.a, &self.b] {
+            let pattern = format!("*.{}", side.extension);
+            let files = FileWalk::new(Some(&pattern), true, false).map(|walk| walk.run(&side.dir)).unwrap_or_default();
+            stems.extend(files.iter().filter_map(|path| side.stem(path)));
+        }
+        // Pairs recorded before but deleted on both sides since
+        stems.extend(self.state.keys().map(PathBuf::from));
+        stems
+    }
+
+    fn reconcile(&mut self, stems: BTreeSet<PathBuf>) -> Result<(), Box<dyn Error>> {
+        let before = self.state.clone();
+        for stem in stems {
+            if let Err(e) = self.reconcile_pair(&stem) {
+                eprintln!("[SYNC] Error syncing {}: {}", stem.display(), e);
+            }
+        }
+        if self.state != before {
+            let text = serde_json::to_string_pretty(&self.state)?;
+            write_atomic(&self.state_path, text.as_bytes(), self.fsync, None)?;
+        }
+        Ok(())
+    }
+
+    fn reconcile_pair(&mut self, stem: &Path) -> Result<(), String> {
+        let key = stem.to_string_lossy().into_owned();
+        let (a_path, b_path) = (self.a.path(stem), self.b.path(stem));
+        let a_text = fs::read_to_string(&a_path).ok();
+        let b_text = fs::read_to_string(&b_path).ok();
+        let recorded = self.state.get(&key).copied();
+
+        match (a_text, b_text) {
+            (None, None) => {
+                self.state.remove(&key);
+            }
+            // Deleted on one side and untouched on the other: delete it there too
+            (Some(text), None) if recorded.is_some_and(|(a, _)| a == hash(&text)) => self.delete(&key, &a_path)?,
+            (None, Some(text)) if recorded.is_some_and(|(_, b)| b == hash(&text)) => self.delete(&key, &b_path)?,
+            (Some(text), None) => self.copy_a_to_b(&key, stem, &text)?,
+            (None, Some(text)) => self.copy_b_to_a(&key, stem, &text)?,
+            (Some(a_text), Some(b_text)) => {
+                if self.equivalent(&a_text, &b_text) {
+                    self.state.insert(key, (hash(&a_text), hash(&b_text)));
+                    self.resolved(stem);
+                    return Ok(());
+                }
+                let changed_a = recorded.is_none_or(|(a, _)| a != hash(&a_text));
+                let changed_b = recorded.is_none_or(|(_, b)| b != hash(&b_text));
+                match (changed_a, changed_b) {
+                    (true, false) => self.copy_a_to_b(&key, stem, &a_text)?,
+                    (false, true) => self.copy_b_to_a(&key, stem, &b_text)?,
+                    // Synced before; the formats just do not round-trip exactly
+                    (false, false) => {}
+                    (true, true) => self.conflict(&key, stem, &a_text, &b_text)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn equivalent(&self, a_text: &str, b_text: &str) -> bool {
+        let a = self.converter.parse(a_text, &self.a.format).map(|(value, _)| value);
+        let b = self.converter.parse(b_text, &self.b.format).map(|(value, _)| value);
+        matches!((a, b), (Ok(a), Ok(b)) if a == b)
+    }
+
+    fn copy_a_to_b(&mut self, key: &str, stem: &Path, text: &str) -> Result<(), String> {
+        let written = self.write(text, &self.a, &self.b, stem)?;
+        self.state.insert(key.to_string(), (hash(text), hash(&written)));
+        self.resolved(stem);
+        Ok(())
+    }
+
+    fn copy_b_to_a(&mut self, key: &str, stem: &Path, text: &str) -> Result<(), String> {
+        let written = self.write(text, &self.b, &self.a, stem)?;
+        self.state.insert(key.to_string(), (hash(&written), hash(text)));
+        self.resolved(stem);
+        Ok(())
+    }
+
+    // Convert `text` from `from`'s format and write it as `stem` on the `to` side
+    fn write(&self, text: &str, from: &Side, to: &Side, stem: &Path) -> Result<String, String> {
+        let output = self.convert(text, from, to)?;
+        let path = to.path(stem);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        write_atomic(&path, output.as_bytes(), self.fsync, None).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        eprintln!("[SYNC] ✓ {:?} -> {:?}", from.path(stem), path);
+        Ok(output)
+    }
+
+    fn convert(&self, text: &str, from: &Side, to: &Side) -> Result<String, String> {
+        let (value, warnings) = self.converter.parse(text, &from.format)?;
+        for warning in &warnings {
+            eprintln!("[SYNC] Warning: {}", warning);
+        }
+        self.converter.emit(&value, &to.format)
+    }
+
+    fn delete(&mut self, key: &str, path: &Path) -> Result<(), String> {
+        fs::remove_file(path).map_err(|e| format!("Failed to delete {:?}: {}", path, e))?;
+        eprintln!("[SYNC] ✓ Deleted {:?}", path);
+        self.state.remove(key);
+        Ok(())
+    }
+
+    fn conflict(&mut self, key: &str, stem: &Path, a_text: &str, b_text: &str) -> Result<(), String> {
+        let (a_path, b_path) = (self.a.path(stem), self.b.path(stem));
+        match self.conflict {
+            ConflictPolicy::Newest => {
+                let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+                let keep_a = modified(&a_path) >= modified(&b_path);
+                let kept = if keep_a { &a_path } else { &b_path };
+                eprintln!("[SYNC] Conflict: both {:?} and {:?} changed; keeping the newer {:?}", a_path, b_path, kept);
+                if keep_a { self.copy_a_to_b(key, stem, a_text) } else { self.copy_b_to_a(key, stem, b_text) }
+            }
+            ConflictPolicy::Manual => {
+                if self.conflicts.insert(stem.to_path_buf()) {
+                    eprintln!("[SYNC] Conflict: both {:?} and {:?} changed; see the .conflict files, then delete the side to drop", a_path, b_path);
+                }
+                let b_as_a = self.convert(b_text, &self.b, &self.a)?;
+                let a_as_b = self.convert(a_text, &self.a, &self.b)?;
+                write_atomic(&conflict_path(&a_path), b_as_a.as_bytes(), self.fsync, None).map_err(|e| e.to_string())?;
+                write_atomic(&conflict_path(&b_path), a_as_b.as_bytes(), self.fsync, None).map_err(|e| e.to_string())?;
+                Ok(())
+            }
+        }
+    }
+
+    // The pair is in sync again; drop what a manual conflict (maybe in an earlier run) left behind
+    fn resolved(&mut self, stem: &Path) {
+        self.conflicts.remove(stem);
+        for path in [self.a.path(stem), self.b.path(stem)] {
+            let _ = fs::remove_file(conflict_path(&path));
+        }
+    }
+}
+
+fn conflict_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".conflict");
+    PathBuf::from(name)
+}
+
+// FNV-1a; stable across runs and toolchains, unlike the std hasher
+fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
@@ -0,0 +1,380 @@
+// `toonify dedupe` and `toonify sort`: row operations on large TOON tables
+//
+// The input is read line by line through `StreamingParser` and written with
+// `ToonWriter`, so no table is ever held as a document. Kept rows are spilled
+// to JSON-lines files in the temp directory until the table ends and its row
+// count (needed for the header) is known:
+//
+// - dedupe keeps the first row for each key; only the keys stay in memory
+// - sort sorts `buffer_rows` rows at a time, spills each sorted run, and
+//   merges the runs. Equal keys keep their input order.
+//
+// Other entities pass through unchanged (tables are buffered as text by the
+// writer, as their header carries the length). Typed header annotations are
+// not carried over.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use serde_json::Value;
+
+use toonify::toon::{StreamingParser, ToonEvent, ToonWriter};
+
+pub enum Operation {
+    Dedupe { keys: Vec<String> },
+    Sort { by: Vec<String>, descending: bool, buffer_rows: usize },
+}
+
+pub struct TableOptions {
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    /// Only this entity; otherwise every table with the columns
+    pub entity: Option<String>,
+}
+
+impl Operation {
+    fn columns(&self) -> &[String] {
+        match self {
+            Operation::Dedupe { keys } => keys,
+            Operation::Sort { by, .. } => by,
+        }
+    }
+
+    fn tag(&self) -> &'static str {
+        match self {
+            Operation::Dedupe { .. } => "[DEDUPE]",
+            Operation::Sort { .. } => "[SORT]",
+        }
+    }
+}
+
+enum Open {
+    Rows(TableRows),
+    // An entity that passes through: its rows go straight to the writer or are collected
+    Table { columns: Vec<String> },
+    Other { name: String, is_array: bool, rows: Vec<Value> },
+}
+
+struct TableRows {
+    name: String,
+    columns: Vec<String>,
+    // Positions of the key / sort columns
+    keys: Vec<usize>,
+    seen: HashSet<String>,
+    dropped: usize,
+    buffer: Vec<Vec<Value>>,
+    runs: Vec<Spill>,
+}
+
+pub fn run(operation: Operation, options: TableOptions) -> Result<(), Box<dyn Error>> {
+    let tag = operation.tag();
+    let mut reader: Box<dyn BufRead> = match &options.input {
+        Some(path) => {
+            eprintln!("{} Reading from file: {:?}", tag, path);
+            Box::new(BufReader::new(File::open(path)?))
+        }
+        None => {
+            eprintln!("{} Reading from STDIN", tag);
+            Box::new(BufReader::new(io::stdin()))
+        }
+    };
+    let out: Box<dyn Write> = match &options.output {
+        Some(path) => {
+            eprintln!("{} Writing to file: {:?}", tag, path);
+            Box::new(BufWriter::new(File::create(path)?))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let mut writer = ToonWriter::new(out);
+
+    let mut parser = StreamingParser::new();
+    let mut open = None;
+    let mut matched = false;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            parser.finish();
+        } else {
+            parser.feed(&line);
+        }
+        for event in parser.by_ref() {
+            match event? {
+                ToonEvent::EntityStart { name, columns, is_array } => {
+                    let selected = options.entity.as_ref().is_none_or(|entity| *entity == name);
+                    let keys: Option<Vec<usize>> = operation
+                        .columns()
+                        .iter()
+                        .map(|key| columns.iter().position(|column| column == key))
+                        .collect();
+                    open = Some(match keys {
+                        Some(keys) if selected && is_array && !columns.is_empty() => {
+                            matched = true;
+                            Open::Rows(TableRows {
+                                name,
+                                columns,
+                                keys,
+                                seen: HashSet::new(),
+                                dropped: 0,
+                                buffer: Vec::new(),
+                                runs: Vec::new(),
+                            })
+                        }
+                        _ if is_array && !columns.is_empty() => {
+                            let header: Vec<&str> = columns.iter().map(String::as_str).collect();
+                            writer.begin_table(&name, &header)?;
+                            Open::Table { columns }
+                        }
+                        _ => Open::Other { name, is_array, rows: Vec::new() },
+                    });
+                }
+                ToonEvent::Row(row) => match open.as_mut() {
+                    Some(Open::Rows(table)) => table.push(&operation, row)?,
+                    Some(Open::Table { columns }) => writer.write_row(&cells(row, columns))?,
+                    Some(Open::Other { rows, .. }) => rows.push(row),
+                    None => {}
+                },
+                ToonEvent::EntityEnd { .. } => match open.take() {
+                    Some(Open::Rows(table)) => table.finish(&operation, &mut writer)?,
+                    Some(Open::Other { name, is_array, mut rows }) => {
+                        let value = if is_array { Value::Array(rows) } else { rows.pop().unwrap_or(Value::Null) };
+                        writer.write_entity(&name, &value)?;
+                    }
+                    Some(Open::Table { .. }) | None => {}
+                },
+                ToonEvent::Scalar { name, value } => writer.write_scalar(&name, &value)?,
+            }
+        }
+        if line.is_empty() {
+            break;
+        }
+    }
+    writer.finish()?;
+
+    if !matched {
+        let columns = operation.columns().join(", ");
+        return Err(match options.entity {
+            Some(entity) => format!("Entity {:?} is not a table with columns {}", entity, columns),
+            None => format!("No table has columns {}", columns),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+// A row object's cells in header order (missing cells are null)
+fn cells(row: Value, columns: &[String]) -> Vec<Value> {
+    match row {
+        Value::Object(mut object) => columns.iter().map(|column| object.remove(column).unwrap_or(Value::Null)).collect(),
+        other => vec![other],
+    }
+}
+
+impl TableRows {
+    fn push(&mut self, operation: &Operation, row: Value) -> Result<(), Box<dyn Error>> {
+        let row = cells(row, &self.columns);
+        match operation {
+            Operation::Dedupe { .. } => {
+                let key: Vec<&Value> = self.keys.iter().map(|&index| &row[index]).collect();
+                if !self.seen.insert(serde_json::to_string(&key)?) {
+                    self.dropped += 1;
+                    return Ok(());
+                }
+                if self.runs.is_empty() {
+                    self.runs.push(Spill::new()?);
+                }
+                self.runs[0].push(&row)?;
+            }
+            Operation::Sort { descending, buffer_rows, .. } => {
+                self.buffer.push(row);
+                if self.buffer.len() >= *buffer_rows {
+                    self.spill_run(*descending)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn sort_buffer(&mut self, descending: bool) {
+        let keys = &self.keys;
+        self.buffer.sort_by(|a, b| compare_keys(keys.iter().map(|&i| &a[i]), keys.iter().map(|&i| &b[i]), descending));
+    }
+
+    fn spill_run(&mut self, descending: bool) -> Result<(), Box<dyn Error>> {
+        self.sort_buffer(descending);
+        let mut run = Spill::new()?;
+        for row in self.buffer.drain(..) {
+            run.push(&row)?;
+        }
+        self.runs.push(run);
+        Ok(())
+    }
+
+    fn finish<W: Write>(mut self, operation: &Operation, writer: &mut ToonWriter<W>) -> Result<(), Box<dyn Error>> {
+        let names = std::mem::take(&mut self.columns);
+        let columns: Vec<&str> = names.iter().map(String::as_str).collect();
+        match operation {
+            Operation::Dedupe { .. } => {
+                let kept = self.runs.first().map_or(0, |run| run.rows);
+                writer.begin_table_with_len(&self.name, &columns, kept)?;
+                if let Some(run) = self.runs.first_mut() {
+                    for row in run.read()? {
+                        writer.write_row(&row?)?;
+                    }
+                }
+                eprintln!("[DEDUPE] {}: kept {} rows, dropped {} duplicates", self.name, kept, self.dropped);
+            }
+            Operation::Sort { descending, .. } if self.runs.is_empty() => {
+                self.sort_buffer(*descending);
+                writer.begin_table_with_len(&self.name, &columns, self.buffer.len())?;
+                for row in &self.buffer {
+                    writer.write_row(row)?;
+                }
+                eprintln!("[SORT] {}: {} rows sorted in memory", self.name, self.buffer.len());
+            }
+            Operation::Sort { descending, .. } => {
+                if !self.buffer.is_empty() {
+                    self.spill_run(*descending)?;
+                }
+                let total = self.runs.iter().map(|run| run.rows).sum();
+                writer.begin_table_with_len(&self.name, &columns, total)?;
+
+                // k-way merge; ties go to the earlier run so the sort stays stable
+                let mut readers = Vec::with_capacity(self.runs.len());
+                let mut heap = BinaryHeap::new();
+                for (index, run) in self.runs.iter_mut().enumerate() {
+                    let mut reader = run.read()?;
+                    if let Some(row) = reader.next() {
+                        heap.push(Head::new(row?, index, &self.keys, *descending));
+                    }
+                    readers.push(reader);
+                }
+                while let Some(head) = heap.pop() {
+                    writer.write_row(&head.row)?;
+                    if let Some(row) = readers[head.run].next() {
+                        heap.push(Head::new(row?, head.run, &self.keys, *descending));
+                    }
+                }
+                eprintln!("[SORT] {}: {} rows merged from {} runs", self.name, total, self.runs.len());
+            }
+        }
+        Ok(())
+    }
+}
+
+// Order used by sort: null < bool < number < string < array < object
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a.as_f64().partial_cmp(&b.as_f64()).unwrap_or(Ordering::Equal),
+        },
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(_), Value::Array(_)) | (Value::Object(_), Value::Object(_)) => a.to_string().cmp(&b.to_string()),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn compare_keys<'a>(a: impl Iterator<Item = &'a Value>, b: impl Iterator<Item = &'a Value>, descending: bool) -> Ordering {
+    for (a, b) in a.zip(b) {
+        let ordering = compare_values(a, b);
+        if ordering != Ordering::Equal {
+            return if descending { ordering.reverse() } else { ordering };
+        }
+    }
+    Ordering::Equal
+}
+
+// The next row of one sorted run; BinaryHeap pops the greatest, so the order is reversed
+struct Head {
+    key: Vec<Value>,
+    row: Vec<Value>,
+    run: usize,
+    descending: bool,
+}
+
+impl Head {
+    fn new(row: Vec<Value>, run: usize, keys: &[usize], descending: bool) -> Self {
+        let key = keys.iter().map(|&index| row[index].clone()).collect();
+        Self { key, row, run, descending }
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_keys(self.key.iter(), other.key.iter(), self.descending)
+            .then(self.run.cmp(&other.run))
+            .reverse()
+    }
+}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+static SPILLS: AtomicUsize = AtomicUsize::new(0);
+
+// Rows as JSON lines in a temp file, removed on drop
+struct Spill {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    rows: usize,
+}
+
+impl Spill {
+    fn new() -> io::Result<Self> {
+        let id = SPILLS.fetch_add(1, AtomicOrdering::Relaxed);
+        let path = std::env::temp_dir().join(format!("toonify-rows-{}-{}.jsonl", std::process::id(), id));
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self { path, writer: Some(writer), rows: 0 })
+    }
+
+    fn push(&mut self, row: &[Value]) -> Result<(), Box<dyn Error>> {
+        let writer = self.writer.as_mut().ok_or("spill file already closed")?;
+        serde_json::to_writer(&mut *writer, row)?;
+        writer.write_all(b"\n")?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn read(&mut self) -> io::Result<impl Iterator<Item = Result<Vec<Value>, Box<dyn Error>>> + use<>> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+        let lines = BufReader::new(File::open(&self.path)?).lines();
+        Ok(lines.map(|line| -> Result<Vec<Value>, Box<dyn Error>> { Ok(serde_json::from_str(&line?)?) }))
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        self.writer.take();
+        let _ = fs::remove_file(&self.path);
+    }
+}
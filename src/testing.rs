@@ -0,0 +1,96 @@
+// Test helpers for crates that embed the converter (feature `testing`)
+//
+//     #[test]
+//     fn users_stay_tabular() {
+//         toonify::testing::assert_toon_roundtrip(r#"{"users":[{"id":1}]}"#);
+//         toonify::assert_toon_snapshot!("users", r#"{"users":[{"id":1}]}"#);
+//     }
+//
+// Snapshots live in `tests/snapshots/<name>.toon` under the calling crate.
+// A missing snapshot is written on first run (and fails when `CI` is set);
+// set `TOONIFY_UPDATE_SNAPSHOTS=1` to accept changed output.
+
+use std::fs;
+use std::path::Path;
+use serde_json::Value;
+
+use crate::converter;
+use crate::toon::format::diff_lines;
+
+/// Panic unless `json` survives JSON → TOON → JSON with an equal value
+#[track_caller]
+pub fn assert_toon_roundtrip(json: &str) {
+    let original: Value = serde_json::from_str(json)
+        .unwrap_or_else(|e| panic!("assert_toon_roundtrip: input is not valid JSON: {}", e));
+    let toon = converter::json_value_to_toon(&original)
+        .unwrap_or_else(|e| panic!("assert_toon_roundtrip: JSON → TOON failed: {}", e));
+    let roundtripped = converter::toon_to_value(&toon)
+        .unwrap_or_else(|e| panic!("assert_toon_roundtrip: TOON → JSON failed: {}\nTOON:\n{}", e, toon));
+
+    if roundtripped != original {
+        let expected = serde_json::to_string_pretty(&original).unwrap_or_default();
+        let back = serde_json::to_string_pretty(&roundtripped).unwrap_or_default();
+        panic!(
+            "assert_toon_roundtrip: round trip changed the document\nTOON:\n{}\n\nJSON diff (- input, + round trip):\n{}",
+            toon,
+            diff_lines(&expected, &back)
+        );
+    }
+}
+
+/// Convert JSON to the TOON text stored in snapshots
+#[track_caller]
+pub fn toon_snapshot(json: &str) -> String {
+    converter::json_to_toon(json)
+        .unwrap_or_else(|e| panic!("assert_toon_snapshot: JSON → TOON failed: {}", e))
+}
+
+/// Compare `actual` with the snapshot at `path`, creating or updating it as configured
+#[track_caller]
+pub fn assert_snapshot_matches(path: &Path, actual: &str) {
+    let update = std::env::var_os("TOONIFY_UPDATE_SNAPSHOTS").is_some_and(|v| v != "0");
+
+    match fs::read_to_string(path) {
+        Ok(expected) if expected.trim_end() == actual.trim_end() => {}
+        Ok(_) | Err(_) if update => write_snapshot(path, actual),
+        Ok(expected) => panic!(
+            "assert_toon_snapshot: {} does not match (- snapshot, + actual)\n{}\nRun with TOONIFY_UPDATE_SNAPSHOTS=1 to accept.",
+            path.display(),
+            diff_lines(&expected, actual)
+        ),
+        Err(_) if std::env::var_os("CI").is_some() => panic!(
+            "assert_toon_snapshot: missing snapshot {} (not created because CI is set)",
+            path.display()
+        ),
+        Err(_) => {
+            write_snapshot(path, actual);
+            eprintln!("[SNAPSHOT] Created {}", path.display());
+        }
+    }
+}
+
+#[track_caller]
+fn write_snapshot(path: &Path, actual: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .unwrap_or_else(|e| panic!("assert_toon_snapshot: cannot create {}: {}", parent.display(), e));
+    }
+    let mut content = actual.trim_end().to_string();
+    content.push('\n');
+    fs::write(path, content)
+        .unwrap_or_else(|e| panic!("assert_toon_snapshot: cannot write {}: {}", path.display(), e));
+}
+
+/// Assert that JSON converts to the TOON stored in `tests/snapshots/<name>.toon`
+#[macro_export]
+macro_rules! assert_toon_snapshot {
+    ($name:expr, $json:expr $(,)?) => {
+        $crate::testing::assert_snapshot_matches(
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join(format!("{}.toon", $name)),
+            &$crate::testing::toon_snapshot($json),
+        )
+    };
+}
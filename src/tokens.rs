@@ -0,0 +1,106 @@
+// Token counting for prompt-size estimates
+//
+// `estimate` needs no vocabulary: it splits text roughly the way GPT-style
+// pre-tokenizers do (words with their leading space, digits in threes,
+// punctuation one by one) and charges long words a token per five letters.
+// It follows real counts closely enough to compare two spellings of the same
+// document. The `tiktoken` feature adds the exact cl100k and o200k
+// encodings, loaded once on first use.
+
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tokenizer {
+    #[default]
+    Estimate,
+    /// GPT-4 / GPT-3.5 encoding
+    Cl100k,
+    /// GPT-4o encoding
+    O200k,
+}
+
+impl Tokenizer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tokenizer::Estimate => "estimate",
+            Tokenizer::Cl100k => "cl100k",
+            Tokenizer::O200k => "o200k",
+        }
+    }
+
+    /// Tokens in `text`
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            Tokenizer::Estimate => estimate(text),
+            #[cfg(feature = "tiktoken")]
+            Tokenizer::Cl100k => bpe::cl100k().encode_ordinary(text).len(),
+            #[cfg(feature = "tiktoken")]
+            Tokenizer::O200k => bpe::o200k().encode_ordinary(text).len(),
+            // Rejected by `from_str` without the feature
+            #[cfg(not(feature = "tiktoken"))]
+            Tokenizer::Cl100k | Tokenizer::O200k => estimate(text),
+        }
+    }
+}
+
+impl FromStr for Tokenizer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokenizer = match s {
+            "estimate" => Tokenizer::Estimate,
+            "cl100k" | "cl100k_base" => Tokenizer::Cl100k,
+            "o200k" | "o200k_base" => Tokenizer::O200k,
+            _ => return Err(format!("Unknown tokenizer '{}' (expected estimate, cl100k, or o200k)", s)),
+        };
+        if tokenizer != Tokenizer::Estimate && !cfg!(feature = "tiktoken") {
+            return Err(format!("Tokenizer '{}' requires the 'tiktoken' feature", s));
+        }
+        Ok(tokenizer)
+    }
+}
+
+fn estimate(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphabetic() {
+            let mut len = 1usize;
+            while chars.next_if(|c| c.is_alphabetic()).is_some() {
+                len += 1;
+            }
+            tokens += len.div_ceil(5);
+        } else if c.is_ascii_digit() {
+            let mut len = 1usize;
+            while chars.next_if(char::is_ascii_digit).is_some() {
+                len += 1;
+            }
+            tokens += len.div_ceil(3);
+        } else if c == ' ' && chars.peek().is_some_and(|next| next.is_alphabetic()) {
+            // Joins the word after it
+        } else if c.is_whitespace() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            tokens += 1;
+        } else {
+            tokens += 1;
+        }
+    }
+    tokens
+}
+
+#[cfg(feature = "tiktoken")]
+mod bpe {
+    use std::sync::OnceLock;
+
+    use tiktoken_rs::CoreBPE;
+
+    pub fn cl100k() -> &'static CoreBPE {
+        static BPE: OnceLock<CoreBPE> = OnceLock::new();
+        BPE.get_or_init(|| tiktoken_rs::cl100k_base().expect("cl100k vocabulary is bundled"))
+    }
+
+    pub fn o200k() -> &'static CoreBPE {
+        static BPE: OnceLock<CoreBPE> = OnceLock::new();
+        BPE.get_or_init(|| tiktoken_rs::o200k_base().expect("o200k vocabulary is bundled"))
+    }
+}
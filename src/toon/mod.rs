@@ -1,5 +0,0 @@
-pub mod parser;
-pub mod serializer;
-
-pub use parser::parse_toon;
-pub use serializer::serialize_toon;
@@ -0,0 +1,386 @@
+// Interactive table browser for `toonify view`
+//
+// Each root entity becomes a tab: arrays of objects are tables, objects are
+// one-row tables, primitive lists are a single `value` column, and root
+// scalars are grouped into a key/value table. The preview pane shows the
+// selected row converted to TOON and JSON.
+
+use std::collections::HashSet;
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState, Tabs, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::{Map, Value};
+
+const MAX_COLUMN_WIDTH: usize = 40;
+const HELP: &str = "Tab entity  ↑↓ rows  ←→ columns  x hide  X show all  / search  n next  p preview  q quit";
+
+struct Entity {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+struct App {
+    entities: Vec<Entity>,
+    current: usize,
+    tables: Vec<TableState>,
+    hidden: Vec<HashSet<usize>>,
+    column: usize,
+    column_offset: usize,
+    search: Option<String>,
+    last_search: String,
+    preview: bool,
+    status: String,
+}
+
+/// Browse `value` until the user quits
+pub fn run(value: &Value, title: &str) -> io::Result<()> {
+    let entities = entities(value);
+    if entities.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Nothing to view: document has no entities"));
+    }
+
+    let mut app = App {
+        tables: entities.iter().map(|_| TableState::default().with_selected(Some(0))).collect(),
+        hidden: entities.iter().map(|_| HashSet::new()).collect(),
+        entities,
+        current: 0,
+        column: 0,
+        column_offset: 0,
+        search: None,
+        last_search: String::new(),
+        preview: false,
+        status: format!("{} — {}", title, HELP),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| render(frame, app))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if let Some(query) = app.search.as_mut() {
+            match key.code {
+                KeyCode::Esc => app.search = None,
+                KeyCode::Enter => {
+                    app.last_search = query.to_lowercase();
+                    app.search = None;
+                    app.find_next();
+                }
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Char(c) => query.push(c),
+                _ => {}
+            }
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Tab => app.switch_entity(1),
+            KeyCode::BackTab => app.switch_entity(app.entities.len() - 1),
+            KeyCode::Down | KeyCode::Char('j') => app.move_rows(1),
+            KeyCode::Up | KeyCode::Char('k') => app.move_rows(-1),
+            KeyCode::PageDown => app.move_rows(20),
+            KeyCode::PageUp => app.move_rows(-20),
+            KeyCode::Home | KeyCode::Char('g') => app.move_rows(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => app.move_rows(isize::MAX),
+            KeyCode::Right | KeyCode::Char('l') => app.move_column(true),
+            KeyCode::Left | KeyCode::Char('h') => app.move_column(false),
+            KeyCode::Char('x') => app.hide_column(),
+            KeyCode::Char('X') => {
+                app.hidden[app.current].clear();
+                app.status = "All columns shown".to_string();
+            }
+            KeyCode::Char('/') => app.search = Some(String::new()),
+            KeyCode::Char('n') => app.find_next(),
+            KeyCode::Char('p') => app.preview = !app.preview,
+            _ => {}
+        }
+    }
+}
+
+impl App {
+    fn entity(&self) -> &Entity {
+        &self.entities[self.current]
+    }
+
+    fn visible_columns(&self) -> Vec<usize> {
+        (0..self.entity().columns.len())
+            .filter(|i| !self.hidden[self.current].contains(i))
+            .collect()
+    }
+
+    fn selected_row(&self) -> usize {
+        self.tables[self.current].selected().unwrap_or(0)
+    }
+
+    fn switch_entity(&mut self, step: usize) {
+        self.current = (self.current + step) % self.entities.len();
+        self.column = 0;
+        self.column_offset = 0;
+    }
+
+    fn move_rows(&mut self, delta: isize) {
+        let last = self.entity().rows.len().saturating_sub(1);
+        let row = self.selected_row().saturating_add_signed(delta).min(last);
+        self.tables[self.current].select(Some(row));
+    }
+
+    fn move_column(&mut self, forward: bool) {
+        let count = self.visible_columns().len();
+        if forward && self.column + 1 < count {
+            self.column += 1;
+        } else if !forward && self.column > 0 {
+            self.column -= 1;
+        }
+    }
+
+    fn hide_column(&mut self) {
+        let visible = self.visible_columns();
+        if visible.len() <= 1 {
+            self.status = "Cannot hide the last visible column".to_string();
+            return;
+        }
+        if let Some(&index) = visible.get(self.column) {
+            self.hidden[self.current].insert(index);
+            self.status = format!("Hid column '{}' (X to show all)", self.entity().columns[index]);
+            self.column = self.column.min(visible.len() - 2);
+        }
+    }
+
+    // Next row (wrapping) whose visible cells contain the last search term
+    fn find_next(&mut self) {
+        if self.last_search.is_empty() {
+            return;
+        }
+
+        let visible = self.visible_columns();
+        let rows = &self.entity().rows;
+        let start = self.selected_row();
+        let found = (1..=rows.len())
+            .map(|step| (start + step) % rows.len())
+            .find(|&row| visible.iter().any(|&col| cell_text(&rows[row][col]).to_lowercase().contains(&self.last_search)));
+
+        match found {
+            Some(row) => {
+                self.tables[self.current].select(Some(row));
+                self.status = format!("Match for '{}' at row {}", self.last_search, row + 1);
+            }
+            None => self.status = format!("No match for '{}'", self.last_search),
+        }
+    }
+}
+
+fn render(frame: &mut Frame, app: &mut App) {
+    let [tabs_area, main_area, status_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let titles: Vec<String> = app.entities.iter()
+        .map(|entity| format!("{} ({})", entity.name, entity.rows.len()))
+        .collect();
+    frame.render_widget(
+        Tabs::new(titles)
+            .select(app.current)
+            .block(Block::bordered().title(" toonify view "))
+            .highlight_style(Style::new().bold().reversed()),
+        tabs_area,
+    );
+
+    let (table_area, preview_area) = if app.preview {
+        let [table, preview] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main_area);
+        (table, Some(preview))
+    } else {
+        (main_area, None)
+    };
+
+    render_table(frame, app, table_area);
+    if let Some(area) = preview_area {
+        render_preview(frame, app, area);
+    }
+
+    let status = match &app.search {
+        Some(query) => format!("/{}", query),
+        None => app.status.clone(),
+    };
+    frame.render_widget(Paragraph::new(status).style(Style::new().dim()), status_area);
+}
+
+fn render_table(frame: &mut Frame, app: &mut App, area: Rect) {
+    let visible = app.visible_columns();
+    let entity = &app.entities[app.current];
+
+    let widths: Vec<usize> = visible.iter()
+        .map(|&col| {
+            let header = entity.columns[col].chars().count();
+            let cells = entity.rows.iter().take(500).map(|row| cell_text(&row[col]).chars().count());
+            cells.fold(header, usize::max).min(MAX_COLUMN_WIDTH) + 1
+        })
+        .collect();
+
+    // Scroll horizontally so the focused column stays on screen
+    if app.column < app.column_offset {
+        app.column_offset = app.column;
+    }
+    let inner_width = area.width.saturating_sub(2) as usize;
+    while app.column_offset < app.column
+        && widths[app.column_offset..=app.column].iter().sum::<usize>() > inner_width
+    {
+        app.column_offset += 1;
+    }
+
+    let shown = &visible[app.column_offset..];
+    let header = Row::new(shown.iter().enumerate().map(|(i, &col)| {
+        let style = if app.column_offset + i == app.column {
+            Style::new().bold().reversed()
+        } else {
+            Style::new().bold()
+        };
+        Cell::from(entity.columns[col].clone()).style(style)
+    }));
+    let rows = entity.rows.iter().map(|row| {
+        Row::new(shown.iter().map(|&col| Cell::from(truncate(&cell_text(&row[col])))))
+    });
+    let constraints = widths[app.column_offset..].iter().map(|&w| Constraint::Length(w as u16));
+
+    let hidden = app.hidden[app.current].len();
+    let title = if hidden > 0 {
+        format!(" {} — {} hidden ", entity.name, hidden)
+    } else {
+        format!(" {} ", entity.name)
+    };
+
+    let table = Table::new(rows, constraints)
+        .header(header)
+        .block(Block::bordered().title(title))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, &mut app.tables[app.current]);
+}
+
+fn render_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let entity = app.entity();
+    let Some(row) = entity.rows.get(app.selected_row()) else {
+        return;
+    };
+
+    let object: Map<String, Value> = entity.columns.iter().cloned().zip(row.iter().cloned()).collect();
+    let mut root = Map::new();
+    root.insert(entity.name.clone(), Value::Array(vec![Value::Object(object.clone())]));
+
+    let toon = toonify::toon::serialize_toon(&Value::Object(root)).unwrap_or_else(|e| e);
+    let json = serde_json::to_string_pretty(&object).unwrap_or_default();
+
+    let mut lines = vec![Line::from("TOON").bold()];
+    lines.extend(toon.lines().map(|line| Line::from(line.to_string())));
+    lines.push(Line::from(""));
+    lines.push(Line::from("JSON").bold());
+    lines.extend(json.lines().map(|line| Line::from(line.to_string())));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(format!(" Row {} ", app.selected_row() + 1))),
+        area,
+    );
+}
+
+fn entities(value: &Value) -> Vec<Entity> {
+    let Value::Object(map) = value else {
+        return vec![entity_from("root", value)];
+    };
+
+    let mut entities = Vec::new();
+    let mut scalars = Vec::new();
+    for (name, value) in map {
+        match value {
+            Value::Array(_) | Value::Object(_) => entities.push(entity_from(name, value)),
+            scalar => scalars.push(vec![Value::String(name.clone()), scalar.clone()]),
+        }
+    }
+
+    if !scalars.is_empty() {
+        entities.push(Entity {
+            name: "(scalars)".to_string(),
+            columns: vec!["key".to_string(), "value".to_string()],
+            rows: scalars,
+        });
+    }
+    entities
+}
+
+fn entity_from(name: &str, value: &Value) -> Entity {
+    let objects: Vec<&Map<String, Value>> = match value {
+        Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+            items.iter().filter_map(Value::as_object).collect()
+        }
+        Value::Object(map) => vec![map],
+        Value::Array(items) => {
+            return Entity {
+                name: name.to_string(),
+                columns: vec!["value".to_string()],
+                rows: items.iter().map(|item| vec![item.clone()]).collect(),
+            };
+        }
+        scalar => {
+            return Entity {
+                name: name.to_string(),
+                columns: vec!["value".to_string()],
+                rows: vec![vec![scalar.clone()]],
+            };
+        }
+    };
+
+    let mut columns: Vec<String> = Vec::new();
+    for object in &objects {
+        for key in object.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    let rows = objects.iter()
+        .map(|object| columns.iter().map(|col| object.get(col).cloned().unwrap_or(Value::Null)).collect())
+        .collect();
+
+    Entity { name: name.to_string(), columns, rows }
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_COLUMN_WIDTH {
+        text.to_string()
+    } else {
+        let mut cut: String = text.chars().take(MAX_COLUMN_WIDTH - 1).collect();
+        cut.push('…');
+        cut
+    }
+}
@@ -0,0 +1,275 @@
+// Declared field types applied during conversion (`--types types.yaml`)
+//
+//     zip: string
+//     users.created: datetime
+//     score: float
+//
+// TOON and CSV cells are untyped text, and `parse_value` guesses: `02134`
+// becomes the number 2134 and the leading zero is gone for good. A rules
+// file names the type a field should have instead. For TOON and CSV input
+// the declared type is used while reading the cells, so `02134` is kept as
+// written; for every format the parsed values are then converted, at any
+// depth: numbers to strings, numeric strings to numbers, and epoch seconds
+// (or milliseconds, once that large) to ISO 8601 dates. `entity.field`
+// applies to one root entity and wins over a bare `field`. Nulls stay null,
+// and a value that can't be converted fails the conversion rather than
+// passing through unchanged.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::{Map, Number, Value};
+
+use crate::toon::ColumnType;
+
+// Epoch values at least this large are milliseconds; in seconds they would be past the year 5000
+const EPOCH_MILLIS_FROM: f64 = 1e11;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetType {
+    String,
+    Int,
+    Float,
+    Bool,
+    /// `YYYY-MM-DD`
+    Date,
+    /// `YYYY-MM-DDTHH:MM:SSZ`, with milliseconds when there are any
+    DateTime,
+}
+
+impl TargetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetType::String => "string",
+            TargetType::Int => "int",
+            TargetType::Float => "float",
+            TargetType::Bool => "bool",
+            TargetType::Date => "date",
+            TargetType::DateTime => "datetime",
+        }
+    }
+
+    // How a TOON or CSV cell is read before conversion; dates start out as numbers or strings
+    fn column_type(&self) -> Option<ColumnType> {
+        match self {
+            TargetType::String => Some(ColumnType::Str),
+            TargetType::Int => Some(ColumnType::Int),
+            TargetType::Float => Some(ColumnType::Float),
+            TargetType::Bool => Some(ColumnType::Bool),
+            TargetType::Date | TargetType::DateTime => None,
+        }
+    }
+
+    /// `value` as this type, or why it can't be
+    pub fn convert(&self, value: &Value) -> Result<Value, String> {
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        if value.is_array() || value.is_object() {
+            return Err("not a scalar".to_string());
+        }
+        let text = value.as_str().map(str::trim);
+        let converted = match self {
+            TargetType::String => match value {
+                Value::String(s) => Some(Value::String(s.clone())),
+                other => Some(Value::String(other.to_string())),
+            },
+            TargetType::Int => match (value, text) {
+                (Value::Number(n), _) if n.is_i64() || n.is_u64() => Some(value.clone()),
+                (Value::Number(n), _) => n.as_f64().filter(|f| f.fract() == 0.0 && f.abs() < 9.0e15).map(|f| Value::from(f as i64)),
+                (_, Some(s)) => s.parse::<i64>().ok().map(Value::from),
+                _ => None,
+            },
+            TargetType::Float => match (value, text) {
+                (Value::Number(n), _) => n.as_f64().and_then(Number::from_f64).map(Value::Number),
+                (_, Some(s)) => s.parse::<f64>().ok().and_then(Number::from_f64).map(Value::Number),
+                _ => None,
+            },
+            TargetType::Bool => match (value, text) {
+                (Value::Bool(_), _) => Some(value.clone()),
+                (Value::Number(n), _) => match n.as_u64() {
+                    Some(0) => Some(Value::Bool(false)),
+                    Some(1) => Some(Value::Bool(true)),
+                    _ => None,
+                },
+                (_, Some(s)) => match s.to_ascii_lowercase().as_str() {
+                    "true" | "1" => Some(Value::Bool(true)),
+                    "false" | "0" => Some(Value::Bool(false)),
+                    _ => None,
+                },
+                _ => None,
+            },
+            TargetType::Date | TargetType::DateTime => {
+                let epoch = match (value, text) {
+                    (Value::Number(n), _) => n.as_f64(),
+                    (_, Some(s)) => s.parse::<f64>().ok(),
+                    _ => None,
+                };
+                match (epoch, text) {
+                    (Some(epoch), _) => Some(Value::String(format_epoch(epoch, *self == TargetType::Date))),
+                    // Already a date; a datetime asked for as a date keeps its date part
+                    (None, Some(s)) if is_iso_date(s) => Some(Value::String(match self {
+                        TargetType::Date => s[..10].to_string(),
+                        _ => s.to_string(),
+                    })),
+                    _ => None,
+                }
+            }
+        };
+        converted.ok_or_else(|| format!("not a valid {}", self.as_str()))
+    }
+}
+
+impl FromStr for TargetType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "string" | "str" => Ok(TargetType::String),
+            "int" | "integer" => Ok(TargetType::Int),
+            "float" | "number" => Ok(TargetType::Float),
+            "bool" | "boolean" => Ok(TargetType::Bool),
+            "date" => Ok(TargetType::Date),
+            "datetime" | "timestamp" => Ok(TargetType::DateTime),
+            _ => Err(format!("Unknown type '{}' (expected string, int, float, bool, date, or datetime)", s)),
+        }
+    }
+}
+
+/// Field names (or `entity.field`) and the type each should have
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeRules {
+    rules: HashMap<String, TargetType>,
+}
+
+impl TypeRules {
+    pub fn new(rules: HashMap<String, TargetType>) -> Self {
+        Self { rules }
+    }
+
+    /// Rules from a flat mapping of field to type name, in any format the registry reads
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        let Value::Object(map) = value else {
+            return Err("Type rules must be a mapping of field to type".to_string());
+        };
+        let mut rules = HashMap::new();
+        for (field, ty) in map {
+            let ty = ty.as_str().ok_or_else(|| format!("Type for '{}' must be a type name", field))?;
+            rules.insert(field.clone(), ty.parse().map_err(|e| format!("Field '{}': {}", field, e))?);
+        }
+        Ok(Self { rules })
+    }
+
+    /// Read a rules file; its extension decides the format (types.yaml, types.json, ...)
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        let format = crate::converter::detect_format(Some(path), content.as_bytes())?;
+        let value = crate::converter::registry().parse(&content, format)?;
+        Self::from_value(&value).map_err(|e| format!("{:?}: {}", path, e))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Types to read TOON and CSV cells with, keyed like the rules
+    pub fn column_types(&self) -> HashMap<String, ColumnType> {
+        self.rules.iter()
+            .filter_map(|(field, ty)| Some((field.clone(), ty.column_type()?)))
+            .collect()
+    }
+
+    /// Convert every field with a rule, anywhere in `value`
+    pub fn apply(&self, value: Value) -> Result<Value, String> {
+        if self.rules.is_empty() {
+            return Ok(value);
+        }
+        match value {
+            Value::Object(map) => {
+                let mut document = Map::new();
+                for (entity, value) in map {
+                    let converted = self.walk(value, &entity, &entity)?;
+                    document.insert(entity, converted);
+                }
+                Ok(Value::Object(document))
+            }
+            other => self.walk(other, "", "$"),
+        }
+    }
+
+    /// The type declared for `field` of `entity`, if any
+    pub fn rule(&self, entity: &str, field: &str) -> Option<TargetType> {
+        self.rules.get(&format!("{}.{}", entity, field))
+            .or_else(|| self.rules.get(field))
+            .copied()
+    }
+
+    fn walk(&self, value: Value, entity: &str, path: &str) -> Result<Value, String> {
+        match value {
+            Value::Array(items) => items.into_iter()
+                .enumerate()
+                .map(|(index, item)| self.walk(item, entity, &format!("{}[{}]", path, index)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            Value::Object(fields) => {
+                let mut converted = Map::new();
+                for (field, value) in fields {
+                    let path = format!("{}.{}", path, field);
+                    let value = match self.rule(entity, &field) {
+                        Some(ty) => ty.convert(&value)
+                            .map_err(|e| format!("Cannot convert {} ({}) to {}: {}", path, value, ty.as_str(), e))?,
+                        None => self.walk(value, entity, &path)?,
+                    };
+                    converted.insert(field, value);
+                }
+                Ok(Value::Object(converted))
+            }
+            other => Ok(other),
+        }
+    }
+}
+
+// `YYYY-MM-DD`, optionally followed by a time
+fn is_iso_date(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.len() >= 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && [0, 1, 2, 3, 5, 6, 8, 9].iter().all(|&i| bytes[i].is_ascii_digit())
+        && (bytes.len() == 10 || matches!(bytes[10], b'T' | b' '))
+}
+
+/// Epoch seconds (or milliseconds) as an ISO 8601 UTC date or datetime
+pub fn format_epoch(epoch: f64, date_only: bool) -> String {
+    let millis = if epoch.abs() >= EPOCH_MILLIS_FROM { epoch } else { epoch * 1000.0 }.round() as i64;
+    let (days, day_millis) = (millis.div_euclid(86_400_000), millis.rem_euclid(86_400_000));
+    let (year, month, day) = civil_from_days(days);
+    if date_only {
+        return format!("{:04}-{:02}-{:02}", year, month, day);
+    }
+    let secs = day_millis / 1000;
+    let time = format!("{:02}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60);
+    match day_millis % 1000 {
+        0 => format!("{:04}-{:02}-{:02}T{}Z", year, month, day, time),
+        ms => format!("{:04}-{:02}-{:02}T{}.{:03}Z", year, month, day, time, ms),
+    }
+}
+
+// Civil date from days since 1970-01-01 (Howard Hinnant's algorithm), also
+// used for audit log timestamps
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
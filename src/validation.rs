@@ -0,0 +1,335 @@
+// Schema validation shared by `toonify validate`, `toonify report`, the daemon,
+// the MCP server and the server's schema registry
+//
+// A schema maps entity names to rules for the array under that key in the
+// document: `{"users": {"type": "array", "fields": ["id", "name"], ...}}`
+// with optional `min_items`/`max_items`, `field_types`, `patterns`,
+// `ranges`, `string_lengths`, `enums` and `formats` (email, url, date, uuid).
+// Validation stops at the first problem and describes it.
+
+use regex::Regex;
+
+/// Reject a schema `validate_value` could not apply, before it is stored
+pub fn check_schema(schema: &serde_json::Value) -> Result<(), String> {
+    let schema_obj = schema.as_object().ok_or("Schema must be a JSON object".to_string())?;
+    if schema_obj.is_empty() {
+        return Err("Schema names no entities".to_string());
+    }
+    for (name, entity) in schema_obj {
+        let entity = entity.as_object().ok_or(format!("Schema for '{}' must be an object", name))?;
+        match entity.get("type").and_then(|v| v.as_str()) {
+            Some("array") => {}
+            Some(other) => return Err(format!("Unsupported entity type: {}", other)),
+            None => return Err(format!("Schema for '{}' must have 'type' field", name)),
+        }
+        if !entity.get("fields").is_some_and(|fields| fields.is_array()) {
+            return Err(format!("Schema for '{}' must have 'fields' array", name));
+        }
+        for pattern in entity.get("patterns").and_then(|v| v.as_object()).into_iter().flat_map(|patterns| patterns.values()) {
+            let pattern = pattern.as_str().ok_or(format!("Patterns for '{}' must be strings", name))?;
+            Regex::new(pattern).map_err(|e| format!("Invalid regex pattern '{}': {}", pattern, e))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_value(value: &serde_json::Value, schema: &serde_json::Value) -> Result<(), String> {
+    let schema_obj = schema.as_object()
+        .ok_or("Schema must be a JSON object".to_string())?;
+    
+    let value_obj = value.as_object()
+        .ok_or("TOON data must represent an object".to_string())?;
+    
+    // Validate each entity in schema
+    for (entity_name, entity_schema) in schema_obj {
+        if !value_obj.contains_key(entity_name) {
+            return Err(format!("Missing entity '{}' in TOON data", entity_name));
+        }
+        
+        let entity_value = &value_obj[entity_name];
+        validate_entity(entity_name, entity_value, entity_schema)?;
+    }
+    
+    Ok(())
+}
+
+fn validate_entity(name: &str, value: &serde_json::Value, schema: &serde_json::Value) -> Result<(), String> {
+    let schema_obj = schema.as_object()
+        .ok_or(format!("Schema for '{}' must be an object", name))?;
+    
+    // Check type
+    let entity_type = schema_obj.get("type")
+        .and_then(|v| v.as_str())
+        .ok_or(format!("Schema for '{}' must have 'type' field", name))?;
+
+    match entity_type {
+        "array" => {
+            let array = value.as_array()
+                .ok_or(format!("Entity '{}' must be an array", name))?;
+
+            // Check min/max items
+            if let Some(min_items) = schema_obj.get("min_items").and_then(|v| v.as_u64())
+                && (array.len() as u64) < min_items
+            {
+                return Err(format!(
+                    "Entity '{}' has {} items but minimum is {}",
+                    name, array.len(), min_items
+                ));
+            }
+            
+            if let Some(max_items) = schema_obj.get("max_items").and_then(|v| v.as_u64())
+                && (array.len() as u64) > max_items
+            {
+                return Err(format!(
+                    "Entity '{}' has {} items but maximum is {}",
+                    name, array.len(), max_items
+                ));
+            }
+            
+            // Get required fields
+            let required_fields = schema_obj.get("fields")
+                .and_then(|v| v.as_array())
+                .ok_or(format!("Schema for '{}' must have 'fields' array", name))?;
+            
+            let required_field_names: Vec<String> = required_fields
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect();
+
+            // Get field types if specified
+            let field_types = schema_obj.get("field_types")
+                .and_then(|v| v.as_object());
+            
+            // Get advanced validation constraints
+            let patterns = schema_obj.get("patterns")
+                .and_then(|v| v.as_object());
+            let ranges = schema_obj.get("ranges")
+                .and_then(|v| v.as_object());
+            let string_lengths = schema_obj.get("string_lengths")
+                .and_then(|v| v.as_object());
+            let enums = schema_obj.get("enums")
+                .and_then(|v| v.as_object());
+            let formats = schema_obj.get("formats")
+                .and_then(|v| v.as_object());
+            
+            // Validate each item in array
+            for (idx, item) in array.iter().enumerate() {
+                let item_obj = item.as_object()
+                    .ok_or(format!("Item {} in '{}' must be an object", idx, name))?;
+                
+                // Check all required fields are present
+                for field_name in &required_field_names {
+                    if !item_obj.contains_key(field_name) {
+                        return Err(format!(
+                            "Item {} in '{}' is missing required field '{}'",
+                            idx, name, field_name
+                        ));
+                    }
+                    
+                    let field_value = &item_obj[field_name];
+                    
+                    // Check field type if specified
+                    if let Some(types) = field_types
+                        && let Some(expected_type) = types.get(field_name).and_then(|v| v.as_str())
+                    {
+                        validate_field_type(name, idx, field_name, field_value, expected_type)?;
+                    }
+                    
+                    // Check regex pattern if specified
+                    if let Some(patterns_map) = patterns
+                        && let Some(pattern_str) = patterns_map.get(field_name).and_then(|v| v.as_str())
+                    {
+                        validate_pattern(name, idx, field_name, field_value, pattern_str)?;
+                    }
+                    
+                    // Check number range if specified
+                    if let Some(ranges_map) = ranges
+                        && let Some(range_obj) = ranges_map.get(field_name).and_then(|v| v.as_object())
+                    {
+                        validate_range(name, idx, field_name, field_value, range_obj)?;
+                    }
+                    
+                    // Check string length if specified
+                    if let Some(lengths_map) = string_lengths
+                        && let Some(length_obj) = lengths_map.get(field_name).and_then(|v| v.as_object())
+                    {
+                        validate_string_length(name, idx, field_name, field_value, length_obj)?;
+                    }
+                    
+                    // Check enum values if specified
+                    if let Some(enums_map) = enums
+                        && let Some(allowed_values) = enums_map.get(field_name).and_then(|v| v.as_array())
+                    {
+                        validate_enum(name, idx, field_name, field_value, allowed_values)?;
+                    }
+                    
+                    // Check custom format if specified
+                    if let Some(formats_map) = formats
+                        && let Some(format_type) = formats_map.get(field_name).and_then(|v| v.as_str())
+                    {
+                        validate_format(name, idx, field_name, field_value, format_type)?;
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(format!("Unsupported entity type: {}", entity_type));
+        }
+    }
+    
+    Ok(())
+}
+
+fn validate_field_type(entity: &str, idx: usize, field: &str, value: &serde_json::Value, expected_type: &str) -> Result<(), String> {
+    let matches = match expected_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => return Err(format!("Unknown type: {}", expected_type)),
+    };
+    
+    if !matches {
+        return Err(format!(
+            "Item {} in '{}': field '{}' has wrong type (expected {}, got {})",
+            idx,
+            entity,
+            field,
+            expected_type,
+            if value.is_string() { "string" }
+            else if value.is_number() { "number" }
+            else if value.is_boolean() { "boolean" }
+            else if value.is_null() { "null" }
+            else { "unknown" }
+        ));
+    }
+    
+    Ok(())
+}
+
+fn validate_pattern(entity: &str, idx: usize, field: &str, value: &serde_json::Value, pattern_str: &str) -> Result<(), String> {
+    let string_value = value.as_str()
+        .ok_or(format!("Item {} in '{}': field '{}' must be a string for pattern matching", idx, entity, field))?;
+
+    let regex = Regex::new(pattern_str)
+        .map_err(|e| format!("Invalid regex pattern '{}': {}", pattern_str, e))?;
+    
+    if !regex.is_match(string_value) {
+        return Err(format!(
+            "Item {} in '{}': field '{}' value '{}' does not match pattern '{}'",
+            idx, entity, field, string_value, pattern_str
+        ));
+    }
+    
+    Ok(())
+}
+
+fn validate_range(entity: &str, idx: usize, field: &str, value: &serde_json::Value, range_obj: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+    let num_value = value.as_f64()
+        .ok_or(format!("Item {} in '{}': field '{}' must be a number for range validation", idx, entity, field))?;
+
+    if let Some(min_val) = range_obj.get("min").and_then(|v| v.as_f64())
+        && num_value < min_val
+    {
+        return Err(format!(
+            "Item {} in '{}': field '{}' value {} is below minimum {}",
+            idx, entity, field, num_value, min_val
+        ));
+    }
+    
+    if let Some(max_val) = range_obj.get("max").and_then(|v| v.as_f64())
+        && num_value > max_val
+    {
+        return Err(format!(
+            "Item {} in '{}': field '{}' value {} exceeds maximum {}",
+            idx, entity, field, num_value, max_val
+        ));
+    }
+    
+    Ok(())
+}
+
+fn validate_string_length(entity: &str, idx: usize, field: &str, value: &serde_json::Value, length_obj: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+    let string_value = value.as_str()
+        .ok_or(format!("Item {} in '{}': field '{}' must be a string for length validation", idx, entity, field))?;
+    
+    let length = string_value.len();
+    
+    if let Some(min_len) = length_obj.get("min").and_then(|v| v.as_u64())
+        && (length as u64) < min_len
+    {
+        return Err(format!(
+            "Item {} in '{}': field '{}' length {} is below minimum {}",
+            idx, entity, field, length, min_len
+        ));
+    }
+    
+    if let Some(max_len) = length_obj.get("max").and_then(|v| v.as_u64())
+        && (length as u64) > max_len
+    {
+        return Err(format!(
+            "Item {} in '{}': field '{}' length {} exceeds maximum {}",
+            idx, entity, field, length, max_len
+        ));
+    }
+    
+    Ok(())
+}
+
+fn validate_enum(entity: &str, idx: usize, field: &str, value: &serde_json::Value, allowed_values: &[serde_json::Value]) -> Result<(), String> {
+    let is_allowed = allowed_values.iter().any(|allowed| allowed == value);
+    
+    if !is_allowed {
+        let allowed_strs: Vec<String> = allowed_values
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| format!("'{}'", s)))
+            .collect();
+        
+        return Err(format!(
+            "Item {} in '{}': field '{}' value {:?} is not one of allowed values: [{}]",
+            idx, entity, field, value, allowed_strs.join(", ")
+        ));
+    }
+    
+    Ok(())
+}
+
+fn validate_format(entity: &str, idx: usize, field: &str, value: &serde_json::Value, format_type: &str) -> Result<(), String> {
+    let string_value = value.as_str()
+        .ok_or(format!("Item {} in '{}': field '{}' must be a string for format validation", idx, entity, field))?;
+
+    let is_valid = match format_type {
+        "email" => {
+            // Basic email validation regex
+            let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
+            email_regex.is_match(string_value)
+        }
+        "url" => {
+            // Basic URL validation
+            let url_regex = Regex::new(r"^https?://[^\s/$.?#].[^\s]*$").unwrap();
+            url_regex.is_match(string_value)
+        }
+        "date" => {
+            // ISO 8601 date format (YYYY-MM-DD)
+            let date_regex = Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
+            date_regex.is_match(string_value)
+        }
+        "uuid" => {
+            // UUID format
+            let uuid_regex = Regex::new(r"^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$").unwrap();
+            uuid_regex.is_match(string_value)
+        }
+        _ => {
+            return Err(format!("Unknown format type: {}", format_type));
+        }
+    };
+    
+    if !is_valid {
+        return Err(format!(
+            "Item {} in '{}': field '{}' value '{}' does not match format '{}'",
+            idx, entity, field, string_value, format_type
+        ));
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,328 @@
+// `toonify verify-bindings`: check that every binding layer agrees with Rust
+//
+// A canonical corpus is written to `case_<n>.json` / `case_<n>.toon` files and
+// each layer runs a small driver that writes `out_<n>.toon` (json_to_toon)
+// and `out_<n>.json` (toon_to_json), or `out_<n>.err` on failure. Plain files
+// keep the drivers free of JSON libraries in languages without one.
+//
+// Layers whose artifacts or toolchains are missing are skipped, not failed,
+// unless `--require-all` is given.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use toonify::converter;
+
+/// Inputs covering tables, nesting, quoting, unicode, and edge values
+const CORPUS: &[&str] = &[
+    r#"{"users":[{"id":1,"name":"Alice","active":true},{"id":2,"name":"Bob","active":false}]}"#,
+    r#"{"config":{"host":"localhost","port":8080},"debug":false}"#,
+    r#"{"tags":["rust","llm","json"],"count":3}"#,
+    r#"{"items":[{"sku":"A-1","price":9.99,"note":"has, comma"},{"sku":"B-2","price":-0.5,"note":"say \"hi\""}]}"#,
+    r#"{"links":[{"url":"https://example.com/a?b=c","at":"2024-01-02T03:04:05Z"}]}"#,
+    r#"{"greeting":"héllo wörld ✓","empty":[],"missing":null}"#,
+    r#"{"orders":[{"id":1,"meta":{"gift":true,"tags":["x","y"]}}]}"#,
+];
+
+pub struct VerifyOptions {
+    pub bindings_dir: PathBuf,
+    pub pkg_dir: PathBuf,
+    pub lib_dir: PathBuf,
+    pub only: Vec<String>,
+    pub require_all: bool,
+}
+
+enum Outcome {
+    Pass,
+    Fail(Vec<String>),
+    Skip(String),
+}
+
+type Prepare = fn(&VerifyOptions, &Path) -> Result<Command, String>;
+
+const LAYERS: &[(&str, Prepare)] = &[
+    ("python", prepare_python),
+    ("kotlin", prepare_kotlin),
+    ("swift", prepare_swift),
+    ("wasm", prepare_wasm),
+];
+
+/// Run every selected layer; returns whether verification passed
+pub fn run(options: &VerifyOptions) -> Result<bool, Box<dyn std::error::Error>> {
+    let work = std::env::temp_dir().join(format!("toonify-verify-bindings-{}", std::process::id()));
+    let cases = work.join("cases");
+    fs::create_dir_all(&cases)?;
+
+    let mut expected = Vec::new();
+    for (i, json) in CORPUS.iter().enumerate() {
+        let toon = converter::json_to_toon(json)?;
+        let back = converter::toon_to_json(&toon)?;
+        fs::write(cases.join(format!("case_{}.json", i)), json)?;
+        fs::write(cases.join(format!("case_{}.toon", i)), &toon)?;
+        expected.push((toon, back));
+    }
+    eprintln!("[VERIFY] Corpus: {} cases in {:?}", CORPUS.len(), cases);
+
+    let mut ok = true;
+    for (name, prepare) in LAYERS {
+        if !options.only.is_empty() && !options.only.iter().any(|layer| layer.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+
+        let layer_work = work.join(name);
+        let outcome = match prepare(options, &layer_work) {
+            Ok(command) => run_layer(command, &cases, &layer_work, &expected),
+            Err(reason) => Outcome::Skip(reason),
+        };
+
+        match outcome {
+            Outcome::Pass => eprintln!("[VERIFY] PASS {}", name),
+            Outcome::Skip(reason) => {
+                eprintln!("[VERIFY] SKIP {}: {}", name, reason);
+                ok &= !options.require_all;
+            }
+            Outcome::Fail(problems) => {
+                eprintln!("[VERIFY] FAIL {}", name);
+                for problem in problems {
+                    eprintln!("[VERIFY]   {}", problem);
+                }
+                ok = false;
+            }
+        }
+    }
+
+    let _ = fs::remove_dir_all(&work);
+    Ok(ok)
+}
+
+fn run_layer(mut command: Command, cases: &Path, work: &Path, expected: &[(String, String)]) -> Outcome {
+    let out = work.join("out");
+    if let Err(e) = fs::create_dir_all(&out) {
+        return Outcome::Fail(vec![format!("cannot create {:?}: {}", out, e)]);
+    }
+
+    let output = match command.arg(cases).arg(&out).output() {
+        Ok(output) => output,
+        Err(e) => return Outcome::Fail(vec![format!("driver failed to start: {}", e)]),
+    };
+    if !output.status.success() {
+        return Outcome::Fail(vec![format!(
+            "driver exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )]);
+    }
+
+    let mut problems = Vec::new();
+    for (i, (toon, json)) in expected.iter().enumerate() {
+        if let Ok(error) = fs::read_to_string(out.join(format!("out_{}.err", i))) {
+            problems.push(format!("case {}: binding raised: {}", i, error.trim()));
+            continue;
+        }
+        match fs::read_to_string(out.join(format!("out_{}.toon", i))) {
+            Ok(actual) if actual == *toon => {}
+            Ok(actual) => problems.push(format!("case {}: json_to_toon differs\n      expected: {:?}\n      actual:   {:?}", i, toon, actual)),
+            Err(_) => problems.push(format!("case {}: no json_to_toon output", i)),
+        }
+        match fs::read_to_string(out.join(format!("out_{}.json", i))) {
+            Ok(actual) if actual == *json => {}
+            Ok(actual) => problems.push(format!("case {}: toon_to_json differs\n      expected: {:?}\n      actual:   {:?}", i, json, actual)),
+            Err(_) => problems.push(format!("case {}: no toon_to_json output", i)),
+        }
+    }
+
+    if problems.is_empty() { Outcome::Pass } else { Outcome::Fail(problems) }
+}
+
+fn find_tool(name: &str) -> Result<PathBuf, String> {
+    let path = std::env::var_os("PATH").ok_or("PATH is not set")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| format!("'{}' not found on PATH", name))
+}
+
+fn require(path: &Path) -> Result<(), String> {
+    if path.exists() { Ok(()) } else { Err(format!("{:?} not found", path)) }
+}
+
+fn native_library(lib_dir: &Path) -> Result<PathBuf, String> {
+    ["libtoonify.so", "libtoonify.dylib", "toonify.dll"]
+        .iter()
+        .map(|name| lib_dir.join(name))
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("no native library in {:?} (run cargo build --lib --release)", lib_dir))
+}
+
+fn write_driver(work: &Path, name: &str, source: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(work).map_err(|e| e.to_string())?;
+    let path = work.join(name);
+    fs::write(&path, source).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+const PYTHON_DRIVER: &str = r#"import os, sys
+sys.path.insert(0, sys.argv[1])
+import toonifypy as t
+cases, out = sys.argv[2], sys.argv[3]
+i = 0
+while os.path.exists(os.path.join(cases, f"case_{i}.json")):
+    def emit(ext, text):
+        with open(os.path.join(out, f"out_{i}.{ext}"), "w", encoding="utf-8", newline="") as f:
+            f.write(text)
+    try:
+        with open(os.path.join(cases, f"case_{i}.json"), encoding="utf-8") as f:
+            emit("toon", t.json_to_toon(f.read()))
+        with open(os.path.join(cases, f"case_{i}.toon"), encoding="utf-8") as f:
+            emit("json", t.toon_to_json(f.read()))
+    except Exception as e:
+        emit("err", repr(e))
+    i += 1
+"#;
+
+fn prepare_python(options: &VerifyOptions, work: &Path) -> Result<Command, String> {
+    let package = options.bindings_dir.join("python");
+    require(&package.join("toonifypy").join("__init__.py"))?;
+    native_library(&package.join("toonifypy"))
+        .map_err(|_| "native library not copied into bindings/python/toonifypy".to_string())?;
+    let python = find_tool("python3").or_else(|_| find_tool("python"))?;
+
+    let driver = write_driver(work, "driver.py", PYTHON_DRIVER)?;
+    let mut command = Command::new(python);
+    command.arg(driver).arg(package);
+    Ok(command)
+}
+
+const KOTLIN_DRIVER: &str = r#"import java.io.File
+import uniffi.toonify.jsonToToon
+import uniffi.toonify.toonToJson
+
+fun main(args: Array<String>) {
+    val cases = File(args[0])
+    val out = File(args[1])
+    var i = 0
+    while (File(cases, "case_$i.json").exists()) {
+        try {
+            File(out, "out_$i.toon").writeText(jsonToToon(File(cases, "case_$i.json").readText()))
+            File(out, "out_$i.json").writeText(toonToJson(File(cases, "case_$i.toon").readText()))
+        } catch (e: Exception) {
+            File(out, "out_$i.err").writeText(e.toString())
+        }
+        i++
+    }
+}
+"#;
+
+// Kotlin bindings need JNA; point JNA_JAR at jna.jar to enable this layer
+fn prepare_kotlin(options: &VerifyOptions, work: &Path) -> Result<Command, String> {
+    let bindings = options.bindings_dir.join("kotlin").join("uniffi").join("toonify").join("toonify.kt");
+    require(&bindings)?;
+    native_library(&options.lib_dir)?;
+    let jna = std::env::var("JNA_JAR").map_err(|_| "JNA_JAR is not set".to_string())?;
+    let kotlinc = find_tool("kotlinc")?;
+    let java = find_tool("java")?;
+
+    let driver = write_driver(work, "Driver.kt", KOTLIN_DRIVER)?;
+    let jar = work.join("driver.jar");
+    let status = Command::new(kotlinc)
+        .arg(&bindings)
+        .arg(&driver)
+        .args(["-cp", &jna, "-include-runtime", "-d"])
+        .arg(&jar)
+        .status()
+        .map_err(|e| format!("kotlinc failed to start: {}", e))?;
+    if !status.success() {
+        return Err(format!("kotlinc exited with {}", status));
+    }
+
+    let separator = if cfg!(windows) { ";" } else { ":" };
+    let mut command = Command::new(java);
+    command
+        .arg(format!("-Djna.library.path={}", options.lib_dir.display()))
+        .arg("-cp")
+        .arg(format!("{}{}{}", jar.display(), separator, jna))
+        .arg("DriverKt");
+    Ok(command)
+}
+
+const SWIFT_DRIVER: &str = r#"import Foundation
+
+let cases = URL(fileURLWithPath: CommandLine.arguments[1])
+let out = URL(fileURLWithPath: CommandLine.arguments[2])
+var i = 0
+while FileManager.default.fileExists(atPath: cases.appendingPathComponent("case_\(i).json").path) {
+    do {
+        let json = try String(contentsOf: cases.appendingPathComponent("case_\(i).json"), encoding: .utf8)
+        try jsonToToon(jsonData: json).write(to: out.appendingPathComponent("out_\(i).toon"), atomically: true, encoding: .utf8)
+        let toon = try String(contentsOf: cases.appendingPathComponent("case_\(i).toon"), encoding: .utf8)
+        try toonToJson(toonData: toon).write(to: out.appendingPathComponent("out_\(i).json"), atomically: true, encoding: .utf8)
+    } catch {
+        try? "\(error)".write(to: out.appendingPathComponent("out_\(i).err"), atomically: true, encoding: .utf8)
+    }
+    i += 1
+}
+"#;
+
+fn prepare_swift(options: &VerifyOptions, work: &Path) -> Result<Command, String> {
+    let bindings = options.bindings_dir.join("swift");
+    require(&bindings.join("toonify.swift"))?;
+    require(&bindings.join("toonifyFFI.modulemap"))?;
+    native_library(&options.lib_dir)?;
+    let swiftc = find_tool("swiftc")?;
+
+    let driver = write_driver(work, "main.swift", SWIFT_DRIVER)?;
+    let binary = work.join("driver");
+    let status = Command::new(swiftc)
+        .arg("-Xcc")
+        .arg(format!("-fmodule-map-file={}", bindings.join("toonifyFFI.modulemap").display()))
+        .arg("-I")
+        .arg(&bindings)
+        .arg("-L")
+        .arg(&options.lib_dir)
+        .arg("-ltoonify")
+        .arg(bindings.join("toonify.swift"))
+        .arg(&driver)
+        .arg("-o")
+        .arg(&binary)
+        .status()
+        .map_err(|e| format!("swiftc failed to start: {}", e))?;
+    if !status.success() {
+        return Err(format!("swiftc exited with {}", status));
+    }
+
+    let mut command = Command::new(binary);
+    command
+        .env("LD_LIBRARY_PATH", &options.lib_dir)
+        .env("DYLD_LIBRARY_PATH", &options.lib_dir);
+    Ok(command)
+}
+
+const WASM_DRIVER: &str = r#"import fs from "node:fs";
+import path from "node:path";
+import { pathToFileURL } from "node:url";
+
+const [pkg, cases, out] = process.argv.slice(2);
+const wasm = await import(pathToFileURL(path.join(pkg, "toonify.js")));
+wasm.initSync({ module: fs.readFileSync(path.join(pkg, "toonify_bg.wasm")) });
+
+for (let i = 0; fs.existsSync(path.join(cases, `case_${i}.json`)); i++) {
+    try {
+        fs.writeFileSync(path.join(out, `out_${i}.toon`), wasm.json_to_toon(fs.readFileSync(path.join(cases, `case_${i}.json`), "utf8")));
+        fs.writeFileSync(path.join(out, `out_${i}.json`), wasm.toon_to_json(fs.readFileSync(path.join(cases, `case_${i}.toon`), "utf8")));
+    } catch (e) {
+        fs.writeFileSync(path.join(out, `out_${i}.err`), String(e));
+    }
+}
+"#;
+
+fn prepare_wasm(options: &VerifyOptions, work: &Path) -> Result<Command, String> {
+    require(&options.pkg_dir.join("toonify.js"))?;
+    require(&options.pkg_dir.join("toonify_bg.wasm"))?;
+    let node = find_tool("node")?;
+
+    let driver = write_driver(work, "driver.mjs", WASM_DRIVER)?;
+    let mut command = Command::new(node);
+    command.arg(driver).arg(&options.pkg_dir);
+    Ok(command)
+}
@@ -0,0 +1,88 @@
+// Per-file job queue for watch mode
+//
+// A save often raises several modify events, and a file can change again
+// while its previous version is still converting. Converting once per event
+// on the watcher thread held every other file up behind it; converting each
+// event on its own thread let two conversions of one file interleave. Here a
+// path is converted by at most one worker at a time. Events for a path that
+// is already queued are dropped (the queued job reads the file when it
+// starts), and events during a conversion mark it dirty so it runs once
+// more afterwards, from the latest contents. Different files convert
+// concurrently on a rayon pool.
+
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+type Job = dyn Fn(&Path) + Send + Sync;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Queued,
+    /// `dirty` once the file changed again mid-conversion
+    Running { dirty: bool },
+}
+
+pub struct WatchQueue {
+    pool: rayon::ThreadPool,
+    slots: Arc<Mutex<HashMap<PathBuf, Slot>>>,
+    job: Arc<Job>,
+}
+
+impl WatchQueue {
+    /// `threads` workers (0 for one per CPU), each running `job` on a path
+    pub fn new(threads: usize, job: impl Fn(&Path) + Send + Sync + 'static) -> Result<Self, String> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("toonify-watch-{}", index))
+            .build()
+            .map_err(|e| format!("Failed to start watch workers: {}", e))?;
+        Ok(Self { pool, slots: Arc::new(Mutex::new(HashMap::new())), job: Arc::new(job) })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Schedule a conversion of `path`; false when an existing one will cover it
+    pub fn submit(&self, path: PathBuf) -> bool {
+        let mut slots = self.slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match slots.get_mut(&path) {
+            Some(Slot::Queued) => return false,
+            Some(Slot::Running { dirty }) => {
+                *dirty = true;
+                return false;
+            }
+            None => {
+                slots.insert(path.clone(), Slot::Queued);
+            }
+        }
+        drop(slots);
+
+        let slots = Arc::clone(&self.slots);
+        let job = Arc::clone(&self.job);
+        self.pool.spawn(move || run(&slots, job.as_ref(), path));
+        true
+    }
+}
+
+// Convert `path` until no event arrived during the last conversion
+fn run(slots: &Mutex<HashMap<PathBuf, Slot>>, job: &Job, path: PathBuf) {
+    let lock = || slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    loop {
+        lock().insert(path.clone(), Slot::Running { dirty: false });
+        // The slot must be released even if a conversion panics
+        if panic::catch_unwind(AssertUnwindSafe(|| job(&path))).is_err() {
+            eprintln!("[WATCH] Conversion of {:?} panicked", path);
+        }
+
+        let mut slots = lock();
+        if slots.get(&path) != Some(&Slot::Running { dirty: true }) {
+            slots.remove(&path);
+            return;
+        }
+        drop(slots);
+        eprintln!("[WATCH] {:?} changed during conversion, converting again", path);
+    }
+}
@@ -0,0 +1,153 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use zip::write::SimpleFileOptions;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("archive_batch");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path
+}
+
+const MEMBERS: &[(&str, &str)] = &[
+    ("users.json", r#"{"users":[{"id":1,"name":"Alice"}]}"#),
+    ("nested/orders.json", r#"{"orders":[{"id":7,"total":9.5}]}"#),
+    ("nested/deeper/config.toon", "retries:3\n"),
+];
+
+fn write_zip(path: &Path, members: &[(&str, &str)]) {
+    let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+    writer.add_directory("nested/", SimpleFileOptions::default()).unwrap();
+    for (name, content) in members {
+        writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+fn write_tar_gz(path: &Path, members: &[(&str, &str)]) {
+    let encoder = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for (name, content) in members {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, content.as_bytes()).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+fn run_batch(archive: &Path, output_dir: &Path, extra: &[&str]) -> Output {
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("batch")
+        .arg("--input")
+        .arg(archive)
+        .arg("--output-dir")
+        .arg(output_dir)
+        .args(extra)
+        .output()
+        .expect("Failed to execute batch command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+fn assert_converted_tree(output_dir: &Path) {
+    let users = fs::read_to_string(output_dir.join("users.toon")).expect("users.toon should exist");
+    assert!(users.contains("Alice"));
+    let orders = fs::read_to_string(output_dir.join("nested/orders.toon")).expect("Internal paths should be kept");
+    assert!(orders.contains("orders[1]"));
+    let config = fs::read_to_string(output_dir.join("nested/deeper/config.json")).expect("TOON members convert to JSON");
+    assert!(config.contains("\"retries\": 3"));
+}
+
+#[test]
+fn test_batch_zip_archive() {
+    println!("=== Archive: batch over a zip ===");
+
+    let dir = temp_dir("zip");
+    let archive = dir.join("data.zip");
+    write_zip(&archive, MEMBERS);
+
+    let output = run_batch(&archive, &dir.join("out"), &[]);
+    assert!(output.status.success(), "Batch over a zip should succeed");
+    assert_converted_tree(&dir.join("out"));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Processed 3 files"));
+
+    println!("✓ Zip members converted in place of extraction\n");
+}
+
+#[test]
+fn test_batch_tar_gz_archive() {
+    println!("=== Archive: batch over a tar.gz ===");
+
+    let dir = temp_dir("tar_gz");
+    let archive = dir.join("data.tar.gz");
+    write_tar_gz(&archive, MEMBERS);
+
+    let output = run_batch(&archive, &dir.join("out"), &["--quiet"]);
+    assert!(output.status.success(), "Batch over a tar.gz should succeed");
+    assert_converted_tree(&dir.join("out"));
+
+    println!("✓ Tar.gz members converted\n");
+}
+
+#[test]
+fn test_batch_archive_pattern() {
+    let dir = temp_dir("pattern");
+    let archive = dir.join("data.zip");
+    write_zip(&archive, MEMBERS);
+
+    let output = run_batch(&archive, &dir.join("out"), &["--pattern", "*.json", "--recursive"]);
+    assert!(output.status.success());
+    assert!(dir.join("out/users.toon").exists());
+    assert!(dir.join("out/nested/orders.toon").exists());
+    assert!(!dir.join("out/nested/deeper/config.json").exists(), "The TOON member does not match *.json");
+}
+
+#[test]
+fn test_batch_archive_refuses_escaping_members() {
+    println!("=== Archive: members outside the output directory ===");
+
+    let dir = temp_dir("escape");
+    let archive = dir.join("evil.zip");
+    write_zip(&archive, &[("../escaped.json", r#"{"a":1}"#), ("safe.json", r#"{"b":2}"#)]);
+
+    let output = run_batch(&archive, &dir.join("out"), &[]);
+    assert!(!output.status.success(), "A refused member should fail the run");
+    assert!(dir.join("out/safe.toon").exists(), "Other members are still converted");
+    assert!(!dir.join("escaped.toon").exists() && !dir.join("escaped.json").exists());
+
+    let log = fs::read_to_string(dir.join("out/failures.jsonl")).expect("Failure log should be written");
+    assert!(log.contains("escaped.json") && log.contains("leaves the output directory"), "Log: {}", log);
+
+    println!("✓ Path traversal refused\n");
+}
+
+#[test]
+fn test_batch_rejects_non_archive() {
+    let dir = temp_dir("not_archive");
+    let input = dir.join("data.bin");
+    fs::write(&input, "status:ok\n").unwrap();
+
+    let output = run_batch(&input, &dir.join("out"), &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not a zip, tar or tar.gz archive"));
+}
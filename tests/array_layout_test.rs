@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter::{self, Converter};
+use toonify::toon::{format_toon, outline, ArrayLayout, FormatOptions, StreamingParser, ToonEvent};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+// Four objects with no key in common: a table would be 3/4 empty cells
+const SPARSE: &str = r#"{"events":[{"click":"buy"},{"scroll":120},{"error":"timeout, retrying"},{"tags":["a","b"]}]}"#;
+const UNIFORM: &str = r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#;
+
+fn with_layout(layout: ArrayLayout) -> Converter {
+    Converter::builder().array_layout(layout).build()
+}
+
+#[test]
+fn test_auto_layout_picks_per_array() {
+    println!("=== Layout: auto ===");
+
+    let toon = with_layout(ArrayLayout::Auto).json_to_toon(SPARSE).unwrap();
+    println!("TOON:\n{}", toon);
+    assert!(toon.starts_with("events[4]:\n{\"click\":\"buy\"}\n"), "Sparse objects should be a list: {}", toon);
+
+    let toon = with_layout(ArrayLayout::Auto).json_to_toon(UNIFORM).unwrap();
+    assert_eq!(toon, "users[2]{id,name}:\n1,Alice\n2,Bob");
+
+    println!("✓ Table for uniform objects, list for sparse ones\n");
+}
+
+#[test]
+fn test_forced_layouts() {
+    let toon = with_layout(ArrayLayout::Table).json_to_toon(SPARSE).unwrap();
+    assert!(toon.starts_with("events[4]{click,error,scroll,tags}:"), "Unexpected TOON: {}", toon);
+
+    let toon = with_layout(ArrayLayout::List).json_to_toon(UNIFORM).unwrap();
+    assert_eq!(toon, "users[2]:\n{\"id\":1,\"name\":\"Alice\"}\n{\"id\":2,\"name\":\"Bob\"}");
+}
+
+#[test]
+fn test_table_layout_keeps_non_object_elements() {
+    let mixed = r#"{"items":[{"id":1},"loose",{"id":2},3]}"#;
+    let toon = with_layout(ArrayLayout::Table).json_to_toon(mixed).unwrap();
+    assert!(toon.starts_with("items[4]:\n"), "A table would drop elements: {}", toon);
+    let back: Value = serde_json::from_str(&converter::toon_to_json(&toon).unwrap()).unwrap();
+    assert_eq!(back, json!({"items": [{"id": 1}, "loose", {"id": 2}, 3]}));
+}
+
+#[test]
+fn test_list_layout_round_trips() {
+    println!("=== Layout: list parses back ===");
+
+    let toon = with_layout(ArrayLayout::List).json_to_toon(SPARSE).unwrap();
+    let back: Value = serde_json::from_str(&converter::toon_to_json(&toon).unwrap()).unwrap();
+    assert_eq!(back, serde_json::from_str::<Value>(SPARSE).unwrap());
+
+    // The streaming parser and the outline read one element per object line
+    let mut parser = StreamingParser::new();
+    parser.feed(&toon);
+    parser.finish();
+    let rows: Vec<Value> = parser
+        .filter_map(|event| match event.unwrap() {
+            ToonEvent::Row(row) => Some(row),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(rows[2], json!({"error": "timeout, retrying"}));
+    assert_eq!(outline(&toon)[0].rows.len(), 4);
+
+    // fmt keeps the count and the object lines intact
+    let formatted = format_toon(&toon, &FormatOptions::default()).unwrap();
+    assert_eq!(formatted.trim_end(), toon);
+
+    println!("✓ Parser, streaming parser, outline and fmt agree\n");
+}
+
+#[test]
+fn test_mixed_array_is_not_truncated() {
+    // A table has no row for the trailing number, so auto falls back to a list
+    let json = r#"{"items":[{"id":1},{"id":2},3]}"#;
+    let toon = with_layout(ArrayLayout::Auto).json_to_toon(json).unwrap();
+    assert!(toon.starts_with("items[3]:"), "Unexpected TOON: {}", toon);
+    let back: Value = serde_json::from_str(&converter::toon_to_json(&toon).unwrap()).unwrap();
+    assert_eq!(back["items"], json!([{"id": 1}, {"id": 2}, 3]));
+}
+
+#[test]
+fn test_cli_layout_flag() {
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["convert", "-", "--from", "json", "--to", "toon", "--layout", "list"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            use std::io::Write;
+            child.stdin.take().unwrap().write_all(UNIFORM.as_bytes())?;
+            child.wait_with_output()
+        })
+        .expect("Failed to execute toonify binary");
+
+    assert!(output.status.success(), "Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("users[2]:\n{\"id\":1"));
+}
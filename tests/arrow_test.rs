@@ -0,0 +1,55 @@
+use arrow::array::{Array, BooleanArray, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::DataType;
+use arrow::ipc::reader::StreamReader;
+use serde_json::json;
+use toonify::export::{toon_to_arrow, value_to_arrow};
+
+fn read_batches(bytes: &[u8]) -> Vec<arrow::record_batch::RecordBatch> {
+    StreamReader::try_new(bytes, None).unwrap().map(|batch| batch.unwrap()).collect()
+}
+
+#[test]
+fn test_toon_to_arrow_uses_typed_headers() {
+    println!("=== Arrow: typed headers decide column types ===");
+
+    // zip is declared str, so "02134" stays text rather than being inferred
+    let toon = "users[2]{id:int,zip:str,score,active}:\n1,\"02134\",9.5,true\n2,\"94110\",7,false";
+    let batches = read_batches(&toon_to_arrow(toon, None).unwrap());
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    let schema = batch.schema();
+    let types: Vec<&DataType> = schema.fields().iter().map(|field| field.data_type()).collect();
+    assert_eq!(types, [&DataType::Int64, &DataType::Utf8, &DataType::Float64, &DataType::Boolean]);
+
+    let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+    assert_eq!(ids.values(), &[1, 2]);
+    let zips = batch.column(1).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(zips.value(0), "02134");
+    let scores = batch.column(2).as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(scores.values(), &[9.5, 7.0]);
+    let active = batch.column(3).as_any().downcast_ref::<BooleanArray>().unwrap();
+    assert!(active.value(0) && !active.value(1));
+
+    println!("=== Arrow typed headers PASSED ===");
+}
+
+#[test]
+fn test_value_to_arrow_needs_entity_for_several_tables() {
+    let value = json!({
+        "users": [{ "id": 1, "tags": ["a"] }, { "id": null, "tags": [] }],
+        "orders": [{ "id": 10 }],
+        "version": 3
+    });
+    let error = value_to_arrow(&value, None).unwrap_err();
+    assert!(error.contains("--entity") && error.contains("users, orders"), "{}", error);
+
+    let batches = read_batches(&value_to_arrow(&value, Some("users")).unwrap());
+    let batch = &batches[0];
+    assert_eq!(batch.num_rows(), 2);
+    assert!(batch.column(0).is_null(1));
+    // Nested values are written as JSON text
+    let tags = batch.column(1).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(tags.value(0), "[\"a\"]");
+
+    assert!(value_to_arrow(&value, Some("missing")).is_err());
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn free_addr() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    listener.local_addr().unwrap().to_string()
+}
+
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn wait_for(http_addr: &str) {
+    for _ in 0..50 {
+        if reqwest::blocking::get(format!("http://{}/healthz", http_addr)).is_ok() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    panic!("Server did not start in time");
+}
+
+#[test]
+fn test_audit_log_records_conversions() {
+    println!("=== Audit log: REST and gRPC records ===");
+
+    let log = temp_path("audit.jsonl");
+    let rotated = temp_path("audit.jsonl.1");
+    let _ = fs::remove_file(&rotated);
+    // Already over the 1 MB limit, so the first record rotates it away
+    fs::write(&log, "{}\n".repeat(400_000)).unwrap();
+
+    let (http_addr, grpc_addr) = (free_addr(), free_addr());
+    let child = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["serve", "--http-addr", &http_addr, "--grpc-addr", &grpc_addr, "--audit-log-max-mb", "1", "--audit-log"])
+        .arg(&log)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start server");
+    let _server = Server(child);
+    wait_for(&http_addr);
+
+    let client = reqwest::blocking::Client::new();
+    let data = r#"{"users":[{"id":1,"name":"Alice"}]}"#;
+    let response = client
+        .post(format!("http://{}/json-to-toon", http_addr))
+        .header("X-API-Key", "secret-key-1234")
+        .json(&serde_json::json!({ "data": data }))
+        .send()
+        .unwrap();
+    assert!(response.status().is_success());
+    let output = response.json::<serde_json::Value>().unwrap()["result"].as_str().unwrap().to_string();
+
+    let response = client
+        .post(format!("http://{}/convert/json/nope", http_addr))
+        .json(&serde_json::json!({ "data": data }))
+        .send()
+        .unwrap();
+    assert!(!response.status().is_success());
+
+    let probe = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["healthcheck", "--grpc", "--grpc-addr", &grpc_addr])
+        .output()
+        .unwrap();
+    assert!(probe.status.success(), "{}", String::from_utf8_lossy(&probe.stderr));
+
+    assert!(rotated.exists(), "oversized log rotated to .1");
+    let content = fs::read_to_string(&log).unwrap();
+    println!("{}", content);
+    let records: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 3);
+
+    let rest = &records[0];
+    assert_eq!(rest["transport"], "rest");
+    assert_eq!(rest["endpoint"], "/json-to-toon");
+    assert_eq!(rest["from"], "json");
+    assert_eq!(rest["to"], "toon");
+    assert_eq!(rest["client_ip"], "127.0.0.1");
+    assert_eq!(rest["api_key"], "…1234", "only the key's tail is logged");
+    assert_eq!(rest["input_bytes"], data.len());
+    assert_eq!(rest["output_bytes"], output.len());
+    assert_eq!(rest["status"], 200);
+    assert!(rest["timestamp"].as_str().unwrap().ends_with('Z'));
+    assert!(rest["duration_ms"].as_f64().unwrap() >= 0.0);
+    assert!(!content.contains("secret-key"));
+
+    let failed = &records[1];
+    assert_eq!(failed["endpoint"], "/convert/json/nope");
+    assert_ne!(failed["status"], 200);
+    assert!(failed["error"].is_string());
+
+    let grpc = &records[2];
+    assert_eq!(grpc["transport"], "grpc");
+    assert_eq!(grpc["endpoint"], "ConverterService/JsonToToon");
+    assert_eq!(grpc["status"], 0);
+
+    println!("=== Audit log PASSED ===");
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toonify::binary::BinarySchema;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+const SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "Users",
+  "fields": [
+    { "name": "users", "type": { "type": "array", "items": {
+      "type": "record",
+      "name": "User",
+      "fields": [
+        { "name": "id", "type": "long" },
+        { "name": "name", "type": "string" },
+        { "name": "score", "type": "double" }
+      ]
+    } } }
+  ]
+}"#;
+
+#[test]
+fn test_avro_roundtrip_api() {
+    println!("=== Avro: encode/decode a single datum ===");
+
+    let path = temp_path("users_api.avsc");
+    fs::write(&path, SCHEMA).unwrap();
+    let schema = BinarySchema::load(&path, None).unwrap();
+
+    let value = json!({ "users": [
+        { "id": 1, "name": "Alice", "score": 9.5 },
+        { "id": 2, "name": "Bob", "score": 7.25 }
+    ] });
+    let bytes = schema.encode(&value).unwrap();
+    assert!(bytes.len() < value.to_string().len());
+    assert_eq!(schema.decode(&bytes).unwrap(), value);
+
+    assert!(schema.encode(&json!({ "users": [{ "id": "one" }] })).is_err());
+    assert!(BinarySchema::load(&path, Some("Users")).is_err(), "--message is .proto only");
+
+    println!("=== Avro API PASSED ===");
+}
+
+#[test]
+fn test_avro_cli_encode_decode() {
+    let path = temp_path("users_cli.avsc");
+    let input = temp_path("users_avro.json");
+    let encoded = temp_path("users.avro");
+    fs::write(&path, SCHEMA).unwrap();
+    fs::write(&input, r#"{"users":[{"id":1,"name":"Alice","score":9.5}]}"#).unwrap();
+
+    let status = Command::new(get_binary_path())
+        .arg("encode")
+        .arg("--schema")
+        .arg(&path)
+        .arg("--input")
+        .arg(&input)
+        .arg("--output")
+        .arg(&encoded)
+        .status()
+        .expect("Failed to execute encode");
+    assert!(status.success());
+
+    let output = Command::new(get_binary_path())
+        .arg("decode")
+        .arg("--schema")
+        .arg(&path)
+        .arg("--input")
+        .arg(&encoded)
+        .args(["--to", "json"])
+        .output()
+        .expect("Failed to execute decode");
+    assert!(output.status.success());
+    let decoded: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(decoded, json!({ "users": [{ "id": 1, "name": "Alice", "score": 9.5 }] }));
+}
@@ -166,6 +166,36 @@ fn test_batch_with_pattern_filter() {
     println!("✓ Test completed\n");
 }
 
+#[test]
+fn test_batch_pattern_with_directory() {
+    let test_dir = "/tmp/batch_pattern_dir";
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(format!("{}/sub/deeper", test_dir)).unwrap();
+    fs::write(format!("{}/top.json", test_dir), r#"{"a":1}"#).unwrap();
+    fs::write(format!("{}/sub/inner.json", test_dir), r#"{"b":2}"#).unwrap();
+    fs::write(format!("{}/sub/deeper/deep.json", test_dir), r#"{"c":3}"#).unwrap();
+
+    // Not recursive, but the pattern names a subdirectory
+    let output = Command::new(get_binary_path())
+        .args([
+            "batch",
+            "--input-dir", test_dir,
+            "--output-dir", &format!("{}/output", test_dir),
+            "--pattern", "sub/*.json"
+        ])
+        .output()
+        .expect("Failed to execute batch command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Batch with a directory pattern should succeed");
+
+    let output_dir = format!("{}/output", test_dir);
+    assert!(Path::new(&format!("{}/sub/inner.toon", output_dir)).exists(), "sub/inner.json matches sub/*.json");
+    assert!(!Path::new(&format!("{}/top.toon", output_dir)).exists());
+    assert!(!Path::new(&format!("{}/sub/deeper/deep.toon", output_dir)).exists(), "* does not cross a separator");
+
+    let _ = fs::remove_dir_all(test_dir);
+}
+
 #[test]
 fn test_batch_recursive() {
     println!("=== Batch: Recursive directory conversion ===");
@@ -434,3 +464,252 @@ fn test_batch_parallel_preserves_directory_structure() {
     println!("✓ Test completed\n");
 }
 
+
+#[test]
+fn test_batch_quiet_suppresses_per_file_logs() {
+    println!("=== Batch: --quiet ===");
+    
+    let test_dir = "/tmp/batch_quiet";
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(test_dir).unwrap();
+    
+    for i in 1..=3 {
+        fs::write(format!("{}/file{}.json", test_dir, i), format!(r#"{{"id":{}}}"#, i)).unwrap();
+    }
+    fs::write(format!("{}/broken.json", test_dir), "{not json").unwrap();
+    
+    let output = Command::new(get_binary_path())
+        .args([
+            "batch",
+            "--input-dir", test_dir,
+            "--output-dir", &format!("{}/output", test_dir),
+            "--from", "json",
+            "--quiet"
+        ])
+        .output()
+        .expect("Failed to execute batch command");
+    
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("Stderr: {}", stderr);
+    
+    // One failure, so the run fails, but the good files are still converted
+    assert!(!output.status.success());
+    assert!(Path::new(&format!("{}/output/file1.toon", test_dir)).exists());
+    
+    assert!(!stderr.contains("Processing file"), "Per-file progress lines should be suppressed");
+    assert!(!stderr.contains("Successfully converted"), "Per-file success lines should be suppressed");
+    assert!(stderr.contains("broken.json"), "Failures should still name the file");
+    assert!(stderr.contains("Failed: 1"), "Summary should still be printed");
+    
+    let _ = fs::remove_dir_all(test_dir);
+    
+    println!("✓ Quiet batch reports only failures and the summary\n");
+}
+
+#[test]
+fn test_batch_failure_log_and_retry() {
+    println!("=== Batch: failures.jsonl and --retry-failed ===");
+    
+    let test_dir = "/tmp/batch_retry";
+    let output_dir = format!("{}/output", test_dir);
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(test_dir).unwrap();
+    
+    fs::write(format!("{}/good.json", test_dir), r#"{"id":1}"#).unwrap();
+    fs::write(format!("{}/bad.json", test_dir), "{not json").unwrap();
+    
+    let run = |extra: &[&str]| {
+        let mut args = vec!["batch", "--input-dir", test_dir, "--output-dir", &output_dir, "--from", "json"];
+        args.extend_from_slice(extra);
+        Command::new(get_binary_path())
+            .args(&args)
+            .output()
+            .expect("Failed to execute batch command")
+    };
+    
+    let first = run(&[]);
+    assert!(!first.status.success());
+    
+    let failures_path = format!("{}/failures.jsonl", output_dir);
+    let log = fs::read_to_string(&failures_path).expect("failures.jsonl should be written");
+    println!("failures.jsonl:\n{}", log);
+    let records: Vec<serde_json::Value> = log.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 1, "Only the bad file should be logged");
+    assert!(records[0]["path"].as_str().unwrap().ends_with("bad.json"));
+    assert_eq!(records[0]["stage"], "convert");
+    assert!(records[0]["error"].as_str().unwrap().contains("Invalid JSON"));
+    
+    // Fix the input and retry only what failed
+    fs::write(format!("{}/bad.json", test_dir), r#"{"id":2}"#).unwrap();
+    fs::remove_file(format!("{}/good.toon", output_dir)).unwrap();
+    
+    let retry = run(&["--retry-failed", &failures_path]);
+    let stderr = String::from_utf8_lossy(&retry.stderr);
+    println!("Retry stderr: {}", stderr);
+    assert!(retry.status.success(), "Retry should succeed once the input is fixed");
+    assert!(Path::new(&format!("{}/bad.toon", output_dir)).exists());
+    assert!(!Path::new(&format!("{}/good.toon", output_dir)).exists(), "Files that did not fail are not re-run");
+    assert!(!Path::new(&failures_path).exists(), "A clean run removes the stale failure log");
+    
+    let _ = fs::remove_dir_all(test_dir);
+    
+    println!("✓ Failures are logged and can be retried\n");
+}
+
+#[test]
+fn test_batch_fsync_leaves_no_temp_files() {
+    println!("=== Batch: atomic writes with --fsync ===");
+    
+    let test_dir = "/tmp/batch_fsync";
+    let output_dir = format!("{}/output", test_dir);
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(test_dir).unwrap();
+    
+    for i in 0..5 {
+        fs::write(format!("{}/file{}.json", test_dir, i), format!(r#"{{"id":{}}}"#, i)).unwrap();
+    }
+    
+    let output = Command::new(get_binary_path())
+        .args(["batch", "--input-dir", test_dir, "--output-dir", &output_dir, "--from", "json", "--parallel", "--fsync"])
+        .output()
+        .expect("Failed to execute batch command");
+    
+    assert!(output.status.success(), "Batch with --fsync should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    
+    let names: Vec<String> = fs::read_dir(&output_dir).unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    println!("Output files: {:?}", names);
+    assert_eq!(names.iter().filter(|name| name.ends_with(".toon")).count(), 5);
+    assert!(!names.iter().any(|name| name.ends_with(".tmp")), "Temp files should be renamed away");
+    
+    let _ = fs::remove_dir_all(test_dir);
+    
+    println!("✓ Outputs are renamed into place\n");
+}
+
+#[test]
+fn test_batch_preserve_metadata() {
+    println!("=== Batch: --preserve-metadata ===");
+    
+    let test_dir = "/tmp/batch_preserve_metadata";
+    let output_dir = format!("{}/output", test_dir);
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(test_dir).unwrap();
+    
+    let source = format!("{}/data.json", test_dir);
+    fs::write(&source, r#"{"id":1}"#).unwrap();
+    let mtime = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+    fs::File::options().write(true).open(&source).unwrap()
+        .set_times(fs::FileTimes::new().set_modified(mtime))
+        .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&source, fs::Permissions::from_mode(0o640)).unwrap();
+    }
+    
+    let output = Command::new(get_binary_path())
+        .args(["batch", "--input-dir", test_dir, "--output-dir", &output_dir, "--from", "json", "--preserve-metadata"])
+        .output()
+        .expect("Failed to execute batch command");
+    
+    assert!(output.status.success(), "Batch should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    
+    let converted = fs::metadata(format!("{}/data.toon", output_dir)).unwrap();
+    assert_eq!(converted.modified().unwrap(), mtime, "mtime should be copied from the source");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(converted.permissions().mode() & 0o777, 0o640, "Permissions should be copied from the source");
+    }
+    
+    let _ = fs::remove_dir_all(test_dir);
+    
+    println!("✓ Converted files keep the source metadata\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_batch_symlink_cycles_and_duplicates() {
+    println!("=== Batch: symlink policy and cycle detection ===");
+    
+    let test_dir = "/tmp/batch_symlinks";
+    let input_dir = format!("{}/input", test_dir);
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(format!("{}/data", input_dir)).unwrap();
+    
+    fs::write(format!("{}/data/a.json", input_dir), r#"{"id":1}"#).unwrap();
+    // A link back to the root and a second name for the data directory
+    std::os::unix::fs::symlink(&input_dir, format!("{}/data/loop", input_dir)).unwrap();
+    std::os::unix::fs::symlink(format!("{}/data", input_dir), format!("{}/alias", input_dir)).unwrap();
+    
+    let run = |output_dir: &str, extra: &[&str]| {
+        let mut args = vec!["batch", "--input-dir", &input_dir, "--output-dir", output_dir, "--from", "json", "--recursive"];
+        args.extend_from_slice(extra);
+        Command::new(get_binary_path())
+            .args(&args)
+            .output()
+            .expect("Failed to execute batch command")
+    };
+    
+    let followed_dir = format!("{}/followed", test_dir);
+    let followed = run(&followed_dir, &["--follow-symlinks"]);
+    let stdout = String::from_utf8_lossy(&followed.stdout);
+    println!("Follow stdout: {}", stdout);
+    assert!(followed.status.success(), "Cycles must not break the batch: {}", String::from_utf8_lossy(&followed.stderr));
+    assert!(stdout.contains("Processed 1 files"), "The file reachable through the alias is converted once");
+    
+    let skipped_dir = format!("{}/skipped", test_dir);
+    let skipped = run(&skipped_dir, &["--no-follow-symlinks"]);
+    assert!(skipped.status.success());
+    assert!(String::from_utf8_lossy(&skipped.stdout).contains("Processed 1 files"));
+    assert!(Path::new(&format!("{}/data/a.toon", skipped_dir)).exists());
+    assert!(!Path::new(&format!("{}/alias", skipped_dir)).exists(), "Symlinked directories are not entered");
+    
+    let _ = fs::remove_dir_all(test_dir);
+    
+    println!("✓ Symlinks are followed once or skipped\n");
+}
+
+#[test]
+fn test_batch_files_from_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+    
+    println!("=== Batch: --files-from - ===");
+    
+    let test_dir = "/tmp/batch_files_from";
+    let output_dir = format!("{}/output", test_dir);
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(format!("{}/nested", test_dir)).unwrap();
+    
+    fs::write(format!("{}/changed.json", test_dir), r#"{"id":1}"#).unwrap();
+    fs::write(format!("{}/nested/also_changed.json", test_dir), r#"{"id":2}"#).unwrap();
+    fs::write(format!("{}/untouched.json", test_dir), r#"{"id":3}"#).unwrap();
+    
+    let mut child = Command::new(get_binary_path())
+        .args(["batch", "--input-dir", test_dir, "--output-dir", &output_dir, "--from", "json", "--files-from", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn batch command");
+    
+    // A deleted path, as `git diff --name-only` would list it, is skipped
+    let list = format!("{0}/changed.json\n{0}/nested/also_changed.json\n{0}/deleted.json\n", test_dir);
+    child.stdin.take().unwrap().write_all(list.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("Stderr: {}", stderr);
+    assert!(output.status.success());
+    assert!(Path::new(&format!("{}/changed.toon", output_dir)).exists());
+    assert!(Path::new(&format!("{}/nested/also_changed.toon", output_dir)).exists());
+    assert!(!Path::new(&format!("{}/untouched.toon", output_dir)).exists(), "Unlisted files are not converted");
+    assert!(stderr.contains("Skipping missing file"));
+    
+    let _ = fs::remove_dir_all(test_dir);
+    
+    println!("✓ Only the listed files are converted\n");
+}
@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path
+}
+
+#[test]
+fn test_convert_dir_mirrors_tree() {
+    println!("=== Build helper: convert_dir ===");
+
+    let root = temp_dir("build_helper_tree");
+    let (src, out) = (root.join("fixtures"), root.join("out"));
+    fs::create_dir_all(src.join("nested")).unwrap();
+    fs::write(src.join("users.json"), r#"{"users":[{"id":1,"name":"Alice"}]}"#).unwrap();
+    fs::write(src.join("nested/config.json"), r#"{"debug":true}"#).unwrap();
+    fs::write(src.join("README.md"), "not a fixture").unwrap();
+
+    let outputs = toonify::build::convert_dir(&src, &out).expect("convert_dir failed");
+    println!("Outputs: {:?}", outputs);
+
+    assert_eq!(outputs, vec![out.join("nested/config.toon"), out.join("users.toon")]);
+    assert!(fs::read_to_string(out.join("users.toon")).unwrap().contains("1,Alice"));
+    assert!(!out.join("README.toon").exists(), "Only JSON files are converted");
+
+    let _ = fs::remove_dir_all(&root);
+
+    println!("✓ Fixtures converted at matching paths\n");
+}
+
+#[test]
+fn test_convert_dir_leaves_unchanged_outputs_alone() {
+    println!("=== Build helper: unchanged outputs are not rewritten ===");
+
+    let root = temp_dir("build_helper_unchanged");
+    let (src, out) = (root.join("fixtures"), root.join("out"));
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("a.json"), r#"{"id":1}"#).unwrap();
+
+    toonify::build::convert_dir(&src, &out).unwrap();
+    let first = fs::metadata(out.join("a.toon")).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    toonify::build::convert_dir(&src, &out).unwrap();
+    let second = fs::metadata(out.join("a.toon")).unwrap().modified().unwrap();
+    assert_eq!(first, second, "Identical output should not be rewritten");
+
+    fs::write(src.join("bad.json"), "{nope").unwrap();
+    let err = toonify::build::convert_dir(&src, &out).unwrap_err();
+    assert!(err.contains("bad.json"), "Errors should name the file: {}", err);
+
+    let _ = fs::remove_dir_all(&root);
+
+    println!("✓ Rebuilds only touch changed outputs\n");
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::PathBuf;
+use toonify::cache_crypto::CacheCipher;
+
+const HEX_KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+#[test]
+fn test_encrypt_decrypt_roundtrip() {
+    println!("=== Cache encryption: Round trip ===");
+
+    let cipher = CacheCipher::from_material(HEX_KEY.as_bytes()).unwrap();
+    let lookup_key = cipher.lookup_key("toonify:json_to_toon:{\"email\":\"alice@example.com\"}");
+    let sealed = cipher.encrypt(&lookup_key, b"email: alice@example.com").unwrap();
+
+    assert!(!sealed.windows(5).any(|w| w == b"alice"), "Ciphertext must not contain the plaintext");
+    assert_eq!(cipher.decrypt(&lookup_key, &sealed).unwrap(), b"email: alice@example.com");
+
+    // Random nonces: the same value never seals to the same bytes
+    assert_ne!(sealed, cipher.encrypt(&lookup_key, b"email: alice@example.com").unwrap());
+    println!("[OK] Entries round-trip and are not stored in the clear");
+}
+
+#[test]
+fn test_lookup_keys_hide_payload() {
+    println!("=== Cache encryption: Lookup keys ===");
+
+    let cipher = CacheCipher::from_material(HEX_KEY.as_bytes()).unwrap();
+    let key = cipher.lookup_key("toonify:json_to_toon:{\"ssn\":\"123-45-6789\"}");
+
+    assert_eq!(key.len(), 32);
+    assert!(!String::from_utf8_lossy(&key).contains("123-45-6789"));
+    assert_eq!(key, cipher.lookup_key("toonify:json_to_toon:{\"ssn\":\"123-45-6789\"}"), "Lookup keys must be stable");
+    assert_ne!(key, cipher.lookup_key("toonify:toon_to_json:{\"ssn\":\"123-45-6789\"}"));
+    println!("[OK] Lookup keys are stable and opaque");
+}
+
+#[test]
+fn test_rejects_wrong_key_tampering_and_plaintext() {
+    println!("=== Cache encryption: Rejection ===");
+
+    let cipher = CacheCipher::from_material(HEX_KEY.as_bytes()).unwrap();
+    let other = CacheCipher::from_key([7u8; 32]);
+    let lookup_key = cipher.lookup_key("k");
+    let sealed = cipher.encrypt(&lookup_key, b"users[1]{id}:\n1\n").unwrap();
+
+    assert!(other.decrypt(&lookup_key, &sealed).is_err(), "Wrong key must fail");
+    assert!(cipher.decrypt(&cipher.lookup_key("other"), &sealed).is_err(), "Entry moved to another key must fail");
+
+    let mut tampered = sealed.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(cipher.decrypt(&lookup_key, &tampered).is_err(), "Tampered entry must fail");
+
+    assert!(cipher.decrypt(&lookup_key, b"users[1]{id}:\n1\n").is_err(), "Plaintext entry must fail");
+    println!("[OK] Wrong keys, moved, tampered and plaintext entries are rejected");
+}
+
+#[test]
+fn test_key_sources() {
+    println!("=== Cache encryption: Key sources ===");
+
+    let hex_file = temp_path("cache_key.hex");
+    fs::write(&hex_file, format!("{}\n", HEX_KEY)).unwrap();
+    let raw_file = temp_path("cache_key.bin");
+    fs::write(&raw_file, (0u8..32).collect::<Vec<u8>>()).unwrap();
+
+    let from_hex = CacheCipher::from_source(hex_file.to_str().unwrap()).unwrap();
+    let from_raw = CacheCipher::from_source(raw_file.to_str().unwrap()).unwrap();
+    assert_eq!(from_hex.lookup_key("k"), from_raw.lookup_key("k"), "Hex and raw keyfiles should load the same key");
+
+    unsafe { std::env::set_var("TOONIFY_TEST_CACHE_KEY", HEX_KEY) };
+    let from_env = CacheCipher::from_source("env:TOONIFY_TEST_CACHE_KEY").unwrap();
+    assert_eq!(from_env.lookup_key("k"), from_hex.lookup_key("k"));
+
+    assert!(CacheCipher::from_source("env:TOONIFY_TEST_CACHE_KEY_UNSET").is_err());
+    assert!(CacheCipher::from_material(b"too short").is_err());
+    assert!(CacheCipher::from_material(&[b'z'; 64]).is_err());
+    println!("[OK] Keys load from hex files, raw files and environment variables");
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use toonify::cache_invalidation::CacheInvalidation;
+use toonify::server::{EmbeddedServer, ServerBuilder};
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn cached_entries(server: &EmbeddedServer) -> u64 {
+    server.state().cache.moka.as_ref().unwrap().entry_count()
+}
+
+async fn settle(server: &EmbeddedServer) {
+    server.state().cache.moka.as_ref().unwrap().run_pending_tasks().await;
+}
+
+// Needs a Valkey or Redis server: TOONIFY_TEST_REDIS_URL=redis://127.0.0.1:6379 cargo test --features cluster-cache
+#[tokio::test]
+async fn test_cache_clear_reaches_other_nodes() {
+    println!("=== Cluster cache invalidation ===");
+
+    let Ok(url) = std::env::var("TOONIFY_TEST_REDIS_URL") else {
+        println!("⚠ TOONIFY_TEST_REDIS_URL not set, skipping\n");
+        return;
+    };
+    let node = || ServerBuilder::new().cache(100, None).cache_invalidation(CacheInvalidation::new(&url).unwrap()).build().unwrap();
+    let (first, second) = (node(), node());
+    let (first_base, second_base) = (spawn(first.router()).await, spawn(second.router()).await);
+    // Let both subscriptions come up
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    for base in [&first_base, &second_base] {
+        let response = client.post(format!("{}/json-to-toon", base)).json(&serde_json::json!({ "data": r#"{"a":1}"# })).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+    }
+    settle(&first).await;
+    settle(&second).await;
+    assert_eq!((cached_entries(&first), cached_entries(&second)), (1, 1));
+
+    let response = client.post(format!("{}/cache/clear", first_base)).send().await.unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["broadcast"], true);
+
+    let mut cleared = false;
+    for _ in 0..50 {
+        settle(&second).await;
+        if cached_entries(&second) == 0 {
+            cleared = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(cleared, "second node still holds cached results");
+    settle(&first).await;
+    assert_eq!(cached_entries(&first), 0);
+
+    println!("✓ /cache/clear on one node clears the others\n");
+}
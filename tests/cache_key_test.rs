@@ -0,0 +1,86 @@
+use toonify::guards::ParserGuards;
+use toonify::server::ServerBuilder;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    std::fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn json_to_toon(base: &str, data: &str, cache_control: Option<&str>) -> (u16, serde_json::Value) {
+    let mut request = reqwest::Client::new().post(format!("{}/json-to-toon", base)).json(&serde_json::json!({ "data": data }));
+    if let Some(value) = cache_control {
+        request = request.header("Cache-Control", value);
+    }
+    let response = request.send().await.unwrap();
+    (response.status().as_u16(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_cache_keys_include_options() {
+    println!("=== Cache keys: options fingerprint and no-store ===");
+
+    let path = temp_path("cache_key_test.db");
+    let _ = std::fs::remove_dir_all(&path);
+    let db = sled::open(&path).unwrap();
+    let nested = r#"{"a":{"b":{"c":1}}}"#;
+
+    let unguarded = ServerBuilder::new().persistent_cache(db.clone()).build().unwrap();
+    let base = spawn(unguarded.router()).await;
+    let (status, _) = json_to_toon(&base, nested, None).await;
+    assert_eq!(status, 200);
+    assert_eq!(db.len(), 1);
+    assert_eq!(db.open_tree("written").unwrap().len(), 1, "write time recorded for cache prune");
+
+    // no-store converts without reading or writing the cache
+    let (status, body) = json_to_toon(&base, r#"{"b":2}"#, Some("no-cache, no-store")).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["result"], "b:2");
+    assert_eq!(db.len(), 1);
+
+    // Same database, stricter guards: the unguarded entry must not answer
+    let guarded = ServerBuilder::new()
+        .persistent_cache(db.clone())
+        .guards(ParserGuards { max_depth: Some(2), ..Default::default() })
+        .build()
+        .unwrap();
+    let base = spawn(guarded.router()).await;
+    let (status, body) = json_to_toon(&base, nested, None).await;
+    assert_eq!(status, 400, "{}", body);
+    assert!(body["error"].as_str().unwrap().contains("--max-depth"), "{}", body);
+
+    println!("✓ Entries are keyed by options; no-store bypasses the cache\n");
+}
+
+#[tokio::test]
+async fn test_cache_clear_endpoint() {
+    println!("=== POST /cache/clear ===");
+
+    let path = temp_path("cache_clear_test.db");
+    let _ = std::fs::remove_dir_all(&path);
+    let db = sled::open(&path).unwrap();
+    let server = ServerBuilder::new().persistent_cache(db.clone()).build().unwrap();
+    let base = spawn(server.router()).await;
+
+    json_to_toon(&base, r#"{"a":1}"#, None).await;
+    assert_eq!(db.len(), 1);
+
+    let response = reqwest::Client::new().post(format!("{}/cache/clear", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, serde_json::json!({ "cleared": true, "broadcast": false }));
+    assert_eq!(db.len(), 0);
+    assert_eq!(db.open_tree("written").unwrap().len(), 0);
+
+    println!("✓ Cache cleared\n");
+}
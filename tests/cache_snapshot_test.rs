@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use toonify::cache_snapshot;
+use toonify::server::{create_moka_cache, ServerBuilder};
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    std::fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+#[tokio::test]
+async fn test_snapshot_round_trip() {
+    println!("=== Moka snapshot save and load ===");
+
+    let path = temp_path("moka_snapshot_round_trip.jsonl");
+    let _ = std::fs::remove_file(&path);
+
+    let moka = create_moka_cache(16, None);
+    moka.insert("first".to_string(), "a:1".to_string()).await;
+    moka.insert("second".to_string(), "b:2".to_string()).await;
+    assert_eq!(cache_snapshot::save(&path, &moka).await.unwrap(), 2);
+
+    let restored = create_moka_cache(16, None);
+    assert_eq!(cache_snapshot::load(&path, &restored, None).await.unwrap(), 2);
+    assert_eq!(restored.get("first").await.as_deref(), Some("a:1"));
+    assert_eq!(restored.get("second").await.as_deref(), Some("b:2"));
+
+    // No snapshot yet is not an error
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(cache_snapshot::load(&path, &restored, None).await.unwrap(), 0);
+
+    println!("✓ Snapshot restored\n");
+}
+
+#[tokio::test]
+async fn test_snapshot_from_other_version_or_past_ttl_is_ignored() {
+    println!("=== Stale Moka snapshots ===");
+
+    let path = temp_path("moka_snapshot_stale.jsonl");
+    std::fs::write(&path, "{\"toonify\":\"0.0.1\",\"written\":0}\n{\"key\":\"k\",\"value\":\"v\"}\n").unwrap();
+    let moka = create_moka_cache(16, None);
+    assert!(cache_snapshot::load(&path, &moka, None).await.unwrap_err().contains("0.0.1"));
+
+    let header = format!("{{\"toonify\":\"{}\",\"written\":0}}", env!("CARGO_PKG_VERSION"));
+    std::fs::write(&path, format!("{}\n{{\"key\":\"k\",\"value\":\"v\"}}\n", header)).unwrap();
+    assert!(cache_snapshot::load(&path, &moka, Some(Duration::from_secs(60))).await.unwrap_err().contains("TTL"));
+    assert_eq!(cache_snapshot::load(&path, &moka, None).await.unwrap(), 1);
+
+    let _ = std::fs::remove_file(&path);
+    println!("✓ Stale snapshots skipped\n");
+}
+
+#[tokio::test]
+async fn test_server_restarts_warm() {
+    println!("=== ServerBuilder::cache_snapshot ===");
+
+    let path = temp_path("moka_snapshot_server.jsonl");
+    let _ = std::fs::remove_file(&path);
+    let interval = Duration::from_secs(3600);
+
+    let server = ServerBuilder::new().cache(16, None).cache_snapshot(&path, interval).build().unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = server.router();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let response = reqwest::Client::new()
+        .post(format!("{}/json-to-toon", base))
+        .json(&serde_json::json!({ "data": r#"{"a":1}"# }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(server.save_cache_snapshot().await.unwrap(), Some(1));
+
+    // The restarted server loads the snapshot in the background
+    let restarted = ServerBuilder::new().cache(16, None).cache_snapshot(&path, interval).build().unwrap();
+    let moka = restarted.state().cache.moka.clone().unwrap();
+    for _ in 0..50 {
+        if moka.entry_count() > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        moka.run_pending_tasks().await;
+    }
+    assert_eq!(moka.iter().next().map(|(_, value)| value).as_deref(), Some("a:1"));
+
+    assert!(ServerBuilder::new().cache_snapshot(&path, interval).build().is_err());
+    let _ = std::fs::remove_file(&path);
+    println!("✓ Restarted with a warm cache\n");
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::chunk::{chunk, CHUNK_ENTITY};
+use toonify::converter;
+use toonify::tokens::Tokenizer;
+use toonify::toon::SerializeOptions;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("chunk");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path
+}
+
+fn document() -> Value {
+    let users: Vec<Value> = (1..=300).map(|id| json!({"id": id, "name": format!("user{}", id), "role": "member"})).collect();
+    json!({
+        "settings": {"theme": "dark", "locale": "en"},
+        "users": users,
+        "page": 1
+    })
+}
+
+fn count(text: &str) -> usize {
+    Tokenizer::Estimate.count(text)
+}
+
+fn parse(toon: &str) -> Value {
+    serde_json::from_str(&converter::toon_to_json(toon).expect("Every chunk should parse on its own")).unwrap()
+}
+
+#[test]
+fn test_chunks_fit_budget_and_reassemble() {
+    println!("=== Chunk: budget and reassembly ===");
+
+    let original = document();
+    let chunks = chunk(&original, 500, &SerializeOptions::default(), &count).unwrap();
+    println!("{} chunks: {:?}", chunks.len(), chunks.iter().map(|chunk| chunk.tokens).collect::<Vec<_>>());
+    assert!(chunks.len() > 2, "300 rows should not fit in 500 tokens");
+
+    let mut users = Vec::new();
+    let mut rest = serde_json::Map::new();
+    for piece in &chunks {
+        assert!(piece.tokens <= 500, "Chunk over budget:\n{}", piece.output);
+        assert_eq!(piece.tokens, count(&piece.output));
+        for (name, entity) in parse(&piece.output).as_object().unwrap() {
+            match name.as_str() {
+                CHUNK_ENTITY => {}
+                "users" => users.extend(entity.as_array().unwrap().iter().cloned()),
+                _ => {
+                    rest.insert(name.clone(), entity.clone());
+                }
+            }
+        }
+    }
+    assert_eq!(Value::Array(users), original["users"], "Rows should come back complete and in order");
+    assert_eq!(rest.get("settings"), original.get("settings"));
+    assert_eq!(rest.get("page"), original.get("page"));
+
+    println!("✓ Every chunk within budget, rows reassembled\n");
+}
+
+#[test]
+fn test_chunk_footer_indexes_rows() {
+    let chunks = chunk(&document(), 500, &SerializeOptions::default(), &count).unwrap();
+    let total = chunks.len();
+
+    let mut next_offset = 0;
+    for (index, piece) in chunks.iter().enumerate() {
+        assert!(piece.output.contains("_chunk["), "Missing footer:\n{}", piece.output);
+        let footer = parse(&piece.output)[CHUNK_ENTITY].clone();
+        for row in footer.as_array().unwrap() {
+            assert_eq!(row["index"], json!(index + 1));
+            assert_eq!(row["chunks"], json!(total));
+            if row["entity"] == json!("users") {
+                assert_eq!(row["offset"], json!(next_offset), "Runs should be consecutive");
+                assert_eq!(row["total"], json!(300));
+                next_offset += row["rows"].as_u64().unwrap();
+            } else {
+                assert_eq!(row["offset"], Value::Null, "Unsplit entities have no row range");
+            }
+        }
+    }
+    assert_eq!(next_offset, 300);
+}
+
+#[test]
+fn test_small_entities_share_a_chunk() {
+    let chunks = chunk(&json!({"a": 1, "b": {"x": "y"}, "c": [{"id": 1}]}), 4000, &SerializeOptions::default(), &count).unwrap();
+    assert_eq!(chunks.len(), 1);
+    assert!(chunks[0].output.starts_with("a:1\n"), "Output:\n{}", chunks[0].output);
+    assert!(chunks[0].output.ends_with("_chunk[3]{index,chunks,entity,offset,rows,total}:\n1,1,a,,,\n1,1,b,,,\n1,1,c,0,1,1\n"), "Output:\n{}", chunks[0].output);
+}
+
+#[test]
+fn test_chunk_rejects_what_cannot_fit() {
+    let wide = json!({"notes": [{"text": "word ".repeat(200)}]});
+    let err = chunk(&wide, 50, &SerializeOptions::default(), &count).unwrap_err();
+    assert!(err.contains("Row 0 of 'notes'"), "Error: {}", err);
+
+    let object = json!({"config": {"description": "word ".repeat(200)}});
+    let err = chunk(&object, 50, &SerializeOptions::default(), &count).unwrap_err();
+    assert!(err.contains("not a table that can be split"), "Error: {}", err);
+
+    let err = chunk(&json!({"_chunk": 1}), 50, &SerializeOptions::default(), &count).unwrap_err();
+    assert!(err.contains("reserved"), "Error: {}", err);
+}
+
+#[test]
+fn test_chunk_cli_writes_numbered_files() {
+    println!("=== Chunk: CLI ===");
+
+    let dir = temp_dir("cli");
+    let input = dir.join("data.json");
+    fs::write(&input, serde_json::to_string(&document()).unwrap()).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("chunk")
+        .arg("--max-tokens")
+        .arg("500")
+        .arg(&input)
+        .arg("--output-dir")
+        .arg(dir.join("chunks"))
+        .output()
+        .expect("Failed to execute chunk command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Chunk should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("chunks of at most 500 tokens"));
+
+    let first = fs::read_to_string(dir.join("chunks/chunk-0001.toon")).expect("First chunk should exist");
+    assert!(first.contains("settings{"));
+    let files = fs::read_dir(dir.join("chunks")).unwrap().count();
+    assert!(files > 2);
+    let last = fs::read_to_string(dir.join(format!("chunks/chunk-{:04}.toon", files))).expect("Chunks are numbered without gaps");
+    assert!(last.contains(&format!("{},{},", files, files)), "Last chunk should know its place:\n{}", last);
+
+    println!("✓ {} chunk files\n", files);
+}
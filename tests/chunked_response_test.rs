@@ -0,0 +1,106 @@
+use serde_json::{json, Value};
+
+use toonify::converter::Converter;
+use toonify::server::ServerBuilder;
+use toonify::toon::{serialize_toon_chunked, serialize_toon_with, SerializeOptions};
+
+fn large_document(rows: usize) -> Value {
+    let users: Vec<Value> = (0..rows)
+        .map(|i| json!({ "id": i, "name": format!("user {}", i), "email": format!("u{}@example.com", i), "note": "ünïcode, \"quoted\"" }))
+        .collect();
+    json!({ "version": "1.0", "users": users, "tags": ["a", "b"], "owner": { "id": 1, "name": "Alice" } })
+}
+
+fn collect(emit_into: impl FnOnce(&mut dyn FnMut(&[u8]) -> Result<(), String>) -> Result<(), String>) -> (String, usize) {
+    let mut bytes = Vec::new();
+    let mut chunks = 0;
+    emit_into(&mut |chunk: &[u8]| {
+        bytes.extend_from_slice(chunk);
+        chunks += 1;
+        Ok(())
+    })
+    .unwrap();
+    (String::from_utf8(bytes).unwrap(), chunks)
+}
+
+#[test]
+fn test_chunked_output_matches_buffered() {
+    println!("=== Chunked emit: same text as buffered ===");
+
+    let value = large_document(2_000);
+    let options = SerializeOptions::default();
+    let expected = serialize_toon_with(&value, &options).unwrap();
+    for chunk_size in [1, 7, 4096, 1 << 20] {
+        let (toon, chunks) = collect(|emit| serialize_toon_chunked(&value, &options, chunk_size, emit));
+        assert_eq!(toon, expected, "chunk size {}", chunk_size);
+        println!("chunk size {}: {} chunks", chunk_size, chunks);
+    }
+    let (_, chunks) = collect(|emit| serialize_toon_chunked(&value, &options, 4096, emit));
+    assert!(chunks > 10, "a large table is split between rows");
+
+    let converter = Converter::builder().build();
+    let (json_text, chunks) = collect(|emit| converter.emit_chunked(&value, "json", 4096, emit));
+    assert_eq!(json_text, converter.emit(&value, "json").unwrap());
+    assert!(chunks > 10);
+
+    // An error from the sink stops serialization
+    let result = serialize_toon_chunked(&value, &options, 16, &mut |_: &[u8]| Err("closed".to_string()));
+    assert_eq!(result, Err("closed".to_string()));
+
+    println!("✓ Chunked TOON and JSON match the buffered output\n");
+}
+
+async fn spawn(builder: ServerBuilder) -> String {
+    let app = builder.build().unwrap().router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_convert_streams_large_results() {
+    println!("=== /convert: streamed response ===");
+
+    let base = spawn(ServerBuilder::new()).await;
+    let input = large_document(20_000).to_string();
+    let expected = toonify::converter::json_to_toon(&input).unwrap();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/convert", base))
+        .header("content-type", "application/json")
+        .header("accept", "application/toon")
+        .body(input.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.content_length().is_none(), "chunked, not a buffered body");
+    assert!(response.headers().contains_key("etag"));
+    assert_eq!(response.text().await.unwrap(), expected);
+
+    // Errors before the first byte are still ordinary responses
+    let response = client
+        .post(format!("{}/convert", base))
+        .header("content-type", "application/json")
+        .body("{broken")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    // With a result cache the response is buffered
+    let cached = spawn(ServerBuilder::new().cache(16, None)).await;
+    let response = client
+        .post(format!("{}/convert", cached))
+        .header("content-type", "application/json")
+        .body(input)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.content_length(), Some(expected.len() as u64));
+    assert_eq!(response.text().await.unwrap(), expected);
+
+    println!("✓ Large conversions stream in chunks\n");
+}
@@ -0,0 +1,131 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use toonify::circuit_breaker::{BreakerOptions, CircuitBreaker};
+use toonify::conversion_cache::ConversionCache;
+use toonify::server::ServerBuilder;
+
+// An embedder cache whose backend is down until `up` is set
+#[derive(Default)]
+struct FlakyCache {
+    up: AtomicBool,
+    calls: AtomicUsize,
+}
+
+impl ConversionCache for FlakyCache {
+    fn get(&self, _key: &str) -> Result<Option<String>, String> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if self.up.load(Ordering::Relaxed) { Ok(None) } else { Err("connection refused".to_string()) }
+    }
+
+    fn put(&self, _key: &str, _value: &str) -> Result<(), String> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if self.up.load(Ordering::Relaxed) { Ok(()) } else { Err("connection refused".to_string()) }
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_breaker_opens_probes_and_closes() {
+    println!("=== CircuitBreaker state changes ===");
+
+    let options = BreakerOptions { failure_threshold: 3, slow_call: Duration::from_millis(100), open_for: Duration::from_millis(50) };
+    let breaker = CircuitBreaker::new("test", options);
+
+    for _ in 0..3 {
+        assert!(breaker.allow());
+        breaker.record(false, Duration::ZERO);
+    }
+    assert_eq!(breaker.stats().state, "open");
+    assert!(!breaker.allow());
+
+    // One probe after open_for; nothing else gets through while it runs
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(breaker.allow());
+    assert_eq!(breaker.stats().state, "half-open");
+    assert!(!breaker.allow());
+    breaker.record(true, Duration::from_millis(1));
+    assert_eq!(breaker.stats().state, "closed");
+
+    // A slow success counts as a failure
+    for _ in 0..3 {
+        assert!(breaker.allow());
+        breaker.record(true, Duration::from_millis(200));
+    }
+    let stats = breaker.stats();
+    assert_eq!((stats.state, stats.trips, stats.bypassed), ("open", 2, 2));
+
+    println!("✓ Breaker opened, probed and closed\n");
+}
+
+#[test]
+fn test_dropped_probe_reopens_breaker() {
+    println!("=== CircuitBreaker permit dropped mid-probe ===");
+
+    let options = BreakerOptions { failure_threshold: 1, slow_call: Duration::from_millis(100), open_for: Duration::from_millis(50) };
+    let breaker = CircuitBreaker::new("test", options);
+    breaker.permit().unwrap().record(false);
+    assert_eq!(breaker.stats().state, "open");
+
+    // A cancelled request drops its probe without an outcome
+    std::thread::sleep(Duration::from_millis(60));
+    drop(breaker.permit().unwrap());
+    assert_eq!(breaker.stats().state, "open");
+
+    std::thread::sleep(Duration::from_millis(60));
+    breaker.permit().unwrap().record(true);
+    assert_eq!(breaker.stats().state, "closed");
+
+    println!("✓ Dropped probe counted as a failure\n");
+}
+
+#[tokio::test]
+async fn test_server_bypasses_failing_cache() {
+    println!("=== Server bypasses a failing embedder cache ===");
+
+    let cache = Arc::new(FlakyCache::default());
+    let options = BreakerOptions { failure_threshold: 2, open_for: Duration::from_millis(200), ..Default::default() };
+    let server = ServerBuilder::new().conversion_cache(cache.clone()).cache_breaker(options).build().unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = server.router();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let client = reqwest::Client::new();
+    let convert = |n: usize| {
+        client
+            .post(format!("{}/json-to-toon", base))
+            .json(&serde_json::json!({ "data": format!(r#"{{"a":{}}}"#, n) }))
+            .send()
+    };
+
+    // The get and put of the first request fail and open the breaker;
+    // conversions keep succeeding without touching the cache
+    for n in 0..4 {
+        let response = convert(n).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["result"], format!("a:{}", n));
+    }
+    assert_eq!(cache.calls.load(Ordering::Relaxed), 2);
+
+    let status: serde_json::Value = client.get(format!("{}/cache/status", base)).send().await.unwrap().json().await.unwrap();
+    let custom = &status["backends"][0];
+    assert_eq!(custom["backend"], "custom");
+    assert_eq!(custom["state"], "open");
+    assert_eq!(custom["trips"], 1);
+    assert_eq!(custom["bypassed"], 6);
+
+    // Once the backend is back, the next probe closes the breaker
+    cache.up.store(true, Ordering::Relaxed);
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(convert(10).await.unwrap().status(), 200);
+    let status: serde_json::Value = client.get(format!("{}/cache/status", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(status["backends"][0]["state"], "closed");
+
+    println!("✓ Failing cache bypassed and recovered\n");
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn convert(input: &Path, cache: &Path, extra: &[&str]) -> Output {
+    let output = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(input)
+        .arg("--cache-dir")
+        .arg(cache)
+        .args(extra)
+        .output()
+        .expect("Failed to execute convert");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+#[test]
+fn test_convert_reuses_cached_output() {
+    println!("=== CLI cache: hits across invocations ===");
+
+    let cache = temp_path("cli_cache");
+    let _ = fs::remove_dir_all(&cache);
+    let input = temp_path("cli_cache_input.json");
+    fs::write(&input, r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#).unwrap();
+
+    let first = convert(&input, &cache, &[]);
+    assert!(String::from_utf8_lossy(&first.stderr).contains("[CACHE] Stored"));
+
+    let second = convert(&input, &cache, &[]);
+    assert!(String::from_utf8_lossy(&second.stderr).contains("[CACHE] Hit"));
+    assert_eq!(first.stdout, second.stdout);
+
+    // Different options are a different entry
+    let typed = convert(&input, &cache, &["--typed-headers"]);
+    assert!(!String::from_utf8_lossy(&typed.stderr).contains("[CACHE] Hit"));
+    assert_ne!(typed.stdout, first.stdout);
+
+    // So is different content
+    fs::write(&input, r#"{"users":[{"id":3,"name":"Carol"}]}"#).unwrap();
+    let changed = convert(&input, &cache, &[]);
+    assert!(!String::from_utf8_lossy(&changed.stderr).contains("[CACHE] Hit"));
+    assert!(String::from_utf8_lossy(&changed.stdout).contains("Carol"));
+
+    let output = Command::new(get_binary_path())
+        .args(["cache", "gc", "--max-age-days", "30", "--max-size-mb", "0", "--cache-dir"])
+        .arg(&cache)
+        .output()
+        .expect("Failed to execute cache gc");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("Removed 3 entries"), "everything is over a zero size limit");
+    assert!(fs::read_dir(&cache).unwrap().next().is_none(), "empty shards are removed");
+
+    println!("=== CLI cache PASSED ===");
+}
@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use toonify::client_gen::{generate, ClientLang, ENDPOINTS};
+use toonify::server::ServerBuilder;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("client_gen");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    path
+}
+
+fn client(lang: ClientLang) -> String {
+    generate(lang).remove(0).contents
+}
+
+#[test]
+fn test_every_endpoint_becomes_a_method() {
+    println!("=== gen-client: methods per language ===");
+
+    let python = client(ClientLang::Python);
+    assert!(python.contains("def json_to_toon(self, data):"));
+    assert!(python.contains("def convert(self, data, source, target):"));
+    assert!(python.contains(r#"self._post(f"/convert/{urllib.parse.quote(source, safe='')}/{urllib.parse.quote(target, safe='')}", data)"#));
+
+    let typescript = client(ClientLang::TypeScript);
+    assert!(typescript.contains("toonToJson(data: string): Promise<string>"));
+    assert!(typescript.contains("this.post(`/convert/${encodeURIComponent(source)}/${encodeURIComponent(target)}`, data)"));
+
+    let go = client(ClientLang::Go);
+    assert!(go.contains("package toonify"));
+    assert!(go.contains("// JsonToToon converts JSON to TOON (POST /json-to-toon)"));
+    assert!(go.contains(r#"return c.post(ctx, "/convert/" + url.PathEscape(source) + "/" + url.PathEscape(target), data)"#));
+
+    for (lang, source) in [("python", &python), ("typescript", &typescript), ("go", &go)] {
+        assert!(source.contains(env!("CARGO_PKG_VERSION")), "{} client names the server version", lang);
+    }
+
+    println!("✓ {} endpoints rendered for each language\n", ENDPOINTS.len());
+}
+
+#[test]
+fn test_lang_names() {
+    assert_eq!("py".parse::<ClientLang>(), Ok(ClientLang::Python));
+    assert_eq!("TypeScript".parse::<ClientLang>(), Ok(ClientLang::TypeScript));
+    assert_eq!("golang".parse::<ClientLang>(), Ok(ClientLang::Go));
+    assert!("rust".parse::<ClientLang>().unwrap_err().contains("expected python, typescript or go"));
+}
+
+// The clients are only as good as the routes they call
+#[tokio::test]
+async fn test_endpoints_exist_on_the_server() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = ServerBuilder::new().build().unwrap().router();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    for endpoint in ENDPOINTS {
+        let path = endpoint.path.replace("{source}", "json").replace("{target}", "toon");
+        let data = if path == "/toon-to-json" { "id: 1" } else { r#"{"id":1}"# };
+        let response = reqwest::Client::new()
+            .post(format!("http://{}{}", addr, path))
+            .json(&serde_json::json!({ "data": data }))
+            .send()
+            .await
+            .unwrap();
+        assert_ne!(response.status(), 404, "{} is not routed", path);
+        let reply: serde_json::Value = response.json().await.unwrap();
+        assert!(reply["result"].is_string(), "{}: {}", path, reply);
+    }
+}
+
+#[test]
+fn test_gen_client_cli() {
+    println!("=== gen-client: CLI ===");
+
+    let out = temp_dir("go");
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("gen-client")
+        .arg("--lang")
+        .arg("go")
+        .arg("--out")
+        .arg(&out)
+        .output()
+        .expect("Failed to execute gen-client command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "gen-client should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Wrote 2 files"));
+
+    assert!(fs::read_to_string(out.join("toonify/client.go")).unwrap().contains("func NewClient(baseURL string) *Client"));
+    assert!(fs::read_to_string(out.join("converter.proto")).unwrap().contains("service ConverterService"));
+
+    println!("✓ Go client and proto written\n");
+}
@@ -0,0 +1,74 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use toonify::highlight::highlight_toon;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut output = String::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            for next in chars.by_ref() {
+                if next == 'm' {
+                    break;
+                }
+            }
+        } else {
+            output.push(ch);
+        }
+    }
+    output
+}
+
+fn run_convert(color: &str) -> String {
+    let mut child = Command::new(get_binary_path())
+        .args(["convert", "-", "--color", color])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute toonify binary");
+    child.stdin.take().unwrap().write_all(br#"{"users":[{"id":1,"name":"Alice"}]}"#).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_highlight_toon_preserves_text() {
+    println!("=== Color: TOON highlighting ===");
+
+    let toon = "users[2]{id,name,active}:\n1,Alice,true\n2,\"Bob, Jr.\",\nstatus:ok";
+    let colored = highlight_toon(toon);
+    println!("{}", colored);
+
+    assert!(colored.contains("\x1b["), "Output should contain ANSI escapes");
+    assert_eq!(strip_ansi(&colored), toon, "Removing colors should give back the input");
+
+    println!("✓ Highlighting adds colors only\n");
+}
+
+#[test]
+fn test_cli_color_modes() {
+    println!("=== CLI: --color always / never ===");
+
+    let plain = run_convert("never");
+    assert!(!plain.contains('\x1b'), "--color never should not emit escapes");
+
+    let colored = run_convert("always");
+    assert!(colored.contains("\x1b["), "--color always should emit escapes");
+    assert_eq!(strip_ansi(&colored), plain);
+
+    // Piped stdout is not a terminal, so auto stays plain
+    assert_eq!(run_convert("auto"), plain);
+
+    println!("✓ Color modes respected\n");
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn convert(config: &Path, env: &[(&str, &str)], args: &[&str]) -> Output {
+    // One input per config, so tests running in parallel don't share it
+    let stem = config.file_stem().unwrap().to_string_lossy();
+    let input = temp_path(&format!("{}_input.json", stem));
+    fs::write(&input, r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#).unwrap();
+    Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", config)
+        .env_remove("TOONIFY_TYPED_HEADERS")
+        .envs(env.iter().copied())
+        .arg("convert")
+        .arg(&input)
+        .args(args)
+        .output()
+        .expect("Failed to execute convert")
+}
+
+#[test]
+fn test_config_file_sets_defaults() {
+    println!("=== Config: file defaults ===");
+
+    let config = temp_path("config_defaults.toml");
+    fs::write(&config, "color = \"never\"\n\n[convert]\ntyped_headers = true\n").unwrap();
+
+    let output = convert(&config, &[], &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("id:int"), "[convert] typed_headers applies");
+
+    println!("=== Config file defaults PASSED ===");
+}
+
+#[test]
+fn test_environment_overrides_config_and_flags_override_both() {
+    println!("=== Config: precedence ===");
+
+    let config = temp_path("config_precedence.toml");
+    fs::write(&config, "[convert]\ntyped_headers = true\nduplicate_keys = \"error\"\n").unwrap();
+
+    let output = convert(&config, &[("TOONIFY_TYPED_HEADERS", "false")], &[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("id:int"), "environment beats the config file");
+
+    let output = convert(&config, &[("TOONIFY_TYPED_HEADERS", "false")], &["--typed-headers"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("id:int"), "flags beat the environment");
+
+    println!("=== Config precedence PASSED ===");
+}
+
+#[test]
+fn test_config_rejects_unknown_keys() {
+    println!("=== Config: unknown keys ===");
+
+    let config = temp_path("config_typo.toml");
+    fs::write(&config, "[convert]\ntyped_header = true\n").unwrap();
+
+    let output = convert(&config, &[], &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("{}", stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("convert.typed_header is not an option of convert"));
+
+    fs::write(&config, "[convertt]\nparallel = true\n").unwrap();
+    let output = convert(&config, &[], &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[convertt] is not a toonify command"));
+
+    println!("=== Config unknown keys PASSED ===");
+}
+
+#[test]
+fn test_missing_explicit_config_is_an_error() {
+    let output = convert(&temp_path("config_missing.toml"), &[], &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to read config"));
+
+    let output = convert(Path::new("none"), &[], &[]);
+    assert!(output.status.success(), "TOONIFY_CONFIG=none skips the file");
+}
@@ -0,0 +1,65 @@
+use toonify::server::ServerBuilder;
+
+async fn spawn() -> String {
+    let app = ServerBuilder::new().build().unwrap().router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+const JSON: &str = r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#;
+const TOON: &str = "users[2]{id,name}:\n1,Alice\n2,Bob";
+
+async fn convert(base: &str, content_type: &str, accept: Option<&str>, body: &str) -> reqwest::Response {
+    let request = reqwest::Client::new().post(format!("{}/convert", base)).header("content-type", content_type).body(body.to_string());
+    let request = match accept {
+        Some(accept) => request.header("accept", accept),
+        None => request,
+    };
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_convert_negotiates_direction() {
+    println!("=== Content negotiation: /convert ===");
+
+    let base = spawn().await;
+
+    let response = convert(&base, "application/json", Some("application/toon"), JSON).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/toon; charset=utf-8");
+    assert_eq!(response.text().await.unwrap(), TOON);
+
+    let response = convert(&base, "text/toon; charset=utf-8", Some("application/json"), TOON).await;
+    assert_eq!(response.headers()["content-type"], "application/json; charset=utf-8");
+    let value: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(value, serde_json::from_str::<serde_json::Value>(JSON).unwrap());
+
+    // No Accept (or a wildcard): the other side of JSON ↔ TOON
+    assert_eq!(convert(&base, "application/json", None, JSON).await.text().await.unwrap(), TOON);
+    let response = convert(&base, "application/toon", Some("*/*"), TOON).await;
+    assert_eq!(response.headers()["content-type"], "application/json; charset=utf-8");
+
+    // Highest quality wins
+    let response = convert(&base, "application/json", Some("application/json;q=0.5, application/toon;q=0.9"), JSON).await;
+    assert_eq!(response.headers()["content-type"], "application/toon; charset=utf-8");
+
+    println!("✓ Direction from Content-Type, target from Accept\n");
+}
+
+#[tokio::test]
+async fn test_convert_rejects_unknown_media_types() {
+    println!("=== Content negotiation: rejections ===");
+
+    let base = spawn().await;
+
+    assert_eq!(convert(&base, "application/octet-stream", None, JSON).await.status(), 415);
+    assert_eq!(convert(&base, "application/json", Some("image/png"), JSON).await.status(), 406);
+
+    let response = convert(&base, "application/json", Some("application/toon"), "{not json").await;
+    assert_eq!(response.status(), 400);
+    assert!(response.text().await.unwrap().contains("Invalid JSON"));
+
+    println!("✓ 415 / 406 / 400 as appropriate\n");
+}
@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use toonify::conversion_cache::{ConversionCache, MemoryCache};
+use toonify::server::ServerBuilder;
+use toonify::CachedConverter;
+
+// A MemoryCache that counts lookups, standing in for an embedder's Redis wrapper
+#[derive(Default)]
+struct CountingCache {
+    inner: MemoryCache,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl ConversionCache for CountingCache {
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        let found = self.inner.get(key)?;
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        Ok(found)
+    }
+
+    fn put(&self, key: &str, value: &str) -> Result<(), String> {
+        self.inner.put(key, value)
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        self.inner.clear()
+    }
+}
+
+#[test]
+fn test_cached_converter_reads_through_custom_cache() {
+    println!("=== CachedConverter with a custom cache ===");
+
+    let cache = Arc::new(CountingCache::default());
+    let converter = CachedConverter::with_cache(cache.clone());
+    let json = r#"{"users":[{"id":1,"name":"Alice"}]}"#.to_string();
+
+    let first = converter.json_to_toon(json.clone()).unwrap();
+    let second = converter.json_to_toon(json).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(first.trim_end(), "users[1]{id,name}:\n1,Alice");
+    assert_eq!((cache.misses.load(Ordering::Relaxed), cache.hits.load(Ordering::Relaxed)), (1, 1));
+    assert_eq!(cache.inner.len(), 1);
+
+    converter.clear_cache();
+    assert!(cache.inner.is_empty());
+
+    println!("✓ Custom cache read through and cleared\n");
+}
+
+#[tokio::test]
+async fn test_server_reads_through_custom_cache() {
+    println!("=== ServerBuilder::conversion_cache ===");
+
+    let cache = Arc::new(CountingCache::default());
+    let server = ServerBuilder::new().conversion_cache(cache.clone()).build().unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = server.router();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let client = reqwest::Client::new();
+    for _ in 0..2 {
+        let response = client
+            .post(format!("{}/json-to-toon", base))
+            .json(&serde_json::json!({ "data": r#"{"a":1}"# }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["result"], "a:1");
+    }
+    assert_eq!((cache.misses.load(Ordering::Relaxed), cache.hits.load(Ordering::Relaxed)), (1, 1));
+
+    let response = client.post(format!("{}/cache/clear", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(cache.inner.is_empty());
+
+    println!("✓ Server results cached in the embedder's cache\n");
+}
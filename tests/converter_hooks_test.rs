@@ -0,0 +1,98 @@
+use serde_json::{json, Value};
+use toonify::converter::{Converter, ConverterBuilder};
+
+#[test]
+fn test_pre_hook_renames_fields() {
+    println!("=== Converter Hooks: Field renaming pre-hook ===");
+
+    let converter = Converter::builder()
+        .with_pre_hook(|mut value: Value| {
+            if let Some(users) = value.get_mut("users").and_then(|v| v.as_array_mut()) {
+                for user in users {
+                    if let Some(obj) = user.as_object_mut()
+                        && let Some(name) = obj.remove("full_name")
+                    {
+                        obj.insert("name".to_string(), name);
+                    }
+                }
+            }
+            Ok(value)
+        })
+        .build();
+
+    let toon = converter
+        .json_to_toon(r#"{"users":[{"id":1,"full_name":"Alice"}]}"#)
+        .expect("Conversion failed");
+    println!("TOON:\n{}", toon);
+
+    assert!(toon.contains("users[1]{id,name}:"), "Field should be renamed before serialization");
+
+    println!("✓ Pre-hook applied\n");
+}
+
+#[test]
+fn test_hooks_run_in_order() {
+    println!("=== Converter Hooks: Ordering ===");
+
+    let converter = ConverterBuilder::new()
+        .with_post_hook(|mut value: Value| {
+            value["steps"].as_array_mut().unwrap().push(json!("post"));
+            Ok(value)
+        })
+        .with_pre_hook(|mut value: Value| {
+            value["steps"] = json!(["pre1"]);
+            Ok(value)
+        })
+        .with_pre_hook(|mut value: Value| {
+            value["steps"].as_array_mut().unwrap().push(json!("pre2"));
+            Ok(value)
+        })
+        .build();
+
+    let output = converter.convert("{}", "json", "json").expect("Conversion failed");
+    let value: Value = serde_json::from_str(&output).unwrap();
+    println!("Output: {}", value);
+
+    assert_eq!(value["steps"], json!(["pre1", "pre2", "post"]), "Pre-hooks run before post-hooks, in registration order");
+
+    println!("✓ Hooks ran in order\n");
+}
+
+#[test]
+fn test_hook_error_aborts_conversion() {
+    println!("=== Converter Hooks: Error propagation ===");
+
+    let converter = Converter::builder()
+        .with_post_hook(|value: Value| {
+            if value.get("secret").is_some() {
+                Err("secret field not allowed".to_string())
+            } else {
+                Ok(value)
+            }
+        })
+        .build();
+
+    let result = converter.json_to_toon(r#"{"secret":"x"}"#);
+    println!("Result: {:?}", result);
+
+    assert_eq!(result, Err("secret field not allowed".to_string()));
+    assert!(converter.json_to_toon(r#"{"public":"y"}"#).is_ok());
+
+    println!("✓ Hook errors abort conversion\n");
+}
+
+#[test]
+fn test_default_converter_matches_free_functions() {
+    println!("=== Converter Hooks: Default converter ===");
+
+    let json = r#"{"items":[{"id":1,"tag":"a"},{"id":2,"tag":"b"}]}"#;
+    let converter = Converter::default();
+
+    assert_eq!(
+        converter.json_to_toon(json).unwrap(),
+        toonify::converter::json_to_toon(json).unwrap(),
+        "A converter without hooks should match json_to_toon"
+    );
+
+    println!("✓ Default converter matches free functions\n");
+}
@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use toonify::corpus;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn corpus_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("corpus")
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+#[test]
+fn test_builtin_corpus_passes() {
+    println!("=== Corpus: built-in cases ===");
+
+    for case in corpus::builtin() {
+        println!("{}", case.name);
+        if let Err(e) = corpus::check(&case) {
+            panic!("{}: {}", case.name, e);
+        }
+    }
+
+    println!("✓ All corpus cases round-trip\n");
+}
+
+#[test]
+fn test_every_corpus_file_is_built_in() {
+    let on_disk: Vec<String> = corpus::load_dir(&corpus_dir()).unwrap().into_iter().map(|case| case.name).collect();
+    let mut built_in: Vec<String> = corpus::builtin().into_iter().map(|case| case.name).collect();
+    built_in.sort();
+    assert_eq!(on_disk, built_in, "add new tests/corpus cases to BUILTIN in src/corpus.rs");
+}
+
+#[test]
+fn test_corpus_run_command() {
+    println!("=== Corpus: toonify corpus run ===");
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["corpus", "run"])
+        .output()
+        .expect("Failed to execute corpus run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains(&format!("{} corpus cases passed", corpus::builtin().len())));
+
+    // A case whose expected TOON no longer matches
+    let dir = temp_path("corpus_broken");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("users.json"), r#"{"users":[{"id":1,"name":"Alice"}]}"#).unwrap();
+    fs::write(dir.join("users.toon"), "users[1]{id,name}:\r\n1,Alicia\r\n").unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["corpus", "run", "--dir"])
+        .arg(&dir)
+        .output()
+        .expect("Failed to execute corpus run");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("{}", stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("FAIL users: TOON differs at line 2"));
+
+    println!("✓ Mismatches reported per case\n");
+}
@@ -0,0 +1,102 @@
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+use serde_json::{json, Value};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+struct Daemon(Child);
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn call(stream: &mut UnixStream, reader: &mut BufReader<UnixStream>, request: Value) -> Value {
+    stream.write_all(format!("{}\n", request).as_bytes()).unwrap();
+    let mut line = String::new();
+    reader.read_line(&mut line).expect("Failed to read response");
+    serde_json::from_str(&line).unwrap()
+}
+
+#[test]
+fn test_daemon_convert_validate_stats() {
+    println!("=== Daemon: JSON-RPC over a Unix socket ===");
+
+    let socket = std::env::temp_dir().join(format!("toonify-daemon-test-{}.sock", std::process::id()));
+    let daemon = Daemon(
+        Command::new(get_binary_path())
+            .arg("daemon")
+            .arg("--socket")
+            .arg(&socket)
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to start daemon"),
+    );
+
+    let mut stream = None;
+    for _ in 0..50 {
+        if let Ok(connected) = UnixStream::connect(&socket) {
+            stream = Some(connected);
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let mut stream = stream.expect("Daemon did not start in time");
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    // Format detected, target defaults to TOON
+    let response = call(&mut stream, &mut reader, json!({
+        "jsonrpc": "2.0", "id": 1, "method": "convert",
+        "params": { "input": r#"{"users":[{"id":1,"name":"Alice"}]}"# }
+    }));
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["from"], "json");
+    assert_eq!(response["result"]["output"].as_str().unwrap().trim_end(), "users[1]{id,name}:\n1,Alice");
+
+    let response = call(&mut stream, &mut reader, json!({
+        "jsonrpc": "2.0", "id": 2, "method": "convert",
+        "params": { "input": "users[1]{id,name}:\n1,Alice", "from": "toon", "to": "json" }
+    }));
+    let output: Value = serde_json::from_str(response["result"]["output"].as_str().unwrap()).unwrap();
+    assert_eq!(output, json!({ "users": [{ "id": 1, "name": "Alice" }] }));
+
+    let response = call(&mut stream, &mut reader, json!({
+        "jsonrpc": "2.0", "id": 3, "method": "validate",
+        "params": { "input": "users[1]{id,name}:\n1,Alice", "format": "toon" }
+    }));
+    assert_eq!(response["result"]["valid"], true);
+
+    let response = call(&mut stream, &mut reader, json!({
+        "jsonrpc": "2.0", "id": 4, "method": "validate",
+        "params": { "input": "{not json", "format": "json" }
+    }));
+    assert_eq!(response["result"]["valid"], false);
+    assert!(response["result"]["error"].is_string());
+
+    let response = call(&mut stream, &mut reader, json!({ "jsonrpc": "2.0", "id": 5, "method": "nope" }));
+    assert_eq!(response["error"]["code"], -32601);
+
+    let response = call(&mut stream, &mut reader, json!({ "jsonrpc": "2.0", "id": 6, "method": "stats" }));
+    println!("  Stats: {}", response["result"]);
+    assert_eq!(response["result"]["conversions"], 2);
+    assert_eq!(response["result"]["requests"], 6);
+    assert_eq!(response["result"]["connections"], 1);
+
+    drop(daemon);
+    let _ = std::fs::remove_file(&socket);
+    println!("=== Daemon PASSED ===");
+}
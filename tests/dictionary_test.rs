@@ -0,0 +1,97 @@
+use serde_json::{json, Value};
+use toonify::converter::{self, Converter};
+use toonify::toon::{format_toon, outline, FormatOptions, StreamingParser, ToonEvent};
+
+const STATUSES: [&str; 3] = ["pending", "shipped", "delivered"];
+
+fn orders(count: usize) -> Value {
+    let orders: Vec<Value> = (0..count)
+        .map(|i| json!({"id": i, "status": STATUSES[i % 3], "customer": format!("customer-{}", i)}))
+        .collect();
+    json!({"orders": orders})
+}
+
+fn to_toon(value: &Value, dictionary: bool) -> String {
+    Converter::builder()
+        .dictionary(dictionary)
+        .build()
+        .json_to_toon(&value.to_string())
+        .expect("Conversion failed")
+}
+
+fn to_value(toon: &str) -> Value {
+    serde_json::from_str(&converter::toon_to_json(toon).expect("Parse failed")).unwrap()
+}
+
+#[test]
+fn test_low_cardinality_column_is_coded() {
+    println!("=== Dictionary: categorical column ===");
+
+    let value = orders(300);
+    let plain = to_toon(&value, false);
+    let coded = to_toon(&value, true);
+    println!("TOON (head):\n{}", coded.lines().take(4).collect::<Vec<_>>().join("\n"));
+
+    assert!(coded.starts_with("#dict status: pending=0,shipped=1,delivered=2\norders[300]{customer,id,status}:\n"), "Unexpected TOON");
+    assert!(coded.contains("\ncustomer-1,1,1\n"), "Rows should hold codes");
+    assert!(!coded.contains("#dict customer"), "Unique values are not worth a dictionary");
+    assert!(coded.len() * 100 < plain.len() * 85, "Expected at least 15% smaller: {} vs {} bytes", coded.len(), plain.len());
+
+    println!("✓ {} bytes instead of {}\n", coded.len(), plain.len());
+}
+
+#[test]
+fn test_codes_expand_on_parse() {
+    println!("=== Dictionary: transparent expansion ===");
+
+    let value = orders(60);
+    let coded = to_toon(&value, true);
+    assert_eq!(to_value(&coded), value);
+
+    let typed = Converter::builder().dictionary(true).typed_headers(true).build().json_to_toon(&value.to_string()).unwrap();
+    assert!(typed.contains("status:str}"), "Unexpected TOON: {}", typed);
+    assert_eq!(to_value(&typed), value, "Typed str columns hold codes too");
+
+    println!("✓ Round trip restores the strings\n");
+}
+
+#[test]
+fn test_other_readers_expand_codes() {
+    let toon = "#dict status: pending=0,\"shipped, partly\"=1\norders[3]{id,status}:\n1,0\n2,1\n3,\n";
+    let expected = json!([
+        {"id": 1, "status": "pending"},
+        {"id": 2, "status": "shipped, partly"},
+        {"id": 3, "status": null},
+    ]);
+    assert_eq!(to_value(toon)["orders"], expected);
+
+    let mut parser = StreamingParser::new();
+    parser.feed(toon);
+    parser.finish();
+    let rows: Vec<Value> = parser
+        .filter_map(|event| match event.unwrap() {
+            ToonEvent::Row(row) => Some(row),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(Value::Array(rows), expected);
+
+    let entities = outline(toon);
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].rows[1].value, expected[1]);
+
+    // fmt keeps the dictionary with its table, also when sorting entities
+    let formatted = format_toon(&format!("zones:2\n{}", toon), &FormatOptions { sort_entities: true, ..Default::default() }).unwrap();
+    assert!(formatted.starts_with("#dict status:"), "Unexpected output: {}", formatted);
+    assert!(formatted.ends_with("zones:2\n"));
+}
+
+#[test]
+fn test_mixed_columns_are_left_alone() {
+    // A number among the strings would read back as a code
+    let rows: Vec<Value> = (0..50).map(|i| json!({"state": if i == 7 { json!(1) } else { json!("open") }})).collect();
+    let value = json!({"tickets": rows});
+    let coded = to_toon(&value, true);
+    assert!(!coded.contains("#dict"), "Unexpected TOON: {}", coded);
+    assert_eq!(to_value(&coded), value);
+}
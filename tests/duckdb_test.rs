@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_sql_aggregates_toon_entities() {
+    println!("=== SQL: DuckDB over TOON entities ===");
+
+    let input = temp_path("sql_input.toon");
+    fs::write(
+        &input,
+        "orders[4]{id,customer,total}:\n1,alice,10.5\n2,bob,3\n3,alice,4.5\n4,carol,8\ncustomers[2]{name,tier}:\nalice,gold\nbob,silver\nversion: 3",
+    )
+    .unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("sql")
+        .arg("SELECT customer, count(*) AS orders, sum(total) AS spent FROM orders GROUP BY 1 ORDER BY 1;")
+        .arg(&input)
+        .arg("--to")
+        .arg("json")
+        .output()
+        .expect("Failed to execute sql command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+
+    let result: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        result,
+        json!({ "result": [
+            { "customer": "alice", "orders": 2, "spent": 15.0 },
+            { "customer": "bob", "orders": 1, "spent": 3.0 },
+            { "customer": "carol", "orders": 1, "spent": 8.0 }
+        ]})
+    );
+
+    // Joins across entities; TOON output uses the --name entity
+    let output = Command::new(get_binary_path())
+        .arg("sql")
+        .arg("SELECT o.id, c.tier FROM orders o JOIN customers c ON c.name = o.customer ORDER BY o.id")
+        .arg(&input)
+        .arg("--name")
+        .arg("tiers")
+        .output()
+        .expect("Failed to execute sql command");
+    assert!(output.status.success());
+    let toon = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(toon.trim_end(), "tiers[3]{id,tier}:\n1,gold\n2,silver\n3,gold");
+
+    println!("=== SQL PASSED ===");
+}
+
+#[test]
+fn test_sql_reports_query_errors() {
+    let input = temp_path("sql_error_input.json");
+    fs::write(&input, r#"{"users":[{"id":1}]}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("sql")
+        .arg("SELECT * FROM missing")
+        .arg(&input)
+        .output()
+        .expect("Failed to execute sql command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Query failed"));
+}
@@ -0,0 +1,89 @@
+use serde_json::{json, Value};
+use toonify::converter::Converter;
+use toonify::toon::{parse_toon_with, DuplicateKeyPolicy, ParseOptions};
+
+fn convert_json(input: &str, policy: DuplicateKeyPolicy) -> Result<(Value, Vec<String>), String> {
+    let converter = Converter::builder().duplicate_keys(policy).build();
+    let converted = converter.convert_detailed(input, "json", "json")?;
+    Ok((serde_json::from_str(&converted.output).unwrap(), converted.warnings))
+}
+
+#[test]
+fn test_json_duplicate_last_wins_by_default() {
+    println!("=== Duplicate Keys: JSON last-wins (default) ===");
+
+    let (value, warnings) = convert_json(r#"{"a":1,"b":2,"a":3}"#, DuplicateKeyPolicy::default()).unwrap();
+    println!("Value: {}\nWarnings: {:?}", value, warnings);
+
+    assert_eq!(value, json!({"a": 3, "b": 2}));
+    assert_eq!(warnings.len(), 1, "Duplicate should be reported as a warning");
+    assert!(warnings[0].contains("'a'"));
+
+    println!("✓ Last value kept and reported\n");
+}
+
+#[test]
+fn test_json_duplicate_first_wins() {
+    println!("=== Duplicate Keys: JSON first-wins ===");
+
+    let (value, warnings) = convert_json(r#"{"user":{"id":1,"id":2}}"#, DuplicateKeyPolicy::FirstWins).unwrap();
+    println!("Value: {}\nWarnings: {:?}", value, warnings);
+
+    assert_eq!(value, json!({"user": {"id": 1}}));
+    assert!(warnings[0].contains("$.user"), "Warning should name the enclosing object");
+
+    println!("✓ First value kept\n");
+}
+
+#[test]
+fn test_json_duplicate_error() {
+    println!("=== Duplicate Keys: JSON error ===");
+
+    let result = convert_json(r#"{"a":1,"a":2}"#, DuplicateKeyPolicy::Error);
+    println!("Result: {:?}", result);
+
+    assert!(result.unwrap_err().contains("Duplicate key 'a'"));
+
+    println!("✓ Duplicate rejected\n");
+}
+
+#[test]
+fn test_json_duplicate_merge_arrays() {
+    println!("=== Duplicate Keys: JSON merge-arrays ===");
+
+    let (value, _) = convert_json(r#"{"tags":["a"],"tags":["b","c"],"n":1,"n":2}"#, DuplicateKeyPolicy::MergeArrays).unwrap();
+    println!("Value: {}", value);
+
+    assert_eq!(value, json!({"tags": ["a", "b", "c"], "n": 2}));
+
+    println!("✓ Arrays merged, scalars fall back to last-wins\n");
+}
+
+#[test]
+fn test_toon_repeated_entity_names() {
+    println!("=== Duplicate Keys: TOON repeated entities ===");
+
+    let toon = "users[1]{id,name}:\n1,Alice\nusers[1]{id,name}:\n2,Bob\n";
+
+    let merge = ParseOptions { duplicate_keys: DuplicateKeyPolicy::MergeArrays, ..Default::default() };
+    let (value, warnings) = parse_toon_with(toon, &merge).unwrap();
+    println!("Merged: {}\nWarnings: {:?}", value, warnings);
+    assert_eq!(value, json!({"users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}]}));
+    assert_eq!(warnings.len(), 1);
+
+    let first = ParseOptions { duplicate_keys: DuplicateKeyPolicy::FirstWins, ..Default::default() };
+    let (value, _) = parse_toon_with(toon, &first).unwrap();
+    assert_eq!(value, json!({"users": [{"id": 1, "name": "Alice"}]}));
+
+    let error = ParseOptions { duplicate_keys: DuplicateKeyPolicy::Error, ..Default::default() };
+    assert!(parse_toon_with(toon, &error).is_err());
+
+    println!("✓ TOON policies applied\n");
+}
+
+#[test]
+fn test_policy_names() {
+    assert_eq!("merge-arrays".parse::<DuplicateKeyPolicy>(), Ok(DuplicateKeyPolicy::MergeArrays));
+    assert_eq!(DuplicateKeyPolicy::FirstWins.as_str(), "first-wins");
+    assert!("newest".parse::<DuplicateKeyPolicy>().is_err());
+}
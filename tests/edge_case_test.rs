@@ -1,10 +1,5 @@
 use serde_json::Value;
-
-#[path = "../src/toon/mod.rs"]
-mod toon;
-
-#[path = "../src/converter.rs"]
-mod converter;
+use toonify::converter;
 
 #[test]
 fn test_values_ending_with_colon() {
@@ -79,3 +74,29 @@ fn test_array_followed_by_scalar() {
     assert_eq!(original, final_value, "Array followed by scalar field should round-trip");
     println!("✓ Array followed by scalar round-trip successful\n");
 }
+
+#[test]
+fn test_padded_and_empty_strings() {
+    let json = r#"{
+  "a": "  x",
+  "b": "",
+  "rows": [
+    {"id": 1, "note": "trailing "},
+    {"id": 2, "note": ""}
+  ],
+  "tags": [" left", "right "]
+}"#;
+
+    println!("=== Padded and Empty Strings Test ===");
+
+    let toon = converter::json_to_toon(json).expect("Failed to convert JSON to TOON");
+    println!("TOON:\n{}\n", toon);
+
+    let back_to_json = converter::toon_to_json(&toon).expect("Failed to convert TOON to JSON");
+    let original: Value = serde_json::from_str(json).unwrap();
+    let final_value: Value = serde_json::from_str(&back_to_json).unwrap();
+
+    assert_eq!(original, final_value, "Whitespace and empty strings should survive a round trip");
+    assert_eq!(converter::json_to_toon(&back_to_json).unwrap(), toon, "Serialization should be stable");
+    println!("✓ Padded and empty strings round-trip successful\n");
+}
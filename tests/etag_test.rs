@@ -0,0 +1,71 @@
+use toonify::guards::ParserGuards;
+use toonify::server::ServerBuilder;
+
+async fn spawn(builder: ServerBuilder) -> String {
+    let app = builder.build().unwrap().router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn post(base: &str, path: &str, data: &str, if_none_match: Option<&str>) -> reqwest::Response {
+    let request = reqwest::Client::new().post(format!("{}{}", base, path)).json(&serde_json::json!({ "data": data }));
+    let request = match if_none_match {
+        Some(tag) => request.header("if-none-match", tag),
+        None => request,
+    };
+    request.send().await.unwrap()
+}
+
+fn etag(response: &reqwest::Response) -> String {
+    response.headers()["etag"].to_str().unwrap().to_string()
+}
+
+const JSON: &str = r#"{"users":[{"id":1,"name":"Alice"}]}"#;
+
+#[tokio::test]
+async fn test_conversion_etag_and_if_none_match() {
+    println!("=== ETag: conditional conversions ===");
+
+    let base = spawn(ServerBuilder::new()).await;
+
+    let first = post(&base, "/json-to-toon", JSON, None).await;
+    assert_eq!(first.status(), 200);
+    let tag = etag(&first);
+    assert!(tag.starts_with('"') && tag.ends_with('"') && !tag.starts_with("W/"), "strong ETag: {}", tag);
+    assert_eq!(etag(&post(&base, "/json-to-toon", JSON, None).await), tag, "same input, same tag");
+    assert_eq!(etag(&post(&base, "/convert/json/toon", JSON, None).await), tag, "routes for the same conversion agree");
+
+    let response = post(&base, "/json-to-toon", JSON, Some(&tag)).await;
+    assert_eq!(response.status(), 304);
+    assert_eq!(etag(&response), tag);
+    assert!(response.text().await.unwrap().is_empty());
+    assert_eq!(post(&base, "/json-to-toon", JSON, Some(&format!("\"other\", W/{}", tag))).await.status(), 304);
+    assert_eq!(post(&base, "/json-to-toon", JSON, Some("*")).await.status(), 304);
+
+    // Other input, other direction: a new tag
+    assert_ne!(etag(&post(&base, "/json-to-toon", r#"{"a":1}"#, None).await), tag);
+    assert_eq!(post(&base, "/json-to-toon", r#"{"a":1}"#, Some(&tag)).await.status(), 200);
+
+    // Failures carry no tag
+    let failed = post(&base, "/json-to-toon", "{not json", None).await;
+    assert_eq!(failed.status(), 400);
+    assert!(failed.headers().get("etag").is_none());
+
+    println!("✓ ETag {} revalidates with 304\n", tag);
+}
+
+#[tokio::test]
+async fn test_etag_depends_on_options() {
+    println!("=== ETag: options ===");
+
+    let plain = spawn(ServerBuilder::new()).await;
+    let guarded = spawn(ServerBuilder::new().guards(ParserGuards { max_depth: Some(8), ..Default::default() })).await;
+
+    let plain_tag = etag(&post(&plain, "/json-to-toon", JSON, None).await);
+    let guarded_tag = etag(&post(&guarded, "/json-to-toon", JSON, None).await);
+    assert_ne!(plain_tag, guarded_tag, "parser guards are part of the tag");
+
+    println!("✓ Tags differ across option sets\n");
+}
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toonify::export::{html_table, toon_to_html, value_to_html};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_toon_to_html_table_per_entity() {
+    println!("=== Export: TOON to HTML tables ===");
+
+    let html = toon_to_html("users[2]{id,name}:\n1,Alice\n2,<Bob>\nconfig{debug}:\ntrue\nversion:3").unwrap();
+    println!("{}", html);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h2>users</h2>\n<table>\n<tr><th>id</th><th>name</th></tr>\n<tr><td>1</td><td>Alice</td></tr>"));
+    assert!(html.contains("<td>&lt;Bob&gt;</td>"));
+    assert!(html.contains("<h2>config</h2>\n<table>\n<tr><th>debug</th></tr>\n<tr><td>true</td></tr>"));
+    // Root scalars share one name/value table
+    assert!(html.contains("<h2>values</h2>\n<table>\n<tr><th>name</th><th>value</th></tr>\n<tr><td>version</td><td>3</td></tr>"));
+
+    println!("=== Export HTML PASSED ===");
+}
+
+#[test]
+fn test_html_table_union_of_keys() {
+    let table = html_table(&json!([{ "a": 1 }, { "b": [1, 2] }]), 10);
+    assert_eq!(table, "<table>\n<tr><th>a</th><th>b</th></tr>\n<tr><td>1</td><td></td></tr>\n<tr><td></td><td>[1,2]</td></tr>\n</table>\n");
+
+    let list = value_to_html(&json!({ "tags": ["x", "y"] }));
+    assert!(list.contains("<tr><th>value</th></tr>\n<tr><td>x</td></tr>\n<tr><td>y</td></tr>"));
+}
+
+#[test]
+fn test_convert_to_html_cli() {
+    let output = Command::new(get_binary_path())
+        .args(["convert", "-", "--to", "html"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            use std::io::Write;
+            child.stdin.take().unwrap().write_all(br#"{"users":[{"id":1,"name":"Alice"}]}"#)?;
+            child.wait_with_output()
+        })
+        .expect("Failed to execute convert");
+    assert!(output.status.success());
+    let html = String::from_utf8(output.stdout).unwrap();
+    assert!(html.contains("<tr><td>1</td><td>Alice</td></tr>"));
+}
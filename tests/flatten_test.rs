@@ -0,0 +1,72 @@
+use serde_json::{json, Value};
+use toonify::converter::Converter;
+use toonify::flatten::{flatten, unflatten};
+
+#[test]
+fn test_flatten_nested_rows() {
+    println!("=== Flatten: Nested rows become dotted columns ===");
+
+    let value = json!({
+        "users": [
+            {"id": 1, "user": {"address": {"city": "Paris", "zip": "75001"}}},
+            {"id": 2, "user": {"address": {"city": "Oslo", "zip": "0150"}}}
+        ]
+    });
+
+    let flat = flatten(value.clone()).expect("Flatten failed");
+    println!("Flat: {}", flat);
+    assert_eq!(flat["users"][0], json!({"id": 1, "user.address.city": "Paris", "user.address.zip": "75001"}));
+
+    let restored = unflatten(flat).expect("Unflatten failed");
+    assert_eq!(restored, value, "Unflatten should invert flatten");
+
+    println!("✓ Flatten round-trip successful\n");
+}
+
+#[test]
+fn test_flatten_produces_tabular_toon() {
+    println!("=== Flatten: Pure tabular TOON ===");
+
+    let json = r#"{"orders":[{"id":1,"customer":{"name":"Alice","tier":"gold"}},{"id":2,"customer":{"name":"Bob","tier":"free"}}]}"#;
+
+    let flat = Converter::builder().flatten(true).build();
+    let toon = flat.json_to_toon(json).expect("Flattened conversion failed");
+    println!("TOON:\n{}", toon);
+    assert!(toon.contains("customer.name"), "Header should use dotted paths");
+    assert!(toon.lines().skip(1).all(|line| !line.contains('{')), "Rows should not embed JSON");
+
+    let nested = Converter::builder().unflatten(true).build();
+    let back: Value = serde_json::from_str(&nested.toon_to_json(&toon).expect("Unflatten conversion failed")).unwrap();
+    assert_eq!(back, serde_json::from_str::<Value>(json).unwrap());
+
+    println!("✓ Flattened TOON round-trips\n");
+}
+
+#[test]
+fn test_root_entities_and_empty_objects_preserved() {
+    println!("=== Flatten: Root entity names and empty objects ===");
+
+    let value = json!({"config": {"db": {"host": "localhost", "pool": {}}}, "version": 2});
+
+    let flat = flatten(value.clone()).unwrap();
+    println!("Flat: {}", flat);
+    assert_eq!(flat, json!({"config": {"db.host": "localhost", "db.pool": {}}, "version": 2}));
+    assert_eq!(unflatten(flat).unwrap(), value);
+
+    println!("✓ Root keys untouched, empty objects kept\n");
+}
+
+#[test]
+fn test_flatten_conflicts_are_errors() {
+    println!("=== Flatten: Path conflicts ===");
+
+    let result = flatten(json!({"row": {"a.b": 1, "a": {"b": 2}}}));
+    println!("Flatten: {:?}", result);
+    assert!(result.is_err());
+
+    let result = unflatten(json!({"row": {"a": 1, "a.b": 2}}));
+    println!("Unflatten: {:?}", result);
+    assert!(result.is_err());
+
+    println!("✓ Conflicts rejected\n");
+}
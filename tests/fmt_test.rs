@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use toonify::toon::{format_toon, parse_toon, FormatOptions};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn create_temp_file(name: &str, content: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    fs::write(&path, content).expect("Failed to write temp file");
+    path
+}
+
+const MESSY: &str = "  users [3] { id , name , active } :\n\n  1 , Alice ,true\n2,  \"Bob, Jr.\" , false\n\nstatus :  ok\ntags[5]:\nrust\nllm\n";
+
+#[test]
+fn test_fmt_normalizes_whitespace_and_counts() {
+    println!("=== Fmt: Whitespace and array counts ===");
+
+    let formatted = format_toon(MESSY, &FormatOptions::default()).expect("Format failed");
+    println!("Formatted:\n{}", formatted);
+
+    assert_eq!(
+        formatted,
+        "users[2]{id,name,active}:\n1,Alice,true\n2,\"Bob, Jr.\",false\nstatus:ok\ntags[2]:\nrust\nllm\n"
+    );
+    assert!(parse_toon(&formatted).is_ok(), "Formatted output must be valid TOON");
+
+    println!("✓ Whitespace normalized, counts fixed\n");
+}
+
+#[test]
+fn test_fmt_is_idempotent() {
+    let options = FormatOptions { align_columns: true, sort_entities: true };
+    let once = format_toon(MESSY, &options).unwrap();
+    let twice = format_toon(&once, &options).unwrap();
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn test_fmt_align_and_sort() {
+    println!("=== Fmt: Aligned columns and sorted entities ===");
+
+    let input = "zeta:1\nusers[2]{id,name,role}:\n1,Alice,admin\n100,Bo,user\n";
+    let options = FormatOptions { align_columns: true, sort_entities: true };
+
+    let formatted = format_toon(input, &options).expect("Format failed");
+    println!("Formatted:\n{}", formatted);
+
+    assert_eq!(formatted, "users[2]{id,name,role}:\n1,   Alice, admin\n100, Bo,    user\nzeta:1\n");
+    assert_eq!(parse_toon(&formatted).unwrap(), parse_toon(input).unwrap());
+
+    println!("✓ Columns aligned, entities sorted\n");
+}
+
+#[test]
+fn test_fmt_rejects_invalid_toon() {
+    let result = format_toon("stray text\nusers[1]{id}:\n1\n", &FormatOptions::default());
+    println!("Result: {:?}", result);
+    assert!(result.is_err(), "Invalid TOON should not be formatted");
+}
+
+#[test]
+fn test_cli_fmt_check() {
+    println!("=== CLI: fmt --check ===");
+
+    let file = create_temp_file("fmt_check.toon", MESSY);
+
+    let check = Command::new(get_binary_path())
+        .args(["fmt", "--check"])
+        .arg(&file)
+        .output()
+        .expect("Failed to execute toonify binary");
+    let stderr = String::from_utf8_lossy(&check.stderr);
+    println!("Check stderr:\n{}", stderr);
+    assert!(!check.status.success(), "--check should fail on unformatted input");
+    assert!(stderr.contains("+users[2]{id,name,active}:"), "Diff should show the formatted header");
+    assert_eq!(fs::read_to_string(&file).unwrap(), MESSY, "--check must not modify the file");
+
+    let write = Command::new(get_binary_path())
+        .arg("fmt")
+        .arg(&file)
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(write.status.success());
+
+    let recheck = Command::new(get_binary_path())
+        .args(["fmt", "--check"])
+        .arg(&file)
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(recheck.status.success(), "Formatted file should pass --check");
+
+    let _ = fs::remove_file(&file);
+    println!("✓ fmt --check detects and fmt fixes formatting\n");
+}
@@ -0,0 +1,136 @@
+use serde_json::{json, Value};
+use toonify::converter::{self, FormatCodec, FormatRegistry};
+
+#[test]
+fn test_builtin_registry_contains_core_formats() {
+    println!("=== Format Registry: Built-in formats ===");
+
+    let names = converter::registry().names();
+    println!("Registered formats: {:?}", names);
+
+    assert!(names.contains(&"json"), "Registry should contain json");
+    assert!(names.contains(&"toon"), "Registry should contain toon");
+    assert!(converter::registry().get("TOON").is_some(), "Lookup should be case-insensitive");
+    assert_eq!(converter::registry().for_extension("toon").map(|c| c.name()), Some("toon"));
+
+    println!("✓ Built-in formats registered\n");
+}
+
+#[test]
+fn test_convert_matches_legacy_functions() {
+    println!("=== Format Registry: convert() matches json_to_toon/toon_to_json ===");
+
+    let json = r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#;
+
+    let via_registry = converter::convert(json, "json", "toon").expect("Registry conversion failed");
+    let via_legacy = converter::json_to_toon(json).expect("Legacy conversion failed");
+    assert_eq!(via_registry, via_legacy, "Registry and legacy JSON → TOON should agree");
+
+    let back_registry = converter::convert(&via_registry, "toon", "json").expect("Registry conversion failed");
+    let back_legacy = converter::toon_to_json(&via_legacy).expect("Legacy conversion failed");
+    assert_eq!(back_registry, back_legacy, "Registry and legacy TOON → JSON should agree");
+
+    println!("✓ Registry routing matches legacy functions\n");
+}
+
+#[test]
+fn test_unknown_format_is_rejected() {
+    println!("=== Format Registry: Unknown format ===");
+
+    let result = converter::convert("{}", "json", "parquet");
+    println!("Result: {:?}", result);
+
+    assert!(result.is_err(), "Unknown target format should be rejected");
+    assert!(result.unwrap_err().contains("parquet"), "Error should name the unknown format");
+
+    println!("✓ Unknown format rejected\n");
+}
+
+struct UpperCaseKeysCodec;
+
+impl FormatCodec for UpperCaseKeysCodec {
+    fn name(&self) -> &'static str {
+        "upper"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["upper"]
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        serde_json::from_str(&input.to_lowercase()).map_err(|e| e.to_string())
+    }
+
+    fn emit(&self, value: &Value) -> Result<String, String> {
+        Ok(value.to_string().to_uppercase())
+    }
+}
+
+#[test]
+fn test_custom_codec_registration() {
+    println!("=== Format Registry: Custom codec ===");
+
+    let mut registry = FormatRegistry::with_builtin_formats();
+    registry.register(UpperCaseKeysCodec);
+
+    let toon = registry.convert(r#"{"STATUS":"OK"}"#, "upper", "toon").expect("Custom → TOON failed");
+    println!("TOON:\n{}", toon);
+    assert_eq!(toon, "status:ok");
+
+    let upper = registry.convert(&toon, "toon", "upper").expect("TOON → custom failed");
+    println!("Custom:\n{}", upper);
+    assert_eq!(upper, r#"{"STATUS":"OK"}"#);
+
+    println!("✓ Custom codec plugs into routing\n");
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_to_toon_roundtrip() {
+    println!("=== Format Registry: YAML ↔ TOON ===");
+
+    let yaml = "users:\n  - id: 1\n    name: Alice\n  - id: 2\n    name: Bob\n";
+
+    let toon = converter::convert(yaml, "yaml", "toon").expect("YAML → TOON failed");
+    println!("TOON:\n{}", toon);
+    assert!(toon.contains("users[2]{id,name}:"), "YAML list should become a TOON table");
+
+    let json = converter::convert(&toon, "toon", "json").expect("TOON → JSON failed");
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value, json!({"users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}]}));
+
+    println!("✓ YAML round-trip successful\n");
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_csv_to_toon_roundtrip() {
+    println!("=== Format Registry: CSV ↔ TOON ===");
+
+    let csv = "id,name,active\n1,Alice,true\n2,Bob,false\n";
+
+    let toon = converter::convert(csv, "csv", "toon").expect("CSV → TOON failed");
+    println!("TOON:\n{}", toon);
+    assert!(toon.starts_with("rows[2]{active,id,name}:"), "CSV should become a 'rows' table");
+
+    let back = converter::convert(&toon, "toon", "csv").expect("TOON → CSV failed");
+    println!("CSV:\n{}", back);
+    assert!(back.contains("1,Alice"), "CSV output should contain row data");
+
+    println!("✓ CSV round-trip successful\n");
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_to_toon() {
+    println!("=== Format Registry: TOML → TOON ===");
+
+    let toml = "title = \"demo\"\n\n[owner]\nname = \"Alice\"\n";
+
+    let toon = converter::convert(toml, "toml", "toon").expect("TOML → TOON failed");
+    println!("TOON:\n{}", toon);
+    assert!(toon.contains("title:demo"));
+    assert!(toon.contains("owner{name}:"));
+
+    println!("✓ TOML conversion successful\n");
+}
@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter::detect_format;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("format_detection");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+#[test]
+fn test_extension_wins_over_content() {
+    println!("=== Detection: extension before content ===");
+
+    assert_eq!(detect_format(Some(Path::new("data.toon")), br#"{"a":1}"#).unwrap(), "toon");
+    assert_eq!(detect_format(Some(Path::new("data.JSON")), b"status:ok").unwrap(), "json");
+    assert_eq!(detect_format(Some(Path::new("data.toon.gz")), b"status:ok").unwrap(), "toon");
+
+    println!("✓ Known extensions decide\n");
+}
+
+#[test]
+fn test_gzip_magic_bytes() {
+    let gzip = [0x1f, 0x8b, 0x08, 0x00];
+    assert_eq!(detect_format(None, &gzip).unwrap(), "gzip");
+    assert_eq!(detect_format(Some(Path::new("data.json")), &gzip).unwrap(), "gzip");
+}
+
+#[test]
+fn test_content_heuristics() {
+    println!("=== Detection: content fallback ===");
+
+    assert_eq!(detect_format(None, br#"{"users":[{"id":1}]}"#).unwrap(), "json");
+    assert_eq!(detect_format(None, b"users[2]{id,name}:\n1,Alice\n2,Bob").unwrap(), "toon");
+    assert_eq!(detect_format(None, b"[2]:\n1\n2").unwrap(), "toon");
+    assert_eq!(detect_format(Some(Path::new("notes.txt")), b"status:ok").unwrap(), "toon");
+    assert_eq!(detect_format(Some(Path::new("-")), b"42").unwrap(), "json");
+    #[cfg(feature = "yaml")]
+    assert_eq!(detect_format(None, b"---\nname: demo\n").unwrap(), "yaml");
+
+    println!("✓ JSON, TOON headers and YAML markers recognised\n");
+}
+
+#[test]
+fn test_invalid_utf8_is_an_error() {
+    let err = detect_format(None, &[0xff, 0xfe, 0x00]).unwrap_err();
+    assert!(err.contains("UTF-8"), "Unexpected error: {}", err);
+}
+
+#[test]
+fn test_cli_convert_gzip_input() {
+    println!("=== Detection: convert gzip-compressed TOON ===");
+
+    let plain = temp_path("orders.toon");
+    let compressed = temp_path("orders.toon.gz");
+    fs::write(&plain, "orders[2]{id,total}:\n1,9.5\n2,12\n").unwrap();
+
+    let status = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("compress")
+        .arg("--input")
+        .arg(&plain)
+        .arg("--output")
+        .arg(&compressed)
+        .status()
+        .expect("Failed to execute compress command");
+    assert!(status.success());
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&compressed)
+        .output()
+        .expect("Failed to execute toonify binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("Stderr: {}", stderr);
+
+    assert!(output.status.success(), "convert should decompress and convert");
+    assert!(stderr.contains("Detected format: toon"));
+    let value: Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    assert_eq!(value["orders"][1], json!({"id": 2, "total": 12}));
+
+    println!("✓ .toon.gz converted to JSON\n");
+}
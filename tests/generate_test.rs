@@ -0,0 +1,99 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toonify::generate::{generate, GenerateOptions};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn schema() -> serde_json::Value {
+    json!({
+        "users": {
+            "type": "array",
+            "fields": ["id", "name", "email", "age", "plan", "signup", "score"],
+            "max_items": 50,
+            "field_types": { "id": "number", "name": "string", "age": "number", "score": "number", "active": "boolean" },
+            "ranges": { "id": { "min": 100 }, "age": { "min": 18, "max": 99 }, "score": { "min": 0.5, "max": 1.5 } },
+            "string_lengths": { "name": { "min": 3, "max": 40 } },
+            "enums": { "plan": ["free", "pro"] },
+            "formats": { "email": "email", "signup": "date" }
+        }
+    })
+}
+
+#[test]
+fn test_generate_honors_schema() {
+    println!("=== Generate: rows follow the schema ===");
+
+    let options = GenerateOptions { rows: 200, seed: 7 };
+    let (value, warnings) = generate(&schema(), &options).unwrap();
+    assert!(warnings.is_empty());
+
+    let users = value["users"].as_array().unwrap();
+    assert_eq!(users.len(), 50, "clamped to max_items");
+    for (index, user) in users.iter().enumerate() {
+        assert_eq!(user["id"], 100 + index as u64);
+        let age = user["age"].as_i64().unwrap();
+        assert!((18..=99).contains(&age));
+        let score = user["score"].as_f64().unwrap();
+        assert!((0.5..=1.5).contains(&score));
+        assert!(["free", "pro"].contains(&user["plan"].as_str().unwrap()));
+        assert!(user["email"].as_str().unwrap().ends_with("@example.com"));
+        assert_eq!(user["signup"].as_str().unwrap().len(), 10);
+        assert!(user["active"].is_boolean(), "fields named only in field_types are generated too");
+    }
+
+    // Deterministic for a seed
+    assert_eq!(generate(&schema(), &options).unwrap().0, value);
+    assert_ne!(generate(&schema(), &GenerateOptions { rows: 200, seed: 8 }).unwrap().0, value);
+
+    let (_, warnings) = generate(&json!({ "t": { "type": "array", "fields": ["code"], "patterns": { "code": "^[A-Z]{3}$" } } }), &options).unwrap();
+    assert_eq!(warnings.len(), 1);
+
+    println!("=== Generate PASSED ===");
+}
+
+#[test]
+fn test_generated_data_passes_validate() {
+    let schema_file = temp_path("generate_schema.json");
+    let data_file = temp_path("generate_data.toon");
+    fs::write(&schema_file, schema().to_string()).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .args(["generate", "--rows", "25", "--seed", "3", "--schema"])
+        .arg(&schema_file)
+        .arg("--output")
+        .arg(&data_file)
+        .output()
+        .expect("Failed to execute generate");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+    assert!(fs::read_to_string(&data_file).unwrap().starts_with("users[25]{"));
+
+    let output = Command::new(get_binary_path())
+        .arg("validate")
+        .arg("--schema")
+        .arg(&schema_file)
+        .arg("--input")
+        .arg(&data_file)
+        .output()
+        .expect("Failed to execute validate");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "generated data should validate");
+}
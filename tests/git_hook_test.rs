@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn create_repo(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create repo dir");
+    git(&path, &["init", "-q"]);
+    git(&path, &["config", "user.email", "test@example.com"]);
+    git(&path, &["config", "user.name", "Test"]);
+    path
+}
+
+fn git(repo: &Path, args: &[&str]) -> String {
+    let output = Command::new("git").args(args).current_dir(repo).output().expect("Failed to run git");
+    assert!(output.status.success(), "git {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn hook_run(repo: &Path, extra: &[&str]) -> std::process::Output {
+    Command::new(get_binary_path())
+        .args(["hook", "run"])
+        .args(extra)
+        .current_dir(repo)
+        .output()
+        .expect("Failed to execute hook run")
+}
+
+#[test]
+fn test_hook_run_regenerates_and_stages_stale_toon() {
+    println!("=== Hook: regenerate stale TOON artifact ===");
+
+    let repo = create_repo("hook_regenerate");
+    fs::write(repo.join("users.json"), r#"{"users":[{"id":1,"name":"Alice"}]}"#).unwrap();
+    fs::write(repo.join("users.toon"), "users[0]:\n").unwrap();
+    git(&repo, &["add", "."]);
+
+    // --check reports the stale artifact without touching it
+    let checked = hook_run(&repo, &["--check"]);
+    assert!(!checked.status.success(), "A stale artifact should fail --check");
+    assert!(String::from_utf8_lossy(&checked.stderr).contains("out of date"));
+    assert_eq!(fs::read_to_string(repo.join("users.toon")).unwrap(), "users[0]:\n");
+
+    let output = hook_run(&repo, &[]);
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+
+    let toon = fs::read_to_string(repo.join("users.toon")).unwrap();
+    assert!(toon.contains("1,Alice"), "Artifact should be regenerated: {}", toon);
+    assert!(git(&repo, &["diff", "--name-only"]).is_empty(), "The regenerated file should be staged");
+
+    let _ = fs::remove_dir_all(&repo);
+
+    println!("✓ Stale TOON is regenerated and staged\n");
+}
+
+#[test]
+fn test_hook_run_rejects_invalid_files() {
+    println!("=== Hook: invalid staged files ===");
+
+    let repo = create_repo("hook_invalid");
+    fs::write(repo.join("broken.json"), "{not json").unwrap();
+    fs::write(repo.join("notes.txt"), "ignored").unwrap();
+    git(&repo, &["add", "."]);
+
+    let output = hook_run(&repo, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("Stderr: {}", stderr);
+    assert!(!output.status.success(), "Invalid JSON should block the commit");
+    assert!(stderr.contains("broken.json"));
+    assert!(!stderr.contains("notes.txt"), "Other files are not checked");
+
+    let _ = fs::remove_dir_all(&repo);
+
+    println!("✓ Invalid JSON blocks the commit\n");
+}
+
+#[test]
+fn test_hook_install_writes_pre_commit() {
+    println!("=== Hook: install ===");
+
+    let repo = create_repo("hook_install");
+    let install = |extra: &[&str]| {
+        Command::new(get_binary_path())
+            .args(["hook", "install"])
+            .args(extra)
+            .current_dir(&repo)
+            .output()
+            .expect("Failed to execute hook install")
+    };
+
+    assert!(install(&[]).status.success());
+    let hook = fs::read_to_string(repo.join(".git/hooks/pre-commit")).unwrap();
+    assert!(hook.contains("hook run"), "Hook should call toonify: {}", hook);
+
+    // Reinstalling over our own hook is fine; a foreign hook needs --force
+    assert!(install(&[]).status.success());
+    fs::write(repo.join(".git/hooks/pre-commit"), "#!/bin/sh\nexit 0\n").unwrap();
+    assert!(!install(&[]).status.success());
+    assert!(install(&["--force"]).status.success());
+
+    let _ = fs::remove_dir_all(&repo);
+
+    println!("✓ pre-commit hook installed\n");
+}
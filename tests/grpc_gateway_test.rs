@@ -0,0 +1,76 @@
+use serde_json::{json, Value};
+use toonify::server::ServerBuilder;
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn post(base: &str, path: &str, body: &str) -> (u16, Value) {
+    let response = reqwest::Client::new()
+        .post(format!("{}{}", base, path))
+        .header("content-type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .unwrap();
+    (response.status().as_u16(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_grpc_methods_served_as_json() {
+    println!("=== gRPC gateway: methods from the proto ===");
+
+    let server = ServerBuilder::new().grpc_gateway(true).build().unwrap();
+    assert_eq!(
+        server.grpc_gateway_paths(),
+        vec!["/converter.ConverterService/JsonToToon", "/converter.ConverterService/ToonToJson"]
+    );
+    let base = spawn(server.router()).await;
+
+    let data = json!({ "data": r#"{"users":[{"id":1,"name":"Alice"}]}"# }).to_string();
+    let (status, reply) = post(&base, "/converter.ConverterService/JsonToToon", &data).await;
+    println!("Reply: {}", reply);
+    assert_eq!(status, 200);
+    assert_eq!(reply, json!({ "result": "users[1]{id,name}:\n1,Alice" }), "Empty error is omitted, as in proto3 JSON");
+
+    let (status, reply) = post(&base, "/converter.ConverterService/ToonToJson", &json!({ "data": "users[1]{id}:\n1" }).to_string()).await;
+    assert_eq!(status, 200);
+    let result: Value = serde_json::from_str(reply["result"].as_str().unwrap()).unwrap();
+    assert_eq!(result, json!({ "users": [{ "id": 1 }] }));
+
+    // The service's own error field comes through like any other
+    let (status, reply) = post(&base, "/converter.ConverterService/JsonToToon", r#"{"data":"not json"}"#).await;
+    assert_eq!(status, 200);
+    assert!(reply["error"].as_str().is_some_and(|e| !e.is_empty()), "Reply: {}", reply);
+
+    println!("✓ Both RPCs transcoded\n");
+}
+
+#[tokio::test]
+async fn test_grpc_gateway_rejects_bad_bodies() {
+    let server = ServerBuilder::new().grpc_gateway(true).build().unwrap();
+    let base = spawn(server.router()).await;
+
+    let (status, reply) = post(&base, "/converter.ConverterService/JsonToToon", r#"{"payload":"x"}"#).await;
+    assert_eq!(status, 400);
+    assert_eq!(reply["code"], json!(3), "INVALID_ARGUMENT");
+    assert!(reply["message"].as_str().unwrap().contains("converter.ConvertRequest"), "Reply: {}", reply);
+
+    let (status, _) = post(&base, "/converter.ConverterService/JsonToToon", "[1, 2]").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_grpc_gateway_off_by_default() {
+    let base = spawn(ServerBuilder::new().build().unwrap().router()).await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/converter.ConverterService/JsonToToon", base))
+        .body(r#"{"data":"{}"}"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
@@ -0,0 +1,107 @@
+use std::net::{SocketAddr, TcpListener};
+use std::time::Duration;
+
+use prost::Message;
+use toonify::pb::{ConvertRequest, ConvertResponse};
+use toonify::server::ServerBuilder;
+
+fn free_addr() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    listener.local_addr().unwrap()
+}
+
+// One gRPC-Web frame: flag byte, big-endian length, message
+fn frame(message: &impl Message) -> Vec<u8> {
+    let body = message.encode_to_vec();
+    let mut framed = vec![0u8];
+    framed.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    framed.extend_from_slice(&body);
+    framed
+}
+
+// (data frames, trailer text)
+fn unframe(mut bytes: &[u8]) -> (Vec<Vec<u8>>, String) {
+    let (mut messages, mut trailers) = (Vec::new(), String::new());
+    while bytes.len() >= 5 {
+        let len = u32::from_be_bytes(bytes[1..5].try_into().unwrap()) as usize;
+        let payload = bytes[5..5 + len].to_vec();
+        if bytes[0] & 0x80 != 0 {
+            trailers = String::from_utf8(payload).unwrap();
+        } else {
+            messages.push(payload);
+        }
+        bytes = &bytes[5 + len..];
+    }
+    (messages, trailers)
+}
+
+async fn start(grpc_web: bool) -> String {
+    let addr = free_addr();
+    let server = ServerBuilder::new().grpc_web(grpc_web).build().unwrap();
+    tokio::spawn(server.serve_grpc(addr));
+    for _ in 0..50 {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() {
+            return format!("http://{}", addr);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("gRPC server did not start in time");
+}
+
+async fn call(base: &str, data: &str) -> reqwest::Result<reqwest::Response> {
+    reqwest::Client::new()
+        .post(format!("{}/converter.ConverterService/JsonToToon", base))
+        .header("content-type", "application/grpc-web+proto")
+        .header("x-grpc-web", "1")
+        .header("origin", "https://app.example.com")
+        .body(frame(&ConvertRequest { data: data.to_string() }))
+        .send()
+        .await
+}
+
+#[tokio::test]
+async fn test_grpc_web_call_from_browser() {
+    println!("=== gRPC-Web: JsonToToon over HTTP/1.1 ===");
+
+    let base = start(true).await;
+    let response = call(&base, r#"{"users":[{"id":1,"name":"Alice"}]}"#).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("application/grpc-web"));
+    assert_eq!(response.headers()["access-control-allow-origin"], "*");
+    // Browsers hide grpc-status from scripts unless it is exposed
+    assert!(response.headers()["access-control-expose-headers"].to_str().unwrap().contains("grpc-status"));
+
+    let (messages, trailers) = unframe(&response.bytes().await.unwrap());
+    assert!(trailers.contains("grpc-status:0"), "trailers: {:?}", trailers);
+    let reply = ConvertResponse::decode(messages[0].as_slice()).unwrap();
+    assert_eq!(reply.result, "users[1]{id,name}:\n1,Alice");
+    assert!(reply.error.is_empty());
+
+    // CORS preflight
+    let preflight = reqwest::Client::new()
+        .request(reqwest::Method::OPTIONS, format!("{}/converter.ConverterService/JsonToToon", base))
+        .header("origin", "https://app.example.com")
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "content-type,x-grpc-web")
+        .send()
+        .await
+        .unwrap();
+    assert!(preflight.status().is_success());
+    assert!(preflight.headers()["access-control-allow-methods"].to_str().unwrap().contains("POST"));
+
+    println!("✓ Browser-style gRPC-Web call converted\n");
+}
+
+#[tokio::test]
+async fn test_grpc_web_off_by_default() {
+    println!("=== gRPC-Web: disabled ===");
+
+    let base = start(false).await;
+    // HTTP/2 only: an HTTP/1.1 request is refused outright or at least not answered as gRPC-Web
+    if let Ok(response) = call(&base, "{}").await {
+        let content_type = response.headers().get("content-type").and_then(|value| value.to_str().ok()).unwrap_or_default();
+        assert!(!content_type.starts_with("application/grpc-web"), "{}", content_type);
+    }
+
+    println!("✓ Plain gRPC port does not speak gRPC-Web\n");
+}
@@ -0,0 +1,149 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("gzip_input");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path
+}
+
+fn gzip(data: &str) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn gunzip(data: &[u8]) -> String {
+    let mut text = String::new();
+    GzDecoder::new(data).read_to_string(&mut text).expect("Output should be gzip");
+    text
+}
+
+#[test]
+fn test_batch_decompresses_gzip_inputs() {
+    println!("=== Gzip: batch over .json.gz inputs ===");
+
+    let dir = temp_dir("batch_in");
+    let input_dir = dir.join("input");
+    let output_dir = dir.join("output");
+    fs::create_dir_all(&input_dir).unwrap();
+    fs::write(input_dir.join("users.json.gz"), gzip(r#"{"users":[{"id":1,"name":"Alice"}]}"#)).unwrap();
+    fs::write(input_dir.join("plain.json"), r#"{"status":"ok"}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("batch")
+        .arg("--input-dir")
+        .arg(&input_dir)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .output()
+        .expect("Failed to execute batch command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    assert!(output.status.success(), "Batch should succeed");
+    let users = fs::read_to_string(output_dir.join("users.toon")).expect("users.json.gz should become users.toon");
+    assert!(users.contains("Alice"));
+    assert!(output_dir.join("plain.toon").exists());
+
+    println!("✓ Gzip inputs converted without a decompress step\n");
+}
+
+#[test]
+fn test_batch_gzip_output() {
+    let dir = temp_dir("batch_out");
+    let input_dir = dir.join("input");
+    let output_dir = dir.join("output");
+    fs::create_dir_all(&input_dir).unwrap();
+    fs::write(input_dir.join("orders.json"), r#"{"orders":[{"id":7}]}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("batch")
+        .arg("--input-dir")
+        .arg(&input_dir)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .arg("--gzip")
+        .output()
+        .expect("Failed to execute batch command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    assert!(output.status.success(), "Batch should succeed");
+    let compressed = fs::read(output_dir.join("orders.toon.gz")).expect("Output should be named orders.toon.gz");
+    assert!(gunzip(&compressed).contains("orders"));
+}
+
+#[test]
+fn test_convert_gzip_round_trip() {
+    println!("=== Gzip: convert gzip in, gzip out ===");
+
+    let dir = temp_dir("convert");
+    let input = dir.join("data.json.gz");
+    let output_file = dir.join("data.toon.gz");
+    fs::write(&input, gzip(r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#)).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .arg("--gzip")
+        .arg("-o")
+        .arg(&output_file)
+        .output()
+        .expect("Failed to execute toonify binary");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "convert --gzip should succeed");
+
+    let toon = gunzip(&fs::read(&output_file).unwrap());
+    assert!(toon.starts_with("users[2]{id,name}:"), "Unexpected TOON: {}", toon);
+
+    // And back: the .toon.gz is read as TOON without --from
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&output_file)
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(output.status.success());
+    let value: Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    assert_eq!(value["users"][1], json!({"id": 2, "name": "Bob"}));
+
+    println!("✓ Round trip through gzip on both ends\n");
+}
+
+#[test]
+fn test_convert_gzip_from_stdin() {
+    let mut child = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["convert", "-"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute toonify binary");
+    child.stdin.take().unwrap().write_all(&gzip(r#"{"status":"ok"}"#)).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success(), "Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "status:ok");
+}
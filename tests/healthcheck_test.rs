@@ -0,0 +1,112 @@
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::Duration;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn free_addr() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    listener.local_addr().unwrap().to_string()
+}
+
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start_server(http_addr: &str, grpc_addr: &str) -> Server {
+    let child = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["serve", "--http-addr", http_addr, "--grpc-addr", grpc_addr])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start server");
+    Server(child)
+}
+
+fn healthcheck(args: &[&str]) -> Output {
+    Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("healthcheck")
+        .args(args)
+        .output()
+        .expect("Failed to execute healthcheck")
+}
+
+#[test]
+fn test_healthcheck_exit_codes() {
+    println!("=== Healthcheck: HTTP and gRPC probes ===");
+
+    let http_addr = free_addr();
+    let grpc_addr = free_addr();
+
+    // Nothing listening yet
+    let output = healthcheck(&["--http-addr", &http_addr, "--timeout-ms", "500"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Not ready"));
+
+    let _server = start_server(&http_addr, &grpc_addr);
+    let mut ready = false;
+    for _ in 0..50 {
+        if healthcheck(&["--http-addr", &http_addr]).status.success() {
+            ready = true;
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert!(ready, "server never became ready");
+
+    let output = healthcheck(&["--http-addr", &http_addr]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("/readyz"));
+
+    let output = healthcheck(&["--grpc", "--grpc-addr", &grpc_addr]);
+    println!("{}", String::from_utf8_lossy(&output.stdout));
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // The address can come from the environment the server is configured with
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .env("TOONIFY_HTTP_ADDR", &http_addr)
+        .arg("healthcheck")
+        .output()
+        .expect("Failed to execute healthcheck");
+    assert!(output.status.success());
+
+    println!("=== Healthcheck PASSED ===");
+}
+
+#[test]
+fn test_readyz_and_healthz_endpoints() {
+    let http_addr = free_addr();
+    let _server = start_server(&http_addr, &free_addr());
+
+    let url = |path: &str| format!("http://{}{}", http_addr, path);
+    let mut response = None;
+    for _ in 0..50 {
+        if let Ok(ok) = reqwest::blocking::get(url("/healthz")) {
+            response = Some(ok);
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let response = response.expect("server never answered");
+    assert!(response.status().is_success());
+    assert_eq!(response.text().unwrap(), "ok");
+
+    let response = reqwest::blocking::get(url("/readyz")).unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.text().unwrap(), "ready");
+}
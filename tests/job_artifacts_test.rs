@@ -0,0 +1,104 @@
+use std::io::{Cursor, Read};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use serde_json::{json, Value};
+use toonify::server::ServerBuilder;
+
+async fn spawn() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = ServerBuilder::new().job_queue(1).build().unwrap().router();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn submit(base: &str, operation: &str, data: &str) -> String {
+    let reply: Value = reqwest::Client::new()
+        .post(format!("{}/jobs/submit", base))
+        .json(&json!({ "operation": operation, "data": data }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    reply["job_id"].as_str().unwrap().to_string()
+}
+
+async fn wait_for(base: &str, job_id: &str) {
+    for _ in 0..100 {
+        let status: Value = reqwest::get(format!("{}/jobs/{}/status", base, job_id)).await.unwrap().json().await.unwrap();
+        if matches!(status["status"].as_str(), Some("completed" | "failed")) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Job {} did not finish in time", job_id);
+}
+
+#[tokio::test]
+async fn test_job_result_as_gzip() {
+    println!("=== Job artifacts: gzip result ===");
+
+    let base = spawn().await;
+    let job_id = submit(&base, "json_to_toon", r#"{"users":[{"id":1,"name":"Alice"}]}"#).await;
+    wait_for(&base, &job_id).await;
+
+    let response = reqwest::get(format!("{}/jobs/{}/result?format=gzip", base, job_id)).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/gzip");
+    let disposition = response.headers()["content-disposition"].to_str().unwrap().to_string();
+    assert!(disposition.contains(&format!("{}.toon.gz", job_id)), "{}", disposition);
+
+    let mut result = String::new();
+    GzDecoder::new(Cursor::new(response.bytes().await.unwrap())).read_to_string(&mut result).unwrap();
+    assert_eq!(result, "users[1]{id,name}:\n1,Alice");
+
+    // Plain JSON is still the default
+    let plain: Value = reqwest::get(format!("{}/jobs/{}/result", base, job_id)).await.unwrap().json().await.unwrap();
+    assert_eq!(plain["result"], json!(result));
+
+    assert_eq!(reqwest::get(format!("{}/jobs/missing/result?format=gzip", base)).await.unwrap().status(), 404);
+    assert_eq!(reqwest::get(format!("{}/jobs/{}/result?format=brotli", base, job_id)).await.unwrap().status(), 400);
+
+    println!("✓ Result downloaded gzipped\n");
+}
+
+#[tokio::test]
+async fn test_job_bundle_zip() {
+    println!("=== Job artifacts: zip bundle ===");
+
+    let base = spawn().await;
+    let toon = submit(&base, "json_to_toon", r#"{"a":[{"x":1}]}"#).await;
+    let json = submit(&base, "toon_to_json", "a[1]{x}:\n1").await;
+    let failed = submit(&base, "csv_to_toon", "x\n1").await;
+    for id in [&toon, &json, &failed] {
+        wait_for(&base, id).await;
+    }
+
+    let response = reqwest::get(format!("{}/jobs/bundle?ids={},{},{}", base, toon, json, failed)).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/zip");
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(response.bytes().await.unwrap())).unwrap();
+    let mut read = |name: &str| {
+        let mut text = String::new();
+        zip.by_name(name).unwrap().read_to_string(&mut text).unwrap();
+        text
+    };
+    assert_eq!(read(&format!("{}.toon", toon)), "a[1]{x}:\n1");
+    assert!(read(&format!("{}.json", json)).contains("\"x\""));
+
+    let manifest: Value = serde_json::from_str(&read("manifest.json")).unwrap();
+    assert_eq!(manifest.as_array().unwrap().len(), 3);
+    assert_eq!(manifest[0]["file"], json!(format!("{}.toon", toon)));
+    assert_eq!(manifest[2]["status"], json!("Failed"));
+    assert_eq!(manifest[2]["file"], Value::Null);
+    assert!(manifest[2]["error"].is_string());
+
+    let missing = reqwest::get(format!("{}/jobs/bundle?ids={},nope", base, toon)).await.unwrap();
+    assert_eq!(missing.status(), 404);
+
+    println!("✓ Bundle holds results and the manifest\n");
+}
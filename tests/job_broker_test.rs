@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use toonify::guards::ParserGuards;
+use toonify::job_broker::{self, JobBroker};
+use toonify::job_queue::{self, LeaseStore};
+use toonify::server::ServerBuilder;
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+// An API node that leaves every job to worker nodes
+async fn api_node(url: &str) -> String {
+    let server = ServerBuilder::new().job_queue(0).job_broker(JobBroker::new(url).unwrap()).build().unwrap();
+    spawn(server.router()).await
+}
+
+async fn wait_for_status(base: &str, job_id: &str, expected: &str) -> Value {
+    let mut status = Value::Null;
+    for _ in 0..100 {
+        status = reqwest::get(format!("{}/jobs/{}/status", base, job_id)).await.unwrap().json().await.unwrap();
+        if status["status"] == expected {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    status
+}
+
+// Needs a Valkey or Redis server: TOONIFY_TEST_REDIS_URL=redis://127.0.0.1:6379 cargo test --features distributed-jobs
+#[tokio::test]
+async fn test_worker_node_runs_api_jobs() {
+    println!("=== Distributed jobs: API node and worker node ===");
+
+    let Ok(url) = std::env::var("TOONIFY_TEST_REDIS_URL") else {
+        println!("⚠ TOONIFY_TEST_REDIS_URL not set, skipping\n");
+        return;
+    };
+    let base = api_node(&url).await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/jobs/submit", base))
+        .json(&json!({ "operation": "json_to_toon", "data": r#"{"users":[{"id":1}]}"#, "labels": {"node": "api"} }))
+        .send()
+        .await
+        .unwrap();
+    let job_id = response.json::<Value>().await.unwrap()["job_id"].as_str().unwrap().to_string();
+
+    job_broker::start_workers(JobBroker::new(&url).unwrap(), 2, ParserGuards::default());
+    let status = wait_for_status(&base, &job_id, "completed").await;
+    assert_eq!(status["status"], "completed", "Status: {}", status);
+
+    let result: Value = reqwest::get(format!("{}/jobs/{}/result", base, job_id)).await.unwrap().json().await.unwrap();
+    assert!(result["result"].as_str().unwrap().starts_with("users[1]{id}:"), "Result: {}", result);
+
+    let mut connection = JobBroker::new(&url).unwrap().connection().unwrap();
+    let stored = JobBroker::new(&url).unwrap().get(&mut connection, &job_id).unwrap().unwrap();
+    assert_eq!(stored.metadata.labels["node"], "api");
+
+    println!("✓ Job queued by the API node and run by a worker\n");
+}
+
+#[tokio::test]
+async fn test_jobs_visible_from_every_api_node() {
+    let Ok(url) = std::env::var("TOONIFY_TEST_REDIS_URL") else {
+        println!("⚠ TOONIFY_TEST_REDIS_URL not set, skipping\n");
+        return;
+    };
+    job_broker::start_workers(JobBroker::new(&url).unwrap(), 1, ParserGuards::default());
+    let first = api_node(&url).await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/jobs/submit", first))
+        .json(&json!({ "operation": "toon_to_json", "data": "id:1" }))
+        .send()
+        .await
+        .unwrap();
+    let job_id = response.json::<Value>().await.unwrap()["job_id"].as_str().unwrap().to_string();
+    assert_eq!(wait_for_status(&first, &job_id, "completed").await["status"], "completed");
+
+    // Started after the job was submitted, like a node replaced by a deploy
+    let second = api_node(&url).await;
+    assert_eq!(wait_for_status(&second, &job_id, "completed").await["status"], "completed");
+}
+
+#[test]
+fn test_leases_shared_through_redis() {
+    let Ok(url) = std::env::var("TOONIFY_TEST_REDIS_URL") else {
+        println!("⚠ TOONIFY_TEST_REDIS_URL not set, skipping\n");
+        return;
+    };
+    let (first, second) = (JobBroker::new(&url).unwrap(), JobBroker::new(&url).unwrap());
+    let name = format!("test:{}", job_queue::node_id());
+    let ttl = Duration::from_millis(300);
+
+    assert!(first.acquire(&name, "a", ttl).unwrap());
+    assert!(!second.acquire(&name, "b", ttl).unwrap(), "Another node sees the holder");
+    assert!(first.acquire(&name, "a", ttl).unwrap());
+    std::thread::sleep(Duration::from_millis(400));
+    assert!(second.acquire(&name, "b", ttl).unwrap(), "Lapsed without renewal");
+    first.release(&name, "a").unwrap();
+    assert!(!first.acquire(&name, "a", ttl).unwrap(), "Only the holder can release");
+    second.release(&name, "b").unwrap();
+}
+
+#[test]
+fn test_invalid_queue_url() {
+    assert!(JobBroker::new("not a url").err().unwrap().contains("Invalid job queue URL"));
+}
@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use toonify::job_queue::{self, JobFilter, LeaseStore, MemoryLeases, RecurringJob};
+use toonify::server::ServerBuilder;
+
+#[test]
+fn test_lease_has_one_holder_until_it_lapses() {
+    println!("=== Leases: one holder at a time ===");
+
+    let leases = MemoryLeases::default();
+    let ttl = Duration::from_millis(200);
+    assert!(leases.acquire("sweep", "a", ttl).unwrap());
+    assert!(!leases.acquire("sweep", "b", ttl).unwrap(), "Held by a");
+    assert!(leases.acquire("sweep", "a", ttl).unwrap(), "The holder renews");
+    assert!(leases.acquire("other", "b", ttl).unwrap(), "Leases are independent");
+
+    std::thread::sleep(Duration::from_millis(250));
+    assert!(leases.acquire("sweep", "b", ttl).unwrap(), "Lapsed without renewal");
+
+    leases.release("sweep", "a").unwrap();
+    assert!(!leases.acquire("sweep", "a", ttl).unwrap(), "Only the holder can release");
+    leases.release("sweep", "b").unwrap();
+    assert!(leases.acquire("sweep", "a", ttl).unwrap());
+
+    println!("✓ Lease taken, renewed, lapsed and released\n");
+}
+
+#[test]
+fn test_only_the_leader_runs() {
+    let leases: Arc<dyn LeaseStore> = Arc::new(MemoryLeases::default());
+    let runs: Vec<Arc<AtomicUsize>> = (0..3).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+    let _leaders: Vec<_> = runs
+        .iter()
+        .map(|count| {
+            let count = Arc::clone(count);
+            job_queue::run_as_leader(Arc::clone(&leases), "cleanup".to_string(), Duration::from_millis(50), move || {
+                count.fetch_add(1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+    std::thread::sleep(Duration::from_millis(500));
+
+    let counts: Vec<usize> = runs.iter().map(|count| count.load(Ordering::SeqCst)).collect();
+    assert_eq!(counts.iter().filter(|&&count| count > 0).count(), 1, "Runs per node: {:?}", counts);
+    assert!(counts.iter().sum::<usize>() >= 5, "The leader keeps running: {:?}", counts);
+}
+
+#[test]
+fn test_stopped_leader_releases_its_lease() {
+    let leases: Arc<dyn LeaseStore> = Arc::new(MemoryLeases::default());
+    let runs = Arc::new(AtomicUsize::new(0));
+    let count = Arc::clone(&runs);
+    // A long TTL, so only the release lets another node in
+    let leader = job_queue::run_as_leader(Arc::clone(&leases), "cleanup".to_string(), Duration::from_secs(60), move || {
+        count.fetch_add(1, Ordering::SeqCst);
+    });
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!leases.acquire("cleanup", "other", Duration::from_secs(1)).unwrap(), "Held by the leader");
+
+    leader.stop();
+    assert_eq!(runs.load(Ordering::SeqCst), 1, "Stopped before its next tick");
+    assert!(leases.acquire("cleanup", "other", Duration::from_secs(1)).unwrap(), "Released on stop");
+}
+
+#[test]
+fn test_recurring_job_fires_once_across_servers() {
+    let leases: Arc<dyn LeaseStore> = Arc::new(MemoryLeases::default());
+    let recurring = RecurringJob {
+        name: "nightly".to_string(),
+        every: Duration::from_millis(100),
+        operation: "json_to_toon".to_string(),
+        data: r#"{"a":1}"#.to_string(),
+        metadata: Default::default(),
+    };
+    let servers: Vec<_> = (0..2)
+        .map(|_| ServerBuilder::new().job_queue(1).recurring_job(recurring.clone()).leases(Arc::clone(&leases)).build().unwrap())
+        .collect();
+    std::thread::sleep(Duration::from_millis(550));
+
+    let submitted: Vec<usize> = servers
+        .iter()
+        .map(|server| job_queue::list_jobs(server.state().job_store.clone().unwrap()).len())
+        .collect();
+    assert_eq!(submitted.iter().filter(|&&count| count > 0).count(), 1, "Jobs per server: {:?}", submitted);
+    assert!((4..=7).contains(&submitted.iter().sum::<usize>()), "One job per tick: {:?}", submitted);
+
+    let store = servers.iter().find_map(|server| server.state().job_store.clone().filter(|store| !store.lock().unwrap().is_empty())).unwrap();
+    let filter = JobFilter { labels: [("schedule".to_string(), "nightly".to_string())].into(), ..Default::default() };
+    assert_eq!(job_queue::list_jobs_matching(store.clone(), &filter).len(), job_queue::list_jobs(store.clone()).len(), "Runs are labelled");
+
+    servers.iter().for_each(|server| server.stop_recurring_jobs());
+    let stopped = job_queue::list_jobs(Arc::clone(&store)).len();
+    std::thread::sleep(Duration::from_millis(250));
+    assert_eq!(job_queue::list_jobs(store).len(), stopped, "No runs after stopping");
+}
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use toonify::job_queue::{self, JobFilter, JobMetadata, JobStatus};
+use toonify::server::ServerBuilder;
+
+async fn spawn() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = ServerBuilder::new().job_queue(1).build().unwrap().router();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn submit(base: &str, body: Value) -> String {
+    let reply: Value = reqwest::Client::new()
+        .post(format!("{}/jobs/submit", base))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    reply["job_id"].as_str().unwrap().to_string()
+}
+
+async fn list(base: &str, query: &str) -> (u16, Value) {
+    let response = reqwest::get(format!("{}/jobs{}", base, query)).await.unwrap();
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap_or(Value::Null))
+}
+
+fn ids(listing: &Value) -> Vec<String> {
+    listing["jobs"].as_array().unwrap().iter().map(|job| job["id"].as_str().unwrap().to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_labels_and_submitter_filter_listings() {
+    println!("=== Job metadata: filtered listings ===");
+
+    let base = spawn().await;
+    let payments = submit(&base, json!({
+        "operation": "json_to_toon", "data": "{\"a\":1}",
+        "labels": {"team": "payments", "env": "prod"}, "submitter": "etl"
+    })).await;
+    let search = submit(&base, json!({
+        "operation": "json_to_toon", "data": "{\"a\":2}",
+        "labels": {"team": "search", "env": "prod"}, "submitter": "alice"
+    })).await;
+
+    let (_, all) = list(&base, "").await;
+    assert_eq!(ids(&all).len(), 2);
+    let job = all["jobs"].as_array().unwrap().iter().find(|job| job["id"] == json!(payments)).unwrap();
+    assert_eq!(job["labels"], json!({"env": "prod", "team": "payments"}));
+    assert_eq!(job["submitter"], json!("etl"));
+    assert!(job["submitted_at"].as_u64().unwrap() > 0);
+
+    assert_eq!(ids(&list(&base, "?labels=team:payments").await.1), vec![payments.clone()]);
+    assert_eq!(ids(&list(&base, "?labels=env:prod,team:search").await.1), vec![search.clone()]);
+    assert_eq!(ids(&list(&base, "?submitter=alice").await.1), vec![search]);
+    assert!(ids(&list(&base, "?labels=team:payments&submitter=alice").await.1).is_empty());
+
+    assert_eq!(list(&base, "?labels=team").await.0, 400);
+    assert_eq!(list(&base, "?status=stuck").await.0, 400);
+
+    println!("✓ Listings sliced by label and submitter\n");
+}
+
+#[tokio::test]
+async fn test_expired_jobs_are_removed() {
+    let base = spawn().await;
+    let short = submit(&base, json!({"operation": "json_to_toon", "data": "{\"a\":1}", "ttl_secs": 0})).await;
+    let kept = submit(&base, json!({"operation": "json_to_toon", "data": "{\"a\":2}", "ttl_secs": 3600})).await;
+
+    for _ in 0..60 {
+        if !ids(&list(&base, "").await.1).contains(&short) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let remaining = ids(&list(&base, "").await.1);
+    assert!(!remaining.contains(&short), "A finished job past its TTL is removed");
+    assert!(remaining.contains(&kept));
+
+    let status: Value = reqwest::get(format!("{}/jobs/{}/status", base, short)).await.unwrap().json().await.unwrap();
+    assert_eq!(status["status"], json!("not_found"));
+}
+
+#[test]
+fn test_sweep_only_removes_finished_jobs() {
+    let store = job_queue::create_job_store();
+    let metadata = JobMetadata { ttl_secs: Some(10), ..Default::default() };
+    let pending = job_queue::submit_job_with(store.clone(), "json_to_toon".into(), "{}".into(), metadata.clone());
+    let finished = job_queue::submit_job_with(store.clone(), "json_to_toon".into(), "{}".into(), metadata);
+    store.lock().unwrap().get_mut(&finished).unwrap().finished_at = Some(100);
+
+    assert_eq!(job_queue::sweep_expired(&store, 109), 0);
+    assert_eq!(job_queue::sweep_expired(&store, 110), 1);
+    assert_eq!(job_queue::sweep_expired(&store, u64::MAX), 0, "Pending jobs never expire");
+    assert!(job_queue::get_job(store.clone(), &pending).is_some());
+
+    let filter = JobFilter { status: Some("PENDING".parse::<JobStatus>().unwrap()), ..Default::default() };
+    assert_eq!(job_queue::list_jobs_matching(store, &filter).len(), 1);
+}
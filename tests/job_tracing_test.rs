@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use toonify::job_queue::TraceContext;
+use toonify::server::ServerBuilder;
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[tokio::test]
+async fn test_submit_continues_the_callers_trace() {
+    println!("=== Job tracing: traceparent on submit ===");
+
+    let base = spawn(ServerBuilder::new().job_queue(1).build().unwrap().router()).await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/jobs/submit", base))
+        .header("traceparent", TRACEPARENT)
+        .json(&json!({ "operation": "json_to_toon", "data": r#"{"a":1}"# }))
+        .send()
+        .await
+        .unwrap();
+    let traceparent = response.headers()["traceparent"].to_str().unwrap().to_string();
+    let reply: Value = response.json().await.unwrap();
+    assert_eq!(reply["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert!(traceparent.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"), "Same trace: {}", traceparent);
+    assert!(!traceparent.contains("00f067aa0ba902b7"), "A span of our own: {}", traceparent);
+
+    let job_id = reply["job_id"].as_str().unwrap();
+    let mut job = Value::Null;
+    for _ in 0..50 {
+        let listing: Value = reqwest::get(format!("{}/jobs", base)).await.unwrap().json().await.unwrap();
+        job = listing["jobs"][0].clone();
+        if job["status"] == "Completed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(job["id"], job_id);
+    assert_eq!(job["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(format!("00-{}-{}-01", job["trace_id"].as_str().unwrap(), job["span_id"].as_str().unwrap()), traceparent);
+    let (submitted, started, finished) = (job["submitted_at"].as_u64(), job["started_at"].as_u64(), job["finished_at"].as_u64());
+    assert!(submitted <= started && started <= finished && finished.is_some(), "Job: {}", job);
+
+    println!("✓ Trace recorded on the job with its timings\n");
+}
+
+#[tokio::test]
+async fn test_submit_without_trace_starts_one() {
+    let base = spawn(ServerBuilder::new().job_queue(1).build().unwrap().router()).await;
+    for header in [None, Some("not-a-traceparent"), Some("00-00000000000000000000000000000000-00f067aa0ba902b7-01")] {
+        let mut request = reqwest::Client::new().post(format!("{}/jobs/submit", base));
+        if let Some(header) = header {
+            request = request.header("traceparent", header);
+        }
+        let reply: Value = request.json(&json!({ "operation": "json_to_toon", "data": "{}" })).send().await.unwrap().json().await.unwrap();
+        let trace_id = reply["trace_id"].as_str().unwrap();
+        assert_eq!(trace_id.len(), 32, "{:?}: {}", header, trace_id);
+        assert_ne!(trace_id, "00000000000000000000000000000000");
+    }
+}
+
+#[test]
+fn test_traceparent_parsing() {
+    let trace = TraceContext::continue_from(TRACEPARENT).unwrap();
+    assert_eq!(trace.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(trace.span_id.len(), 16);
+    let child = trace.child();
+    assert_eq!(child.trace_id, trace.trace_id);
+    assert_ne!(child.span_id, trace.span_id);
+
+    for invalid in ["", "00-4bf92f3577b34da6-00f067aa0ba902b7-01", "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01"] {
+        assert!(TraceContext::continue_from(invalid).is_none(), "{:?}", invalid);
+    }
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use toonify::guards::ParserGuards;
+use toonify::job_queue;
+use toonify::server::ServerBuilder;
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn run_job(base: &str, operation: &str, data: &str) -> (String, Value) {
+    let reply: Value = reqwest::Client::new()
+        .post(format!("{}/jobs/submit", base))
+        .json(&json!({ "operation": operation, "data": data }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let job_id = reply["job_id"].as_str().unwrap().to_string();
+    let mut status = Value::Null;
+    for _ in 0..50 {
+        status = reqwest::get(format!("{}/jobs/{}/status", base, job_id)).await.unwrap().json().await.unwrap();
+        if status["status"] == "completed" || status["status"] == "failed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    (job_id, status)
+}
+
+#[tokio::test]
+async fn test_status_reports_usage() {
+    println!("=== Job usage: resources per job ===");
+
+    let base = spawn(ServerBuilder::new().job_queue(1).build().unwrap().router()).await;
+    let rows: Vec<Value> = (0..500).map(|id| json!({ "id": id, "name": format!("user-{}", id) })).collect();
+    let data = json!({ "users": rows }).to_string();
+    let (job_id, status) = run_job(&base, "json_to_toon", &data).await;
+    assert_eq!(status["status"], "completed", "Status: {}", status);
+
+    let result: Value = reqwest::get(format!("{}/jobs/{}/result", base, job_id)).await.unwrap().json().await.unwrap();
+    let usage = &status["usage"];
+    assert_eq!(usage["input_bytes"], data.len());
+    assert_eq!(usage["output_bytes"], result["result"].as_str().unwrap().len());
+    let peak = usage["peak_bytes_estimate"].as_u64().unwrap();
+    assert!(peak > usage["input_bytes"].as_u64().unwrap() + usage["output_bytes"].as_u64().unwrap(), "The parsed document counts: {}", usage);
+    #[cfg(unix)]
+    assert!(usage["cpu_time_ms"].as_f64().unwrap() >= 0.0, "Usage: {}", usage);
+
+    println!("✓ {} input bytes, {} output bytes, ~{} bytes peak\n", usage["input_bytes"], usage["output_bytes"], peak);
+}
+
+#[tokio::test]
+async fn test_failed_job_usage() {
+    let base = spawn(ServerBuilder::new().job_queue(1).build().unwrap().router()).await;
+    let (_, status) = run_job(&base, "json_to_toon", "{not json").await;
+    assert_eq!(status["status"], "failed");
+    assert_eq!(status["usage"]["input_bytes"], 9);
+    assert_eq!(status["usage"]["output_bytes"], 0);
+
+    let missing: Value = reqwest::get(format!("{}/jobs/nope/status", base)).await.unwrap().json().await.unwrap();
+    assert!(missing.get("usage").is_none());
+}
+
+#[test]
+fn test_peak_estimate_grows_with_the_document() {
+    let small = job_queue::run_operation("json_to_toon", r#"{"a":[1,2,3]}"#, ParserGuards::default()).1;
+    let rows: Vec<Value> = (0..100).map(|id| json!({ "id": id })).collect();
+    let large = job_queue::run_operation("json_to_toon", &json!({ "a": rows }).to_string(), ParserGuards::default()).1;
+    assert!(large.peak_bytes_estimate > small.peak_bytes_estimate * 10, "{:?} vs {:?}", small, large);
+
+    let (result, usage) = job_queue::run_operation("csv_to_toon", "a,b", ParserGuards::default());
+    assert!(result.unwrap_err().contains("Unknown operation"));
+    assert_eq!((usage.input_bytes, usage.output_bytes), (3, 0));
+}
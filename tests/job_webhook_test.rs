@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use axum::http::HeaderMap;
+use axum::routing::post;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use toonify::server::ServerBuilder;
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_callback_carries_the_trace() {
+    println!("=== Job webhooks: callback in the job's trace ===");
+
+    let (sender, mut received) = mpsc::channel::<(Option<String>, Value)>(4);
+    let receiver = axum::Router::new().route("/hook", post(move |headers: HeaderMap, axum::Json(body): axum::Json<Value>| {
+        let sender = sender.clone();
+        async move {
+            let traceparent = headers.get("traceparent").map(|value| value.to_str().unwrap().to_string());
+            sender.send((traceparent, body)).await.unwrap();
+        }
+    }));
+    let hook = spawn(receiver).await;
+
+    let base = spawn(ServerBuilder::new().job_queue(1).build().unwrap().router()).await;
+    let reply: Value = reqwest::Client::new()
+        .post(format!("{}/jobs/submit", base))
+        .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        .json(&json!({ "operation": "json_to_toon", "data": r#"{"id":1}"#, "callback_url": format!("{}/hook", hook) }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let (traceparent, body) = tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap().unwrap();
+    assert_eq!(body["job_id"], reply["job_id"]);
+    assert_eq!(body["status"], "completed");
+    assert_eq!(body["result"], "id:1");
+    assert_eq!(body["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    let traceparent = traceparent.expect("Callback sends a traceparent");
+    assert!(traceparent.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"), "{}", traceparent);
+
+    println!("✓ Callback delivered with the submitter's trace id\n");
+}
@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::toon::{format_toon, parse_toon, parse_toon_lossy, FormatOptions};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    if cfg!(windows) {
+        path.set_extension("exe");
+    }
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("line_endings");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+const CRLF_TOON: &str = "users[2]{id,name}:\r\n1,Alice\r\n2,Bob\r\nstatus:ok\r\n";
+
+#[test]
+fn test_parse_crlf_toon() {
+    println!("=== Line endings: CRLF TOON parses like LF ===");
+
+    let crlf = parse_toon(CRLF_TOON).expect("CRLF TOON should parse");
+    let lf = parse_toon(&CRLF_TOON.replace("\r\n", "\n")).unwrap();
+    println!("Parsed: {}", crlf);
+
+    assert_eq!(crlf, lf);
+    assert_eq!(crlf["users"][1]["name"], json!("Bob"));
+    assert_eq!(crlf["status"], json!("ok"));
+
+    println!("✓ No stray carriage returns in values\n");
+}
+
+#[test]
+fn test_lossy_parse_crlf_toon() {
+    let (value, errors) = parse_toon_lossy(CRLF_TOON);
+    assert!(errors.is_empty(), "Unexpected errors: {:?}", errors);
+    assert_eq!(value["users"][0]["name"], json!("Alice"));
+}
+
+#[test]
+fn test_fmt_keeps_crlf() {
+    println!("=== Line endings: fmt keeps CRLF ===");
+
+    let messy = "users [2] { id , name }:\r\n1 , Alice\r\n2,Bob\r\n";
+    let formatted = format_toon(messy, &FormatOptions::default()).expect("Format failed");
+    println!("Formatted: {:?}", formatted);
+
+    assert_eq!(formatted, "users[2]{id,name}:\r\n1,Alice\r\n2,Bob\r\n");
+    assert_eq!(format_toon(&formatted, &FormatOptions::default()).unwrap(), formatted);
+
+    let formatted_lf = format_toon(&messy.replace("\r\n", "\n"), &FormatOptions::default()).unwrap();
+    assert!(!formatted_lf.contains('\r'));
+
+    println!("✓ CRLF in, CRLF out\n");
+}
+
+#[test]
+fn test_cli_convert_crlf_toon() {
+    let input = temp_path("crlf.toon");
+    fs::write(&input, CRLF_TOON).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .output()
+        .expect("Failed to execute toonify binary");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    assert!(output.status.success(), "convert should succeed");
+    let value: Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    assert_eq!(value["users"][1], json!({"id": 2, "name": "Bob"}));
+}
+
+#[test]
+fn test_cli_fmt_check_accepts_formatted_crlf() {
+    let input = temp_path("formatted_crlf.toon");
+    fs::write(&input, "users[2]{id,name}:\r\n1,Alice\r\n2,Bob\r\n").unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["fmt", "--check"])
+        .arg(&input)
+        .output()
+        .expect("Failed to execute toonify binary");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    assert!(output.status.success(), "A formatted CRLF file should pass --check");
+}
+
+#[cfg(windows)]
+#[test]
+fn test_batch_with_backslash_paths() {
+    println!("=== Line endings: batch with backslash paths (Windows) ===");
+
+    let input_dir = temp_path("win_input");
+    let output_dir = temp_path("win_output");
+    let _ = fs::remove_dir_all(&input_dir);
+    let _ = fs::remove_dir_all(&output_dir);
+    fs::create_dir_all(input_dir.join("nested")).unwrap();
+    fs::write(input_dir.join("top.json"), r#"{"a":1}"#).unwrap();
+    fs::write(input_dir.join("nested").join("deep.json"), r#"{"b":2}"#).unwrap();
+
+    let input_arg = input_dir.to_string_lossy().replace('/', "\\");
+    let output_arg = output_dir.to_string_lossy().replace('/', "\\");
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["batch", "--input-dir", &input_arg, "--output-dir", &output_arg, "--from", "json", "--to", "toon", "--recursive", "--pattern", "**\\*.json"])
+        .output()
+        .expect("Failed to execute batch command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    assert!(output.status.success(), "Batch should succeed");
+    assert!(output_dir.join("top.toon").exists());
+    assert!(output_dir.join("nested").join("deep.toon").exists(), "Nested layout should be kept");
+
+    println!("✓ Backslash paths and patterns walk the tree\n");
+}
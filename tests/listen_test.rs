@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+struct Listener(Child);
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start_listener(port: u16, output_dir: &PathBuf) -> Listener {
+    let listener = Listener(
+        Command::new(get_binary_path())
+            .args(["listen", "--bind", "127.0.0.1", "--port", &port.to_string(), "--output-dir"])
+            .arg(output_dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to start listener"),
+    );
+
+    for _ in 0..50 {
+        if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+            return listener;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    panic!("Listener did not start in time");
+}
+
+#[test]
+fn test_listen_writes_toon_per_delivery() {
+    println!("=== Listen: webhook deliveries become TOON files ===");
+
+    let output_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/tmp/listen_incoming");
+    let _ = fs::remove_dir_all(&output_dir);
+    let listener = start_listener(18081, &output_dir);
+    let client = reqwest::blocking::Client::new();
+
+    // Named by path segment
+    let response = client
+        .post("http://127.0.0.1:18081/order-42")
+        .body(r#"{"order":{"id":42,"total":9.5}}"#)
+        .send()
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+
+    // Named by header, with unsafe characters stripped
+    let response = client
+        .post("http://127.0.0.1:18081/")
+        .header("X-Request-Id", "../evt 7")
+        .body(r#"{"events":[{"id":1},{"id":2}]}"#)
+        .send()
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+
+    // Invalid JSON is rejected and nothing is written
+    let response = client
+        .post("http://127.0.0.1:18081/")
+        .body("not json")
+        .send()
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+
+    drop(listener);
+
+    let mut names: Vec<String> = fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    println!("  Files: {:?}", names);
+    assert_eq!(names.len(), 2);
+
+    let order = names.iter().find(|name| name.ends_with("-order-42.toon")).expect("order file");
+    assert!(fs::read_to_string(output_dir.join(order)).unwrap().contains("order{id,total}:"));
+
+    let events = names.iter().find(|name| name.ends_with("-evt7.toon")).expect("events file");
+    assert!(fs::read_to_string(output_dir.join(events)).unwrap().contains("events[2]{id}:"));
+
+    println!("=== Listen PASSED ===");
+}
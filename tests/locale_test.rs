@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter::Converter;
+use toonify::locale::Locale;
+use toonify::type_rules::{TargetType, TypeRules};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("locale");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+#[test]
+fn test_locale_numbers() {
+    println!("=== Locale: numbers ===");
+
+    let de = locale("de");
+    assert_eq!(de.parse_number("1.234,56"), Some(json!(1234.56)));
+    assert_eq!(de.parse_number("1.234"), Some(json!(1234)), "A point groups thousands in German");
+    assert_eq!(de.parse_number("-0,5"), Some(json!(-0.5)));
+    assert_eq!(de.parse_number("1.234.567"), Some(json!(1234567)));
+    assert_eq!(de.parse_number("1234"), None, "Plain digits need no locale");
+    assert_eq!(de.parse_number("12.34"), None, "Groups are three digits");
+    assert_eq!(de.parse_number("10.0.0.1"), None);
+
+    let fr = locale("fr-FR");
+    assert_eq!(fr.parse_number("1 234,5"), Some(json!(1234.5)));
+    assert_eq!(fr.parse_number("2\u{202f}500"), Some(json!(2500)));
+
+    let us = locale("en-US");
+    assert_eq!(us.parse_number("1,234.5"), Some(json!(1234.5)));
+    assert_eq!(us.parse_number("1.5"), None, "Already how parse_value reads it");
+    assert_eq!(us.parse_number("1,5"), None);
+
+    println!("✓ Separators by locale\n");
+}
+
+#[test]
+fn test_locale_dates() {
+    let de = locale("de_DE");
+    assert_eq!(de.parse_date("01.05.2024").as_deref(), Some("2024-05-01"));
+    assert_eq!(de.parse_date("01.05.2024 10:30").as_deref(), Some("2024-05-01T10:30:00"));
+    assert_eq!(de.parse_date("31.02.2024"), None, "No such day");
+    assert_eq!(de.parse_date("01.05.24"), None, "Two-digit years are not guessed");
+
+    assert_eq!(locale("en-US").parse_date("05/01/2024").as_deref(), Some("2024-05-01"));
+    assert_eq!(locale("en-GB").parse_date("05/01/2024").as_deref(), Some("2024-01-05"));
+    assert_eq!(locale("ja").parse_date("2024/05/01").as_deref(), Some("2024-05-01"));
+    assert_eq!(locale("sv").parse_date("2024-05-01"), None, "Already ISO 8601");
+
+    let err = "xx".parse::<Locale>().unwrap_err();
+    assert!(err.contains("Unknown locale 'xx'"), "Error: {}", err);
+}
+
+#[test]
+fn test_locale_conversion_of_json_strings() {
+    println!("=== Locale: JSON strings ===");
+
+    let converter = Converter::builder()
+        .locale(locale("de"))
+        .type_rules(TypeRules::new(HashMap::from([("sku".to_string(), TargetType::String)])))
+        .build();
+    let json = r#"{"orders":[{"total":"1.234,56","date":"01.05.2024","note":"Lieferung 1,5 Tage","sku":"1.234","count":3}]}"#;
+    let value: Value = serde_json::from_str(&converter.convert(json, "json", "json").unwrap()).unwrap();
+    println!("Converted: {}", value);
+
+    assert_eq!(value["orders"][0]["total"], json!(1234.56));
+    assert_eq!(value["orders"][0]["date"], json!("2024-05-01"));
+    assert_eq!(value["orders"][0]["note"], json!("Lieferung 1,5 Tage"), "Text around a number is left alone");
+    assert_eq!(value["orders"][0]["sku"], json!("1.234"), "Fields with a type rule are skipped");
+    assert_eq!(value["orders"][0]["count"], json!(3));
+
+    println!("✓ Strings converted\n");
+}
+
+#[test]
+fn test_locale_reads_csv_cells_before_sniffing() {
+    let converter = Converter::builder().locale(locale("de")).build();
+    let (value, _) = converter.convert_to_value("menge,preis,datum\n1.234,\"12,50\",24.12.2024\n", "csv").unwrap();
+    assert_eq!(value["rows"][0]["menge"], json!(1234), "Without the locale this would read as 1.234");
+    assert_eq!(value["rows"][0]["preis"], json!(12.5));
+    assert_eq!(value["rows"][0]["datum"], json!("2024-12-24"));
+}
+
+#[test]
+fn test_convert_cli_locale() {
+    println!("=== Locale: CLI ===");
+
+    let input = temp_path("umsatz.json");
+    fs::write(&input, r#"{"umsatz":[{"betrag":"1.234,56","tag":"01.05.2024"}]}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .arg("--locale")
+        .arg("de")
+        .output()
+        .expect("Failed to execute convert command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Convert with --locale should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1234.56,2024-05-01"), "Output: {}", stdout);
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .arg("--locale")
+        .arg("tlh")
+        .output()
+        .expect("Failed to execute convert command");
+    assert!(!output.status.success(), "An unknown locale is rejected");
+
+    println!("✓ --locale applied\n");
+}
@@ -0,0 +1,47 @@
+use serde_json::{json, Value};
+use toonify::converter;
+
+const DAMAGED: &str = "??? truncated header from a rotated log\nlogs[4]{ts,level,msg}:\n1,info,start\n2,warn\n3,error,disk full\n4,info,a,b,c\nstatus:ok\n";
+
+#[test]
+fn test_lossy_skips_malformed_rows() {
+    println!("=== Lossy: skip malformed rows ===");
+
+    let converted = converter::toon_to_json_lossy(DAMAGED).expect("Lossy conversion should not fail");
+    let value: Value = serde_json::from_str(&converted.output).unwrap();
+    println!("Output: {}", converted.output);
+    for error in &converted.errors {
+        println!("Skipped {}", error);
+    }
+
+    assert_eq!(
+        value,
+        json!({
+            "logs": [
+                {"ts": 1, "level": "info", "msg": "start"},
+                {"ts": 3, "level": "error", "msg": "disk full"}
+            ],
+            "status": "ok"
+        })
+    );
+
+    let lines: Vec<usize> = converted.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![1, 4, 6]);
+    assert_eq!(converted.errors[1].content, "2,warn");
+    assert!(converted.errors[1].message.contains("expected 3 cells, found 2"));
+
+    println!("✓ Bad lines are skipped and reported\n");
+}
+
+#[test]
+fn test_lossy_matches_strict_on_clean_input() {
+    println!("=== Lossy: clean input ===");
+
+    let toon = converter::json_to_toon(r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}],"count":2}"#).unwrap();
+    let lossy = converter::toon_to_json_lossy(&toon).unwrap();
+
+    assert!(lossy.errors.is_empty());
+    assert_eq!(lossy.output, converter::toon_to_json(&toon).unwrap());
+
+    println!("✓ No errors and identical output for valid TOON\n");
+}
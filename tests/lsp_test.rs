@@ -0,0 +1,108 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{ChildStdout, Command, Stdio};
+use serde_json::{json, Value};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn frame(message: &Value) -> String {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+fn read_message(reader: &mut BufReader<ChildStdout>) -> Value {
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).expect("Failed to read header");
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).expect("Failed to read body");
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn test_lsp_session() {
+    println!("=== LSP: diagnostics, hover, formatting, symbols ===");
+
+    let uri = "file:///tmp/users.toon";
+    // users declares 4 rows but has 3, one of them malformed
+    let text = "users[4]{id,name}:\n1,Alice\n2\n3,Carol\nversion: 2\n";
+
+    let mut child = Command::new(get_binary_path())
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start lsp");
+    let mut stdin = child.stdin.take().unwrap();
+    let mut reader = BufReader::new(child.stdout.take().unwrap());
+
+    let mut request = |message: Value| stdin.write_all(frame(&message).as_bytes()).unwrap();
+
+    request(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "capabilities": {} } }));
+    let response = read_message(&mut reader);
+    assert_eq!(response["result"]["capabilities"]["hoverProvider"], true);
+
+    request(json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }));
+    request(json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+        "textDocument": { "uri": uri, "languageId": "toon", "version": 1, "text": text }
+    } }));
+    let published = read_message(&mut reader);
+    assert_eq!(published["method"], "textDocument/publishDiagnostics");
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    println!("  Diagnostics: {}", serde_json::to_string(diagnostics).unwrap());
+    assert!(diagnostics.iter().any(|d| d["severity"] == 1 && d["range"]["start"]["line"] == 2));
+    assert!(diagnostics.iter().any(|d| d["severity"] == 2 && d["message"].as_str().unwrap().contains("declares 4 rows but has 3")));
+
+    // Hover over Carol's row shows it as JSON
+    request(json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/hover", "params": {
+        "textDocument": { "uri": uri }, "position": { "line": 3, "character": 0 }
+    } }));
+    let hover = read_message(&mut reader);
+    let contents = hover["result"]["contents"]["value"].as_str().unwrap();
+    assert!(contents.contains("\"name\": \"Carol\""), "hover: {}", contents);
+
+    request(json!({ "jsonrpc": "2.0", "id": 3, "method": "textDocument/documentSymbol", "params": {
+        "textDocument": { "uri": uri }
+    } }));
+    let symbols = read_message(&mut reader);
+    let names: Vec<&str> = symbols["result"].as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["users", "version"]);
+    assert_eq!(symbols["result"][0]["range"]["end"]["line"], 3);
+
+    // Fix the document, then format it
+    let fixed = "users[1]{id,name}:\n  1,Alice\n\n\n";
+    request(json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+        "textDocument": { "uri": uri, "version": 2 }, "contentChanges": [{ "text": fixed }]
+    } }));
+    let published = read_message(&mut reader);
+    assert_eq!(published["params"]["diagnostics"], json!([]));
+
+    request(json!({ "jsonrpc": "2.0", "id": 4, "method": "textDocument/formatting", "params": {
+        "textDocument": { "uri": uri }, "options": { "tabSize": 2, "insertSpaces": true }
+    } }));
+    let edits = read_message(&mut reader);
+    assert_eq!(edits["result"][0]["newText"].as_str().unwrap().trim_end(), "users[1]{id,name}:\n1,Alice");
+
+    request(json!({ "jsonrpc": "2.0", "id": 5, "method": "shutdown" }));
+    assert_eq!(read_message(&mut reader)["id"], 5);
+    request(json!({ "jsonrpc": "2.0", "method": "exit" }));
+
+    assert!(child.wait().unwrap().success());
+    println!("=== LSP PASSED ===");
+}
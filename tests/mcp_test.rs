@@ -0,0 +1,90 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_mcp_tools() {
+    println!("=== toonify mcp ===");
+
+    let mut child = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["mcp", "--max-depth", "4"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start mcp");
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut send = |message: Value| writeln!(stdin, "{}", message).unwrap();
+    let mut receive = || -> Value {
+        let mut line = String::new();
+        stdout.read_line(&mut line).expect("Failed to read response");
+        serde_json::from_str(&line).unwrap_or_else(|e| panic!("stdout carries only JSON-RPC ({}): {:?}", e, line))
+    };
+
+    send(json!({
+        "jsonrpc": "2.0", "id": 1, "method": "initialize",
+        "params": { "protocolVersion": "2024-11-05", "capabilities": {}, "clientInfo": { "name": "test", "version": "0" } }
+    }));
+    let response = receive();
+    assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(response["result"]["serverInfo"]["name"], "toonify");
+    assert!(response["result"]["capabilities"]["tools"].is_object());
+
+    // The initialized notification gets no reply; the next line answers id 2
+    send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }));
+    send(json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }));
+    let response = receive();
+    assert_eq!(response["id"], 2);
+    let names: Vec<&str> = response["result"]["tools"].as_array().unwrap().iter().map(|tool| tool["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["convert", "stats", "validate"]);
+
+    let users = r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#;
+    send(json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": { "name": "convert", "arguments": { "input": users } } }));
+    let response = receive();
+    assert_eq!(response["result"]["isError"], false);
+    assert_eq!(response["result"]["content"][0]["text"].as_str().unwrap().trim_end(), "users[2]{id,name}:\n1,Alice\n2,Bob");
+
+    send(json!({ "jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": { "name": "stats", "arguments": { "input": users } } }));
+    let response = receive();
+    let stats: Value = serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+    assert_eq!(stats["from"], "json");
+    assert!(stats["bytes"]["toon"].as_u64().unwrap() < stats["bytes"]["json_pretty"].as_u64().unwrap());
+    assert!(stats["estimated_tokens_saved"].as_u64().unwrap() > 0);
+
+    // Tool failures are results the model can read, not protocol errors
+    send(json!({ "jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": { "name": "convert", "arguments": { "input": r#"{"a":[[[[[1]]]]]}"# } } }));
+    let response = receive();
+    assert_eq!(response["result"]["isError"], true);
+    assert!(response["result"]["content"][0]["text"].as_str().unwrap().contains("--max-depth"), "{}", response);
+
+    let schema = json!({ "users": { "type": "array", "fields": ["id", "name"] } });
+    send(json!({ "jsonrpc": "2.0", "id": 6, "method": "tools/call", "params": { "name": "validate", "arguments": { "input": users, "schema": schema } } }));
+    let response = receive();
+    assert_eq!(response["result"]["isError"], false, "{}", response);
+
+    let schema = json!({ "users": { "type": "array", "fields": ["id", "email"] } });
+    send(json!({ "jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": { "name": "validate", "arguments": { "input": users, "schema": schema } } }));
+    let response = receive();
+    assert_eq!(response["result"]["isError"], true);
+    assert!(response["result"]["content"][0]["text"].as_str().unwrap().contains("'email'"), "{}", response);
+
+    send(json!({ "jsonrpc": "2.0", "id": 8, "method": "tools/call", "params": { "name": "nope", "arguments": {} } }));
+    assert_eq!(receive()["error"]["code"], -32602);
+
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+
+    println!("✓ MCP initialize, tools/list and tools/call\n");
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toonify::merge::{merge, split, MergeStrategy};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    path
+}
+
+#[test]
+fn test_merge_union_concatenates_and_rekeys() {
+    println!("=== Merge: union strategy ===");
+
+    let a = json!({
+        "users": [{ "id": 1, "name": "Alice" }],
+        "orders": [{ "id": 10, "total": 5 }],
+        "version": 1
+    });
+    let b = json!({
+        "users": [{ "id": 2, "email": "bob@example.com" }],
+        "orders": [{ "id": "A-11", "total": 2.5 }],
+        "version": 1,
+        "region": "eu"
+    });
+
+    let (merged, warnings) = merge(vec![a, b], MergeStrategy::Union).unwrap();
+    assert_eq!(
+        merged["users"],
+        json!([
+            { "id": 1, "name": "Alice", "email": null },
+            { "id": 2, "name": null, "email": "bob@example.com" }
+        ])
+    );
+    // id is int in one input and str in the other
+    assert_eq!(merged["orders_2"], json!([{ "id": "A-11", "total": 2.5 }]));
+    assert_eq!(merged["version"], 1);
+    assert_eq!(merged["region"], "eu");
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("column id"), "{}", warnings[0]);
+
+    println!("=== Merge union PASSED ===");
+}
+
+#[test]
+fn test_merge_intersect_columns() {
+    let a = json!({ "users": [{ "id": 1, "name": "Alice" }], "mode": "a" });
+    let b = json!({ "users": [{ "id": 2, "email": "bob@example.com" }], "mode": "b" });
+
+    let (merged, warnings) = merge(vec![a, b], MergeStrategy::IntersectColumns).unwrap();
+    assert_eq!(merged["users"], json!([{ "id": 1 }, { "id": 2 }]));
+    assert_eq!(merged["mode"], "a");
+    assert_eq!(merged["mode_2"], "b");
+    assert_eq!(warnings.len(), 1);
+
+    assert!(merge(vec![json!([1, 2])], MergeStrategy::Union).is_err());
+    assert_eq!("intersect-columns".parse::<MergeStrategy>(), Ok(MergeStrategy::IntersectColumns));
+}
+
+#[test]
+fn test_split_writes_one_file_per_entity() {
+    println!("=== Split: one file per entity ===");
+
+    let parts = split(json!({ "users": [{ "id": 1 }], "version": 3 })).unwrap();
+    assert_eq!(parts[0], ("users".to_string(), json!({ "users": [{ "id": 1 }] })));
+    assert_eq!(parts[1].1, json!({ "version": 3 }));
+
+    let dir = temp_path("split_output");
+    let input = temp_path("split_input.toon");
+    fs::write(&input, "users[2]{id,name}:\n1,Alice\n2,Bob\norders[1]{id}:\n10").unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("split")
+        .arg(&input)
+        .arg("--output-dir")
+        .arg(&dir)
+        .output()
+        .expect("Failed to execute split command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+
+    let users = fs::read_to_string(dir.join("users.toon")).unwrap();
+    assert_eq!(users.trim_end(), "users[2]{id,name}:\n1,Alice\n2,Bob");
+    let orders = fs::read_to_string(dir.join("orders.toon")).unwrap();
+    assert_eq!(orders.trim_end(), "orders[1]{id}:\n10");
+
+    println!("=== Split PASSED ===");
+}
+
+#[test]
+fn test_split_numbers_colliding_file_names() {
+    println!("=== Split: colliding file names ===");
+
+    let dir = temp_path("split_collision_output");
+    let input = temp_path("split_collision_input.json");
+    fs::write(&input, r#"{"a.b": [{"id": 1}], "a_b": [{"id": 2}], "a_b_2": [{"id": 3}]}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("split")
+        .arg(&input)
+        .arg("--output-dir")
+        .arg(&dir)
+        .output()
+        .expect("Failed to execute split command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+
+    assert!(fs::read_to_string(dir.join("a_b.toon")).unwrap().trim_end().ends_with("\n1"));
+    assert!(fs::read_to_string(dir.join("a_b_2.toon")).unwrap().trim_end().ends_with("\n2"));
+    assert!(fs::read_to_string(dir.join("a_b_2_2.toon")).unwrap().trim_end().ends_with("\n3"));
+
+    println!("=== Split collision PASSED ===");
+}
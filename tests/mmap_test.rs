@@ -0,0 +1,94 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+// Just over the 16 MiB mapping threshold
+fn write_large_json(path: &PathBuf, rows: usize) {
+    let mut file = fs::File::create(path).unwrap();
+    write!(file, r#"{{"users":["#).unwrap();
+    for i in 0..rows {
+        if i > 0 {
+            write!(file, ",").unwrap();
+        }
+        write!(file, r#"{{"id":{},"name":"user number {}","active":true}}"#, i, i).unwrap();
+    }
+    write!(file, "]}}").unwrap();
+}
+
+#[test]
+fn test_large_input_is_memory_mapped() {
+    println!("=== Mmap: Large CLI input ===");
+
+    let input = temp_path("mmap_large.json");
+    write_large_json(&input, 400_000);
+    assert!(fs::metadata(&input).unwrap().len() >= 16 * 1024 * 1024);
+    let output = temp_path("mmap_large.toon");
+
+    let result = Command::new(get_binary_path())
+        .arg("convert").arg(&input)
+        .arg("-o").arg(&output)
+        .output()
+        .expect("Failed to execute toonify binary");
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(result.status.success(), "convert failed: {}", stderr);
+    assert!(stderr.contains("(memory-mapped)"), "Large input should be mapped: {}", stderr);
+
+    let toon = fs::read_to_string(&output).unwrap();
+    assert!(toon.starts_with("users[400000]{active,id,name}:\n"));
+    assert!(toon.trim_end().ends_with("true,399999,user number 399999"));
+
+    println!("✓ Large input converted through a memory map\n");
+}
+
+#[test]
+fn test_small_input_is_read() {
+    println!("=== Mmap: Small CLI input ===");
+
+    let input = temp_path("mmap_small.json");
+    fs::write(&input, r#"{"users":[{"id":1}]}"#).unwrap();
+
+    let result = Command::new(get_binary_path())
+        .arg("convert").arg(&input)
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(result.status.success());
+    assert!(!String::from_utf8_lossy(&result.stderr).contains("memory-mapped"));
+
+    println!("✓ Small input read normally\n");
+}
+
+#[test]
+fn test_large_invalid_utf8_is_rejected() {
+    println!("=== Mmap: Invalid UTF-8 ===");
+
+    let input = temp_path("mmap_invalid.json");
+    write_large_json(&input, 400_000);
+    fs::OpenOptions::new().append(true).open(&input).unwrap().write_all(&[0xff, 0xfe]).unwrap();
+
+    let result = Command::new(get_binary_path())
+        .arg("convert").arg(&input)
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("not valid UTF-8"));
+
+    println!("✓ Mapped input is validated before parsing\n");
+}
@@ -0,0 +1,221 @@
+use std::fs;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use toonify::converter::Converter;
+use toonify::guards::ParserGuards;
+use toonify::job_queue::{self, JobStatus};
+use toonify::server::ServerBuilder;
+use toonify::toon::{parse_toon_with, ParseOptions};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn guarded(guards: ParserGuards) -> Converter {
+    Converter::builder().guards(guards).build()
+}
+
+fn nested_json(depth: usize) -> String {
+    format!("{}1{}", "[".repeat(depth), "]".repeat(depth))
+}
+
+#[test]
+fn test_max_depth() {
+    println!("=== Parser Guards: --max-depth ===");
+
+    let converter = guarded(ParserGuards { max_depth: Some(8), ..Default::default() });
+    assert!(converter.convert(&format!(r#"{{"a":{}}}"#, nested_json(7)), "json", "toon").is_ok());
+
+    let error = converter.convert(&format!(r#"{{"a":{}}}"#, nested_json(8)), "json", "toon").unwrap_err();
+    println!("Error: {}", error);
+    assert!(error.contains("nested more than 8 levels deep"));
+    assert!(error.contains(r#"entity "a""#));
+
+    // Quoted JSON in a TOON cell is parsed too, so it counts
+    let toon = format!("rows[1]{{id,payload}}:\n  1,\"{}\"\n", nested_json(20));
+    let error = converter.convert(&toon, "toon", "json").unwrap_err();
+    assert!(error.contains("--max-depth"));
+
+    println!("✓ Deep nesting rejected\n");
+}
+
+#[test]
+fn test_max_entities() {
+    println!("=== Parser Guards: --max-entities ===");
+
+    let converter = guarded(ParserGuards { max_entities: Some(10), ..Default::default() });
+    // root + array + 2 rows * (object + 2 cells) = 8
+    assert!(converter.convert("users[2]{id,name}:\n  1,Alice\n  2,Bob\n", "toon", "json").is_ok());
+
+    let error = converter.convert("users[3]{id,name}:\n  1,Alice\n  2,Bob\n  3,Carol\n", "toon", "json").unwrap_err();
+    println!("Error: {}", error);
+    assert!(error.contains("more than 10 values"));
+
+    println!("✓ Oversized document rejected\n");
+}
+
+#[test]
+fn test_guards_stop_parsing_early() {
+    println!("=== Parser Guards: checked while parsing ===");
+
+    // Past serde_json's own recursion limit, the guard still reports first
+    let converter = guarded(ParserGuards { max_depth: Some(8), ..Default::default() });
+    let error = converter.convert(&nested_json(1000), "json", "toon").unwrap_err();
+    println!("Error: {}", error);
+    assert!(error.contains("nested more than 8 levels deep"));
+
+    let guards = ParserGuards { max_entities: Some(10), ..Default::default() };
+    let rows: String = (0..10_000).map(|i| format!("  {},name{}\n", i, i)).collect();
+    let toon = format!("users[10000]{{id,name}}:\n{}", rows);
+    let error = parse_toon_with(&toon, &ParseOptions { guards, ..Default::default() }).unwrap_err();
+    assert!(error.contains("more than 10 values"));
+
+    let error = guarded(guards).convert("[1,2,3,4,5,6,7,8,9,10,11]", "json", "toon").unwrap_err();
+    assert!(error.contains("more than 10 values"));
+
+    println!("✓ Parsing stops at the first value past a limit\n");
+}
+
+#[test]
+fn test_max_line_length() {
+    println!("=== Parser Guards: --max-line-length ===");
+
+    let converter = guarded(ParserGuards { max_line_length: Some(64), ..Default::default() });
+    let toon = format!("name: short\nnote: {}\n", "x".repeat(100));
+    let error = converter.convert(&toon, "toon", "json").unwrap_err();
+    println!("Error: {}", error);
+    assert!(error.contains("line 2 is 106 bytes long"));
+
+    // CRLF line endings don't count against the limit
+    assert!(converter.convert(&format!("note: {}\r\n", "x".repeat(58)), "toon", "json").is_ok());
+
+    println!("✓ Long line rejected before parsing\n");
+}
+
+#[test]
+fn test_guards_off_by_default() {
+    let input = format!(r#"{{"a":{}}}"#, nested_json(100));
+    assert!(Converter::default().convert(&input, "json", "json").is_ok());
+    assert!(ParserGuards::default().is_unlimited());
+}
+
+#[test]
+fn test_cli_guard_flags() {
+    println!("=== Parser Guards: convert flags ===");
+
+    let input = temp_path("parser_guards_deep.json");
+    fs::write(&input, format!(r#"{{"a":{}}}"#, nested_json(30))).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .args(["--max-depth", "16"])
+        .output()
+        .expect("Failed to execute convert");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("{}", stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("nested more than 16 levels deep"));
+
+    println!("✓ convert --max-depth\n");
+}
+
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[test]
+fn test_job_guards() {
+    println!("=== Parser Guards: jobs ===");
+
+    let guards = ParserGuards { max_depth: Some(8), ..Default::default() };
+    let server = ServerBuilder::new().job_queue(1).guards(guards).build().unwrap();
+    let store = server.state().job_store.clone().unwrap();
+    let deep = job_queue::submit_job(store.clone(), "json_to_toon".to_string(), format!(r#"{{"a":{}}}"#, nested_json(20)));
+    let shallow = job_queue::submit_job(store.clone(), "json_to_toon".to_string(), r#"{"a":[1]}"#.to_string());
+
+    let finished = |id: &str| {
+        (0..50).find_map(|_| {
+            let job = job_queue::get_job(store.clone(), id).filter(|job| job.finished_at.is_some());
+            if job.is_none() {
+                thread::sleep(Duration::from_millis(100));
+            }
+            job
+        })
+    };
+    let deep = finished(&deep).expect("Deep job should finish");
+    assert_eq!(deep.status, JobStatus::Failed);
+    assert!(deep.error.unwrap().contains("--max-depth"));
+    assert_eq!(finished(&shallow).expect("Shallow job should finish").status, JobStatus::Completed);
+
+    println!("✓ Jobs are parsed within the server's guards\n");
+}
+
+fn free_addr() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    listener.local_addr().unwrap().to_string()
+}
+
+#[test]
+fn test_server_guards() {
+    println!("=== Parser Guards: serve ===");
+
+    let http_addr = free_addr();
+    let child = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["serve", "--http-addr", &http_addr, "--grpc-addr", &free_addr(), "--max-line-length", "1024"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start server");
+    let _server = Server(child);
+    for _ in 0..50 {
+        if reqwest::blocking::get(format!("http://{}/healthz", http_addr)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    let client = reqwest::blocking::Client::new();
+    let huge_line = format!(r#"{{"blob":"{}"}}"#, "x".repeat(10_000));
+    let response = client
+        .post(format!("http://{}/json-to-toon", http_addr))
+        .json(&serde_json::json!({ "data": huge_line }))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: serde_json::Value = response.json().unwrap();
+    assert!(body["error"].as_str().unwrap().contains("--max-line-length"));
+
+    let response = client
+        .post(format!("http://{}/json-to-toon", http_addr))
+        .json(&serde_json::json!({ "data": r#"{"ok":true}"# }))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    println!("✓ Server refuses guarded input with 400\n");
+}
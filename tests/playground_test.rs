@@ -0,0 +1,49 @@
+use toonify::server::ServerBuilder;
+
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_playground_page() {
+    println!("=== Playground: embedded page ===");
+
+    let base = spawn(ServerBuilder::new().playground(true).build().unwrap().router()).await;
+    let response = reqwest::get(format!("{}/playground", base)).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+
+    let page = response.text().await.unwrap();
+    assert!(page.contains("<title>TOONify playground</title>"));
+    assert!(!page.contains("__FORMATS__"), "Formats are filled in");
+    assert!(page.contains(r#"const FORMATS = ["json","toon""#), "Page: {}", page);
+    assert!(page.contains("fetch(`convert/"), "The page converts through this server");
+
+    println!("✓ Page served with the registered formats\n");
+}
+
+#[tokio::test]
+async fn test_playground_off_by_default() {
+    let base = spawn(ServerBuilder::new().build().unwrap().router()).await;
+    let response = reqwest::get(format!("{}/playground", base)).await.unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_playground_under_a_prefix() {
+    let server = ServerBuilder::new().playground(true).health_routes(false).build().unwrap();
+    let base = spawn(axum::Router::new().nest("/toonify", server.router())).await;
+
+    assert_eq!(reqwest::get(format!("{}/toonify/playground", base)).await.unwrap().status(), 200);
+    // The relative URL the page posts to from /toonify/playground
+    let response = reqwest::Client::new()
+        .post(format!("{}/toonify/convert/json/toon", base))
+        .json(&serde_json::json!({ "data": r#"{"id":1}"# }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
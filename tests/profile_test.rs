@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::profile::profile;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_profile_columns() {
+    println!("=== Profile: per-column statistics ===");
+
+    let value = json!({
+        "users": [
+            { "id": 1, "name": "Alice", "score": 9.5, "tag": "a" },
+            { "id": 2, "name": "Bob", "score": null, "tag": 3 },
+            { "id": 3, "name": "Alice", "score": 7 }
+        ],
+        "tags": ["rust", "llm"],
+        "version": 3
+    });
+    let profiles = profile(&value);
+    assert_eq!(profiles.len(), 2, "root scalars are skipped");
+
+    let users = &profiles[0];
+    assert_eq!(users.rows, 3);
+    let columns: Vec<&str> = users.columns.iter().map(|column| column.name.as_str()).collect();
+    assert_eq!(columns, ["id", "name", "score", "tag"]);
+
+    let id = &users.columns[0];
+    assert_eq!((id.inferred_type.as_str(), id.distinct, id.nulls), ("int", 3, 0));
+    assert_eq!((id.min.clone(), id.max.clone()), (Some(json!(1)), Some(json!(3))));
+
+    let name = &users.columns[1];
+    assert_eq!(name.distinct, 2);
+    assert_eq!(name.max, Some(json!("Bob")));
+    assert_eq!(name.avg_length, Some(13.0 / 3.0));
+
+    let score = &users.columns[2];
+    assert_eq!(score.inferred_type, "num");
+    assert!((score.null_rate - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(score.min, Some(json!(7)));
+
+    // A missing cell counts as null; mixed columns have no min/max
+    let tag = &users.columns[3];
+    assert_eq!((tag.inferred_type.as_str(), tag.nulls), ("mixed", 1));
+    assert_eq!(tag.min, None);
+
+    assert_eq!(profiles[1].name, "tags");
+    assert_eq!(profiles[1].columns[0].name, "value");
+
+    println!("=== Profile PASSED ===");
+}
+
+#[test]
+fn test_profile_cli() {
+    let mut input = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    input.push("target");
+    input.push("tmp");
+    fs::create_dir_all(&input).unwrap();
+    input.push("profile_input.toon");
+    fs::write(&input, "users[2]{id,email}:\n1,a@example.com\n2,").unwrap();
+
+    let output = Command::new(get_binary_path()).arg("profile").arg(&input).output().expect("Failed to run profile");
+    assert!(output.status.success());
+    let text = String::from_utf8_lossy(&output.stdout);
+    println!("{}", text);
+    assert!(text.starts_with("users (2 rows)"));
+    assert!(text.contains("distinct") && text.contains("email"));
+
+    let output = Command::new(get_binary_path()).arg("profile").arg(&input).arg("--json").output().expect("Failed to run profile");
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["columns"][0]["inferred_type"], "int");
+    assert_eq!(json[0]["columns"][0]["distinct"], 2);
+}
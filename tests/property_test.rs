@@ -0,0 +1,145 @@
+use proptest::prelude::*;
+use serde_json::{Map, Number, Value};
+use toonify::selftest::{self, SelftestOptions};
+use toonify::toon::{parse_toon, SerializeOptions};
+
+// Scalars TOON can round-trip without typed headers
+fn arb_scalar() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(|n| Value::Number(Number::from(n))),
+        any::<u64>().prop_map(|n| Value::Number(Number::from(n))),
+        any::<f64>().prop_filter_map("a finite float", Number::from_f64).prop_map(Value::Number),
+        any::<String>().prop_map(Value::String),
+    ]
+}
+
+// Arrays and objects, which cells and list items hold as quoted JSON
+fn arb_nested() -> impl Strategy<Value = Value> {
+    let container = arb_scalar().prop_recursive(3, 16, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::btree_map(any::<String>(), inner, 0..4)
+                .prop_map(|fields| Value::Object(fields.into_iter().collect())),
+        ]
+    });
+    container.prop_filter("a container", |v| v.is_array() || v.is_object())
+}
+
+fn arb_cell() -> impl Strategy<Value = Value> {
+    prop_oneof![4 => arb_scalar(), 1 => arb_nested()]
+}
+
+fn arb_key() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9_]{0,8}"
+}
+
+// A non-null value for one column so a row never serializes as a blank line
+fn arb_row(columns: Vec<String>) -> impl Strategy<Value = Value> {
+    let cells = prop::collection::vec(arb_cell(), columns.len());
+    cells.prop_map(move |mut cells| {
+        if cells[0].is_null() {
+            cells[0] = Value::Bool(true);
+        }
+        Value::Object(columns.iter().cloned().zip(cells).collect::<Map<_, _>>())
+    })
+}
+
+fn arb_table() -> impl Strategy<Value = Value> {
+    prop::collection::btree_set(arb_key(), 1..5)
+        .prop_flat_map(|columns| {
+            let columns: Vec<String> = columns.into_iter().collect();
+            prop::collection::vec(arb_row(columns), 1..6)
+        })
+        .prop_map(Value::Array)
+}
+
+fn arb_entity() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        arb_table(),
+        // An object would make the list a table
+        prop::collection::vec(arb_cell().prop_filter("list items are non-null scalars or arrays", |v| !v.is_null() && !v.is_object()), 0..4)
+            .prop_map(Value::Array),
+        arb_scalar(),
+    ]
+}
+
+fn arb_document() -> impl Strategy<Value = Value> {
+    prop::collection::btree_map(arb_key(), arb_entity(), 1..5)
+        .prop_map(|entities| Value::Object(entities.into_iter().collect()))
+}
+
+proptest! {
+    #[test]
+    fn prop_json_toon_roundtrip(document in arb_document()) {
+        prop_assert_eq!(selftest::check_roundtrip(&document), Ok(()));
+    }
+
+    #[test]
+    fn prop_typed_headers_roundtrip(document in arb_document()) {
+        let options = SerializeOptions { typed_headers: true, ..Default::default() };
+        prop_assert_eq!(selftest::check_roundtrip_with(&document, &options), Ok(()));
+    }
+
+    #[test]
+    fn prop_parser_never_panics(input in "\\PC{0,200}") {
+        let _ = parse_toon(&input);
+    }
+
+    #[test]
+    fn prop_parser_never_panics_on_structured_noise(input in "([a-z_]{1,6}(\\[[0-9]{1,2}\\])?(\\{[a-z:,]{0,12}\\})?:\n([a-z0-9\",:\\[\\]{} ]{0,16}\n){0,4}){1,4}") {
+        let _ = parse_toon(&input);
+    }
+}
+
+#[test]
+fn test_builtin_selftest_passes() {
+    println!("=== Selftest: Built-in generator ===");
+
+    let report = selftest::run(&SelftestOptions { cases: 512, ..Default::default() });
+    for failure in &report.failures {
+        println!("{}", failure);
+    }
+
+    assert_eq!(report.cases, 512);
+    assert!(report.passed(), "Built-in self test should pass");
+
+    println!("✓ {} generated cases passed\n", report.cases);
+}
+
+#[test]
+fn test_backslashes_and_line_breaks_roundtrip() {
+    println!("=== Property: Escaped strings ===");
+
+    for json in [r#"{"u":[{"p":"C:\\dir"}]}"#, r#"{"u":[{"p":"a\nb"},{"p":"c"}]}"#, r#"{"a":"x\ny"}"#] {
+        let document: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(selftest::check_roundtrip(&document), Ok(()), "{}", json);
+    }
+
+    println!("✓ Backslashes and newlines survive the round trip\n");
+}
+
+#[test]
+fn test_header_lookalike_strings_roundtrip() {
+    println!("=== Property: Strings that read like entries ===");
+
+    for json in [r#"{"a":[{"a":true}],"da":"//"}"#, r#"{"o":{"a":"e{x","b":"}:y"}}"#, r#"{"u":[{"a":"e[1]{x","b":"}:y"}]}"#] {
+        let document: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(selftest::check_roundtrip(&document), Ok(()), "{}", json);
+    }
+
+    println!("✓ Values and rows never turn into headers\n");
+}
+
+#[test]
+fn test_large_integers_roundtrip() {
+    println!("=== Property: Integers past i64 ===");
+
+    for json in [r#"{"n":18446744073709551615}"#, r#"{"u":[{"n":9223372036854775808},{"n":1}]}"#, r#"{"l":[18446744073709551615,-1]}"#] {
+        let document: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(selftest::check_roundtrip(&document), Ok(()), "{}", json);
+    }
+
+    println!("✓ u64 values come back exact\n");
+}
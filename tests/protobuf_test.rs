@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toonify::binary::BinarySchema;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+const PROTO: &str = r#"syntax = "proto3";
+package demo;
+
+message User {
+  int64 id = 1;
+  string name = 2;
+  bool active = 3;
+}
+
+message Users {
+  repeated User users = 1;
+}
+"#;
+
+#[test]
+fn test_protobuf_roundtrip_api() {
+    println!("=== Protobuf: encode/decode through a runtime-compiled .proto ===");
+
+    let proto = temp_path("users_api.proto");
+    fs::write(&proto, PROTO).unwrap();
+
+    // Two messages: the caller has to pick one
+    let error = BinarySchema::load(&proto, None).err().expect("ambiguous message");
+    assert!(error.contains("--message"), "{}", error);
+
+    let schema = BinarySchema::load(&proto, Some("Users")).unwrap();
+    let value = json!({ "users": [
+        { "id": 1, "name": "Alice", "active": true },
+        { "id": 2, "name": "Bob", "active": false }
+    ] });
+    let bytes = schema.encode(&value).unwrap();
+    assert!(bytes.len() < value.to_string().len());
+
+    // Defaults (active: false) come back, and int64 stays a number
+    assert_eq!(schema.decode(&bytes).unwrap(), value);
+
+    let error = schema.encode(&json!({ "users": [{ "id": 1, "email": "x" }] })).unwrap_err();
+    assert!(error.contains("demo.Users"), "{}", error);
+
+    println!("=== Protobuf API PASSED ===");
+}
+
+#[test]
+fn test_protobuf_cli_encode_decode() {
+    let proto = temp_path("users_cli.proto");
+    let input = temp_path("users_pb.toon");
+    let encoded = temp_path("users.pb");
+    fs::write(&proto, PROTO).unwrap();
+    fs::write(&input, "users[2]{id,name,active}:\n1,Alice,true\n2,Bob,false").unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("encode")
+        .arg("--schema")
+        .arg(&proto)
+        .args(["--message", "demo.Users", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&encoded)
+        .output()
+        .expect("Failed to execute encode");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+
+    let output = Command::new(get_binary_path())
+        .arg("decode")
+        .arg("--schema")
+        .arg(&proto)
+        .args(["--message", "Users", "--input"])
+        .arg(&encoded)
+        .output()
+        .expect("Failed to execute decode");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim_end(), "users[2]{id,name,active}:\n1,Alice,true\n2,Bob,false");
+}
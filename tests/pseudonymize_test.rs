@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter::Converter;
+use toonify::pseudonymize::Pseudonymizer;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_pseudonyms_are_deterministic_and_joinable() {
+    println!("=== Pseudonymize: deterministic, shape-preserving ===");
+
+    let pseudonyms = Pseudonymizer::new(&["email", "customer_id", "id"], b"salt-1");
+    let value = json!({
+        "customers": [
+            { "id": 7, "email": "alice@example.org", "plan": "pro" },
+            { "id": 8, "email": "bob@example.org", "plan": "free" }
+        ],
+        "orders": [{ "customer_id": 7, "contact": { "email": "alice@example.org" } }]
+    });
+    let out = pseudonyms.apply(value.clone());
+    println!("Pseudonymized: {}", out);
+
+    let alice = &out["customers"][0];
+    assert!(alice["id"].is_u64(), "integers stay integers");
+    assert_ne!(alice["id"], 7);
+    let email = alice["email"].as_str().unwrap();
+    assert!(email.starts_with("anon-") && email.ends_with("@example.com"));
+    assert_eq!(alice["plan"], "pro", "other fields are untouched");
+
+    // Same value → same pseudonym, across fields and nesting, so joins still work
+    assert_eq!(out["orders"][0]["customer_id"], alice["id"]);
+    assert_eq!(out["orders"][0]["contact"]["email"], alice["email"]);
+    assert_ne!(out["customers"][1]["email"], alice["email"]);
+    assert_eq!(pseudonyms.apply(value.clone()), out);
+
+    // A different salt gives unrelated pseudonyms
+    let other = Pseudonymizer::new(&["email"], b"salt-2").apply(value);
+    assert_ne!(other["customers"][0]["email"], alice["email"]);
+
+    assert_eq!(Pseudonymizer::parse_fields("fields=email, name").unwrap(), ["email", "name"]);
+    assert!(Pseudonymizer::parse_fields("fields=").is_err());
+
+    println!("=== Pseudonymize PASSED ===");
+}
+
+#[test]
+fn test_pseudonymize_in_converter_and_cli() {
+    let converter = Converter::builder().flatten(true).pseudonymize(Pseudonymizer::new(&["name"], b"k")).build();
+    let toon = converter.json_to_toon(r#"{"users":[{"id":1,"profile":{"name":"Alice"}}]}"#).unwrap();
+    assert!(!toon.contains("Alice"));
+    assert!(toon.contains("anon-"));
+
+    let mut input = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    input.push("target");
+    input.push("tmp");
+    std::fs::create_dir_all(&input).unwrap();
+    input.push("pseudonymize_input.json");
+    std::fs::write(&input, r#"{"users":[{"id":1,"name":"Alice"}]}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&input)
+        .args(["--to", "json", "--pseudonymize", "fields=name", "--salt", "env:TOONIFY_TEST_SALT"])
+        .env("TOONIFY_TEST_SALT", "k")
+        .output()
+        .expect("Failed to execute convert");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let expected = Pseudonymizer::new(&["name"], b"k").apply(json!({"users":[{"id":1,"name":"Alice"}]}));
+    assert_eq!(json, expected);
+}
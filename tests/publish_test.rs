@@ -0,0 +1,99 @@
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Command;
+use flate2::read::GzDecoder;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("publish");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path
+}
+
+fn publish(input_dir: &PathBuf, output: &PathBuf, extra: &[&str]) -> std::process::Output {
+    Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("publish")
+        .arg("--input-dir")
+        .arg(input_dir)
+        .arg("--output")
+        .arg(output)
+        .args(extra)
+        .output()
+        .expect("Failed to execute publish command")
+}
+
+#[test]
+fn test_publish_site() {
+    println!("=== Publish: static dataset site ===");
+
+    let root = temp_dir("site");
+    let data = root.join("data");
+    fs::create_dir_all(data.join("sales")).unwrap();
+    fs::write(data.join("users.json"), r#"{"users":[{"id":1,"name":"Alice <admin>"},{"id":2,"name":"Bob"}],"meta":{"owner":"ops"}}"#).unwrap();
+    fs::write(data.join("sales").join("q1.toon"), "orders[2]{id,total}:\n1,9.5\n2,12").unwrap();
+    fs::write(data.join("README.md"), "# Not a dataset").unwrap();
+    let site = root.join("site");
+
+    let output = publish(&data, &site, &["--title", "Team data"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("Stderr: {}", stderr);
+    assert!(output.status.success(), "publish should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Published 2 datasets (3 tables)"));
+    assert!(stderr.contains("Skipping") && stderr.contains("README.md"));
+
+    let index = fs::read_to_string(site.join("index.html")).unwrap();
+    assert!(index.contains("<h1>Team data</h1>"));
+    assert!(index.contains(r#"<a href="sales/q1/orders.html">orders</a>"#), "Index: {}", index);
+    assert!(index.contains(r#"href="sales/q1.toon.gz""#));
+    assert!(index.contains(r#"href="users/meta.json""#));
+
+    let page = fs::read_to_string(site.join("sales/q1/orders.html")).unwrap();
+    assert!(page.contains(r#"<a href="../../index.html">Team data</a>"#));
+    assert!(page.contains("<td>12</td>"));
+    assert!(page.contains(r#"href="orders.toon""#));
+    assert!(fs::read_to_string(site.join("users/users.html")).unwrap().contains("Alice &lt;admin&gt;"), "Cells are escaped");
+
+    assert_eq!(fs::read_to_string(site.join("sales/q1/orders.toon")).unwrap(), "orders[2]{id,total}:\n1,9.5\n2,12");
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(site.join("users.json")).unwrap()).unwrap();
+    assert_eq!(json["meta"]["owner"], "ops");
+    let mut unzipped = String::new();
+    GzDecoder::new(fs::File::open(site.join("users/users.toon.gz")).unwrap()).read_to_string(&mut unzipped).unwrap();
+    assert!(unzipped.starts_with("users[2]{id,name}:"), "Gzip download: {}", unzipped);
+
+    println!("✓ Index, table pages and downloads written\n");
+}
+
+#[test]
+fn test_publish_inside_input_dir() {
+    let root = temp_dir("nested");
+    fs::write(root.join("events.json"), r#"{"events":[{"id":1}]}"#).unwrap();
+    let site = root.join("site");
+
+    for _ in 0..2 {
+        let output = publish(&root, &site, &["--pattern", "*.json"]);
+        assert!(output.status.success(), "Stderr: {}", String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Published 1 datasets"), "The site's own downloads are not republished");
+    }
+}
+
+#[test]
+fn test_publish_empty_dir_fails() {
+    let root = temp_dir("empty");
+    let output = publish(&root, &root.join("site"), &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No datasets found"));
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use toonify::server::ServerBuilder;
+
+async fn quota(client: &reqwest::Client, base: &str) -> serde_json::Value {
+    let response = client.get(format!("{}/rate-limit", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limit_headers_and_quota_endpoint() {
+    println!("=== Rate limit headers and /rate-limit ===");
+
+    let server = ServerBuilder::new().rate_limit(3, Duration::from_secs(60)).build().unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = server.router();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap()
+    });
+    let client = reqwest::Client::new();
+
+    let body = quota(&client, &base).await;
+    assert_eq!((body["limit"].as_u64(), body["remaining"].as_u64(), body["window_secs"].as_u64()), (Some(3), Some(3), Some(60)));
+
+    for expected in [2, 1, 0] {
+        let response = client.post(format!("{}/json-to-toon", base)).json(&serde_json::json!({ "data": r#"{"a":1}"# })).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["x-ratelimit-limit"], "3");
+        assert_eq!(response.headers()["x-ratelimit-remaining"], expected.to_string().as_str());
+    }
+
+    let response = client.post(format!("{}/json-to-toon", base)).json(&serde_json::json!({ "data": r#"{"a":1}"# })).send().await.unwrap();
+    assert_eq!(response.status(), 429);
+    assert_eq!(response.headers()["x-ratelimit-remaining"], "0");
+    let retry_after: u64 = response.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after), "Retry-After: {}", retry_after);
+
+    // Asking for the quota does not spend it
+    for _ in 0..2 {
+        let body = quota(&client, &base).await;
+        assert_eq!(body["remaining"], 0);
+        assert!(body["reset_secs"].as_u64().unwrap() > 0);
+    }
+
+    println!("✓ Quota reported in headers and on /rate-limit\n");
+}
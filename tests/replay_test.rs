@@ -0,0 +1,144 @@
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::Duration;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn free_addr() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    listener.local_addr().unwrap().to_string()
+}
+
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start_server(http_addr: &str, extra: &[&str]) -> Server {
+    let server = Server(
+        Command::new(get_binary_path())
+            .env("TOONIFY_CONFIG", "none")
+            .args(["serve", "--http-addr", http_addr, "--grpc-addr", &free_addr()])
+            .args(extra)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to start server"),
+    );
+    for _ in 0..50 {
+        if reqwest::blocking::get(format!("http://{}/healthz", http_addr)).is_ok() {
+            return server;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    panic!("Server did not start in time");
+}
+
+fn replay(log: &Path, target: &str, report: &Path) -> Output {
+    Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("replay")
+        .arg(log)
+        .args(["--target", &format!("http://{}", target), "--report"])
+        .arg(report)
+        .output()
+        .expect("Failed to execute replay")
+}
+
+#[test]
+fn test_replay_against_another_instance() {
+    println!("=== Replay: recorded traffic against a second server ===");
+
+    let log = temp_path("replay_audit.jsonl");
+    let report = temp_path("replay_report.jsonl");
+    let _ = fs::remove_file(&log);
+
+    // Record traffic on one instance
+    let recording_addr = free_addr();
+    {
+        let _recording = start_server(&recording_addr, &["--audit-log-payloads", "--audit-log", log.to_str().unwrap()]);
+        let client = reqwest::blocking::Client::new();
+        for (path, data) in [
+            ("/json-to-toon", r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#),
+            ("/toon-to-json", "items[1]{sku,qty}:\n  A1,2\n"),
+            ("/convert/json/yaml", r#"{"a":1}"#),
+            ("/json-to-toon", "{not json"),
+        ] {
+            client.post(format!("http://{}{}", recording_addr, path)).json(&serde_json::json!({ "data": data })).send().unwrap();
+        }
+    }
+    assert_eq!(fs::read_to_string(&log).unwrap().lines().count(), 4);
+
+    // Replay on another
+    let target = free_addr();
+    let _target = start_server(&target, &[]);
+    let output = replay(&log, &target, &report);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("4 replayed"));
+    assert!(stdout.contains("4 identical"));
+    assert_eq!(fs::read_to_string(&report).unwrap(), "");
+
+    // A recorded result the target no longer produces
+    let tampered = temp_path("replay_audit_tampered.jsonl");
+    let lines: Vec<String> = fs::read_to_string(&log)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let mut record: serde_json::Value = serde_json::from_str(line).unwrap();
+            if record["endpoint"] == "/json-to-toon" && record["status"] == 200 {
+                record["output"] = serde_json::Value::String(record["output"].as_str().unwrap().replace("Bob", "Robert"));
+            }
+            record.to_string()
+        })
+        .collect();
+    fs::write(&tampered, lines.join("\n") + "\n").unwrap();
+
+    let output = replay(&tampered, &target, &report);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("{}", stderr);
+    assert!(!output.status.success(), "differences fail the replay");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 different"));
+    assert!(stderr.contains("line 1 /json-to-toon"));
+    let differences: Vec<serde_json::Value> = fs::read_to_string(&report).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(differences.len(), 1);
+    assert!(differences[0]["expected"].as_str().unwrap().contains("Robert"));
+    assert!(differences[0]["actual"].as_str().unwrap().contains("Bob"));
+
+    println!("=== Replay PASSED ===");
+}
+
+#[test]
+fn test_replay_skips_records_without_payloads() {
+    let log = temp_path("replay_no_payloads.jsonl");
+    fs::write(&log, r#"{"timestamp":"2025-01-01T00:00:00.000Z","transport":"rest","endpoint":"/json-to-toon","from":"json","to":"toon","client_ip":"127.0.0.1","forwarded_for":null,"api_key":null,"input_bytes":2,"output_bytes":0,"duration_ms":0.1,"status":200,"error":null}"#.to_string() + "\n").unwrap();
+
+    let target = free_addr();
+    let _target = start_server(&target, &[]);
+    let output = replay(&log, &target, &temp_path("replay_no_payloads_report.jsonl"));
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 skipped"));
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_report_writes_self_contained_html() {
+    println!("=== Report: HTML audit of a conversion ===");
+
+    let input = temp_path("report_input.json");
+    let schema = temp_path("report_schema.json");
+    let report = temp_path("report.html");
+    fs::write(&input, r#"{"users":[{"id":1,"name":"<Alice>"},{"id":2,"name":"Bob"},{"id":3,"name":"Carol"}],"version":3}"#).unwrap();
+    // email is required but missing, so the schema check fails
+    fs::write(&schema, r#"{"users":{"type":"array","fields":["id","name","email"]}}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("report")
+        .arg(&input)
+        .arg("--schema")
+        .arg(&schema)
+        .arg("--max-rows")
+        .arg("2")
+        .arg("--output")
+        .arg(&report)
+        .output()
+        .expect("Failed to execute report command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "report should succeed even when validation fails");
+
+    let html = fs::read_to_string(&report).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    // No external assets
+    assert!(!html.contains("<script src") && !html.contains("<link"));
+    assert!(html.contains("✓ TOON round-trip reproduces the input"));
+    assert!(html.contains("class=\"fail\">✗ Schema"));
+    assert!(html.contains("id=\"entity-users\""));
+    assert!(html.contains("<td>&lt;Alice&gt;</td>"), "cells are escaped");
+    assert!(html.contains("Showing 2 of 3 rows"));
+    assert!(!html.contains("<td>Carol</td>"));
+    assert!(html.contains("TOON saves"));
+
+    println!("=== Report PASSED ===");
+}
@@ -432,3 +432,44 @@ fn test_roundtrip_with_moka_cache_and_tmp() {
     fs::remove_dir_all(tmp_dir).expect("Failed to clean tmp dir after test");
     println!("Cleaned up tmp dir");
 }
+
+#[test]
+fn test_number_formatting_matches_json() {
+    let json = r#"{"numbers":[{"big":9223372036854775807,"neg":-9223372036854775808,"float":0.1,"exp":1e300,"whole":2.0,"tiny":5e-324}]}"#;
+
+    println!("=== Number Formatting Test ===");
+
+    let toon = converter::json_to_toon(json).expect("Failed to convert JSON to TOON");
+    println!("TOON:\n{}\n", toon);
+
+    // Cells use the same spelling serde_json uses for each number
+    let original: Value = serde_json::from_str(json).unwrap();
+    let row = &original["numbers"][0];
+    let expected: Vec<String> = ["big", "exp", "float", "neg", "tiny", "whole"]
+        .iter()
+        .map(|col| row[col].to_string())
+        .collect();
+    assert_eq!(toon, format!("numbers[1]{{big,exp,float,neg,tiny,whole}}:\n{}", expected.join(",")));
+
+    let back: Value = serde_json::from_str(&converter::toon_to_json(&toon).unwrap()).unwrap();
+    assert_eq!(original, back, "Number round-trip failed");
+    println!("✓ Numbers formatted exactly\n");
+}
+
+#[test]
+fn test_value_level_api_matches_text_api() {
+    let toon = "users[2]{id,name}:\n1,Alice\n2,Bob";
+
+    println!("=== Value-level API Test ===");
+
+    let value = converter::toon_to_value(toon).expect("Failed to parse TOON");
+    let via_text: Value = serde_json::from_str(&converter::toon_to_json(toon).unwrap()).unwrap();
+    assert_eq!(value, via_text, "toon_to_value should match parsing toon_to_json output");
+
+    let back = converter::json_value_to_toon(&value).expect("Failed to emit TOON");
+    assert_eq!(back, converter::json_to_toon(&value.to_string()).unwrap());
+    assert_eq!(back, toon);
+
+    assert!(converter::json_value_to_toon(&serde_json::json!([1, 2])).is_err(), "Root must be an object");
+    println!("✓ Value-level API skips JSON text without changing results\n");
+}
@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter;
+use toonify::rows::{emit_rows, parse_row, parse_rows};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("rows");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+#[test]
+fn test_one_line_per_record() {
+    println!("=== Rows: emit ===");
+
+    let document = json!({
+        "users": [
+            {"id": 1, "name": "Alice", "role": "admin"},
+            {"id": 2, "name": "Bob Stone", "role": null, "tags": ["ops", "db"]}
+        ]
+    });
+    let rows = emit_rows(&document).unwrap();
+    println!("Rows:\n{}", rows);
+
+    assert_eq!(rows, "users: id=1 name=Alice role=admin\nusers: id=2 name=\"Bob Stone\" role=null tags=[\"ops\",\"db\"]\n");
+
+    println!("✓ Every line names its entity and fields\n");
+}
+
+#[test]
+fn test_rows_round_trip_tables() {
+    let document = json!({
+        "users": [
+            {"id": 1, "name": "Alice", "zip": "02134", "active": true, "score": 9.5},
+            {"id": 2, "name": "a=b", "zip": "true", "active": false, "score": -1},
+            {"id": 3, "name": "", "zip": "line\nbreak", "active": null, "score": 0, "meta": {"k": "v w"}}
+        ],
+        "events": [{"kind": "login", "at": "2024-05-01T10:00:00Z"}]
+    });
+    let rows = emit_rows(&document).unwrap();
+    assert_eq!(rows.lines().count(), 4, "A newline in a value must not split its line:\n{}", rows);
+    assert_eq!(parse_rows(&rows).unwrap(), document);
+}
+
+#[test]
+fn test_single_line_parses_alone() {
+    let (entity, record) = parse_row("users: id=7 name=\"Ann Lee\" \"first name\"=Ann").unwrap();
+    assert_eq!(entity, "users");
+    assert_eq!(record, json!({"id": 7, "name": "Ann Lee", "first name": "Ann"}));
+
+    let (entity, record) = parse_row("\"order items\": sku=A-1").unwrap();
+    assert_eq!(entity, "order items");
+    assert_eq!(record, json!({"sku": "A-1"}));
+}
+
+#[test]
+fn test_non_table_entities_read_back_as_tables() {
+    let rows = emit_rows(&json!({"page": 3, "settings": {"theme": "dark"}, "tags": ["a", "1"], "empty": []})).unwrap();
+    assert_eq!(rows, "page: 3\nsettings: theme=dark\ntags: a\ntags: \"1\"\n");
+    assert_eq!(
+        parse_rows(&rows).unwrap(),
+        json!({"page": [3], "settings": [{"theme": "dark"}], "tags": ["a", "1"]})
+    );
+}
+
+#[test]
+fn test_malformed_rows_are_rejected() {
+    let err = parse_rows("users: id=1\nno entity here\n").unwrap_err();
+    assert!(err.starts_with("Line 2:"), "Error: {}", err);
+
+    let err = parse_row("users: id=1 stray").unwrap_err();
+    assert!(err.contains("expected key=value"), "Error: {}", err);
+
+    let err = parse_row("users: name=\"Ann\"x").unwrap_err();
+    assert!(err.contains("whitespace after the value of 'name'"), "Error: {}", err);
+
+    assert!(emit_rows(&json!([1, 2])).is_err(), "Rows need an object root");
+}
+
+#[test]
+fn test_rows_registered_as_format() {
+    assert_eq!(converter::registry().for_extension("rows").map(|codec| codec.name()), Some("rows"));
+    let toon = converter::convert("users: id=1 name=Alice\nusers: id=2 name=Bob\n", "rows", "toon").unwrap();
+    assert_eq!(toon, "users[2]{id,name}:\n1,Alice\n2,Bob");
+}
+
+#[test]
+fn test_convert_cli_to_rows_and_back() {
+    println!("=== Rows: CLI ===");
+
+    let input = temp_path("users.json");
+    let rows_path = temp_path("users.rows");
+    fs::write(&input, r#"{"users":[{"id":1,"name":"Alice","role":"admin"},{"id":2,"name":"Bob","role":"dev"}]}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .arg("--to")
+        .arg("rows")
+        .arg("-o")
+        .arg(&rows_path)
+        .output()
+        .expect("Failed to execute convert command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Convert to rows should succeed");
+    assert_eq!(fs::read_to_string(&rows_path).unwrap(), "users: id=1 name=Alice role=admin\nusers: id=2 name=Bob role=dev\n");
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&rows_path)
+        .arg("--to")
+        .arg("json")
+        .output()
+        .expect("Failed to execute convert command");
+    assert!(output.status.success(), "The .rows extension should be detected");
+    let value: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["users"][1]["role"], json!("dev"));
+
+    println!("✓ JSON → rows → JSON\n");
+}
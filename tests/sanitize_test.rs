@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter::Converter;
+use toonify::sanitize::{Sanitizer, Sanitizers};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("sanitize");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+#[test]
+fn test_builtin_sanitizers() {
+    println!("=== Sanitize: built-ins ===");
+
+    let clean = |name: &str, text: &str| Sanitizer::builtin(name).unwrap().clean(text);
+    assert_eq!(clean("trim", "  Alice \n"), "Alice");
+    assert_eq!(clean("collapse_ws", "Alice \t van\n\nDyke"), "Alice van Dyke");
+    assert_eq!(clean("lowercase", "Alice@Example.COM"), "alice@example.com");
+    assert_eq!(clean("uppercase", "gb"), "GB");
+    assert_eq!(clean("casefold", "STRASSE Straße"), "strasse strasse");
+    assert_eq!(clean("strip_control", "a\u{0}b\u{7}c\nd"), "abc\nd");
+
+    let err = Sanitizer::builtin("titlecase").unwrap_err();
+    assert!(err.contains("Unknown sanitizer 'titlecase'"), "Error: {}", err);
+
+    println!("✓ Built-ins clean as documented\n");
+}
+
+#[test]
+fn test_sanitizers_apply_to_named_fields() {
+    let sanitizers = Sanitizers::parse(&["name=trim,collapse_ws email=lowercase", "email=trim"]).unwrap();
+    assert_eq!(sanitizers.describe(), vec!["name=trim,collapse_ws", "email=lowercase,trim"]);
+
+    let document = json!({
+        "users": [
+            {"name": "  Alice   Smith ", "email": " Alice@Example.com ", "note": "  kept  "},
+            {"name": null, "email": ["A@X.io", 3], "profile": {"name": " Bob "}}
+        ],
+        "user.email": " X@Y.Z "
+    });
+    let cleaned = sanitizers.apply(document);
+    assert_eq!(cleaned["users"][0]["name"], json!("Alice Smith"));
+    assert_eq!(cleaned["users"][0]["email"], json!("alice@example.com"));
+    assert_eq!(cleaned["users"][0]["note"], json!("  kept  "), "Other fields are untouched");
+    assert_eq!(cleaned["users"][1]["name"], Value::Null);
+    assert_eq!(cleaned["users"][1]["email"], json!(["a@x.io", 3]));
+    assert_eq!(cleaned["users"][1]["profile"]["name"], json!("Bob"), "Fields match at any depth");
+    assert_eq!(cleaned["user.email"], json!("x@y.z"), "Flattened columns match on their last segment");
+}
+
+#[test]
+fn test_custom_sanitizer() {
+    let digits = Sanitizer::new("digits", |s| s.chars().filter(char::is_ascii_digit).collect());
+    let sanitizers = Sanitizers::new().with("phone", Sanitizer::builtin("trim").unwrap()).with("phone", digits);
+    let converter = Converter::builder().sanitize(sanitizers).build();
+
+    let json = converter.convert(r#"{"contacts":[{"phone":" +1 (555) 010-2030 "}]}"#, "json", "json").unwrap();
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["contacts"][0]["phone"], json!("15550102030"));
+}
+
+#[test]
+fn test_invalid_specs_are_rejected() {
+    assert!(Sanitizers::parse(&["name"]).unwrap_err().contains("expected field=trim,lowercase"));
+    assert!(Sanitizers::parse(&["=trim"]).is_err());
+    assert!(Sanitizers::parse(&["name=trim,shout"]).unwrap_err().contains("Unknown sanitizer 'shout'"));
+}
+
+#[test]
+fn test_convert_cli_sanitize() {
+    println!("=== Sanitize: CLI ===");
+
+    let input = temp_path("users.json");
+    fs::write(&input, r#"{"users":[{"email":"  Alice@Example.COM","name":"Alice   Smith"}]}"#).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .arg("--sanitize")
+        .arg("name=collapse_ws")
+        .arg("--sanitize")
+        .arg("email=trim,lowercase")
+        .output()
+        .expect("Failed to execute convert command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Convert with --sanitize should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("alice@example.com,Alice Smith"), "Output: {}", stdout);
+
+    println!("✓ --sanitize applied\n");
+}
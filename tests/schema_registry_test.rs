@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use toonify::schema_registry::SchemaRegistry;
+use toonify::server::ServerBuilder;
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    std::fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn users_schema(max_items: u64) -> Value {
+    json!({ "users": { "type": "array", "fields": ["id", "name"], "field_types": { "id": "number" }, "max_items": max_items } })
+}
+
+async fn start(builder: ServerBuilder) -> String {
+    let server = builder.build().unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = server.router();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    base
+}
+
+#[test]
+fn test_registry_versions() {
+    println!("=== Schema registry versions ===");
+
+    let registry = SchemaRegistry::new();
+    assert_eq!(registry.register("users", users_schema(10)).unwrap(), (1, true));
+    assert_eq!(registry.register("users", users_schema(10)).unwrap(), (1, false));
+    assert_eq!(registry.register("users", users_schema(2)).unwrap(), (2, true));
+
+    assert_eq!(registry.get("users", None).unwrap().version, 2);
+    assert_eq!(registry.get("users", Some(1)).unwrap().schema, users_schema(10));
+    assert!(registry.get("users", Some(3)).is_none());
+
+    let listed = registry.list();
+    assert_eq!((listed.len(), listed[0].latest, listed[0].versions), (1, 2, 2));
+
+    assert!(registry.register("../users", users_schema(1)).is_err());
+    assert!(registry.register("broken", json!({ "users": { "type": "tree" } })).is_err());
+    assert!(registry.register("broken", json!({ "users": { "type": "array", "fields": [], "patterns": { "id": "(" } } })).is_err());
+
+    println!("✓ Versions kept\n");
+}
+
+#[test]
+fn test_registry_loads_directory() {
+    println!("=== SchemaRegistry::load_dir ===");
+
+    let dir = temp_path("schema_registry_dir");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("users.json"), users_schema(5).to_string()).unwrap();
+    std::fs::write(dir.join("notes.txt"), "not a schema").unwrap();
+
+    let registry = SchemaRegistry::load_dir(&dir).unwrap();
+    assert_eq!(registry.list().len(), 1);
+    assert_eq!(registry.get("users", None).unwrap().schema, users_schema(5));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("✓ Directory registered\n");
+}
+
+#[tokio::test]
+async fn test_convert_with_registered_schema() {
+    println!("=== POST /convert?schema= ===");
+
+    let base = start(ServerBuilder::new()).await;
+    let client = reqwest::Client::new();
+
+    let put = |schema: Value| client.put(format!("{}/schemas/users", base)).json(&schema).send();
+    let response = put(users_schema(10)).await.unwrap();
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.json::<Value>().await.unwrap(), json!({ "name": "users", "version": 1 }));
+    assert_eq!(put(users_schema(1)).await.unwrap().status(), 201);
+    assert_eq!(put(users_schema(1)).await.unwrap().status(), 200);
+
+    let listed: Value = client.get(format!("{}/schemas", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(listed["schemas"], json!([{ "name": "users", "latest": 2, "versions": 2 }]));
+    let first: Value = client.get(format!("{}/schemas/users?version=1", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(first["schema"], users_schema(10));
+
+    let convert = |query: &str| {
+        client
+            .post(format!("{}/convert?{}", base, query))
+            .header("Content-Type", "application/json")
+            .body(r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#)
+            .send()
+    };
+    // Version 2 allows one user; version 1 allows ten
+    let rejected = convert("schema=users").await.unwrap();
+    assert_eq!(rejected.status(), 422);
+    assert!(rejected.text().await.unwrap().contains("maximum is 1"));
+    let accepted = convert("schema=users&schema_version=1").await.unwrap();
+    assert_eq!(accepted.status(), 200);
+    assert_eq!(accepted.text().await.unwrap().trim_end(), "users[2]{id,name}:\n1,Alice\n2,Bob");
+    assert_eq!(convert("schema=orders").await.unwrap().status(), 404);
+
+    println!("✓ Conversions validated against registered schemas\n");
+}
+
+#[cfg(feature = "job-queue")]
+#[tokio::test]
+async fn test_job_submission_with_registered_schema() {
+    println!("=== /jobs/submit with a schema ===");
+
+    let base = start(ServerBuilder::new().job_queue(1)).await;
+    let client = reqwest::Client::new();
+    client.put(format!("{}/schemas/users", base)).json(&users_schema(10)).send().await.unwrap();
+
+    let submit = |data: &str| {
+        client
+            .post(format!("{}/jobs/submit", base))
+            .json(&json!({ "operation": "json_to_toon", "data": data, "schema": "users" }))
+            .send()
+    };
+    let rejected = submit(r#"{"users":[{"id":"one","name":"Alice"}]}"#).await.unwrap();
+    assert_eq!(rejected.status(), 422);
+    let body: Value = rejected.json().await.unwrap();
+    assert_eq!(body["job_id"], "error:schema_validation_failed");
+    assert!(body["error"].as_str().unwrap().contains("wrong type"));
+
+    let accepted: Value = submit(r#"{"users":[{"id":1,"name":"Alice"}]}"#).await.unwrap().json().await.unwrap();
+    assert!(!accepted["job_id"].as_str().unwrap().starts_with("error:"));
+    assert!(accepted.get("error").is_none());
+
+    println!("✓ Jobs validated before they are queued\n");
+}
@@ -0,0 +1,104 @@
+use std::fs;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter::Converter;
+use toonify::scripting::ScriptTransform;
+
+fn get_binary_path() -> String {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    format!("{}/target/debug/toonify", manifest_dir)
+}
+
+#[test]
+fn test_script_filters_and_computes_fields() {
+    println!("=== Scripting: Filter rows and compute field ===");
+
+    let script = ScriptTransform::compile(r##"
+        data.users = data.users.filter(|u| u.active);
+        data.users = data.users.map(|u| { u.label = u.name + "#" + u.id; u });
+    "##).expect("Script should compile");
+
+    let input = json!({"users": [
+        {"id": 1, "name": "Alice", "active": true},
+        {"id": 2, "name": "Bob", "active": false}
+    ]});
+
+    let output = script.apply(input).expect("Script should run");
+    println!("Output: {}", output);
+
+    assert_eq!(output["users"].as_array().unwrap().len(), 1, "Inactive user should be filtered out");
+    assert_eq!(output["users"][0]["label"], json!("Alice#1"));
+
+    println!("✓ Script filtered and computed fields\n");
+}
+
+#[test]
+fn test_script_return_value_replaces_document() {
+    println!("=== Scripting: Returned value replaces document ===");
+
+    let script = ScriptTransform::compile(r#"#{ count: data.items.len() }"#).expect("Script should compile");
+    let output = script.apply(json!({"items": [1, 2, 3]})).expect("Script should run");
+    println!("Output: {}", output);
+
+    assert_eq!(output, json!({"count": 3}));
+
+    println!("✓ Returned value used as output\n");
+}
+
+#[test]
+fn test_script_errors_are_reported() {
+    println!("=== Scripting: Compile and runtime errors ===");
+
+    assert!(ScriptTransform::compile("data.users = ").is_err(), "Syntax error should fail to compile");
+
+    let script = ScriptTransform::compile(r#"throw "bad data""#).unwrap();
+    let err = script.apply(json!({})).unwrap_err();
+    println!("Runtime error: {}", err);
+    assert!(err.contains("Transform script failed"));
+
+    println!("✓ Script errors reported\n");
+}
+
+#[test]
+fn test_script_as_converter_hook() {
+    println!("=== Scripting: ConverterBuilder::with_transform_script ===");
+
+    let script = ScriptTransform::compile("data.status = \"processed\";").unwrap();
+    let converter = Converter::builder().with_transform_script(script).build();
+
+    let toon = converter.json_to_toon(r#"{"status":"raw"}"#).expect("Conversion failed");
+    println!("TOON:\n{}", toon);
+
+    assert_eq!(toon, "status:processed");
+
+    println!("✓ Script applied through converter\n");
+}
+
+#[test]
+fn test_cli_convert_with_transform() {
+    println!("=== Scripting: CLI --transform ===");
+
+    let test_dir = "/tmp/toonify_transform_test";
+    let _ = fs::remove_dir_all(test_dir);
+    fs::create_dir_all(test_dir).unwrap();
+
+    let input = format!("{}/input.json", test_dir);
+    let script = format!("{}/drop_email.rhai", test_dir);
+    fs::write(&input, r#"{"users":[{"id":1,"name":"Alice","email":"a@example.com"}]}"#).unwrap();
+    fs::write(&script, "data.users = data.users.map(|u| { u.remove(\"email\"); u });").unwrap();
+
+    let output = Command::new(get_binary_path())
+        .args(["convert", &input, "--transform", &script, "--to", "json"])
+        .output()
+        .expect("Failed to execute convert");
+
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Convert with transform should succeed");
+
+    let value: Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    assert_eq!(value, json!({"users": [{"id": 1, "name": "Alice"}]}));
+
+    println!("✓ CLI transform applied\n");
+
+    let _ = fs::remove_dir_all(test_dir);
+}
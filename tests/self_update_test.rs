@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::thread;
+
+use sha2::{Digest, Sha256};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests");
+    path.push("fixtures");
+    path.push("signing");
+    path.push(name);
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn asset_name() -> &'static str {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") if cfg!(target_env = "musl") => "toonify-linux-x86_64-musl",
+        ("linux", "x86_64") => "toonify-linux-x86_64",
+        ("linux", "aarch64") => "toonify-linux-arm64-musl",
+        ("macos", "x86_64") => "toonify-macos-x86_64",
+        ("macos", "aarch64") => "toonify-macos-arm64",
+        ("windows", "x86_64") => "toonify-windows-x86_64.exe",
+        _ => "unsupported",
+    }
+}
+
+// Minimal releases API: serves /releases/latest and the listed assets until the test exits
+fn serve_release(tag: &str, assets: Vec<(String, Vec<u8>)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    let base = format!("http://{}", listener.local_addr().unwrap());
+
+    let listing: Vec<_> = assets
+        .iter()
+        .map(|(name, _)| serde_json::json!({ "name": name, "browser_download_url": format!("{}/download/{}", base, name) }))
+        .collect();
+    let mut routes: HashMap<String, Vec<u8>> =
+        assets.into_iter().map(|(name, body)| (format!("/download/{}", name), body)).collect();
+    routes.insert("/releases/latest".to_string(), serde_json::json!({ "tag_name": tag, "assets": listing }).to_string().into_bytes());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap_or_default();
+            let mut header = String::new();
+            while reader.read_line(&mut header).is_ok_and(|n| n > 0) && header != "\r\n" {
+                header.clear();
+            }
+            let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+            let response = match routes.get(path) {
+                Some(body) => [format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len()).into_bytes(), body.clone()].concat(),
+                None => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec(),
+            };
+            let _ = stream.write_all(&response);
+        }
+    });
+    base
+}
+
+// A copy of the binary to update, so the test build itself is left alone
+fn binary_copy(name: &str) -> PathBuf {
+    let copy = temp_path(name);
+    fs::copy(get_binary_path(), &copy).expect("Failed to copy binary");
+    copy
+}
+
+fn self_update(binary: &Path, api: &str, args: &[&str]) -> Output {
+    Command::new(binary)
+        .env("TOONIFY_UPDATE_URL", api)
+        .env("TOONIFY_CONFIG", "none")
+        .arg("self-update")
+        .args(args)
+        .output()
+        .expect("Failed to execute self-update")
+}
+
+// A signed payload standing in for the new binary
+fn signed_payload(stem: &str) -> (Vec<u8>, Vec<u8>) {
+    let input = temp_path(&format!("{}.json", stem));
+    let output = temp_path(&format!("{}.toon", stem));
+    fs::write(&input, r#"{"release":{"version":"99.0.0"}}"#).unwrap();
+    let status = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .arg("--sign-key")
+        .arg(fixture("test_signing.pem"))
+        .status()
+        .expect("Failed to sign payload");
+    assert!(status.success());
+    let signature = fs::read(temp_path(&format!("{}.toon.sig", stem))).unwrap();
+    (fs::read(&output).unwrap(), signature)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn test_self_update_check() {
+    println!("=== Self-update: --check ===");
+
+    let binary = binary_copy("toonify_update_check");
+    let api = serve_release("v0.0.1", vec![]);
+    let output = self_update(&binary, &api, &["--check"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is up to date"));
+
+    let api = serve_release("v99.0.0", vec![]);
+    let output = self_update(&binary, &api, &["--check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+    assert!(!output.status.success(), "an available update fails the check");
+    assert!(stdout.contains("Update available"));
+    assert!(stdout.contains("99.0.0"));
+
+    println!("=== Self-update --check PASSED ===");
+}
+
+#[test]
+fn test_self_update_replaces_binary() {
+    println!("=== Self-update: verified install ===");
+
+    let (payload, signature) = signed_payload("self_update_install");
+    let name = asset_name();
+    let api = serve_release(
+        "v99.0.0",
+        vec![
+            (name.to_string(), payload.clone()),
+            (format!("{}.sha256", name), format!("{}  {}\n", sha256_hex(&payload), name).into_bytes()),
+            (format!("{}.sig", name), signature),
+        ],
+    );
+
+    let binary = binary_copy("toonify_update_install");
+    let old_mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    fs::File::options().write(true).open(&binary).unwrap().set_modified(old_mtime).unwrap();
+    let output = self_update(&binary, &api, &["--public-key", fixture("test_signing.pub.pem").to_str().unwrap()]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("{}", stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("Checksum OK"));
+    assert!(stderr.contains("Signature OK"));
+    assert_eq!(fs::read(&binary).unwrap(), payload, "binary replaced with the release asset");
+    assert!(fs::metadata(&binary).unwrap().modified().unwrap() > old_mtime, "the new binary keeps its own mtime");
+
+    println!("=== Self-update install PASSED ===");
+}
+
+#[test]
+fn test_self_update_rejects_bad_artifacts() {
+    println!("=== Self-update: rejected downloads ===");
+
+    let (payload, signature) = signed_payload("self_update_rejected");
+    let name = asset_name();
+    let original = fs::read(get_binary_path()).unwrap();
+
+    // Checksum of something else
+    let api = serve_release(
+        "v99.0.0",
+        vec![(name.to_string(), payload.clone()), (format!("{}.sha256", name), sha256_hex(b"other").into_bytes())],
+    );
+    let binary = binary_copy("toonify_update_bad_checksum");
+    let output = self_update(&binary, &api, &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Checksum mismatch"));
+    assert_eq!(fs::read(&binary).unwrap(), original, "binary untouched");
+
+    // Matching checksum, signed by another key
+    let api = serve_release(
+        "v99.0.0",
+        vec![
+            (name.to_string(), payload.clone()),
+            (format!("{}.sha256", name), sha256_hex(&payload).into_bytes()),
+            (format!("{}.sig", name), signature),
+        ],
+    );
+    let binary = binary_copy("toonify_update_bad_signature");
+    let output = self_update(&binary, &api, &["--public-key", fixture("other.pub.pem").to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Signed by a different key"));
+    assert_eq!(fs::read(&binary).unwrap(), original, "binary untouched");
+
+    println!("=== Self-update rejections PASSED ===");
+}
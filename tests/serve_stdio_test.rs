@@ -0,0 +1,110 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_serve_stdio_json_rpc() {
+    println!("=== serve --transport stdio ===");
+
+    let mut child = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["serve", "--transport", "stdio", "--max-depth", "4"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start serve");
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut call = |request: Value| -> Value {
+        writeln!(stdin, "{}", request).unwrap();
+        let mut line = String::new();
+        stdout.read_line(&mut line).expect("Failed to read response");
+        serde_json::from_str(&line).unwrap_or_else(|e| panic!("stdout carries only JSON-RPC ({}): {:?}", e, line))
+    };
+
+    let response = call(json!({
+        "jsonrpc": "2.0", "id": 1, "method": "convert",
+        "params": { "input": r#"{"users":[{"id":1,"name":"Alice"}]}"#, "to": "toon" }
+    }));
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["output"].as_str().unwrap().trim_end(), "users[1]{id,name}:\n1,Alice");
+
+    // Parser guards from the serve flags apply
+    let response = call(json!({
+        "jsonrpc": "2.0", "id": 2, "method": "convert",
+        "params": { "input": r#"{"a":[[[[[1]]]]]}"#, "to": "toon" }
+    }));
+    assert!(response["error"]["message"].as_str().unwrap().contains("--max-depth"), "{}", response);
+
+    let response = call(json!({ "jsonrpc": "2.0", "id": 3, "method": "stats" }));
+    assert_eq!(response["result"]["conversions"], 1);
+
+    // Closing stdin ends the server
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+
+    println!("✓ JSON-RPC on stdin/stdout\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_serve_unix_transport() {
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    println!("=== serve --transport unix ===");
+
+    let socket = std::env::temp_dir().join(format!("toonify-serve-unix-{}.sock", std::process::id()));
+    let mut child = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["serve", "--transport", "unix", "--socket"])
+        .arg(&socket)
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start serve");
+
+    let mut stream = None;
+    for _ in 0..50 {
+        if let Ok(connected) = UnixStream::connect(&socket) {
+            stream = Some(connected);
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let mut stream = stream.expect("Server did not start in time");
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    writeln!(stream, "{}", json!({ "jsonrpc": "2.0", "id": 1, "method": "convert", "params": { "input": "a:1", "from": "toon", "to": "json" } })).unwrap();
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let response: Value = serde_json::from_str(&line).unwrap();
+    let output: Value = serde_json::from_str(response["result"]["output"].as_str().unwrap()).unwrap();
+    assert_eq!(output, json!({ "a": 1 }));
+
+    let _ = child.kill();
+    let _ = child.wait();
+    let _ = std::fs::remove_file(&socket);
+
+    println!("✓ JSON-RPC on a Unix socket\n");
+}
+
+#[test]
+fn test_serve_unix_requires_socket() {
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["serve", "--transport", "unix"])
+        .output()
+        .expect("Failed to run serve");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--socket"));
+}
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use toonify::audit_log::AuditLog;
+use toonify::conversion_pool::ConversionLimits;
+use toonify::guards::ParserGuards;
+use toonify::server::{router, AppState, CacheState, ServerBuilder};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    std::fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn state(audit: Option<Arc<AuditLog>>) -> AppState {
+    AppState {
+        cache: CacheState {
+            #[cfg(feature = "cache")]
+            moka: Some(toonify::server::create_moka_cache(16, None)),
+            #[cfg(feature = "persistent-cache")]
+            sled: None,
+            #[cfg(feature = "cache-encryption")]
+            cipher: None,
+            custom: None,
+            breakers: Default::default(),
+        },
+        limits: ConversionLimits::new(None, 1, 8).unwrap(),
+        guards: ParserGuards { max_depth: Some(4), ..Default::default() },
+        audit,
+        #[cfg(feature = "job-queue")]
+        job_store: None,
+        #[cfg(feature = "cluster-cache")]
+        invalidation: None,
+        #[cfg(feature = "validation")]
+        schemas: Default::default(),
+    }
+}
+
+// Serve the library router on an ephemeral port, as an embedding application would
+async fn spawn(app: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_library_router_converts() {
+    println!("=== Server library: router ===");
+
+    let log = temp_path("server_lib_audit.jsonl");
+    let _ = std::fs::remove_file(&log);
+    let audit = Arc::new(AuditLog::open(&log, None, 0).unwrap());
+    let base = spawn(router(state(Some(audit)))).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/json-to-toon", base))
+        .json(&serde_json::json!({ "data": r#"{"users":[{"id":1,"name":"Alice"}]}"# }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["result"], "users[1]{id,name}:\n1,Alice");
+
+    // Parser guards in the state apply
+    let response = client
+        .post(format!("{}/convert/json/toon", base))
+        .json(&serde_json::json!({ "data": r#"{"a":[[[[[1]]]]]}"# }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    assert_eq!(client.get(format!("{}/readyz", base)).send().await.unwrap().status(), 200);
+    assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 2, "both conversions audited");
+
+    // No job store, no /jobs routes
+    assert_eq!(client.get(format!("{}/jobs", base)).send().await.unwrap().status(), 404);
+
+    println!("✓ Router from toonify::server serves conversions\n");
+}
+
+async fn tag_response(request: axum::extract::Request, next: axum::middleware::Next) -> axum::response::Response {
+    let mut response = next.run(request).await;
+    response.headers_mut().insert("x-served-by", "host-app".parse().unwrap());
+    response
+}
+
+#[tokio::test]
+async fn test_server_builder_nests_under_host_app() {
+    println!("=== Server library: ServerBuilder ===");
+
+    let toonify = ServerBuilder::new()
+        .guards(ParserGuards { max_depth: Some(4), ..Default::default() })
+        .health_routes(false)
+        .layer(axum::middleware::from_fn(tag_response))
+        .build()
+        .unwrap();
+    let app = axum::Router::new()
+        .route("/healthz", axum::routing::get(|| async { "host ok" }))
+        .nest("/toonify", toonify.router());
+    let _grpc = toonify.grpc_service();
+    let base = spawn(app).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/toonify/json-to-toon", base))
+        .json(&serde_json::json!({ "data": r#"{"users":[{"id":1,"name":"Alice"}]}"# }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-served-by"], "host-app", "builder middleware wraps the routes");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["result"], "users[1]{id,name}:\n1,Alice");
+
+    let response = client
+        .post(format!("{}/toonify/convert/json/toon", base))
+        .json(&serde_json::json!({ "data": r#"{"a":[[[[[1]]]]]}"# }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400, "guards from the builder apply");
+
+    // The host's own probe, and none from TOONify
+    assert_eq!(client.get(format!("{}/healthz", base)).send().await.unwrap().text().await.unwrap(), "host ok");
+    assert_eq!(client.get(format!("{}/toonify/healthz", base)).send().await.unwrap().status(), 404);
+
+    println!("✓ ServerBuilder router mounts under another application\n");
+}
+
+#[cfg(feature = "job-queue")]
+#[tokio::test]
+async fn test_server_builder_job_queue() {
+    println!("=== Server library: ServerBuilder job queue ===");
+
+    let toonify = ServerBuilder::new().job_queue(1).build().unwrap();
+    assert!(toonify.state().job_store.is_some());
+    let base = spawn(toonify.router()).await;
+    let client = reqwest::Client::new();
+
+    assert_eq!(client.get(format!("{}/jobs", base)).send().await.unwrap().status(), 200);
+
+    println!("✓ ServerBuilder job queue serves /jobs\n");
+}
@@ -0,0 +1,200 @@
+use std::process::{Command, Child, Stdio};
+use std::thread;
+use std::time::Duration;
+use std::sync::Mutex;
+
+// Global mutex to ensure server tests run serially (avoid port conflicts)
+static SERVER_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn cleanup_servers() {
+    let _ = Command::new("pkill")
+        .args(["-9", "toonify"])
+        .output();
+    thread::sleep(Duration::from_millis(500));
+}
+
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start_server(args: &[&str]) -> Server {
+    let binary_path = "./target/release/toonify";
+    println!("Starting server: {} serve {}", binary_path, args.join(" "));
+
+    Server(
+        Command::new(binary_path)
+            .arg("serve")
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to start server"),
+    )
+}
+
+fn wait_for_server() {
+    for _ in 0..30 {
+        if let Ok(response) = reqwest::blocking::get("http://localhost:5000/")
+            && response.status().is_success()
+        {
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    panic!("Server did not start in time");
+}
+
+// Large enough that JSON -> TOON takes well over a millisecond, and under
+// the 2 MB request body limit
+fn large_payload() -> serde_json::Value {
+    let rows: Vec<String> = (0..30_000)
+        .map(|i| format!(r#"{{"id":{},"name":"user {}","active":true}}"#, i, i))
+        .collect();
+    serde_json::json!({ "data": format!(r#"{{"users":[{}]}}"#, rows.join(",")) })
+}
+
+#[test]
+fn test_conversion_timeout_returns_504() {
+    let _lock = SERVER_TEST_LOCK.lock().unwrap();
+    cleanup_servers();
+
+    println!("=== Server Limits: --conversion-timeout-ms ===");
+
+    let server = start_server(&["--conversion-timeout-ms", "1"]);
+    wait_for_server();
+
+    let client = reqwest::blocking::Client::new();
+    let response = client
+        .post("http://localhost:5000/json-to-toon")
+        .json(&large_payload())
+        .send()
+        .expect("Failed to send request");
+
+    println!("  Large request: Status {}", response.status());
+    assert_eq!(response.status(), reqwest::StatusCode::GATEWAY_TIMEOUT);
+    let body: serde_json::Value = response.json().unwrap();
+    assert!(body["error"].as_str().unwrap().contains("timed out"));
+
+    // The server keeps serving after a timeout
+    let response = client
+        .get("http://localhost:5000/")
+        .send()
+        .expect("Failed to send request");
+    assert!(response.status().is_success());
+
+    drop(server);
+    println!("=== Server Limits: timeout PASSED ===");
+}
+
+#[test]
+fn test_conversion_within_timeout_succeeds() {
+    let _lock = SERVER_TEST_LOCK.lock().unwrap();
+    cleanup_servers();
+
+    println!("=== Server Limits: conversions within the timeout ===");
+
+    let server = start_server(&["--conversion-timeout-ms", "5000"]);
+    wait_for_server();
+
+    let client = reqwest::blocking::Client::new();
+    let response = client
+        .post("http://localhost:5000/json-to-toon")
+        .json(&serde_json::json!({ "data": r#"{"users":[{"id":1,"name":"Alice"}]}"# }))
+        .send()
+        .expect("Failed to send request");
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().unwrap();
+    assert!(body["result"].as_str().unwrap().contains("1,Alice"));
+
+    drop(server);
+    println!("=== Server Limits: within timeout PASSED ===");
+}
+
+#[test]
+fn test_saturated_pool_sheds_load_with_429() {
+    let _lock = SERVER_TEST_LOCK.lock().unwrap();
+    cleanup_servers();
+
+    println!("=== Server Limits: --conversion-queue load shedding ===");
+
+    let server = start_server(&["--conversion-threads", "1", "--conversion-queue", "1"]);
+    wait_for_server();
+
+    let payload = std::sync::Arc::new(large_payload());
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let payload = std::sync::Arc::clone(&payload);
+            thread::spawn(move || {
+                reqwest::blocking::Client::new()
+                    .post("http://localhost:5000/json-to-toon")
+                    .json(&*payload)
+                    .send()
+                    .expect("Failed to send request")
+                    .status()
+            })
+        })
+        .collect();
+    let statuses: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    println!("  Statuses: {:?}", statuses);
+
+    assert!(statuses.contains(&reqwest::StatusCode::OK), "At least one conversion should run");
+    assert!(statuses.contains(&reqwest::StatusCode::TOO_MANY_REQUESTS), "Excess conversions should be shed");
+    assert!(statuses.iter().all(|s| *s == reqwest::StatusCode::OK || *s == reqwest::StatusCode::TOO_MANY_REQUESTS));
+
+    // Once the queue drains, requests are accepted again
+    let response = reqwest::blocking::Client::new()
+        .post("http://localhost:5000/json-to-toon")
+        .json(&serde_json::json!({ "data": r#"{"users":[{"id":1}]}"# }))
+        .send()
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    drop(server);
+    println!("=== Server Limits: load shedding PASSED ===");
+}
+
+#[test]
+fn test_small_requests_bypass_busy_pool() {
+    let _lock = SERVER_TEST_LOCK.lock().unwrap();
+    cleanup_servers();
+
+    println!("=== Server Limits: small requests while the pool is busy ===");
+
+    let server = start_server(&["--conversion-threads", "1", "--conversion-queue", "1", "--small-request-bytes", "4096"]);
+    wait_for_server();
+
+    // Occupy the only conversion thread and the only queue slot
+    let payload = large_payload();
+    let heavy = thread::spawn(move || {
+        reqwest::blocking::Client::new()
+            .post("http://localhost:5000/json-to-toon")
+            .json(&payload)
+            .send()
+            .expect("Failed to send request")
+            .status()
+    });
+    thread::sleep(Duration::from_millis(50));
+
+    let client = reqwest::blocking::Client::new();
+    for _ in 0..5 {
+        let started = std::time::Instant::now();
+        let response = client
+            .post("http://localhost:5000/json-to-toon")
+            .json(&serde_json::json!({ "data": r#"{"users":[{"id":1,"name":"Alice"}]}"# }))
+            .send()
+            .expect("Failed to send request");
+        println!("  Small request: Status {} in {:?}", response.status(), started.elapsed());
+        assert_eq!(response.status(), reqwest::StatusCode::OK, "Small requests should not be shed behind the large one");
+    }
+
+    assert_eq!(heavy.join().unwrap(), reqwest::StatusCode::OK);
+
+    drop(server);
+    println!("=== Server Limits: small-request lane PASSED ===");
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests");
+    path.push("fixtures");
+    path.push("signing");
+    path.push(name);
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn verify(input: &PathBuf, public_key: &str) -> std::process::Output {
+    Command::new(get_binary_path())
+        .arg("verify-signature")
+        .arg(input)
+        .arg("--public-key")
+        .arg(fixture(public_key))
+        .output()
+        .expect("Failed to execute toonify binary")
+}
+
+#[test]
+fn test_detached_signature_roundtrip() {
+    println!("=== Signing: Detached signature ===");
+
+    let input = temp_path("sign_input.json");
+    fs::write(&input, r#"{"users":[{"id":1,"name":"Alice"}]}"#).unwrap();
+    let output = temp_path("sign_output.toon");
+    let signature = temp_path("sign_output.toon.sig");
+    let _ = fs::remove_file(&signature);
+
+    let convert = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&input)
+        .arg("-o").arg(&output)
+        .arg("--sign-key").arg(fixture("test_signing.pem"))
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(convert.status.success(), "convert --sign-key failed: {}", String::from_utf8_lossy(&convert.stderr));
+    assert!(signature.exists(), "Detached signature should be written next to the output");
+
+    assert!(verify(&output, "test_signing.pub.pem").status.success(), "Signature should verify");
+    assert!(!verify(&output, "other.pub.pem").status.success(), "Another key must not verify");
+
+    fs::write(&output, fs::read_to_string(&output).unwrap().replace("Alice", "Mallory")).unwrap();
+    assert!(!verify(&output, "test_signing.pub.pem").status.success(), "Tampered content must not verify");
+
+    println!("✓ Detached signatures verify and catch tampering\n");
+}
+
+#[test]
+fn test_footer_signature_roundtrip() {
+    println!("=== Signing: Signature footer ===");
+
+    let input = temp_path("footer_input.json");
+    fs::write(&input, r#"{"status":"ok"}"#).unwrap();
+    let output = temp_path("footer_output.toon");
+    let _ = fs::remove_file(temp_path("footer_output.toon.sig"));
+
+    let convert = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&input)
+        .arg("-o").arg(&output)
+        .arg("--sign-key").arg(fixture("test_signing.pem"))
+        .arg("--sign-footer")
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(convert.status.success());
+
+    let signed = fs::read_to_string(&output).unwrap();
+    println!("Signed output:\n{}", signed);
+    assert!(signed.starts_with("status:ok\n# toonify-signature ed25519 "));
+
+    let stripped = temp_path("footer_stripped.toon");
+    let check = Command::new(get_binary_path())
+        .arg("verify-signature")
+        .arg(&output)
+        .arg("--public-key").arg(fixture("test_signing.pub.pem"))
+        .arg("-o").arg(&stripped)
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(check.status.success(), "Footer signature should verify: {}", String::from_utf8_lossy(&check.stderr));
+    assert_eq!(fs::read_to_string(&stripped).unwrap(), "status:ok");
+
+    println!("✓ Footer signatures verify and strip cleanly\n");
+}
+
+#[test]
+fn test_footer_signed_toon_converts_back_to_json() {
+    println!("=== Signing: Footer-signed TOON back to JSON ===");
+
+    let json = r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#;
+    let input = temp_path("footer_table_input.json");
+    fs::write(&input, json).unwrap();
+    let output = temp_path("footer_table_output.toon");
+
+    let convert = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&input)
+        .arg("-o").arg(&output)
+        .arg("--sign-key").arg(fixture("test_signing.pem"))
+        .arg("--sign-footer")
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(convert.status.success(), "convert --sign-footer failed: {}", String::from_utf8_lossy(&convert.stderr));
+
+    let back = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&output)
+        .arg("--to").arg("json")
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(back.status.success(), "Footer-signed TOON should convert: {}", String::from_utf8_lossy(&back.stderr));
+    let value: serde_json::Value = serde_json::from_slice(&back.stdout).expect("Output should be valid JSON");
+    assert_eq!(value, serde_json::from_str::<serde_json::Value>(json).unwrap(), "The footer must not become a row");
+
+    let to_json = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&output)
+        .arg("--to").arg("json")
+        .arg("--sign-key").arg(fixture("test_signing.pem"))
+        .arg("--sign-footer")
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(!to_json.status.success(), "A footer would make the JSON invalid");
+    assert!(String::from_utf8_lossy(&to_json.stderr).contains("--sign-footer only applies to TOON output"));
+
+    println!("✓ Footer skipped on read, refused for JSON output\n");
+}
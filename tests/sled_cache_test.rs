@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use toonify::sled_cache;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    std::fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn cache(args: &[&str]) -> (bool, String, String) {
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("cache")
+        .args(args)
+        .output()
+        .expect("Failed to run toonify cache");
+    (output.status.success(), String::from_utf8_lossy(&output.stdout).into_owned(), String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+#[test]
+fn test_cache_stats_prune_compact() {
+    println!("=== cache stats / prune / compact ===");
+
+    let path = temp_path("sled_cache_test.db");
+    let _ = std::fs::remove_dir_all(&path);
+    {
+        let db = sled::open(&path).unwrap();
+        // Two entries written now, one from before write times were recorded
+        for key in ["toonify:fresh-1", "toonify:fresh-2"] {
+            sled_cache::record_write(&db, key.as_bytes());
+            db.insert(key, "a:1").unwrap();
+        }
+        db.insert("toonify:legacy", "b:2").unwrap();
+        db.flush().unwrap();
+    }
+    let path_arg = path.to_str().unwrap();
+
+    let (ok, stdout, stderr) = cache(&["stats", path_arg]);
+    assert!(ok, "{}", stderr);
+    assert!(stdout.contains("entries:      3"), "{}", stdout);
+    assert!(stdout.contains("untimed:      1"), "{}", stdout);
+
+    let (ok, stdout, stderr) = cache(&["prune", path_arg, "--older-than", "7d"]);
+    assert!(ok, "{}", stderr);
+    assert!(stdout.contains("Removed 1 entries; 2 kept"), "{}", stdout);
+
+    let (ok, stdout, stderr) = cache(&["compact", path_arg]);
+    assert!(ok, "{}", stderr);
+    assert!(stdout.contains("Compacted"), "{}", stdout);
+
+    // Entries and their write times survive the rewrite
+    let db = sled::open(&path).unwrap();
+    assert_eq!(db.get("toonify:fresh-1").unwrap().as_deref(), Some(&b"a:1"[..]));
+    let stats = sled_cache::stats(&db).unwrap();
+    assert_eq!((stats.entries, stats.untimed), (2, 0));
+    drop(db);
+
+    let (ok, _, stderr) = cache(&["prune", path_arg, "--older-than", "7 days"]);
+    assert!(!ok);
+    assert!(stderr.contains("Invalid age"), "{}", stderr);
+
+    println!("✓ Sled cache maintenance\n");
+}
+
+#[test]
+fn test_parse_age() {
+    assert_eq!(sled_cache::parse_age("30s").unwrap(), Duration::from_secs(30));
+    assert_eq!(sled_cache::parse_age("12h").unwrap(), Duration::from_secs(12 * 3600));
+    assert_eq!(sled_cache::parse_age("7d").unwrap(), Duration::from_secs(7 * 86_400));
+    assert_eq!(sled_cache::parse_age("2w").unwrap(), Duration::from_secs(14 * 86_400));
+    assert!(sled_cache::parse_age("7").is_err());
+    assert!(sled_cache::parse_age("d").is_err());
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+const SNAPSHOT: &str = "orders[2]{id:int,total:float,paid:bool}:\n10,9.5,true\n11,20.25,false\nusers[2]{id:int,name:str,zip:str}:\n1,Alice,\"02134\"\n2,\"Bob, Jr.\",\nversion:3\n";
+
+#[test]
+fn test_sqlite_import_export_roundtrip() {
+    println!("=== SQLite: import then export ===");
+
+    let db = temp_path("roundtrip.sqlite");
+    let toon_path = temp_path("roundtrip.toon");
+    fs::write(&toon_path, SNAPSHOT).unwrap();
+
+    let import = Command::new(get_binary_path())
+        .args(["sqlite", "import"])
+        .arg(&db)
+        .arg(&toon_path)
+        .output()
+        .expect("Failed to execute sqlite import");
+    let stderr = String::from_utf8_lossy(&import.stderr);
+    println!("Import stderr: {}", stderr);
+    assert!(import.status.success());
+    assert!(stderr.contains("Skipping \"version\""), "Scalars are not tables");
+
+    let export = Command::new(get_binary_path())
+        .args(["sqlite", "export"])
+        .arg(&db)
+        .output()
+        .expect("Failed to execute sqlite export");
+    let stdout = String::from_utf8_lossy(&export.stdout);
+    println!("Export:\n{}", stdout);
+    assert!(export.status.success(), "{}", String::from_utf8_lossy(&export.stderr));
+    assert!(stdout.contains("{id:int,total:float,paid:bool}"), "Headers should be typed");
+
+    let value = toonify::converter::toon_to_value(&stdout).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "orders": [{"id": 10, "total": 9.5, "paid": true}, {"id": 11, "total": 20.25, "paid": false}],
+            "users": [{"id": 1, "name": "Alice", "zip": "02134"}, {"id": 2, "name": "Bob, Jr.", "zip": null}]
+        })
+    );
+
+    // --tables selects and orders entities; unknown names are rejected
+    let only_users = Command::new(get_binary_path())
+        .args(["sqlite", "export"])
+        .arg(&db)
+        .args(["--tables", "users"])
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&only_users.stdout).contains("orders"));
+    let missing = Command::new(get_binary_path())
+        .args(["sqlite", "export"])
+        .arg(&db)
+        .args(["--tables", "nope"])
+        .output()
+        .unwrap();
+    assert!(!missing.status.success());
+
+    let _ = fs::remove_file(&db);
+    let _ = fs::remove_file(&toon_path);
+
+    println!("✓ SQLite tables round-trip through typed TOON\n");
+}
+
+#[test]
+fn test_sqlite_import_replace() {
+    println!("=== SQLite: --replace ===");
+
+    let db = temp_path("replace.sqlite");
+    let import = |toon: &str, replace: bool| {
+        let mut command = Command::new(get_binary_path());
+        command.args(["sqlite", "import"]).arg(&db);
+        if replace {
+            command.arg("--replace");
+        }
+        let mut child = command
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::io::Write::write_all(&mut child.stdin.take().unwrap(), toon.as_bytes()).unwrap();
+        assert!(child.wait_with_output().unwrap().status.success());
+    };
+
+    import("items[1]{id}:\n1\n", false);
+    import("items[1]{id}:\n2\n", false);
+    import("items[1]{id}:\n3\n", true);
+
+    let export = Command::new(get_binary_path()).args(["sqlite", "export"]).arg(&db).output().unwrap();
+    let value = toonify::converter::toon_to_value(&String::from_utf8_lossy(&export.stdout)).unwrap();
+    assert_eq!(value, json!({"items": [{"id": 3}]}), "--replace starts the table over");
+
+    let _ = fs::remove_file(&db);
+
+    println!("✓ Imports append unless --replace is given\n");
+}
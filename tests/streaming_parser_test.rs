@@ -0,0 +1,115 @@
+use serde_json::{json, Map, Value};
+use toonify::toon::{parse_toon, StreamingParser, ToonEvent};
+
+const DOC: &str = "users[2]{id,name}:\n1,Alice\n2,\"Bob, Jr.\"\nconfig{host,port}:\nlocalhost,8080\ntags[3]:\nrust\nllm\njson\nstatus:ok\n";
+
+// Feed `input` in `chunk`-byte pieces and collect every event
+fn events(input: &str, chunk: usize) -> Vec<ToonEvent> {
+    let mut parser = StreamingParser::new();
+    let mut events = Vec::new();
+    for piece in input.as_bytes().chunks(chunk) {
+        parser.feed(std::str::from_utf8(piece).unwrap());
+        events.extend(parser.by_ref().map(|event| event.unwrap()));
+    }
+    parser.finish();
+    events.extend(parser.by_ref().map(|event| event.unwrap()));
+    events
+}
+
+// Rebuild the document from events, the way a consumer would
+fn rebuild(events: &[ToonEvent]) -> Value {
+    let mut doc = Map::new();
+    let mut current: Option<(String, bool, Vec<Value>)> = None;
+    for event in events {
+        match event {
+            ToonEvent::EntityStart { name, is_array, .. } => current = Some((name.clone(), *is_array, Vec::new())),
+            ToonEvent::Row(row) => current.as_mut().unwrap().2.push(row.clone()),
+            ToonEvent::EntityEnd { .. } => {
+                let (name, is_array, mut rows) = current.take().unwrap();
+                let value = if is_array { Value::Array(rows) } else { rows.remove(0) };
+                doc.insert(name, value);
+            }
+            ToonEvent::Scalar { name, value } => {
+                doc.insert(name.clone(), value.clone());
+            }
+        }
+    }
+    Value::Object(doc)
+}
+
+#[test]
+fn test_streaming_parser_events() {
+    println!("=== StreamingParser: event sequence ===");
+
+    let events = events(DOC, DOC.len());
+    println!("Events: {:#?}", events);
+
+    assert_eq!(events[0], ToonEvent::EntityStart { name: "users".into(), columns: vec!["id".into(), "name".into()], is_array: true });
+    assert_eq!(events[1], ToonEvent::Row(json!({"id": 1, "name": "Alice"})));
+    assert_eq!(events[2], ToonEvent::Row(json!({"id": 2, "name": "Bob, Jr."})));
+    assert_eq!(events[3], ToonEvent::EntityEnd { name: "users".into() });
+    assert_eq!(events.last(), Some(&ToonEvent::Scalar { name: "status".into(), value: json!("ok") }));
+
+    println!("✓ Entities, rows and scalars are reported in order\n");
+}
+
+#[test]
+fn test_streaming_parser_matches_parse_toon_for_any_chunking() {
+    println!("=== StreamingParser: chunk boundaries ===");
+
+    let expected = parse_toon(DOC).unwrap();
+    for chunk in [1, 2, 3, 7, 16, DOC.len()] {
+        assert_eq!(rebuild(&events(DOC, chunk)), expected, "chunk size {}", chunk);
+    }
+
+    // No trailing newline: the last row is released by finish()
+    let trimmed = DOC.trim_end();
+    assert_eq!(rebuild(&events(trimmed, 5)), expected);
+
+    println!("✓ Same document regardless of how input is split\n");
+}
+
+#[test]
+fn test_streaming_parser_inline_rows() {
+    println!("=== StreamingParser: rows on the header line ===");
+
+    let doc = "tags[2]: x,y\npt{x,y}: 1,2\nusers[2]{id,name}: 1,Alice\n2,Bob\nstatus:ok\n";
+    let expected = parse_toon(doc).unwrap();
+    println!("Expected: {}", expected);
+    for chunk in [1, 4, doc.len()] {
+        assert_eq!(rebuild(&events(doc, chunk)), expected, "chunk size {}", chunk);
+    }
+    assert_eq!(expected["tags"], json!(["x", "y"]));
+
+    println!("✓ Inline rows match parse_toon\n");
+}
+
+#[test]
+fn test_streaming_parser_rows_before_finish() {
+    println!("=== StreamingParser: rows are available as lines complete ===");
+
+    let mut parser = StreamingParser::new();
+    parser.feed("logs[2]{level,msg}:\ninfo,started\nwarn,disk");
+    let ready: Vec<ToonEvent> = parser.by_ref().map(|event| event.unwrap()).collect();
+    assert_eq!(ready.len(), 2, "Header and first row only: {:?}", ready);
+    assert_eq!(ready[1], ToonEvent::Row(json!({"level": "info", "msg": "started"})));
+
+    parser.feed(" full\n");
+    assert_eq!(parser.next(), Some(Ok(ToonEvent::Row(json!({"level": "warn", "msg": "disk full"})))));
+    assert_eq!(parser.next(), None, "The table stays open until the next header or finish()");
+
+    parser.finish();
+    assert_eq!(parser.next(), Some(Ok(ToonEvent::EntityEnd { name: "logs".into() })));
+    assert_eq!(parser.next(), None);
+
+    println!("✓ Tail-style consumers see each row as soon as its line ends\n");
+}
+
+#[test]
+fn test_streaming_parser_reports_stray_lines() {
+    let mut parser = StreamingParser::new();
+    parser.feed("not an entry\n");
+    parser.finish();
+    let err = parser.next().unwrap().unwrap_err();
+    assert!(err.contains("line 1"), "{}", err);
+}
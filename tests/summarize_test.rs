@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter;
+use toonify::summarize::summarize;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("summarize");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn document() -> Value {
+    let mut users: Vec<Value> = (1..=100).map(|id| json!({"id": id, "name": format!("user{}", id), "role": "member"})).collect();
+    // A column only the last row has still shows up in the header
+    users[99]["email"] = json!("last@example.com");
+    json!({
+        "users": users,
+        "tags": ["a", "b", "c", "d", "e", "f", "g"],
+        "meta": {"source": "crm", "exported": "2024-05-01"},
+        "page": 1
+    })
+}
+
+#[test]
+fn test_summary_keeps_headers_and_first_rows() {
+    println!("=== Summarize: head + schema ===");
+
+    let summary = summarize(&document(), 3).unwrap();
+    println!("Summary:\n{}", summary);
+
+    assert!(summary.starts_with("users[3]{email:str,id:int,name:str,role:str}:\n,1,user1,member\n"), "Unexpected summary");
+    assert!(!summary.contains("user4"));
+    assert!(summary.contains("tags[3]:\na\nb\nc\n"));
+    assert!(summary.contains("meta{source,exported}:\ncrm,2024-05-01\n"));
+    assert!(summary.contains("page:1\n"));
+    assert!(summary.ends_with("_summary[2]{entity,rows,columns,shown}:\nusers,100,4,3\ntags,7,0,3\n"));
+
+    println!("✓ 3 of 100 rows, all 4 columns\n");
+}
+
+#[test]
+fn test_summary_is_valid_toon() {
+    let summary = summarize(&document(), 2).unwrap();
+    let value: Value = serde_json::from_str(&converter::toon_to_json(&summary).unwrap()).unwrap();
+    assert_eq!(value["users"].as_array().unwrap().len(), 2);
+    assert_eq!(value["users"][1]["name"], json!("user2"));
+    assert_eq!(value["_summary"][0], json!({"entity": "users", "rows": 100, "columns": 4, "shown": 2}));
+}
+
+#[test]
+fn test_short_tables_are_complete() {
+    let value = json!({"items": [{"id": 1}, {"id": 2}]});
+    let summary = summarize(&value, 5).unwrap();
+    assert!(summary.starts_with("items[2]{id:int}:\n1\n2\n"));
+    assert!(summary.contains("items,2,1,2"));
+}
+
+#[test]
+fn test_cli_summarize() {
+    println!("=== Summarize: CLI ===");
+
+    let input = temp_path("crm.json");
+    fs::write(&input, document().to_string()).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .args(["summarize", "--rows", "5"])
+        .arg(&input)
+        .output()
+        .expect("Failed to run summarize");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("users[5]{email:str,id:int,name:str,role:str}:"));
+    assert!(stdout.contains("users,100,4,5"));
+
+    println!("✓ Overview written to stdout\n");
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::thread;
+use std::time::Duration;
+
+fn get_binary_path() -> String {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    format!("{}/target/debug/toonify", manifest_dir)
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path
+}
+
+fn sync_once(a: &Path, b: &Path, extra: &[&str]) -> Output {
+    Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("sync")
+        .arg("--dir-a")
+        .arg(a)
+        .arg("--dir-b")
+        .arg(b)
+        .arg("--once")
+        .args(extra)
+        .output()
+        .expect("Failed to run sync")
+}
+
+#[test]
+fn test_sync_both_directions() {
+    println!("=== Sync: new files, edits and deletions both ways ===");
+
+    let root = temp_path("sync_both_directions");
+    let (a, b) = (root.join("json"), root.join("toon"));
+    fs::create_dir_all(a.join("nested")).unwrap();
+    fs::create_dir_all(&b).unwrap();
+    fs::write(a.join("nested/users.json"), r#"{"users":[{"id":1,"name":"Alice"}]}"#).unwrap();
+    fs::write(b.join("notes.toon"), "title:Hello\n").unwrap();
+
+    let output = sync_once(&a, &b, &[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(b.join("nested/users.toon")).unwrap().trim_end(), "users[1]{id,name}:\n1,Alice");
+    let notes: serde_json::Value = serde_json::from_str(&fs::read_to_string(a.join("notes.json")).unwrap()).unwrap();
+    assert_eq!(notes, serde_json::json!({ "title": "Hello" }));
+
+    // An edit on the TOON side flows back to JSON
+    fs::write(b.join("nested/users.toon"), "users[2]{id,name}:\n1,Alice\n2,Bob\n").unwrap();
+    assert!(sync_once(&a, &b, &[]).status.success());
+    let users: serde_json::Value = serde_json::from_str(&fs::read_to_string(a.join("nested/users.json")).unwrap()).unwrap();
+    assert_eq!(users["users"][1]["name"], "Bob");
+
+    // Deleting one side deletes the other
+    fs::remove_file(a.join("notes.json")).unwrap();
+    assert!(sync_once(&a, &b, &[]).status.success());
+    assert!(!b.join("notes.toon").exists());
+
+    let _ = fs::remove_dir_all(&root);
+    println!("✓ Directories kept equivalent\n");
+}
+
+#[test]
+fn test_sync_conflicts() {
+    println!("=== Sync: conflicting edits ===");
+
+    let root = temp_path("sync_conflicts");
+    let (a, b) = (root.join("json"), root.join("toon"));
+    fs::create_dir_all(&a).unwrap();
+    fs::create_dir_all(&b).unwrap();
+    fs::write(a.join("config.json"), r#"{"retries":1}"#).unwrap();
+    assert!(sync_once(&a, &b, &[]).status.success());
+
+    // Both sides edited: manual leaves them and exits non-zero
+    fs::write(a.join("config.json"), r#"{"retries":2}"#).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    fs::write(b.join("config.toon"), "retries:3\n").unwrap();
+    let output = sync_once(&a, &b, &["--conflict", "manual"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("conflicts need resolving"));
+    assert_eq!(fs::read_to_string(a.join("config.json")).unwrap(), r#"{"retries":2}"#);
+    assert_eq!(fs::read_to_string(b.join("config.toon.conflict")).unwrap().trim_end(), "retries:2");
+
+    // The default keeps the newer file, here the TOON one, and clears the conflict copies
+    assert!(sync_once(&a, &b, &[]).status.success());
+    let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(a.join("config.json")).unwrap()).unwrap();
+    assert_eq!(config["retries"], 3);
+    assert!(!b.join("config.toon.conflict").exists());
+
+    let _ = fs::remove_dir_all(&root);
+    println!("✓ Conflicts detected and resolved\n");
+}
+
+#[test]
+fn test_sync_watches_both_directories() {
+    println!("=== Sync: watch mode ===");
+
+    let root = temp_path("sync_watch");
+    let (a, b) = (root.join("json"), root.join("toon"));
+    let mut process = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("sync")
+        .arg("--dir-a")
+        .arg(&a)
+        .arg("--dir-b")
+        .arg(&b)
+        .spawn()
+        .expect("Failed to start sync");
+    thread::sleep(Duration::from_millis(500));
+
+    fs::write(b.join("items.toon"), "count:7\n").unwrap();
+    let mut synced = false;
+    for _ in 0..50 {
+        thread::sleep(Duration::from_millis(100));
+        if fs::read_to_string(a.join("items.json")).is_ok_and(|text| text.contains('7')) {
+            synced = true;
+            break;
+        }
+    }
+
+    let _ = process.kill();
+    let _ = process.wait();
+    let _ = fs::remove_dir_all(&root);
+    assert!(synced, "items.toon was not synced to items.json");
+    println!("✓ Watch mode synced a new file\n");
+}
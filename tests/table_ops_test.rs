@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+const EVENTS: &str = "events[6]{id,created_at,kind}:\n3,2024-01-03,click\n1,2024-01-01,view\n3,2024-01-03,click\n2,2024-01-02,view\n5,2024-01-02,click\n1,2024-01-05,view\ntags[2]:\na\nb\nversion:2\n";
+
+fn run(args: &[&str], input: &PathBuf) -> String {
+    let output = Command::new(get_binary_path())
+        .args(args)
+        .arg(input)
+        .output()
+        .expect("Failed to execute toonify");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "{:?} should succeed", args);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_dedupe_keeps_first_row_per_key() {
+    println!("=== Dedupe: first row per key ===");
+
+    let input = temp_path("dedupe_input.toon");
+    fs::write(&input, EVENTS).unwrap();
+
+    let output = run(&["dedupe", "--key", "id"], &input);
+    assert_eq!(
+        output,
+        "events[4]{id,created_at,kind}:\n3,2024-01-03,click\n1,2024-01-01,view\n2,2024-01-02,view\n5,2024-01-02,click\n\ntags[2]:\na\nb\n\nversion:2\n"
+    );
+
+    // Composite keys
+    let output = run(&["dedupe", "--key", "id,created_at"], &input);
+    assert!(output.starts_with("events[5]{id,created_at,kind}:"));
+
+    println!("=== Dedupe PASSED ===");
+}
+
+#[test]
+fn test_sort_merges_spilled_runs_stably() {
+    println!("=== Sort: external merge of sorted runs ===");
+
+    let input = temp_path("sort_input.toon");
+    fs::write(&input, EVENTS).unwrap();
+
+    // Two rows per run forces three spilled runs; ties keep input order
+    let external = run(&["sort", "--by", "created_at", "--desc", "--buffer-rows", "2"], &input);
+    let in_memory = run(&["sort", "--by", "created_at", "--desc"], &input);
+    assert_eq!(external, in_memory);
+    assert!(external.starts_with(
+        "events[6]{id,created_at,kind}:\n1,2024-01-05,view\n3,2024-01-03,click\n3,2024-01-03,click\n2,2024-01-02,view\n5,2024-01-02,click\n1,2024-01-01,view\n"
+    ));
+    assert!(external.ends_with("version:2\n"));
+
+    // Numbers compare numerically, with a second key breaking ties
+    let output = run(&["sort", "--by", "id,created_at", "--buffer-rows", "4"], &input);
+    let ids: Vec<&str> = output.lines().skip(1).take(6).map(|line| line.split(',').next().unwrap()).collect();
+    assert_eq!(ids, ["1", "1", "2", "3", "3", "5"]);
+
+    println!("=== Sort PASSED ===");
+}
+
+#[test]
+fn test_sort_requires_a_matching_table() {
+    let input = temp_path("sort_missing_input.toon");
+    fs::write(&input, EVENTS).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .args(["sort", "--by", "missing"])
+        .arg(&input)
+        .output()
+        .expect("Failed to execute toonify");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No table has columns missing"));
+}
+
+#[test]
+fn test_unselected_ragged_table_passes_through() {
+    let input = temp_path("ragged_passthrough.toon");
+    fs::write(&input, "logs[2]{id,msg}:\n1,hi\n2\nevents[2]{id}:\n1\n1\n").unwrap();
+
+    // `logs` is not the selected entity; its short row keeps the header's columns
+    let output = run(&["dedupe", "--key", "id", "--entity", "events"], &input);
+    assert_eq!(output, "logs[2]{id,msg}:\n1,hi\n2,\n\nevents[1]{id}:\n1\n");
+}
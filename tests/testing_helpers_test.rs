@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::PathBuf;
+use toonify::assert_toon_snapshot;
+use toonify::testing::{assert_snapshot_matches, assert_toon_roundtrip};
+
+#[test]
+fn test_assert_toon_roundtrip_passes() {
+    assert_toon_roundtrip(r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}],"count":2}"#);
+}
+
+#[test]
+#[should_panic(expected = "round trip changed the document")]
+fn test_assert_toon_roundtrip_reports_lossy_input() {
+    // Numeric-looking strings come back as numbers without typed headers
+    assert_toon_roundtrip(r#"{"codes":[{"zip":"02134"}]}"#);
+}
+
+#[test]
+fn test_snapshot_macro_matches_committed_file() {
+    assert_toon_snapshot!("users_table", r#"{"users":[{"id":1,"name":"Alice"},{"id":2,"name":"Bob"}]}"#);
+}
+
+#[test]
+#[should_panic(expected = "does not match")]
+fn test_snapshot_mismatch_panics_with_diff() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    fs::create_dir_all(&path).unwrap();
+    path.push("mismatch_snapshot.toon");
+    fs::write(&path, "status:old\n").unwrap();
+
+    assert_snapshot_matches(&path, "status:new");
+}
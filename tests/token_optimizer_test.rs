@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter;
+use toonify::tokens::Tokenizer;
+use toonify::toon::{optimize_tokens, parse_toon, serialize_toon, SerializeOptions};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("token_optimizer");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn shifts() -> Value {
+    let shifts: Vec<Value> = (0..40)
+        .map(|i| json!({"id": i, "start": format!("{:02}:00", 6 + i % 12), "end": format!("{:02}:30", 7 + i % 12)}))
+        .collect();
+    json!({"shifts": shifts})
+}
+
+#[test]
+fn test_columns_reordered_for_counter() {
+    println!("=== Token optimizer: column order ===");
+
+    // A counter that charges for a cell boundary before a number
+    let value = json!({"items": [{"a": "x", "b": 1}, {"a": "y", "b": 2}]});
+    let count = |text: &str| text.len() + 10 * text.matches(",1").count();
+    let optimized = optimize_tokens(&value, &SerializeOptions::default(), &count).unwrap();
+    println!("TOON:\n{}", optimized.output);
+
+    assert_eq!(optimized.output, "items[2]{b,a}:\n1,x\n2,y");
+    assert_eq!(optimized.tables[0].columns, ["b", "a"]);
+    assert!(optimized.tokens < optimized.baseline_tokens);
+    assert_eq!(parse_toon(&optimized.output).unwrap(), value);
+
+    println!("✓ Cheaper order chosen\n");
+}
+
+#[test]
+fn test_unneeded_colon_quotes_dropped() {
+    println!("=== Token optimizer: quoting ===");
+
+    let value = shifts();
+    let optimized = optimize_tokens(&value, &SerializeOptions::default(), &|text| Tokenizer::Estimate.count(text)).unwrap();
+    println!("TOON (head):\n{}", optimized.output.lines().take(3).collect::<Vec<_>>().join("\n"));
+
+    assert!(optimized.tables[0].bare_colons);
+    assert_eq!(optimized.tables[0].columns, ["id", "end", "start"], "The quoted column should leave first place");
+    assert!(optimized.output.contains("\n0,07:30,06:00\n"), "Colons should be unquoted: {}", optimized.output);
+    assert!(optimized.saved_percent() > 10.0, "Saved only {:.1}%", optimized.saved_percent());
+    assert_eq!(parse_toon(&optimized.output).unwrap(), value);
+
+    println!("✓ {} of {} tokens saved\n", optimized.saved(), optimized.baseline_tokens);
+}
+
+#[test]
+fn test_nothing_to_gain_keeps_default_output() {
+    let value = json!({"users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}], "page": 3});
+    let optimized = optimize_tokens(&value, &SerializeOptions::default(), &|text| Tokenizer::Estimate.count(text)).unwrap();
+    assert!(optimized.tables.is_empty());
+    assert_eq!(optimized.output, serialize_toon(&value).unwrap());
+    assert_eq!(optimized.saved(), 0);
+}
+
+#[test]
+fn test_tokenizer_names() {
+    assert_eq!("estimate".parse::<Tokenizer>().unwrap(), Tokenizer::Estimate);
+    assert!("bogus".parse::<Tokenizer>().unwrap_err().contains("Unknown tokenizer"));
+    #[cfg(not(feature = "tiktoken"))]
+    assert!("cl100k".parse::<Tokenizer>().unwrap_err().contains("tiktoken"));
+    #[cfg(feature = "tiktoken")]
+    assert!(Tokenizer::Cl100k.count("users[2]{id,name}:\n1,Alice") > 0);
+}
+
+#[test]
+fn test_cli_optimize_tokens() {
+    println!("=== Token optimizer: convert --optimize-tokens ===");
+
+    let input = temp_path("shifts.json");
+    fs::write(&input, shifts().to_string()).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .arg("--optimize-tokens")
+        .output()
+        .expect("Failed to execute toonify binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("Stderr: {}", stderr);
+
+    assert!(output.status.success());
+    assert!(stderr.contains("[TOKENS] shifts: columns"));
+    assert!(stderr.contains("fewer than the default layout"));
+    let back: Value = serde_json::from_str(&converter::toon_to_json(&String::from_utf8_lossy(&output.stdout)).unwrap()).unwrap();
+    assert_eq!(back, shifts());
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .args(["--to", "yaml", "--optimize-tokens"])
+        .output()
+        .expect("Failed to execute toonify binary");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only applies to TOON output"));
+
+    println!("✓ Optimized output converts back unchanged\n");
+}
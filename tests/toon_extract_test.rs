@@ -0,0 +1,84 @@
+use axum::response::Response;
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+
+use toonify::extract::{ResponseFormat, Toon};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Order {
+    id: u32,
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Item {
+    sku: String,
+    qty: u32,
+}
+
+async fn echo(Toon(order): Toon<Order>) -> Toon<Order> {
+    Toon(order)
+}
+
+async fn total(format: ResponseFormat, Toon(order): Toon<Order>) -> Response {
+    let qty: u32 = order.items.iter().map(|item| item.qty).sum();
+    format.respond(serde_json::json!({ "id": order.id, "qty": qty }))
+}
+
+async fn spawn() -> String {
+    let app = Router::new().route("/echo", post(echo)).route("/total", post(total));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+const ORDER: &str = "id:7\n\nitems[2]{qty,sku}:\n2,A-1\n3,B-2";
+
+#[tokio::test]
+async fn test_toon_extractor_and_responder() {
+    println!("=== Extract: Toon<T> ===");
+
+    let base = spawn().await;
+    let client = reqwest::Client::new();
+
+    let response = client.post(format!("{}/echo", base)).header("content-type", "application/toon").body(ORDER).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/toon; charset=utf-8");
+    assert_eq!(response.text().await.unwrap(), ORDER);
+
+    // Wrong content type, broken TOON, TOON of the wrong shape
+    let response = client.post(format!("{}/echo", base)).header("content-type", "application/json").body("{}").send().await.unwrap();
+    assert_eq!(response.status(), 415);
+    let response = client.post(format!("{}/echo", base)).header("content-type", "text/toon").body("stray text\nitems[1]{sku}:\nA\n").send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    let response = client.post(format!("{}/echo", base)).header("content-type", "application/toon").body("id:seven").send().await.unwrap();
+    assert_eq!(response.status(), 422);
+
+    println!("✓ Toon<T> parses and writes TOON bodies\n");
+}
+
+#[tokio::test]
+async fn test_response_format_follows_accept() {
+    println!("=== Extract: ResponseFormat ===");
+
+    let base = spawn().await;
+    let client = reqwest::Client::new();
+    let post = |accept: &'static str| {
+        client.post(format!("{}/total", base)).header("content-type", "application/toon").header("accept", accept).body(ORDER).send()
+    };
+
+    let response = post("application/toon").await.unwrap();
+    assert_eq!(response.headers()["content-type"], "application/toon; charset=utf-8");
+    assert_eq!(response.text().await.unwrap(), "id:7\n\nqty:5");
+
+    let response = post("application/json").await.unwrap();
+    assert_eq!(response.headers()["content-type"], "application/json");
+    assert_eq!(response.json::<serde_json::Value>().await.unwrap(), serde_json::json!({ "id": 7, "qty": 5 }));
+
+    let response = post("application/toon;q=0, application/json").await.unwrap();
+    assert_eq!(response.headers()["content-type"], "application/json");
+
+    println!("✓ ResponseFormat answers TOON only when asked\n");
+}
@@ -0,0 +1,40 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+
+static USERS: LazyLock<Value> = LazyLock::new(|| toonify::toon_include!("tests/fixtures/include/users.toon"));
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    users: Vec<User>,
+    version: u32,
+}
+
+#[test]
+fn test_toon_include_as_value() {
+    println!("=== toon_include!: serde_json::Value ===");
+
+    assert_eq!(
+        *USERS,
+        json!({"users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}], "version": 3})
+    );
+
+    println!("✓ Embedded TOON matches the parsed file\n");
+}
+
+#[test]
+fn test_toon_include_as_struct() {
+    println!("=== toon_include!: user struct ===");
+
+    let fixture = toonify::toon_include!("tests/fixtures/include/users.toon" as Fixture);
+    assert_eq!(fixture.version, 3);
+    assert_eq!(fixture.users[1], User { id: 2, name: "Bob".to_string() });
+
+    println!("✓ Embedded TOON deserializes into a struct\n");
+}
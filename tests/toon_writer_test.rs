@@ -0,0 +1,49 @@
+use serde_json::json;
+use toonify::toon::{parse_toon, serialize_toon, ToonWriter};
+
+#[test]
+fn test_writer_matches_serializer() {
+    println!("=== ToonWriter: same text as serialize_toon ===");
+
+    let mut writer = ToonWriter::new(Vec::new());
+    writer.begin_table("users", &["id", "name", "note"]).unwrap();
+    writer.write_row(&[json!(1), json!("Alice"), json!("has, comma")]).unwrap();
+    writer.write_row(&[json!(2), json!("Bob"), json!(null)]).unwrap();
+    writer.begin_table_with_len("orders", &["id", "total"], 1).unwrap();
+    writer.write_row(&[json!(10), json!(9.5)]).unwrap();
+    writer.write_scalar("page", &json!(3)).unwrap();
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+    println!("Output:\n{}", output);
+
+    let expected = json!({
+        "users": [
+            {"id": 1, "name": "Alice", "note": "has, comma"},
+            {"id": 2, "name": "Bob", "note": null}
+        ],
+        "orders": [{"id": 10, "total": 9.5}],
+        "page": 3
+    });
+    assert_eq!(output.trim_end(), serialize_toon(&expected).unwrap());
+    assert_eq!(parse_toon(&output).unwrap(), expected);
+
+    println!("✓ Event-written TOON matches the serializer\n");
+}
+
+#[test]
+fn test_writer_rejects_bad_rows() {
+    println!("=== ToonWriter: row validation ===");
+
+    let mut writer = ToonWriter::new(Vec::new());
+    assert!(writer.write_row(&[json!(1)]).is_err(), "Rows need an open table");
+
+    writer.begin_table("users", &["id", "name"]).unwrap();
+    let err = writer.write_row(&[json!(1)]).unwrap_err();
+    assert!(err.to_string().contains("2 columns"), "{}", err);
+
+    let mut writer = ToonWriter::new(Vec::new());
+    writer.begin_table_with_len("users", &["id"], 2).unwrap();
+    writer.write_row(&[json!(1)]).unwrap();
+    assert!(writer.finish().is_err(), "A short table must not end silently");
+
+    println!("✓ Mismatched rows and counts are errors\n");
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::{json, Value};
+use toonify::converter::Converter;
+use toonify::type_rules::{format_epoch, TargetType, TypeRules};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push("type_rules");
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path.push(name);
+    path
+}
+
+fn rules(pairs: &[(&str, TargetType)]) -> TypeRules {
+    TypeRules::new(pairs.iter().map(|(field, ty)| (field.to_string(), *ty)).collect::<HashMap<_, _>>())
+}
+
+fn convert(input: &str, from: &str, rules: TypeRules) -> Result<Value, String> {
+    let converter = Converter::builder().type_rules(rules).build();
+    let json = converter.convert(input, from, "json")?;
+    Ok(serde_json::from_str(&json).unwrap())
+}
+
+#[test]
+fn test_toon_cells_keep_declared_strings() {
+    println!("=== Type rules: zip codes stay strings ===");
+
+    let toon = "users[2]{id,zip}:\n1,02134\n2,90210";
+    let untyped = convert(toon, "toon", TypeRules::default()).unwrap();
+    assert_eq!(untyped["users"][0]["zip"], json!(2134), "Without rules the cell is sniffed as a number");
+
+    let typed = convert(toon, "toon", rules(&[("zip", TargetType::String)])).unwrap();
+    println!("Typed: {}", typed);
+    assert_eq!(typed["users"][0]["zip"], json!("02134"));
+    assert_eq!(typed["users"][1]["zip"], json!("90210"));
+    assert_eq!(typed["users"][0]["id"], json!(1));
+
+    println!("✓ Leading zero kept\n");
+}
+
+#[test]
+fn test_csv_cells_keep_declared_strings() {
+    let typed = convert("id,zip\n1,02134\n", "csv", rules(&[("zip", TargetType::String)])).unwrap();
+    assert_eq!(typed["rows"][0]["zip"], json!("02134"));
+    assert_eq!(typed["rows"][0]["id"], json!(1));
+}
+
+#[test]
+fn test_epochs_become_iso_dates() {
+    println!("=== Type rules: epoch to ISO 8601 ===");
+
+    let json = r#"{"events":[{"at":1714557600,"day":1714557600000,"seen":"1714557600.25"},{"at":null,"day":"2024-05-02T08:00:00Z","seen":"2024-05-02T08:00:00Z"}]}"#;
+    let typed = convert(json, "json", rules(&[("at", TargetType::DateTime), ("day", TargetType::Date), ("seen", TargetType::DateTime)])).unwrap();
+    println!("Typed: {}", typed);
+
+    assert_eq!(typed["events"][0]["at"], json!("2024-05-01T10:00:00Z"));
+    assert_eq!(typed["events"][0]["day"], json!("2024-05-01"), "Large epochs are milliseconds");
+    assert_eq!(typed["events"][0]["seen"], json!("2024-05-01T10:00:00.250Z"));
+    assert_eq!(typed["events"][1]["at"], Value::Null);
+    assert_eq!(typed["events"][1]["day"], json!("2024-05-02"));
+    assert_eq!(typed["events"][1]["seen"], json!("2024-05-02T08:00:00Z"));
+
+    println!("✓ Epochs converted\n");
+}
+
+#[test]
+fn test_format_epoch_before_1970() {
+    assert_eq!(format_epoch(-86_400.0, false), "1969-12-31T00:00:00Z");
+    assert_eq!(format_epoch(0.0, true), "1970-01-01");
+}
+
+#[test]
+fn test_entity_qualified_rules() {
+    let json = r#"{"users":[{"id":1,"code":7}],"orders":[{"id":2,"code":8}],"meta":{"owner":{"code":9}}}"#;
+    let typed = convert(json, "json", rules(&[("orders.id", TargetType::String), ("code", TargetType::String), ("users.code", TargetType::Float)])).unwrap();
+    assert_eq!(typed["users"][0]["id"], json!(1));
+    assert_eq!(typed["orders"][0]["id"], json!("2"));
+    assert_eq!(typed["users"][0]["code"], json!(7.0), "entity.field wins over field");
+    assert_eq!(typed["orders"][0]["code"], json!("8"));
+    assert_eq!(typed["meta"]["owner"]["code"], json!("9"), "Rules apply at any depth");
+}
+
+#[test]
+fn test_unconvertible_value_fails() {
+    let err = convert(r#"{"users":[{"age":30},{"age":"thirty"}]}"#, "json", rules(&[("age", TargetType::Int)])).unwrap_err();
+    assert!(err.contains("users[1].age") && err.contains("to int"), "Error: {}", err);
+
+    let flags = convert(r#"{"flags":[{"on":"yes"}]}"#, "json", rules(&[("on", TargetType::Bool)])).unwrap_err();
+    assert!(flags.contains("not a valid bool"), "Error: {}", flags);
+}
+
+#[test]
+fn test_rules_file_values() {
+    let parsed = TypeRules::from_value(&json!({"zip": "string", "created": "timestamp", "n": "integer"})).unwrap();
+    assert_eq!(parsed, rules(&[("zip", TargetType::String), ("created", TargetType::DateTime), ("n", TargetType::Int)]));
+
+    let err = TypeRules::from_value(&json!({"zip": "text"})).unwrap_err();
+    assert!(err.contains("Field 'zip'") && err.contains("Unknown type 'text'"), "Error: {}", err);
+    assert!(TypeRules::from_value(&json!({"zip": 1})).is_err());
+}
+
+#[test]
+fn test_convert_cli_types_file() {
+    println!("=== Type rules: CLI ===");
+
+    let input = temp_path("users.toon");
+    let types = temp_path("types.yaml");
+    fs::write(&input, "users[1]{id,zip,created}:\n1,02134,1714557600\n").unwrap();
+    fs::write(&types, "zip: string\ncreated: datetime\n").unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("TOONIFY_CONFIG", "none")
+        .arg("convert")
+        .arg(&input)
+        .arg("--types")
+        .arg(&types)
+        .output()
+        .expect("Failed to execute convert command");
+    println!("Stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Convert with --types should succeed");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Loaded 2 type rules"));
+
+    let value: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["users"][0]["zip"], json!("02134"));
+    assert_eq!(value["users"][0]["created"], json!("2024-05-01T10:00:00Z"));
+
+    println!("✓ --types applied\n");
+}
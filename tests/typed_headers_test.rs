@@ -0,0 +1,98 @@
+use serde_json::{json, Value};
+use toonify::converter::{self, Converter};
+
+fn typed() -> Converter {
+    Converter::builder().typed_headers(true).build()
+}
+
+#[test]
+fn test_typed_header_emitted() {
+    println!("=== Typed Headers: Serializer output ===");
+
+    let json = r#"{"users":[{"id":1,"name":"Alice","active":true},{"id":2,"name":"Bob","active":false}]}"#;
+    let toon = typed().json_to_toon(json).expect("Conversion failed");
+    println!("TOON:\n{}", toon);
+
+    assert!(toon.starts_with("users[2]{active:bool,id:int,name:str}:"), "Header should carry column types");
+
+    println!("✓ Typed header emitted\n");
+}
+
+#[test]
+fn test_numeric_strings_survive_roundtrip() {
+    println!("=== Typed Headers: String \"123\" vs number 123 ===");
+
+    let json = r#"{"zips":[{"code":"02134","count":3},{"code":"10001","count":5}],"meta":{"version":"1.0","flag":"true"}}"#;
+    let toon = typed().json_to_toon(json).expect("Conversion failed");
+    println!("TOON:\n{}", toon);
+
+    let back = converter::toon_to_json(&toon).expect("Parse failed");
+    let original: Value = serde_json::from_str(json).unwrap();
+    let final_value: Value = serde_json::from_str(&back).unwrap();
+
+    assert_eq!(original, final_value, "Typed columns should round-trip without sniffing");
+
+    println!("✓ Numeric strings preserved\n");
+}
+
+#[test]
+fn test_nulls_and_empty_strings_in_str_columns() {
+    println!("=== Typed Headers: Null vs empty string ===");
+
+    let json = r#"{"rows":[{"id":1,"note":""},{"id":2,"note":null},{"id":3,"note":"x"}]}"#;
+    let toon = typed().json_to_toon(json).expect("Conversion failed");
+    println!("TOON:\n{}", toon);
+
+    let back = converter::toon_to_json(&toon).expect("Parse failed");
+    let value: Value = serde_json::from_str(&back).unwrap();
+
+    assert_eq!(value["rows"][0]["note"], json!(""));
+    assert_eq!(value["rows"][1]["note"], Value::Null);
+    assert_eq!(value["rows"][2]["note"], json!("x"));
+
+    println!("✓ Null and empty string distinguished\n");
+}
+
+#[test]
+fn test_mixed_int_float_column() {
+    println!("=== Typed Headers: Mixed numbers ===");
+
+    let json = r#"{"prices":[{"amount":10},{"amount":9.99}]}"#;
+    let toon = typed().json_to_toon(json).expect("Conversion failed");
+    println!("TOON:\n{}", toon);
+    assert!(toon.contains("{amount:num}"), "Mixed int/float column should be typed num");
+
+    let back = converter::toon_to_json(&toon).expect("Parse failed");
+    let original: Value = serde_json::from_str(json).unwrap();
+    let final_value: Value = serde_json::from_str(&back).unwrap();
+    assert_eq!(original, final_value);
+
+    println!("✓ Mixed numbers keep their kind\n");
+}
+
+#[test]
+fn test_typed_tables_followed_by_entities() {
+    println!("=== Typed Headers: Multiple typed entities ===");
+
+    let toon = "users[1]{id:int,name:str}:\n7,007\n\nmeta{total:int}:\n1";
+    let json = converter::toon_to_json(toon).expect("Parse failed");
+    println!("JSON:\n{}", json);
+
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value, json!({"users": [{"id": 7, "name": "007"}], "meta": {"total": 1}}));
+
+    println!("✓ Typed table boundaries detected\n");
+}
+
+#[test]
+fn test_untyped_output_unchanged() {
+    println!("=== Typed Headers: Default output unchanged ===");
+
+    let json = r#"{"users":[{"id":1,"name":"Alice"}]}"#;
+    assert_eq!(
+        Converter::default().json_to_toon(json).unwrap(),
+        "users[1]{id,name}:\n1,Alice"
+    );
+
+    println!("✓ Untyped output unchanged\n");
+}
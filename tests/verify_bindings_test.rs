@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+fn empty_dir(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("tmp");
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create tmp dir");
+    path
+}
+
+#[test]
+fn test_verify_bindings_skips_missing_layers() {
+    println!("=== CLI: verify-bindings with no artifacts ===");
+
+    let empty = empty_dir("verify_bindings_empty");
+    let run = |require_all: bool| {
+        let mut command = Command::new(get_binary_path());
+        command
+            .arg("verify-bindings")
+            .arg("--bindings-dir").arg(&empty)
+            .arg("--pkg-dir").arg(&empty)
+            .arg("--lib-dir").arg(&empty);
+        if require_all {
+            command.arg("--require-all");
+        }
+        command.output().expect("Failed to execute toonify binary")
+    };
+
+    let lenient = run(false);
+    let stderr = String::from_utf8_lossy(&lenient.stderr);
+    println!("Stderr:\n{}", stderr);
+    assert!(lenient.status.success(), "Missing layers should be skipped by default");
+    for layer in ["python", "kotlin", "swift", "wasm"] {
+        assert!(stderr.contains(&format!("SKIP {}", layer)), "{} should be reported as skipped", layer);
+    }
+
+    let strict = run(true);
+    assert!(!strict.status.success(), "--require-all should fail when layers are skipped");
+
+    println!("✓ Skipped layers reported\n");
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_version_json_reports_build_metadata() {
+    println!("=== Version: --json ===");
+
+    let output = Command::new(get_binary_path())
+        .args(["version", "--json"])
+        .output()
+        .expect("Failed to execute version");
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).expect("version --json prints JSON");
+    println!("{}", info);
+
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(info["format_version"], toonify::toon::FORMAT_VERSION);
+    assert!(!info["git_sha"].as_str().unwrap().is_empty());
+    assert!(!info["target"].as_str().unwrap().is_empty());
+    let features: Vec<&str> = info["features"].as_array().unwrap().iter().filter_map(|f| f.as_str()).collect();
+    assert!(features.contains(&"cli"), "the binary always has the cli feature");
+    assert_eq!(info["static_build"], features.contains(&"static"));
+
+    println!("=== Version --json PASSED ===");
+}
+
+#[test]
+fn test_version_flag_and_plain_output() {
+    let output = Command::new(get_binary_path()).arg("--version").output().expect("Failed to execute --version");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains(env!("CARGO_PKG_VERSION")));
+
+    let output = Command::new(get_binary_path()).arg("version").output().expect("Failed to execute version");
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with(&format!("toonify {}", env!("CARGO_PKG_VERSION"))));
+}
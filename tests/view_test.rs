@@ -0,0 +1,38 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_view_requires_terminal() {
+    println!("=== CLI: view without a terminal ===");
+
+    let mut file = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    file.push("target");
+    file.push("tmp");
+    fs::create_dir_all(&file).expect("Failed to create tmp dir");
+    file.push("view_input.toon");
+    fs::write(&file, "users[1]{id,name}:\n1,Alice\n").unwrap();
+
+    // Piped stdout is not a TTY, so the TUI must refuse to start instead of garbling output
+    let output = Command::new(get_binary_path())
+        .arg("view")
+        .arg(&file)
+        .output()
+        .expect("Failed to execute toonify binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    println!("Stderr: {}", stderr);
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("interactive terminal"));
+
+    let _ = fs::remove_file(&file);
+    println!("✓ view refuses non-interactive output\n");
+}
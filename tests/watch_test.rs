@@ -276,3 +276,85 @@ fn test_watch_stops_cleanly() {
     println!("✓ Watch mode stopped cleanly\n");
 }
 
+
+#[test]
+fn test_watch_rapid_rewrites_end_on_latest_version() {
+    println!("=== Watch: Rapid rewrites of one file ===");
+    
+    let test_dir = "/tmp/watch_test_rapid";
+    let output_dir = "/tmp/watch_test_rapid_output";
+    let _ = fs::remove_dir_all(test_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(test_dir).expect("Failed to create test directory");
+    
+    let mut watch_process = Command::new(get_binary_path())
+        .args([
+            "watch",
+            "--input-dir", test_dir,
+            "--output-dir", output_dir,
+            "--jobs", "4"
+        ])
+        .spawn()
+        .expect("Failed to start watch command");
+    
+    thread::sleep(Duration::from_millis(500));
+    
+    // Events for these writes overlap the conversions they trigger
+    for version in 1..=30 {
+        fs::write(format!("{}/rapid.json", test_dir), format!(r#"{{"version":{}}}"#, version)).unwrap();
+        thread::sleep(Duration::from_millis(5));
+    }
+    
+    let output_file = format!("{}/rapid.toon", output_dir);
+    let converted = wait_for_file_update(&output_file, "version:30", 50);
+    
+    let _ = watch_process.kill();
+    let _ = watch_process.wait();
+    let final_content = fs::read_to_string(&output_file).unwrap_or_default();
+    let _ = fs::remove_dir_all(test_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    
+    assert!(converted, "Output should end on the last version, got {:?}", final_content);
+    assert_eq!(final_content.trim(), "version:30");
+    
+    println!("✓ Latest version converted\n");
+}
+
+#[test]
+fn test_watch_converts_many_files_concurrently() {
+    println!("=== Watch: Many files at once ===");
+    
+    let test_dir = "/tmp/watch_test_many";
+    let output_dir = "/tmp/watch_test_many_output";
+    let _ = fs::remove_dir_all(test_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(test_dir).expect("Failed to create test directory");
+    
+    let mut watch_process = Command::new(get_binary_path())
+        .args([
+            "watch",
+            "--input-dir", test_dir,
+            "--output-dir", output_dir
+        ])
+        .spawn()
+        .expect("Failed to start watch command");
+    
+    thread::sleep(Duration::from_millis(500));
+    
+    for index in 0..20 {
+        fs::write(format!("{}/file{}.json", test_dir, index), format!(r#"{{"index":{}}}"#, index)).unwrap();
+    }
+    
+    let all_converted = (0..20).all(|index| {
+        wait_for_file_update(&format!("{}/file{}.toon", output_dir, index), &format!("index:{}", index), 50)
+    });
+    
+    let _ = watch_process.kill();
+    let _ = watch_process.wait();
+    let _ = fs::remove_dir_all(test_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    
+    assert!(all_converted, "Every file should be converted");
+    
+    println!("✓ All files converted\n");
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toonify::export::{toon_to_xlsx, value_to_xlsx};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("toonify");
+    path
+}
+
+#[test]
+fn test_toon_to_xlsx_workbook() {
+    println!("=== Export: TOON to xlsx ===");
+
+    let bytes = toon_to_xlsx("users[2]{id,name,active}:\n1,Alice,true\n2,Bob,false\nversion:3").unwrap();
+    // xlsx is a zip archive
+    assert_eq!(&bytes[..2], b"PK");
+
+    // Sheet names Excel would reject are repaired rather than failing
+    let awkward = json!({
+        "a/b": [{ "x": 1 }],
+        "A:B": [{ "x": 2 }],
+        "an_entity_name_well_over_thirty_one_characters": [{ "x": 3 }],
+        "an_entity_name_well_over_thirty_one_characters_too": [{ "x": 4 }],
+    });
+    assert_eq!(&value_to_xlsx(&awkward).unwrap()[..2], b"PK");
+    assert_eq!(&value_to_xlsx(&json!({})).unwrap()[..2], b"PK");
+
+    println!("=== Export xlsx PASSED ===");
+}
+
+#[test]
+fn test_convert_to_xlsx_cli() {
+    let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    dir.push("target");
+    dir.push("tmp");
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("xlsx_input.toon");
+    let output = dir.join("xlsx_output.xlsx");
+    fs::write(&input, "users[1]{id,name}:\n1,Alice").unwrap();
+    let _ = fs::remove_file(&output);
+
+    let status = Command::new(get_binary_path())
+        .arg("convert")
+        .arg(&input)
+        .args(["--to", "xlsx", "--output"])
+        .arg(&output)
+        .status()
+        .expect("Failed to execute convert");
+    assert!(status.success());
+    assert_eq!(&fs::read(&output).unwrap()[..2], b"PK");
+}
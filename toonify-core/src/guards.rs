@@ -0,0 +1,103 @@
+// Parser guards against adversarial input (`--max-depth`, `--max-entities`, `--max-line-length`)
+//
+// Line length is checked on the raw text before any parser sees it, so one
+// gigantic line is refused without being tokenized or copied. The JSON and
+// TOON parsers count values as they go and stop as soon as a limit is passed
+// (JSON checks depth too), so an oversized document is never built in full.
+// Depth and the entity count are checked again on the parsed document, for
+// other formats and for quoted JSON in TOON cells, before hooks run and
+// before anything is emitted; that walk uses an explicit stack, so a deeply
+// nested document cannot overflow it. An entity here is any value: every
+// object, array and scalar counts, so a table row of five cells is six.
+//
+// All guards are off by default.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParserGuards {
+    /// Deepest nesting of objects and arrays; the root container is depth 1
+    pub max_depth: Option<usize>,
+    /// Most values in the whole document
+    pub max_entities: Option<usize>,
+    /// Longest input line, in bytes
+    pub max_line_length: Option<usize>,
+}
+
+impl ParserGuards {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_depth.is_none() && self.max_entities.is_none() && self.max_line_length.is_none()
+    }
+
+    /// Refuse input with a line over `max_line_length`
+    pub fn check_text(&self, input: &str) -> Result<(), String> {
+        let Some(max) = self.max_line_length else {
+            return Ok(());
+        };
+        if input.len() <= max {
+            return Ok(());
+        }
+        for (index, line) in input.split('\n').enumerate() {
+            let len = line.strip_suffix('\r').unwrap_or(line).len();
+            if len > max {
+                return Err(format!("Input line {} is {} bytes long, over the limit of {} (--max-line-length)", index + 1, len, max));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `max_depth` or `max_entities` is set
+    pub fn limits_values(&self) -> bool {
+        self.max_depth.is_some() || self.max_entities.is_some()
+    }
+
+    /// Refuse the `count`th value of a document if that is over `max_entities`
+    pub fn check_entities(&self, count: usize) -> Result<(), String> {
+        match self.max_entities {
+            Some(max) if count > max => Err(format!("Input has more than {} values (--max-entities)", max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Refuse a container at `depth` (the root is 1) if that is over `max_depth`
+    pub fn check_depth(&self, depth: usize, entity: Option<&str>) -> Result<(), String> {
+        match self.max_depth {
+            Some(max) if depth > max => {
+                let location = entity.map(|name| format!(" in entity {:?}", name)).unwrap_or_default();
+                Err(format!("Input is nested more than {} levels deep{} (--max-depth)", max, location))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Refuse a parsed document nested deeper than `max_depth` or holding more than `max_entities` values
+    pub fn check_value(&self, value: &Value) -> Result<(), String> {
+        if !self.limits_values() {
+            return Ok(());
+        }
+
+        // (value, depth, root entity it belongs to)
+        let mut stack: Vec<(&Value, usize, Option<&str>)> = vec![(value, 0, None)];
+        let mut entities = 0usize;
+        while let Some((value, depth, entity)) = stack.pop() {
+            entities += 1;
+            self.check_entities(entities)?;
+
+            let depth = match value {
+                Value::Object(_) | Value::Array(_) => depth + 1,
+                _ => continue,
+            };
+            self.check_depth(depth, entity)?;
+            match value {
+                Value::Object(map) => {
+                    for (key, child) in map {
+                        stack.push((child, depth, entity.or(Some(key.as_str()))));
+                    }
+                }
+                Value::Array(items) => stack.extend(items.iter().map(|child| (child, depth, entity))),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
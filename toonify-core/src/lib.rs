@@ -0,0 +1,6 @@
+// TOON parser, serializer and parser guards, shared by `toonify` and
+// `toonify-macros` so `toon_include!` rejects exactly what toonify rejects.
+// Use them through `toonify::toon` and `toonify::guards`.
+
+pub mod guards;
+pub mod toon;
@@ -0,0 +1,84 @@
+use std::str::FromStr;
+use serde_json::{Map, Value};
+
+/// What to do when a key appears more than once in the same object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    /// Reject the document
+    Error,
+    /// Keep the first value
+    FirstWins,
+    /// Keep the last value (serde_json's behavior)
+    #[default]
+    LastWins,
+    /// Concatenate when both values are arrays, otherwise keep the last value
+    MergeArrays,
+}
+
+impl DuplicateKeyPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateKeyPolicy::Error => "error",
+            DuplicateKeyPolicy::FirstWins => "first-wins",
+            DuplicateKeyPolicy::LastWins => "last-wins",
+            DuplicateKeyPolicy::MergeArrays => "merge-arrays",
+        }
+    }
+}
+
+impl FromStr for DuplicateKeyPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(DuplicateKeyPolicy::Error),
+            "first-wins" => Ok(DuplicateKeyPolicy::FirstWins),
+            "last-wins" => Ok(DuplicateKeyPolicy::LastWins),
+            "merge-arrays" => Ok(DuplicateKeyPolicy::MergeArrays),
+            _ => Err(format!("Unknown duplicate key policy '{}' (expected error, first-wins, last-wins, or merge-arrays)", s)),
+        }
+    }
+}
+
+/// Insert `key` into `map`, resolving a collision with `policy`
+///
+/// `location` describes the enclosing object for messages (e.g. "$.users[0]").
+/// Resolved collisions are recorded in `warnings`; `Error` returns `Err`.
+pub fn insert_with_policy(
+    map: &mut Map<String, Value>,
+    key: String,
+    value: Value,
+    policy: DuplicateKeyPolicy,
+    location: &str,
+    warnings: &mut Vec<String>,
+) -> Result<(), String> {
+    let Some(existing) = map.get_mut(&key) else {
+        map.insert(key, value);
+        return Ok(());
+    };
+
+    match policy {
+        DuplicateKeyPolicy::Error => {
+            return Err(format!("Duplicate key '{}' in {}", key, location));
+        }
+        DuplicateKeyPolicy::FirstWins => {
+            warnings.push(format!("Duplicate key '{}' in {}: keeping first value", key, location));
+        }
+        DuplicateKeyPolicy::LastWins => {
+            warnings.push(format!("Duplicate key '{}' in {}: keeping last value", key, location));
+            *existing = value;
+        }
+        DuplicateKeyPolicy::MergeArrays => match (existing, value) {
+            (Value::Array(items), Value::Array(more)) => {
+                warnings.push(format!("Duplicate key '{}' in {}: merged arrays", key, location));
+                items.extend(more);
+            }
+            (existing, value) => {
+                warnings.push(format!("Duplicate key '{}' in {}: values are not both arrays, keeping last value", key, location));
+                *existing = value;
+            }
+        },
+    }
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+// Canonical formatting for TOON text (`toonify fmt`)
+//
+// Works on lines rather than re-serializing, so cell text (number spelling,
+// quoting) is kept as written. Headers with stray whitespace that the parser
+// rejects (`users [2] { id , name } :`) are still recognized and repaired.
+// The result must parse, and when the input parsed too the two documents
+// are compared, so a formatter bug can never silently change data.
+
+use super::parser::{dictionary_line, inline_object, is_entry_header_line, parse_toon};
+
+/// Options for `format_toon`
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    /// Pad cells so columns line up across rows
+    pub align_columns: bool,
+    /// Order entities by name (stable for repeated names)
+    pub sort_entities: bool,
+}
+
+struct Block {
+    // `#dict` lines written above the header, which move with the entity
+    dictionaries: Vec<String>,
+    name: String,
+    header: Header,
+    rows: Vec<Vec<String>>,
+}
+
+struct Header {
+    has_count: bool,
+    columns: Option<Vec<String>>,
+    inline: String,
+}
+
+/// Normalize whitespace, fix array counts, and optionally align columns and sort entities
+pub fn format_toon(input: &str, options: &FormatOptions) -> Result<String, String> {
+    let original = parse_toon(input).ok();
+
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut dictionaries = Vec::new();
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if dictionary_line(line).is_some() {
+            dictionaries.push(line.to_string());
+        } else if is_entry_header_line(line) {
+            let (name, header) = split_header(line)?;
+            blocks.push(Block { dictionaries: std::mem::take(&mut dictionaries), name, header, rows: Vec::new() });
+        } else {
+            let block = blocks.last_mut()
+                .ok_or("Data line before the first entity header")?;
+            // A list's inline object is one element, commas and all
+            let cells = match (&block.header.columns, inline_object(line)) {
+                (None, Some(_)) => vec![line.to_string()],
+                _ => split_cells(line),
+            };
+            block.rows.push(cells);
+        }
+    }
+    if !dictionaries.is_empty() {
+        return Err("Dictionary line after the last entity".to_string());
+    }
+
+    if options.sort_entities {
+        blocks.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    let mut output = String::new();
+    for block in &blocks {
+        write_block(&mut output, block, options.align_columns);
+    }
+
+    let formatted = parse_toon(&output)
+        .map_err(|e| format!("Cannot format invalid TOON: {}", e))?;
+    if original.is_some_and(|original| original != formatted) {
+        return Err("Formatting changed the document (please report)".to_string());
+    }
+
+    // Keep Windows line endings, so `fmt --check` does not flag every CRLF file
+    if input.contains("\r\n") {
+        output = output.replace('\n', "\r\n");
+    }
+    Ok(output)
+}
+
+// "name [n] {a, b} : rest" -> parts; only called on lines the parser treats as headers
+fn split_header(line: &str) -> Result<(String, Header), String> {
+    let name_end = line.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(line.len());
+    let name = line[..name_end].to_string();
+    let mut rest = line[name_end..].trim_start();
+
+    let mut has_count = false;
+    if let Some(after) = rest.strip_prefix('[') {
+        let close = after.find(']').ok_or_else(|| format!("Unclosed array count in header: {}", line))?;
+        has_count = true;
+        rest = after[close + 1..].trim_start();
+    }
+
+    let mut columns = None;
+    if let Some(after) = rest.strip_prefix('{') {
+        let close = after.find('}').ok_or_else(|| format!("Unclosed column list in header: {}", line))?;
+        columns = Some(after[..close].split(',').map(|col| col.trim().to_string()).filter(|col| !col.is_empty()).collect());
+        rest = after[close + 1..].trim_start();
+    }
+
+    let inline = rest.strip_prefix(':')
+        .ok_or_else(|| format!("Missing ':' in header: {}", line))?
+        .trim()
+        .to_string();
+
+    Ok((name, Header { has_count, columns, inline }))
+}
+
+// Split a data line on commas outside quotes, keeping each cell's text verbatim
+pub fn split_cells(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut prev_was_backslash = false;
+
+    for ch in line.chars() {
+        if prev_was_backslash {
+            current.push(ch);
+            prev_was_backslash = false;
+            continue;
+        }
+
+        match ch {
+            '\\' => {
+                current.push(ch);
+                prev_was_backslash = true;
+            }
+            '"' => {
+                current.push(ch);
+                in_quotes = !in_quotes;
+            }
+            ',' if !in_quotes => {
+                cells.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(ch),
+        }
+    }
+    cells.push(current.trim().to_string());
+
+    cells
+}
+
+fn write_block(output: &mut String, block: &Block, align: bool) {
+    for dictionary in &block.dictionaries {
+        output.push_str(dictionary);
+        output.push('\n');
+    }
+    let header = &block.header;
+    output.push_str(&block.name);
+    if header.has_count {
+        // Tables count rows; primitive lists count items, which may share a line
+        let count = if header.columns.is_some() {
+            block.rows.len()
+        } else {
+            block.rows.iter().map(Vec::len).sum()
+        };
+        output.push_str(&format!("[{}]", count));
+    }
+    if let Some(columns) = &header.columns {
+        output.push_str(&format!("{{{}}}", columns.join(",")));
+    }
+    output.push(':');
+    output.push_str(&header.inline);
+    output.push('\n');
+
+    let widths: Vec<usize> = if align {
+        let mut widths = Vec::new();
+        for row in &block.rows {
+            for (i, cell) in row.iter().enumerate() {
+                if widths.len() <= i {
+                    widths.push(0);
+                }
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+        widths
+    } else {
+        Vec::new()
+    };
+
+    for row in &block.rows {
+        let mut line = String::new();
+        for (i, cell) in row.iter().enumerate() {
+            if i + 1 == row.len() {
+                line.push_str(cell);
+            } else if align {
+                // Pad after the comma so the next cell starts in the same column
+                let cell = format!("{},", cell);
+                line.push_str(&format!("{:<width$}", cell, width = widths[i] + 2));
+            } else {
+                line.push_str(cell);
+                line.push(',');
+            }
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+}
+
+/// Line diff between two texts (`-` removed, `+` added), empty when equal
+pub fn diff_lines(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old == new {
+        return String::new();
+    }
+
+    // LCS table is quadratic; large files just get a summary
+    if old.len().saturating_mul(new.len()) > 4_000_000 {
+        return format!("-{} lines\n+{} lines\n", old.len(), new.len());
+    }
+
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            diff.push_str(&format!("{:>5} +{}\n", j + 1, new[j]));
+            j += 1;
+        } else {
+            diff.push_str(&format!("{:>5} -{}\n", i + 1, old[i]));
+            i += 1;
+        }
+    }
+
+    diff
+}
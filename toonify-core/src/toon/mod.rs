@@ -0,0 +1,23 @@
+pub mod duplicates;
+pub mod format;
+pub mod optimize;
+pub mod outline;
+pub mod parser;
+pub mod serializer;
+pub mod streaming;
+pub mod types;
+pub mod writer;
+
+pub use duplicates::DuplicateKeyPolicy;
+pub use format::{format_toon, FormatOptions};
+pub use optimize::{optimize_tokens, TableChoice, TokenOptimization};
+pub use outline::{outline, OutlineEntity, OutlineRow};
+pub use parser::{parse_toon, parse_toon_lossy, parse_toon_with, ParseOptions, RecoverableError};
+pub use parser::parse_value;
+pub use streaming::{StreamingParser, ToonEvent};
+pub use serializer::{serialize_toon, serialize_toon_chunked, serialize_toon_with, ArrayLayout, SerializeOptions};
+pub use types::{columns_of, ColumnType};
+pub use writer::ToonWriter;
+
+/// Version of the TOON syntax this crate reads and writes
+pub const FORMAT_VERSION: &str = "1.0";
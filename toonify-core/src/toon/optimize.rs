@@ -0,0 +1,160 @@
+// Token-minimizing TOON output (`toonify convert --optimize-tokens`)
+//
+// Byte counts say little about what a document costs in a prompt: BPE
+// tokenizers merge some cell boundaries and split others, so the same table
+// can tokenize differently depending on which column follows which and how
+// its cells are separated. For every table this tries column orders (pairwise
+// swaps, kept while they lower the count), ", " instead of "," between cells,
+// and leaving strings with ':' unquoted where the parser doesn't need the
+// quotes, scoring each candidate on a sample of rows with the caller's token
+// counter. The result must parse back to the same document as the default
+// output, otherwise the default output is returned.
+
+use serde_json::Value;
+
+use super::parser::parse_toon;
+use super::serializer::{serialize_toon_with, serialize_tuned, table_columns, SerializeOptions, TableTuning, Tuning};
+
+/// Rows of each table that candidates are scored on
+pub const SAMPLE_ROWS: usize = 64;
+
+// Pairwise swaps are quadratic in the column count; wider tables keep their order
+const MAX_REORDERED_COLUMNS: usize = 32;
+const MAX_PASSES: usize = 4;
+
+// Each toggles one of the per-table flags
+const FLIPS: [fn(&mut TableTuning); 2] = [|t| t.spaced = !t.spaced, |t| t.bare_colons = !t.bare_colons];
+
+/// What `optimize_tokens` chose for one table
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableChoice {
+    pub entity: String,
+    pub columns: Vec<String>,
+    /// Cells separated by ", " rather than ","
+    pub spaced: bool,
+    /// Strings containing ':' left unquoted
+    pub bare_colons: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenOptimization {
+    pub output: String,
+    pub tokens: usize,
+    /// Tokens in the default `serialize_toon_with` output
+    pub baseline_tokens: usize,
+    /// Tables written differently from the default, in document order
+    pub tables: Vec<TableChoice>,
+}
+
+impl TokenOptimization {
+    pub fn saved(&self) -> usize {
+        self.baseline_tokens.saturating_sub(self.tokens)
+    }
+
+    pub fn saved_percent(&self) -> f64 {
+        if self.baseline_tokens == 0 {
+            0.0
+        } else {
+            self.saved() as f64 * 100.0 / self.baseline_tokens as f64
+        }
+    }
+}
+
+/// Serialize `value` with the table layout that `count_tokens` scores lowest
+pub fn optimize_tokens(value: &Value, options: &SerializeOptions, count_tokens: &dyn Fn(&str) -> usize) -> Result<TokenOptimization, String> {
+    let baseline = serialize_toon_with(value, options)?;
+    let baseline_tokens = count_tokens(&baseline);
+    let unchanged = |output: String| TokenOptimization { output, tokens: baseline_tokens, baseline_tokens, tables: Vec::new() };
+
+    let Value::Object(map) = value else { return Ok(unchanged(baseline)) };
+    let mut tuning = Tuning::new();
+    let mut tables = Vec::new();
+    for (name, entity) in map {
+        let Value::Array(rows) = entity else { continue };
+        let Some(columns) = table_columns(rows, options.layout) else { continue };
+        let default = TableTuning {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            spaced: false,
+            bare_colons: false,
+        };
+        let sample = Value::Array(rows.iter().take(SAMPLE_ROWS).cloned().collect());
+        let best = tune_table(name, &sample, options, default.clone(), count_tokens)?;
+        if best != default {
+            tables.push(TableChoice { entity: name.clone(), columns: best.columns.clone(), spaced: best.spaced, bare_colons: best.bare_colons });
+            tuning.insert(name.clone(), best);
+        }
+    }
+    if tuning.is_empty() {
+        return Ok(unchanged(baseline));
+    }
+
+    let output = serialize_tuned(value, options, &tuning)?;
+    let tokens = count_tokens(&output);
+    // Sample scores can mislead on the full document; never return something worse or different
+    if tokens >= baseline_tokens || parse_toon(&output).ok() != parse_toon(&baseline).ok() {
+        return Ok(unchanged(baseline));
+    }
+    Ok(TokenOptimization { output, tokens, baseline_tokens, tables })
+}
+
+fn tune_table(
+    name: &str,
+    sample: &Value,
+    options: &SerializeOptions,
+    start: TableTuning,
+    count_tokens: &dyn Fn(&str) -> usize,
+) -> Result<TableTuning, String> {
+    let score = |tuning: &TableTuning| -> Result<usize, String> {
+        let mut document = serde_json::Map::new();
+        document.insert(name.to_string(), sample.clone());
+        let tuning = Tuning::from([(name.to_string(), tuning.clone())]);
+        Ok(count_tokens(&serialize_tuned(&Value::Object(document), options, &tuning)?))
+    };
+
+    // Keeps `candidate` when it scores lower than the best so far
+    let improve = |best: &mut (TableTuning, usize), candidate: TableTuning| -> Result<bool, String> {
+        let candidate_score = score(&candidate)?;
+        if candidate_score >= best.1 {
+            return Ok(false);
+        }
+        *best = (candidate, candidate_score);
+        Ok(true)
+    };
+    let try_flags = |best: &mut (TableTuning, usize)| -> Result<(), String> {
+        for flip in FLIPS {
+            let mut candidate = best.0.clone();
+            flip(&mut candidate);
+            improve(best, candidate)?;
+        }
+        Ok(())
+    };
+
+    let start_score = score(&start)?;
+    let mut best = (start, start_score);
+    // Flags first, then swaps; a swap is also tried with each flag toggled,
+    // since an order can pay off only with a flag set (a quoted column moved
+    // out of first place) or cleared again
+    try_flags(&mut best)?;
+    if best.0.columns.len() <= MAX_REORDERED_COLUMNS {
+        for _ in 0..MAX_PASSES {
+            let mut improved = false;
+            for i in 0..best.0.columns.len() {
+                for j in i + 1..best.0.columns.len() {
+                    let mut candidate = best.0.clone();
+                    candidate.columns.swap(i, j);
+                    for flip in FLIPS {
+                        let mut flipped = candidate.clone();
+                        flip(&mut flipped);
+                        improved |= improve(&mut best, flipped)?;
+                    }
+                    improved |= improve(&mut best, candidate)?;
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+    }
+    try_flags(&mut best)?;
+    Ok(best.0)
+}
@@ -0,0 +1,122 @@
+// Line-level structure of a TOON document, for editors and diagnostics
+//
+// `parse_toon` returns values; this returns where they are: each entity's
+// header line, its declared `[n]` count and the line of every row, so a tool
+// can point at the row a problem is on. Rows are decoded exactly as the
+// parser decodes them. Lines that belong to no entity are left out; use
+// `parse_toon_lossy` to report those. Line numbers are 1-based.
+
+use serde_json::Value;
+
+use super::parser::{declared_len, dictionary_line, expand_codes, header_line, is_entry_header_line, list_line, parse_value, row_object, split_csv, Column, Dictionary};
+use super::types::ColumnType;
+
+/// One entry of the document and the rows under it
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntity {
+    pub name: String,
+    /// Line of the `name...:` header
+    pub line: usize,
+    /// `n` from `name[n]...:`; `None` for objects and scalars
+    pub declared_len: Option<usize>,
+    pub columns: Vec<String>,
+    /// Types from a typed header (`{id:int,name}`), one per column
+    pub column_types: Vec<Option<ColumnType>>,
+    pub rows: Vec<OutlineRow>,
+    /// The value of a `name:value` entry
+    pub scalar: Option<Value>,
+}
+
+/// A data line: a table row as an object, a list line as an array
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineRow {
+    pub line: usize,
+    pub cells: usize,
+    pub value: Value,
+}
+
+impl OutlineEntity {
+    pub fn is_array(&self) -> bool {
+        self.declared_len.is_some()
+    }
+
+    /// Elements actually present: rows of a table, values of a list
+    pub fn len(&self) -> usize {
+        if self.columns.is_empty() {
+            self.rows.iter().map(|row| row.cells).sum()
+        } else {
+            self.rows.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Last line belonging to the entity
+    pub fn end_line(&self) -> usize {
+        self.rows.last().map_or(self.line, |row| row.line)
+    }
+}
+
+/// Entities in document order
+pub fn outline(input: &str) -> Vec<OutlineEntity> {
+    let mut entities: Vec<OutlineEntity> = Vec::new();
+    // Typed columns and dictionaries of the entity still taking rows, if any
+    let mut open: Option<(Vec<Column>, Vec<Dictionary>)> = None;
+    let mut dictionaries = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(dictionary) = dictionary_line(trimmed) {
+            open = None;
+            dictionaries.push(dictionary);
+            continue;
+        }
+
+        if is_entry_header_line(trimmed) {
+            open = None;
+            let Some((name, is_array, columns, rest)) = header_line(trimmed) else {
+                continue;
+            };
+            let scalar = (!is_array && columns.is_empty()).then(|| parse_value(rest));
+            entities.push(OutlineEntity {
+                name,
+                line: index + 1,
+                declared_len: if is_array { declared_len(trimmed) } else { None },
+                columns: columns.iter().map(|(column, _)| column.clone()).collect(),
+                column_types: columns.iter().map(|(_, ty)| *ty).collect(),
+                rows: Vec::new(),
+                scalar: scalar.clone(),
+            });
+            let entity_dictionaries = std::mem::take(&mut dictionaries);
+            if scalar.is_none() {
+                open = Some((columns, entity_dictionaries));
+            }
+            continue;
+        }
+
+        let (Some((columns, dictionaries)), Some(entity)) = (&open, entities.last_mut()) else {
+            continue;
+        };
+        let (cells, value) = if columns.is_empty() {
+            let items = list_line(trimmed);
+            (items.len(), Value::Array(items))
+        } else {
+            let mut row = row_object(columns, trimmed);
+            expand_codes(&mut row, dictionaries);
+            (split_csv(trimmed).len(), row)
+        };
+        entity.rows.push(OutlineRow { line: index + 1, cells, value });
+        // An object has exactly one row
+        if !entity.is_array() {
+            open = None;
+        }
+    }
+
+    entities
+}
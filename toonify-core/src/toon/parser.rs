@@ -0,0 +1,683 @@
+use nom::{
+    bytes::complete::{take_while, take_while1},
+    character::complete::{char, digit1, multispace0, newline},
+    combinator::{map, map_res, opt},
+    multi::{many0, separated_list0},
+    sequence::terminated,
+    IResult,
+};
+use std::collections::HashMap;
+
+use serde_json::{Map, Number, Value};
+
+use crate::guards::ParserGuards;
+
+use super::duplicates::{insert_with_policy, DuplicateKeyPolicy};
+use super::types::{split_typed_column, ColumnType};
+
+/// Header column: name plus optional type annotation
+pub(super) type Column = (String, Option<ColumnType>);
+
+/// A `#dict column: value=0,...` line: the column and its values by code
+pub(super) type Dictionary = (String, HashMap<String, String>);
+
+/// Options controlling how TOON input is parsed
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// How repeated entity names are resolved
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// Types for header columns written without one, by `column` or `entity.column`
+    pub column_types: HashMap<String, ColumnType>,
+    /// Parsing stops as soon as the document has more values than `max_entities`
+    pub guards: ParserGuards,
+}
+
+impl ParseOptions {
+    /// Declared type for `column` of `entity`; `entity.column` wins over `column`
+    pub fn column_type(&self, entity: &str, column: &str) -> Option<ColumnType> {
+        if self.column_types.is_empty() {
+            return None;
+        }
+        self.column_types.get(&format!("{}.{}", entity, column))
+            .or_else(|| self.column_types.get(column))
+            .copied()
+    }
+}
+
+pub fn parse_toon(input: &str) -> Result<Value, String> {
+    parse_toon_with(input, &ParseOptions::default()).map(|(value, _)| value)
+}
+
+/// Parse TOON with explicit options; returns the document and any warnings
+pub fn parse_toon_with(input: &str, options: &ParseOptions) -> Result<(Value, Vec<String>), String> {
+    let normalized = unix_line_endings(input);
+    let input = without_signature_footer(normalized.as_ref());
+    let values = std::cell::Cell::new(1);
+    match toon_document(input, options, &values) {
+        Ok((remaining, entries)) => {
+            if !remaining.trim().is_empty() {
+                return Err(format!("Parse error: unexpected content at end: {:?}", remaining.chars().take(50).collect::<String>()));
+            }
+            
+            let mut map = Map::new();
+            let mut warnings = Vec::new();
+            for (key, value) in entries {
+                insert_with_policy(&mut map, key, value, options.duplicate_keys, "document root", &mut warnings)?;
+            }
+            Ok((Value::Object(map), warnings))
+        },
+        Err(e) => {
+            options.guards.check_entities(values.get())?;
+            Err(format!("Parse error: {}", e))
+        }
+    }
+}
+
+/// A malformed line skipped by `parse_toon_lossy`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoverableError {
+    /// 1-based line number in the input
+    pub line: usize,
+    pub message: String,
+    /// The skipped line, as written
+    pub content: String,
+}
+
+impl std::fmt::Display for RecoverableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// Collects skipped lines while a lossy parse carries on past them
+struct Recovery<'a> {
+    source: &'a str,
+    errors: Vec<RecoverableError>,
+}
+
+impl Recovery<'_> {
+    fn skip_line<'i>(&mut self, at: &'i str, message: String) -> &'i str {
+        let line = self.source[..self.source.len() - at.len()].matches('\n').count() + 1;
+        let end = at.find('\n').map_or(at.len(), |i| i + 1);
+        self.errors.push(RecoverableError {
+            line,
+            message,
+            content: at[..end].trim_end().to_string(),
+        });
+        &at[end..]
+    }
+}
+
+/// Parse TOON, skipping lines that can't be read instead of failing
+///
+/// Table rows whose cell count doesn't match the header, and lines that don't
+/// start a valid entry, are dropped and reported; everything else is parsed as
+/// `parse_toon` would. Meant for log-style inputs where one bad line shouldn't
+/// abort the whole conversion.
+pub fn parse_toon_lossy(input: &str) -> (Value, Vec<RecoverableError>) {
+    let normalized = unix_line_endings(input);
+    let input = without_signature_footer(normalized.as_ref());
+    let mut recovery = Recovery { source: input, errors: Vec::new() };
+    let mut map = Map::new();
+    let mut warnings = Vec::new();
+    let mut rest = input;
+    let values = std::cell::Cell::new(1);
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        match entry_with(rest, &ParseOptions::default(), &values, Some(&mut recovery)) {
+            Ok((next, (key, value))) if next.len() < rest.len() => {
+                let at = rest;
+                rest = next;
+                if let Err(e) = insert_with_policy(&mut map, key, value, DuplicateKeyPolicy::default(), "document root", &mut warnings) {
+                    recovery.skip_line(at, e);
+                }
+            }
+            _ => rest = recovery.skip_line(rest, "not a TOON entry".to_string()),
+        }
+    }
+
+    (Value::Object(map), recovery.errors)
+}
+
+// Files saved on Windows end lines with CRLF; the grammar below only knows LF.
+// Line numbers are unchanged, so errors still point at the right line.
+fn unix_line_endings(input: &str) -> std::borrow::Cow<'_, str> {
+    if input.contains('\r') {
+        std::borrow::Cow::Owned(input.replace("\r\n", "\n"))
+    } else {
+        std::borrow::Cow::Borrowed(input)
+    }
+}
+
+/// Starts the last line of output signed with `--sign-footer`:
+/// `# toonify-signature ed25519 <hex>`, over everything before it
+pub const SIGNATURE_FOOTER: &str = "\n# toonify-signature ed25519 ";
+
+// The footer isn't part of the document, so a signed file reads like the
+// original; checking the signature is `verify-signature`'s job
+fn without_signature_footer(input: &str) -> &str {
+    match input.rfind(SIGNATURE_FOOTER) {
+        Some(at) if !input[at + SIGNATURE_FOOTER.len()..].trim_end().contains('\n') => &input[..at],
+        _ => input,
+    }
+}
+
+// `values` counts the document's values so far, the root included
+fn toon_document<'i>(input: &'i str, options: &ParseOptions, values: &std::cell::Cell<usize>) -> IResult<&'i str, Vec<(String, Value)>> {
+    let (input, _) = multispace0(input)?;
+    many0(terminated(|input: &'i str| entry_with(input, options, values, None), multispace0))(input)
+}
+
+fn entry_with<'i>(input: &'i str, options: &ParseOptions, values: &std::cell::Cell<usize>, recovery: Option<&mut Recovery>) -> IResult<&'i str, (String, Value)> {
+    let (input, dictionaries) = many0(terminated(dictionary_entry, multispace0))(input)?;
+    let (input, key) = identifier(input)?;
+    let (input, meta) = opt(metadata)(input)?;
+    // Not past the line end: a null scalar has nothing after its ':'
+    let (input, _) = char(':')(input)?;
+    let rows_counted = matches!(meta, Some((true, _)));
+    
+    let (input, value) = if let Some((is_array, columns)) = meta {
+        let columns: Vec<Column> = columns.into_iter()
+            .map(|(name, ty)| {
+                let ty = ty.or_else(|| options.column_type(key, &name));
+                (name, ty)
+            })
+            .collect();
+        if is_array {
+            array_value(input, columns, &dictionaries, &Counter { guards: &options.guards, values }, recovery)?
+        } else if !columns.is_empty() {
+            object_value(input, columns, &dictionaries)?
+        } else {
+            let (input, rest) = take_until_newline_or_end(input)?;
+            let val = parse_value(rest.trim());
+            (input, val)
+        }
+    } else {
+        let (input, rest) = take_until_newline_or_end(input)?;
+        let val = parse_value(rest.trim());
+        (input, val)
+    };
+    // A table or list counts its rows as they are read
+    if !rows_counted {
+        Counter { guards: &options.guards, values }.add(input, &value)?;
+    }
+    
+    Ok((input, (key.to_string(), value)))
+}
+
+/// An entry header line split into name, `[n]` presence, columns and the text after ':'
+pub(super) fn header_line(line: &str) -> Option<(String, bool, Vec<Column>, &str)> {
+    let (input, key) = identifier(line.trim_start()).ok()?;
+    let (input, meta) = opt(metadata)(input).ok()?;
+    let (rest, _) = char::<&str, nom::error::Error<&str>>(':')(input).ok()?;
+    let (is_array, columns) = meta.unwrap_or_default();
+    Some((key.to_string(), is_array, columns, rest.trim()))
+}
+
+/// The `n` of a `name[n]...:` header
+pub(super) fn declared_len(line: &str) -> Option<usize> {
+    let (input, _) = identifier(line.trim_start()).ok()?;
+    array_metadata(input).ok().map(|(_, count)| count)
+}
+
+fn metadata(input: &str) -> IResult<&str, (bool, Vec<Column>)> {
+    let (input, array_meta) = opt(array_metadata)(input)?;
+    let (input, columns) = opt(column_metadata)(input)?;
+    
+    let is_array = array_meta.is_some();
+    let cols = columns.unwrap_or_default();
+    
+    Ok((input, (is_array, cols)))
+}
+
+fn array_metadata(input: &str) -> IResult<&str, usize> {
+    let (input, _) = char('[')(input)?;
+    let (input, count) = map_res(digit1, |s: &str| s.parse::<usize>())(input)?;
+    let (input, _) = char(']')(input)?;
+    Ok((input, count))
+}
+
+fn column_metadata(input: &str) -> IResult<&str, Vec<Column>> {
+    let (input, _) = char('{')(input)?;
+    let (input, cols) = separated_list0(
+        char(','),
+        map(
+            take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '@' || c == '/' || c == '.' || c == ':'),
+            |s: &str| split_typed_column(s.trim()),
+        ),
+    )(input)?;
+    let (input, _) = char('}')(input)?;
+    Ok((input, cols))
+}
+
+fn dictionary_entry(input: &str) -> IResult<&str, Dictionary> {
+    let (rest, line) = take_until_newline_or_end(input)?;
+    match dictionary_line(line) {
+        Some(dictionary) => Ok((rest, dictionary)),
+        None => Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag))),
+    }
+}
+
+/// Parse `#dict status: pending=0,shipped=1`, which applies to the next entity
+pub(super) fn dictionary_line(line: &str) -> Option<Dictionary> {
+    let rest = line.trim().strip_prefix("#dict ")?;
+    let (column, entries) = rest.split_once(':')?;
+    let column = column.trim();
+    if column.is_empty() {
+        return None;
+    }
+    let mut codes = HashMap::new();
+    for entry in split_csv(entries) {
+        // Values may contain '=' (they are quoted then); codes never do
+        let (value, code) = entry.rsplit_once('=')?;
+        codes.insert(code.trim().to_string(), value.to_string());
+    }
+    Some((column.to_string(), codes))
+}
+
+/// Replace dictionary codes in a row's columns with the values they stand for
+pub(super) fn expand_codes(row: &mut Value, dictionaries: &[Dictionary]) {
+    let Value::Object(obj) = row else { return };
+    for (column, codes) in dictionaries {
+        let Some(cell) = obj.get_mut(column) else { continue };
+        let value = match cell {
+            Value::Number(n) => codes.get(&n.to_string()),
+            Value::String(s) => codes.get(s.as_str()),
+            _ => None,
+        };
+        if let Some(value) = value {
+            *cell = Value::String(value.clone());
+        }
+    }
+}
+
+// Tallies a document's values against `--max-entities` while it is parsed
+struct Counter<'a> {
+    guards: &'a ParserGuards,
+    values: &'a std::cell::Cell<usize>,
+}
+
+impl Counter<'_> {
+    // Count `value` and everything in it; `parse_toon_with` reports the guard
+    // when this fails
+    fn add<'i>(&self, input: &'i str, value: &Value) -> Result<(), nom::Err<nom::error::Error<&'i str>>> {
+        if self.guards.max_entities.is_none() {
+            return Ok(());
+        }
+        let mut stack = vec![value];
+        let mut count = 0;
+        while let Some(value) = stack.pop() {
+            count += 1;
+            match value {
+                Value::Object(map) => stack.extend(map.values()),
+                Value::Array(items) => stack.extend(items),
+                _ => {}
+            }
+        }
+        self.values.set(self.values.get() + count);
+        match self.guards.check_entities(self.values.get()) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(nom::Err::Failure(nom::error::Error::new(input, nom::error::ErrorKind::TooLarge))),
+        }
+    }
+}
+
+fn array_value<'i>(input: &'i str, columns: Vec<Column>, dictionaries: &[Dictionary], counter: &Counter, mut recovery: Option<&mut Recovery>) -> IResult<&'i str, Value> {
+    let mut input = input;
+    let mut items = Vec::new();
+    // The array itself, before any of its rows
+    counter.add(input, &Value::Null)?;
+    
+    loop {
+        let (remaining, _) = multispace0(input)?;
+        
+        match data_line(remaining) {
+            Ok((next_input, line)) => {
+                // Strict parsing pads or truncates ragged rows; lossy parsing drops them
+                if let Some(recovery) = recovery.as_deref_mut().filter(|_| !columns.is_empty()) {
+                    let cells = split_csv(&line).len();
+                    if cells != columns.len() {
+                        input = recovery.skip_line(remaining, format!("expected {} cells, found {}", columns.len(), cells));
+                        continue;
+                    }
+                }
+                
+                if !columns.is_empty() {
+                    let mut row = row_object(&columns, &line);
+                    expand_codes(&mut row, dictionaries);
+                    counter.add(remaining, &row)?;
+                    items.push(row);
+                } else {
+                    for item in list_line(&line) {
+                        counter.add(remaining, &item)?;
+                        items.push(item);
+                    }
+                }
+                
+                input = next_input;
+            }
+            Err(_) => break,
+        }
+    }
+    
+    Ok((input, Value::Array(items)))
+}
+
+/// The elements on one line of a list: a single inline JSON object, or comma-separated values
+pub(super) fn list_line(line: &str) -> Vec<Value> {
+    match inline_object(line) {
+        Some(obj) => vec![obj],
+        None => split_csv_cells(line).iter().map(cell_value).collect(),
+    }
+}
+
+// `{"id":1,"tags":["a"]}` as written for objects in a list
+pub(super) fn inline_object(line: &str) -> Option<Value> {
+    let line = line.trim();
+    if !(line.starts_with('{') && line.ends_with('}')) {
+        return None;
+    }
+    serde_json::from_str::<Map<String, Value>>(line).ok().map(Value::Object)
+}
+
+fn object_value<'i>(input: &'i str, columns: Vec<Column>, dictionaries: &[Dictionary]) -> IResult<&'i str, Value> {
+    let (input, _) = multispace0(input)?;
+    let (input, line) = data_line(input)?;
+    
+    let mut row = row_object(&columns, &line);
+    expand_codes(&mut row, dictionaries);
+    Ok((input, row))
+}
+
+// Build an object from one data row, honoring column type annotations;
+// missing trailing cells are null
+pub(super) fn row_object(columns: &[Column], line: &str) -> Value {
+    let cells = split_csv_cells(line);
+    let obj = columns.iter()
+        .enumerate()
+        .map(|(i, (name, ty))| {
+            let value = match (cells.get(i), ty) {
+                (None, _) => Value::Null,
+                (Some(cell), Some(ty)) => ty.parse_cell(&cell.text, cell.quoted),
+                (Some(cell), None) => cell_value(cell),
+            };
+            (name.clone(), value)
+        })
+        .collect();
+    Value::Object(obj)
+}
+
+fn data_line(input: &str) -> IResult<&str, String> {
+    let start_input = input;
+    let (input, line) = take_until_newline_or_end(input)?;
+    let trimmed = line.trim();
+    
+    if trimmed.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            start_input,
+            nom::error::ErrorKind::Tag,
+        )));
+    }
+    
+    // A dictionary line belongs to the entity after it
+    if is_entry_header_line(trimmed) || trimmed.starts_with("#dict ") {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            start_input,
+            nom::error::ErrorKind::Tag,
+        )));
+    }
+    
+    Ok((input, line.to_string()))
+}
+
+pub fn is_entry_header_line(line: &str) -> bool {
+    let chars: Vec<char> = line.trim().chars().collect();
+    
+    let mut i = 0;
+    
+    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+    }
+    
+    if i == 0 {
+        return false;
+    }
+    
+    // Walk optional [count] and {columns} sections up to the entry colon.
+    // Column lists may contain ':' (typed headers), so they are skipped whole.
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        
+        if chars[i] == ':' {
+            // Reject URL-like patterns (colon followed by //)
+            return !(chars.get(i + 1) == Some(&'/') && chars.get(i + 2) == Some(&'/'));
+        }
+        
+        if chars[i] == '[' {
+            i += 1;
+            while i < chars.len() && chars[i].is_numeric() {
+                i += 1;
+            }
+            if i >= chars.len() || chars[i] != ']' {
+                return false;
+            }
+            i += 1;
+            continue;
+        }
+        
+        if chars[i] == '{' {
+            while i < chars.len() && chars[i] != '}' {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return false;
+            }
+            i += 1;
+            continue;
+        }
+        
+        return false;
+    }
+    
+    false
+}
+
+fn take_until_newline_or_end(input: &str) -> IResult<&str, &str> {
+    let (remaining, content) = take_while(|c| c != '\n' && c != '\r')(input)?;
+    let (remaining, _) = opt(newline)(remaining)?;
+    Ok((remaining, content))
+}
+
+fn identifier(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+pub(super) fn split_csv(line: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut prev_was_backslash = false;
+    
+    for ch in line.chars() {
+        if prev_was_backslash {
+            current.push(escaped(ch));
+            prev_was_backslash = false;
+            continue;
+        }
+        
+        match ch {
+            '\\' => {
+                prev_was_backslash = true;
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                parts.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(ch),
+        }
+    }
+    
+    if !current.is_empty() || !parts.is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    
+    parts
+}
+
+/// A CSV cell and whether it was written in quotes
+struct Cell {
+    text: String,
+    quoted: bool,
+}
+
+// Like split_csv, but keeps whitespace inside quotes and records quoting,
+// which typed `str` columns need to tell "" apart from null
+fn split_csv_cells(line: &str) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut prev_was_backslash = false;
+    
+    for ch in line.chars() {
+        if prev_was_backslash {
+            current.push(escaped(ch));
+            prev_was_backslash = false;
+            continue;
+        }
+        
+        match ch {
+            '\\' => {
+                prev_was_backslash = true;
+            }
+            '"' => {
+                if !quoted && current.trim().is_empty() {
+                    // Drop whitespace before the opening quote
+                    current.clear();
+                }
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            ',' if !in_quotes => {
+                cells.push(finish_cell(&current, quoted));
+                current.clear();
+                quoted = false;
+            }
+            c if quoted && !in_quotes && c.is_whitespace() => {}
+            _ => current.push(ch),
+        }
+    }
+    
+    if !current.is_empty() || !cells.is_empty() || quoted {
+        cells.push(finish_cell(&current, quoted));
+    }
+    
+    cells
+}
+
+// An untyped cell: quoted text is a string, or JSON, and never a number,
+// bool or null
+fn cell_value(cell: &Cell) -> Value {
+    if cell.quoted {
+        quoted_value(&cell.text)
+    } else {
+        parse_value(&cell.text)
+    }
+}
+
+// The character `\c` stands for inside quotes; the serializer escapes line
+// breaks so a quoted value never spans lines
+fn escaped(ch: char) -> char {
+    match ch {
+        'n' => '\n',
+        'r' => '\r',
+        other => other,
+    }
+}
+
+fn unescape(quoted: &str) -> String {
+    let mut text = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => text.extend(chars.next().map(escaped)),
+            _ => text.push(ch),
+        }
+    }
+    text
+}
+
+/// JSON that quoted text stands for: a nested array or object, or a JSON
+/// string, which is how strings that look like either are written
+pub(super) fn quoted_json(text: &str) -> Option<Value> {
+    if !text.trim_start().starts_with(['[', '{', '"']) {
+        return None;
+    }
+    match serde_json::from_str(text) {
+        Ok(value @ (Value::Array(_) | Value::Object(_) | Value::String(_))) => Some(value),
+        _ => None,
+    }
+}
+
+fn quoted_value(text: &str) -> Value {
+    quoted_json(text).unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+fn finish_cell(text: &str, quoted: bool) -> Cell {
+    Cell {
+        text: if quoted { text.to_string() } else { text.trim().to_string() },
+        quoted,
+    }
+}
+
+pub fn parse_value(s: &str) -> Value {
+    let s = s.trim();
+    
+    if s.is_empty() || s == "null" {
+        return Value::Null;
+    }
+    
+    if s == "true" {
+        return Value::Bool(true);
+    }
+    
+    if s == "false" {
+        return Value::Bool(false);
+    }
+    
+    if let Ok(num) = s.parse::<i64>() {
+        return Value::Number(Number::from(num));
+    }
+    
+    if let Ok(num) = s.parse::<u64>() {
+        return Value::Number(Number::from(num));
+    }
+    
+    if let Ok(num) = s.parse::<f64>()
+        && let Some(n) = Number::from_f64(num)
+    {
+        return Value::Number(n);
+    }
+    
+    // Handle quoted strings - strip quotes and unescape
+    if s.starts_with('"') && s.ends_with('"') && s.len() >= 2 {
+        return quoted_value(&unescape(&s[1..s.len()-1]));
+    }
+    
+    // Try to parse as JSON (for nested arrays/objects)
+    if ((s.starts_with('[') && s.ends_with(']')) || (s.starts_with('{') && s.ends_with('}')))
+        && let Ok(json_value) = serde_json::from_str(s)
+    {
+        return json_value;
+    }
+    
+    Value::String(s.to_string())
+}
@@ -0,0 +1,512 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use std::str::FromStr;
+use serde_json::{Map, Number, Value};
+
+use super::parser::quoted_json;
+use super::types::ColumnType;
+
+/// Options controlling TOON output
+#[derive(Debug, Clone, Default)]
+pub struct SerializeOptions {
+    /// Annotate header columns with their types (`{id:int,name:str}`)
+    pub typed_headers: bool,
+    /// How arrays of objects are written
+    pub layout: ArrayLayout,
+    /// Write low-cardinality string columns as codes under a `#dict` line
+    pub dictionary: bool,
+}
+
+/// Table or one-object-per-line output for arrays of objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayLayout {
+    /// A table, unless the objects share too few keys for one to pay off
+    #[default]
+    Auto,
+    /// Always a table, filling missing keys with empty cells
+    Table,
+    /// Always one JSON object per line (`users[2]:\n{"id":1}\n{"name":"Bob"}`)
+    List,
+}
+
+impl ArrayLayout {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArrayLayout::Auto => "auto",
+            ArrayLayout::Table => "table",
+            ArrayLayout::List => "list",
+        }
+    }
+}
+
+impl FromStr for ArrayLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ArrayLayout::Auto),
+            "table" => Ok(ArrayLayout::Table),
+            "list" => Ok(ArrayLayout::List),
+            _ => Err(format!("Unknown array layout '{}' (expected auto, table, or list)", s)),
+        }
+    }
+}
+
+/// In `Auto` layout, the share of table cells that must hold a value
+///
+/// Below this, most of the table would be empty cells for keys a row lacks,
+/// and writing each object on its own line is shorter.
+pub const MIN_TABLE_FILL: f64 = 0.5;
+
+/// Most distinct values a `#dict` line lists
+pub const MAX_DICTIONARY_VALUES: usize = 64;
+
+pub fn serialize_toon(value: &Value) -> Result<String, String> {
+    serialize_toon_with(value, &SerializeOptions::default())
+}
+
+// Everything is written straight into one output buffer, sized up front from
+// the entity shapes, so there is no String per cell or row. Integers go
+// through itoa and floats through serde_json's own Display, so the text is
+// identical to what serde_json writes.
+pub fn serialize_toon_with(value: &Value, options: &SerializeOptions) -> Result<String, String> {
+    serialize_tuned(value, options, &Tuning::new())
+}
+
+/// Column order and cell spelling for one table, as chosen by `optimize_tokens`
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TableTuning {
+    pub columns: Vec<String>,
+    /// Separate cells with ", " instead of ","
+    pub spaced: bool,
+    /// Leave strings containing ':' unquoted outside the first column
+    pub bare_colons: bool,
+}
+
+impl TableTuning {
+    // The tuned order, if it names exactly the table's columns
+    fn order<'a>(&self, columns: Vec<&'a str>) -> Vec<&'a str> {
+        let tuned: Vec<&'a str> = self.columns.iter()
+            .filter_map(|name| columns.iter().find(|col| **col == name.as_str()).copied())
+            .collect();
+        if tuned.len() == columns.len() && self.columns.len() == columns.len() { tuned } else { columns }
+    }
+}
+
+/// Table tunings by entity name
+pub(super) type Tuning = HashMap<String, TableTuning>;
+
+pub(super) fn serialize_tuned(value: &Value, options: &SerializeOptions, tuning: &Tuning) -> Result<String, String> {
+    match value {
+        Value::Object(map) => {
+            let mut output = String::with_capacity(estimate_len(value));
+
+            for (key, val) in map {
+                write_entry(&mut output, key, val, options, tuning.get(key), &mut |_: &mut String| Ok(()))?;
+                output.push('\n');
+            }
+
+            output.truncate(output.trim_end().len());
+            Ok(output)
+        }
+        _ => Err("Root value must be an object".to_string()),
+    }
+}
+
+/// The text `serialize_toon_with` returns, handed to `emit` in pieces of
+/// roughly `chunk_size` bytes as it is written (split between rows)
+///
+/// An error from `emit` stops serialization and is returned.
+pub fn serialize_toon_chunked(
+    value: &Value,
+    options: &SerializeOptions,
+    chunk_size: usize,
+    emit: &mut dyn FnMut(&[u8]) -> Result<(), String>,
+) -> Result<(), String> {
+    let Value::Object(map) = value else {
+        return Err("Root value must be an object".to_string());
+    };
+    let mut output = String::with_capacity(chunk_size.min(estimate_len(value)) + 256);
+
+    // Trailing whitespace stays buffered: it is only written if more text follows
+    let mut flush = |output: &mut String| -> Result<(), String> {
+        let text_len = output.trim_end().len();
+        if text_len < chunk_size.max(1) {
+            return Ok(());
+        }
+        emit(&output.as_bytes()[..text_len])?;
+        output.drain(..text_len);
+        Ok(())
+    };
+    for (key, val) in map {
+        write_entry(&mut output, key, val, options, None, &mut flush)?;
+        output.push('\n');
+        flush(&mut output)?;
+    }
+
+    let text_len = output.trim_end().len();
+    if text_len > 0 {
+        emit(&output.as_bytes()[..text_len])?;
+    }
+    Ok(())
+}
+
+// Rough output size: ~12 bytes per scalar cell is typical for real payloads
+fn estimate_len(value: &Value) -> usize {
+    let Value::Object(map) = value else { return 0 };
+    map.iter()
+        .map(|(key, entity)| {
+            let cells = match entity {
+                Value::Array(items) => items.len() * match items.first() {
+                    Some(Value::Object(row)) => row.len(),
+                    _ => 1,
+                },
+                Value::Object(row) => row.len(),
+                _ => 1,
+            };
+            key.len() + 16 + cells * 12
+        })
+        .sum()
+}
+
+// `flush` runs after every row, so chunked output can hand off part of a large table
+fn write_entry(
+    output: &mut String,
+    key: &str,
+    value: &Value,
+    options: &SerializeOptions,
+    tuning: Option<&TableTuning>,
+    flush: &mut dyn FnMut(&mut String) -> Result<(), String>,
+) -> Result<(), String> {
+    match value {
+        Value::Array(arr) => {
+            if arr.is_empty() {
+                output.push_str(key);
+                output.push_str("[0]:\n");
+                return Ok(());
+            }
+
+            if let Some(columns) = table_columns(arr, options.layout) {
+                let columns = match tuning {
+                    Some(tuning) => tuning.order(columns),
+                    None => columns,
+                };
+                let delimiter = if tuning.is_some_and(|tuning| tuning.spaced) { ", " } else { "," };
+                let bare_colons = tuning.is_some_and(|tuning| tuning.bare_colons);
+                let types: Vec<Option<ColumnType>> = if options.typed_headers {
+                    columns.iter()
+                        .map(|col| ColumnType::infer(arr.iter().filter_map(|item| item.get(col))))
+                        .collect()
+                } else {
+                    vec![None; columns.len()]
+                };
+
+                let dictionaries: Vec<Option<ColumnDictionary>> = if options.dictionary {
+                    columns.iter().map(|col| ColumnDictionary::build(arr, col)).collect()
+                } else {
+                    columns.iter().map(|_| None).collect()
+                };
+                for (col, dictionary) in columns.iter().zip(&dictionaries) {
+                    if let Some(dictionary) = dictionary {
+                        dictionary.write_line(output, col);
+                    }
+                }
+
+                output.push_str(key);
+                output.push('[');
+                output.push_str(itoa::Buffer::new().format(arr.len()));
+                output.push(']');
+                write_header_columns(output, &columns, &types);
+
+                for item in arr {
+                    if let Value::Object(obj) = item {
+                        for (i, (col, dictionary)) in columns.iter().zip(&dictionaries).enumerate() {
+                            if i > 0 {
+                                output.push_str(delimiter);
+                            }
+                            let value = obj.get(*col).unwrap_or(&Value::Null);
+                            match (dictionary, value) {
+                                (Some(dictionary), Value::String(s)) => {
+                                    output.push_str(itoa::Buffer::new().format(dictionary.codes[s.as_str()]));
+                                }
+                                // Only a first cell with ':' can make a row look like a header
+                                (None, Value::String(s)) if bare_colons && i > 0 && !needs_quotes(s, false) => {
+                                    output.push_str(s);
+                                }
+                                (None, Value::String(s)) if i == 0 && opens_header(s) => write_quoted(output, s),
+                                // The row would be a blank line, which is skipped
+                                (None, Value::Null) if columns.len() == 1 => output.push_str("null"),
+                                _ => write_cell(output, value, types[i]),
+                            }
+                        }
+                        output.push('\n');
+                        flush(output)?;
+                    }
+                }
+            } else {
+                write_list(output, key, arr, flush)?;
+            }
+        }
+        // A header without columns would have a blank row
+        Value::Object(obj) if obj.is_empty() => {
+            output.push_str(key);
+            output.push_str(":{}\n");
+        }
+        Value::Object(obj) => {
+            let columns: Vec<&str> = obj.keys().map(String::as_str).collect();
+            let types: Vec<Option<ColumnType>> = if options.typed_headers {
+                obj.values().map(ColumnType::of_value).collect()
+            } else {
+                vec![None; columns.len()]
+            };
+            output.push_str(key);
+            write_header_columns(output, &columns, &types);
+
+            for (i, (val, ty)) in obj.values().zip(&types).enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                match val {
+                    Value::String(s) if i == 0 && opens_header(s) => write_quoted(output, s),
+                    // The row would be a blank line, which is skipped
+                    Value::Null if obj.len() == 1 => output.push_str("null"),
+                    _ => write_cell(output, val, *ty),
+                }
+            }
+            output.push('\n');
+        }
+        _ => {
+            output.push_str(key);
+            output.push(':');
+            write_value(output, value);
+            output.push('\n');
+        }
+    }
+    Ok(())
+}
+
+/// The columns of the table `arr` is written as, in default order, or `None` for a list
+pub(super) fn table_columns(arr: &[Value], layout: ArrayLayout) -> Option<Vec<&str>> {
+    let Some(Value::Object(_)) = arr.first() else { return None };
+    let mut columns_set = BTreeSet::new();
+    for item in arr {
+        if let Value::Object(obj) = item {
+            columns_set.extend(obj.keys().map(String::as_str));
+        }
+    }
+    let columns: Vec<&str> = columns_set.into_iter().collect();
+    // Rows of a table without columns would be blank lines
+    (!columns.is_empty() && use_table(arr, columns.len(), layout)).then_some(columns)
+}
+
+// Whether an array whose first element is an object is written as a table.
+// A table has no row for an element that is not an object, so those force a
+// list under every layout, `Table` included.
+fn use_table(arr: &[Value], columns: usize, layout: ArrayLayout) -> bool {
+    let mut filled = 0;
+    for item in arr {
+        match item {
+            Value::Object(obj) => filled += obj.len(),
+            _ => return false,
+        }
+    }
+    match layout {
+        ArrayLayout::Table => true,
+        ArrayLayout::List => false,
+        ArrayLayout::Auto => filled as f64 >= (arr.len() * columns) as f64 * MIN_TABLE_FILL,
+    }
+}
+
+// "key[n]:\n" then one element per line; objects are written as inline JSON
+fn write_list(
+    output: &mut String,
+    key: &str,
+    arr: &[Value],
+    flush: &mut dyn FnMut(&mut String) -> Result<(), String>,
+) -> Result<(), String> {
+    output.push_str(key);
+    output.push('[');
+    output.push_str(itoa::Buffer::new().format(arr.len()));
+    output.push_str("]:\n");
+    for item in arr {
+        match item {
+            Value::Object(obj) => write_inline_object(output, obj)?,
+            // A blank line would be skipped on the way back
+            Value::Null => output.push_str("null"),
+            _ => write_value(output, item),
+        }
+        output.push('\n');
+        flush(output)?;
+    }
+    Ok(())
+}
+
+fn write_inline_object(output: &mut String, obj: &Map<String, Value>) -> Result<(), String> {
+    let json = serde_json::to_string(obj).map_err(|e| format!("Failed to serialize object: {}", e))?;
+    output.push_str(&json);
+    Ok(())
+}
+
+// Codes for the distinct strings of one table column, in order of first use
+struct ColumnDictionary<'a> {
+    values: Vec<&'a str>,
+    codes: HashMap<&'a str, usize>,
+}
+
+impl<'a> ColumnDictionary<'a> {
+    // Only for columns holding nothing but strings and nulls, with few
+    // distinct values, and only when the `#dict` line costs less than it saves.
+    // The line's cells are read back trimmed and unescaped, so values with
+    // edge whitespace, backslashes or newlines keep the column out.
+    fn build(arr: &'a [Value], column: &str) -> Option<Self> {
+        let mut dictionary = ColumnDictionary { values: Vec::new(), codes: HashMap::new() };
+        let mut plain_len = 0;
+        let mut coded_len = 0;
+        for item in arr {
+            match item.get(column) {
+                None | Some(Value::Null) => {}
+                Some(Value::String(s)) if s.trim() == s.as_str() && !s.contains(['\\', '\n', '\r']) => {
+                    let next = dictionary.values.len();
+                    let code = *dictionary.codes.entry(s.as_str()).or_insert(next);
+                    if code == next {
+                        dictionary.values.push(s);
+                        if next == MAX_DICTIONARY_VALUES {
+                            return None;
+                        }
+                    }
+                    plain_len += s.len();
+                    coded_len += code_len(code);
+                }
+                Some(_) => return None,
+            }
+        }
+
+        let line_len: usize = "#dict : \n".len()
+            + column.len()
+            + dictionary.values.iter().enumerate().map(|(code, value)| value.len() + code_len(code) + 2).sum::<usize>();
+        (!dictionary.values.is_empty() && coded_len + line_len < plain_len).then_some(dictionary)
+    }
+
+    // "#dict status: pending=0,shipped=1\n"
+    fn write_line(&self, output: &mut String, column: &str) {
+        output.push_str("#dict ");
+        output.push_str(column);
+        output.push_str(": ");
+        for (code, value) in self.values.iter().enumerate() {
+            if code > 0 {
+                output.push(',');
+            }
+            if value.contains([',', '"', '=']) {
+                write_quoted(output, value);
+            } else {
+                output.push_str(value);
+            }
+            output.push('=');
+            output.push_str(itoa::Buffer::new().format(code));
+        }
+        output.push('\n');
+    }
+}
+
+fn code_len(code: usize) -> usize {
+    itoa::Buffer::new().format(code).len()
+}
+
+// "{a,b:int}:\n"
+fn write_header_columns(output: &mut String, columns: &[&str], types: &[Option<ColumnType>]) {
+    output.push('{');
+    for (i, (col, ty)) in columns.iter().zip(types).enumerate() {
+        if i > 0 {
+            output.push(',');
+        }
+        output.push_str(col);
+        if let Some(ty) = ty {
+            output.push(':');
+            output.push_str(ty.as_str());
+        }
+    }
+    output.push_str("}:\n");
+}
+
+pub(super) fn write_value(output: &mut String, value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => output.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(output, n),
+        Value::String(s) => write_string(output, s),
+        Value::Array(_) | Value::Object(_) => {
+            let json = serde_json::to_string(value).unwrap_or_default();
+            // Quote JSON values so CSV parser doesn't split on internal commas
+            write_quoted(output, &json);
+        }
+    }
+}
+
+// `str` columns read quoted text back as is, so their strings are never JSON-encoded
+fn write_cell(output: &mut String, value: &Value, ty: Option<ColumnType>) {
+    match (value, ty) {
+        (Value::String(s), Some(ColumnType::Str)) if needs_quotes(s, true) => write_quoted(output, s),
+        (Value::String(s), Some(ColumnType::Str)) => output.push_str(s),
+        _ => write_value(output, value),
+    }
+}
+
+// Strings that would read back as JSON even in quotes are written as JSON
+// strings, which the parser decodes
+fn write_string(output: &mut String, s: &str) {
+    if quoted_json(s).is_some() {
+        write_quoted(output, &serde_json::to_string(s).unwrap_or_default());
+    } else if needs_quotes(s, true) {
+        write_quoted(output, s);
+    } else {
+        output.push_str(s);
+    }
+}
+
+// Strings the parser would split, trim, unescape or read as something other
+// than a string unless quoted
+fn needs_quotes(s: &str, colons: bool) -> bool {
+    s.is_empty()
+        || s.trim().len() != s.len()
+        || s.contains([',', '"', '\\', '\n', '\r'])
+        || (colons && s.contains(':'))
+        || s.starts_with(['[', '{', '#'])
+        // `key://...` is read as text, not as an entry
+        || s.starts_with("//")
+        || matches!(s, "null" | "true" | "false")
+        || s.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+// A first cell like `e{x` could run into a later cell's `}` and a colon after
+// it, making the row read as a header
+fn opens_header(s: &str) -> bool {
+    let rest = s.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_');
+    rest.len() < s.len() && rest.trim_start().starts_with(['[', '{'])
+}
+
+fn write_number(output: &mut String, n: &Number) {
+    if let Some(u) = n.as_u64() {
+        output.push_str(itoa::Buffer::new().format(u));
+    } else if let Some(i) = n.as_i64() {
+        output.push_str(itoa::Buffer::new().format(i));
+    } else {
+        let _ = write!(output, "{}", n);
+    }
+}
+
+fn write_quoted(output: &mut String, s: &str) {
+    output.reserve(s.len() + 2);
+    output.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' | '\\' => {
+                output.push('\\');
+                output.push(ch);
+            }
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            _ => output.push(ch),
+        }
+    }
+    output.push('"');
+}
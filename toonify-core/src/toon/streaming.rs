@@ -0,0 +1,205 @@
+// Incremental TOON parsing
+//
+//     let mut parser = StreamingParser::new();
+//     while let Some(chunk) = upload.next_chunk() {
+//         parser.feed(&chunk);
+//         for event in parser.by_ref() {
+//             handle(event?);
+//         }
+//     }
+//     parser.finish();
+//     for event in parser.by_ref() {
+//         handle(event?);
+//     }
+//
+// Input may be split anywhere, including mid-line; only complete lines are
+// parsed, so memory stays bounded by the longest line rather than the
+// document. Rows are parsed exactly as `parse_toon` parses them. Because an
+// entity ends where the next header starts, its `EntityEnd` is emitted when
+// that header (or the end of input) arrives.
+
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+use super::parser::{dictionary_line, expand_codes, header_line, is_entry_header_line, list_line, parse_value, row_object, Column, Dictionary};
+
+/// One step through a TOON document
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToonEvent {
+    /// A table (`name[n]{cols}:`), list (`name[n]:`) or single-row object (`name{cols}:`) begins
+    EntityStart {
+        name: String,
+        columns: Vec<String>,
+        is_array: bool,
+    },
+    /// One table row as an object, one list element, or the object's row
+    Row(Value),
+    EntityEnd { name: String },
+    /// A `name:value` entry
+    Scalar { name: String, value: Value },
+}
+
+enum State {
+    Idle,
+    Array { name: String, columns: Vec<Column>, dictionaries: Vec<Dictionary> },
+    Object { name: String, columns: Vec<Column>, dictionaries: Vec<Dictionary> },
+}
+
+pub struct StreamingParser {
+    buffer: String,
+    // Start of the unparsed text in `buffer`
+    pos: usize,
+    line: usize,
+    finished: bool,
+    state: State,
+    // `#dict` lines read since the last entity, for the next one
+    dictionaries: Vec<Dictionary>,
+    pending: VecDeque<ToonEvent>,
+}
+
+impl StreamingParser {
+    pub fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            pos: 0,
+            line: 0,
+            finished: false,
+            state: State::Idle,
+            dictionaries: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Append more input; events for every completed line become available
+    pub fn feed(&mut self, chunk: &str) {
+        if self.pos > 0 {
+            self.buffer.drain(..self.pos);
+            self.pos = 0;
+        }
+        self.buffer.push_str(chunk);
+    }
+
+    /// Mark the end of input, releasing the last line and the final `EntityEnd`
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    fn next_line(&mut self) -> Option<String> {
+        let rest = &self.buffer[self.pos..];
+        let line = match rest.find('\n') {
+            Some(end) => {
+                self.pos += end + 1;
+                &rest[..end]
+            }
+            None if self.finished && !rest.is_empty() => {
+                self.pos = self.buffer.len();
+                rest
+            }
+            None => return None,
+        };
+        self.line += 1;
+        Some(line.trim_end_matches('\r').to_string())
+    }
+
+    fn close_entity(&mut self) {
+        match std::mem::replace(&mut self.state, State::Idle) {
+            State::Idle => {}
+            State::Array { name, .. } | State::Object { name, .. } => self.pending.push_back(ToonEvent::EntityEnd { name }),
+        }
+    }
+
+    fn process_line(&mut self, line: &str) -> Result<(), String> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(dictionary) = dictionary_line(trimmed) {
+            self.close_entity();
+            self.dictionaries.push(dictionary);
+            return Ok(());
+        }
+
+        if !is_entry_header_line(trimmed) {
+            return match &mut self.state {
+                State::Array { columns, .. } if columns.is_empty() => {
+                    self.pending.extend(list_line(trimmed).into_iter().map(ToonEvent::Row));
+                    Ok(())
+                }
+                State::Array { columns, dictionaries, .. } => {
+                    let mut row = row_object(columns, trimmed);
+                    expand_codes(&mut row, dictionaries);
+                    self.pending.push_back(ToonEvent::Row(row));
+                    Ok(())
+                }
+                State::Object { columns, dictionaries, .. } => {
+                    let mut row = row_object(columns, trimmed);
+                    expand_codes(&mut row, dictionaries);
+                    self.pending.push_back(ToonEvent::Row(row));
+                    self.close_entity();
+                    Ok(())
+                }
+                State::Idle => Err(format!("Parse error: line {}: expected an entry, found {:?}", self.line, trimmed)),
+            };
+        }
+
+        self.close_entity();
+        let (name, is_array, columns, rest) =
+            header_line(trimmed).ok_or_else(|| format!("Parse error: line {}: malformed header {:?}", self.line, trimmed))?;
+
+        if !is_array && columns.is_empty() {
+            self.dictionaries.clear();
+            self.pending.push_back(ToonEvent::Scalar { name, value: parse_value(rest) });
+            return Ok(());
+        }
+
+        self.pending.push_back(ToonEvent::EntityStart {
+            name: name.clone(),
+            columns: columns.iter().map(|(column, _)| column.clone()).collect(),
+            is_array,
+        });
+        let dictionaries = std::mem::take(&mut self.dictionaries);
+        self.state = if is_array {
+            State::Array { name, columns, dictionaries }
+        } else {
+            State::Object { name, columns, dictionaries }
+        };
+        // `tags[2]: a,b` and `pt{x,y}: 1,2` carry their first line after the ':'
+        if rest.is_empty() {
+            return Ok(());
+        }
+        self.process_line(rest)
+    }
+}
+
+impl Default for StreamingParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Yields events for the input fed so far; `None` means "feed more" until `finish` is called
+impl Iterator for StreamingParser {
+    type Item = Result<ToonEvent, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+            match self.next_line() {
+                Some(line) => {
+                    if let Err(e) = self.process_line(&line) {
+                        return Some(Err(e));
+                    }
+                }
+                None if self.finished => {
+                    self.close_entity();
+                    return self.pending.pop_front().map(Ok);
+                }
+                None => return None,
+            }
+        }
+    }
+}
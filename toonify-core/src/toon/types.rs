@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Number, Value};
+
+use super::parser::parse_value;
+
+/// The union of the rows' keys, in first-seen order: a table's columns
+pub fn columns_of<'a>(rows: impl IntoIterator<Item = &'a Map<String, Value>>) -> Vec<&'a str> {
+    let mut seen = HashSet::new();
+    let mut columns = Vec::new();
+    for row in rows {
+        for key in row.keys() {
+            if seen.insert(key.as_str()) {
+                columns.push(key.as_str());
+            }
+        }
+    }
+    columns
+}
+
+/// Column type annotation in a typed TOON header (`users[2]{id:int,name:str}:`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Int,
+    Float,
+    /// Mixed integers and floats; parsed as a number without changing its kind
+    Num,
+    Str,
+    Bool,
+    /// Nested arrays/objects stored as quoted JSON
+    Json,
+}
+
+impl ColumnType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColumnType::Int => "int",
+            ColumnType::Float => "float",
+            ColumnType::Num => "num",
+            ColumnType::Str => "str",
+            ColumnType::Bool => "bool",
+            ColumnType::Json => "json",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "int" => Some(ColumnType::Int),
+            "float" => Some(ColumnType::Float),
+            "num" => Some(ColumnType::Num),
+            "str" => Some(ColumnType::Str),
+            "bool" => Some(ColumnType::Bool),
+            "json" => Some(ColumnType::Json),
+            _ => None,
+        }
+    }
+
+    /// Type of a single value (`None` for null)
+    pub fn of_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => None,
+            Value::Bool(_) => Some(ColumnType::Bool),
+            Value::Number(n) if n.is_f64() => Some(ColumnType::Float),
+            Value::Number(_) => Some(ColumnType::Int),
+            Value::String(_) => Some(ColumnType::Str),
+            Value::Array(_) | Value::Object(_) => Some(ColumnType::Json),
+        }
+    }
+
+    /// Common type of a column, ignoring nulls
+    ///
+    /// Returns `None` when the column is all-null or mixes incompatible types,
+    /// in which case the column is left untyped and values are sniffed.
+    pub fn infer<'a>(values: impl Iterator<Item = &'a Value>) -> Option<Self> {
+        let mut inferred = None;
+        for value in values {
+            let Some(ty) = Self::of_value(value) else { continue };
+            inferred = match (inferred, ty) {
+                (None, ty) => Some(ty),
+                (Some(a), b) if a == b => Some(a),
+                (Some(ColumnType::Int | ColumnType::Float | ColumnType::Num), ColumnType::Int | ColumnType::Float) => Some(ColumnType::Num),
+                _ => return None,
+            };
+        }
+        inferred
+    }
+
+    /// Parse a cell according to this type
+    ///
+    /// `quoted` tells whether the cell was written in quotes, which
+    /// distinguishes an empty string from a null in `str` columns. Cells that
+    /// don't fit the declared type fall back to untyped parsing.
+    pub fn parse_cell(&self, cell: &str, quoted: bool) -> Value {
+        let cell = if quoted { cell } else { cell.trim() };
+
+        if !quoted && (cell.is_empty() || cell == "null") {
+            return Value::Null;
+        }
+
+        match self {
+            ColumnType::Str => Value::String(cell.to_string()),
+            ColumnType::Int => cell.parse::<i64>().map(Value::from)
+                .or_else(|_| cell.parse::<u64>().map(Value::from))
+                .unwrap_or_else(|_| parse_value(cell)),
+            ColumnType::Float => cell.parse::<f64>().ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .unwrap_or_else(|| parse_value(cell)),
+            ColumnType::Num => parse_value(cell),
+            ColumnType::Bool => match cell {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => parse_value(cell),
+            },
+            ColumnType::Json => serde_json::from_str(cell).unwrap_or_else(|_| parse_value(cell)),
+        }
+    }
+}
+
+/// Split a header column such as `id:int` into its name and type
+///
+/// Only known type names are treated as annotations, so columns like
+/// `ns:tag` keep their full name.
+pub fn split_typed_column(column: &str) -> (String, Option<ColumnType>) {
+    if let Some((name, ty)) = column.rsplit_once(':')
+        && let Some(ty) = ColumnType::from_name(ty)
+        && !name.is_empty()
+    {
+        return (name.to_string(), Some(ty));
+    }
+    (column.to_string(), None)
+}
@@ -0,0 +1,171 @@
+// Event-driven TOON output
+//
+//     let mut writer = ToonWriter::new(io::stdout().lock());
+//     writer.begin_table("users", &["id", "name"])?;
+//     while let Some(row) = cursor.next() {
+//         writer.write_row(&[json!(row.id), json!(row.name)])?;
+//     }
+//     writer.write_scalar("page", &json!(3))?;
+//     writer.finish()?;
+//
+// Cells are written with the same quoting as `serialize_toon`, so the output
+// parses back exactly like a serialized document. A TOON header carries the
+// row count, so `begin_table` holds the table's text until the table ends;
+// when the count is known up front (`SELECT COUNT(*)`, an API's total),
+// `begin_table_with_len` writes the header immediately and streams each row
+// straight to the output.
+
+use std::io::{self, Write};
+
+use serde_json::{Map, Value};
+
+use super::serializer::{serialize_toon, write_value};
+
+struct OpenTable {
+    name: String,
+    columns: Vec<String>,
+    rows: usize,
+    // Expected row count when the header was already written
+    declared: Option<usize>,
+    body: String,
+}
+
+pub struct ToonWriter<W: Write> {
+    out: W,
+    table: Option<OpenTable>,
+    line: String,
+    // Whether an entity has been written, so the next one needs a blank line first
+    started: bool,
+}
+
+impl<W: Write> ToonWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, table: None, line: String::new(), started: false }
+    }
+
+    /// Start a table whose rows are buffered until it ends
+    pub fn begin_table(&mut self, name: &str, columns: &[&str]) -> io::Result<()> {
+        self.end_table()?;
+        self.table = Some(OpenTable {
+            name: name.to_string(),
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows: 0,
+            declared: None,
+            body: String::new(),
+        });
+        Ok(())
+    }
+
+    /// Start a table of exactly `len` rows, streaming rows as they are written
+    pub fn begin_table_with_len(&mut self, name: &str, columns: &[&str], len: usize) -> io::Result<()> {
+        self.end_table()?;
+        let columns: Vec<String> = columns.iter().map(|column| column.to_string()).collect();
+        self.separate()?;
+        self.out.write_all(header(name, len, &columns).as_bytes())?;
+        self.table = Some(OpenTable {
+            name: name.to_string(),
+            columns,
+            rows: 0,
+            declared: Some(len),
+            body: String::new(),
+        });
+        Ok(())
+    }
+
+    /// One row of the current table, cells in column order
+    pub fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        let table = self.table.as_mut().ok_or_else(|| invalid("write_row called without an open table".to_string()))?;
+        if values.len() != table.columns.len() {
+            return Err(invalid(format!(
+                "Table {:?} has {} columns but the row has {} values",
+                table.name,
+                table.columns.len(),
+                values.len()
+            )));
+        }
+        if table.declared.is_some_and(|len| table.rows == len) {
+            return Err(invalid(format!("Table {:?} was declared with {} rows", table.name, table.rows)));
+        }
+
+        self.line.clear();
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                self.line.push(',');
+            }
+            write_value(&mut self.line, value);
+        }
+        self.line.push('\n');
+        table.rows += 1;
+
+        if table.declared.is_some() {
+            self.out.write_all(self.line.as_bytes())
+        } else {
+            table.body.push_str(&self.line);
+            Ok(())
+        }
+    }
+
+    /// A `name:value` entry; ends any open table
+    pub fn write_scalar(&mut self, name: &str, value: &Value) -> io::Result<()> {
+        self.end_table()?;
+        self.separate()?;
+        self.line.clear();
+        self.line.push_str(name);
+        self.line.push(':');
+        write_value(&mut self.line, value);
+        self.line.push('\n');
+        self.out.write_all(self.line.as_bytes())
+    }
+
+    /// A whole entity of any shape (list, single-row object, nested rows),
+    /// written as `serialize_toon` would write it; ends any open table
+    pub fn write_entity(&mut self, name: &str, value: &Value) -> io::Result<()> {
+        self.end_table()?;
+        let mut single = Map::new();
+        single.insert(name.to_string(), value.clone());
+        let text = serialize_toon(&Value::Object(single)).map_err(invalid)?;
+        self.separate()?;
+        self.out.write_all(text.as_bytes())?;
+        self.out.write_all(b"\n")
+    }
+
+    /// Close the last table, flush, and hand back the output
+    pub fn finish(mut self) -> io::Result<W> {
+        self.end_table()?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn end_table(&mut self) -> io::Result<()> {
+        let Some(table) = self.table.take() else { return Ok(()) };
+        match table.declared {
+            Some(len) if len != table.rows => Err(invalid(format!(
+                "Table {:?} was declared with {} rows but {} were written",
+                table.name, len, table.rows
+            ))),
+            Some(_) => Ok(()),
+            None => {
+                self.separate()?;
+                self.out.write_all(header(&table.name, table.rows, &table.columns).as_bytes())?;
+                self.out.write_all(table.body.as_bytes())
+            }
+        }
+    }
+
+    // Entities are separated by a blank line, as `serialize_toon` writes them
+    fn separate(&mut self) -> io::Result<()> {
+        if std::mem::replace(&mut self.started, true) {
+            self.out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+// "name[n]{a,b}:\n"
+fn header(name: &str, len: usize, columns: &[String]) -> String {
+    format!("{}[{}]{{{}}}:\n", name, len, columns.join(","))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
@@ -0,0 +1,68 @@
+// `toon_include!`: embed a TOON file, parsed and checked at compile time
+//
+//     static USERS: LazyLock<serde_json::Value> = LazyLock::new(|| toonify::toon_include!("fixtures/users.toon"));
+//     let config: Config = toonify::toon_include!("config.toon" as Config);
+//
+// Paths are relative to the calling crate's Cargo.toml. Malformed TOON is a
+// compile error pointing at the path literal. The document is embedded as
+// JSON text and deserialized on first use, so `as T` reports a shape
+// mismatch at runtime; the file itself is tracked through `include_bytes!`,
+// so editing it rebuilds the crate.
+
+use std::path::PathBuf;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, LitStr, Token, Type};
+use toonify_core::toon;
+
+struct Include {
+    path: LitStr,
+    target: Option<Type>,
+}
+
+impl Parse for Include {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let target = if input.parse::<Option<Token![as]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Include { path, target })
+    }
+}
+
+#[proc_macro]
+pub fn toon_include(input: TokenStream) -> TokenStream {
+    let Include { path, target } = parse_macro_input!(input as Include);
+
+    match expand(&path, target.as_ref()) {
+        Ok(tokens) => tokens.into(),
+        Err(message) => syn::Error::new(path.span(), message).to_compile_error().into(),
+    }
+}
+
+fn expand(path: &LitStr, target: Option<&Type>) -> Result<proc_macro2::TokenStream, String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| "CARGO_MANIFEST_DIR is not set".to_string())?;
+    let full_path = PathBuf::from(manifest_dir).join(path.value());
+    let full_path_str = full_path.to_string_lossy().into_owned();
+
+    let text = std::fs::read_to_string(&full_path).map_err(|e| format!("Failed to read {}: {}", full_path_str, e))?;
+    let value = toon::parse_toon(&text).map_err(|e| format!("Invalid TOON in {}: {}", path.value(), e))?;
+    let json = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+
+    let target = match target {
+        Some(ty) => quote!(#ty),
+        None => quote!(::toonify::__private::serde_json::Value),
+    };
+    let message = format!("toon_include!({:?}) does not match the requested type", path.value());
+
+    Ok(quote! {
+        {
+            const _: &[u8] = include_bytes!(#full_path_str);
+            ::toonify::__private::serde_json::from_str::<#target>(#json).expect(#message)
+        }
+    })
+}